@@ -0,0 +1,296 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::Module;
+use anyhow::Result;
+use serde::Serialize;
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fmt::{
+        Display,
+        Formatter,
+    },
+};
+use wasmparser::{
+    Operator,
+    TypeRef,
+    ValType,
+};
+
+/// A class of Wasm instructions or a Wasm proposal which `pallet-contracts` does not
+/// accept in the code of contracts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnsupportedFeature {
+    /// Floating point types and instructions, whose results are not deterministic
+    /// across platforms, e.g. the bits of NaN values.
+    FloatingPoint,
+    /// The SIMD and relaxed SIMD proposals.
+    Simd,
+    /// The threads proposal: shared memories and atomic instructions.
+    Threads,
+    /// The bulk memory proposal, e.g. `memory.copy` and `memory.fill`.
+    BulkMemory,
+    /// The reference types, typed function references and GC proposals.
+    ReferenceTypes,
+    /// The tail call proposal.
+    TailCall,
+    /// The exception handling proposal.
+    Exceptions,
+    /// The memory control proposal.
+    MemoryControl,
+    /// Functions returning more than one value.
+    MultiValue,
+    /// Imports of mutable globals.
+    MutableGlobal,
+}
+
+impl UnsupportedFeature {
+    /// Returns `true` if code using the feature may execute differently on different
+    /// platforms.
+    ///
+    /// Code using floating point instructions can still be uploaded with
+    /// `Determinism::Relaxed` to be executed off-chain, all other features are
+    /// rejected regardless of the determinism.
+    pub fn is_nondeterministic(&self) -> bool {
+        matches!(self, Self::FloatingPoint | Self::Simd | Self::Threads)
+    }
+}
+
+impl Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::FloatingPoint => "floating point",
+            Self::Simd => "SIMD",
+            Self::Threads => "threads",
+            Self::BulkMemory => "bulk memory",
+            Self::ReferenceTypes => "reference types",
+            Self::TailCall => "tail calls",
+            Self::Exceptions => "exceptions",
+            Self::MemoryControl => "memory control",
+            Self::MultiValue => "multi-value",
+            Self::MutableGlobal => "mutable globals",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The uses of an unsupported feature in the Wasm code of a contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FeatureUsage {
+    /// The feature used by the code.
+    pub feature: UnsupportedFeature,
+    /// The number of instructions, types and imports using the feature.
+    pub occurrences: usize,
+    /// The distinct instructions, types and imports using the feature, e.g. `F64Add`
+    /// or `import env.memory`.
+    pub uses: BTreeSet<String>,
+    /// The indices of the functions containing instructions using the feature.
+    pub functions: BTreeSet<u32>,
+}
+
+/// Scans the Wasm code of a contract for instructions and proposals which
+/// `pallet-contracts` does not accept, e.g. floating point or SIMD instructions.
+///
+/// Returns the usages ordered by feature, empty if the code uses none of them.
+pub fn unsupported_features(code: &[u8]) -> Result<Vec<FeatureUsage>> {
+    let module = Module::new(code)?;
+    let mut usages = BTreeMap::<UnsupportedFeature, FeatureUsage>::new();
+    let mut record = |feature, used: String, function: Option<u32>| {
+        let usage = usages.entry(feature).or_insert_with(|| {
+            FeatureUsage {
+                feature,
+                occurrences: 0,
+                uses: BTreeSet::new(),
+                functions: BTreeSet::new(),
+            }
+        });
+        usage.occurrences += 1;
+        usage.uses.insert(used);
+        usage.functions.extend(function);
+    };
+
+    let mut imported_functions = 0;
+    for import in &module.import_sections {
+        let name = format!("import {}.{}", import.module, import.name);
+        match import.ty {
+            TypeRef::Func(_) => imported_functions += 1,
+            TypeRef::Memory(memory) if memory.shared => {
+                record(UnsupportedFeature::Threads, name, None)
+            }
+            TypeRef::Global(global) if global.mutable => {
+                record(UnsupportedFeature::MutableGlobal, name, None)
+            }
+            _ => {}
+        }
+    }
+    for ty in &module.type_sections {
+        for val in ty.params().iter().chain(ty.results()) {
+            if let Some(feature) = val_type_feature(val) {
+                record(feature, format!("type {val}"), None)
+            }
+        }
+        if ty.results().len() > 1 {
+            let results = ty
+                .results()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            let name = format!("type (result {})", results.join(" "));
+            record(UnsupportedFeature::MultiValue, name, None)
+        }
+    }
+    for (index, body) in module.code_sections.iter().enumerate() {
+        let function = imported_functions + index as u32;
+        for operator in body {
+            let (proposal, name) = classify(operator);
+            if let Some(feature) = proposal_feature(proposal, name) {
+                record(feature, name.to_string(), Some(function))
+            }
+        }
+    }
+    Ok(usages.into_values().collect())
+}
+
+/// Returns the unsupported feature required by a value type, if any.
+fn val_type_feature(val: &ValType) -> Option<UnsupportedFeature> {
+    match val {
+        ValType::F32 | ValType::F64 => Some(UnsupportedFeature::FloatingPoint),
+        ValType::V128 => Some(UnsupportedFeature::Simd),
+        ValType::Ref(_) => Some(UnsupportedFeature::ReferenceTypes),
+        ValType::I32 | ValType::I64 => None,
+    }
+}
+
+/// Returns the unsupported feature of an instruction by the Wasm proposal it was
+/// introduced with.
+fn proposal_feature(proposal: &str, name: &str) -> Option<UnsupportedFeature> {
+    let feature = match proposal {
+        "mvp" if name.contains("F32") || name.contains("F64") => {
+            UnsupportedFeature::FloatingPoint
+        }
+        "mvp" | "sign_extension" => return None,
+        "saturating_float_to_int" => UnsupportedFeature::FloatingPoint,
+        "simd" | "relaxed_simd" => UnsupportedFeature::Simd,
+        "threads" | "shared_everything_threads" => UnsupportedFeature::Threads,
+        "bulk_memory" => UnsupportedFeature::BulkMemory,
+        "tail_call" => UnsupportedFeature::TailCall,
+        "exceptions" => UnsupportedFeature::Exceptions,
+        "memory_control" => UnsupportedFeature::MemoryControl,
+        _ => UnsupportedFeature::ReferenceTypes,
+    };
+    Some(feature)
+}
+
+/// Defines `classify`, returning the proposal and the name of an instruction, from the
+/// list of all instructions of `wasmparser`.
+macro_rules! define_classify {
+    ($(@$proposal:ident $op:ident $({ $($payload:tt)* })? => $visit:ident)*) => {
+        fn classify(operator: &Operator) -> (&'static str, &'static str) {
+            match operator {
+                $(
+                    Operator::$op { .. } => (stringify!($proposal), stringify!($op)),
+                )*
+            }
+        }
+    };
+}
+
+wasmparser::for_each_operator!(define_classify);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_unsupported_features() {
+        let contract = r#"
+        (module
+            (type (;0;) (func (param f64 f64) (result f64)))
+            (type (;1;) (func (param i32 i32)))
+            (import "seal0" "seal_input" (func (;0;) (type 1)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (func (;1;) (type 0) (param f64 f64) (result f64)
+                local.get 0
+                local.get 1
+                f64.add
+            )
+            (func (;2;) (type 1) (param i32 i32)
+                local.get 0
+                local.get 1
+                i32.const 8
+                memory.copy
+                local.get 0
+                i32.extend8_s
+                drop
+            )
+        )
+        "#;
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+
+        let usages = unsupported_features(&code).unwrap();
+
+        assert_eq!(
+            usages
+                .iter()
+                .map(|usage| {
+                    (
+                        usage.feature,
+                        usage.occurrences,
+                        usage.uses.iter().cloned().collect::<Vec<_>>(),
+                        usage.functions.iter().copied().collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    UnsupportedFeature::FloatingPoint,
+                    4,
+                    vec!["F64Add".to_string(), "type f64".to_string()],
+                    vec![1],
+                ),
+                (
+                    UnsupportedFeature::BulkMemory,
+                    1,
+                    vec!["MemoryCopy".to_string()],
+                    vec![2],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_supported_code() {
+        let contract = r#"
+        (module
+            (type (;0;) (func (param i32 i32)))
+            (import "seal0" "seal_input" (func (;0;) (type 0)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (func (;1;) (type 0) (param i32 i32)
+                local.get 0
+                i64.extend_i32_u
+                drop
+            )
+        )
+        "#;
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+
+        assert!(unsupported_features(&code).unwrap().is_empty());
+    }
+}
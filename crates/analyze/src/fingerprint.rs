@@ -0,0 +1,219 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    language,
+    Language,
+    Module,
+};
+use anyhow::Result;
+use std::fmt::{
+    Display,
+    Formatter,
+};
+use wasmparser::{
+    ProducersSectionReader,
+    TypeRef,
+};
+
+/// Fingerprints of ink! releases, newest first: host functions which are only imported
+/// by code built with the release or a newer one.
+///
+/// Not every contract imports the host functions of the release it was built with, so
+/// the newest matching release is a lower bound.
+const INK_FINGERPRINTS: &[(&str, &[(&str, &str)])] = &[
+    (
+        ">=5",
+        &[
+            ("seal0", "lock_delegate_dependency"),
+            ("seal0", "unlock_delegate_dependency"),
+            ("seal2", "call"),
+            ("seal2", "instantiate"),
+        ],
+    ),
+    (
+        ">=4",
+        &[
+            ("seal0", "input"),
+            ("seal0", "value_transferred"),
+            ("seal1", "get_storage"),
+            ("seal2", "set_storage"),
+        ],
+    ),
+    (
+        "^3",
+        &[
+            ("seal0", "seal_input"),
+            ("seal0", "seal_value_transferred"),
+            ("seal0", "seal_get_storage"),
+            ("seal0", "seal_set_storage"),
+        ],
+    ),
+];
+
+/// The compiler recording its release in the `producers` section of Solidity contracts.
+const SOLANG: &str = "solang";
+
+/// The source language of a contract, with the release of the language or its compiler
+/// as far as it can be told from the Wasm code.
+#[derive(Clone, Debug)]
+pub struct SourceLanguageVersion {
+    /// The source language of the contract.
+    pub language: Language,
+    /// The release of ink! the contract was built with, e.g. `>=5`, or of Solang for
+    /// Solidity contracts, e.g. `0.3.3`.
+    pub release: Option<String>,
+    /// The tools which processed the code according to its `producers` section, e.g.
+    /// `rustc 1.78.0`.
+    pub processed_by: Vec<String>,
+}
+
+impl Display for SourceLanguageVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.release {
+            Some(release) => write!(f, "{} {}", self.language, release)?,
+            None => write!(f, "{}", self.language)?,
+        }
+        if !self.processed_by.is_empty() {
+            write!(f, " ({})", self.processed_by.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Detects the source language of a smart contract from its Wasm code like
+/// [`determine_language`](crate::determine_language), and fingerprints the release of
+/// ink! or Solang it was built with.
+///
+/// The release of ink! is derived from the host functions imported by the code, the
+/// release of Solang and the other tools which processed the code from its
+/// `producers` custom section.
+pub fn determine_language_version(code: &[u8]) -> Result<SourceLanguageVersion> {
+    let module = Module::new(code)?;
+    let language = language(&module)?;
+    let processed_by = processed_by(&module)?;
+    let release = match language {
+        Language::Ink => ink_release(&module),
+        Language::Solidity => {
+            processed_by
+                .iter()
+                .find_map(|(name, version)| (name == SOLANG).then(|| version.clone()))
+        }
+        Language::AssemblyScript => None,
+    };
+    Ok(SourceLanguageVersion {
+        language,
+        release,
+        processed_by: processed_by
+            .into_iter()
+            .map(|(name, version)| format!("{name} {version}"))
+            .collect(),
+    })
+}
+
+/// Returns the newest ink! release whose fingerprint matches the imports of the code.
+fn ink_release(module: &Module) -> Option<String> {
+    INK_FINGERPRINTS
+        .iter()
+        .find(|(_, host_functions)| {
+            module.import_sections.iter().any(|import| {
+                matches!(import.ty, TypeRef::Func(_))
+                    && host_functions.contains(&(import.module, import.name))
+            })
+        })
+        .map(|(release, _)| release.to_string())
+}
+
+/// Returns the names and versions of the `processed-by` field of the `producers`
+/// custom section, if the code has one.
+fn processed_by(module: &Module) -> Result<Vec<(String, String)>> {
+    let Some(section) = module.custom_sections.get("producers") else {
+        return Ok(Vec::new())
+    };
+    let mut tools = Vec::new();
+    for field in ProducersSectionReader::new(section, 0)? {
+        let field = field?;
+        if field.name != "processed-by" {
+            continue
+        }
+        for value in field.values {
+            let value = value?;
+            tools.push((value.name.to_string(), value.version.to_string()));
+        }
+    }
+    Ok(tools)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes a `producers` section with the `processed-by` tools.
+    fn producers(tools: &[(&str, &str)]) -> String {
+        let mut bytes = vec![1, 12];
+        bytes.extend(b"processed-by");
+        bytes.push(tools.len() as u8);
+        for (name, version) in tools {
+            bytes.push(name.len() as u8);
+            bytes.extend(name.as_bytes());
+            bytes.push(version.len() as u8);
+            bytes.extend(version.as_bytes());
+        }
+        bytes.iter().map(|byte| format!("\\{byte:02x}")).collect()
+    }
+
+    #[test]
+    fn fingerprints_ink_release() {
+        let contract = r#"
+        (module
+            (type (;0;) (func (param i32 i32)))
+            (type (;1;) (func (result i32)))
+            (import "seal0" "input" (func (;0;) (type 0)))
+            (import "seal0" "lock_delegate_dependency" (func (;1;) (type 0)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (func (;2;) (type 0))
+            (func (;3;) (type 1) (result i32) i32.const 0)
+        )
+        "#;
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+        let module = Module::new(&code).unwrap();
+
+        assert_eq!(ink_release(&module), Some(">=5".to_string()));
+    }
+
+    #[test]
+    fn fingerprints_solang_release() {
+        let contract = format!(
+            r#"
+            (module
+                (type (;0;) (func (param i32 i32 i32)))
+                (import "env" "memory" (memory (;0;) 16 16))
+                (func (;0;) (type 0))
+                (@custom "producers" "{}")
+            )
+            "#,
+            producers(&[("solang", "0.3.3")])
+        );
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+
+        let version = determine_language_version(&code).unwrap();
+
+        assert!(matches!(version.language, Language::Solidity));
+        assert_eq!(version.release, Some("0.3.3".to_string()));
+        assert_eq!(version.processed_by, vec!["solang 0.3.3".to_string()]);
+        assert_eq!(version.to_string(), "Solidity 0.3.3 (solang 0.3.3)");
+    }
+}
@@ -0,0 +1,176 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::Module;
+use anyhow::Result;
+use std::fmt::{
+    Display,
+    Formatter,
+};
+use wasmparser::TypeRef;
+
+/// The host functions of `pallet-contracts` by their module, as used by ink! 5.
+///
+/// Most of them are also available with a `seal_` prefix, as imported by older versions
+/// of ink!.
+const KNOWN_HOST_FUNCTIONS: &[(&str, &[&str])] = &[
+    (
+        "seal0",
+        &[
+            "account_reentrance_count",
+            "address",
+            "balance",
+            "block_number",
+            "call",
+            "call_chain_extension",
+            "call_runtime",
+            "caller",
+            "caller_is_origin",
+            "caller_is_root",
+            "clear_storage",
+            "code_hash",
+            "contains_storage",
+            "debug_message",
+            "delegate_call",
+            "deposit_event",
+            "ecdsa_recover",
+            "ecdsa_to_eth_address",
+            "gas_left",
+            "get_storage",
+            "hash_blake2_128",
+            "hash_blake2_256",
+            "hash_keccak_256",
+            "hash_sha2_256",
+            "input",
+            "instantiate",
+            "instantiation_nonce",
+            "is_contract",
+            "lock_delegate_dependency",
+            "minimum_balance",
+            "now",
+            "own_code_hash",
+            "reentrance_count",
+            "seal_return",
+            "set_code_hash",
+            "set_storage",
+            "sr25519_verify",
+            "take_storage",
+            "terminate",
+            "transfer",
+            "unlock_delegate_dependency",
+            "value_transferred",
+            "weight_to_fee",
+            "xcm_execute",
+            "xcm_send",
+        ],
+    ),
+    (
+        "seal1",
+        &[
+            "call",
+            "clear_storage",
+            "contains_storage",
+            "gas_left",
+            "get_storage",
+            "instantiate",
+            "set_storage",
+            "terminate",
+            "weight_to_fee",
+        ],
+    ),
+    ("seal2", &["call", "instantiate", "set_storage"]),
+];
+
+/// A host function imported by a contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HostFunction {
+    /// The module of the function, e.g. `seal0`.
+    pub module: String,
+    /// The name of the function, e.g. `call`.
+    pub name: String,
+}
+
+impl HostFunction {
+    /// Returns `true` if the host function is provided by `pallet-contracts`.
+    ///
+    /// Chains running an older version of `pallet-contracts` may not support all of
+    /// the known host functions, and unknown ones may be provided by newer versions.
+    pub fn is_known(&self) -> bool {
+        let unprefixed = self.name.strip_prefix("seal_").unwrap_or(&self.name);
+        KNOWN_HOST_FUNCTIONS
+            .iter()
+            .filter(|(module, _)| *module == self.module)
+            .any(|(_, names)| {
+                names.contains(&self.name.as_str()) || names.contains(&unprefixed)
+            })
+    }
+}
+
+impl Display for HostFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.module, self.name)
+    }
+}
+
+/// Returns the host functions imported by the Wasm code of a contract, e.g.
+/// `seal0.call` or `seal2.instantiate`.
+pub fn host_functions(code: &[u8]) -> Result<Vec<HostFunction>> {
+    let module = Module::new(code)?;
+    Ok(module
+        .import_sections
+        .iter()
+        .filter(|import| matches!(import.ty, TypeRef::Func(_)))
+        .map(|import| {
+            HostFunction {
+                module: import.module.to_string(),
+                name: import.name.to_string(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_host_function_imports() {
+        let contract = r#"
+        (module
+            (type (;0;) (func (param i32 i32)))
+            (import "seal0" "seal_input" (func (;0;) (type 0)))
+            (import "seal2" "call" (func (;1;) (type 0)))
+            (import "seal1" "unknown" (func (;2;) (type 0)))
+            (import "env" "memory" (memory (;0;) 2 16))
+        )
+        "#;
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+
+        let host_functions = host_functions(&code).unwrap();
+
+        assert_eq!(
+            host_functions
+                .iter()
+                .map(|function| (function.to_string(), function.is_known()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("seal0.seal_input".to_string(), true),
+                ("seal2.call".to_string(), true),
+                ("seal1.unknown".to_string(), false),
+            ]
+        );
+    }
+}
@@ -15,12 +15,35 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 #![deny(unused_crate_dependencies)]
 
+mod determinism;
+mod fingerprint;
+mod host_functions;
+mod metadata_hash;
+
+pub use contract_metadata::Language;
+pub use determinism::{
+    unsupported_features,
+    FeatureUsage,
+    UnsupportedFeature,
+};
+pub use fingerprint::{
+    determine_language_version,
+    SourceLanguageVersion,
+};
+pub use host_functions::{
+    host_functions,
+    HostFunction,
+};
+pub use metadata_hash::{
+    metadata_hash,
+    METADATA_HASH_SECTION,
+};
+
 use anyhow::{
     anyhow,
     bail,
     Result,
 };
-pub use contract_metadata::Language;
 use std::collections::HashMap;
 use wasmparser::{
     FuncType,
@@ -225,7 +248,11 @@ fn is_ink_function_present(module: &Module) -> bool {
 /// the contract's source language. It currently supports detection for Ink!, Solidity,
 /// and AssemblyScript languages.
 pub fn determine_language(code: &[u8]) -> Result<Language> {
-    let module = Module::new(code)?;
+    language(&Module::new(code)?)
+}
+
+/// Detects the programming language of a smart contract from its parsed Wasm module.
+fn language(module: &Module) -> Result<Language> {
     let start_section = module.start_section.is_some();
 
     if !start_section && module.custom_sections.keys().any(|e| e == &"producers") {
@@ -238,7 +265,7 @@ pub fn determine_language(code: &[u8]) -> Result<Language> {
     {
         return Ok(Language::AssemblyScript)
     } else if !start_section
-        && (is_ink_function_present(&module)
+        && (is_ink_function_present(module)
             || matches!(module.has_function_name("ink_env"), Ok(true)))
     {
         return Ok(Language::Ink)
@@ -0,0 +1,76 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::Module;
+use anyhow::{
+    anyhow,
+    Result,
+};
+
+/// The name of the custom section holding the hash of the ABI of a contract, embedded
+/// into its Wasm code by `cargo contract build`.
+///
+/// The hash is the BLAKE2b-256 hash of the canonical serialization of the ABI, see
+/// `ContractMetadata::abi_hash`.
+pub const METADATA_HASH_SECTION: &str = "ink-metadata-hash";
+
+/// Returns the hash of the ABI embedded into the Wasm code of a contract, `None` if the
+/// code has no [`METADATA_HASH_SECTION`].
+pub fn metadata_hash(code: &[u8]) -> Result<Option<[u8; 32]>> {
+    let module = Module::new(code)?;
+    module
+        .custom_sections
+        .get(METADATA_HASH_SECTION)
+        .map(|data| {
+            <[u8; 32]>::try_from(*data).map_err(|_| {
+                anyhow!(
+                    "Invalid `{}` section: expected 32 bytes, found {}",
+                    METADATA_HASH_SECTION,
+                    data.len()
+                )
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_metadata_hash() {
+        let hash = "\\01".repeat(32);
+        let contract = format!(
+            r#"
+            (module
+                (import "env" "memory" (memory (;0;) 2 16))
+                (@custom "ink-metadata-hash" "{hash}")
+            )
+            "#
+        );
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+
+        assert_eq!(metadata_hash(&code).unwrap(), Some([1; 32]));
+    }
+
+    #[test]
+    fn code_without_metadata_hash() {
+        let contract = r#"(module (import "env" "memory" (memory (;0;) 2 16)))"#;
+        let code = wat::parse_str(contract).expect("Invalid wat.");
+
+        assert_eq!(metadata_hash(&code).unwrap(), None);
+    }
+}
@@ -46,7 +46,7 @@ fn main() {
     let out_dir: PathBuf = env::var("OUT_DIR")
         .expect("OUT_DIR should be set by cargo")
         .into();
-    let res = zip_template(&manifest_dir, &out_dir);
+    let res = zip_templates(&manifest_dir, &out_dir);
 
     match res {
         Ok(()) => std::process::exit(0),
@@ -57,22 +57,33 @@ fn main() {
     }
 }
 
-/// Creates a zip archive `template.zip` of the `new` project template in `out_dir`.
-fn zip_template(manifest_dir: &Path, out_dir: &Path) -> Result<()> {
-    let template_dir = manifest_dir.join("templates").join("new");
-    let template_dst_file = out_dir.join("template.zip");
-    println!(
-        "Creating template zip: template_dir '{}', destination archive '{}'",
-        template_dir.display(),
-        template_dst_file.display()
-    );
-    zip_dir(&template_dir, &template_dst_file, CompressionMethod::Stored).map(|_| {
+/// Creates a zip archive `template_<name>.zip` in `out_dir` for each of the `new`
+/// project templates in `templates/new/<name>`.
+fn zip_templates(manifest_dir: &Path, out_dir: &Path) -> Result<()> {
+    let templates_dir = manifest_dir.join("templates").join("new");
+    for entry in std::fs::read_dir(&templates_dir)? {
+        let template_dir = entry?.path();
+        if !template_dir.is_dir() {
+            continue
+        }
+        let name = template_dir
+            .file_name()
+            .expect("template directories have a name")
+            .to_string_lossy();
+        let template_dst_file = out_dir.join(format!("template_{name}.zip"));
+        println!(
+            "Creating template zip: template_dir '{}', destination archive '{}'",
+            template_dir.display(),
+            template_dst_file.display()
+        );
+        zip_dir(&template_dir, &template_dst_file, CompressionMethod::Stored)?;
         println!(
             "Done: {} written to {}",
             template_dir.display(),
             template_dst_file.display()
         );
-    })
+    }
+    Ok(())
 }
 
 /// Creates a zip archive at `dst_file` with the content of the `src_dir`.
@@ -252,11 +252,23 @@ impl TryFrom<&UnstableOptions> for UnstableFlags {
 }
 
 /// Define the standard `cargo` features args to be passed through.
-#[derive(Default, Clone, Debug, Args)]
+#[derive(
+    Default, Clone, Debug, Eq, PartialEq, Args, serde::Serialize, serde::Deserialize,
+)]
+#[serde(default)]
 pub struct Features {
     /// Space or comma separated list of features to activate
     #[clap(long, value_delimiter = ',')]
     features: Vec<String>,
+    /// Activate all available features
+    #[clap(long)]
+    all_features: bool,
+    /// Do not activate the `default` feature
+    ///
+    /// The default features are never activated for the on-chain build, this only
+    /// affects the generation of the metadata.
+    #[clap(long)]
+    no_default_features: bool,
 }
 
 impl Features {
@@ -265,7 +277,40 @@ impl Features {
         self.features.push(feature.to_owned())
     }
 
+    /// Returns `true` if the `default` feature should not be activated.
+    pub fn no_default_features(&self) -> bool {
+        self.no_default_features
+    }
+
+    /// Replaces `--all-features` by the `available` features of the contract, except
+    /// for `default`, `std` and `ink-as-dependency`.
+    ///
+    /// Passing `--all-features` through to `cargo` would activate these as well, which
+    /// breaks the build: `std` can't be compiled for the on-chain target, and
+    /// `ink-as-dependency` removes the entry points of the contract.
+    pub fn expand_all_features<'a>(
+        &self,
+        available: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        const EXCLUDED: [&str; 3] = ["default", "std", "ink-as-dependency"];
+        let mut features = self.clone();
+        if features.all_features {
+            features.all_features = false;
+            for feature in available {
+                if !EXCLUDED.contains(&feature.as_str())
+                    && !features.features.contains(feature)
+                {
+                    features.push(feature);
+                }
+            }
+        }
+        features
+    }
+
     /// Appends the raw features args to pass through to the `cargo` invocation.
+    ///
+    /// `--no-default-features` is not appended, since the on-chain build always
+    /// disables the default features.
     pub fn append_to_args(&self, args: &mut Vec<String>) {
         if !self.features.is_empty() {
             args.push("--features".to_string());
@@ -276,5 +321,98 @@ impl Features {
             };
             args.push(features);
         }
+        if self.all_features {
+            args.push("--all-features".to_string());
+        }
+    }
+}
+
+/// Additional flags for the on-chain build, merged with the defaults for the target.
+///
+/// They can be configured in the `[package.metadata.contract.build]` section of the
+/// contract's `Cargo.toml`, e.g.
+///
+/// ```toml
+/// [package.metadata.contract.build]
+/// rustflags = ["-Coverflow-checks=on"]
+/// link-args = ["-zstack-size=131072"]
+/// ```
+///
+/// and via the corresponding CLI flags, which are appended to the configured ones.
+#[derive(
+    Default, Clone, Debug, Eq, PartialEq, Args, serde::Serialize, serde::Deserialize,
+)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BuildFlags {
+    /// Additional flag to pass to `rustc` for the on-chain build, can be repeated
+    #[clap(long = "rustflag", value_name = "FLAG", allow_hyphen_values = true)]
+    pub rustflags: Vec<String>,
+    /// Additional argument to pass to the linker, can be repeated
+    #[clap(long = "link-arg", value_name = "ARG", allow_hyphen_values = true)]
+    pub link_args: Vec<String>,
+    /// Additional unstable `-Z` flag to pass to `cargo`, can be repeated
+    #[clap(long = "cargo-z-flag", value_name = "FLAG", allow_hyphen_values = true)]
+    pub cargo_z_flags: Vec<String>,
+}
+
+impl BuildFlags {
+    /// Returns `true` if no additional flags are set.
+    pub fn is_empty(&self) -> bool {
+        self.rustflags.is_empty()
+            && self.link_args.is_empty()
+            && self.cargo_z_flags.is_empty()
+    }
+
+    /// Appends the flags of `other` to the flags of `self`.
+    pub fn merge(&mut self, other: &BuildFlags) {
+        self.rustflags.extend(other.rustflags.iter().cloned());
+        self.link_args.extend(other.link_args.iter().cloned());
+        self.cargo_z_flags
+            .extend(other.cargo_z_flags.iter().cloned());
+    }
+
+    /// Returns the `rustc` flags, including the linker arguments.
+    pub fn rustflags(&self) -> Vec<String> {
+        let link_args = self.link_args.iter().map(|arg| format!("-Clink-arg={arg}"));
+        self.rustflags.iter().cloned().chain(link_args).collect()
+    }
+
+    /// Appends the unstable flags to pass through to the `cargo` invocation.
+    pub fn append_to_args(&self, args: &mut Vec<String>) {
+        args.extend(self.cargo_z_flags.iter().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_features_exclude_std_and_ink_as_dependency() {
+        let features = Features {
+            features: vec!["foo".to_string()],
+            all_features: true,
+            no_default_features: false,
+        };
+        let available = ["default", "std", "ink-as-dependency", "foo", "bar"]
+            .map(String::from);
+
+        let features = features.expand_all_features(&available);
+        let mut args = Vec::new();
+        features.append_to_args(&mut args);
+
+        assert_eq!(args, vec!["--features".to_string(), "foo,bar".to_string()]);
+    }
+
+    #[test]
+    fn features_are_kept_without_all_features() {
+        let features = Features {
+            features: vec!["foo".to_string()],
+            all_features: false,
+            no_default_features: true,
+        };
+        let available = ["std", "foo", "bar"].map(String::from);
+
+        assert_eq!(features.expand_all_features(&available), features);
     }
 }
@@ -0,0 +1,345 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Comparison of two versions of a contract, e.g. to review an upgrade before switching
+//! the code of a contract with `set_code_hash`.
+
+use crate::code_hash;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{
+    Map,
+    Value,
+};
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+use wasmparser::{
+    Parser,
+    Payload,
+};
+
+/// The differences between two versions of a contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ArtifactDiff {
+    /// The hex encoded code hash of the old code.
+    pub old_code_hash: String,
+    /// The hex encoded code hash of the new code.
+    pub new_code_hash: String,
+    /// The sizes of the sections in both versions of the code.
+    pub sections: Vec<SectionDiff>,
+    /// The names of the exports only present in the new code.
+    pub added_exports: Vec<String>,
+    /// The names of the exports only present in the old code.
+    pub removed_exports: Vec<String>,
+    /// The differences of the constructors and messages, `None` if the metadata of
+    /// either version is unavailable.
+    pub abi: Option<AbiDiff>,
+}
+
+/// The size of a section in both versions of the code, `None` if it is absent.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SectionDiff {
+    /// The name of the section, custom sections are prefixed with `custom:`.
+    pub name: String,
+    pub old_size: Option<usize>,
+    pub new_size: Option<usize>,
+}
+
+impl SectionDiff {
+    /// Returns `true` if the size of the section changed.
+    pub fn changed(&self) -> bool {
+        self.old_size != self.new_size
+    }
+}
+
+/// The differences of the constructors and messages of two versions of a contract.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct AbiDiff {
+    pub constructors: EntryPointsDiff,
+    pub messages: EntryPointsDiff,
+}
+
+impl AbiDiff {
+    /// Returns `true` if no constructor or message was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.constructors.is_empty() && self.messages.is_empty()
+    }
+}
+
+/// The differences of either the constructors or the messages of a contract.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct EntryPointsDiff {
+    /// The labels of the entry points only present in the new version.
+    pub added: Vec<String>,
+    /// The labels of the entry points only present in the old version.
+    pub removed: Vec<String>,
+    /// The entry points present in both versions, whose selector changed.
+    pub changed_selectors: Vec<SelectorChange>,
+}
+
+impl EntryPointsDiff {
+    /// Returns `true` if no entry point was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed_selectors.is_empty()
+    }
+}
+
+/// The selectors of an entry point in both versions of a contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SelectorChange {
+    pub label: String,
+    pub old_selector: String,
+    pub new_selector: String,
+}
+
+impl ArtifactDiff {
+    /// Compares the `old` and `new` Wasm code of a contract, and the ink! ABIs from
+    /// their metadata, if available.
+    pub fn new(
+        old_code: &[u8],
+        old_abi: Option<&Map<String, Value>>,
+        new_code: &[u8],
+        new_abi: Option<&Map<String, Value>>,
+    ) -> Result<Self> {
+        let old = ModuleSummary::from_module(old_code)?;
+        let new = ModuleSummary::from_module(new_code)?;
+
+        let section_names = old
+            .sections
+            .keys()
+            .chain(new.sections.keys())
+            .collect::<BTreeSet<_>>();
+        let sections = section_names
+            .into_iter()
+            .map(|name| {
+                SectionDiff {
+                    name: name.clone(),
+                    old_size: old.sections.get(name).copied(),
+                    new_size: new.sections.get(name).copied(),
+                }
+            })
+            .collect();
+
+        let abi = match (old_abi, new_abi) {
+            (Some(old_abi), Some(new_abi)) => {
+                Some(AbiDiff {
+                    constructors: diff_entry_points(old_abi, new_abi, "constructors"),
+                    messages: diff_entry_points(old_abi, new_abi, "messages"),
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            old_code_hash: format!("0x{}", hex::encode(code_hash(old_code))),
+            new_code_hash: format!("0x{}", hex::encode(code_hash(new_code))),
+            sections,
+            added_exports: new.exports.difference(&old.exports).cloned().collect(),
+            removed_exports: old.exports.difference(&new.exports).cloned().collect(),
+            abi,
+        })
+    }
+
+    /// Returns `true` if both versions have the same code hash.
+    pub fn code_equal(&self) -> bool {
+        self.old_code_hash == self.new_code_hash
+    }
+}
+
+/// The section sizes and exports of a Wasm module.
+struct ModuleSummary {
+    sections: BTreeMap<String, usize>,
+    exports: BTreeSet<String>,
+}
+
+impl ModuleSummary {
+    fn from_module(module: &[u8]) -> Result<Self> {
+        let mut sections = BTreeMap::new();
+        let mut exports = BTreeSet::new();
+        for payload in Parser::new(0).parse_all(module) {
+            let payload = payload?;
+            let name = match &payload {
+                Payload::CustomSection(section) => format!("custom:{}", section.name()),
+                Payload::ExportSection(reader) => {
+                    for export in reader.clone() {
+                        exports.insert(export?.name.to_string());
+                    }
+                    "export".to_string()
+                }
+                payload => {
+                    match payload.as_section() {
+                        Some((id, _)) => section_name(id).to_string(),
+                        None => continue,
+                    }
+                }
+            };
+            if let Some((_, range)) = payload.as_section() {
+                *sections.entry(name).or_default() += range.len();
+            }
+        }
+        Ok(Self { sections, exports })
+    }
+}
+
+/// Returns the name of the standard section with the `id`.
+fn section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+/// Compares the constructors or `messages` of two ink! ABIs by their labels.
+fn diff_entry_points(
+    old_abi: &Map<String, Value>,
+    new_abi: &Map<String, Value>,
+    kind: &str,
+) -> EntryPointsDiff {
+    let old = entry_points(old_abi, kind);
+    let new = entry_points(new_abi, kind);
+    EntryPointsDiff {
+        added: new
+            .keys()
+            .filter(|label| !old.contains_key(*label))
+            .cloned()
+            .collect(),
+        removed: old
+            .keys()
+            .filter(|label| !new.contains_key(*label))
+            .cloned()
+            .collect(),
+        changed_selectors: old
+            .iter()
+            .filter_map(|(label, old_selector)| {
+                let new_selector = new.get(label)?;
+                (new_selector != old_selector).then(|| {
+                    SelectorChange {
+                        label: label.clone(),
+                        old_selector: old_selector.clone(),
+                        new_selector: new_selector.clone(),
+                    }
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Returns the selectors of the constructors or messages of an ink! ABI by their
+/// labels.
+fn entry_points(abi: &Map<String, Value>, kind: &str) -> BTreeMap<String, String> {
+    abi.get("spec")
+        .and_then(|spec| spec.get(kind))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let label = entry.get("label")?.as_str()?;
+            let selector = entry.get("selector")?.as_str()?;
+            Some((label.to_string(), selector.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn module(wat: &str) -> Vec<u8> {
+        wabt::wat2wasm(wat).expect("Invalid wabt")
+    }
+
+    fn abi(messages: Value) -> Map<String, Value> {
+        json!({ "spec": { "constructors": [], "messages": messages } })
+            .as_object()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn detects_changed_code_and_exports() {
+        let old = module(r#"(module (func (export "call")) (func (export "deploy")))"#);
+        let new = module(
+            r#"(module
+                (func (export "call") (drop (i32.const 1)))
+                (func (export "upgrade"))
+            )"#,
+        );
+
+        let diff = ArtifactDiff::new(&old, None, &new, None).unwrap();
+
+        assert!(!diff.code_equal());
+        assert_eq!(diff.added_exports, vec!["upgrade"]);
+        assert_eq!(diff.removed_exports, vec!["deploy"]);
+        let code = diff.sections.iter().find(|s| s.name == "code").unwrap();
+        assert!(code.changed());
+        assert!(diff.abi.is_none());
+    }
+
+    #[test]
+    fn identical_code_is_equal() {
+        let code = module(r#"(module (func (export "call")))"#);
+
+        let diff = ArtifactDiff::new(&code, None, &code, None).unwrap();
+
+        assert!(diff.code_equal());
+        assert!(diff.sections.iter().all(|section| !section.changed()));
+    }
+
+    #[test]
+    fn detects_abi_changes() {
+        let code = module("(module)");
+        let old = abi(json!([
+            { "label": "flip", "selector": "0x633aa551" },
+            { "label": "get", "selector": "0x2f865bd9" },
+        ]));
+        let new = abi(json!([
+            { "label": "flip", "selector": "0x00000001" },
+            { "label": "set", "selector": "0xe8c45eb6" },
+        ]));
+
+        let diff = ArtifactDiff::new(&code, Some(&old), &code, Some(&new)).unwrap();
+        let abi = diff.abi.unwrap();
+
+        assert!(abi.constructors.is_empty());
+        assert_eq!(abi.messages.added, vec!["set"]);
+        assert_eq!(abi.messages.removed, vec!["get"]);
+        assert_eq!(
+            abi.messages.changed_selectors,
+            vec![SelectorChange {
+                label: "flip".to_string(),
+                old_selector: "0x633aa551".to_string(),
+                new_selector: "0x00000001".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,540 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Audit of the licenses of the dependencies of a contract and of the security
+//! advisories of the [RustSec advisory database](https://rustsec.org) affecting them.
+
+use crate::{
+    output,
+    CrateMetadata,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use semver::{
+    Version,
+    VersionReq,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+    },
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+/// Git repository of the RustSec advisory database.
+pub const ADVISORY_DB_URL: &str = "https://github.com/rustsec/advisory-db.git";
+
+/// Settings of the dependency audit, configured in the
+/// `[package.metadata.contract.audit]` section of the contract's `Cargo.toml`, e.g.
+///
+/// ```toml
+/// [package.metadata.contract.audit]
+/// allow-licenses = ["Apache-2.0", "MIT", "Unicode-DFS-2016"]
+/// deny-licenses = ["GPL-3.0"]
+/// deny-advisories = true
+/// ignore-advisories = ["RUSTSEC-2020-0071"]
+/// ```
+///
+/// and via the corresponding CLI flags, which are merged with the configured ones.
+#[derive(Default, Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct AuditConfig {
+    /// SPDX identifiers of the only licenses the dependencies may be used under.
+    pub allow_licenses: Vec<String>,
+    /// SPDX identifiers of licenses the dependencies must not be used under. A
+    /// license also denies its `-only` and `-or-later` variants.
+    pub deny_licenses: Vec<String>,
+    /// Fail if a dependency is affected by a security advisory.
+    pub deny_advisories: bool,
+    /// Ids of advisories which are not reported.
+    pub ignore_advisories: Vec<String>,
+    /// A checkout of the advisory database to use as is, instead of the one in
+    /// `$CARGO_HOME/advisory-db` which is fetched before the audit.
+    pub advisory_db: Option<PathBuf>,
+}
+
+impl AuditConfig {
+    /// Returns `true` if any check of the audit is enabled.
+    pub fn is_enabled(&self) -> bool {
+        !self.allow_licenses.is_empty()
+            || !self.deny_licenses.is_empty()
+            || self.deny_advisories
+    }
+
+    /// Merges the settings of `other` into `self`, the advisory database of `other`
+    /// takes precedence.
+    pub fn merge(&mut self, other: &AuditConfig) {
+        self.allow_licenses.extend(other.allow_licenses.iter().cloned());
+        self.deny_licenses.extend(other.deny_licenses.iter().cloned());
+        self.deny_advisories |= other.deny_advisories;
+        self.ignore_advisories
+            .extend(other.ignore_advisories.iter().cloned());
+        if other.advisory_db.is_some() {
+            self.advisory_db.clone_from(&other.advisory_db);
+        }
+    }
+
+    /// Returns `true` if the license expression of a package is acceptable.
+    ///
+    /// An expression is acceptable if one of its alternatives contains no denied
+    /// license and, if allowed licenses are configured, only allowed ones. Packages
+    /// without a license expression are only rejected if allowed licenses are
+    /// configured.
+    fn accepts(&self, expression: Option<&str>) -> bool {
+        let Some(expression) = expression else {
+            return self.allow_licenses.is_empty()
+        };
+        let Ok(alternatives) = license_alternatives(expression) else {
+            return false
+        };
+        let denied = |license: &String| {
+            self.deny_licenses
+                .iter()
+                .any(|id| license_matches(license, id))
+        };
+        let allowed = |license: &String| {
+            self.allow_licenses.is_empty()
+                || self
+                    .allow_licenses
+                    .iter()
+                    .any(|id| license_matches(license, id))
+        };
+        alternatives.iter().any(|licenses| {
+            licenses
+                .iter()
+                .all(|license| !denied(license) && allowed(license))
+        })
+    }
+}
+
+/// A dependency used under a license which is not acceptable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicenseViolation {
+    pub package: String,
+    pub version: String,
+    /// The license expression of the package, `None` if it declares none.
+    pub license: Option<String>,
+}
+
+/// A dependency affected by a security advisory.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdvisoryMatch {
+    pub package: String,
+    pub version: String,
+    /// The id of the advisory, e.g. `RUSTSEC-2021-0001`.
+    pub id: String,
+    pub title: String,
+    /// The versions of the package in which the advisory is fixed.
+    pub patched: Vec<String>,
+}
+
+/// The findings of the audit of the dependencies of a contract.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditReport {
+    pub licenses: Vec<LicenseViolation>,
+    pub advisories: Vec<AdvisoryMatch>,
+}
+
+impl AuditReport {
+    /// Returns `true` if the audit found nothing.
+    pub fn is_empty(&self) -> bool {
+        self.licenses.is_empty() && self.advisories.is_empty()
+    }
+}
+
+impl Display for AuditReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for violation in &self.licenses {
+            match &violation.license {
+                Some(license) => {
+                    writeln!(
+                        f,
+                        "  {} {}: license `{}` is not allowed",
+                        violation.package, violation.version, license
+                    )?
+                }
+                None => {
+                    writeln!(
+                        f,
+                        "  {} {}: no license declared",
+                        violation.package, violation.version
+                    )?
+                }
+            }
+        }
+        for advisory in &self.advisories {
+            let patched = if advisory.patched.is_empty() {
+                "no patched versions".to_string()
+            } else {
+                format!("patched: {}", advisory.patched.join(", "))
+            };
+            writeln!(
+                f,
+                "  {} {}: {} {} ({})",
+                advisory.package, advisory.version, advisory.id, advisory.title, patched
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Audits the dependencies the contract is built with, see
+/// [`CrateMetadata::dependency_graph`], and fails with a report of the findings.
+///
+/// Only dependencies from crates.io are checked against the advisory database.
+pub fn audit_dependencies(
+    crate_metadata: &CrateMetadata,
+    config: &AuditConfig,
+) -> Result<AuditReport> {
+    let graph = crate_metadata.dependency_graph()?;
+    let root = &crate_metadata.root_package.id;
+    let packages = graph
+        .iter()
+        .map(|(package, _)| *package)
+        .filter(|package| &package.id != root)
+        .collect::<Vec<_>>();
+
+    let mut report = AuditReport::default();
+    if !config.allow_licenses.is_empty() || !config.deny_licenses.is_empty() {
+        report.licenses = packages
+            .iter()
+            .filter(|package| !config.accepts(package.license.as_deref()))
+            .map(|package| {
+                LicenseViolation {
+                    package: package.name.clone(),
+                    version: package.version.to_string(),
+                    license: package.license.clone(),
+                }
+            })
+            .collect();
+    }
+    if config.deny_advisories {
+        let db = match &config.advisory_db {
+            Some(db) => db.clone(),
+            None => fetch_advisory_db()?,
+        };
+        for package in packages.iter().filter(|package| {
+            package
+                .source
+                .as_ref()
+                .is_some_and(|source| source.is_crates_io())
+        }) {
+            for advisory in advisories(&db, &package.name)? {
+                if config.ignore_advisories.contains(&advisory.id)
+                    || !advisory.affects(&package.version)
+                {
+                    continue
+                }
+                report.advisories.push(AdvisoryMatch {
+                    package: package.name.clone(),
+                    version: package.version.to_string(),
+                    id: advisory.id,
+                    title: advisory.title,
+                    patched: advisory.patched.iter().map(ToString::to_string).collect(),
+                });
+            }
+        }
+    }
+
+    if !report.is_empty() {
+        anyhow::bail!("The dependency audit failed:\n{report}")
+    }
+    Ok(report)
+}
+
+/// Clones the advisory database into `$CARGO_HOME/advisory-db`, or updates it if it
+/// was cloned before. A failing update only results in a warning.
+fn fetch_advisory_db() -> Result<PathBuf> {
+    let cargo_home = match std::env::var_os("CARGO_HOME") {
+        Some(cargo_home) => PathBuf::from(cargo_home),
+        None => {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".cargo"))
+                .context(
+                    "Cannot locate the advisory database, pass its path with \
+                    `--advisory-db`",
+                )?
+        }
+    };
+    let db = cargo_home.join("advisory-db");
+    if db.join(".git").exists() {
+        let updated = Command::new("git")
+            .args(["pull", "--quiet", "--ff-only"])
+            .current_dir(&db)
+            .status()
+            .is_ok_and(|status| status.success());
+        if !updated {
+            output::warning(format!(
+                "Failed to update the advisory database in {}, auditing with the \
+                existing one",
+                db.display()
+            ));
+        }
+    } else {
+        let status = Command::new("git")
+            .args(["clone", "--quiet", "--depth", "1", ADVISORY_DB_URL])
+            .arg(&db)
+            .status()
+            .context("Failed to execute git, is it installed?")?;
+        if !status.success() {
+            anyhow::bail!("Failed to clone the advisory database from {ADVISORY_DB_URL}");
+        }
+    }
+    Ok(db)
+}
+
+/// An advisory of the RustSec advisory database.
+#[derive(Clone, Debug)]
+struct Advisory {
+    id: String,
+    title: String,
+    patched: Vec<VersionReq>,
+    unaffected: Vec<VersionReq>,
+    /// Informational advisories, e.g. about unmaintained crates, and withdrawn ones
+    /// are not reported.
+    ignored: bool,
+}
+
+impl Advisory {
+    /// Parses an advisory, a markdown file with a TOML front matter.
+    fn parse(content: &str) -> Result<Self> {
+        #[derive(serde::Deserialize)]
+        struct Front {
+            advisory: Meta,
+            #[serde(default)]
+            versions: Versions,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Meta {
+            id: String,
+            informational: Option<String>,
+            withdrawn: Option<String>,
+        }
+
+        #[derive(Default, serde::Deserialize)]
+        struct Versions {
+            #[serde(default)]
+            patched: Vec<VersionReq>,
+            #[serde(default)]
+            unaffected: Vec<VersionReq>,
+        }
+
+        let (front, markdown) = content
+            .trim_start()
+            .strip_prefix("```toml")
+            .and_then(|rest| rest.split_once("\n```"))
+            .context("Missing the TOML front matter of the advisory")?;
+        let front: Front = toml::from_str(front)?;
+        let title = markdown
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        Ok(Self {
+            id: front.advisory.id,
+            title,
+            patched: front.versions.patched,
+            unaffected: front.versions.unaffected,
+            ignored: front.advisory.informational.is_some()
+                || front.advisory.withdrawn.is_some(),
+        })
+    }
+
+    /// Returns `true` if the version of a package is affected by the advisory.
+    fn affects(&self, version: &Version) -> bool {
+        !self.ignored
+            && !self
+                .patched
+                .iter()
+                .chain(&self.unaffected)
+                .any(|req| req.matches(version))
+    }
+}
+
+/// Returns the advisories of a crate in the advisory database.
+fn advisories(db: &Path, name: &str) -> Result<Vec<Advisory>> {
+    let dir = db.join("crates").join(name);
+    if !dir.exists() {
+        return Ok(Vec::new())
+    }
+    let mut advisories = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            continue
+        }
+        let content = fs::read_to_string(&path)?;
+        let advisory = Advisory::parse(&content)
+            .with_context(|| format!("Invalid advisory {}", path.display()))?;
+        advisories.push(advisory);
+    }
+    advisories.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(advisories)
+}
+
+/// Returns `true` if `license` is the SPDX identifier `id`, or one of its `-only`,
+/// `-or-later` and `+` variants.
+fn license_matches(license: &str, id: &str) -> bool {
+    ["", "-only", "-or-later", "+"]
+        .iter()
+        .any(|suffix| license.eq_ignore_ascii_case(&format!("{id}{suffix}")))
+}
+
+/// Parses an SPDX license expression into its alternatives, each the licenses which
+/// apply together, e.g. `MIT OR (Apache-2.0 AND ISC)` into
+/// `[[MIT], [Apache-2.0, ISC]]`.
+///
+/// The `/` separator of older crates is read as `OR`, exceptions of a `WITH` clause are
+/// dropped.
+fn license_alternatives(expression: &str) -> Result<Vec<Vec<String>>> {
+    let normalized = expression
+        .replace('/', " OR ")
+        .replace('(', " ( ")
+        .replace(')', " ) ");
+    let mut parser = LicenseParser {
+        tokens: normalized.split_whitespace().collect(),
+        position: 0,
+    };
+    let alternatives = parser.or_expression();
+    match alternatives {
+        Some(alternatives) if parser.position == parser.tokens.len() => {
+            Ok(alternatives)
+        }
+        _ => anyhow::bail!("Invalid license expression `{expression}`"),
+    }
+}
+
+/// Recursive descent parser of SPDX license expressions, expanding them into a
+/// disjunction of conjunctions.
+struct LicenseParser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> LicenseParser<'a> {
+    fn next_if(&mut self, keyword: &str) -> bool {
+        let matches = self.tokens.get(self.position) == Some(&keyword);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn or_expression(&mut self) -> Option<Vec<Vec<String>>> {
+        let mut alternatives = self.and_expression()?;
+        while self.next_if("OR") {
+            alternatives.extend(self.and_expression()?);
+        }
+        Some(alternatives)
+    }
+
+    fn and_expression(&mut self) -> Option<Vec<Vec<String>>> {
+        let mut alternatives = self.term()?;
+        while self.next_if("AND") {
+            let right = self.term()?;
+            alternatives = alternatives
+                .iter()
+                .flat_map(|left| {
+                    right.iter().map(move |right| {
+                        left.iter().chain(right).cloned().collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+        }
+        Some(alternatives)
+    }
+
+    fn term(&mut self) -> Option<Vec<Vec<String>>> {
+        if self.next_if("(") {
+            let alternatives = self.or_expression()?;
+            return self.next_if(")").then_some(alternatives)
+        }
+        let license = *self.tokens.get(self.position)?;
+        if ["(", ")", "AND", "OR", "WITH"].contains(&license) {
+            return None
+        }
+        self.position += 1;
+        if self.next_if("WITH") {
+            self.tokens.get(self.position)?;
+            self.position += 1;
+        }
+        Some(vec![vec![license.to_string()]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_license_expressions() {
+        let config = AuditConfig {
+            allow_licenses: vec!["MIT".into(), "Apache-2.0".into(), "GPL-3.0".into()],
+            deny_licenses: vec!["GPL-3.0".into()],
+            ..Default::default()
+        };
+
+        assert!(config.accepts(Some("MIT OR Apache-2.0")));
+        assert!(config.accepts(Some("GPL-3.0-only OR (MIT AND Apache-2.0)")));
+        assert!(config.accepts(Some("MIT/Apache-2.0")));
+        assert!(config.accepts(Some("Apache-2.0 WITH LLVM-exception")));
+        assert!(!config.accepts(Some("GPL-3.0-or-later")));
+        assert!(!config.accepts(Some("MIT AND ISC")));
+        assert!(!config.accepts(Some("MIT OR")));
+        assert!(!config.accepts(None));
+        assert!(AuditConfig::default().accepts(None));
+    }
+
+    #[test]
+    fn parses_advisory() {
+        let content = r#"```toml
+[advisory]
+id = "RUSTSEC-2020-0071"
+package = "time"
+date = "2020-11-18"
+url = "https://github.com/time-rs/time/issues/293"
+categories = ["code-execution", "memory-corruption"]
+
+[versions]
+patched = [">= 0.2.23"]
+unaffected = ["= 0.2.0", "= 0.2.1"]
+```
+
+# Potential segfault in the time crate
+
+## Impact
+"#;
+        let advisory = Advisory::parse(content).unwrap();
+
+        assert_eq!(advisory.id, "RUSTSEC-2020-0071");
+        assert_eq!(advisory.title, "Potential segfault in the time crate");
+        let affects = |version| advisory.affects(&Version::parse(version).unwrap());
+        assert!(affects("0.1.45"));
+        assert!(!affects("0.2.1"));
+        assert!(affects("0.2.22"));
+        assert!(!affects("0.3.0"));
+    }
+}
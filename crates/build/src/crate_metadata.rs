@@ -23,9 +23,11 @@ use anyhow::{
     Result,
 };
 use cargo_metadata::{
+    DependencyKind,
     Metadata as CargoMetadata,
     MetadataCommand,
     Package,
+    PackageId,
 };
 use semver::Version;
 use serde_json::{
@@ -33,8 +35,16 @@ use serde_json::{
     Value,
 };
 use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    env,
     fs,
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 use toml::value;
 use url::Url;
@@ -54,6 +64,11 @@ pub struct CrateMetadata {
     pub user: Option<Map<String, Value>>,
     pub target_directory: PathBuf,
     pub target_file_path: PathBuf,
+    /// The directory the contract artifacts are written to, the target directory unless
+    /// overridden with `--artifacts-dir` or `--target-dir`.
+    pub artifact_directory: PathBuf,
+    /// The file recording the artifact directory of the last build, if overridden.
+    pub artifact_directory_file: PathBuf,
 }
 
 impl CrateMetadata {
@@ -67,9 +82,38 @@ impl CrateMetadata {
     }
 
     /// Parses the contract manifest and returns relevant metadata.
+    ///
+    /// The artifacts are located in the artifact directory recorded by the last build.
     pub fn collect(manifest_path: &ManifestPath, target: Target) -> Result<Self> {
+        let mut crate_metadata =
+            Self::collect_with_directories(manifest_path, target, None, None)?;
+        if let Ok(directory) = fs::read_to_string(&crate_metadata.artifact_directory_file)
+        {
+            let directory = PathBuf::from(directory.trim());
+            let dest_code = crate_metadata
+                .dest_code
+                .file_name()
+                .expect("the contract code path has a file name");
+            crate_metadata.dest_code = directory.join(dest_code);
+            crate_metadata.artifact_directory = directory;
+        }
+        Ok(crate_metadata)
+    }
+
+    /// Parses the contract manifest and returns relevant metadata, overriding the cargo
+    /// target directory and the directory the artifacts are written to.
+    pub fn collect_with_directories(
+        manifest_path: &ManifestPath,
+        target: Target,
+        target_dir: Option<&Path>,
+        artifacts_dir: Option<&Path>,
+    ) -> Result<Self> {
         let (metadata, root_package) = get_cargo_metadata(manifest_path)?;
-        let mut target_directory = metadata.target_directory.as_path().join("ink");
+        let mut default_directory = metadata.target_directory.as_std_path().join("ink");
+        let mut target_directory = match target_dir {
+            Some(target_dir) => absolute_path(target_dir)?.join("ink"),
+            None => default_directory.clone(),
+        };
 
         // Normalize the final contract artifact name.
         let contract_artifact_name = root_package.name.replace('-', "_");
@@ -82,13 +126,11 @@ impl CrateMetadata {
             if lib_name.name != root_package.name {
                 // warn user if they still specify a lib name different from the
                 // package name
-                use colored::Colorize;
-                eprintln!(
-                    "{} the `name` field in the `[lib]` section of the `Cargo.toml`, \
+                crate::output::warning(
+                    "the `name` field in the `[lib]` section of the `Cargo.toml`, \
                     is no longer used for the name of generated contract artifacts. \
                     The package name is used instead. Remove the `[lib] name` to \
                     stop this warning.",
-                    "warning:".yellow().bold(),
                 );
             }
         }
@@ -99,7 +141,12 @@ impl CrateMetadata {
             // If the contract is a package in a workspace, we use the package name
             // as the name of the sub-folder where we put the `.contract` bundle.
             target_directory = target_directory.join(contract_artifact_name.clone());
+            default_directory = default_directory.join(contract_artifact_name.clone());
         }
+        let artifact_directory = match artifacts_dir {
+            Some(artifacts_dir) => absolute_path(artifacts_dir)?,
+            None => target_directory.clone(),
+        };
 
         // {target_dir}/{target}/release/{contract_artifact_name}.{extension}
         let mut original_code = target_directory.clone();
@@ -108,8 +155,8 @@ impl CrateMetadata {
         original_code.push(root_package.name.clone());
         original_code.set_extension(target.source_extension());
 
-        // {target_dir}/{contract_artifact_name}.code
-        let mut dest_code = target_directory.clone();
+        // {artifact_dir}/{contract_artifact_name}.code
+        let mut dest_code = artifact_directory.clone();
         dest_code.push(contract_artifact_name.clone());
         dest_code.set_extension(target.dest_extension());
 
@@ -139,14 +186,16 @@ impl CrateMetadata {
             cargo_meta: metadata,
             root_package,
             contract_artifact_name,
-            original_code: original_code.into(),
-            dest_code: dest_code.into(),
+            original_code,
+            dest_code,
             ink_version,
             documentation,
             homepage,
             user,
-            target_file_path: target_directory.join(".target").into(),
-            target_directory: target_directory.into(),
+            target_file_path: target_directory.join(".target"),
+            target_directory,
+            artifact_directory,
+            artifact_directory_file: default_directory.join(".artifacts"),
         };
         Ok(crate_metadata)
     }
@@ -154,15 +203,130 @@ impl CrateMetadata {
     /// Get the path of the contract metadata file
     pub fn metadata_path(&self) -> PathBuf {
         let metadata_file = format!("{}.json", self.contract_artifact_name);
-        self.target_directory.join(metadata_file)
+        self.artifact_directory.join(metadata_file)
     }
 
     /// Get the path of the contract bundle, containing metadata + code.
     pub fn contract_bundle_path(&self) -> PathBuf {
-        let target_directory = self.target_directory.clone();
+        let artifact_directory = self.artifact_directory.clone();
         let fname_bundle = format!("{}.contract", self.contract_artifact_name);
-        target_directory.join(fname_bundle)
+        artifact_directory.join(fname_bundle)
     }
+
+    /// Records the artifact directory if it is overridden, so that [`Self::collect`]
+    /// locates the artifacts for the commands using them later on.
+    pub fn persist_artifact_directory(&self) -> Result<()> {
+        let default_directory = self
+            .artifact_directory_file
+            .parent()
+            .expect("the artifact directory file has a parent directory");
+        if self.artifact_directory == default_directory {
+            fs::remove_file(&self.artifact_directory_file).ok();
+        } else {
+            fs::create_dir_all(default_directory)?;
+            fs::write(
+                &self.artifact_directory_file,
+                self.artifact_directory.to_string_lossy().as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the packages the contract is built with, the root package and its
+    /// dependencies without the dev-dependencies, each with the packages it depends on.
+    ///
+    /// The packages are ordered by their id.
+    pub fn dependency_graph(&self) -> Result<Vec<(&Package, Vec<&Package>)>> {
+        let resolve = self
+            .cargo_meta
+            .resolve
+            .as_ref()
+            .context("Cannot resolve the dependencies of the contract")?;
+        let packages = self
+            .cargo_meta
+            .packages
+            .iter()
+            .map(|package| (&package.id, package))
+            .collect::<HashMap<_, _>>();
+        let nodes = resolve
+            .nodes
+            .iter()
+            .map(|node| (&node.id, node))
+            .collect::<HashMap<_, _>>();
+        let package = |id: &PackageId| {
+            packages
+                .get(id)
+                .copied()
+                .with_context(|| format!("Package {id} not found"))
+        };
+
+        let mut graph = BTreeMap::new();
+        let mut pending = vec![&self.root_package.id];
+        while let Some(id) = pending.pop() {
+            if graph.contains_key(id) {
+                continue
+            }
+            let deps = nodes
+                .get(id)
+                .map(|node| {
+                    node.deps
+                        .iter()
+                        .filter(|dep| {
+                            dep.dep_kinds
+                                .iter()
+                                .any(|info| info.kind != DependencyKind::Development)
+                        })
+                        .map(|dep| &dep.pkg)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let depends_on = deps
+                .iter()
+                .map(|dep| package(dep))
+                .collect::<Result<Vec<_>>>()?;
+            graph.insert(id, (package(id)?, depends_on));
+            pending.extend(deps);
+        }
+        Ok(graph.into_values().collect())
+    }
+}
+
+/// Returns the `path` relative to the current directory if it is not absolute.
+fn absolute_path(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf())
+    }
+    Ok(env::current_dir()?.join(path))
+}
+
+/// Returns the manifests of the contracts which are members of the workspace of
+/// `manifest_path`, i.e. the members depending on `ink`.
+///
+/// The `manifest_path` may also be the one of a virtual workspace.
+pub(crate) fn workspace_contracts(
+    manifest_path: &ManifestPath,
+) -> Result<Vec<ManifestPath>> {
+    let metadata = MetadataCommand::new()
+        .manifest_path(manifest_path.as_ref())
+        .no_deps()
+        .exec()
+        .with_context(|| {
+            format!(
+                "Error invoking `cargo metadata` for {}",
+                manifest_path.as_ref().display()
+            )
+        })?;
+    metadata
+        .workspace_packages()
+        .into_iter()
+        .filter(|package| {
+            package
+                .dependencies
+                .iter()
+                .any(|dep| dep.name == "ink" && dep.kind == DependencyKind::Normal)
+        })
+        .map(|package| ManifestPath::new(&package.manifest_path))
+        .collect()
 }
 
 /// Get the result of `cargo metadata`, together with the root package id.
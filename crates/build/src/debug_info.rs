@@ -0,0 +1,210 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generation of debug artifacts, which allow symbolicating traps of the deployed
+//! contract without bloating its code.
+
+use crate::CrateMetadata;
+use anyhow::{
+    Context,
+    Result,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+use wasm_encoder::{
+    RawSection,
+    Section as _,
+};
+use wasmparser::{
+    Name,
+    NameSectionReader,
+    Parser,
+    Payload,
+};
+
+/// Paths to the debug artifacts generated alongside the contract code.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DebugArtifacts {
+    /// Path to the optimized Wasm which retains the name section and DWARF debug info.
+    pub debug_wasm: PathBuf,
+    /// Path to the JSON file mapping function indices to Rust symbols.
+    pub symbol_map: PathBuf,
+}
+
+impl DebugArtifacts {
+    /// Returns the paths of the debug artifacts for the contract.
+    pub fn new(crate_metadata: &CrateMetadata) -> Self {
+        Self {
+            debug_wasm: crate_metadata.dest_code.with_extension("debug.wasm"),
+            symbol_map: crate_metadata.dest_code.with_extension("symbols.json"),
+        }
+    }
+
+    /// Returns `true` if all debug artifacts exist.
+    pub fn exist(&self) -> bool {
+        self.debug_wasm.exists() && self.symbol_map.exists()
+    }
+
+    /// Removes the debug artifacts, so that none of them are stale.
+    pub fn clear(&self) {
+        fs::remove_file(&self.debug_wasm).ok();
+        fs::remove_file(&self.symbol_map).ok();
+    }
+}
+
+/// Maps the function indices of the contract code to the demangled Rust symbols.
+///
+/// The contract code is derived from the debug Wasm by stripping its debug info, so the
+/// indices are valid for the deployed contract code as well.
+#[derive(Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SymbolMap {
+    pub functions: BTreeMap<u32, String>,
+}
+
+impl SymbolMap {
+    /// Extracts the function names from the name section of `module`.
+    pub fn from_module(module: &[u8]) -> Result<Self> {
+        let mut functions = BTreeMap::new();
+        for payload in Parser::new(0).parse_all(module) {
+            let Payload::CustomSection(section) = payload? else {
+                continue
+            };
+            if section.name() != "name" {
+                continue
+            }
+            let reader = NameSectionReader::new(section.data(), section.data_offset());
+            for name in reader {
+                if let Name::Function(names) = name? {
+                    for naming in names {
+                        let naming = naming?;
+                        let symbol =
+                            format!("{:#}", rustc_demangle::demangle(naming.name));
+                        functions.insert(naming.index, symbol);
+                    }
+                }
+            }
+        }
+        Ok(Self { functions })
+    }
+}
+
+/// Writes the symbol map extracted from the optimized debug Wasm, and derives the
+/// contract code at `dest_code` from it by stripping the debug info.
+///
+/// The name section is kept in the contract code if `keep_debug_symbols` is set.
+pub(crate) fn generate(
+    debug_artifacts: &DebugArtifacts,
+    dest_code: &Path,
+    keep_debug_symbols: bool,
+) -> Result<()> {
+    let module = fs::read(&debug_artifacts.debug_wasm)?;
+    let symbol_map = SymbolMap::from_module(&module)
+        .context("Failed to extract the symbols from the debug Wasm")?;
+    fs::write(
+        &debug_artifacts.symbol_map,
+        serde_json::to_string_pretty(&symbol_map)?,
+    )?;
+    fs::write(dest_code, strip_debug_info(&module, keep_debug_symbols)?)?;
+    Ok(())
+}
+
+/// Removes the DWARF sections, and the name section unless `keep_names` is set, from
+/// `module` without touching any other section.
+fn strip_debug_info(module: &[u8], keep_names: bool) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    for payload in Parser::new(0).parse_all(module) {
+        let payload = payload?;
+        match payload {
+            Payload::Version { .. } => {
+                output.extend_from_slice(&wasm_encoder::Module::HEADER);
+                continue
+            }
+            Payload::End(_) => break,
+            Payload::CustomSection(ref c)
+                if c.name().starts_with(".debug_")
+                    || (c.name() == "name" && !keep_names) =>
+            {
+                continue
+            }
+            _ => {}
+        }
+        if let Some((id, range)) = payload.as_section() {
+            RawSection {
+                id,
+                data: &module[range],
+            }
+            .append_to(&mut output);
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_map_contains_demangled_function_names() {
+        let contract = r#"
+            (module
+                (func $_ZN7flipper7Flipper4flip17h0123456789abcdefE)
+                (func $deploy)
+            )"#;
+        let module = wabt::Wat2Wasm::new()
+            .write_debug_names(true)
+            .convert(contract)
+            .expect("Invalid wabt");
+
+        let symbol_map = SymbolMap::from_module(module.as_ref()).unwrap();
+
+        assert_eq!(
+            symbol_map.functions,
+            BTreeMap::from([
+                (0, "flipper::Flipper::flip".to_string()),
+                (1, "deploy".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn stripping_debug_info_keeps_the_code() {
+        let contract = r#"
+            (module
+                (func $flip (result i32) (i32.const 1))
+                (func $deploy)
+            )"#;
+        let with_names = wabt::Wat2Wasm::new()
+            .write_debug_names(true)
+            .convert(contract)
+            .expect("Invalid wabt");
+        let without_names = wabt::Wat2Wasm::new()
+            .convert(contract)
+            .expect("Invalid wabt");
+
+        let stripped = strip_debug_info(with_names.as_ref(), false).unwrap();
+        let kept = strip_debug_info(with_names.as_ref(), true).unwrap();
+
+        assert_eq!(stripped, without_names.as_ref());
+        assert_eq!(kept, with_names.as_ref());
+        assert!(SymbolMap::from_module(&stripped).unwrap().functions.is_empty());
+    }
+}
@@ -137,7 +137,7 @@ pub fn docker_build(args: ExecuteArgs) -> Result<BuildResult> {
         .block_on(async {
             let crate_metadata = CrateMetadata::collect(&manifest_path, target)?;
             let host_folder = std::env::current_dir()?;
-            let args = compose_build_args()?;
+            let args = container_build_args(std::env::args());
 
             let client = Docker::connect_with_socket_defaults().map_err(|e| {
                 anyhow::anyhow!("{}\nDo you have the docker engine installed in path?", e)
@@ -234,10 +234,14 @@ fn update_build_result(host_folder: &Path, build_result: &mut BuildResult) -> Re
         );
         m
     });
+
+    if let Some(sbom) = build_result.sbom.as_mut() {
+        sbom.path = host_folder.join(sbom.path.strip_prefix(MOUNT_DIR)?);
+    }
     Ok(())
 }
 
-/// Overwrites `build_result` and `image` fields in the metadata.
+/// Overwrites `build_result`, `image` and `source.image_digest` fields in the metadata.
 async fn update_metadata(
     build_result: &BuildResult,
     verbosity: &Verbosity,
@@ -261,6 +265,16 @@ async fn update_metadata(
         };
 
         metadata.image = Some(image_tag);
+        // the repo digest pins the exact image, unlike the tag
+        let image_digest = match build_image
+            .repo_digests
+            .iter()
+            .find_map(|d| d.split_once('@'))
+        {
+            Some((_, digest)) => digest.to_owned(),
+            None => build_image.id.clone(),
+        };
+        metadata.source.image_digest = Some(image_digest);
 
         crate::metadata::write_metadata(metadata_artifacts, metadata, verbosity, true)?;
     }
@@ -468,34 +482,62 @@ async fn run_build(
     }
 }
 
-/// Takes CLI args from the host and appends them to the build command inside the docker.
-fn compose_build_args() -> Result<Vec<String>> {
-    use regex::Regex;
-    let mut args: Vec<String> = Vec::new();
-    // match `--image` or `verify` with arg with 1 or more white spaces surrounded
-    let rex = Regex::new(r#"(--image|verify)[ ]*[^ ]*[ ]*"#)?;
-    // we join the args together, so we can remove `--image <arg>`
-    let args_string: String = std::env::args().collect::<Vec<String>>().join(" ");
-    let args_string = rex.replace_all(&args_string, "").to_string();
-
-    // and then we turn it back to the vec, filtering out commands and arguments
-    // that should not be passed to the docker build command
-    let mut os_args: Vec<String> = args_string
-        .split_ascii_whitespace()
-        .filter(|a| {
-            a != &"--verifiable"
-                && !a.contains("cargo-contract")
-                && a != &"cargo"
-                && a != &"contract"
-                && a != &"build"
-                && a != &"--output-json"
-        })
-        .map(|s| s.to_string())
-        .collect();
-
-    args.append(&mut os_args);
-
-    Ok(args)
+/// Options of the build on the host, taking a value, which are not passed to the build
+/// inside the container.
+///
+/// The image is chosen, the code size limit is checked and the dependencies are audited
+/// outside of the container. The bundle is compressed, signed and annotated with its
+/// provenance on the host as well, which also keeps the signing secret out of the
+/// configuration of the container.
+const HOST_OPTIONS: &[&str] = &[
+    "--image",
+    "--url",
+    "--allow-licenses",
+    "--deny-licenses",
+    "--advisory-db",
+    "--sign",
+    "--signature-scheme",
+    "--compress-wasm",
+    "--source-archive-url",
+    "verify",
+];
+
+/// Flags and subcommands of the build on the host which are not passed to the build
+/// inside the container.
+const HOST_FLAGS: &[&str] = &[
+    "cargo",
+    "contract",
+    "build",
+    "--verifiable",
+    "--output-json",
+    "--deny-advisories",
+    "--sign-stdin",
+];
+
+/// Takes CLI args from the host and returns the ones appended to the build command
+/// inside the docker.
+fn container_build_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut args = args.into_iter();
+    let mut container_args = Vec::new();
+    while let Some(arg) = args.next() {
+        if HOST_OPTIONS.contains(&arg.as_str()) {
+            // skip the value of the option as well
+            args.next();
+            continue
+        }
+        let is_host_option_with_value = HOST_OPTIONS.iter().any(|option| {
+            arg.strip_prefix(option)
+                .is_some_and(|value| value.starts_with('='))
+        });
+        if is_host_option_with_value
+            || HOST_FLAGS.contains(&arg.as_str())
+            || arg.contains("cargo-contract")
+        {
+            continue
+        }
+        container_args.push(arg)
+    }
+    container_args
 }
 
 /// Pulls the docker image from the registry.
@@ -607,3 +649,40 @@ fn container_digest(entrypoint: Vec<String>, image_digest: String) -> String {
     let digest_code: String = digest.to_string().chars().take(5).collect();
     digest_code
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container_args(args: &str) -> Vec<String> {
+        container_build_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn host_options_are_not_passed_to_the_container() {
+        let args = container_args(
+            "cargo-contract contract build --verifiable --release --image foo \
+             --sign //Alice --signature-scheme ed25519 --compress-wasm=zstd \
+             --source-archive-url https://example.com/src.tar.gz --features bar",
+        );
+
+        assert_eq!(args, ["--release", "--features", "bar"]);
+    }
+
+    #[test]
+    fn signing_secret_is_not_passed_to_the_container() {
+        for args in [
+            "cargo contract build --verifiable --sign //Alice",
+            "cargo contract build --sign=//Alice --verifiable",
+            "cargo contract build --verifiable --sign-stdin",
+        ] {
+            let args = container_args(args);
+
+            assert!(
+                args.iter()
+                    .all(|arg| !arg.contains("//Alice") && !arg.contains("sign")),
+                "{args:?}"
+            );
+        }
+    }
+}
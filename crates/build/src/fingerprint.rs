@@ -0,0 +1,284 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fingerprinting of the contract sources, used to detect whether the build artifacts
+//! are still up to date.
+
+use crate::{
+    code_hash,
+    util,
+    BuildArtifacts,
+    CrateMetadata,
+    ExecuteArgs,
+    OptimizationPasses,
+    VERSION,
+};
+use anyhow::Result;
+use blake2::digest::{
+    consts::U32,
+    Digest as _,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+/// Name of the file in the target directory the fingerprint is persisted to.
+const FINGERPRINT_FILE: &str = ".fingerprint";
+
+/// Fingerprint of the inputs of a contract build.
+///
+/// It is persisted to `target/ink/.fingerprint` after every successful build, so that
+/// subsequent invocations can detect whether anything changed since.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BuildFingerprint {
+    /// Hash of the contract sources and the `Cargo.lock`.
+    pub sources: String,
+    /// The Rust toolchain the artifacts were built with.
+    pub toolchain: String,
+    /// The `cargo-contract` version the artifacts were built with.
+    pub version: String,
+    /// Hash of the build settings used to produce the artifacts.
+    pub settings: String,
+}
+
+impl BuildFingerprint {
+    /// Computes the fingerprint of the contract for the given build arguments.
+    pub fn new(
+        crate_metadata: &CrateMetadata,
+        args: &ExecuteArgs,
+        optimization_passes: &OptimizationPasses,
+        wasm_opt_passes: &[String],
+    ) -> Result<Self> {
+        let settings = format!(
+            "{:?}|{:?}|{:?}|{:?}|{}|{:?}|{}|{}|{}|{}|{}|{}|{}",
+            args.build_mode,
+            args.features,
+            args.build_flags,
+            args.target,
+            optimization_passes,
+            wasm_opt_passes,
+            args.keep_debug_symbols,
+            args.debug_artifacts,
+            args.embed_metadata_hash,
+            args.sbom,
+            args.skip_wasm_validation,
+            args.max_memory_pages,
+            args.unstable_flags.original_manifest,
+        );
+        Ok(Self {
+            sources: sources_hash(crate_metadata)?,
+            toolchain: util::rust_toolchain()?,
+            version: VERSION.to_string(),
+            settings: hex::encode(code_hash(settings.as_bytes())),
+        })
+    }
+
+    /// Loads the fingerprint persisted by the last successful build, if any.
+    pub fn load(crate_metadata: &CrateMetadata) -> Result<Option<Self>> {
+        let path = fingerprint_path(crate_metadata);
+        if !path.exists() {
+            return Ok(None)
+        }
+        let json = fs::read_to_string(&path)?;
+        // A corrupt fingerprint is treated like a missing one, the next build will
+        // simply overwrite it.
+        Ok(serde_json::from_str(&json).ok())
+    }
+
+    /// Persists the fingerprint to the target directory.
+    pub fn store(&self, crate_metadata: &CrateMetadata) -> Result<()> {
+        let path = fingerprint_path(crate_metadata);
+        fs::create_dir_all(&crate_metadata.target_directory)?;
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Removes any persisted fingerprint, e.g. after a failed build.
+    pub fn clear(crate_metadata: &CrateMetadata) {
+        fs::remove_file(fingerprint_path(crate_metadata)).ok();
+    }
+}
+
+/// Returns `true` if the persisted fingerprint matches `fingerprint` and all the
+/// artifacts for `build_artifact` exist.
+pub fn artifacts_up_to_date(
+    crate_metadata: &CrateMetadata,
+    fingerprint: &BuildFingerprint,
+    build_artifact: BuildArtifacts,
+) -> Result<bool> {
+    let artifacts_exist = match build_artifact {
+        BuildArtifacts::All => {
+            crate_metadata.dest_code.exists()
+                && crate_metadata.metadata_path().exists()
+                && crate_metadata.contract_bundle_path().exists()
+        }
+        BuildArtifacts::CodeOnly => crate_metadata.dest_code.exists(),
+        BuildArtifacts::CheckOnly => false,
+    };
+    if !artifacts_exist {
+        return Ok(false)
+    }
+    Ok(BuildFingerprint::load(crate_metadata)?.as_ref() == Some(fingerprint))
+}
+
+/// Whether the artifacts in the target directory were built from the current sources
+/// with the current tools, see [`sources_status`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SourcesStatus {
+    /// Neither the sources nor the tools changed since the artifacts were built.
+    UpToDate,
+    /// The contract sources or the `Cargo.lock` changed since the artifacts were built.
+    SourcesChanged,
+    /// The sources did not change, but the artifacts were built with another Rust
+    /// toolchain or `cargo-contract` version. Each is `Some` with the one the artifacts
+    /// were built with if it differs.
+    ToolsChanged {
+        toolchain: Option<String>,
+        version: Option<String>,
+    },
+}
+
+/// Compares the fingerprint of the artifacts in the target directory with the current
+/// sources and tools, `None` if there is no fingerprint to compare against.
+///
+/// The build settings are not compared, they are not known to callers which merely
+/// consume the artifacts.
+pub fn sources_status(crate_metadata: &CrateMetadata) -> Result<Option<SourcesStatus>> {
+    let Some(stored) = BuildFingerprint::load(crate_metadata)? else {
+        return Ok(None)
+    };
+    if stored.sources != sources_hash(crate_metadata)? {
+        return Ok(Some(SourcesStatus::SourcesChanged))
+    }
+    let current_toolchain = util::rust_toolchain()?;
+    let toolchain = Some(stored.toolchain).filter(|t| *t != current_toolchain);
+    let version = Some(stored.version).filter(|v| v != VERSION);
+    if toolchain.is_none() && version.is_none() {
+        return Ok(Some(SourcesStatus::UpToDate))
+    }
+    Ok(Some(SourcesStatus::ToolsChanged { toolchain, version }))
+}
+
+/// Path of the fingerprint file for the contract.
+fn fingerprint_path(crate_metadata: &CrateMetadata) -> PathBuf {
+    crate_metadata.target_directory.join(FINGERPRINT_FILE)
+}
+
+/// Hashes all inputs of the build which are not build settings.
+///
+/// Only the files cargo actually compiles are hashed: the manifests of the contract and
+/// its path dependencies, and the source files listed in the dep-info of the last build.
+/// Registry and git dependencies are covered by the `Cargo.lock`.
+fn sources_hash(crate_metadata: &CrateMetadata) -> Result<String> {
+    let mut files = compiled_source_files(crate_metadata)?;
+    for (package, _) in crate_metadata.dependency_graph()? {
+        if package.source.is_none() {
+            files.push(package.manifest_path.clone().into_std_path_buf());
+        }
+    }
+    let lock_file = crate_metadata
+        .cargo_meta
+        .workspace_root
+        .as_std_path()
+        .join("Cargo.lock");
+    if lock_file.exists() {
+        files.push(lock_file);
+    }
+    files.sort();
+    files.dedup();
+
+    let mut hasher = blake2::Blake2b::<U32>::new();
+    for file in files {
+        hasher.update(file.to_string_lossy().as_bytes());
+        // a file which disappeared since the last build is a change as well
+        if let Ok(content) = fs::read(&file) {
+            hasher.update(code_hash(&content));
+        }
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Returns the source files cargo compiled into the contract in the last build, read
+/// from the dep-info file it writes next to the original code.
+///
+/// Returns no files if the contract was not built yet.
+fn compiled_source_files(crate_metadata: &CrateMetadata) -> Result<Vec<PathBuf>> {
+    let dep_info = crate_metadata.original_code.with_extension("d");
+    if !dep_info.exists() {
+        return Ok(Vec::new())
+    }
+    Ok(parse_dep_info(&fs::read_to_string(dep_info)?))
+}
+
+/// Parses the dependencies of all rules in a Makefile style dep-info file.
+fn parse_dep_info(dep_info: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for line in dep_info.lines().filter(|line| !line.starts_with('#')) {
+        let Some((_, deps)) = line.split_once(": ") else {
+            continue
+        };
+        let mut path = String::new();
+        let mut chars = deps.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                // spaces within paths are escaped
+                '\\' if chars.clone().next() == Some(' ') => {
+                    path.push(' ');
+                    chars.next();
+                }
+                ' ' => {
+                    if !path.is_empty() {
+                        files.push(PathBuf::from(std::mem::take(&mut path)));
+                    }
+                }
+                c => path.push(c),
+            }
+        }
+        if !path.is_empty() {
+            files.push(PathBuf::from(path));
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dep_info_returns_dependencies_of_all_rules() {
+        let dep_info = "/target/release/flipper.wasm: /src/lib.rs /my\\ dir/mod.rs\n\n\
+                        /target/release/other.wasm: /src/other.rs\n\n\
+                        # env-dep:CARGO_PKG_NAME=flipper\n";
+
+        assert_eq!(
+            parse_dep_info(dep_info),
+            vec![
+                PathBuf::from("/src/lib.rs"),
+                PathBuf::from("/my dir/mod.rs"),
+                PathBuf::from("/src/other.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_dep_info_ignores_rules_without_dependencies() {
+        let dep_info = "/target/release/flipper.wasm: /src/lib.rs\n\n/src/lib.rs:\n";
+
+        assert_eq!(parse_dep_info(dep_info), vec![PathBuf::from("/src/lib.rs")]);
+    }
+}
@@ -24,11 +24,20 @@ use contract_metadata::{
 use which as _;
 
 mod args;
+mod artifact_diff;
+pub mod audit;
 mod crate_metadata;
+mod debug_info;
 mod docker;
+pub mod fingerprint;
+pub mod linting;
 pub mod metadata;
 mod new;
+pub mod output;
 mod post_process_wasm;
+pub mod progress;
+mod sbom;
+pub mod testing;
 #[cfg(test)]
 mod tests;
 pub mod util;
@@ -42,6 +51,7 @@ pub use self::metadata::MetadataArtifacts as MetadataResult;
 pub use self::{
     args::{
         BuildArtifacts,
+        BuildFlags,
         BuildMode,
         Features,
         Network,
@@ -52,19 +62,42 @@ pub use self::{
         Verbosity,
         VerbosityFlags,
     },
+    artifact_diff::{
+        AbiDiff,
+        ArtifactDiff,
+        EntryPointsDiff,
+        SectionDiff,
+        SelectorChange,
+    },
     crate_metadata::CrateMetadata,
+    debug_info::{
+        DebugArtifacts,
+        SymbolMap,
+    },
     metadata::{
         BuildInfo,
         MetadataArtifacts,
         WasmOptSettings,
     },
-    new::new_contract_project,
+    new::{
+        new_contract_project,
+        new_contract_project_with_options,
+        BundledTemplate,
+        NewProjectOptions,
+        Template,
+    },
     post_process_wasm::{
         load_module,
         post_process_wasm,
+        WasmSettings,
+    },
+    sbom::{
+        SbomArtifact,
+        CYCLONEDX_SPEC_VERSION,
     },
     util::DEFAULT_KEY_COL_WIDTH,
     wasm_opt::{
+        OptimizationConfig,
         OptimizationPasses,
         OptimizationResult,
     },
@@ -79,7 +112,14 @@ pub use self::{
     },
 };
 
-use crate::wasm_opt::WasmOptHandler;
+use crate::{
+    fingerprint::BuildFingerprint,
+    progress::{
+        Operation,
+        ProgressReporter,
+    },
+    wasm_opt::WasmOptHandler,
+};
 pub use docker::{
     docker_build,
     ImageVariant,
@@ -93,12 +133,14 @@ use colored::Colorize;
 use semver::Version;
 use std::{
     fs,
+    panic,
     path::{
         Path,
         PathBuf,
     },
     process::Command,
     str,
+    thread,
 };
 use strum::IntoEnumIterator;
 
@@ -108,19 +150,6 @@ pub const DEFAULT_MAX_MEMORY_PAGES: u64 = 16;
 /// Version of the currently executing `cargo-contract` binary.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Configuration of the linting module.
-///
-/// Ensure it is kept up-to-date when updating `cargo-contract`.
-pub(crate) mod linting {
-    /// Toolchain used to build ink_linting:
-    /// https://github.com/paritytech/ink/blob/master/linting/rust-toolchain.toml
-    pub const TOOLCHAIN_VERSION: &str = "nightly-2024-02-08";
-    /// Git repository with ink_linting libraries
-    pub const GIT_URL: &str = "https://github.com/use-ink/ink/";
-    /// Git revision number of the linting crate
-    pub const GIT_REV: &str = "ef91c60e52eb5d3ae80fd25082bc0a9468332e36";
-}
-
 /// Arguments to use when executing `build` or `check` commands.
 #[derive(Clone)]
 pub struct ExecuteArgs {
@@ -129,17 +158,49 @@ pub struct ExecuteArgs {
     pub verbosity: Verbosity,
     pub build_mode: BuildMode,
     pub features: Features,
+    /// Additional flags for the on-chain build, appended to the ones configured in
+    /// `[package.metadata.contract.build]`.
+    pub build_flags: BuildFlags,
     pub network: Network,
     pub build_artifact: BuildArtifacts,
     pub unstable_flags: UnstableFlags,
     pub optimization_passes: Option<OptimizationPasses>,
+    /// The `wasm-opt` passes to run instead of the default passes of the optimization
+    /// level, replacing the ones of `[package.metadata.contract.optimization]`.
+    pub wasm_opt_passes: Vec<String>,
     pub keep_debug_symbols: bool,
+    /// Additionally emit a Wasm file retaining the DWARF debug info and a map from
+    /// function indices to symbols, without affecting the contract code itself.
+    pub debug_artifacts: bool,
+    /// Embed the hash of the ABI of the contract into its code, which requires the
+    /// metadata to be generated. This changes the code hash of the contract.
+    pub embed_metadata_hash: bool,
+    /// Audit the licenses of the dependencies and the security advisories affecting
+    /// them, merged with the settings of `[package.metadata.contract.audit]`.
+    pub audit: audit::AuditConfig,
+    /// Additionally emit a CycloneDX SBOM of the contract crate and its dependency tree,
+    /// and record its hash in the `build_info` of the metadata.
+    pub sbom: bool,
     pub extra_lints: bool,
     pub output_type: OutputType,
     pub skip_wasm_validation: bool,
     pub target: Target,
     pub max_memory_pages: u64,
+    /// The maximum size of the contract code in bytes, e.g. the `MaxCodeLen` of the
+    /// chain. The build fails if the code exceeds it.
+    pub max_code_size: Option<u32>,
+    /// The cargo target directory to build the contract in, instead of the one of the
+    /// workspace.
+    pub target_dir: Option<PathBuf>,
+    /// The directory to write the contract artifacts to, instead of the `ink` directory
+    /// in the target directory.
+    pub artifacts_dir: Option<PathBuf>,
     pub image: ImageVariant,
+    /// Skip the build if neither the contract sources nor the build settings changed
+    /// since the last successful build, and all artifacts are still present.
+    pub skip_build_if_unchanged: bool,
+    /// Receives the progress of compiling and optimizing the contract.
+    pub progress: ProgressReporter,
 }
 
 impl Default for ExecuteArgs {
@@ -149,17 +210,28 @@ impl Default for ExecuteArgs {
             verbosity: Default::default(),
             build_mode: Default::default(),
             features: Default::default(),
+            build_flags: Default::default(),
             network: Default::default(),
             build_artifact: Default::default(),
             unstable_flags: Default::default(),
             optimization_passes: Default::default(),
+            wasm_opt_passes: Default::default(),
             keep_debug_symbols: Default::default(),
+            debug_artifacts: Default::default(),
+            embed_metadata_hash: Default::default(),
+            audit: Default::default(),
+            sbom: Default::default(),
             extra_lints: Default::default(),
             output_type: Default::default(),
             skip_wasm_validation: Default::default(),
             target: Default::default(),
             max_memory_pages: DEFAULT_MAX_MEMORY_PAGES,
+            max_code_size: Default::default(),
+            target_dir: Default::default(),
+            artifacts_dir: Default::default(),
             image: Default::default(),
+            skip_build_if_unchanged: Default::default(),
+            progress: Default::default(),
         }
     }
 }
@@ -175,6 +247,12 @@ pub struct BuildResult {
     pub target_directory: PathBuf,
     /// If existent the result of the optimization.
     pub optimization_result: Option<OptimizationResult>,
+    /// Paths to the debug artifacts, if they were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug_artifacts: Option<DebugArtifacts>,
+    /// The SBOM of the contract, if it was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom: Option<SbomArtifact>,
     /// The mode to build the contract in.
     pub build_mode: BuildMode,
     /// Which build artifacts were generated.
@@ -252,6 +330,21 @@ impl BuildResult {
             );
             out.push_str(&metadata);
         }
+        if let Some(debug_artifacts) = self.debug_artifacts.as_ref() {
+            let debug = format!(
+                "\n  - {} (the contract's code with debug info)\n  - {} (function index to symbol map)",
+                util::base_name(&debug_artifacts.debug_wasm).bold(),
+                util::base_name(&debug_artifacts.symbol_map).bold()
+            );
+            out.push_str(&debug);
+        }
+        if let Some(sbom) = self.sbom.as_ref() {
+            let sbom = format!(
+                "\n  - {} (the SBOM of the contract and its dependencies)",
+                util::base_name(&sbom.path).bold()
+            );
+            out.push_str(&sbom);
+        }
         out
     }
 
@@ -287,11 +380,13 @@ fn exec_cargo_for_onchain_target(
     crate_metadata: &CrateMetadata,
     command: &str,
     features: &Features,
+    build_flags: &BuildFlags,
     build_mode: &BuildMode,
     network: &Network,
     verbosity: &Verbosity,
     unstable_flags: &UnstableFlags,
     target: &Target,
+    debug_info: bool,
 ) -> Result<()> {
     let cargo_build = |manifest_path: &ManifestPath| {
         let target_dir = format!(
@@ -302,18 +397,25 @@ fn exec_cargo_for_onchain_target(
         args.extend(onchain_cargo_options(target));
         network.append_to_args(&mut args);
 
-        let mut features = features.clone();
+        let mut features =
+            features.expand_all_features(crate_metadata.root_package.features.keys());
         if build_mode == &BuildMode::Debug {
             features.push("ink/ink-debug");
         } else {
             args.push("-Zbuild-std-features=panic_immediate_abort".to_owned());
         }
         features.append_to_args(&mut args);
+        build_flags.append_to_args(&mut args);
         let mut env = Vec::new();
         if rustc_version::version_meta()?.channel == rustc_version::Channel::Stable {
             // Allow nightly features on a stable toolchain
             env.push(("RUSTC_BOOTSTRAP", Some("1".to_string())))
         }
+        if debug_info {
+            // Only affects the unoptimized code, `wasm-opt` strips the debug info again
+            // unless told to keep it.
+            env.push(("CARGO_PROFILE_RELEASE_DEBUG", Some("true".to_string())))
+        }
 
         // merge target specific flags with the common flags (defined here)
         // We want to disable warnings here as they will be duplicates of the clippy pass.
@@ -324,11 +426,13 @@ fn exec_cargo_for_onchain_target(
         // warnings.
         let rustflags = {
             let common_flags = "-Clinker-plugin-lto";
+            let mut flags = vec![common_flags.to_string()];
             if let Some(target_flags) = target.rustflags() {
-                format!("{}\x1f{}", common_flags, target_flags)
-            } else {
-                common_flags.to_string()
+                flags.push(target_flags.to_string());
             }
+            // Additional flags come last, so they take precedence over the defaults.
+            flags.extend(build_flags.rustflags());
+            flags.join("\x1f")
         };
 
         // the linker needs our linker script as file
@@ -357,13 +461,12 @@ fn exec_cargo_for_onchain_target(
     };
 
     if unstable_flags.original_manifest {
-        verbose_eprintln!(
-            verbosity,
-            "{} {}",
-            "warning:".yellow().bold(),
-            "with 'original-manifest' enabled, the contract binary may not be of optimal size."
-                .bold()
-        );
+        if verbosity.is_verbose() {
+            output::warning(
+                "with 'original-manifest' enabled, the contract binary may not be of \
+                 optimal size.",
+            );
+        }
         cargo_build(&crate_metadata.manifest_path)?;
     } else {
         Workspace::new(&crate_metadata.cargo_meta, &crate_metadata.root_package.id)?
@@ -432,23 +535,21 @@ fn check_buffer_size_invoke_cargo_clean(
                 );
             }
             Ok(_) => {
-                verbose_eprintln!(
-                    verbosity,
-                    "{} {}",
-                    "warning:".yellow().bold(),
-                    "Detected a change in the configured buffer size. Rebuilding the project."
-                        .bold()
-                );
+                if verbosity.is_verbose() {
+                    output::warning(
+                        "Detected a change in the configured buffer size. Rebuilding the \
+                         project.",
+                    );
+                }
                 execute_cargo(cargo)?;
             }
             Err(_) => {
-                verbose_eprintln!(
-                    verbosity,
-                    "{} {}",
-                    "warning:".yellow().bold(),
-                    "Cannot find the previous size of the static buffer. Rebuilding the project."
-                        .bold()
-                );
+                if verbosity.is_verbose() {
+                    output::warning(
+                        "Cannot find the previous size of the static buffer. Rebuilding \
+                         the project.",
+                    );
+                }
                 execute_cargo(cargo)?;
             }
         }
@@ -480,7 +581,7 @@ fn lint(
         "[==]".bold(),
         "Checking clippy linting rules".bright_green().bold()
     );
-    exec_cargo_clippy(crate_metadata, *verbosity)?;
+    exec_cargo_clippy(crate_metadata, *verbosity, false)?;
 
     // TODO (jubnzv): Dylint needs a custom toolchain installed by the user. Currently,
     // it's required only for RiscV target. We're working on the toolchain integration
@@ -492,30 +593,56 @@ fn lint(
             "[==]".bold(),
             "Checking ink! linting rules".bright_green().bold()
         );
-        exec_cargo_dylint(extra_lints, crate_metadata, target, *verbosity)?;
+        let lint_config =
+            Manifest::new(crate_metadata.manifest_path.clone())?.lint_config()?;
+        exec_cargo_dylint(
+            extra_lints,
+            crate_metadata,
+            target,
+            *verbosity,
+            &lint_config,
+            false,
+        )?;
     }
 
     Ok(())
 }
 
 /// Run cargo clippy on the unmodified manifest.
-fn exec_cargo_clippy(crate_metadata: &CrateMetadata, verbosity: Verbosity) -> Result<()> {
-    let args = [
-        "--all-features",
+///
+/// If `capture` is set, the diagnostics are emitted as JSON and returned instead of
+/// being printed, violations do not result in an error in that case. Failing to run
+/// clippy still does.
+fn exec_cargo_clippy(
+    crate_metadata: &CrateMetadata,
+    verbosity: Verbosity,
+    capture: bool,
+) -> Result<String> {
+    let mut args = vec!["--all-features"];
+    if capture {
+        args.push("--message-format=json");
+    }
+    args.extend([
         // customize clippy lints after the "--"
         "--",
         // this is a hard error because we want to guarantee that implicit overflows
         // never happen
         "-Dclippy::arithmetic_side_effects",
-    ];
+    ]);
     // we execute clippy with the plain manifest no temp dir required
-    execute_cargo(util::cargo_cmd(
+    let cargo = util::cargo_cmd(
         "clippy",
         args,
         crate_metadata.manifest_path.directory(),
         verbosity,
         vec![],
-    ))
+    );
+    if capture {
+        let output = cargo.stdout_capture().unchecked().run()?;
+        return linting::captured_output("clippy", output)
+    }
+    execute_cargo(cargo)?;
+    Ok(String::new())
 }
 
 /// Returns a list of cargo options used for on-chain builds
@@ -531,12 +658,17 @@ fn onchain_cargo_options(target: &Target) -> Vec<String> {
 ///
 /// We create a temporary folder, extract the linting driver there and run
 /// `cargo dylint` with it.
+///
+/// The lint levels of `lint_config` are passed on to the linting driver. If `capture` is
+/// set, the diagnostics are emitted as JSON and returned instead of being printed.
 fn exec_cargo_dylint(
     extra_lints: bool,
     crate_metadata: &CrateMetadata,
     target: &Target,
     verbosity: Verbosity,
-) -> Result<()> {
+    lint_config: &linting::LintConfig,
+    capture: bool,
+) -> Result<String> {
     check_dylint_requirements(crate_metadata.manifest_path.directory())?;
 
     // `dylint` is verbose by default, it doesn't have a `--verbose` argument,
@@ -554,6 +686,9 @@ fn exec_cargo_dylint(
         vec!["--lib=ink_linting_mandatory".to_owned()]
     };
     args.push("--".to_owned());
+    if capture {
+        args.push("--message-format=json".to_owned());
+    }
     // Pass on-chain build options to ensure the linter expands all conditional `cfg_attr`
     // macros, as it does for the release build.
     args.extend(onchain_cargo_options(target));
@@ -572,8 +707,11 @@ fn exec_cargo_dylint(
         // there is this bug: https://github.com/mozilla/sccache/issues/1000.
         // Until we have a justification for leaving the wrapper we should unset it.
         ("RUSTC_WRAPPER", None),
+        // The lint levels configured in the `Cargo.toml` of the contract.
+        ("DYLINT_RUSTFLAGS", Some(lint_config.rustflags().join(" "))),
     ];
 
+    let mut output = String::new();
     Workspace::new(&crate_metadata.cargo_meta, &crate_metadata.root_package.id)?
         .with_root_package_manifest(|manifest| {
             manifest.with_dylint()?;
@@ -587,11 +725,16 @@ fn exec_cargo_dylint(
                 verbosity,
                 env,
             );
-            cargo.run()?;
+            if capture {
+                let out = cargo.stdout_capture().unchecked().run()?;
+                output = linting::captured_output("dylint", out)?;
+            } else {
+                cargo.run()?;
+            }
             Ok(())
         })?;
 
-    Ok(())
+    Ok(output)
 }
 
 /// Checks if all requirements for `dylint` are installed.
@@ -696,29 +839,106 @@ pub fn assert_debug_mode_supported(ink_version: &Version) -> Result<()> {
     Ok(())
 }
 
+/// Audits the dependencies of the contract, see [`audit::audit_dependencies`].
+fn audit_dependencies(
+    crate_metadata: &CrateMetadata,
+    config: &audit::AuditConfig,
+    verbosity: &Verbosity,
+) -> Result<()> {
+    verbose_eprintln!(
+        verbosity,
+        " {} {}",
+        "[==]".bold(),
+        "Auditing dependencies".bright_green().bold()
+    );
+    audit::audit_dependencies(crate_metadata, config)?;
+    Ok(())
+}
+
 /// Executes build of the smart contract which produces a Wasm binary that is ready for
 /// deploying.
 ///
 /// It does so by invoking `cargo build` and then post processing the final binary.
 pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
+    match compile(args)? {
+        Compilation::Finished(result) => Ok(result),
+        Compilation::Compiled(build) => finish(build),
+    }
+}
+
+/// The outcome of [`compile`].
+// there is a single, short-lived value per build, boxing a variant would gain nothing
+#[allow(clippy::large_enum_variant)]
+enum Compilation {
+    /// The build finished without compiling the contract, e.g. since its artifacts are
+    /// up to date.
+    Finished(BuildResult),
+    /// The contract is compiled, but its code is not post processed yet, see [`finish`].
+    Compiled(CompiledBuild),
+}
+
+/// A build of which the contract is compiled, but its code is not post processed yet.
+struct CompiledBuild {
+    args: ExecuteArgs,
+    crate_metadata: CrateMetadata,
+    optimization_passes: OptimizationPasses,
+    wasm_opt_passes: Vec<String>,
+    debug_artifacts: Option<DebugArtifacts>,
+    fingerprint: Option<BuildFingerprint>,
+    sbom: Option<SbomArtifact>,
+    /// The compiled code, `None` if the contract is only checked.
+    code: Option<CompiledCode>,
+}
+
+/// Runs the steps of the build up to and including the compilation of the contract.
+fn compile(args: ExecuteArgs) -> Result<Compilation> {
     let ExecuteArgs {
         manifest_path,
         verbosity,
-        features,
         build_mode,
-        network,
         build_artifact,
-        unstable_flags,
         optimization_passes,
+        wasm_opt_passes,
         extra_lints,
         output_type,
         target,
+        skip_build_if_unchanged,
+        debug_artifacts,
+        embed_metadata_hash,
+        sbom,
+        audit,
+        max_code_size,
+        target_dir,
+        artifacts_dir,
         ..
     } = &args;
 
+    anyhow::ensure!(
+        !embed_metadata_hash || build_artifact == &BuildArtifacts::All,
+        "The hash of the metadata can only be embedded if the metadata is generated"
+    );
+
+    let mut audit_config = Manifest::new(manifest_path.clone())?.audit_config()?;
+    audit_config.merge(audit);
+
     // if image exists, then --verifiable was called and we need to build inside docker.
     if build_mode == &BuildMode::Verifiable {
-        return docker_build(args)
+        // the dependencies are audited on the host, the image might not be able to
+        // fetch the advisory database
+        let crate_metadata = CrateMetadata::collect(manifest_path, *target)?;
+        if audit_config.is_enabled() {
+            audit_dependencies(&crate_metadata, &audit_config, verbosity)?;
+        }
+        let max_code_size = *max_code_size;
+        // The fingerprint only describes local builds, one left behind by an earlier
+        // build or written by the build inside the container would not match the
+        // verifiable artifacts.
+        BuildFingerprint::clear(&crate_metadata);
+        let result = docker_build(args);
+        BuildFingerprint::clear(&crate_metadata);
+        let result = result?;
+        ensure_max_code_size(&result, max_code_size)?;
+        return Ok(Compilation::Finished(result))
     }
 
     // The CLI flag `optimization-passes` overwrites optimization passes which are
@@ -736,44 +956,203 @@ pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
             }
         }
     };
+    // Likewise the CLI flags `wasm-opt-pass` replace the configured pass pipeline.
+    let wasm_opt_passes = if wasm_opt_passes.is_empty() {
+        Manifest::new(manifest_path.clone())?
+            .optimization_config()?
+            .passes
+    } else {
+        wasm_opt_passes.clone()
+    };
 
-    let crate_metadata = CrateMetadata::collect(manifest_path, *target)?;
+    let crate_metadata = CrateMetadata::collect_with_directories(
+        manifest_path,
+        *target,
+        target_dir.as_deref(),
+        artifacts_dir.as_deref(),
+    )?;
+    if build_artifact != &BuildArtifacts::CheckOnly {
+        fs::create_dir_all(&crate_metadata.artifact_directory)?;
+        crate_metadata.persist_artifact_directory()?;
+    }
 
     if build_mode == &BuildMode::Debug {
         assert_debug_mode_supported(&crate_metadata.ink_version)?;
     }
 
     if let Err(e) = check_contract_ink_compatibility(&crate_metadata.ink_version, None) {
-        eprintln!("{} {}", "warning:".yellow().bold(), e.to_string().bold());
+        output::warning(e);
+    }
+
+    if audit_config.is_enabled() {
+        audit_dependencies(&crate_metadata, &audit_config, verbosity)?;
     }
 
-    let clean_metadata = || {
-        fs::remove_file(crate_metadata.metadata_path()).ok();
-        fs::remove_file(crate_metadata.contract_bundle_path()).ok();
+    // Debug artifacts are only supported for Wasm, since they are derived by `wasm-opt`.
+    let debug_artifacts = (*debug_artifacts
+        && *target == Target::Wasm
+        && build_artifact != &BuildArtifacts::CheckOnly)
+        .then(|| DebugArtifacts::new(&crate_metadata));
+
+    let fingerprint = match build_artifact {
+        BuildArtifacts::CheckOnly => None,
+        BuildArtifacts::CodeOnly | BuildArtifacts::All => {
+            Some(BuildFingerprint::new(
+                &crate_metadata,
+                &args,
+                &optimization_passes,
+                &wasm_opt_passes,
+            )?)
+        }
     };
 
-    let (opt_result, metadata_result, dest_wasm) = match build_artifact {
+    if let Some(fingerprint) = fingerprint.as_ref().filter(|_| *skip_build_if_unchanged) {
+        let debug_artifacts_exist = debug_artifacts
+            .as_ref()
+            .map(DebugArtifacts::exist)
+            .unwrap_or(true);
+        let sbom = if *sbom {
+            SbomArtifact::load(&crate_metadata)?
+        } else {
+            None
+        };
+        if debug_artifacts_exist
+            && (!args.sbom || sbom.is_some())
+            && fingerprint::artifacts_up_to_date(
+                &crate_metadata,
+                fingerprint,
+                *build_artifact,
+            )?
+        {
+            verbose_eprintln!(
+                verbosity,
+                " {} {}",
+                "[==]".bold(),
+                "Contract sources unchanged, artifacts up to date"
+                    .bright_green()
+                    .bold()
+            );
+            let metadata_result = (build_artifact == &BuildArtifacts::All).then(|| {
+                MetadataArtifacts {
+                    dest_metadata: crate_metadata.metadata_path(),
+                    dest_bundle: crate_metadata.contract_bundle_path(),
+                }
+            });
+            let result = BuildResult {
+                dest_wasm: Some(crate_metadata.dest_code.clone()),
+                metadata_result,
+                target_directory: crate_metadata.artifact_directory,
+                optimization_result: None,
+                debug_artifacts,
+                sbom,
+                build_mode: *build_mode,
+                build_artifact: *build_artifact,
+                verbosity: *verbosity,
+                image: None,
+                output_type: output_type.clone(),
+            };
+            ensure_max_code_size(&result, *max_code_size)?;
+            return Ok(Compilation::Finished(result))
+        }
+    }
+    // a stale fingerprint must never outlive a failed build
+    BuildFingerprint::clear(&crate_metadata);
+
+    // the SBOM is generated first, so that its hash can be recorded in the metadata
+    fs::remove_file(SbomArtifact::path(&crate_metadata)).ok();
+    let sbom = (*sbom && build_artifact != &BuildArtifacts::CheckOnly)
+        .then(|| SbomArtifact::generate(&crate_metadata))
+        .transpose()?;
+
+    let code = match build_artifact {
         BuildArtifacts::CheckOnly => {
             // Check basically means only running our linter without building.
             lint(*extra_lints, &crate_metadata, target, verbosity)?;
-            (None, None, None)
+            None
         }
         BuildArtifacts::CodeOnly => {
             // when building only the code metadata will become stale
-            clean_metadata();
-            let (opt_result, _, dest_wasm) =
-                local_build(&crate_metadata, &optimization_passes, &args)?;
-            (opt_result, None, Some(dest_wasm))
+            clean_metadata(&crate_metadata);
+            Some(compile_code(
+                &crate_metadata,
+                &optimization_passes,
+                &wasm_opt_passes,
+                &args,
+            )?)
         }
         BuildArtifacts::All => {
-            let (opt_result, build_info, dest_wasm) =
-                local_build(&crate_metadata, &optimization_passes, &args).map_err(
-                    |e| {
-                        // build error -> bundle is stale
-                        clean_metadata();
-                        e
-                    },
-                )?;
+            // build error -> bundle is stale
+            let code = compile_code(
+                &crate_metadata,
+                &optimization_passes,
+                &wasm_opt_passes,
+                &args,
+            )
+            .inspect_err(|_| clean_metadata(&crate_metadata))?;
+            Some(code)
+        }
+    };
+
+    Ok(Compilation::Compiled(CompiledBuild {
+        args,
+        crate_metadata,
+        optimization_passes,
+        wasm_opt_passes,
+        debug_artifacts,
+        fingerprint,
+        sbom,
+        code,
+    }))
+}
+
+/// Post processes the compiled code of the `build` and generates the metadata.
+fn finish(build: CompiledBuild) -> Result<BuildResult> {
+    let CompiledBuild {
+        args,
+        crate_metadata,
+        optimization_passes,
+        wasm_opt_passes,
+        debug_artifacts,
+        fingerprint,
+        sbom,
+        code,
+    } = build;
+    let ExecuteArgs {
+        verbosity,
+        features,
+        build_mode,
+        network,
+        build_artifact,
+        unstable_flags,
+        output_type,
+        max_code_size,
+        ..
+    } = &args;
+
+    let (opt_result, metadata_result, dest_wasm) = match (build_artifact, code) {
+        (BuildArtifacts::CodeOnly, Some(code)) => {
+            let (opt_result, _, dest_wasm) = post_process_code(
+                code,
+                &crate_metadata,
+                &optimization_passes,
+                &wasm_opt_passes,
+                &args,
+            )?;
+            (opt_result, None, Some(dest_wasm))
+        }
+        (BuildArtifacts::All, Some(code)) => {
+            let (opt_result, mut build_info, dest_wasm) = post_process_code(
+                code,
+                &crate_metadata,
+                &optimization_passes,
+                &wasm_opt_passes,
+                &args,
+            )
+            .inspect_err(|_| {
+                // build error -> bundle is stale
+                clean_metadata(&crate_metadata);
+            })?;
+            build_info.sbom_hash = sbom.as_ref().map(|sbom| sbom.hash.clone());
 
             let metadata_result = MetadataArtifacts {
                 dest_metadata: crate_metadata.metadata_path(),
@@ -783,11 +1162,12 @@ pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
             // skip metadata generation if contract unchanged and all metadata artifacts
             // exist.
             if opt_result.is_some()
+                || sbom.is_some()
                 || !metadata_result.dest_metadata.exists()
                 || !metadata_result.dest_bundle.exists()
             {
                 // if metadata build fails after a code build it might become stale
-                clean_metadata();
+                clean_metadata(&crate_metadata);
                 metadata::execute(
                     &crate_metadata,
                     dest_wasm.as_path(),
@@ -801,27 +1181,127 @@ pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
             }
             (opt_result, Some(metadata_result), Some(dest_wasm))
         }
+        _ => (None, None, None),
     };
 
-    Ok(BuildResult {
+    let result = BuildResult {
         dest_wasm,
         metadata_result,
-        target_directory: crate_metadata.target_directory,
+        target_directory: crate_metadata.artifact_directory.clone(),
         optimization_result: opt_result,
+        debug_artifacts,
+        sbom,
         build_mode: *build_mode,
         build_artifact: *build_artifact,
         verbosity: *verbosity,
         image: None,
         output_type: output_type.clone(),
+    };
+    // an oversized contract is a failed build, it must not be skipped next time
+    ensure_max_code_size(&result, *max_code_size)?;
+
+    // The sources are hashed again, since cargo only reports the files it compiled
+    // once the build finished.
+    if fingerprint.is_some() {
+        BuildFingerprint::new(
+            &crate_metadata,
+            &args,
+            &optimization_passes,
+            &wasm_opt_passes,
+        )?
+        .store(&crate_metadata)?;
+    }
+
+    Ok(result)
+}
+
+/// Removes the metadata artifacts of the contract, e.g. since they became stale.
+fn clean_metadata(crate_metadata: &CrateMetadata) {
+    fs::remove_file(crate_metadata.metadata_path()).ok();
+    fs::remove_file(crate_metadata.contract_bundle_path()).ok();
+}
+
+/// Executes the build of all contracts which are members of the workspace of
+/// `args.manifest_path`, see [`execute`].
+///
+/// The contracts are compiled one after the other, since cargo already makes use of all
+/// cores, while the code of each compiled contract is post processed in a thread of its
+/// own, so that `wasm-opt` optimizes the code of the contracts in parallel.
+pub fn execute_workspace(args: ExecuteArgs) -> Result<Vec<BuildResult>> {
+    anyhow::ensure!(
+        args.build_mode != BuildMode::Verifiable,
+        "Verifiable builds of all contracts of a workspace are not supported"
+    );
+    let manifest_paths = crate_metadata::workspace_contracts(&args.manifest_path)?;
+    anyhow::ensure!(
+        !manifest_paths.is_empty(),
+        "No contracts found in the workspace of {}",
+        args.manifest_path.as_ref().display()
+    );
+    thread::scope(|scope| {
+        let builds = manifest_paths
+            .into_iter()
+            .map(|manifest_path| {
+                let args = ExecuteArgs {
+                    manifest_path,
+                    ..args.clone()
+                };
+                let compilation = compile(args)?;
+                Ok(scope.spawn(move || {
+                    match compilation {
+                        Compilation::Finished(result) => Ok(result),
+                        Compilation::Compiled(build) => finish(build),
+                    }
+                }))
+            })
+            .collect::<Vec<Result<_>>>();
+        builds
+            .into_iter()
+            .map(|build| build?.join().unwrap_or_else(|err| panic::resume_unwind(err)))
+            .collect()
     })
 }
 
-/// Build the contract on host locally
-fn local_build(
+/// Fails if the contract code of the build exceeds the `max_code_size` in bytes.
+fn ensure_max_code_size(result: &BuildResult, max_code_size: Option<u32>) -> Result<()> {
+    let (Some(max_code_size), Some(dest_wasm)) = (max_code_size, &result.dest_wasm)
+    else {
+        return Ok(())
+    };
+    let code_size = fs::metadata(dest_wasm)?.len();
+    if code_size > max_code_size as u64 {
+        let sizes = match &result.optimization_result {
+            Some(sizes) => {
+                format!(
+                    "\nOriginal wasm size: {:.1}K, Optimized: {:.1}K",
+                    sizes.original_size, sizes.optimized_size
+                )
+            }
+            None => String::new(),
+        };
+        anyhow::bail!(
+            "The contract code of {code_size} bytes exceeds the maximum code size of \
+             {max_code_size} bytes, uploading it would fail.{sizes}"
+        )
+    }
+    Ok(())
+}
+
+/// The code of a contract compiled on the host, see [`compile_code`].
+struct CompiledCode {
+    build_info: BuildInfo,
+    wasm_settings: WasmSettings,
+    /// Whether the compiled code is the one the existing artifacts were derived from.
+    unchanged: bool,
+}
+
+/// Compile the contract on host locally
+fn compile_code(
     crate_metadata: &CrateMetadata,
     optimization_passes: &OptimizationPasses,
+    wasm_opt_passes: &[String],
     args: &ExecuteArgs,
-) -> Result<(Option<OptimizationResult>, BuildInfo, PathBuf)> {
+) -> Result<CompiledCode> {
     let ExecuteArgs {
         verbosity,
         features,
@@ -830,9 +1310,10 @@ fn local_build(
         unstable_flags,
         keep_debug_symbols,
         extra_lints,
-        skip_wasm_validation,
         target,
-        max_memory_pages,
+        debug_artifacts,
+        embed_metadata_hash,
+        progress,
         ..
     } = args;
 
@@ -849,16 +1330,24 @@ fn local_build(
         "Building cargo project".bright_green().bold()
     );
     check_buffer_size_invoke_cargo_clean(crate_metadata, verbosity)?;
-    exec_cargo_for_onchain_target(
-        crate_metadata,
-        "build",
-        features,
-        build_mode,
-        network,
-        verbosity,
-        unstable_flags,
-        target,
-    )?;
+    let manifest = Manifest::new(crate_metadata.manifest_path.clone())?;
+    let mut build_flags = manifest.build_flags()?;
+    build_flags.merge(&args.build_flags);
+    let wasm_settings = manifest.wasm_settings()?;
+    progress.run(Operation::Compile, || {
+        exec_cargo_for_onchain_target(
+            crate_metadata,
+            "build",
+            features,
+            &build_flags,
+            build_mode,
+            network,
+            verbosity,
+            unstable_flags,
+            target,
+            *debug_artifacts,
+        )
+    })?;
 
     // We persist the latest target we used so we trigger a rebuild when we switch
     fs::write(&crate_metadata.target_file_path, target.llvm_target())?;
@@ -879,7 +1368,13 @@ fn local_build(
         wasm_opt_settings: WasmOptSettings {
             optimization_passes: *optimization_passes,
             keep_debug_symbols: *keep_debug_symbols,
+            passes: wasm_opt_passes.to_vec(),
         },
+        features: features.clone(),
+        build_flags: args.build_flags.clone(),
+        sbom_hash: None,
+        debug_artifacts: *debug_artifacts,
+        embed_metadata_hash: *embed_metadata_hash,
     };
 
     let post_fingerprint = Fingerprint::new(crate_metadata)?.ok_or_else(|| {
@@ -895,18 +1390,62 @@ fn local_build(
         post_fingerprint
     );
 
-    let dest_code_path = crate_metadata.dest_code.clone();
-
-    if pre_fingerprint == Some(post_fingerprint) && crate_metadata.dest_code.exists() {
+    let debug_artifacts_exist = (*debug_artifacts && *target == Target::Wasm)
+        .then(|| DebugArtifacts::new(crate_metadata))
+        .as_ref()
+        .map(DebugArtifacts::exist)
+        .unwrap_or(true);
+    let unchanged = pre_fingerprint.as_ref() == Some(&post_fingerprint)
+        && crate_metadata.dest_code.exists()
+        && debug_artifacts_exist;
+    if unchanged {
         tracing::info!(
             "No changes in the original wasm at {}, fingerprint {:?}. \
                 Skipping Wasm optimization and metadata generation.",
             crate_metadata.original_code.display(),
             pre_fingerprint
         );
+    }
+
+    Ok(CompiledCode {
+        build_info,
+        wasm_settings,
+        unchanged,
+    })
+}
+
+/// Post process the compiled `code` of the contract, e.g. optimize it with `wasm-opt`.
+fn post_process_code(
+    code: CompiledCode,
+    crate_metadata: &CrateMetadata,
+    optimization_passes: &OptimizationPasses,
+    wasm_opt_passes: &[String],
+    args: &ExecuteArgs,
+) -> Result<(Option<OptimizationResult>, BuildInfo, PathBuf)> {
+    let ExecuteArgs {
+        verbosity,
+        keep_debug_symbols,
+        skip_wasm_validation,
+        target,
+        max_memory_pages,
+        debug_artifacts,
+        progress,
+        ..
+    } = args;
+    let CompiledCode {
+        build_info,
+        wasm_settings,
+        unchanged,
+    } = code;
+
+    let dest_code_path = crate_metadata.dest_code.clone();
+    if unchanged {
         return Ok((None, build_info, dest_code_path))
     }
 
+    let debug_artifacts = (*debug_artifacts && *target == Target::Wasm)
+        .then(|| DebugArtifacts::new(crate_metadata));
+
     verbose_eprintln!(
         verbosity,
         " {} {}",
@@ -918,19 +1457,57 @@ fn local_build(
     for t in Target::iter() {
         fs::remove_file(crate_metadata.dest_code.with_extension(t.dest_extension())).ok();
     }
+    DebugArtifacts::new(crate_metadata).clear();
 
     let original_size =
         fs::metadata(&crate_metadata.original_code)?.len() as f64 / 1000.0;
 
     match target {
         Target::Wasm => {
-            let handler = WasmOptHandler::new(*optimization_passes, *keep_debug_symbols)?;
-            handler.optimize(&crate_metadata.original_code, &crate_metadata.dest_code)?;
+            match &debug_artifacts {
+                Some(debug_artifacts) => {
+                    // the contract code is derived from the debug Wasm, so that the
+                    // symbol map is valid for the deployed code
+                    verbose_eprintln!(
+                        verbosity,
+                        " {} {}",
+                        "[==]".bold(),
+                        "Generating debug artifacts".bright_green().bold()
+                    );
+                    let handler =
+                        WasmOptHandler::new(*optimization_passes, wasm_opt_passes, true)?;
+                    progress.run(Operation::Optimize, || {
+                        handler.optimize(
+                            &crate_metadata.original_code,
+                            &debug_artifacts.debug_wasm,
+                        )
+                    })?;
+                    debug_info::generate(
+                        debug_artifacts,
+                        &crate_metadata.dest_code,
+                        *keep_debug_symbols,
+                    )?;
+                }
+                None => {
+                    let handler = WasmOptHandler::new(
+                        *optimization_passes,
+                        wasm_opt_passes,
+                        *keep_debug_symbols,
+                    )?;
+                    progress.run(Operation::Optimize, || {
+                        handler.optimize(
+                            &crate_metadata.original_code,
+                            &crate_metadata.dest_code,
+                        )
+                    })?;
+                }
+            }
             post_process_wasm(
                 &crate_metadata.dest_code,
                 *skip_wasm_validation,
                 verbosity,
                 *max_memory_pages,
+                &wasm_settings,
             )?;
         }
         Target::RiscV => {
@@ -1068,6 +1645,8 @@ mod unit_tests {
                 original_size: 64.0,
                 optimized_size: 32.0,
             }),
+            debug_artifacts: None,
+            sbom: None,
             build_mode: Default::default(),
             build_artifact: Default::default(),
             image: None,
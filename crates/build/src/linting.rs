@@ -0,0 +1,295 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Configuration of the linting module and the standalone `lint` command.
+//!
+//! Ensure the constants are kept up-to-date when updating `cargo-contract`.
+
+use crate::{
+    verbose_eprintln,
+    CrateMetadata,
+    Manifest,
+    ManifestPath,
+    OutputType,
+    Target,
+    Verbosity,
+};
+use anyhow::Result;
+use colored::Colorize;
+use std::process::Output;
+
+/// Toolchain used to build ink_linting:
+/// https://github.com/paritytech/ink/blob/master/linting/rust-toolchain.toml
+pub const TOOLCHAIN_VERSION: &str = "nightly-2024-02-08";
+/// Git repository with ink_linting libraries
+pub const GIT_URL: &str = "https://github.com/use-ink/ink/";
+/// Git revision number of the linting crate
+pub const GIT_REV: &str = "ef91c60e52eb5d3ae80fd25082bc0a9468332e36";
+
+/// Lint levels configured in the `[package.metadata.contract.lint]` section of the
+/// contract's `Cargo.toml`, e.g.
+///
+/// ```toml
+/// [package.metadata.contract.lint]
+/// allow = ["primitive_topic"]
+/// deny = ["storage_never_freed"]
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    /// Lints which are not reported.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Lints which are reported as warnings.
+    #[serde(default)]
+    pub warn: Vec<String>,
+    /// Lints which are reported as errors.
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl LintConfig {
+    /// Returns the `rustc` flags setting the configured lint levels.
+    pub fn rustflags(&self) -> Vec<String> {
+        let levels = [("-A", &self.allow), ("-W", &self.warn), ("-D", &self.deny)];
+        levels
+            .into_iter()
+            .flat_map(|(flag, lints)| {
+                lints.iter().map(move |lint| format!("{flag}{lint}"))
+            })
+            .collect()
+    }
+}
+
+/// Arguments to use when executing the `lint` command.
+#[derive(Clone, Default)]
+pub struct LintArgs {
+    /// The location of the Cargo manifest (`Cargo.toml`) file to use.
+    pub manifest_path: ManifestPath,
+    pub verbosity: Verbosity,
+    /// Run only the mandatory ink! lints, skip the extra ones.
+    pub mandatory_only: bool,
+    pub target: Target,
+    pub output_type: OutputType,
+}
+
+/// A single issue reported by one of the linters.
+#[derive(Debug, Clone, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LintDiagnostic {
+    /// Name of the lint, if the diagnostic originates from one.
+    pub lint: Option<String>,
+    /// Level of the diagnostic, e.g. `warning` or `error`.
+    pub level: String,
+    /// The diagnostic message.
+    pub message: String,
+    /// Source file the diagnostic points to.
+    pub file: Option<String>,
+    /// Line in the source file the diagnostic points to.
+    pub line: Option<usize>,
+    /// The diagnostic as rendered by `rustc`.
+    pub rendered: Option<String>,
+}
+
+/// Result of the `lint` command.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LintResult {
+    /// Diagnostics reported by the linters.
+    ///
+    /// Only collected for JSON output, otherwise they are printed directly.
+    pub diagnostics: Vec<LintDiagnostic>,
+    /// The verbosity flags.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub verbosity: Verbosity,
+    /// The type of formatting to use for the lint output.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub output_type: OutputType,
+}
+
+impl LintResult {
+    /// Returns `true` if any of the diagnostics is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.level == "error")
+    }
+
+    pub fn display(&self) -> String {
+        if self.has_errors() {
+            return format!("{}", "The contract violates linting rules.".red().bold())
+        }
+        format!("{}", "The contract passed all linting checks.".bold())
+    }
+
+    /// Display the lint results in a pretty formatted JSON string.
+    pub fn serialize_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Runs `clippy` and all ink! linting rules on the contract without building it.
+pub fn execute(args: LintArgs) -> Result<LintResult> {
+    let LintArgs {
+        manifest_path,
+        verbosity,
+        mandatory_only,
+        target,
+        output_type,
+    } = args;
+
+    let crate_metadata = CrateMetadata::collect(&manifest_path, target)?;
+    let lint_config = Manifest::new(manifest_path)?.lint_config()?;
+    let capture = matches!(output_type, OutputType::Json);
+
+    verbose_eprintln!(
+        verbosity,
+        " {} {}",
+        "[==]".bold(),
+        "Checking clippy linting rules".bright_green().bold()
+    );
+    let clippy_output = crate::exec_cargo_clippy(&crate_metadata, verbosity, capture)?;
+
+    verbose_eprintln!(
+        verbosity,
+        " {} {}",
+        "[==]".bold(),
+        "Checking ink! linting rules".bright_green().bold()
+    );
+    let dylint_output = crate::exec_cargo_dylint(
+        !mandatory_only,
+        &crate_metadata,
+        &target,
+        verbosity,
+        &lint_config,
+        capture,
+    )?;
+
+    let diagnostics = [clippy_output, dylint_output]
+        .iter()
+        .flat_map(|output| parse_diagnostics(output))
+        .collect();
+
+    Ok(LintResult {
+        diagnostics,
+        verbosity,
+        output_type,
+    })
+}
+
+/// Returns the captured stdout of the `linter`.
+///
+/// Fails if the linter exited with an error without reporting any diagnostics, e.g. if
+/// its toolchain is missing, instead of reporting that there are no diagnostics.
+pub(crate) fn captured_output(linter: &str, output: Output) -> Result<String> {
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() && parse_diagnostics(&stdout).is_empty() {
+        return Err(anyhow::anyhow!(
+            "`cargo {linter}` failed with {} without reporting any diagnostics",
+            output.status
+        ))
+    }
+    Ok(stdout)
+}
+
+/// Extracts the compiler diagnostics from the output of a `cargo` invocation with
+/// `--message-format=json`.
+fn parse_diagnostics(output: &str) -> Vec<LintDiagnostic> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|msg| msg["reason"] == "compiler-message")
+        .filter_map(|msg| {
+            let message = &msg["message"];
+            let primary_span = message["spans"]
+                .as_array()?
+                .iter()
+                .find(|span| span["is_primary"] == true)?;
+            Some(LintDiagnostic {
+                lint: message["code"]["code"].as_str().map(ToOwned::to_owned),
+                level: message["level"].as_str()?.to_owned(),
+                message: message["message"].as_str()?.to_owned(),
+                file: primary_span["file_name"].as_str().map(ToOwned::to_owned),
+                line: primary_span["line_start"]
+                    .as_u64()
+                    .map(|line| line as usize),
+                rendered: message["rendered"].as_str().map(ToOwned::to_owned),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_config_rustflags() {
+        let config: LintConfig = toml::from_str(
+            r#"
+            allow = ["primitive_topic"]
+            deny = ["storage_never_freed", "no_main"]
+            "#,
+        )
+        .expect("valid lint config");
+
+        assert_eq!(
+            config.rustflags(),
+            vec!["-Aprimitive_topic", "-Dstorage_never_freed", "-Dno_main"]
+        );
+    }
+
+    #[test]
+    fn lint_config_rejects_unknown_levels() {
+        let res = toml::from_str::<LintConfig>(r#"forbid = ["primitive_topic"]"#);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn parses_compiler_messages() {
+        let output = r#"{"reason":"compiler-artifact","package_id":"foo"}
+{"reason":"compiler-message","message":{"message":"this topic is a primitive","code":{"code":"primitive_topic","explanation":null},"level":"warning","spans":[{"file_name":"lib.rs","line_start":12,"is_primary":true}],"rendered":"warning: this topic is a primitive"}}
+{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"rendered":"error: aborting"}}
+{"reason":"build-finished","success":false}"#;
+
+        let diagnostics = parse_diagnostics(output);
+
+        assert_eq!(
+            diagnostics,
+            vec![LintDiagnostic {
+                lint: Some("primitive_topic".to_owned()),
+                level: "warning".to_owned(),
+                message: "this topic is a primitive".to_owned(),
+                file: Some("lib.rs".to_owned()),
+                line: Some(12),
+                rendered: Some("warning: this topic is a primitive".to_owned()),
+            }]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_linter_without_diagnostics_is_an_error() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let diagnostic = r#"{"reason":"compiler-message","message":{"message":"unbounded","code":null,"level":"error","spans":[{"file_name":"lib.rs","line_start":1,"is_primary":true}],"rendered":null}}"#;
+        let output = |code, stdout: &str| Output {
+            status: ExitStatusExt::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+        };
+
+        assert!(captured_output("dylint", output(1, "")).is_err());
+        assert!(captured_output("dylint", output(1, diagnostic)).is_ok());
+        assert!(captured_output("dylint", output(0, "")).is_ok());
+    }
+}
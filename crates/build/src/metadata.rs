@@ -17,12 +17,14 @@
 use crate::{
     code_hash,
     crate_metadata::CrateMetadata,
+    post_process_wasm::embed_custom_section,
     util,
     verbose_eprintln,
     workspace::{
         ManifestPath,
         Workspace,
     },
+    BuildFlags,
     BuildMode,
     Features,
     Lto,
@@ -35,6 +37,7 @@ use crate::{
 
 use anyhow::Result;
 use colored::Colorize;
+use contract_analyze::METADATA_HASH_SECTION;
 use contract_metadata::{
     Compiler,
     Contract,
@@ -86,6 +89,28 @@ pub struct BuildInfo {
     pub build_mode: BuildMode,
     /// Information about the `wasm-opt` optimization settings.
     pub wasm_opt_settings: WasmOptSettings,
+    /// The cargo features selected when building the contract.
+    #[serde(default)]
+    pub features: Features,
+    /// The additional build flags passed on the command line.
+    ///
+    /// Flags configured in the `Cargo.toml` are not included, they are part of the
+    /// contract sources.
+    #[serde(default, skip_serializing_if = "BuildFlags::is_empty")]
+    pub build_flags: BuildFlags,
+    /// The hash of the CycloneDX SBOM of the contract crate and its dependency tree,
+    /// if it was generated with `--sbom`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom_hash: Option<String>,
+    /// Whether debug artifacts were generated with `--debug-artifacts`.
+    ///
+    /// The contract is then compiled with debug info, which changes its code.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub debug_artifacts: bool,
+    /// Whether the hash of the ABI was embedded into the code with
+    /// `--embed-metadata-hash`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub embed_metadata_hash: bool,
 }
 
 impl TryFrom<BuildInfo> for serde_json::Map<String, serde_json::Value> {
@@ -104,6 +129,9 @@ pub struct WasmOptSettings {
     pub optimization_passes: OptimizationPasses,
     /// Whether or not the Wasm name section should be kept.
     pub keep_debug_symbols: bool,
+    /// The passes run instead of the default passes of the optimization level.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passes: Vec<String>,
 }
 
 /// Generates a file with metadata describing the ABI of the smart contract.
@@ -120,6 +148,7 @@ pub fn execute(
     unstable_options: &UnstableFlags,
     build_info: BuildInfo,
 ) -> Result<()> {
+    let embed = build_info.embed_metadata_hash;
     // build the extended contract project metadata
     let ExtendedMetadataResult {
         source,
@@ -147,6 +176,13 @@ pub fn execute(
             "--release".to_owned(),
         ];
         network.append_to_args(&mut args);
+        let mut features =
+            features.expand_all_features(crate_metadata.root_package.features.keys());
+        if features.no_default_features() {
+            // The metadata is generated on the host, which requires the `std` feature.
+            args.push("--no-default-features".to_owned());
+            features.push("std");
+        }
         features.append_to_args(&mut args);
 
         #[cfg(windows)]
@@ -169,7 +205,11 @@ pub fn execute(
 
         let ink_meta: serde_json::Map<String, serde_json::Value> =
             serde_json::from_slice(&output.stdout)?;
-        let metadata = ContractMetadata::new(source, contract, None, user, ink_meta);
+        let mut metadata =
+            ContractMetadata::new(source, contract, None, user, ink_meta);
+        if embed {
+            embed_metadata_hash(final_contract_wasm, &mut metadata)?;
+        }
 
         write_metadata(metadata_artifacts, metadata, &verbosity, false)?;
 
@@ -232,6 +272,23 @@ pub fn write_metadata(
     Ok(())
 }
 
+/// Embeds the hash of the ABI of the contract into its Wasm code, linking the code to its
+/// ABI, and updates the code and its hash in the `metadata` accordingly.
+///
+/// The hash is embedded in the [`METADATA_HASH_SECTION`] custom section.
+fn embed_metadata_hash(
+    final_contract_wasm: &Path,
+    metadata: &mut ContractMetadata,
+) -> Result<()> {
+    let wasm = fs::read(final_contract_wasm)?;
+    let wasm =
+        embed_custom_section(&wasm, METADATA_HASH_SECTION, &metadata.abi_hash()?)?;
+    fs::write(final_contract_wasm, &wasm)?;
+    metadata.source.hash = code_hash(&wasm).into();
+    metadata.source.wasm = Some(SourceWasm::new(wasm));
+    Ok(())
+}
+
 /// Generate the extended contract project metadata
 fn extended_metadata(
     crate_metadata: &CrateMetadata,
@@ -14,25 +14,197 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Result;
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_metadata::compatibility::check_contract_ink_compatibility;
 use heck::ToUpperCamelCase as _;
+use semver::{
+    Op,
+    Version,
+    VersionReq,
+};
 use std::{
     env,
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
     fs,
     io::{
         Cursor,
         Read,
-        Seek,
         Write,
     },
     path::{
         Path,
         PathBuf,
     },
+    process::Command,
+    str::FromStr,
 };
 
-/// Creates a new contract project from the template.
+/// The templates bundled with cargo-contract.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, strum::EnumIter)]
+pub enum BundledTemplate {
+    /// A contract storing a single `bool` which can be flipped.
+    #[default]
+    Flipper,
+    /// A fungible token implementing the PSP22 standard.
+    Psp22,
+    /// A non-fungible token implementing the PSP34 standard.
+    Psp34,
+    /// A wallet executing calls once enough of its owners confirmed them.
+    Multisig,
+    /// A proxy delegating calls to upgradeable logic.
+    Proxy,
+}
+
+impl BundledTemplate {
+    /// The zip archive of the template, created by the build script.
+    fn archive(&self) -> &'static [u8] {
+        match self {
+            Self::Flipper => {
+                include_bytes!(concat!(env!("OUT_DIR"), "/template_flipper.zip"))
+            }
+            Self::Psp22 => {
+                include_bytes!(concat!(env!("OUT_DIR"), "/template_psp22.zip"))
+            }
+            Self::Psp34 => {
+                include_bytes!(concat!(env!("OUT_DIR"), "/template_psp34.zip"))
+            }
+            Self::Multisig => {
+                include_bytes!(concat!(env!("OUT_DIR"), "/template_multisig.zip"))
+            }
+            Self::Proxy => {
+                include_bytes!(concat!(env!("OUT_DIR"), "/template_proxy.zip"))
+            }
+        }
+    }
+}
+
+impl Display for BundledTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Flipper => write!(f, "flipper"),
+            Self::Psp22 => write!(f, "psp22"),
+            Self::Psp34 => write!(f, "psp34"),
+            Self::Multisig => write!(f, "multisig"),
+            Self::Proxy => write!(f, "proxy"),
+        }
+    }
+}
+
+/// The template a new contract project is created from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Template {
+    /// A template bundled with cargo-contract.
+    Bundled(BundledTemplate),
+    /// A template in the root of a git repository, cloned from the URL.
+    Git(String),
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self::Bundled(Default::default())
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains("://") || s.starts_with("git@") {
+            // the URL is passed to `git clone`, it must not be mistaken for an option
+            if s.starts_with('-') {
+                return Err(format!("Invalid git URL '{s}'"))
+            }
+            return Ok(Self::Git(s.to_string()))
+        }
+        <BundledTemplate as strum::IntoEnumIterator>::iter()
+            .find(|template| template.to_string() == s)
+            .map(Self::Bundled)
+            .ok_or_else(|| {
+                let bundled = <BundledTemplate as strum::IntoEnumIterator>::iter()
+                    .map(|template| template.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Unknown template '{s}', expected one of {bundled} or a git URL")
+            })
+    }
+}
+
+impl Template {
+    /// Loads the files of the template.
+    ///
+    /// Fails for templates which require network access if `offline` is set.
+    fn files(&self, offline: bool) -> Result<Vec<TemplateFile>> {
+        match self {
+            Self::Bundled(template) => unzip(template.archive()),
+            Self::Git(url) if offline => {
+                anyhow::bail!(
+                    "Cloning the template from {url} requires network access, only the \
+                     bundled templates are available offline"
+                )
+            }
+            Self::Git(url) => clone(url),
+        }
+    }
+}
+
+/// Options for creating a new contract project.
+#[derive(Clone, Debug, Default)]
+pub struct NewProjectOptions {
+    /// The template to create the project from.
+    pub template: Template,
+    /// The ink! version to pin the project to, instead of the version the template
+    /// depends on.
+    pub ink_version: Option<Version>,
+    /// Only use templates which are available without network access.
+    pub offline: bool,
+}
+
+/// A file of a template.
+struct TemplateFile {
+    /// The path of the file, relative to the root of the project.
+    path: PathBuf,
+    contents: Vec<u8>,
+    unix_mode: Option<u32>,
+}
+
+impl TemplateFile {
+    /// Replaces the `{{name}}` and `{{camel_name}}` placeholders in text files.
+    fn render(self, name: &str) -> Self {
+        let contents = match String::from_utf8(self.contents) {
+            Ok(contents) => {
+                contents
+                    .replace("{{name}}", name)
+                    .replace("{{camel_name}}", &name.to_upper_camel_case())
+                    .into_bytes()
+            }
+            Err(err) => err.into_bytes(),
+        };
+        Self { contents, ..self }
+    }
+}
+
+/// Creates a new contract project from the default template.
 pub fn new_contract_project<P>(name: &str, dir: Option<P>) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    new_contract_project_with_options(name, dir, &NewProjectOptions::default())
+}
+
+/// Creates a new contract project as configured by the `options`.
+pub fn new_contract_project_with_options<P>(
+    name: &str,
+    dir: Option<P>,
+    options: &NewProjectOptions,
+) -> Result<()>
 where
     P: AsRef<Path>,
 {
@@ -57,78 +229,265 @@ where
     if out_dir.join("Cargo.toml").exists() {
         anyhow::bail!("A Cargo package already exists in {}", name);
     }
+
+    let mut files = options
+        .template
+        .files(options.offline)?
+        .into_iter()
+        .map(|file| file.render(name))
+        .collect::<Vec<_>>();
+    if let Some(ink_version) = &options.ink_version {
+        let manifest = files
+            .iter_mut()
+            .find(|file| file.path == Path::new("Cargo.toml"))
+            .ok_or_else(|| anyhow!("The template has no `Cargo.toml` in its root"))?;
+        manifest.contents =
+            pin_ink_version(std::str::from_utf8(&manifest.contents)?, ink_version)?
+                .into_bytes();
+    }
+    check_ink_compatibility(&files)?;
+
     if !out_dir.exists() {
         fs::create_dir(&out_dir)?;
     }
-
-    let template = include_bytes!(concat!(env!("OUT_DIR"), "/template.zip"));
-
-    unzip(template, out_dir, Some(name))?;
+    for file in files {
+        write_file(&out_dir, file)?;
+    }
 
     Ok(())
 }
 
-// Unzips the file at `template` to `out_dir`.
-//
-// In case `name` is set the zip file is treated as if it were a template for a new
-// contract. Replacements in `Cargo.toml` for `name`-placeholders are attempted in
-// that case.
-fn unzip(template: &[u8], out_dir: PathBuf, name: Option<&str>) -> Result<()> {
-    let mut cursor = Cursor::new(Vec::new());
-    cursor.write_all(template)?;
-    cursor.rewind()?;
+/// Checks that the ink! version the template depends on is supported by this version
+/// of cargo-contract.
+///
+/// Templates depending on ink! via git, a path or the workspace are not checked.
+fn check_ink_compatibility(files: &[TemplateFile]) -> Result<()> {
+    let manifest = files
+        .iter()
+        .find(|file| file.path == Path::new("Cargo.toml"))
+        .ok_or_else(|| anyhow!("The template has no `Cargo.toml` in its root"))?;
+    let manifest: toml::Table = toml::from_str(std::str::from_utf8(&manifest.contents)?)
+        .context("Failed to parse the `Cargo.toml` of the template")?;
+    let ink = manifest
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.get("ink"))
+        .ok_or_else(|| anyhow!("The template does not depend on ink!"))?;
+    let requirement = match ink {
+        toml::Value::String(version) => Some(version.as_str()),
+        toml::Value::Table(dependency) => {
+            dependency.get("version").and_then(toml::Value::as_str)
+        }
+        _ => None,
+    };
+    if let Some(requirement) = requirement {
+        let version = VersionReq::parse(requirement)
+            .ok()
+            .as_ref()
+            .and_then(lowest_matching_version)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unsupported ink! version requirement '{requirement}' in the template"
+                )
+            })?;
+        check_contract_ink_compatibility(&version, None)?;
+    }
+    Ok(())
+}
 
-    let mut archive = zip::ZipArchive::new(cursor)?;
+/// Returns the lowest version matching the `requirement`, `None` if it has no inclusive
+/// lower bound, e.g. `<5.0.0` or `*`.
+fn lowest_matching_version(requirement: &VersionReq) -> Option<Version> {
+    requirement
+        .comparators
+        .iter()
+        .filter(|comparator| {
+            matches!(
+                comparator.op,
+                Op::Exact | Op::GreaterEq | Op::Tilde | Op::Caret | Op::Wildcard
+            )
+        })
+        .map(|comparator| {
+            let mut version = Version::new(
+                comparator.major,
+                comparator.minor.unwrap_or(0),
+                comparator.patch.unwrap_or(0),
+            );
+            version.pre = comparator.pre.clone();
+            version
+        })
+        .filter(|version| requirement.matches(version))
+        .min()
+}
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let outpath = out_dir.join(file.name());
+/// Returns the versions of `parity-scale-codec` and `scale-info` compatible with
+/// `ink_version`.
+fn scale_versions(ink_version: &Version) -> Result<(&'static str, &'static str)> {
+    match ink_version.major {
+        5 => Ok(("3.6.9", "2.6")),
+        _ => {
+            anyhow::bail!(
+                "No compatible versions of `parity-scale-codec` and `scale-info` are \
+                 known for ink! {ink_version}"
+            )
+        }
+    }
+}
 
-        if (*file.name()).ends_with('/') {
-            fs::create_dir_all(&outpath)?;
-        } else {
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    fs::create_dir_all(p)?;
+/// Pins the ink! dependencies of the `manifest` exactly to `ink_version`, and requires
+/// the compatible versions of the SCALE crates if the manifest depends on them.
+///
+/// Dependencies on ink! from git or a path are replaced by the released version.
+fn pin_ink_version(manifest: &str, ink_version: &Version) -> Result<String> {
+    let (scale_version, scale_info_version) = scale_versions(ink_version)?;
+    let mut manifest: toml_edit::DocumentMut = manifest
+        .parse()
+        .context("Failed to parse the `Cargo.toml` of the template")?;
+    for section in ["dependencies", "dev-dependencies"] {
+        let Some(dependencies) = manifest
+            .get_mut(section)
+            .and_then(toml_edit::Item::as_table_like_mut)
+        else {
+            continue
+        };
+        for (key, dependency) in dependencies.iter_mut() {
+            let package = dependency
+                .get("package")
+                .and_then(toml_edit::Item::as_str)
+                .unwrap_or(key.get());
+            let version = match package {
+                "ink" | "ink_e2e" => format!("={ink_version}"),
+                "parity-scale-codec" => scale_version.to_string(),
+                "scale-info" => scale_info_version.to_string(),
+                _ => continue,
+            };
+            match dependency.as_table_like_mut() {
+                Some(dependency) => {
+                    for source in ["git", "branch", "tag", "rev", "path", "workspace"] {
+                        dependency.remove(source);
+                    }
+                    dependency.insert("version", toml_edit::value(version));
+                    dependency.fmt();
                 }
+                None => *dependency = toml_edit::value(version),
             }
-            let mut outfile = fs::OpenOptions::new()
-                .write(true)
-                .create_new(true)
-                .open(outpath.clone())
-                .map_err(|e| {
-                    if e.kind() == std::io::ErrorKind::AlreadyExists {
-                        anyhow::anyhow!("File {} already exists", file.name(),)
-                    } else {
-                        anyhow::anyhow!(e)
-                    }
-                })?;
-
-            if let Some(name) = name {
-                let mut contents = String::new();
-                file.read_to_string(&mut contents)?;
-                let contents = contents.replace("{{name}}", name);
-                let contents =
-                    contents.replace("{{camel_name}}", &name.to_upper_camel_case());
-                outfile.write_all(contents.as_bytes())?;
+        }
+    }
+    Ok(manifest.to_string())
+}
+
+/// Writes `file` to `out_dir`, failing if it already exists.
+fn write_file(out_dir: &Path, file: TemplateFile) -> Result<()> {
+    let outpath = out_dir.join(&file.path);
+    if let Some(p) = outpath.parent() {
+        if !p.exists() {
+            fs::create_dir_all(p)?;
+        }
+    }
+    let mut outfile = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&outpath)
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                anyhow::anyhow!("File {} already exists", file.path.display())
             } else {
-                let mut v = Vec::new();
-                file.read_to_end(&mut v)?;
-                outfile.write_all(v.as_slice())?;
+                anyhow::anyhow!(e)
             }
+        })?;
+    outfile.write_all(&file.contents)?;
+
+    // Set permissions
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if let Some(mode) = file.unix_mode {
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
         }
+    }
 
-        // Get and set permissions
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
+    Ok(())
+}
 
-            if let Some(mode) = file.unix_mode() {
-                fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))?;
-            }
+// Reads the files of the zip archive `template`.
+fn unzip(template: &[u8]) -> Result<Vec<TemplateFile>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(template))?;
+
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if (*file.name()).ends_with('/') {
+            continue
         }
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        files.push(TemplateFile {
+            path: PathBuf::from(file.name()),
+            contents,
+            unix_mode: file.unix_mode(),
+        });
+    }
+
+    Ok(files)
+}
+
+// Clones the git repository at `url` and reads its files.
+fn clone(url: &str) -> Result<Vec<TemplateFile>> {
+    let tmp_dir = tempfile::tempdir()?;
+    let status = Command::new("git")
+        .args(["clone", "--quiet", "--depth", "1", "--", url])
+        .arg(tmp_dir.path())
+        .status()
+        .context("Failed to execute git, is it installed?")?;
+    if !status.success() {
+        anyhow::bail!("Failed to clone the template from {url}");
     }
 
+    let mut files = Vec::new();
+    read_dir(tmp_dir.path(), tmp_dir.path(), &mut files)?;
+    Ok(files)
+}
+
+// Reads the files in `dir` recursively, skipping the `.git` directory.
+//
+// Like for the bundled templates, `_Cargo.toml` files are renamed to `Cargo.toml`.
+// Symlinks are rejected, otherwise a template could copy arbitrary local files into the
+// new project.
+fn read_dir(root: &Path, dir: &Path, files: &mut Vec<TemplateFile>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = fs::symlink_metadata(&path)?.file_type();
+        if file_type.is_symlink() {
+            anyhow::bail!(
+                "The template contains the symlink {}, which is not supported",
+                path.strip_prefix(root)?.display()
+            )
+        }
+        if file_type.is_dir() {
+            if entry.file_name() != ".git" {
+                read_dir(root, &path, files)?;
+            }
+            continue
+        }
+
+        let mut relative = path.strip_prefix(root)?.to_path_buf();
+        if relative.file_name() == Some("_Cargo.toml".as_ref()) {
+            relative.set_file_name("Cargo.toml");
+        }
+        #[cfg(unix)]
+        let unix_mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(entry.metadata()?.permissions().mode())
+        };
+        #[cfg(not(unix))]
+        let unix_mode = None;
+        files.push(TemplateFile {
+            path: relative,
+            contents: fs::read(&path)?,
+            unix_mode,
+        });
+    }
     Ok(())
 }
 
@@ -209,4 +568,228 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn every_bundled_template_creates_project() {
+        with_tmp_dir(|path| {
+            for template in <BundledTemplate as strum::IntoEnumIterator>::iter() {
+                let name = format!("new_{template}");
+                let options = NewProjectOptions {
+                    template: Template::Bundled(template),
+                    ..Default::default()
+                };
+                new_contract_project_with_options(&name, Some(path), &options)?;
+
+                let manifest = fs::read_to_string(path.join(&name).join("Cargo.toml"))?;
+                let lib = fs::read_to_string(path.join(&name).join("lib.rs"))?;
+                assert!(manifest.contains(&format!("name = \"{name}\"")));
+                assert!(lib.contains(&format!("mod {name} {{")));
+                assert!(!lib.contains("{{"));
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn parses_templates() {
+        assert_eq!(
+            "psp22".parse(),
+            Ok(Template::Bundled(BundledTemplate::Psp22))
+        );
+        assert_eq!(
+            "https://github.com/use-ink/template".parse(),
+            Ok(Template::Git(
+                "https://github.com/use-ink/template".to_string()
+            ))
+        );
+        assert_eq!(
+            "--upload-pack=touch /tmp/x;://".parse::<Template>(),
+            Err("Invalid git URL '--upload-pack=touch /tmp/x;://'".to_string())
+        );
+        assert_eq!(
+            "psp37".parse::<Template>(),
+            Err(
+                "Unknown template 'psp37', expected one of flipper, psp22, psp34, \
+                 multisig, proxy or a git URL"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_incompatible_ink_version() {
+        let manifest = |contents: &str| {
+            vec![TemplateFile {
+                path: PathBuf::from("Cargo.toml"),
+                contents: contents.as_bytes().to_vec(),
+                unix_mode: None,
+            }]
+        };
+
+        assert!(check_ink_compatibility(&manifest(
+            "[dependencies]\nink = { version = \"5.0.0\" }"
+        ))
+        .is_ok());
+        assert!(check_ink_compatibility(&manifest(
+            "[dependencies]\nink = { git = \"https://github.com/use-ink/ink\" }"
+        ))
+        .is_ok());
+        assert!(
+            check_ink_compatibility(&manifest("[dependencies]\nink = \"5\"")).is_ok()
+        );
+        assert!(
+            check_ink_compatibility(&manifest("[dependencies]\nink = \">=5.0, <6\""))
+                .is_ok()
+        );
+        assert!(
+            check_ink_compatibility(&manifest("[dependencies]\nink = \"4.3.0\""))
+                .is_err()
+        );
+        assert_eq!(
+            check_ink_compatibility(&manifest("[dependencies]\nink = \"<5\""))
+                .unwrap_err()
+                .to_string(),
+            "Unsupported ink! version requirement '<5' in the template"
+        );
+        assert_eq!(
+            check_ink_compatibility(&manifest("[dependencies]"))
+                .unwrap_err()
+                .to_string(),
+            "The template does not depend on ink!"
+        );
+    }
+
+    #[test]
+    fn creates_project_from_git_template() {
+        with_tmp_dir(|path| {
+            let repo = path.join("template");
+            fs::create_dir(&repo)?;
+            fs::write(
+                repo.join("_Cargo.toml"),
+                "[package]\nname = \"{{name}}\"\n\n[dependencies]\nink = \"5.0.0\"\n",
+            )?;
+            fs::write(repo.join("lib.rs"), "struct {{camel_name}};\n")?;
+            let git = |args: &[&str]| {
+                let status = Command::new("git")
+                    .args(["-c", "user.name=ink", "-c", "user.email=ink@use.ink"])
+                    .args(args)
+                    .current_dir(&repo)
+                    .status()
+                    .unwrap();
+                assert!(status.success());
+            };
+            git(&["init", "--quiet"]);
+            git(&["add", "."]);
+            git(&["commit", "--quiet", "-m", "template"]);
+
+            let options = NewProjectOptions {
+                template: Template::Git(format!("file://{}", repo.display())),
+                ..Default::default()
+            };
+            new_contract_project_with_options("from_git", Some(path), &options)?;
+
+            let project = path.join("from_git");
+            assert_eq!(
+                fs::read_to_string(project.join("lib.rs"))?,
+                "struct FromGit;\n"
+            );
+            assert!(fs::read_to_string(project.join("Cargo.toml"))?
+                .contains("name = \"from_git\""));
+            assert!(!project.join(".git").exists());
+            Ok(())
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_in_templates() {
+        with_tmp_dir(|path| {
+            let secret = path.join("id_ed25519");
+            fs::write(&secret, "secret")?;
+            let template = path.join("template");
+            fs::create_dir(&template)?;
+            fs::write(template.join("lib.rs"), "struct {{camel_name}};\n")?;
+            std::os::unix::fs::symlink(&secret, template.join("key"))?;
+
+            let mut files = Vec::new();
+            let result = read_dir(&template, &template, &mut files);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "The template contains the symlink key, which is not supported"
+            );
+            assert!(files.iter().all(|file| file.contents != b"secret"));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn pins_ink_version() {
+        let manifest = r#"[package]
+name = "pinned"
+
+[dependencies]
+ink = { git = "https://github.com/use-ink/ink", branch = "master", default-features = false }
+scale = { package = "parity-scale-codec", version = "3", default-features = false }
+scale-info = "2"
+serde = "1"
+
+[dev-dependencies]
+ink_e2e = { version = "5.0.0-rc" }
+"#;
+
+        let pinned = pin_ink_version(manifest, &Version::new(5, 0, 0)).unwrap();
+
+        assert_eq!(
+            pinned,
+            r#"[package]
+name = "pinned"
+
+[dependencies]
+ink = { default-features = false, version = "=5.0.0" }
+scale = { package = "parity-scale-codec", version = "3.6.9", default-features = false }
+scale-info = "2.6"
+serde = "1"
+
+[dev-dependencies]
+ink_e2e = { version = "=5.0.0" }
+"#
+        );
+    }
+
+    #[test]
+    fn rejects_incompatible_pinned_ink_version() {
+        with_tmp_dir(|path| {
+            let options = NewProjectOptions {
+                ink_version: Some(Version::new(4, 3, 0)),
+                ..Default::default()
+            };
+            let result =
+                new_contract_project_with_options("pinned", Some(path), &options);
+
+            assert!(result.is_err(), "Should fail");
+            assert!(!path.join("pinned").exists());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn offline_rejects_git_templates() {
+        with_tmp_dir(|path| {
+            let options = NewProjectOptions {
+                template: Template::Git("https://github.com/use-ink/template".into()),
+                offline: true,
+                ..Default::default()
+            };
+            let result =
+                new_contract_project_with_options("offline", Some(path), &options);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Cloning the template from https://github.com/use-ink/template requires \
+                 network access, only the bundled templates are available offline"
+            );
+            Ok(())
+        })
+    }
 }
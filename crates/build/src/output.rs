@@ -0,0 +1,234 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Output of the commands, rendered by a [`Renderer`].
+//!
+//! Results are printed to stdout, while diagnostics like progress messages and
+//! warnings are printed to stderr, either for humans or as JSON lines emitted via
+//! `tracing` with the target [`OUTPUT_TARGET`].
+//!
+//! Applications embedding the commands, like a TUI or a web UI, install their own
+//! [`Renderer`] with [`set_renderer`] to receive the output instead.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use colored::Colorize as _;
+use std::{
+    fmt::{
+        Arguments,
+        Display,
+    },
+    io::{
+        self,
+        Write as _,
+    },
+    sync::OnceLock,
+};
+
+/// The `tracing` target of the diagnostics emitted in the [`LogFormat::Json`] format.
+pub const OUTPUT_TARGET: &str = "output";
+
+static LOG_FORMAT: OnceLock<LogFormat> = OnceLock::new();
+
+static RENDERER: OnceLock<Box<dyn Renderer>> = OnceLock::new();
+
+/// The format of the diagnostics printed to stderr.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human readable, colored text.
+    #[default]
+    Human,
+    /// One JSON object per line.
+    Json,
+}
+
+/// Sets the format of the diagnostics for the rest of the process.
+///
+/// Disables colored output for [`LogFormat::Json`]. Has no effect if the format was
+/// already set.
+pub fn set_log_format(format: LogFormat) {
+    if LOG_FORMAT.set(format).is_ok() && format == LogFormat::Json {
+        colored::control::set_override(false);
+    }
+}
+
+/// Returns the format of the diagnostics.
+pub fn log_format() -> LogFormat {
+    LOG_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Renders the output of the commands.
+pub trait Renderer: Send + Sync {
+    /// Renders a named value of a result, e.g. the hash of an uploaded code. The names
+    /// of consecutive values are right aligned to the `width`.
+    fn name_value(&self, name: &str, value: &str, width: usize);
+
+    /// Renders the status of a command, e.g. `Dry-running`, and a message describing
+    /// it.
+    fn status(&self, status: &str, message: &str);
+
+    /// Renders a diagnostic message, e.g. about the progress of a command.
+    fn diagnostic(&self, message: &str);
+
+    /// Renders a warning.
+    fn warning(&self, message: &str);
+
+    /// Asks the user to answer the `question` with yes or no, e.g. before an extrinsic
+    /// is submitted. The `default` is the answer if the user enters nothing.
+    fn confirm(&self, question: &str, default: bool) -> Result<bool>;
+}
+
+/// Sets the [`Renderer`] of the output for the rest of the process.
+///
+/// Has no effect if a renderer was already set. Defaults to the [`TerminalRenderer`].
+pub fn set_renderer(renderer: Box<dyn Renderer>) {
+    let _ = RENDERER.set(renderer);
+}
+
+/// Returns the [`Renderer`] of the output.
+pub fn renderer() -> &'static dyn Renderer {
+    RENDERER.get().map_or(&TerminalRenderer, |renderer| renderer.as_ref())
+}
+
+/// Renders the output to the terminal, with the diagnostics in the [`LogFormat`] set
+/// with [`set_log_format`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TerminalRenderer;
+
+impl Renderer for TerminalRenderer {
+    fn name_value(&self, name: &str, value: &str, width: usize) {
+        println!("{:>width$} {}", name.bright_purple().bold(), value);
+    }
+
+    fn status(&self, status: &str, message: &str) {
+        println!(
+            "{:>width$} {}",
+            status.bright_green().bold(),
+            message,
+            width = crate::DEFAULT_KEY_COL_WIDTH
+        );
+    }
+
+    fn diagnostic(&self, message: &str) {
+        match log_format() {
+            LogFormat::Human => eprintln!("{message}"),
+            LogFormat::Json => tracing::info!(target: OUTPUT_TARGET, "{message}"),
+        }
+    }
+
+    fn warning(&self, message: &str) {
+        match log_format() {
+            LogFormat::Human => {
+                eprintln!("{} {}", "warning:".yellow().bold(), message.bold())
+            }
+            LogFormat::Json => tracing::warn!(target: OUTPUT_TARGET, "{message}"),
+        }
+    }
+
+    fn confirm(&self, question: &str, default: bool) -> Result<bool> {
+        let options = if default { "Y/n" } else { "y/N" };
+        print!(
+            "{} ({}): ",
+            question.bright_white().bold(),
+            options.bright_white().bold()
+        );
+        let mut buf = String::new();
+        io::stdout().flush()?;
+        io::stdin().read_line(&mut buf)?;
+        match buf.trim().to_lowercase().as_str() {
+            "" => Ok(default),
+            "y" => Ok(true),
+            "n" => Ok(false),
+            c => Err(anyhow!("Expected either 'y' or 'n', got '{}'", c)),
+        }
+    }
+}
+
+/// Renders the output as one JSON object per line, the results to stdout and the
+/// diagnostics to stderr.
+///
+/// Questions can not be answered, so commands asking to confirm an action fail.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonRenderer;
+
+impl JsonRenderer {
+    /// Returns the JSON line of an output of the `kind`, e.g. `warning`, with the
+    /// `fields`.
+    fn line(kind: &str, fields: &[(&str, &str)]) -> String {
+        let mut object = serde_json::Map::new();
+        object.insert("type".into(), kind.into());
+        for (name, value) in fields {
+            object.insert((*name).into(), (*value).into());
+        }
+        serde_json::Value::Object(object).to_string()
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn name_value(&self, name: &str, value: &str, _width: usize) {
+        println!("{}", Self::line("value", &[("name", name), ("value", value)]));
+    }
+
+    fn status(&self, status: &str, message: &str) {
+        println!(
+            "{}",
+            Self::line("status", &[("status", status), ("message", message)])
+        );
+    }
+
+    fn diagnostic(&self, message: &str) {
+        eprintln!("{}", Self::line("diagnostic", &[("message", message)]));
+    }
+
+    fn warning(&self, message: &str) {
+        eprintln!("{}", Self::line("warning", &[("message", message)]));
+    }
+
+    fn confirm(&self, question: &str, _default: bool) -> Result<bool> {
+        Err(anyhow!(
+            "Confirmation required, which is not supported with JSON output: {question}"
+        ))
+    }
+}
+
+/// Prints a diagnostic message to stderr.
+pub fn diagnostic(message: Arguments) {
+    renderer().diagnostic(&message.to_string())
+}
+
+/// Prints a warning to stderr.
+pub fn warning(message: impl Display) {
+    renderer().warning(&message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_renderer_lines() {
+        let line =
+            JsonRenderer::line("value", &[("name", "Code hash"), ("value", "0x01")]);
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+            serde_json::json!({ "type": "value", "name": "Code hash", "value": "0x01" })
+        );
+        assert!(JsonRenderer.confirm("Submit?", true).is_err());
+    }
+}
@@ -15,6 +15,7 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::{
+    collections::BTreeMap,
     fs,
     path::{
         Path,
@@ -24,15 +25,18 @@ use std::{
 
 use colored::Colorize;
 use wasm_encoder::{
+    CustomSection,
     EntityType,
     ExportSection,
     ImportSection,
+    MemoryType,
     RawSection,
     Section,
 };
 use wasmparser::{
     ExportSectionReader,
     ImportSectionReader,
+    MemorySectionReader,
     Parser,
     Payload,
 };
@@ -45,45 +49,129 @@ use anyhow::{
 };
 
 use crate::{
+    output,
     validate_wasm,
     verbose_eprintln,
     Verbosity,
 };
 
+/// The id of the import section, sections with a higher id (except custom sections)
+/// must follow it.
+const IMPORT_SECTION_ID: u8 = 2;
+
+/// Settings for the post-processing of the Wasm code.
+///
+/// They can be configured in the `[package.metadata.contract.wasm]` section of the
+/// contract's `Cargo.toml`, e.g.
+///
+/// ```toml
+/// [package.metadata.contract.wasm]
+/// initial-memory-pages = 2
+/// maximum-memory-pages = 16
+/// rename-exports = { "_call" = "call", "_deploy" = "deploy" }
+/// ```
+#[derive(Default, Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct WasmSettings {
+    /// The initial number of pages of the memory import, defaults to the number of
+    /// pages requested by the module.
+    pub initial_memory_pages: Option<u64>,
+    /// The maximum number of pages of the memory import, defaults to the number of
+    /// pages requested by the module or the maximum number of allowed pages.
+    pub maximum_memory_pages: Option<u64>,
+    /// Exports to rename, e.g. to the `call` and `deploy` functions.
+    pub rename_exports: BTreeMap<String, String>,
+}
+
+/// Returns the memory defined by the module itself instead of being imported, e.g. if
+/// the contract was linked without `--import-memory`.
+fn defined_memory(module: &[u8]) -> Result<Option<MemoryType>> {
+    for payload in Parser::new(0).parse_all(module) {
+        if let Payload::MemorySection(memories) = payload? {
+            return single_memory(&memories).map(Some)
+        }
+    }
+    Ok(None)
+}
+
+fn single_memory(memories: &MemorySectionReader) -> Result<MemoryType> {
+    let mut memories = memories.clone().into_iter();
+    match (memories.next(), memories.next()) {
+        (Some(memory), None) => Ok(memory?.into()),
+        _ => anyhow::bail!("The wasm module must not define more than one memory"),
+    }
+}
+
+/// Applies the configured memory limits and ensures the memory has a maximum number of
+/// pages within the allowed limit.
+fn normalize_memory(
+    mut memory: MemoryType,
+    maximum_allowed_pages: u64,
+    settings: &WasmSettings,
+) -> Result<MemoryType> {
+    if let Some(initial) = settings.initial_memory_pages {
+        memory.minimum = initial;
+    }
+    if let Some(maximum) = settings.maximum_memory_pages {
+        memory.maximum = Some(maximum);
+    }
+    let maximum = match memory.maximum {
+        Some(requested_maximum) if requested_maximum > maximum_allowed_pages => {
+            anyhow::bail!(
+                "The wasm module requires {} pages. The maximum allowed number of pages is {}",
+                requested_maximum,
+                maximum_allowed_pages,
+            );
+        }
+        Some(requested_maximum) => requested_maximum,
+        None => maximum_allowed_pages,
+    };
+    if memory.minimum > maximum {
+        anyhow::bail!(
+            "The wasm module requires {} initial pages, which exceeds its maximum of \
+             {} pages",
+            memory.minimum,
+            maximum,
+        );
+    }
+    memory.maximum = Some(maximum);
+    Ok(memory)
+}
+
 /// Ensures the Wasm memory import of a given module has the maximum number of pages.
 ///
-/// Iterates over the import section, finds the memory import entry if any and adjusts the
-/// maximum limit.
+/// Iterates over the import section, finds the memory import entry if any and adjusts its
+/// limits. A memory defined by the module itself is turned into the `env.memory` import
+/// expected by `pallet-contracts`.
 fn ensure_maximum_memory_pages(
-    imports_reader: &ImportSectionReader,
+    imports_reader: Option<&ImportSectionReader>,
+    defined_memory: Option<MemoryType>,
     maximum_allowed_pages: u64,
+    settings: &WasmSettings,
 ) -> Result<ImportSection> {
     let mut memory_found = false;
-    let imports = imports_reader.clone().into_iter().try_fold(
-        ImportSection::new(), |mut imports, entry| {
-            let entry = entry?;
-            let mut entity  = EntityType::try_from(
-                entry.ty).map_err(|_| anyhow!("Unsupported type in import section"))?;
-            if let EntityType::Memory(mut mem) = entity {
-                memory_found = true;
-               if let Some(requested_maximum) = mem.maximum {
-                    // The module already has maximum, check if it is within the limit bail out.
-                    if requested_maximum > maximum_allowed_pages {
-                        anyhow::bail!(
-                            "The wasm module requires {} pages. The maximum allowed number of pages is {}",
-                            requested_maximum, maximum_allowed_pages,
-                        );
-                    }
-                }
-                else {
-                    mem.maximum = Some(maximum_allowed_pages);
-                    entity = EntityType::from(mem);
-                }
-            }
-            imports.import(entry.module, entry.name, entity);
+    let mut imports = ImportSection::new();
+    for entry in imports_reader.into_iter().flat_map(|reader| reader.clone()) {
+        let entry = entry?;
+        let mut entity = EntityType::try_from(entry.ty)
+            .map_err(|_| anyhow!("Unsupported type in import section"))?;
+        if let EntityType::Memory(memory) = entity {
+            memory_found = true;
+            entity = normalize_memory(memory, maximum_allowed_pages, settings)?.into();
+        }
+        imports.import(entry.module, entry.name, entity);
+    }
 
-            Ok::<_, Error>(imports)
-    })?;
+    if let Some(memory) = defined_memory {
+        if memory_found {
+            anyhow::bail!(
+                "The wasm module defines a memory in addition to the imported memory"
+            );
+        }
+        memory_found = true;
+        let memory = normalize_memory(memory, maximum_allowed_pages, settings)?;
+        imports.import("env", "memory", memory);
+    }
 
     if !memory_found {
         anyhow::bail!(
@@ -103,17 +191,24 @@ fn strip_custom_sections(name: &str) -> bool {
 
 /// A contract should export nothing but the "call" and "deploy" functions.
 ///
-/// Any elements not referenced by these exports become orphaned and are removed by
-/// `wasm-opt`.
-fn strip_export_section(exports_reader: &ExportSectionReader) -> Result<ExportSection> {
+/// Exports are renamed according to `rename_exports` first. Any elements not referenced
+/// by these exports become orphaned and are removed by `wasm-opt`.
+fn strip_export_section(
+    exports_reader: &ExportSectionReader,
+    rename_exports: &BTreeMap<String, String>,
+) -> Result<ExportSection> {
     let filtered_exports = exports_reader.clone().into_iter().try_fold(
         ExportSection::new(),
         |mut exports, entry| {
             let entry = entry.context("Parsing of wasm export section failed")?;
+            let name = rename_exports
+                .get(entry.name)
+                .map(String::as_str)
+                .unwrap_or(entry.name);
             if matches!(entry.kind, wasmparser::ExternalKind::Func)
-                && (entry.name == "call" || entry.name == "deploy")
+                && (name == "call" || name == "deploy")
             {
-                exports.export(entry.name, entry.kind.into(), entry.index);
+                exports.export(name, entry.kind.into(), entry.index);
             }
             Ok::<_, Error>(exports)
         },
@@ -137,12 +232,18 @@ pub fn post_process_wasm(
     skip_wasm_validation: bool,
     verbosity: &Verbosity,
     max_memory_pages: u64,
+    settings: &WasmSettings,
 ) -> Result<()> {
     // Deserialize Wasm module from a file.
     let module =
         load_module(optimized_code).context("Loading of optimized wasm failed")?;
-    let output =
-        post_process_module(&module, skip_wasm_validation, verbosity, max_memory_pages)?;
+    let output = post_process_module(
+        &module,
+        skip_wasm_validation,
+        verbosity,
+        max_memory_pages,
+        settings,
+    )?;
     fs::write(optimized_code, output)?;
     Ok(())
 }
@@ -153,7 +254,12 @@ pub fn post_process_module(
     skip_wasm_validation: bool,
     verbosity: &Verbosity,
     max_memory_pages: u64,
+    settings: &WasmSettings,
 ) -> Result<Vec<u8>> {
+    let defined_memory = defined_memory(module)?;
+    // A module defining its memory without importing anything has no import section
+    // to add the memory import to yet.
+    let mut imports_written = defined_memory.is_none();
     let mut output = Vec::new();
     for payload in Parser::new(0).parse_all(module) {
         let payload = payload?;
@@ -175,19 +281,44 @@ pub fn post_process_module(
                 }
             }
             Payload::ExportSection(ref e) => {
-                let exports = strip_export_section(e)?;
+                let exports = strip_export_section(e, &settings.rename_exports)?;
                 exports.append_to(&mut output);
                 continue
             }
             Payload::ImportSection(ref i) => {
-                let imports = ensure_maximum_memory_pages(i, max_memory_pages)?;
+                let imports = ensure_maximum_memory_pages(
+                    Some(i),
+                    defined_memory,
+                    max_memory_pages,
+                    settings,
+                )?;
                 imports.append_to(&mut output);
+                imports_written = true;
+                continue
+            }
+            // The defined memory was moved to the import section
+            Payload::MemorySection(_) => continue,
+            Payload::StartSection { func, .. } => {
+                output::warning(format!(
+                    "Removed the start function {func} of the wasm module, contracts \
+                     must not have a start function"
+                ));
                 continue
             }
             _ => {}
         }
         // Forward a section without touching it
         if let Some((id, range)) = payload.as_section() {
+            if !imports_written && id > IMPORT_SECTION_ID {
+                let imports = ensure_maximum_memory_pages(
+                    None,
+                    defined_memory,
+                    max_memory_pages,
+                    settings,
+                )?;
+                imports.append_to(&mut output);
+                imports_written = true;
+            }
             RawSection {
                 id,
                 data: &module[range],
@@ -203,6 +334,7 @@ pub fn post_process_module(
 
     if !skip_wasm_validation {
         validate_wasm::validate_import_section(&output)?;
+        validate_wasm::check_host_functions(&output, verbosity)?;
     } else {
         verbose_eprintln!(
             verbosity,
@@ -216,6 +348,37 @@ pub fn post_process_module(
     Ok(output)
 }
 
+/// Embeds a custom section `name` with `data` at the end of the Wasm module, replacing
+/// existing custom sections with the same name.
+pub fn embed_custom_section(module: &[u8], name: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    for payload in Parser::new(0).parse_all(module) {
+        let payload = payload?;
+        match payload {
+            Payload::Version { .. } => {
+                output.extend_from_slice(&wasm_encoder::Module::HEADER);
+                continue
+            }
+            Payload::End(_) => break,
+            Payload::CustomSection(ref c) if c.name() == name => continue,
+            _ => {}
+        }
+        if let Some((id, range)) = payload.as_section() {
+            RawSection {
+                id,
+                data: &module[range],
+            }
+            .append_to(&mut output);
+        }
+    }
+    CustomSection {
+        name: name.into(),
+        data: data.into(),
+    }
+    .append_to(&mut output);
+    Ok(output)
+}
+
 #[cfg(test)]
 mod unit_tests {
     use super::*;
@@ -235,7 +398,13 @@ mod unit_tests {
         let module = wabt::wat2wasm(contract).expect("Invalid wabt");
 
         // when
-        let res = post_process_module(&module, true, &Verbosity::Verbose, 16);
+        let res = post_process_module(
+            &module,
+            true,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        );
 
         // then
         assert!(res.is_err());
@@ -257,8 +426,14 @@ mod unit_tests {
         let module = wabt::wat2wasm(contract).expect("Invalid wabt");
 
         // when
-        let output = post_process_module(&module, true, &Verbosity::Verbose, 16)
-            .expect("Invalid wasm module");
+        let output = post_process_module(
+            &module,
+            true,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        )
+        .expect("Invalid wasm module");
 
         // then
         let maximum = Parser::new(0).parse_all(&output).find_map(|p| {
@@ -289,7 +464,13 @@ mod unit_tests {
         let module = wabt::wat2wasm(contract).expect("Invalid wabt");
 
         // when
-        let res = post_process_module(&module, true, &Verbosity::Verbose, 16);
+        let res = post_process_module(
+            &module,
+            true,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        );
 
         // then
         assert!(res.is_err());
@@ -316,8 +497,14 @@ mod unit_tests {
         let module = wabt::wat2wasm(contract).expect("Invalid wabt");
 
         // when
-        let output = post_process_module(&module, true, &Verbosity::Verbose, 1)
-            .expect("Invalid wasm module");
+        let output = post_process_module(
+            &module,
+            true,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        )
+        .expect("Invalid wasm module");
 
         // then
         let exports_count = Parser::new(0).parse_all(&output).find_map(|p| {
@@ -353,10 +540,223 @@ mod unit_tests {
         let module = wabt::wat2wasm(contract).expect("Invalid wabt");
 
         // when
-        let output = post_process_module(&module, false, &Verbosity::Verbose, 16)
-            .expect("Invalid wasm module");
+        let output = post_process_module(
+            &module,
+            false,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        )
+        .expect("Invalid wasm module");
 
         // then
         assert_eq!(module, output);
     }
+
+    fn memory_import(module: &[u8]) -> Option<(String, wasmparser::MemoryType)> {
+        Parser::new(0).parse_all(module).find_map(|p| {
+            if let Payload::ImportSection(section) = p.unwrap() {
+                section.into_iter().find_map(|e| {
+                    let e = e.unwrap();
+                    if let TypeRef::Memory(mem) = e.ty {
+                        Some((format!("{}.{}", e.module, e.name), mem))
+                    } else {
+                        None
+                    }
+                })
+            } else {
+                None
+            }
+        })
+    }
+
+    #[test]
+    fn post_process_wasm_defined_memory_is_imported() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func))
+                (func (;0;) (type 0))
+                (memory (;0;) 2)
+                (export "memory" (memory 0))
+                (export "call" (func 0))
+                (export "deploy" (func 0))
+                (data (;0;) (i32.const 65536) "test")
+            )"#;
+        let module = wabt::wat2wasm(contract).expect("Invalid wabt");
+
+        // when
+        let output = post_process_module(
+            &module,
+            true,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        )
+        .expect("Invalid wasm module");
+
+        // then
+        wasmparser::validate(&output).expect("Invalid wasm module");
+        let (name, memory) = memory_import(&output).expect("Memory import not found");
+        assert_eq!(name, "env.memory");
+        assert_eq!((memory.initial, memory.maximum), (2, Some(16)));
+        let has_memory_section = Parser::new(0)
+            .parse_all(&output)
+            .any(|p| matches!(p.unwrap(), Payload::MemorySection(_)));
+        assert!(!has_memory_section);
+    }
+
+    #[test]
+    fn post_process_wasm_configured_memory_pages() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func (param i32 i32 i32)))
+                (import "seal" "foo" (func (;0;) (type 0)))
+                (import "env" "memory" (memory (;0;) 2))
+                (func (;1;) (type 0))
+            )"#;
+        let module = wabt::wat2wasm(contract).expect("Invalid wabt");
+        let settings = WasmSettings {
+            initial_memory_pages: Some(4),
+            maximum_memory_pages: Some(8),
+            ..Default::default()
+        };
+
+        // when
+        let output =
+            post_process_module(&module, true, &Verbosity::Verbose, 16, &settings)
+                .expect("Invalid wasm module");
+
+        // then
+        let (_, memory) = memory_import(&output).expect("Memory import not found");
+        assert_eq!((memory.initial, memory.maximum), (4, Some(8)));
+    }
+
+    #[test]
+    fn post_process_wasm_initial_pages_exceed_maximum() {
+        // given
+        let contract = r#"
+            (module
+                (import "env" "memory" (memory (;0;) 2 8))
+            )"#;
+        let module = wabt::wat2wasm(contract).expect("Invalid wabt");
+        let settings = WasmSettings {
+            initial_memory_pages: Some(10),
+            ..Default::default()
+        };
+
+        // when
+        let res = post_process_module(&module, true, &Verbosity::Verbose, 16, &settings);
+
+        // then
+        assert_eq!(
+            res.err().unwrap().to_string(),
+            "The wasm module requires 10 initial pages, which exceeds its maximum of 8 \
+             pages"
+        );
+    }
+
+    #[test]
+    fn post_process_wasm_strip_start_section() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func (;0;) (type 0))
+                (export "call" (func 0))
+                (export "deploy" (func 0))
+                (start 0)
+            )"#;
+        let module = wabt::wat2wasm(contract).expect("Invalid wabt");
+
+        // when
+        let output = post_process_module(
+            &module,
+            true,
+            &Verbosity::Verbose,
+            16,
+            &Default::default(),
+        )
+        .expect("Invalid wasm module");
+
+        // then
+        let has_start_section = Parser::new(0)
+            .parse_all(&output)
+            .any(|p| matches!(p.unwrap(), Payload::StartSection { .. }));
+        assert!(!has_start_section);
+    }
+
+    #[test]
+    fn post_process_wasm_rename_exports() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func (;0;) (type 0))
+                (export "_call" (func 0))
+                (export "_deploy" (func 0))
+            )"#;
+        let module = wabt::wat2wasm(contract).expect("Invalid wabt");
+        let settings = WasmSettings {
+            rename_exports: [("_call", "call"), ("_deploy", "deploy")]
+                .into_iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        // when
+        let output =
+            post_process_module(&module, true, &Verbosity::Verbose, 16, &settings)
+                .expect("Invalid wasm module");
+
+        // then
+        let exports = Parser::new(0).parse_all(&output).find_map(|p| {
+            if let Payload::ExportSection(section) = p.unwrap() {
+                Some(
+                    section
+                        .into_iter()
+                        .map(|e| e.unwrap().name.to_string())
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                None
+            }
+        });
+        assert_eq!(exports, Some(vec!["call".to_string(), "deploy".to_string()]));
+    }
+
+    #[test]
+    fn embed_custom_section_replaces_existing_one() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func (;0;) (type 0))
+                (export "call" (func 0))
+                (export "deploy" (func 0))
+            )"#;
+        let module = wabt::wat2wasm(contract).expect("Invalid wabt");
+
+        // when
+        let embedded = embed_custom_section(&module, "hash", &[1; 32]).unwrap();
+        let output = embed_custom_section(&embedded, "hash", &[2; 32]).unwrap();
+
+        // then
+        let sections = Parser::new(0)
+            .parse_all(&output)
+            .filter_map(|p| {
+                match p.unwrap() {
+                    Payload::CustomSection(c) => Some((c.name(), c.data().to_vec())),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(sections, vec![("hash", vec![2; 32])]);
+        assert!(wasmparser::validate(&output).is_ok());
+    }
 }
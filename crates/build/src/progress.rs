@@ -0,0 +1,196 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Progress of long running operations.
+//!
+//! The library crates report the progress of long running operations to a
+//! [`ProgressReporter`], which allows applications embedding them to render the
+//! progress in their own way.
+
+use std::{
+    fmt,
+    sync::Arc,
+};
+
+/// A long running operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, strum::Display)]
+#[serde(rename_all = "kebab-case")]
+pub enum Operation {
+    /// Compiling the contract with `cargo`.
+    #[strum(to_string = "Compiling")]
+    Compile,
+    /// Optimizing the contract code with `wasm-opt`.
+    #[strum(to_string = "Optimizing")]
+    Optimize,
+    /// Fetching the addresses of all contracts from the storage of a chain.
+    #[strum(to_string = "Fetching contracts")]
+    FetchContracts,
+    /// Waiting for a submitted extrinsic to be included in a block, or finalized.
+    #[strum(to_string = "Waiting for extrinsic")]
+    WaitForExtrinsic,
+}
+
+/// The progress of an [`Operation`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum ProgressEvent {
+    /// The operation started.
+    Started { operation: Operation },
+    /// The operation completed `done` out of `total` units of work, if the total is
+    /// known.
+    Advanced {
+        operation: Operation,
+        done: u64,
+        total: Option<u64>,
+    },
+    /// The status of the operation changed, e.g. a submitted extrinsic was included in
+    /// a block.
+    Status {
+        operation: Operation,
+        status: String,
+        details: String,
+    },
+    /// The operation finished, whether successfully or not.
+    Finished { operation: Operation },
+}
+
+/// A callback receiving [`ProgressEvent`]s.
+type Callback = dyn Fn(&ProgressEvent) + Send + Sync;
+
+/// Receives the [`ProgressEvent`]s of long running operations.
+///
+/// The default reporter discards all events. Clones of a reporter share the callback.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    callback: Option<Arc<Callback>>,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter passing every event to `callback`.
+    pub fn new(callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Some(Arc::new(callback)),
+        }
+    }
+
+    /// Reports the `event`.
+    pub fn report(&self, event: ProgressEvent) {
+        if let Some(callback) = &self.callback {
+            callback(&event)
+        }
+    }
+
+    /// Reports that the `operation` started.
+    pub fn started(&self, operation: Operation) {
+        self.report(ProgressEvent::Started { operation })
+    }
+
+    /// Reports that the `operation` completed `done` out of `total` units of work.
+    pub fn advanced(&self, operation: Operation, done: u64, total: Option<u64>) {
+        self.report(ProgressEvent::Advanced {
+            operation,
+            done,
+            total,
+        })
+    }
+
+    /// Reports a change of the `status` of the `operation`.
+    pub fn status(
+        &self,
+        operation: Operation,
+        status: impl Into<String>,
+        details: impl Into<String>,
+    ) {
+        self.report(ProgressEvent::Status {
+            operation,
+            status: status.into(),
+            details: details.into(),
+        })
+    }
+
+    /// Reports that the `operation` finished.
+    pub fn finished(&self, operation: Operation) {
+        self.report(ProgressEvent::Finished { operation })
+    }
+
+    /// Runs `f`, reporting it as the `operation`.
+    pub fn run<T>(&self, operation: Operation, f: impl FnOnce() -> T) -> T {
+        self.started(operation);
+        let result = f();
+        self.finished(operation);
+        result
+    }
+}
+
+impl fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("enabled", &self.callback.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn run_reports_start_and_finish() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let reporter = ProgressReporter::new({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event.clone())
+        });
+
+        let result = reporter.clone().run(Operation::Optimize, || {
+            reporter.advanced(Operation::Optimize, 1, Some(2));
+            42
+        });
+
+        assert_eq!(result, 42);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ProgressEvent::Started {
+                    operation: Operation::Optimize
+                },
+                ProgressEvent::Advanced {
+                    operation: Operation::Optimize,
+                    done: 1,
+                    total: Some(2)
+                },
+                ProgressEvent::Finished {
+                    operation: Operation::Optimize
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn events_serialize_with_tag() {
+        let event = ProgressEvent::Advanced {
+            operation: Operation::FetchContracts,
+            done: 3,
+            total: None,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"advanced","operation":"fetch-contracts","done":3,"total":null}"#
+        );
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generation of a [CycloneDX](https://cyclonedx.org) software bill of materials (SBOM)
+//! of the contract crate and its dependency tree.
+
+use crate::{
+    code_hash,
+    CrateMetadata,
+    VERSION,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use cargo_metadata::Package;
+use std::{
+    collections::{
+        BTreeSet,
+        HashMap,
+    },
+    fs,
+    path::PathBuf,
+};
+
+/// The version of the CycloneDX specification the SBOM conforms to.
+pub const CYCLONEDX_SPEC_VERSION: &str = "1.5";
+
+/// The SBOM generated alongside the contract artifacts.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SbomArtifact {
+    /// Path to the CycloneDX JSON file.
+    pub path: PathBuf,
+    /// The hex encoded BLAKE2b-256 hash of the file, recorded as `sbom_hash` in the
+    /// `build_info` of the metadata.
+    pub hash: String,
+}
+
+impl SbomArtifact {
+    /// Returns the path of the SBOM of the contract.
+    pub fn path(crate_metadata: &CrateMetadata) -> PathBuf {
+        crate_metadata.dest_code.with_extension("cdx.json")
+    }
+
+    /// Generates the SBOM of the contract and writes it next to the contract code.
+    pub fn generate(crate_metadata: &CrateMetadata) -> Result<Self> {
+        let path = Self::path(crate_metadata);
+        let lock_file = crate_metadata.cargo_meta.workspace_root.join("Cargo.lock");
+        let checksums = match fs::read_to_string(&lock_file) {
+            Ok(cargo_lock) => lock_checksums(&cargo_lock)?,
+            Err(_) => HashMap::new(),
+        };
+        let bom = Bom::new(crate_metadata, &checksums)?;
+        let json = serde_json::to_vec_pretty(&bom)?;
+        fs::write(&path, &json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self {
+            path,
+            hash: format!("0x{}", hex::encode(code_hash(&json))),
+        })
+    }
+
+    /// Loads the SBOM written by a previous build, if it exists.
+    pub fn load(crate_metadata: &CrateMetadata) -> Result<Option<Self>> {
+        let path = Self::path(crate_metadata);
+        if !path.exists() {
+            return Ok(None)
+        }
+        let json = fs::read(&path)?;
+        Ok(Some(Self {
+            path,
+            hash: format!("0x{}", hex::encode(code_hash(&json))),
+        }))
+    }
+}
+
+/// A CycloneDX bill of materials.
+///
+/// It has no timestamp and no serial number, so that reproducible builds produce the
+/// same SBOM.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: BomMetadata,
+    components: Vec<Component>,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BomMetadata {
+    tools: Vec<Tool>,
+    component: Component,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Tool {
+    vendor: &'static str,
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Component {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    purl: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<License>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Hash {
+    alg: &'static str,
+    content: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct License {
+    expression: String,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Dependency {
+    #[serde(rename = "ref")]
+    reference: String,
+    depends_on: Vec<String>,
+}
+
+impl Bom {
+    /// Creates the bill of materials of the contract crate and the dependencies it is
+    /// built with, see [`CrateMetadata::dependency_graph`].
+    fn new(
+        crate_metadata: &CrateMetadata,
+        checksums: &HashMap<(String, String), String>,
+    ) -> Result<Self> {
+        let graph = crate_metadata.dependency_graph()?;
+        let root = &crate_metadata.root_package.id;
+        let components = graph
+            .iter()
+            .filter(|(package, _)| &package.id != root)
+            .map(|(package, _)| component(package, "library", checksums))
+            .collect();
+        let dependencies = graph
+            .iter()
+            .map(|(package, depends_on)| {
+                Dependency {
+                    reference: purl(package),
+                    depends_on: depends_on
+                        .iter()
+                        .map(|dep| purl(dep))
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect(),
+                }
+            })
+            .collect();
+        Ok(Self {
+            bom_format: "CycloneDX",
+            spec_version: CYCLONEDX_SPEC_VERSION,
+            version: 1,
+            metadata: BomMetadata {
+                tools: vec![Tool {
+                    vendor: "Use Ink",
+                    name: "cargo-contract",
+                    version: VERSION,
+                }],
+                component: component(
+                    &crate_metadata.root_package,
+                    "application",
+                    checksums,
+                ),
+            },
+            components,
+            dependencies,
+        })
+    }
+}
+
+/// Returns the component of a package, with its checksum from the `Cargo.lock` if it
+/// was downloaded from a registry.
+fn component(
+    package: &Package,
+    ty: &'static str,
+    checksums: &HashMap<(String, String), String>,
+) -> Component {
+    let version = package.version.to_string();
+    let hashes = checksums
+        .get(&(package.name.clone(), version.clone()))
+        .map(|checksum| {
+            Hash {
+                alg: "SHA-256",
+                content: checksum.clone(),
+            }
+        })
+        .into_iter()
+        .collect();
+    let licenses = package
+        .license
+        .iter()
+        .map(|expression| {
+            License {
+                expression: expression.clone(),
+            }
+        })
+        .collect();
+    Component {
+        ty,
+        bom_ref: purl(package),
+        name: package.name.clone(),
+        version,
+        description: package.description.clone(),
+        purl: purl(package),
+        hashes,
+        licenses,
+    }
+}
+
+/// Returns the package URL of a crate, e.g. `pkg:cargo/ink@5.0.0`.
+fn purl(package: &Package) -> String {
+    format!("pkg:cargo/{}@{}", package.name, package.version)
+}
+
+/// Returns the checksums of the packages in a `Cargo.lock` by their name and version.
+fn lock_checksums(cargo_lock: &str) -> Result<HashMap<(String, String), String>> {
+    #[derive(serde::Deserialize)]
+    struct CargoLock {
+        #[serde(default)]
+        package: Vec<LockedPackage>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct LockedPackage {
+        name: String,
+        version: String,
+        checksum: Option<String>,
+    }
+
+    let cargo_lock: CargoLock =
+        toml::from_str(cargo_lock).context("Failed to parse the Cargo.lock")?;
+    Ok(cargo_lock
+        .package
+        .into_iter()
+        .filter_map(|package| {
+            let checksum = package.checksum?;
+            Some(((package.name, package.version), checksum))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_checksums_of_registry_packages() {
+        let cargo_lock = r#"
+            version = 3
+
+            [[package]]
+            name = "flipper"
+            version = "5.0.0"
+
+            [[package]]
+            name = "ink"
+            version = "5.0.0"
+            source = "registry+https://github.com/rust-lang/crates.io-index"
+            checksum = "abcd"
+            dependencies = ["ink_env"]
+        "#;
+
+        let checksums = lock_checksums(cargo_lock).unwrap();
+
+        assert_eq!(checksums.len(), 1);
+        assert_eq!(
+            checksums.get(&("ink".to_string(), "5.0.0".to_string())),
+            Some(&"abcd".to_string())
+        );
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Running the off-chain unit tests of a contract, optionally collecting their
+//! source-based code coverage.
+
+use crate::{
+    util,
+    verbose_eprintln,
+    CrateMetadata,
+    Features,
+    ManifestPath,
+    Target,
+    Verbosity,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use colored::Colorize;
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// The format of a coverage report.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, clap::ValueEnum)]
+pub enum CoverageFormat {
+    /// An `lcov.info` file, as consumed by most coverage services and IDEs.
+    #[clap(name = "lcov")]
+    Lcov,
+    /// An HTML report browsable in the `html` directory.
+    #[clap(name = "html")]
+    Html,
+}
+
+/// Arguments to use when executing the `test` command.
+#[derive(Clone, Default)]
+pub struct TestArgs {
+    /// The location of the Cargo manifest (`Cargo.toml`) file to use.
+    pub manifest_path: ManifestPath,
+    pub verbosity: Verbosity,
+    pub features: Features,
+    /// The formats of the coverage reports to generate, coverage is not collected if
+    /// empty.
+    pub coverage: Vec<CoverageFormat>,
+    /// Arguments passed through to the test binaries, e.g. a filter for the test names.
+    pub test_args: Vec<String>,
+}
+
+/// Result of the `test` command.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct TestResult {
+    /// Path to the `lcov.info` coverage report, if generated.
+    pub lcov: Option<PathBuf>,
+    /// Path to the directory of the HTML coverage report, if generated.
+    pub html: Option<PathBuf>,
+}
+
+/// Runs the off-chain unit tests of the contract with `cargo test`.
+///
+/// If coverage is requested the tests are instrumented with `-C instrument-coverage`
+/// and the reports, scoped to the sources of the contract crate, are written to
+/// `target/ink/coverage`. Generating them requires the `llvm-tools-preview` component
+/// of the toolchain.
+pub fn execute(args: TestArgs) -> Result<TestResult> {
+    let crate_metadata = CrateMetadata::collect(&args.manifest_path, Target::Wasm)?;
+    let mut cargo_args = vec![format!("--package={}", crate_metadata.root_package.name)];
+    if args.features.no_default_features() {
+        cargo_args.push("--no-default-features".to_owned());
+    }
+    args.features.append_to_args(&mut cargo_args);
+
+    if args.coverage.is_empty() {
+        cargo_args.push("--".to_owned());
+        cargo_args.extend(args.test_args);
+        let cmd = util::cargo_cmd(
+            "test",
+            cargo_args,
+            args.manifest_path.directory(),
+            args.verbosity,
+            vec![],
+        );
+        util::cargo_tty_output(cmd)
+            .run()
+            .context("The contract tests failed")?;
+        return Ok(TestResult::default())
+    }
+
+    let tools_dir = llvm_tools_dir()?;
+    let coverage_dir = crate_metadata.target_directory.join("coverage");
+    let profraw_dir = coverage_dir.join("profraw");
+    if profraw_dir.exists() {
+        fs::remove_dir_all(&profraw_dir)?;
+    }
+    fs::create_dir_all(&profraw_dir)?;
+
+    // The instrumented build uses its own target directory, so that it doesn't
+    // invalidate the artifacts of regular builds. Build scripts and procedural macros
+    // are instrumented as well, their profiles are not part of the reports.
+    let instrumented_target_dir = coverage_dir.join("target");
+    let profile_file = |dir: &Path| {
+        (
+            "LLVM_PROFILE_FILE",
+            Some(dir.join("%p-%m.profraw").to_string_lossy().into_owned()),
+        )
+    };
+    let env = vec![
+        (
+            "CARGO_ENCODED_RUSTFLAGS",
+            Some("-Cinstrument-coverage".to_owned()),
+        ),
+        (
+            "CARGO_TARGET_DIR",
+            Some(instrumented_target_dir.to_string_lossy().into_owned()),
+        ),
+    ];
+
+    verbose_eprintln!(
+        args.verbosity,
+        " {} {}",
+        "[==]".bold(),
+        "Building the instrumented tests".bright_green().bold()
+    );
+    let mut build_args = cargo_args.clone();
+    build_args.extend(["--no-run".to_owned(), "--message-format=json".to_owned()]);
+    let mut build_env = env.clone();
+    build_env.push(profile_file(&instrumented_target_dir.join("profraw")));
+    let output = util::cargo_cmd(
+        "test",
+        build_args,
+        args.manifest_path.directory(),
+        args.verbosity,
+        build_env,
+    )
+    .stdout_capture()
+    .run()
+    .context("Building the contract tests failed")?;
+    let binaries = test_binaries(
+        &String::from_utf8_lossy(&output.stdout),
+        &crate_metadata.root_package.id.repr,
+    );
+    if binaries.is_empty() {
+        anyhow::bail!("No test binaries found for the contract");
+    }
+
+    verbose_eprintln!(
+        args.verbosity,
+        " {} {}",
+        "[==]".bold(),
+        "Running the instrumented tests".bright_green().bold()
+    );
+    cargo_args.push("--".to_owned());
+    cargo_args.extend(args.test_args);
+    let mut run_env = env;
+    run_env.push(profile_file(&profraw_dir));
+    let cmd = util::cargo_cmd(
+        "test",
+        cargo_args,
+        args.manifest_path.directory(),
+        args.verbosity,
+        run_env,
+    );
+    util::cargo_tty_output(cmd)
+        .run()
+        .context("The contract tests failed")?;
+
+    verbose_eprintln!(
+        args.verbosity,
+        " {} {}",
+        "[==]".bold(),
+        "Generating the coverage reports".bright_green().bold()
+    );
+    let profdata = coverage_dir.join("coverage.profdata");
+    let profraw_files = fs::read_dir(&profraw_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    duct::cmd(
+        tools_dir.join("llvm-profdata"),
+        [
+            "merge".into(),
+            "-sparse".into(),
+            "-o".into(),
+            profdata.clone(),
+        ]
+        .into_iter()
+        .chain(profraw_files),
+    )
+    .run()
+    .context("Merging the coverage profiles failed")?;
+
+    let package_dir = crate_metadata
+        .manifest_path
+        .absolute_directory()?
+        .to_string_lossy()
+        .into_owned();
+    let llvm_cov_args = |subcommand: &str| {
+        let mut llvm_cov_args = vec![
+            subcommand.to_owned(),
+            format!("-instr-profile={}", profdata.display()),
+            format!(
+                "-ignore-filename-regex={}",
+                regex::escape(&coverage_dir.to_string_lossy())
+            ),
+        ];
+        for binary in &binaries {
+            llvm_cov_args.push(format!("-object={}", binary.display()));
+        }
+        llvm_cov_args
+    };
+
+    let mut result = TestResult::default();
+    for format in args.coverage {
+        match format {
+            CoverageFormat::Lcov => {
+                let lcov = coverage_dir.join("lcov.info");
+                let mut lcov_args = llvm_cov_args("export");
+                lcov_args.extend(["-format=lcov".to_owned(), package_dir.clone()]);
+                duct::cmd(tools_dir.join("llvm-cov"), lcov_args)
+                    .stdout_path(&lcov)
+                    .run()
+                    .context("Exporting the lcov coverage report failed")?;
+                result.lcov = Some(lcov);
+            }
+            CoverageFormat::Html => {
+                let html = coverage_dir.join("html");
+                let mut html_args = llvm_cov_args("show");
+                html_args.extend([
+                    "-format=html".to_owned(),
+                    format!("-output-dir={}", html.display()),
+                    package_dir.clone(),
+                ]);
+                duct::cmd(tools_dir.join("llvm-cov"), html_args)
+                    .run()
+                    .context("Generating the HTML coverage report failed")?;
+                result.html = Some(html);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the directory of the `llvm-profdata` and `llvm-cov` binaries shipped with
+/// the `llvm-tools-preview` component of the active toolchain.
+fn llvm_tools_dir() -> Result<PathBuf> {
+    let sysroot = duct::cmd("rustc", ["--print", "sysroot"])
+        .read()
+        .context("Cannot determine the sysroot of the toolchain")?;
+    let host = rustc_version::version_meta()?.host;
+    let tools_dir = Path::new(sysroot.trim())
+        .join("lib")
+        .join("rustlib")
+        .join(host)
+        .join("bin");
+    if !tools_dir.join("llvm-cov").exists() {
+        anyhow::bail!(
+            "llvm-cov not found, install it with `rustup component add llvm-tools-preview`"
+        );
+    }
+    Ok(tools_dir)
+}
+
+/// Extracts the paths of the test binaries of the package with `package_id` from the
+/// output of `cargo test --no-run --message-format=json`.
+fn test_binaries(output: &str, package_id: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|msg| {
+            msg["reason"] == "compiler-artifact"
+                && msg["package_id"] == package_id
+                && msg["profile"]["test"] == true
+        })
+        .filter_map(|msg| msg["executable"].as_str().map(PathBuf::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_test_binaries_of_the_package() {
+        let output = r#"{"reason":"compiler-artifact","package_id":"dep 1.0.0","profile":{"test":false},"executable":null}
+{"reason":"compiler-artifact","package_id":"flipper 0.1.0","profile":{"test":false},"executable":null}
+{"reason":"compiler-artifact","package_id":"flipper 0.1.0","profile":{"test":true},"executable":"/target/debug/deps/flipper-1234"}
+{"reason":"compiler-artifact","package_id":"other 0.1.0","profile":{"test":true},"executable":"/target/debug/deps/other-5678"}
+{"reason":"build-finished","success":true}"#;
+
+        let binaries = test_binaries(output, "flipper 0.1.0");
+
+        assert_eq!(
+            binaries,
+            vec![PathBuf::from("/target/debug/deps/flipper-1234")]
+        );
+    }
+}
@@ -78,12 +78,18 @@ build_tests!(
     keep_debug_symbols_in_debug_mode,
     keep_debug_symbols_in_release_mode,
     build_with_json_output_works,
+    exceeding_max_code_size_must_fail,
+    building_into_artifacts_dir_must_work,
+    building_workspace_must_build_all_contracts,
     building_contract_with_source_file_in_subfolder_must_work,
     building_contract_with_build_rs_must_work,
     missing_linting_toolchain_installation_must_be_detected,
     generates_metadata,
     unchanged_contract_skips_optimization_and_metadata_steps,
-    unchanged_contract_no_metadata_artifacts_generates_metadata
+    unchanged_contract_no_metadata_artifacts_generates_metadata,
+    unchanged_contract_with_skip_build_if_unchanged_skips_build,
+    verifiable_build_must_not_keep_stale_fingerprint,
+    embedding_metadata_hash_is_opt_in
 );
 
 fn build_code_only(manifest_path: &ManifestPath) -> Result<()> {
@@ -381,6 +387,109 @@ fn build_with_json_output_works(manifest_path: &ManifestPath) -> Result<()> {
     Ok(())
 }
 
+fn exceeding_max_code_size_must_fail(manifest_path: &ManifestPath) -> Result<()> {
+    // given
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        build_artifact: BuildArtifacts::CodeOnly,
+        max_code_size: Some(1),
+        extra_lints: false,
+        ..Default::default()
+    };
+
+    // when
+    let res = super::execute(args);
+
+    // then
+    let Err(err) = res else {
+        panic!("build must fail")
+    };
+    assert!(
+        err.to_string()
+            .contains("exceeds the maximum code size of 1 bytes"),
+        "unexpected error: {err}"
+    );
+    Ok(())
+}
+
+fn building_workspace_must_build_all_contracts(
+    manifest_path: &ManifestPath,
+) -> Result<()> {
+    // given
+    let workspace_dir = manifest_path
+        .directory()
+        .expect("dir must exist")
+        .join("workspace");
+    fs::create_dir_all(&workspace_dir)?;
+    fs::write(
+        workspace_dir.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"contract_a\", \"contract_b\"]\nresolver = \"2\"\n",
+    )?;
+    crate::new_contract_project("contract_a", Some(&workspace_dir))?;
+    crate::new_contract_project("contract_b", Some(&workspace_dir))?;
+    let args = ExecuteArgs {
+        manifest_path: ManifestPath::new(workspace_dir.join("Cargo.toml"))?,
+        build_artifact: BuildArtifacts::CodeOnly,
+        ..Default::default()
+    };
+
+    // when
+    let results = super::execute_workspace(args).expect("workspace build failed");
+
+    // then
+    let mut contracts = results
+        .iter()
+        .map(|res| {
+            assert!(res.optimization_result.is_some(), "code must be optimized");
+            let dest_wasm = res.dest_wasm.as_ref().expect("code must be built");
+            assert!(dest_wasm.exists());
+            dest_wasm
+                .file_stem()
+                .expect("file must have a name")
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    contracts.sort();
+    assert_eq!(contracts, ["contract_a", "contract_b"]);
+
+    Ok(())
+}
+
+fn building_into_artifacts_dir_must_work(manifest_path: &ManifestPath) -> Result<()> {
+    // given
+    let artifacts_dir = tempfile::tempdir()?;
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        build_artifact: BuildArtifacts::CodeOnly,
+        artifacts_dir: Some(artifacts_dir.path().to_path_buf()),
+        extra_lints: false,
+        ..Default::default()
+    };
+
+    // when
+    let res = super::execute(args).expect("build failed");
+
+    // then
+    assert_eq!(res.target_directory, artifacts_dir.path());
+    assert!(res.dest_wasm.unwrap().starts_with(artifacts_dir.path()));
+    let crate_metadata = CrateMetadata::collect(manifest_path, Target::Wasm)?;
+    assert_eq!(crate_metadata.artifact_directory, artifacts_dir.path());
+    assert!(crate_metadata.dest_code.exists());
+
+    // a build without `artifacts_dir` writes to the target directory again
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        build_artifact: BuildArtifacts::CodeOnly,
+        extra_lints: false,
+        ..Default::default()
+    };
+    super::execute(args).expect("build failed");
+    let crate_metadata = CrateMetadata::collect(manifest_path, Target::Wasm)?;
+    assert_eq!(crate_metadata.artifact_directory, crate_metadata.target_directory);
+    Ok(())
+}
+
 #[cfg(unix)]
 fn missing_linting_toolchain_installation_must_be_detected(
     manifest_path: &ManifestPath,
@@ -629,6 +738,118 @@ fn unchanged_contract_no_metadata_artifacts_generates_metadata(
     Ok(())
 }
 
+fn unchanged_contract_with_skip_build_if_unchanged_skips_build(
+    manifest_path: &ManifestPath,
+) -> Result<()> {
+    // given
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        skip_build_if_unchanged: true,
+        ..Default::default()
+    };
+    let res1 = super::execute(args.clone()).expect("build failed");
+    let bundle_modified_pre =
+        file_last_modified(&res1.metadata_result.as_ref().unwrap().dest_bundle);
+
+    // when
+    let res2 = super::execute(args.clone()).expect("build failed");
+
+    // then
+    assert!(res2.optimization_result.is_none());
+    assert_eq!(
+        bundle_modified_pre,
+        file_last_modified(&res2.metadata_result.as_ref().unwrap().dest_bundle),
+        "Unchanged contract must not be rebuilt"
+    );
+
+    // when
+    let project_dir = manifest_path.directory().expect("dir must exist");
+    fs::write(
+        project_dir.join("README.md"),
+        "# A change to the documentation",
+    )?;
+    let crate_metadata = CrateMetadata::collect(manifest_path, Target::Wasm)?;
+
+    // then
+    assert_eq!(
+        crate::fingerprint::sources_status(&crate_metadata)?,
+        Some(crate::fingerprint::SourcesStatus::UpToDate),
+        "Files which are not compiled must not be fingerprinted"
+    );
+
+    // when
+    let lib_path = manifest_path
+        .directory()
+        .expect("dir must exist")
+        .join("lib.rs");
+    let mut lib = fs::read_to_string(&lib_path)?;
+    lib.push_str("\n// a change to the contract sources\n");
+    fs::write(&lib_path, lib)?;
+    let crate_metadata = CrateMetadata::collect(manifest_path, Target::Wasm)?;
+
+    // then
+    assert_eq!(
+        crate::fingerprint::sources_status(&crate_metadata)?,
+        Some(crate::fingerprint::SourcesStatus::SourcesChanged),
+        "Changed sources must be detected"
+    );
+    let res3 = super::execute(args).expect("build failed");
+    assert_eq!(
+        crate::fingerprint::sources_status(&crate_metadata)?,
+        Some(crate::fingerprint::SourcesStatus::UpToDate),
+        "Fingerprint must be updated after a build"
+    );
+
+    // when
+    let mut fingerprint = crate::fingerprint::BuildFingerprint::load(&crate_metadata)?
+        .expect("fingerprint must exist");
+    fingerprint.version = "0.0.0".to_string();
+    fingerprint.store(&crate_metadata)?;
+
+    // then
+    assert_eq!(
+        crate::fingerprint::sources_status(&crate_metadata)?,
+        Some(crate::fingerprint::SourcesStatus::ToolsChanged {
+            toolchain: None,
+            version: Some("0.0.0".to_string()),
+        }),
+        "Another cargo-contract version must not be reported as changed sources"
+    );
+    assert!(res3.metadata_result.unwrap().dest_bundle.exists());
+
+    Ok(())
+}
+
+fn verifiable_build_must_not_keep_stale_fingerprint(
+    manifest_path: &ManifestPath,
+) -> Result<()> {
+    // given
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        skip_build_if_unchanged: true,
+        ..Default::default()
+    };
+    super::execute(args.clone()).expect("build failed");
+    let crate_metadata = CrateMetadata::collect(manifest_path, Target::Wasm)?;
+    assert!(crate::fingerprint::BuildFingerprint::load(&crate_metadata)?.is_some());
+
+    // when
+    // without a docker engine the build fails, the fingerprint must be gone either way
+    let _ = super::execute(ExecuteArgs {
+        build_mode: BuildMode::Verifiable,
+        ..args
+    });
+
+    // then
+    assert_eq!(
+        crate::fingerprint::sources_status(&crate_metadata)?,
+        None,
+        "A verifiable build must not leave a fingerprint of a local build behind"
+    );
+
+    Ok(())
+}
+
 /// Get the last modified date of the given file.
 /// Panics if the file does not exist.
 fn file_last_modified(path: &Path) -> SystemTime {
@@ -655,6 +876,38 @@ fn build_byte_str(bytes: &[u8]) -> String {
     str
 }
 
+fn embedding_metadata_hash_is_opt_in(manifest_path: &ManifestPath) -> Result<()> {
+    let has_metadata_hash = |res: &BuildResult| {
+        let module = crate::load_module(res.dest_wasm.as_ref().unwrap()).unwrap();
+        contract_analyze::metadata_hash(&module).unwrap().is_some()
+    };
+
+    let res = super::execute(ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        ..Default::default()
+    })
+    .expect("build failed");
+    assert!(!has_metadata_hash(&res));
+
+    let res = super::execute(ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        embed_metadata_hash: true,
+        ..Default::default()
+    })
+    .expect("build failed");
+    assert!(has_metadata_hash(&res));
+
+    let code_only = super::execute(ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        build_artifact: BuildArtifacts::CodeOnly,
+        embed_metadata_hash: true,
+        ..Default::default()
+    });
+    assert!(code_only.is_err());
+
+    Ok(())
+}
+
 fn has_debug_symbols<P: AsRef<Path>>(p: P) -> bool {
     let module = crate::load_module(p).unwrap();
     let has_debug_symbols = Parser::new(0).parse_all(&module).any(|e| {
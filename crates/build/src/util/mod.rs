@@ -124,11 +124,14 @@ pub fn decode_hex(input: &str) -> Result<Vec<u8>, hex::FromHexError> {
 
 /// Prints to stderr if `verbosity.is_verbose()` is `true`.
 /// Like `cargo`, we use stderr for verbose output.
+///
+/// The message is printed as a [diagnostic](crate::output::diagnostic), so it respects
+/// the configured [`LogFormat`](crate::output::LogFormat).
 #[macro_export]
 macro_rules! verbose_eprintln {
     ($verbosity:expr, $($msg:tt)*) => {
         if $verbosity.is_verbose() {
-            ::std::eprintln!($($msg)*);
+            $crate::output::diagnostic(::std::format_args!($($msg)*));
         }
     };
 }
@@ -136,15 +139,15 @@ macro_rules! verbose_eprintln {
 pub const DEFAULT_KEY_COL_WIDTH: usize = 12;
 
 /// Pretty print name value, name right aligned with colour.
+///
+/// The value is rendered by the [`Renderer`](crate::output::Renderer) of the output.
 #[macro_export]
 macro_rules! name_value_println {
     ($name:tt, $value:expr, $width:expr) => {{
-        use colored::Colorize as _;
-        ::std::println!(
-            "{:>width$} {}",
-            $name.bright_purple().bold(),
-            $value,
-            width = $width,
+        $crate::output::renderer().name_value(
+            &::std::string::ToString::to_string(&$name),
+            &::std::string::ToString::to_string(&$value),
+            $width,
         );
     }};
     ($name:tt, $value:expr) => {
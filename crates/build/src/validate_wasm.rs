@@ -14,6 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::{
+    verbose_eprintln,
+    Verbosity,
+};
 use anyhow::Result;
 use colored::Colorize;
 use impl_serde::serialize as serde_hex;
@@ -134,6 +138,37 @@ pub fn validate_import_section(module: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Warns about imported host functions which are not known to be provided by
+/// `pallet-contracts`, uploading the contract to a chain without them fails.
+///
+/// All imported host functions are listed in verbose mode.
+pub fn check_host_functions(module: &[u8], verbosity: &Verbosity) -> Result<()> {
+    let host_functions = contract_analyze::host_functions(module)?;
+    verbose_eprintln!(
+        verbosity,
+        " {} {}",
+        "Host functions:".bright_green().bold(),
+        host_functions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let unknown = host_functions
+        .iter()
+        .filter(|function| !function.is_known())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !unknown.is_empty() {
+        crate::output::warning(format!(
+            "The contract imports host functions which are not known to be provided by \
+            pallet-contracts: {unknown}. Uploading it fails on chains which don't support them."
+        ));
+    }
+    Ok(())
+}
+
 /// Returns `Ok` if the import is allowed.
 fn check_import(module: &str, field: &str) -> Result<(), String> {
     if module.starts_with("seal") || field.starts_with("memory") {
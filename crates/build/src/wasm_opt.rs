@@ -27,10 +27,153 @@ use std::{
     str,
 };
 
+/// All passes of `wasm-opt` which can be configured by their name.
+const PASSES: &[Pass] = &[
+    Pass::AlignmentLowering,
+    Pass::Asyncify,
+    Pass::AvoidReinterprets,
+    Pass::Dae,
+    Pass::DaeOptimizing,
+    Pass::AbstractTypeRefining,
+    Pass::CoalesceLocals,
+    Pass::CoalesceLocalsLearning,
+    Pass::CodePushing,
+    Pass::CodeFolding,
+    Pass::ConstHoisting,
+    Pass::Cfp,
+    Pass::Dce,
+    Pass::Dealign,
+    Pass::DeNan,
+    Pass::Directize,
+    Pass::DiscardGlobalEffects,
+    Pass::Dfo,
+    Pass::DwarfDump,
+    Pass::DuplicateImportElimination,
+    Pass::DuplicateFunctionElimination,
+    Pass::EmitTargetFeatures,
+    Pass::ExtractFunction,
+    Pass::ExtractFunctionIndex,
+    Pass::Flatten,
+    Pass::FpCastEmu,
+    Pass::FuncMetrics,
+    Pass::GenerateDyncalls,
+    Pass::GenerateI64Dyncalls,
+    Pass::GenerateGlobalEffects,
+    Pass::GenerateStackIr,
+    Pass::GlobalRefining,
+    Pass::Gto,
+    Pass::Gsi,
+    Pass::Gufa,
+    Pass::GufaCastAll,
+    Pass::GufaOptimizing,
+    Pass::TypeRefining,
+    Pass::Heap2Local,
+    Pass::InlineMain,
+    Pass::Inlining,
+    Pass::InliningOptimizing,
+    Pass::IntrinsicLowering,
+    Pass::Jspi,
+    Pass::LegalizeJsInterface,
+    Pass::LegalizeJsInterfaceMinimally,
+    Pass::LocalCse,
+    Pass::LocalSubtyping,
+    Pass::LogExecution,
+    Pass::I64ToI32Lowering,
+    Pass::InstrumentLocals,
+    Pass::InstrumentMemory,
+    Pass::Licm,
+    Pass::LimitSegments,
+    Pass::Memory64Lowering,
+    Pass::MemoryPacking,
+    Pass::MergeBlocks,
+    Pass::MergeSimilarFunctions,
+    Pass::MergeLocals,
+    Pass::Metrics,
+    Pass::MinifyImports,
+    Pass::MinifyImportsAndExports,
+    Pass::MinifyImportsAndExportsAndModules,
+    Pass::ModAsyncifyAlwaysAndOnlyUnwind,
+    Pass::ModAsyncifyNeverUnwind,
+    Pass::Monomorphize,
+    Pass::MonomorphizeAlways,
+    Pass::MultiMemoryLowering,
+    Pass::MultiMemoryLoweringWithBoundsChecks,
+    Pass::Nm,
+    Pass::NameTypes,
+    Pass::OnceReduction,
+    Pass::OptimizeAddedConstants,
+    Pass::OptimizeAddedConstantsPropagate,
+    Pass::OptimizeCasts,
+    Pass::OptimizeInstructions,
+    Pass::OptimizeStackIr,
+    Pass::PickLoadSigns,
+    Pass::Poppify,
+    Pass::PostEmscripten,
+    Pass::OptimizeForJs,
+    Pass::Precompute,
+    Pass::PrecomputePropagate,
+    Pass::Print,
+    Pass::PrintMinified,
+    Pass::PrintFeatures,
+    Pass::PrintFull,
+    Pass::PrintCallGraph,
+    Pass::PrintFunctionMap,
+    Pass::Symbolmap,
+    Pass::PrintStackIr,
+    Pass::RemoveNonJsOps,
+    Pass::RemoveImports,
+    Pass::RemoveMemory,
+    Pass::RemoveUnusedBrs,
+    Pass::RemoveUnusedModuleElements,
+    Pass::RemoveUnusedNonfunctionModuleElements,
+    Pass::RemoveUnusedNames,
+    Pass::RemoveUnusedTypes,
+    Pass::ReorderFunctionsByName,
+    Pass::ReorderFunctions,
+    Pass::ReorderGlobals,
+    Pass::RecorderLocals,
+    Pass::Rereloop,
+    Pass::Rse,
+    Pass::Roundtrip,
+    Pass::SafeHeap,
+    Pass::SetGlobals,
+    Pass::SignaturePruning,
+    Pass::SignatureRefining,
+    Pass::SignextLowering,
+    Pass::SimplifyGlobals,
+    Pass::SimplifyGlobalsOptimizing,
+    Pass::SimplifyLocals,
+    Pass::SimplifyLocalsNonesting,
+    Pass::SimplifyLocalsNotee,
+    Pass::SimplifyLocalsNostructure,
+    Pass::SimplifyLocalsNoteeNostructure,
+    Pass::Souperify,
+    Pass::SouperifySingleUse,
+    Pass::SpillPointers,
+    Pass::StubUnsupportedJs,
+    Pass::Ssa,
+    Pass::SsaNomerge,
+    Pass::Strip,
+    Pass::StackCheck,
+    Pass::StripDebug,
+    Pass::StripDwarf,
+    Pass::StripProducers,
+    Pass::StripEh,
+    Pass::StripTargetFeatuers,
+    Pass::TrapModeClamp,
+    Pass::TrapModeJs,
+    Pass::TypeMerging,
+    Pass::TypeSsa,
+    Pass::Untee,
+    Pass::Vacuum,
+];
+
 /// A helpful struct for interacting with Binaryen's `wasm-opt` tool.
 pub struct WasmOptHandler {
     /// The optimization level that should be used when optimizing the Wasm binary.
     optimization_level: OptimizationPasses,
+    /// The passes to run instead of the default passes of the optimization level.
+    passes: Vec<Pass>,
     /// Whether or not to keep debugging information in the final Wasm binary.
     keep_debug_symbols: bool,
 }
@@ -38,14 +181,26 @@ pub struct WasmOptHandler {
 impl WasmOptHandler {
     /// Generate a new instance of the handler.
     ///
-    /// Fails if the `wasm-opt` binary is not installed on the system, or if an outdated
-    /// `wasm-opt` binary is used (currently a version >= 99 is required).
+    /// If `passes` is not empty, only these passes are run, with the optimize and
+    /// shrink levels of `optimization_level`. Fails if a pass is unknown to `wasm-opt`.
     pub fn new(
         optimization_level: OptimizationPasses,
+        passes: &[String],
         keep_debug_symbols: bool,
     ) -> Result<Self> {
+        let passes = passes
+            .iter()
+            .map(|name| {
+                PASSES
+                    .iter()
+                    .find(|pass| pass.name() == name)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Unknown wasm-opt pass `{name}`"))
+            })
+            .collect::<Result<_>>()?;
         Ok(Self {
             optimization_level,
+            passes,
             keep_debug_symbols,
         })
     }
@@ -59,7 +214,14 @@ impl WasmOptHandler {
             self.optimization_level
         );
 
-        OptimizationOptions::from(self.optimization_level)
+        let mut options = OptimizationOptions::from(self.optimization_level);
+        if !self.passes.is_empty() {
+            options.add_default_passes(false);
+            for pass in &self.passes {
+                options.add_pass(pass.clone());
+            }
+        }
+        options
             .mvp_features_only()
             // Since rustc 1.70 `SignExt` can't be disabled anymore. Hence we have to allow it,
             // in order that the Wasm binary containing these instructions can be loaded.
@@ -158,6 +320,24 @@ impl From<OptimizationPasses> for OptimizationOptions {
     }
 }
 
+/// The `wasm-opt` pass pipeline configured in the
+/// `[package.metadata.contract.optimization]` section of the contract's `Cargo.toml`,
+/// e.g.
+///
+/// ```toml
+/// [package.metadata.contract.optimization]
+/// passes = ["dce", "inlining-optimizing", "vacuum"]
+/// ```
+///
+/// The passes given with `--wasm-opt-pass` replace the configured ones.
+#[derive(Default, Clone, Debug, Eq, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct OptimizationConfig {
+    /// The names of the passes to run instead of the default passes of the
+    /// optimization level, as given to the `wasm-opt` CLI without the leading `--`.
+    pub passes: Vec<String>,
+}
+
 /// Result of the optimization process.
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct OptimizationResult {
@@ -166,3 +346,40 @@ pub struct OptimizationResult {
     /// The Wasm size after optimizations have been applied.
     pub optimized_size: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_passes_by_name() {
+        let handler = WasmOptHandler::new(
+            OptimizationPasses::Z,
+            &["dce".to_string(), "inlining-optimizing".to_string()],
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            handler.passes.iter().map(Pass::name).collect::<Vec<_>>(),
+            vec!["dce", "inlining-optimizing"]
+        );
+
+        let unknown = WasmOptHandler::new(OptimizationPasses::Z, &["dse".into()], false);
+        assert_eq!(
+            unknown.err().map(|err| err.to_string()),
+            Some("Unknown wasm-opt pass `dse`".to_string())
+        );
+    }
+
+    #[test]
+    fn configurable_passes_are_registered() {
+        let registered = wasm_opt::base::pass_registry::get_registered_names();
+        for pass in PASSES {
+            assert!(
+                registered.iter().any(|name| name == pass.name()),
+                "{} is not a registered pass",
+                pass.name()
+            );
+        }
+    }
+}
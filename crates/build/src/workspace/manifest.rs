@@ -24,8 +24,13 @@ use super::{
     Profile,
 };
 use crate::{
+    audit::AuditConfig,
+    linting::LintConfig,
+    BuildFlags,
     CrateMetadata,
+    OptimizationConfig,
     OptimizationPasses,
+    WasmSettings,
 };
 
 use std::{
@@ -225,6 +230,97 @@ impl Manifest {
             .map(Into::into)
     }
 
+    /// Extract the lint levels from `[package.metadata.contract.lint]`
+    pub fn lint_config(&self) -> Result<LintConfig> {
+        let lint = self
+            .toml
+            .get("package")
+            .and_then(|v| v.get("metadata"))
+            .and_then(|v| v.get("contract"))
+            .and_then(|v| v.get("lint"));
+        match lint {
+            Some(lint) => {
+                lint.clone().try_into().context(
+                    "Invalid `[package.metadata.contract.lint]` section in Cargo.toml",
+                )
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// Extract the `wasm-opt` passes from `[package.metadata.contract.optimization]`
+    pub fn optimization_config(&self) -> Result<OptimizationConfig> {
+        let optimization = self
+            .toml
+            .get("package")
+            .and_then(|v| v.get("metadata"))
+            .and_then(|v| v.get("contract"))
+            .and_then(|v| v.get("optimization"));
+        match optimization {
+            Some(optimization) => {
+                optimization.clone().try_into().context(
+                    "Invalid `[package.metadata.contract.optimization]` section in \
+                    Cargo.toml",
+                )
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// Extract the dependency audit settings from `[package.metadata.contract.audit]`
+    pub fn audit_config(&self) -> Result<AuditConfig> {
+        let audit = self
+            .toml
+            .get("package")
+            .and_then(|v| v.get("metadata"))
+            .and_then(|v| v.get("contract"))
+            .and_then(|v| v.get("audit"));
+        match audit {
+            Some(audit) => {
+                audit.clone().try_into().context(
+                    "Invalid `[package.metadata.contract.audit]` section in Cargo.toml",
+                )
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// Extract the additional build flags from `[package.metadata.contract.build]`
+    pub fn build_flags(&self) -> Result<BuildFlags> {
+        let build = self
+            .toml
+            .get("package")
+            .and_then(|v| v.get("metadata"))
+            .and_then(|v| v.get("contract"))
+            .and_then(|v| v.get("build"));
+        match build {
+            Some(build) => {
+                build.clone().try_into().context(
+                    "Invalid `[package.metadata.contract.build]` section in Cargo.toml",
+                )
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// Extract the Wasm post-processing settings from `[package.metadata.contract.wasm]`
+    pub fn wasm_settings(&self) -> Result<WasmSettings> {
+        let wasm = self
+            .toml
+            .get("package")
+            .and_then(|v| v.get("metadata"))
+            .and_then(|v| v.get("contract"))
+            .and_then(|v| v.get("wasm"));
+        match wasm {
+            Some(wasm) => {
+                wasm.clone().try_into().context(
+                    "Invalid `[package.metadata.contract.wasm]` section in Cargo.toml",
+                )
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
     /// Set preferred defaults for the `[profile.release]` section
     ///
     /// # Note
@@ -295,16 +391,11 @@ impl Manifest {
 
         if members.contains(&LEGACY_METADATA_PACKAGE_PATH.into()) {
             // warn user if they have legacy metadata generation artifacts
-            use colored::Colorize;
-            eprintln!(
-                "{} {} {} {}",
-                "warning:".yellow().bold(),
-                "please remove".bold(),
-                LEGACY_METADATA_PACKAGE_PATH.bold(),
-                "from the `[workspace]` section in the `Cargo.toml`, \
-                and delete that directory. These are now auto-generated."
-                    .bold()
-            );
+            crate::output::warning(format!(
+                "please remove {LEGACY_METADATA_PACKAGE_PATH} from the `[workspace]` \
+                section in the `Cargo.toml`, and delete that directory. These are now \
+                auto-generated."
+            ));
         } else {
             members.push(METADATA_PACKAGE_PATH.into());
         }
@@ -678,7 +769,10 @@ fn merge_workspace_with_crate_dependencies(
 
 #[cfg(test)]
 mod test {
-    use super::ManifestPath;
+    use super::{
+        Manifest,
+        ManifestPath,
+    };
     use crate::util::tests::with_tmp_dir;
     use std::fs;
 
@@ -701,4 +795,67 @@ mod test {
             Ok(())
         })
     }
+
+    #[test]
+    fn build_flags_are_read_from_manifest() {
+        with_tmp_dir(|path| {
+            // given
+            let cargo_toml_path = path.join("Cargo.toml");
+            fs::write(
+                &cargo_toml_path,
+                r#"
+                [package]
+                name = "flipper"
+
+                [package.metadata.contract.build]
+                rustflags = ["-Coverflow-checks=on"]
+                link-args = ["-zstack-size=131072"]
+                "#,
+            )?;
+            let manifest = Manifest::new(ManifestPath::new(cargo_toml_path)?)?;
+
+            // when
+            let build_flags = manifest.build_flags()?;
+
+            // then
+            assert_eq!(
+                build_flags.rustflags(),
+                vec!["-Coverflow-checks=on", "-Clink-arg=-zstack-size=131072"]
+            );
+            assert!(build_flags.cargo_z_flags.is_empty());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn wasm_settings_are_read_from_manifest() {
+        with_tmp_dir(|path| {
+            // given
+            let cargo_toml_path = path.join("Cargo.toml");
+            fs::write(
+                &cargo_toml_path,
+                r#"
+                [package]
+                name = "flipper"
+
+                [package.metadata.contract.wasm]
+                initial-memory-pages = 2
+                rename-exports = { "_call" = "call" }
+                "#,
+            )?;
+            let manifest = Manifest::new(ManifestPath::new(cargo_toml_path)?)?;
+
+            // when
+            let wasm_settings = manifest.wasm_settings()?;
+
+            // then
+            assert_eq!(wasm_settings.initial_memory_pages, Some(2));
+            assert_eq!(wasm_settings.maximum_memory_pages, None);
+            assert_eq!(
+                wasm_settings.rename_exports.get("_call").map(String::as_str),
+                Some("call")
+            );
+            Ok(())
+        })
+    }
 }
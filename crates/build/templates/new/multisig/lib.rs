@@ -0,0 +1,261 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod {{name}} {
+    use ink::{
+        env::call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        prelude::vec::Vec,
+        storage::Mapping,
+    };
+
+    /// The identifier of a transaction.
+    pub type TransactionId = u32;
+
+    /// A call to another contract, executed once enough owners confirmed it.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Transaction {
+        /// The contract to call.
+        pub callee: AccountId,
+        /// The selector of the message to call.
+        pub selector: [u8; 4],
+        /// The SCALE encoded arguments of the message.
+        pub input: Vec<u8>,
+        /// The amount of tokens transferred with the call.
+        pub transferred_value: Balance,
+    }
+
+    /// Errors of the multisig contract.
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// The caller is not an owner of the wallet.
+        NotOwner,
+        /// The owners or the threshold passed to the constructor are invalid.
+        InvalidRequirement,
+        /// No transaction with the given id exists.
+        TransactionNotFound,
+        /// The caller already confirmed the transaction.
+        AlreadyConfirmed,
+        /// The transaction has not been confirmed by enough owners.
+        NotEnoughConfirmations,
+        /// The call of the transaction failed.
+        TransactionFailed,
+    }
+
+    /// Event emitted when a transaction is confirmed by an owner.
+    #[ink(event)]
+    pub struct Confirmation {
+        #[ink(topic)]
+        transaction: TransactionId,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    /// Event emitted when a transaction is executed.
+    #[ink(event)]
+    pub struct Execution {
+        #[ink(topic)]
+        transaction: TransactionId,
+    }
+
+    /// A wallet which executes transactions once `threshold` of its owners
+    /// confirmed them.
+    #[ink(storage)]
+    pub struct {{camel_name}} {
+        /// The owners of the wallet.
+        owners: Vec<AccountId>,
+        /// The number of confirmations required to execute a transaction.
+        threshold: u32,
+        /// The pending transactions.
+        transactions: Mapping<TransactionId, Transaction>,
+        /// The confirmations of the pending transactions.
+        confirmations: Mapping<(TransactionId, AccountId), ()>,
+        /// The number of confirmations of each pending transaction.
+        confirmation_count: Mapping<TransactionId, u32>,
+        /// The id of the next submitted transaction.
+        next_id: TransactionId,
+    }
+
+    impl {{camel_name}} {
+        /// Creates a wallet owned by `owners` requiring `threshold` confirmations.
+        #[ink(constructor)]
+        pub fn new(owners: Vec<AccountId>, threshold: u32) -> Result<Self, Error> {
+            if threshold == 0 || threshold as usize > owners.len() {
+                return Err(Error::InvalidRequirement)
+            }
+            Ok(Self {
+                owners,
+                threshold,
+                transactions: Default::default(),
+                confirmations: Default::default(),
+                confirmation_count: Default::default(),
+                next_id: 0,
+            })
+        }
+
+        /// Returns the owners of the wallet.
+        #[ink(message)]
+        pub fn owners(&self) -> Vec<AccountId> {
+            self.owners.clone()
+        }
+
+        /// Returns the number of confirmations required to execute a transaction.
+        #[ink(message)]
+        pub fn threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        /// Returns the pending transaction `id`.
+        #[ink(message)]
+        pub fn transaction(&self, id: TransactionId) -> Option<Transaction> {
+            self.transactions.get(id)
+        }
+
+        /// Returns the number of confirmations of the pending transaction `id`.
+        #[ink(message)]
+        pub fn confirmations(&self, id: TransactionId) -> u32 {
+            self.confirmation_count.get(id).unwrap_or_default()
+        }
+
+        /// Submits a transaction, confirming it on behalf of the caller.
+        #[ink(message)]
+        pub fn submit(&mut self, transaction: Transaction) -> Result<TransactionId, Error> {
+            self.ensure_owner()?;
+            let id = self.next_id;
+            self.next_id = self.next_id.wrapping_add(1);
+            self.transactions.insert(id, &transaction);
+            self.confirm(id)?;
+            Ok(id)
+        }
+
+        /// Confirms the pending transaction `id` on behalf of the caller.
+        #[ink(message)]
+        pub fn confirm(&mut self, id: TransactionId) -> Result<(), Error> {
+            let owner = self.ensure_owner()?;
+            if !self.transactions.contains(id) {
+                return Err(Error::TransactionNotFound)
+            }
+            if self.confirmations.contains((id, owner)) {
+                return Err(Error::AlreadyConfirmed)
+            }
+            self.confirmations.insert((id, owner), &());
+            self.confirmation_count
+                .insert(id, &(self.confirmations(id) + 1));
+            self.env().emit_event(Confirmation {
+                transaction: id,
+                owner,
+            });
+            Ok(())
+        }
+
+        /// Executes the pending transaction `id` if it has been confirmed by enough
+        /// owners.
+        #[ink(message, payable)]
+        pub fn execute(&mut self, id: TransactionId) -> Result<(), Error> {
+            self.ensure_owner()?;
+            let transaction = self.transactions.get(id).ok_or(Error::TransactionNotFound)?;
+            if self.confirmations(id) < self.threshold {
+                return Err(Error::NotEnoughConfirmations)
+            }
+            self.transactions.remove(id);
+            self.confirmation_count.remove(id);
+            for owner in &self.owners {
+                self.confirmations.remove((id, *owner));
+            }
+
+            build_call::<<Self as ink::env::ContractEnv>::Env>()
+                .call(transaction.callee)
+                .transferred_value(transaction.transferred_value)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(transaction.selector))
+                        .push_arg(CallInput(&transaction.input)),
+                )
+                .returns::<()>()
+                .try_invoke()
+                .map_err(|_| Error::TransactionFailed)?
+                .map_err(|_| Error::TransactionFailed)?;
+
+            self.env().emit_event(Execution { transaction: id });
+            Ok(())
+        }
+
+        fn ensure_owner(&self) -> Result<AccountId, Error> {
+            let caller = self.env().caller();
+            if !self.owners.contains(&caller) {
+                return Err(Error::NotOwner)
+            }
+            Ok(caller)
+        }
+    }
+
+    /// Encodes the already SCALE encoded arguments of a call as is.
+    struct CallInput<'a>(&'a [u8]);
+
+    impl ink::scale::Encode for CallInput<'_> {
+        fn encode_to<T: ink::scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        fn transaction() -> Transaction {
+            Transaction {
+                callee: accounts().django,
+                selector: [0; 4],
+                input: Vec::new(),
+                transferred_value: 0,
+            }
+        }
+
+        #[ink::test]
+        fn new_rejects_invalid_threshold() {
+            let owners = vec![accounts().alice];
+            assert_eq!(
+                {{camel_name}}::new(owners.clone(), 0).err(),
+                Some(Error::InvalidRequirement)
+            );
+            assert_eq!(
+                {{camel_name}}::new(owners, 2).err(),
+                Some(Error::InvalidRequirement)
+            );
+        }
+
+        #[ink::test]
+        fn submit_confirms_transaction() {
+            let accounts = accounts();
+            let mut {{name}} =
+                {{camel_name}}::new(vec![accounts.alice, accounts.bob], 2).unwrap();
+            let id = {{name}}.submit(transaction()).unwrap();
+
+            assert_eq!({{name}}.confirmations(id), 1);
+            assert_eq!({{name}}.confirm(id), Err(Error::AlreadyConfirmed));
+            assert_eq!({{name}}.execute(id), Err(Error::NotEnoughConfirmations));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!({{name}}.confirm(id), Ok(()));
+            assert_eq!({{name}}.confirmations(id), 2);
+        }
+
+        #[ink::test]
+        fn non_owner_cannot_submit() {
+            let accounts = accounts();
+            let mut {{name}} = {{camel_name}}::new(vec![accounts.alice], 1).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!({{name}}.submit(transaction()), Err(Error::NotOwner));
+        }
+    }
+}
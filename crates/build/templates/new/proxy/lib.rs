@@ -0,0 +1,122 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod {{name}} {
+    use ink::{
+        env::{
+            call::build_call,
+            CallFlags,
+        },
+        storage::{
+            traits::ManualKey,
+            Lazy,
+        },
+    };
+
+    /// Errors of the proxy contract.
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// The caller is not the admin of the proxy.
+        NotAdmin,
+    }
+
+    /// Event emitted when the logic of the proxy is upgraded.
+    #[ink(event)]
+    pub struct Upgraded {
+        #[ink(topic)]
+        code_hash: Hash,
+    }
+
+    /// A proxy which delegates all calls it does not handle itself to the code at
+    /// `code_hash`, executing it in the context of the proxy.
+    ///
+    /// The logic can be upgraded by the admin calling [`Self::upgrade`]. The fields of
+    /// the proxy are stored under manual keys, so that they don't collide with the
+    /// storage of the delegated code.
+    #[ink(storage)]
+    pub struct {{camel_name}} {
+        /// The hash of the code calls are delegated to.
+        code_hash: Lazy<Hash, ManualKey<0xC0DE_0001>>,
+        /// The account allowed to upgrade the proxy.
+        admin: Lazy<AccountId, ManualKey<0xC0DE_0002>>,
+    }
+
+    impl {{camel_name}} {
+        /// Creates a proxy delegating to the code at `code_hash`, administered by the
+        /// caller.
+        #[ink(constructor)]
+        pub fn new(code_hash: Hash) -> Self {
+            let mut code_hash_cell = Lazy::new();
+            code_hash_cell.set(&code_hash);
+            let mut admin = Lazy::new();
+            admin.set(&Self::env().caller());
+            Self {
+                code_hash: code_hash_cell,
+                admin,
+            }
+        }
+
+        /// Delegates calls to the code at `code_hash` from now on.
+        ///
+        /// Since all other calls are delegated, this is the only message of the proxy
+        /// itself and has the well-known selector `@` complementing the wildcard
+        /// selector.
+        #[ink(message, selector = @)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), Error> {
+            if self.admin.get() != Some(self.env().caller()) {
+                return Err(Error::NotAdmin)
+            }
+            self.code_hash.set(&code_hash);
+            self.env().emit_event(Upgraded { code_hash });
+            Ok(())
+        }
+
+        /// Delegates any other call to the code at `code_hash`.
+        ///
+        /// The call is a tail call, so the output of the delegated code is returned
+        /// to the caller as is.
+        #[ink(message, payable, selector = _)]
+        pub fn forward(&self) {
+            build_call::<<Self as ink::env::ContractEnv>::Env>()
+                .delegate(self.code_hash.get_or_default())
+                .call_flags(CallFlags::FORWARD_INPUT | CallFlags::TAIL_CALL)
+                .try_invoke()
+                .unwrap_or_else(|env_err| {
+                    panic!("delegate call failed due to {:?}", env_err)
+                })
+                .unwrap_or_else(|lang_err| {
+                    panic!("delegate call failed due to {:?}", lang_err)
+                });
+            unreachable!("the delegated call never returns since `tail_call` is set");
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        #[ink::test]
+        fn admin_can_upgrade() {
+            let mut {{name}} = {{camel_name}}::new(Hash::from([1; 32]));
+            assert_eq!({{name}}.upgrade(Hash::from([2; 32])), Ok(()));
+            assert_eq!({{name}}.code_hash.get(), Some(Hash::from([2; 32])));
+        }
+
+        #[ink::test]
+        fn non_admin_cannot_upgrade() {
+            let mut {{name}} = {{camel_name}}::new(Hash::from([1; 32]));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts().bob);
+            assert_eq!(
+                {{name}}.upgrade(Hash::from([2; 32])),
+                Err(Error::NotAdmin)
+            );
+            assert_eq!({{name}}.code_hash.get(), Some(Hash::from([1; 32])));
+        }
+    }
+}
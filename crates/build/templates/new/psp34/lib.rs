@@ -0,0 +1,238 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod {{name}} {
+    use ink::{
+        prelude::{
+            string::String,
+            vec::Vec,
+        },
+        storage::Mapping,
+    };
+
+    /// The identifier of a token.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Id {
+        U8(u8),
+        U16(u16),
+        U32(u32),
+        U64(u64),
+        U128(u128),
+        Bytes(Vec<u8>),
+    }
+
+    /// The PSP34 error type.
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum PSP34Error {
+        /// Custom error type for implementation-based errors.
+        Custom(String),
+        /// Returned when the caller is not allowed to perform the operation.
+        NotApproved,
+        /// Returned when a token with the given id already exists.
+        TokenExists,
+        /// Returned when no token with the given id exists.
+        TokenNotExists,
+    }
+
+    /// Event emitted when a token is transferred, minted or burned.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        id: Id,
+    }
+
+    /// Event emitted when an operator is approved or disapproved.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    }
+
+    /// A non-fungible token implementing the PSP34 standard.
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct {{camel_name}} {
+        /// The owner of each token.
+        token_owner: Mapping<Id, AccountId>,
+        /// The number of tokens owned by each account.
+        owned_tokens_count: Mapping<AccountId, u32>,
+        /// Operators approved by an owner, either for a single token or for all
+        /// tokens if the id is `None`.
+        allowances: Mapping<(AccountId, AccountId, Option<Id>), ()>,
+        /// The number of existing tokens.
+        total_supply: Balance,
+    }
+
+    impl {{camel_name}} {
+        /// Creates a collection without any tokens.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Default::default()
+        }
+
+        /// Returns the id of the collection.
+        #[ink(message)]
+        pub fn collection_id(&self) -> Id {
+            let account_id = self.env().account_id();
+            let bytes: &[u8] = account_id.as_ref();
+            Id::Bytes(bytes.to_vec())
+        }
+
+        /// Returns the number of tokens owned by `owner`.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> u32 {
+            self.owned_tokens_count.get(owner).unwrap_or_default()
+        }
+
+        /// Returns the owner of the token `id`.
+        #[ink(message)]
+        pub fn owner_of(&self, id: Id) -> Option<AccountId> {
+            self.token_owner.get(id)
+        }
+
+        /// Returns whether `operator` may transfer the token `id` of `owner`, or all
+        /// tokens of `owner` if `id` is `None`.
+        #[ink(message)]
+        pub fn allowance(
+            &self,
+            owner: AccountId,
+            operator: AccountId,
+            id: Option<Id>,
+        ) -> bool {
+            self.allowances.contains((owner, operator, None::<Id>))
+                || id.is_some() && self.allowances.contains((owner, operator, id))
+        }
+
+        /// Approves or disapproves `operator` to transfer the token `id` of the caller,
+        /// or all tokens of the caller if `id` is `None`.
+        #[ink(message)]
+        pub fn approve(
+            &mut self,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            let owner = self.env().caller();
+            if let Some(id) = &id {
+                if self.owner_of(id.clone()) != Some(owner) {
+                    return Err(PSP34Error::NotApproved)
+                }
+            }
+            if approved {
+                self.allowances.insert((owner, operator, id.clone()), &());
+            } else {
+                self.allowances.remove((owner, operator, id.clone()));
+            }
+            self.env().emit_event(Approval {
+                owner,
+                operator,
+                id,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Transfers the token `id` to `to`.
+        #[ink(message)]
+        pub fn transfer(
+            &mut self,
+            to: AccountId,
+            id: Id,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id.clone()).ok_or(PSP34Error::TokenNotExists)?;
+            if owner != caller && !self.allowance(owner, caller, Some(id.clone())) {
+                return Err(PSP34Error::NotApproved)
+            }
+            self.allowances.remove((owner, caller, Some(id.clone())));
+            self.owned_tokens_count
+                .insert(owner, &(self.balance_of(owner) - 1));
+            self.owned_tokens_count.insert(to, &(self.balance_of(to) + 1));
+            self.token_owner.insert(id.clone(), &to);
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: Some(to),
+                id,
+            });
+            Ok(())
+        }
+
+        /// Returns the number of existing tokens.
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        /// Mints the token `id` to the caller.
+        #[ink(message)]
+        pub fn mint(&mut self, id: Id) -> Result<(), PSP34Error> {
+            let caller = self.env().caller();
+            if self.token_owner.contains(id.clone()) {
+                return Err(PSP34Error::TokenExists)
+            }
+            self.token_owner.insert(id.clone(), &caller);
+            self.owned_tokens_count
+                .insert(caller, &(self.balance_of(caller) + 1));
+            self.total_supply += 1;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                id,
+            });
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut {{name}} = {{camel_name}}::new();
+            assert_eq!({{name}}.mint(Id::U8(1)), Ok(()));
+            assert_eq!({{name}}.owner_of(Id::U8(1)), Some(accounts().alice));
+            assert_eq!({{name}}.balance_of(accounts().alice), 1);
+            assert_eq!({{name}}.mint(Id::U8(1)), Err(PSP34Error::TokenExists));
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let mut {{name}} = {{camel_name}}::new();
+            let accounts = accounts();
+            assert_eq!({{name}}.mint(Id::U8(1)), Ok(()));
+            assert_eq!({{name}}.transfer(accounts.bob, Id::U8(1), Vec::new()), Ok(()));
+            assert_eq!({{name}}.owner_of(Id::U8(1)), Some(accounts.bob));
+            assert_eq!({{name}}.balance_of(accounts.alice), 0);
+            assert_eq!({{name}}.balance_of(accounts.bob), 1);
+        }
+
+        #[ink::test]
+        fn transfer_requires_approval() {
+            let mut {{name}} = {{camel_name}}::new();
+            let accounts = accounts();
+            assert_eq!({{name}}.mint(Id::U8(1)), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                {{name}}.transfer(accounts.bob, Id::U8(1), Vec::new()),
+                Err(PSP34Error::NotApproved)
+            );
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    str::FromStr,
+};
+
+use super::{
+    config::SignerConfig,
+    display_extrinsic_receipt,
+    prompt_confirm_tx,
+    CLIExtrinsicOpts,
+    TxContext,
+};
+use anyhow::{
+    anyhow,
+    Result,
+};
+use clap::{
+    Args,
+    Subcommand,
+};
+use contract_build::name_value_println;
+use contract_extrinsics::{
+    DisplayEvents,
+    ExtrinsicOpts,
+    ExtrinsicOptsBuilder,
+    MapAccountCommandBuilder,
+    MapAccountExec,
+    TokenMetadata,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::scale_decode::IntoVisitor,
+    tx::Signer,
+    Config,
+};
+use url::Url;
+
+#[derive(Debug, Args)]
+pub struct AccountCommand {
+    #[clap(subcommand)]
+    commands: AccountCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AccountCommands {
+    /// Map the account to an address, as required by chains running `pallet-revive`
+    #[clap(name = "map")]
+    Map(MapAccountCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct MapAccountCommand {
+    #[clap(flatten)]
+    extrinsic_cli_opts: CLIExtrinsicOpts,
+    /// Export the call output as JSON.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+}
+
+impl AccountCommand {
+    /// Returns whether to export the call output in JSON format.
+    pub fn output_json(&self) -> bool {
+        match &self.commands {
+            AccountCommands::Map(map) => map.output_json,
+        }
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        match &self.commands {
+            AccountCommands::Map(map) => map.handle().await,
+        }
+    }
+}
+
+impl MapAccountCommand {
+    async fn handle(&self) -> Result<(), ErrorVariant> {
+        self.extrinsic_cli_opts.ensure_can_submit()?;
+        call_with_config!(
+            self,
+            run,
+            self.extrinsic_cli_opts.chain_cli_opts.chain().config()
+        )
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + AsRef<[u8]> + Display,
+        C::Balance: TryFrom<u128> + Display + Default + FromStr + Serialize + Debug,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
+            .url(chain.url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(&token_metadata)?)
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider())
+            .progress(self.extrinsic_cli_opts.progress()?)
+            .done();
+
+        let map_exec: MapAccountExec<C, C, _> =
+            MapAccountCommandBuilder::new(extrinsic_opts).done().await?;
+        if map_exec.is_mapped().await? {
+            return Err(anyhow!(
+                "The account {} is already mapped to the address 0x{}",
+                map_exec.opts().signer().account_id(),
+                hex::encode(map_exec.address())
+            )
+            .into())
+        }
+        if !self.extrinsic_cli_opts.skip_confirm {
+            confirm_map_account(&map_exec, &chain.url(), &token_metadata).await?;
+        }
+        let result = map_exec.map_account().await?;
+        let metadata = map_exec.client().metadata();
+        let display_events = DisplayEvents::from_events::<C, C>(
+            &result.events,
+            None,
+            &metadata,
+            self.extrinsic_cli_opts
+                .chain_cli_opts
+                .ss58_prefix_for(Some(&metadata)),
+        )?
+        .filter(&self.extrinsic_cli_opts.event_filter());
+
+        let address = format!("0x{}", hex::encode(map_exec.address()));
+        if self.output_json {
+            let json_object = serde_json::json!({
+                "events": serde_json::from_str::<serde_json::Value>(&display_events.to_json()?)?,
+                "address": address,
+                "extrinsic": result.receipt,
+            });
+            println!("{}", serde_json::to_string_pretty(&json_object)?);
+        } else {
+            println!(
+                "{}",
+                display_events.display_events::<C>(
+                    self.extrinsic_cli_opts.verbosity()?,
+                    &token_metadata,
+                )?
+            );
+            name_value_println!("Address", address);
+            display_extrinsic_receipt(&result.receipt);
+        }
+        Ok(())
+    }
+}
+
+/// Makes sure the account of the signer is mapped to an address, if required by the
+/// chain.
+///
+/// An unmapped account is mapped if `map_account` is set, or after asking the user
+/// otherwise. Fails if the account is neither mapped nor allowed to be mapped.
+pub async fn ensure_account_mapped<C: Config + Environment + SignerConfig<C>>(
+    extrinsic_opts: ExtrinsicOpts<C, C, C::Signer>,
+    map_account: bool,
+    skip_confirm: bool,
+    output_json: bool,
+) -> Result<(), ErrorVariant>
+where
+    <C as Config>::AccountId: AsRef<[u8]> + Display,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    let map_exec = MapAccountCommandBuilder::new(extrinsic_opts).done().await?;
+    if map_exec.is_mapped().await? {
+        return Ok(())
+    }
+    if !map_account && (skip_confirm || output_json) {
+        return Err(anyhow!(
+            "The account {} must be mapped to an address before it can interact with \
+            contracts on chains running the `{}` pallet. Pass --map-account to map it \
+            first, or use `cargo contract account map`.",
+            map_exec.opts().signer().account_id(),
+            map_exec.backend().pallet()
+        )
+        .into())
+    }
+    if !skip_confirm {
+        let url = Url::parse(&map_exec.opts().url())
+            .map_err(|e| anyhow!("Failed to parse the url of the node: {e}"))?;
+        let token_metadata =
+            TokenMetadata::query_with_options::<C>(&url, map_exec.opts().rpc_options())
+                .await?;
+        confirm_map_account(&map_exec, &url, &token_metadata).await?;
+    }
+    let result = map_exec.map_account().await?;
+    if !output_json {
+        name_value_println!("Address", format!("0x{}", hex::encode(map_exec.address())));
+        display_extrinsic_receipt(&result.receipt);
+    }
+    Ok(())
+}
+
+/// Asks the user to confirm mapping the account of the signer.
+async fn confirm_map_account<C: Config + Environment + SignerConfig<C>>(
+    map_exec: &MapAccountExec<C, C, C::Signer>,
+    url: &Url,
+    token_metadata: &TokenMetadata,
+) -> Result<()>
+where
+    <C as Config>::AccountId: AsRef<[u8]> + Display,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    let context = TxContext::query::<C>(
+        url,
+        map_exec.opts().rpc_options(),
+        &map_exec.opts().signer().account_id(),
+        map_exec.estimate_fee(),
+        token_metadata,
+    )
+    .await?;
+    prompt_confirm_tx(&context, || print_map_account_preview(map_exec))
+}
+
+fn print_map_account_preview<C: Config + Environment + SignerConfig<C>>(
+    map_exec: &MapAccountExec<C, C, C::Signer>,
+) where
+    <C as Config>::AccountId: AsRef<[u8]> + Display,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    name_value_println!(
+        "Map account",
+        map_exec.opts().signer().account_id().to_string()
+    );
+    name_value_println!("Address", format!("0x{}", hex::encode(map_exec.address())));
+}
@@ -0,0 +1,327 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A local address book of named contract addresses, which can be referenced as
+//! `--contract @<name>` instead of pasting the address.
+
+use super::{
+    config::SignerConfig,
+    parse_account,
+    Chain,
+    CLIChainOpts,
+};
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use clap::{
+    Args,
+    Subcommand,
+};
+use contract_build::name_value_println;
+use contract_extrinsics::url_to_string;
+use ink_env::Environment;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+use subxt::Config;
+
+/// Environment variable overriding the path of the address book file.
+const ADDRESS_BOOK_ENV: &str = "CARGO_CONTRACT_ADDRESS_BOOK";
+
+#[derive(Debug, Args)]
+pub struct AddressBookCommand {
+    #[clap(subcommand)]
+    commands: AddressBookCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AddressBookCommands {
+    /// Add a named contract address, replacing an existing one of the same name
+    #[clap(name = "add")]
+    Add(AddAddress),
+    /// List the named contract addresses of the chain
+    #[clap(name = "list")]
+    List(ListAddresses),
+    /// Remove a named contract address
+    #[clap(name = "remove")]
+    Remove(RemoveAddress),
+}
+
+#[derive(Debug, Args)]
+pub struct AddAddress {
+    /// The name of the contract, referenced as `--contract @<name>`.
+    name: String,
+    /// The address of the contract.
+    address: String,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+#[derive(Debug, Args)]
+pub struct ListAddresses {
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+    /// Export the addresses in JSON format.
+    #[clap(long)]
+    output_json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveAddress {
+    /// The name of the contract.
+    name: String,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl AddressBookCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        match &self.commands {
+            AddressBookCommands::Add(add) => {
+                call_with_config!(add, run, add.chain_cli_opts.chain().config())
+            }
+            AddressBookCommands::List(list) => Ok(list.run()?),
+            AddressBookCommands::Remove(remove) => Ok(remove.run()?),
+        }
+    }
+}
+
+impl AddAddress {
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: FromStr,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+    {
+        parse_account::<<C as Config>::AccountId>(&self.address)
+            .context("Failed to parse the contract address")?;
+        let name = entry_name(&self.name)?;
+        let path = AddressBook::path()?;
+        let mut book = AddressBook::load(&path)?;
+        book.insert(&chain_key(&self.chain_cli_opts.chain()), name, &self.address);
+        book.store(&path)?;
+        Ok(())
+    }
+}
+
+impl ListAddresses {
+    fn run(&self) -> Result<()> {
+        let addresses = contract_addresses(&self.chain_cli_opts.chain())?;
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&addresses)?);
+        } else {
+            for (name, address) in &addresses {
+                let name = format!("@{name}");
+                name_value_println!(name, address);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RemoveAddress {
+    fn run(&self) -> Result<()> {
+        let name = entry_name(&self.name)?;
+        let path = AddressBook::path()?;
+        let mut book = AddressBook::load(&path)?;
+        let chain = chain_key(&self.chain_cli_opts.chain());
+        if !book.remove(&chain, name) {
+            anyhow::bail!("No contract named '@{name}' in the address book of {chain}")
+        }
+        book.store(&path)
+    }
+}
+
+/// Resolves a `--contract` reference to the address of the contract.
+///
+/// References starting with `@` are looked up in the address book of the `chain`, all
+/// other references are returned as they are.
+pub fn resolve_contract(contract: &str, chain: &Chain) -> Result<String> {
+    if !contract.starts_with('@') {
+        return Ok(contract.to_string())
+    }
+    let book = AddressBook::load(&AddressBook::path()?)?;
+    book.resolve(contract, &chain_key(chain))
+}
+
+/// Returns the named contract addresses of the `chain` in the address book.
+pub fn contract_addresses(chain: &Chain) -> Result<BTreeMap<String, String>> {
+    let book = AddressBook::load(&AddressBook::path()?)?;
+    Ok(book.addresses(&chain_key(chain)))
+}
+
+/// Returns the key of the addresses of the `chain` in the address book: the name of a
+/// production chain or the url of the node.
+fn chain_key(chain: &Chain) -> String {
+    match chain {
+        Chain::Production(chain) => chain.to_string(),
+        Chain::Custom(url, _) => url_to_string(url),
+    }
+}
+
+/// Returns the name of an address book entry, without the optional leading `@`.
+fn entry_name(name: &str) -> Result<&str> {
+    let name = name.strip_prefix('@').unwrap_or(name);
+    if name.is_empty() {
+        anyhow::bail!("The name of a contract must not be empty")
+    }
+    Ok(name)
+}
+
+/// The named contract addresses, by chain.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct AddressBook(BTreeMap<String, BTreeMap<String, String>>);
+
+impl AddressBook {
+    /// Returns the path of the address book file.
+    ///
+    /// Defaults to `cargo-contract/address-book.json` in `$XDG_CONFIG_HOME`, or in
+    /// `~/.config` if unset.
+    fn path() -> Result<PathBuf> {
+        if let Some(path) = env::var_os(ADDRESS_BOOK_ENV) {
+            return Ok(PathBuf::from(path))
+        }
+        let config = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+            })
+            .with_context(|| {
+                format!("Unknown home directory, set {ADDRESS_BOOK_ENV} instead")
+            })?;
+        Ok(config.join("cargo-contract").join("address-book.json"))
+    }
+
+    /// Loads the address book, which is empty if the file does not exist yet.
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| {
+            format!("Failed to parse the address book {}", path.display())
+        })
+    }
+
+    fn store(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn insert(&mut self, chain: &str, name: &str, address: &str) {
+        self.0
+            .entry(chain.to_string())
+            .or_default()
+            .insert(name.to_string(), address.to_string());
+    }
+
+    /// Removes the entry, returns whether it existed.
+    fn remove(&mut self, chain: &str, name: &str) -> bool {
+        let Some(addresses) = self.0.get_mut(chain) else {
+            return false
+        };
+        let removed = addresses.remove(name).is_some();
+        if addresses.is_empty() {
+            self.0.remove(chain);
+        }
+        removed
+    }
+
+    fn addresses(&self, chain: &str) -> BTreeMap<String, String> {
+        self.0.get(chain).cloned().unwrap_or_default()
+    }
+
+    /// Returns the address of the `@<name>` reference on the `chain`.
+    fn resolve(&self, reference: &str, chain: &str) -> Result<String> {
+        let name = entry_name(reference)?;
+        self.0
+            .get(chain)
+            .and_then(|addresses| addresses.get(name))
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "No contract named '@{name}' in the address book of {chain}, add it \
+                    with `cargo contract address-book add {name} <ADDRESS>`"
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    fn stored_addresses_resolve_per_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("address-book.json");
+        let mut book = AddressBook::load(&path).unwrap();
+        book.insert("ws://localhost:9944", "my-dex", ADDRESS);
+        book.store(&path).unwrap();
+
+        let book = AddressBook::load(&path).unwrap();
+
+        assert_eq!(
+            book.resolve("@my-dex", "ws://localhost:9944").unwrap(),
+            ADDRESS
+        );
+        let err = book.resolve("@my-dex", "Astar").unwrap_err();
+        assert!(err.to_string().starts_with(
+            "No contract named '@my-dex' in the address book of Astar"
+        ));
+    }
+
+    #[test]
+    fn removing_the_last_address_removes_the_chain() {
+        let mut book = AddressBook::default();
+        book.insert("Astar", "my-dex", ADDRESS);
+
+        assert!(book.remove("Astar", "my-dex"));
+        assert!(!book.remove("Astar", "my-dex"));
+        assert!(book.0.is_empty());
+    }
+
+    #[test]
+    fn names_may_have_a_leading_at() {
+        assert_eq!(entry_name("@my-dex").unwrap(), "my-dex");
+        assert_eq!(entry_name("my-dex").unwrap(), "my-dex");
+        assert!(entry_name("@").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use contract_analyze::unsupported_features;
+use contract_build::name_value_println;
+use contract_extrinsics::ContractArtifacts;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    name = "analyze",
+    about = "Scan the Wasm code of a contract for non-deterministic instructions and \
+    Wasm proposals unsupported by pallet-contracts"
+)]
+pub struct AnalyzeCommand {
+    /// Path to the `.wasm` code or the `.contract` bundle of the contract.
+    #[clap(value_parser)]
+    file: PathBuf,
+    /// Export the result as JSON.
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl AnalyzeCommand {
+    pub fn run(&self) -> Result<()> {
+        let artifacts =
+            ContractArtifacts::from_manifest_or_file(None, Some(&self.file))?;
+        let code = artifacts.code.ok_or_else(|| {
+            anyhow!("No Wasm code found in {}", self.file.display())
+        })?;
+        let usages = unsupported_features(code.as_bytes())?;
+
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&usages)?);
+        } else if usages.is_empty() {
+            name_value_println!("Supported", "yes");
+        } else {
+            for usage in &usages {
+                let uses = usage.uses.iter().cloned().collect::<Vec<_>>();
+                name_value_println!(
+                    "Unsupported",
+                    format!(
+                        "{}: {} occurrences of {}",
+                        usage.feature,
+                        usage.occurrences,
+                        uses.join(", ")
+                    )
+                );
+            }
+        }
+        if !usages.is_empty() {
+            anyhow::bail!("The code uses features unsupported by pallet-contracts")
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,549 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    config::SignerConfig,
+    parse_account,
+    parse_balance,
+    CLIExtrinsicOpts,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use comfy_table::{
+    ContentArrangement,
+    Table,
+};
+use contract_build::Verbosity;
+use contract_extrinsics::{
+    CallCommandBuilder,
+    ExtrinsicOptsBuilder,
+};
+use ink_env::Environment;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    Config,
+};
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    name = "bench",
+    about = "Benchmark the gas consumption of contract messages with dry-runs"
+)]
+pub struct BenchCommand {
+    /// The address of the the contract to benchmark.
+    #[clap(name = "contract", long, env = "CONTRACT")]
+    contract: String,
+    /// The name of the contract message to benchmark.
+    #[clap(long, short, required_unless_present = "cases")]
+    message: Option<String>,
+    /// The arguments of the contract message to benchmark.
+    #[clap(long, num_args = 0.., requires = "message")]
+    args: Vec<String>,
+    /// Path to a JSON file with the messages and argument sets to benchmark, e.g.
+    /// `[{ "message": "transfer", "args": ["5GrwvaEF...", "100"] }]`.
+    #[clap(long, value_parser, conflicts_with = "message")]
+    cases: Option<PathBuf>,
+    /// How often each message is dry-run.
+    #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// Path to a JSON file the results are appended to, and compared with the previous
+    /// results in it.
+    #[clap(long, value_parser)]
+    history: Option<PathBuf>,
+    /// A label for the results in the history, e.g. the version of the contract.
+    #[clap(long, requires = "history")]
+    label: Option<String>,
+    /// Path to a JSON file the results are written to as baseline for `--assert`.
+    #[clap(long, value_parser)]
+    save_baseline: Option<PathBuf>,
+    /// Path to a JSON file with baseline results, written by `--save-baseline` or with
+    /// `--output-json`. Fails if the median `ref_time` or `proof_size` of a message
+    /// exceeds the one in the baseline by more than `--tolerance`.
+    #[clap(long, value_parser, value_name = "BASELINE")]
+    assert: Option<PathBuf>,
+    /// The increase over the baseline tolerated by `--assert`, in percent.
+    #[clap(long, default_value_t = 5.0, requires = "assert")]
+    tolerance: f64,
+    #[clap(flatten)]
+    extrinsic_cli_opts: CLIExtrinsicOpts,
+    /// Export the results in JSON format.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+}
+
+/// A message and the arguments it is benchmarked with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchCase {
+    pub message: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The value transferred with the call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// The minimum, median and maximum of the measurements of the runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats<T> {
+    pub min: T,
+    pub median: T,
+    pub max: T,
+}
+
+impl<T: Copy + Ord> Stats<T> {
+    /// Returns the statistics of the measurements, `None` if there are none.
+    pub fn new(mut measurements: Vec<T>) -> Option<Self> {
+        measurements.sort();
+        Some(Self {
+            min: *measurements.first()?,
+            median: measurements[measurements.len() / 2],
+            max: *measurements.last()?,
+        })
+    }
+}
+
+impl<T: Display + PartialEq> Display for Stats<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.median)
+        } else {
+            write!(f, "{} / {} / {}", self.min, self.median, self.max)
+        }
+    }
+}
+
+/// The results of benchmarking a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchResult {
+    #[serde(flatten)]
+    pub case: BenchCase,
+    pub runs: u32,
+    pub ref_time: Stats<u64>,
+    pub proof_size: Stats<u64>,
+    /// The storage deposit charged, negative if it is refunded.
+    pub storage_deposit: Stats<i128>,
+}
+
+/// The results of a benchmark, as stored in the history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchRun {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchRun {
+    /// Returns the change of the median `ref_time` of the `result` compared to the same
+    /// case in this run, in percent.
+    pub fn ref_time_change(&self, result: &BenchResult) -> Option<f64> {
+        let previous = self
+            .results
+            .iter()
+            .find(|previous| previous.case == result.case)?
+            .ref_time
+            .median;
+        (previous != 0).then(|| {
+            (result.ref_time.median as f64 - previous as f64) / previous as f64 * 100.0
+        })
+    }
+}
+
+/// A measurement of a message exceeding the baseline by more than the tolerance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub case: BenchCase,
+    /// The name of the measurement, `ref_time` or `proof_size`.
+    pub measurement: &'static str,
+    /// The median in the baseline.
+    pub baseline: u64,
+    /// The median of the current results.
+    pub current: u64,
+}
+
+impl Regression {
+    /// Returns the increase over the baseline in percent.
+    pub fn change(&self) -> f64 {
+        if self.baseline == 0 {
+            return f64::INFINITY
+        }
+        (self.current as f64 - self.baseline as f64) / self.baseline as f64 * 100.0
+    }
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.case.message)?;
+        if !self.case.args.is_empty() {
+            write!(f, " {}", self.case.args.join(" "))?;
+        }
+        write!(
+            f,
+            ": {} {} -> {} ({:+.2}%)",
+            self.measurement,
+            self.baseline,
+            self.current,
+            self.change()
+        )
+    }
+}
+
+/// Returns the median `ref_time`s and `proof_size`s of the `results` which exceed the
+/// ones of the same case in the `baseline` by more than `tolerance` percent.
+///
+/// Cases missing in the baseline are not compared.
+pub fn regressions(
+    results: &[BenchResult],
+    baseline: &[BenchResult],
+    tolerance: f64,
+) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for result in results {
+        let Some(base) = baseline.iter().find(|base| base.case == result.case) else {
+            continue
+        };
+        for (measurement, baseline, current) in [
+            ("ref_time", base.ref_time.median, result.ref_time.median),
+            ("proof_size", base.proof_size.median, result.proof_size.median),
+        ] {
+            let regression = Regression {
+                case: result.case.clone(),
+                measurement,
+                baseline,
+                current,
+            };
+            if current > baseline && regression.change() > tolerance {
+                regressions.push(regression);
+            }
+        }
+    }
+    regressions
+}
+
+impl BenchCommand {
+    /// Returns whether to export the results in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(
+            self,
+            run,
+            self.extrinsic_cli_opts.chain_cli_opts.chain().config()
+        )
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Display,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Into<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let contract: <C as Config>::AccountId = parse_account(&self.contract)
+            .map_err(|e| anyhow::anyhow!("Failed to parse contract option: {}", e))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
+        let verbosity = self.extrinsic_cli_opts.verbosity()?;
+        let history = self.history.as_deref().map(load_history).transpose()?;
+        let baseline = self.assert.as_deref().map(load_baseline).transpose()?;
+
+        let mut results = Vec::new();
+        for case in self.cases()? {
+            let extrinsic_opts = ExtrinsicOptsBuilder::<C, C, _>::new(signer.clone())
+                .file(self.extrinsic_cli_opts.file.clone())
+                .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
+                .url(chain.url())
+                .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
+                .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+                .verbosity(Verbosity::Quiet)
+                .done();
+            let value = case
+                .value
+                .as_deref()
+                .map(|value| parse_balance(value, &token_metadata))
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Failed to parse value: {}", e))?
+                .unwrap_or_default();
+            let call_exec =
+                CallCommandBuilder::new(contract.clone(), &case.message, extrinsic_opts)
+                    .args(case.args.clone())
+                    .value(value)
+                    .done()
+                    .await?;
+
+            if verbosity.is_verbose() {
+                eprintln!("Benchmarking {} ({} runs)", case.message, self.runs);
+            }
+            let (mut ref_times, mut proof_sizes, mut deposits) =
+                (Vec::new(), Vec::new(), Vec::new());
+            for _ in 0..self.runs {
+                let cost = call_exec.call_dry_run_cost().await?;
+                ref_times.push(cost.ref_time);
+                proof_sizes.push(cost.proof_size);
+                deposits.push(cost.storage_deposit);
+            }
+            results.push(BenchResult {
+                case,
+                runs: self.runs,
+                ref_time: Stats::new(ref_times).expect("at least one run"),
+                proof_size: Stats::new(proof_sizes).expect("at least one run"),
+                storage_deposit: Stats::new(deposits).expect("at least one run"),
+            });
+        }
+
+        let previous = history.as_ref().and_then(|history| history.last());
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&results)?);
+        } else {
+            display_results(&results, previous);
+        }
+
+        if let Some(path) = &self.save_baseline {
+            fs::write(path, serde_json::to_string_pretty(&results)?).map_err(|e| {
+                anyhow::anyhow!("Failed to write {}: {}", path.display(), e)
+            })?;
+        }
+        if let (Some(path), Some(mut history)) = (&self.history, history) {
+            history.push(BenchRun {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(anyhow::Error::from)?
+                    .as_secs(),
+                label: self.label.clone(),
+                results: results.clone(),
+            });
+            fs::write(path, serde_json::to_string_pretty(&history)?).map_err(|e| {
+                anyhow::anyhow!("Failed to write {}: {}", path.display(), e)
+            })?;
+        }
+
+        if let (Some(path), Some(baseline)) = (&self.assert, baseline) {
+            let regressions = regressions(&results, &baseline, self.tolerance);
+            if !regressions.is_empty() {
+                let regressions = regressions
+                    .iter()
+                    .map(|regression| format!("\n  {regression}"))
+                    .collect::<String>();
+                return Err(anyhow::anyhow!(
+                    "The results exceed the baseline {} by more than {}%:{regressions}",
+                    path.display(),
+                    self.tolerance
+                )
+                .into())
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the benchmarked cases, either from the `--cases` file or the
+    /// `--message` and `--args`.
+    fn cases(&self) -> Result<Vec<BenchCase>> {
+        match (&self.cases, &self.message) {
+            (Some(path), _) => {
+                let file = fs::File::open(path)
+                    .context(format!("Failed to open {}", path.display()))?;
+                serde_json::from_reader(file)
+                    .context(format!("Failed to parse the cases in {}", path.display()))
+            }
+            (None, Some(message)) => {
+                Ok(vec![BenchCase {
+                    message: message.clone(),
+                    args: self.args.clone(),
+                    value: None,
+                }])
+            }
+            (None, None) => anyhow::bail!("Either --message or --cases is required"),
+        }
+    }
+}
+
+/// Loads the runs in the history file, which is empty if it doesn't exist yet.
+fn load_history(path: &Path) -> Result<Vec<BenchRun>> {
+    if !path.exists() {
+        return Ok(Vec::new())
+    }
+    let file =
+        fs::File::open(path).context(format!("Failed to open {}", path.display()))?;
+    serde_json::from_reader(file)
+        .context(format!("Failed to parse the history in {}", path.display()))
+}
+
+/// Loads the results in the baseline file.
+fn load_baseline(path: &Path) -> Result<Vec<BenchResult>> {
+    let file =
+        fs::File::open(path).context(format!("Failed to open {}", path.display()))?;
+    serde_json::from_reader(file)
+        .context(format!("Failed to parse the baseline in {}", path.display()))
+}
+
+fn display_results(results: &[BenchResult], previous: Option<&BenchRun>) {
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    let mut header = vec![
+        "Message",
+        "Args",
+        "ref_time (min / median / max)",
+        "proof_size (min / median / max)",
+        "Storage deposit (min / median / max)",
+    ];
+    if previous.is_some() {
+        header.push("ref_time change");
+    }
+    table.set_header(header);
+
+    for result in results {
+        let mut row = vec![
+            result.case.message.clone(),
+            result.case.args.join(" "),
+            result.ref_time.to_string(),
+            result.proof_size.to_string(),
+            result.storage_deposit.to_string(),
+        ];
+        if let Some(previous) = previous {
+            row.push(
+                previous
+                    .ref_time_change(result)
+                    .map_or_else(|| "-".to_string(), |change| format!("{change:+.2}%")),
+            );
+        }
+        table.add_row(row);
+    }
+    println!("{table}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(message: &str, ref_times: Vec<u64>) -> BenchResult {
+        BenchResult {
+            case: BenchCase {
+                message: message.to_string(),
+                args: vec!["1".to_string()],
+                value: None,
+            },
+            runs: ref_times.len() as u32,
+            ref_time: Stats::new(ref_times).unwrap(),
+            proof_size: Stats::new(vec![10]).unwrap(),
+            storage_deposit: Stats::new(vec![-5]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn stats_of_measurements() {
+        let stats = Stats::new(vec![30, 10, 20, 50, 40]).unwrap();
+
+        assert_eq!(
+            stats,
+            Stats {
+                min: 10,
+                median: 30,
+                max: 50
+            }
+        );
+        assert_eq!(stats.to_string(), "10 / 30 / 50");
+        assert_eq!(Stats::new(vec![7, 7]).unwrap().to_string(), "7");
+        assert_eq!(Stats::<u64>::new(vec![]), None);
+    }
+
+    #[test]
+    fn compares_with_previous_run() {
+        let previous = BenchRun {
+            timestamp: 0,
+            label: Some("v1".to_string()),
+            results: vec![result("flip", vec![200])],
+        };
+        let json = serde_json::to_string(&vec![previous.clone()]).unwrap();
+        let history: Vec<BenchRun> = serde_json::from_str(&json).unwrap();
+        assert_eq!(history, vec![previous.clone()]);
+
+        assert_eq!(
+            previous.ref_time_change(&result("flip", vec![250])),
+            Some(25.0)
+        );
+        assert_eq!(previous.ref_time_change(&result("get", vec![250])), None);
+    }
+
+    #[test]
+    fn regressions_beyond_the_tolerance() {
+        let baseline = vec![result("flip", vec![200]), result("get", vec![100])];
+        let mut proof_size = result("get", vec![100]);
+        proof_size.proof_size = Stats::new(vec![20]).unwrap();
+        let results = vec![
+            result("flip", vec![209]),
+            proof_size,
+            result("new", vec![1_000]),
+        ];
+
+        let regressions = regressions(&results, &baseline, 5.0);
+
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].to_string(), "get 1: proof_size 10 -> 20 (+100.00%)");
+        assert_eq!(super::regressions(&results, &baseline, 4.0).len(), 2);
+    }
+}
@@ -14,15 +14,24 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
+use super::{
+    progress,
+    read_suri_stdin,
+    signature::sign_bundle,
+    Suri,
+};
 use anyhow::Result;
 use contract_build::{
+    audit::AuditConfig,
     BuildArtifacts,
+    BuildFlags,
     BuildMode,
     BuildResult,
     ExecuteArgs,
     Features,
     ImageVariant,
     ManifestPath,
+    MetadataArtifacts,
     Network,
     OptimizationPasses,
     OutputType,
@@ -32,10 +41,28 @@ use contract_build::{
     Verbosity,
     VerbosityFlags,
 };
+use contract_extrinsics::{
+    max_code_len,
+    online_client,
+    rpc_client,
+    url_to_string,
+    RpcOptions,
+};
+use contract_metadata::{
+    ContractMetadata,
+    SignatureScheme,
+    WasmEncoding,
+};
 use std::{
     convert::TryFrom,
-    path::PathBuf,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
 };
+use subxt::SubstrateConfig;
 
 /// Executes build of the smart contract which produces a Wasm binary that is ready for
 /// deploying.
@@ -43,10 +70,17 @@ use std::{
 /// It does so by invoking `cargo build` and then post processing the final binary.
 #[derive(Debug, clap::Args)]
 #[clap(name = "build")]
+#[clap(group(clap::ArgGroup::new("signing").multiple(true).args(["sign", "sign_stdin"])))]
 pub struct BuildCommand {
     /// Path to the `Cargo.toml` of the contract to build
     #[clap(long, value_parser)]
     manifest_path: Option<PathBuf>,
+    /// Build all contracts of the workspace, optimizing their code in parallel.
+    ///
+    /// With `--output-json` the result of every contract is printed as a JSON document
+    /// of its own.
+    #[clap(long, conflicts_with = "verifiable")]
+    workspace: bool,
     /// By default the contract is compiled with debug functionality
     /// included. This enables the contract to output debug messages,
     /// but increases the contract size and the amount of gas used.
@@ -77,6 +111,8 @@ pub struct BuildCommand {
     #[clap(flatten)]
     features: Features,
     #[clap(flatten)]
+    build_flags: BuildFlags,
+    #[clap(flatten)]
     verbosity: VerbosityFlags,
     #[clap(flatten)]
     unstable_options: UnstableOptions,
@@ -107,11 +143,53 @@ pub struct BuildCommand {
     ///   = "3"`. The CLI argument always takes precedence over the profile value.
     #[clap(long)]
     optimization_passes: Option<OptimizationPasses>,
+    /// Run this `wasm-opt` pass instead of the default passes of the optimization
+    /// level, can be repeated, e.g. `--wasm-opt-pass dce --wasm-opt-pass vacuum`.
+    ///
+    /// The passes replace the ones configured in the
+    /// `[package.metadata.contract.optimization]` section of the `Cargo.toml`.
+    #[clap(long = "wasm-opt-pass", value_name = "PASS")]
+    wasm_opt_passes: Vec<String>,
     /// Do not remove symbols (Wasm name section) when optimizing.
     ///
     /// This is useful if one wants to analyze or debug the optimized binary.
     #[clap(long)]
     keep_debug_symbols: bool,
+    /// Additionally emit `<name>.debug.wasm` retaining the DWARF debug info, and
+    /// `<name>.symbols.json` mapping function indices to symbols.
+    ///
+    /// The contract's code itself stays the same, so traps of the deployed contract
+    /// can be symbolicated using these files.
+    #[clap(long, conflicts_with = "keep_debug_symbols")]
+    debug_artifacts: bool,
+    /// Embed the hash of the ABI into the `ink-metadata-hash` custom section of the
+    /// contract's code, linking the deployed code to its ABI.
+    ///
+    /// This changes the code hash of the contract compared to a build without it.
+    #[clap(long)]
+    embed_metadata_hash: bool,
+    /// Additionally emit `<name>.cdx.json`, a CycloneDX SBOM of the contract crate and
+    /// its dependency tree with their versions, checksums and licenses.
+    ///
+    /// Its hash is recorded as `sbom_hash` in the `build_info` of the metadata.
+    #[clap(long)]
+    sbom: bool,
+    /// Fail the build if a dependency is used under one of these licenses, given as
+    /// SPDX identifiers, e.g. `GPL-3.0`.
+    #[clap(long, value_name = "LICENSE", value_delimiter = ',')]
+    deny_licenses: Vec<String>,
+    /// Fail the build if a dependency is not used under one of these licenses, given
+    /// as SPDX identifiers, e.g. `Apache-2.0,MIT`.
+    #[clap(long, value_name = "LICENSE", value_delimiter = ',')]
+    allow_licenses: Vec<String>,
+    /// Fail the build if a dependency is affected by an advisory of the RustSec
+    /// advisory database.
+    #[clap(long)]
+    deny_advisories: bool,
+    /// Path to a checkout of the RustSec advisory database, instead of the one fetched
+    /// into `$CARGO_HOME/advisory-db`.
+    #[clap(long, value_parser)]
+    advisory_db: Option<PathBuf>,
     /// Export the build output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
@@ -124,6 +202,13 @@ pub struct BuildCommand {
     /// The maximum number of pages available for a wasm contract to allocate.
     #[clap(long, default_value_t = contract_build::DEFAULT_MAX_MEMORY_PAGES)]
     max_memory_pages: u64,
+    /// Fail the build if the contract code exceeds the given size in bytes.
+    #[clap(long, conflicts_with = "url")]
+    max_code_size: Option<u32>,
+    /// Websockets url of a node to fetch the maximum code size (`MaxCodeLen`) from,
+    /// failing the build if the contract code exceeds it.
+    #[clap(long, value_parser)]
+    url: Option<url::Url>,
     /// Executes the build inside a docker container to produce a verifiable bundle.
     /// Requires docker daemon running.
     #[clap(long, default_value_t = false)]
@@ -131,10 +216,62 @@ pub struct BuildCommand {
     /// Specify a custom image for the verifiable build
     #[clap(long, default_value = None)]
     image: Option<String>,
+    /// Skip the build if the contract sources, the toolchain and the build settings
+    /// did not change since the last build and all artifacts are still present.
+    ///
+    /// The fingerprint of the last build is stored in `target/ink/.fingerprint`.
+    #[clap(long, conflicts_with = "verifiable")]
+    skip_build_if_unchanged: bool,
+    /// Directory for the intermediate build output of cargo, instead of the target
+    /// directory of the workspace.
+    #[clap(long, value_parser, conflicts_with = "verifiable")]
+    target_dir: Option<PathBuf>,
+    /// Directory to write the contract artifacts to, e.g. `dist`, instead of
+    /// `target/ink`.
+    ///
+    /// The directory is recorded, so other commands like `cargo contract upload` load
+    /// the artifacts from it.
+    #[clap(long, value_parser, conflicts_with = "verifiable")]
+    artifacts_dir: Option<PathBuf>,
+    /// Sign the `.contract` bundle with the key derived from the given secret key URI,
+    /// e.g. "//Alice".
+    ///
+    /// The signature covers the `source`, `contract` and ABI sections of the metadata
+    /// and can be checked with `cargo contract verify-bundle`.
+    ///
+    /// Prefer `--sign-stdin` or the `CARGO_CONTRACT_SIGN_SURI` environment variable to
+    /// keep the secret out of the shell history and the process listing.
+    #[clap(
+        long,
+        value_name = "SURI",
+        env = "CARGO_CONTRACT_SIGN_SURI",
+        hide_env_values = true
+    )]
+    sign: Option<Suri>,
+    /// Read the secret key URI the bundle is signed with, including its password if
+    /// any, from the first line of stdin. Takes precedence over `--sign`.
+    #[clap(long)]
+    sign_stdin: bool,
+    /// The scheme used to sign the bundle.
+    #[clap(long, requires = "signing", default_value = "sr25519")]
+    signature_scheme: SignatureScheme,
+    /// Compress the Wasm code in the `.contract` bundle: `gzip` or `zstd`.
+    ///
+    /// The code is decompressed transparently when the bundle is loaded by other
+    /// commands, e.g. `cargo contract upload`.
+    #[clap(long, value_name = "ENCODING")]
+    compress_wasm: Option<WasmEncoding>,
+    /// The URL of an archive of the contract sources, recorded in the metadata as
+    /// `source.source_archive_url`.
+    ///
+    /// The hash of the git commit the contract is built from is detected and recorded
+    /// as `source.vcs_commit`.
+    #[clap(long, value_name = "URL")]
+    source_archive_url: Option<url::Url>,
 }
 
 impl BuildCommand {
-    pub fn exec(&self) -> Result<BuildResult> {
+    pub fn exec(&self) -> Result<Vec<BuildResult>> {
         let manifest_path = ManifestPath::try_from(self.manifest_path.as_ref())?;
         let unstable_flags: UnstableFlags =
             TryFrom::<&UnstableOptions>::try_from(&self.unstable_options)?;
@@ -168,27 +305,161 @@ impl BuildCommand {
             None => ImageVariant::Default,
         };
 
+        let vcs_commit = detect_vcs_commit(&manifest_path);
+
+        let max_code_size = match &self.url {
+            Some(url) => Some(fetch_max_code_len(url)?),
+            None => self.max_code_size,
+        };
+
         let args = ExecuteArgs {
             manifest_path,
             verbosity,
             build_mode,
             features: self.features.clone(),
+            build_flags: self.build_flags.clone(),
             network,
             build_artifact: self.build_artifact,
             unstable_flags,
             optimization_passes: self.optimization_passes,
+            wasm_opt_passes: self.wasm_opt_passes.clone(),
             keep_debug_symbols: self.keep_debug_symbols,
+            debug_artifacts: self.debug_artifacts,
+            embed_metadata_hash: self.embed_metadata_hash,
+            sbom: self.sbom,
+            audit: AuditConfig {
+                allow_licenses: self.allow_licenses.clone(),
+                deny_licenses: self.deny_licenses.clone(),
+                deny_advisories: self.deny_advisories,
+                ignore_advisories: Vec::new(),
+                advisory_db: self.advisory_db.clone(),
+            },
             extra_lints: self.lint,
             output_type,
             skip_wasm_validation: self.skip_wasm_validation,
             target: self.target,
             max_memory_pages: self.max_memory_pages,
+            max_code_size,
+            target_dir: self.target_dir.clone(),
+            artifacts_dir: self.artifacts_dir.clone(),
             image,
+            skip_build_if_unchanged: self.skip_build_if_unchanged,
+            progress: progress::reporter(&verbosity),
         };
-        contract_build::execute(args)
+        let results = match self.workspace {
+            true => contract_build::execute_workspace(args)?,
+            false => vec![contract_build::execute(args)?],
+        };
+        for result in &results {
+            self.finish_bundle(result, vcs_commit.clone())?;
+        }
+        Ok(results)
+    }
+
+    /// Returns the secret key URI the bundle is signed with, read from stdin if
+    /// `--sign-stdin` is passed.
+    fn sign_suri(&self) -> Result<Option<Suri>> {
+        if self.sign_stdin {
+            return read_suri_stdin().map(Some)
+        }
+        Ok(self.sign.clone())
+    }
+
+    /// Records the provenance in, compresses and signs the bundle of a build, as
+    /// requested.
+    fn finish_bundle(
+        &self,
+        result: &BuildResult,
+        vcs_commit: Option<String>,
+    ) -> Result<()> {
+        if let Some(artifacts) = &result.metadata_result {
+            if self.source_archive_url.is_some() || vcs_commit.is_some() {
+                record_provenance(
+                    artifacts,
+                    self.source_archive_url.clone(),
+                    vcs_commit,
+                )?;
+            }
+        }
+        if let Some(encoding) = self.compress_wasm {
+            let artifacts = result.metadata_result.as_ref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Compressing the Wasm code requires the bundle to be generated"
+                )
+            })?;
+            compress_bundle(artifacts, encoding)?;
+        }
+        if let Some(suri) = self.sign_suri()? {
+            let artifacts = result.metadata_result.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("Signing requires the bundle to be generated")
+            })?;
+            sign_bundle(artifacts, suri.expose(), self.signature_scheme)?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the source archive URL and the VCS commit in the metadata of a build.
+fn record_provenance(
+    artifacts: &MetadataArtifacts,
+    source_archive_url: Option<url::Url>,
+    vcs_commit: Option<String>,
+) -> Result<()> {
+    let mut metadata = ContractMetadata::load(&artifacts.dest_bundle)?;
+    metadata.source.source_archive_url = source_archive_url;
+    metadata.source.vcs_commit = vcs_commit;
+    fs::write(&artifacts.dest_bundle, serde_json::to_string(&metadata)?)?;
+    metadata.remove_source_wasm_attribute();
+    fs::write(
+        &artifacts.dest_metadata,
+        serde_json::to_string_pretty(&metadata)?,
+    )?;
+    Ok(())
+}
+
+/// Returns the hash of the git commit checked out in the directory of the manifest,
+/// suffixed with `-dirty` if there are uncommitted changes.
+///
+/// Returns `None` if the contract is not in a git repository or git is not installed.
+fn detect_vcs_commit(manifest_path: &ManifestPath) -> Option<String> {
+    let dir = manifest_path.directory().unwrap_or_else(|| Path::new("."));
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None
+        }
+        String::from_utf8(output.stdout).ok()
+    };
+    let commit = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let dirty = !git(&["status", "--porcelain"])?.trim().is_empty();
+    match dirty {
+        true => Some(format!("{commit}-dirty")),
+        false => Some(commit),
     }
 }
 
+/// Compresses the Wasm code in the bundle of a build with the `encoding`.
+fn compress_bundle(artifacts: &MetadataArtifacts, encoding: WasmEncoding) -> Result<()> {
+    let mut metadata = ContractMetadata::load(&artifacts.dest_bundle)?;
+    metadata.compress_wasm(encoding)?;
+    fs::write(&artifacts.dest_bundle, serde_json::to_string(&metadata)?)?;
+    Ok(())
+}
+
+/// Fetches the maximum size of contract code accepted by the node at `url`.
+fn fetch_max_code_len(url: &url::Url) -> Result<u32> {
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let options = RpcOptions::default();
+        let rpc = rpc_client(&url_to_string(url), options.clone()).await?;
+        let client = online_client::<SubstrateConfig>(rpc, options).await?;
+        max_code_len(&client)
+    })
+}
+
 #[derive(Debug, clap::Args)]
 #[clap(name = "check")]
 pub struct CheckCommand {
@@ -209,17 +480,28 @@ impl CheckCommand {
             verbosity,
             build_mode: BuildMode::Debug,
             features: Default::default(),
+            build_flags: Default::default(),
             network: Network::default(),
             build_artifact: BuildArtifacts::CheckOnly,
             unstable_flags: Default::default(),
             optimization_passes: Some(OptimizationPasses::Zero),
+            wasm_opt_passes: Vec::new(),
             keep_debug_symbols: false,
+            debug_artifacts: false,
+            embed_metadata_hash: false,
+            sbom: false,
+            audit: Default::default(),
             extra_lints: false,
             output_type: OutputType::default(),
             skip_wasm_validation: false,
             target: Default::default(),
             max_memory_pages: 0,
+            max_code_size: None,
+            target_dir: None,
+            artifacts_dir: None,
             image: ImageVariant::Default,
+            skip_build_if_unchanged: false,
+            progress: progress::reporter(&verbosity),
         };
 
         contract_build::execute(args)
@@ -0,0 +1,231 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use clap::{
+    Args,
+    Subcommand,
+};
+use contract_build::{
+    code_hash,
+    name_value_println,
+};
+use contract_metadata::{
+    CodeHash,
+    ContractMetadata,
+    SourceWasm,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+/// The file name of the metadata of an unpacked bundle.
+const METADATA_FILE: &str = "metadata.json";
+/// The file name of the Wasm code of an unpacked bundle.
+const CODE_FILE: &str = "code.wasm";
+
+#[derive(Debug, Args)]
+pub struct BundleCommand {
+    #[clap(subcommand)]
+    commands: BundleCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BundleCommands {
+    /// Combine a `metadata.json` and a `code.wasm` into a `.contract` bundle
+    #[clap(name = "pack")]
+    Pack(PackBundle),
+    /// Split a `.contract` bundle into a `metadata.json` and a `code.wasm`
+    #[clap(name = "unpack")]
+    Unpack(UnpackBundle),
+}
+
+#[derive(Debug, Args)]
+pub struct PackBundle {
+    /// Path to the contract metadata without the Wasm code.
+    #[clap(long, value_parser, default_value = METADATA_FILE)]
+    metadata: PathBuf,
+    /// Path to the Wasm code of the contract.
+    #[clap(long, value_parser, default_value = CODE_FILE)]
+    code: PathBuf,
+    /// Path of the `.contract` bundle to write.
+    #[clap(long, short, value_parser)]
+    output: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct UnpackBundle {
+    /// Path to the `.contract` bundle.
+    #[clap(value_parser)]
+    bundle: PathBuf,
+    /// Directory the `metadata.json` and `code.wasm` are written to.
+    #[clap(long, value_parser, default_value = ".")]
+    output_dir: PathBuf,
+}
+
+impl BundleCommand {
+    pub fn run(&self) -> Result<()> {
+        match &self.commands {
+            BundleCommands::Pack(pack) => pack.run(),
+            BundleCommands::Unpack(unpack) => unpack.run(),
+        }
+    }
+}
+
+impl PackBundle {
+    fn run(&self) -> Result<()> {
+        let mut metadata = ContractMetadata::load(&self.metadata)?;
+        let code = fs::read(&self.code)
+            .with_context(|| format!("Failed to read {}", self.code.display()))?;
+        let previous_hash = metadata.source.hash;
+
+        pack(&mut metadata, code)?;
+        fs::write(&self.output, serde_json::to_string(&metadata)?)?;
+
+        if metadata.source.hash != previous_hash {
+            contract_build::output::warning(format!(
+                "The Wasm code changed, updated `source.hash` from {previous_hash} to {}",
+                metadata.source.hash
+            ));
+            if metadata.signature.is_some() {
+                contract_build::output::warning(
+                    "The bundle signature does not cover the changed code anymore",
+                );
+            }
+        }
+        name_value_println!("Bundle", self.output.display().to_string());
+        Ok(())
+    }
+}
+
+impl UnpackBundle {
+    fn run(&self) -> Result<()> {
+        let metadata = ContractMetadata::load(&self.bundle)?;
+        let (metadata, code) = unpack(metadata)?;
+
+        fs::create_dir_all(&self.output_dir)?;
+        let metadata_path = self.output_dir.join(METADATA_FILE);
+        let code_path = self.output_dir.join(CODE_FILE);
+        fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?)?;
+        fs::write(&code_path, code)?;
+
+        name_value_println!("Metadata", metadata_path.display().to_string());
+        name_value_println!("Code", code_path.display().to_string());
+        Ok(())
+    }
+}
+
+/// Embeds the Wasm `code` in the `metadata`, updating `source.hash` if it does not
+/// match the code.
+fn pack(metadata: &mut ContractMetadata, code: Vec<u8>) -> Result<()> {
+    if !code.starts_with(b"\0asm") {
+        anyhow::bail!("The code is not a Wasm binary")
+    }
+    metadata.source.hash = CodeHash(code_hash(&code));
+    metadata.source.wasm = Some(SourceWasm::new(code));
+    metadata.source.wasm_encoding = None;
+    Ok(())
+}
+
+/// Splits the `bundle` into the metadata without the Wasm code and the decompressed
+/// Wasm code.
+fn unpack(mut bundle: ContractMetadata) -> Result<(ContractMetadata, Vec<u8>)> {
+    bundle.decompress_wasm()?;
+    let code = bundle
+        .source
+        .wasm
+        .take()
+        .context("The bundle does not contain the Wasm code")?;
+    if code_hash(&code.0) != bundle.source.hash.0 {
+        anyhow::bail!("The Wasm code of the bundle does not match its `source.hash`")
+    }
+    bundle.remove_source_wasm_attribute();
+    Ok((bundle, code.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CODE: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn metadata() -> ContractMetadata {
+        serde_json::from_value(json!({
+            "source": {
+                "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "language": "ink! 5.0.0",
+                "compiler": "rustc 1.78.0"
+            },
+            "contract": {
+                "name": "flipper",
+                "version": "0.1.0",
+                "authors": ["Use Ink <admin@use.ink>"]
+            },
+            "image": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unpacked_bundle_packs_into_the_same_bundle() {
+        let mut bundle = metadata();
+        pack(&mut bundle, CODE.to_vec()).unwrap();
+
+        let (mut metadata, code) = unpack(bundle.clone()).unwrap();
+        assert_eq!(metadata.source.wasm, None);
+        assert_eq!(code, CODE);
+
+        pack(&mut metadata, code).unwrap();
+        assert_eq!(
+            serde_json::to_value(metadata).unwrap(),
+            serde_json::to_value(bundle).unwrap()
+        );
+    }
+
+    #[test]
+    fn pack_updates_the_code_hash() {
+        let mut metadata = metadata();
+
+        pack(&mut metadata, CODE.to_vec()).unwrap();
+
+        assert_eq!(metadata.source.hash.0, code_hash(CODE));
+    }
+
+    #[test]
+    fn unpack_rejects_mismatching_code_hash() {
+        let mut bundle = metadata();
+        bundle.source.wasm = Some(SourceWasm::new(CODE.to_vec()));
+
+        let err = unpack(bundle).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The Wasm code of the bundle does not match its `source.hash`"
+        );
+    }
+
+    #[test]
+    fn pack_rejects_non_wasm_code() {
+        let err = pack(&mut metadata(), b"not wasm".to_vec()).unwrap_err();
+
+        assert_eq!(err.to_string(), "The code is not a Wasm binary");
+    }
+}
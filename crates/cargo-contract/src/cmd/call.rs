@@ -27,20 +27,36 @@ use std::{
         Debug,
         Display,
     },
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
     str::FromStr,
 };
 
 use super::{
+    address_book::resolve_contract,
     config::SignerConfig,
     display_contract_exec_result,
     display_contract_exec_result_debug,
     display_dry_run_result_warning,
+    display_extrinsic_receipt,
+    ensure_sudo_key,
+    json_output::{
+        ExtrinsicOutput,
+        ScheduledOutput,
+        SubmissionOutput,
+    },
     parse_account,
     parse_balance,
     print_dry_running_status,
     print_gas_required_success,
     prompt_confirm_tx,
+    sandbox::CLISandboxOpts,
     CLIExtrinsicOpts,
+    ExplorerLinks,
+    TxContext,
     MAX_KEY_COL_WIDTH,
 };
 use anyhow::{
@@ -50,14 +66,25 @@ use anyhow::{
 };
 use contract_build::name_value_println;
 use contract_extrinsics::{
-    pallet_contracts_primitives::StorageDeposit,
+    call_wrapper,
+    pallet_contracts_primitives::{
+        ContractExecResult,
+        StorageDeposit,
+    },
+    ss58,
+    BatchCallExec,
     CallCommandBuilder,
     CallExec,
     DisplayEvents,
+    ExtrinsicOpts,
     ExtrinsicOptsBuilder,
     TokenMetadata,
 };
-use contract_transcode::Value;
+use contract_transcode::{
+    unwrap_lang_result,
+    ReturnError,
+    Value,
+};
 use sp_weights::Weight;
 use subxt::{
     config::{
@@ -68,18 +95,24 @@ use subxt::{
         scale_decode::IntoVisitor,
         scale_encode::EncodeAsType,
     },
+    tx::Signer as _,
     Config,
 };
 
-#[derive(Debug, clap::Args)]
+#[derive(Clone, Debug, clap::Args)]
 #[clap(name = "call", about = "Call a contract")]
 pub struct CallCommand {
-    /// The address of the the contract to call.
-    #[clap(name = "contract", long, env = "CONTRACT")]
-    contract: String,
-    /// The name of the contract message to call.
-    #[clap(long, short)]
-    message: String,
+    /// The address of the the contract to call, or `@<name>` of the address book.
+    #[clap(
+        name = "contract",
+        long,
+        env = "CONTRACT",
+        required_unless_present_any = ["batch", "call_file"]
+    )]
+    contract: Option<String>,
+    /// The name of the contract message to call, or its selector e.g. `0x633aa551`.
+    #[clap(long, short, required_unless_present_any = ["batch", "call_file"])]
+    message: Option<String>,
     /// The arguments of the contract message to call.
     #[clap(long, num_args = 0..)]
     args: Vec<String>,
@@ -95,12 +128,127 @@ pub struct CallCommand {
     /// the call.
     #[clap(long)]
     proof_size: Option<u64>,
-    /// The value to be transferred as part of the call.
-    #[clap(name = "value", long, default_value = "0")]
-    value: String,
+    /// The value to be transferred as part of the call [default: 0].
+    #[clap(name = "value", long)]
+    value: Option<String>,
     /// Export the call output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
+    /// Path to a Solidity ABI file of the contract, e.g. produced by `solang`.
+    ///
+    /// The message and its arguments are then encoded according to the Solidity ABI
+    /// instead of the ink! metadata.
+    #[clap(long, value_parser)]
+    abi: Option<PathBuf>,
+    /// Path to a TOML file with several calls, possibly to different contracts, which
+    /// are submitted together in a single `utility.batch_all` extrinsic.
+    ///
+    /// Each call is a `[[call]]` table with the `contract`, the `message` and its
+    /// `args`, and optionally the `value`, the `gas` and `proof_size` limits and the
+    /// contract artifact `file`.
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with_all = ["message", "args", "gas", "proof_size", "abi", "sandbox"]
+    )]
+    batch: Option<PathBuf>,
+    /// Schedule the call for execution at the given block with the `Scheduler`
+    /// pallet, instead of executing it right away.
+    ///
+    /// Scheduling requires the root origin, so the call must be scheduled with `--sudo`
+    /// by the sudo key. The scheduled call is dispatched through `utility.dispatch_as`
+    /// with the signed origin of the signer, not the root origin, so the contract sees
+    /// the sudo key as its `caller()`. Requires the `Utility` pallet.
+    #[clap(
+        long,
+        value_name = "block",
+        requires = "sudo",
+        conflicts_with_all = ["batch", "sandbox"]
+    )]
+    schedule_at: Option<u64>,
+    /// Path to a TOML file describing the call, e.g. to review it like code and replay
+    /// it exactly.
+    ///
+    /// The file holds the `contract`, the `message` and its `args`, and optionally the
+    /// `value`, the `gas` and `proof-size` limits and the `storage-deposit-limit`. The
+    /// options passed on the command line override the ones of the file.
+    #[clap(long, value_parser, conflicts_with = "batch")]
+    call_file: Option<PathBuf>,
+    /// Save the call to a TOML file, to be replayed with `--call-file`.
+    #[clap(long, value_parser, conflicts_with = "batch")]
+    save_call_file: Option<PathBuf>,
+    /// Dispatch the call with the root origin through `sudo.sudo`.
+    ///
+    /// The signer must be the sudo key of the chain.
+    #[clap(long, conflicts_with_all = ["as_proposal", "sandbox"])]
+    sudo: bool,
+    /// Submit the call as a proposal of the `Council` pallet, which is executed once
+    /// the given number of council members approved it.
+    #[clap(long, value_name = "threshold", conflicts_with = "sandbox")]
+    as_proposal: Option<u32>,
+    #[clap(flatten)]
+    sandbox_opts: CLISandboxOpts,
+}
+
+/// The collective pallet the call is proposed to with `--as-proposal`.
+const PROPOSAL_PALLET: &str = "Council";
+
+/// The maximum length of the encoded call proposed with `--as-proposal`.
+const PROPOSAL_LENGTH_BOUND: u32 = 64 * 1024;
+
+/// A call of a `--call-file`.
+#[derive(Debug, Default, PartialEq, serde::Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct CallFile {
+    contract: String,
+    message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_deposit_limit: Option<String>,
+}
+
+impl CallFile {
+    /// Reads the call from a TOML file.
+    fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .context(format!("Failed to read {}", path.display()))?;
+        toml::from_str(&contents).context(format!("Failed to parse {}", path.display()))
+    }
+
+    /// Writes the call to a TOML file.
+    fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string_pretty(self)?;
+        fs::write(path, contents).context(format!("Failed to write {}", path.display()))
+    }
+}
+
+/// The calls of a `--batch` file.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchFile {
+    call: Vec<BatchFileCall>,
+}
+
+/// A call of a `--batch` file.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct BatchFileCall {
+    contract: String,
+    message: String,
+    #[serde(default)]
+    args: Vec<String>,
+    value: Option<String>,
+    gas: Option<u64>,
+    proof_size: Option<u64>,
+    /// The contract artifact, defaults to the one of the command.
+    file: Option<PathBuf>,
 }
 
 impl CallCommand {
@@ -110,81 +258,223 @@ impl CallCommand {
     }
 
     pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        let call = self.with_call_file()?;
+        if let Some(path) = &call.save_call_file {
+            call.call_file().save(path)?;
+        }
+        if call.sandbox_opts.sandbox {
+            return call.run_in_sandbox()
+        }
+        if call.extrinsic_cli_opts.execute {
+            call.extrinsic_cli_opts.ensure_can_submit()?;
+        }
         call_with_config!(
-            self,
+            call,
             run,
-            self.extrinsic_cli_opts.chain_cli_opts.chain().config()
+            call.extrinsic_cli_opts.chain_cli_opts.chain().config()
         )
     }
 
+    /// Returns the command with the options of the `--call-file`, if passed, which are
+    /// not passed on the command line.
+    fn with_call_file(&self) -> Result<Self> {
+        let mut call = self.clone();
+        let Some(path) = &self.call_file else {
+            return Ok(call)
+        };
+        let file = CallFile::load(path)?;
+        call.contract = call.contract.or(Some(file.contract));
+        call.message = call.message.or(Some(file.message));
+        if call.args.is_empty() {
+            call.args = file.args;
+        }
+        call.value = call.value.or(file.value);
+        call.gas_limit = call.gas_limit.or(file.gas);
+        call.proof_size = call.proof_size.or(file.proof_size);
+        let opts = &mut call.extrinsic_cli_opts;
+        opts.storage_deposit_limit =
+            opts.storage_deposit_limit.take().or(file.storage_deposit_limit);
+        Ok(call)
+    }
+
+    /// Returns the call as passed, to be saved to a `--call-file`.
+    fn call_file(&self) -> CallFile {
+        CallFile {
+            contract: self.contract.clone().expect("required by clap"),
+            message: self.message.clone().expect("required by clap"),
+            args: self.args.clone(),
+            value: self.value.clone(),
+            gas: self.gas_limit,
+            proof_size: self.proof_size,
+            storage_deposit_limit: self.extrinsic_cli_opts.storage_deposit_limit.clone(),
+        }
+    }
+
+    /// Returns the value to be transferred as part of the call.
+    fn value(&self) -> &str {
+        self.value.as_deref().unwrap_or("0")
+    }
+
+    /// Calls the contract in the sandbox and displays the result like a dry-run.
+    #[cfg(feature = "sandbox")]
+    fn run_in_sandbox(&self) -> Result<(), ErrorVariant> {
+        use super::sandbox::{
+            self,
+            SANDBOX_TOKEN,
+        };
+        use contract_extrinsics::{
+            ContractArtifacts,
+            SandboxBackend,
+        };
+        use ink_env::DefaultEnvironment;
+        use subxt::utils::AccountId32;
+
+        let ss58_prefix = self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix_for(None);
+        let contract_str = self.contract.as_deref().expect("required by clap");
+        let message = self.message.as_deref().expect("required by clap");
+        let contract: AccountId32 = parse_account(contract_str)
+            .map_err(|e| anyhow!("Failed to parse contract option: {}", e))?;
+        let artifacts = ContractArtifacts::from_manifest_or_file(
+            self.extrinsic_cli_opts.manifest_path.as_ref(),
+            self.extrinsic_cli_opts.file.as_ref(),
+        )?;
+        let transcoder = artifacts.contract_transcoder()?.with_ss58_prefix(ss58_prefix);
+        let data = transcoder.encode(message, &self.args)?;
+        let value = parse_balance(self.value(), &SANDBOX_TOKEN)
+            .map_err(|e| anyhow!("Failed to parse value option: {}", e))?;
+        let storage_deposit_limit = self
+            .extrinsic_cli_opts
+            .storage_deposit_limit
+            .as_ref()
+            .map(|b| parse_balance(b, &SANDBOX_TOKEN))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse storage_deposit_limit option: {}", e))?;
+        let gas_limit = sandbox::gas_limit(self.gas_limit, self.proof_size)?;
+
+        let mut backend = self.sandbox_opts.load()?;
+        let called =
+            backend.call(&contract, data, value, gas_limit, storage_deposit_limit)?;
+        let result = &called.result;
+        let ret_val = match &result.result {
+            Ok(ret_val) => ret_val,
+            Err(err) => {
+                return Err(ErrorVariant::from_dispatch_error(
+                    err,
+                    &SandboxBackend::metadata()?,
+                )?)
+            }
+        };
+        let value = transcoder
+            .decode_message_return(message, &mut &ret_val.data[..])
+            .context(format!("Failed to decode return value {:?}", &ret_val))
+            .map_err(ErrorVariant::decode)?;
+        let reverted = ret_val.did_revert();
+        let dry_run_result = CallDryRunResult::new(
+            value,
+            transcoder.message_returns_lang_result(message),
+            reverted,
+            result,
+        );
+        let events = sandbox::display_events(called.events, &transcoder, ss58_prefix)?;
+        if !reverted {
+            self.sandbox_opts.store(&mut backend)?;
+        }
+
+        if self.output_json() {
+            let mut output = ExtrinsicOutput::call_dry_run(
+                ss58::display_account(&contract, ss58_prefix),
+                dry_run_result,
+            );
+            output.events = Some(events);
+            println!("{}", output.to_json()?);
+        } else {
+            dry_run_result.print();
+            display_contract_exec_result_debug::<_, DEFAULT_KEY_COL_WIDTH, _>(result)?;
+            println!(
+                "{}",
+                events.display_events::<DefaultEnvironment>(
+                    self.extrinsic_cli_opts.verbosity()?,
+                    &SANDBOX_TOKEN
+                )?
+            );
+        }
+        if reverted {
+            return Err(ErrorVariant::dry_run_revert("The message reverted"))
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    fn run_in_sandbox(&self) -> Result<(), ErrorVariant> {
+        Err(super::sandbox::sandbox_unsupported())
+    }
+
     async fn run<C: Config + Environment + SignerConfig<C>>(
         &self,
     ) -> Result<(), ErrorVariant>
     where
-        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Display,
         <<C as Config>::AccountId as FromStr>::Err: Display,
-        C::Balance:
-            From<u128> + Display + Default + FromStr + Serialize + Debug + EncodeAsType,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
         <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
     {
-        let contract = parse_account(&self.contract)
-            .map_err(|e| anyhow::anyhow!("Failed to parse contract option: {}", e))?;
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        if let Some(batch) = &self.batch {
+            return self.run_batch::<C>(batch).await
+        }
+        let contract_str = self.contract.as_deref().expect("required by clap");
+        let message = self.message.as_deref().expect("required by clap");
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
-        let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
-        let storage_deposit_limit = self
+        let contract = parse_account(&resolve_contract(contract_str, &chain)?)
+            .map_err(|e| anyhow::anyhow!("Failed to parse contract option: {}", e))?;
+        let token_metadata = self
             .extrinsic_cli_opts
-            .storage_deposit_limit
-            .clone()
-            .map(|b| parse_balance(&b, &token_metadata))
-            .transpose()
-            .map_err(|e| {
-                anyhow::anyhow!("Failed to parse storage_deposit_limit option: {}", e)
-            })?;
-        let value = parse_balance(&self.value, &token_metadata)
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
+        let value = parse_balance(self.value(), &token_metadata)
             .map_err(|e| anyhow::anyhow!("Failed to parse value option: {}", e))?;
-        let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
-            .file(self.extrinsic_cli_opts.file.clone())
-            .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
-            .url(chain.url())
-            .storage_deposit_limit(storage_deposit_limit)
-            .verbosity(self.extrinsic_cli_opts.verbosity()?)
-            .done();
+        let extrinsic_opts = self
+            .extrinsic_opts::<C>(&token_metadata, self.extrinsic_cli_opts.file.clone())?;
 
-        let call_exec = CallCommandBuilder::new(contract, &self.message, extrinsic_opts)
+        let call_exec = CallCommandBuilder::new(contract, message, extrinsic_opts)
             .args(self.args.clone())
             .gas_limit(self.gas_limit)
             .proof_size(self.proof_size)
             .value(value)
+            .abi(self.abi.clone())
             .done()
             .await?;
         let metadata = call_exec.client().metadata();
+        let ss58_prefix = call_exec.ss58_prefix();
 
         if !self.extrinsic_cli_opts.execute {
             let result = call_exec.call_dry_run().await?;
             match result.result {
                 Ok(ref ret_val) => {
                     let value = call_exec
-                        .transcoder()
-                        .decode_message_return(
-                            call_exec.message(),
-                            &mut &ret_val.data[..],
-                        )
-                        .context(format!(
-                            "Failed to decode return value {:?}",
-                            &ret_val
-                        ))?;
-                    let dry_run_result = CallDryRunResult {
-                        reverted: ret_val.did_revert(),
-                        data: value,
-                        gas_consumed: result.gas_consumed,
-                        gas_required: result.gas_required,
-                        storage_deposit: result.storage_deposit.clone(),
-                    };
+                        .decode_return(&ret_val.data)
+                        .context(format!("Failed to decode return value {:?}", &ret_val))
+                        .map_err(ErrorVariant::decode)?;
+                    let reverted = ret_val.did_revert();
+                    let dry_run_result = CallDryRunResult::new(
+                        value,
+                        call_exec.returns_lang_result(),
+                        reverted,
+                        &result,
+                    );
                     if self.output_json() {
-                        println!("{}", dry_run_result.to_json()?);
+                        let output = ExtrinsicOutput::call_dry_run(
+                            ss58::display_account(call_exec.contract(), ss58_prefix),
+                            dry_run_result,
+                        );
+                        println!("{}", output.to_json()?);
                     } else {
                         dry_run_result.print();
                         display_contract_exec_result_debug::<_, DEFAULT_KEY_COL_WIDTH, _>(
@@ -192,6 +482,9 @@ impl CallCommand {
                         )?;
                         display_dry_run_result_warning("message");
                     };
+                    if reverted {
+                        return Err(ErrorVariant::dry_run_revert("The message reverted"))
+                    }
                 }
                 Err(ref err) => {
                     let object = ErrorVariant::from_dispatch_error(err, &metadata)?;
@@ -204,65 +497,507 @@ impl CallCommand {
                 }
             }
         } else {
-            let gas_limit = pre_submit_dry_run_gas_estimate_call(
+            let (gas_limit, dry_run_result) = pre_submit_dry_run_gas_estimate_call(
                 &call_exec,
                 self.output_json(),
                 self.extrinsic_cli_opts.skip_dry_run,
             )
             .await?;
+            let output = match dry_run_result {
+                Some(dry_run_result) => {
+                    ExtrinsicOutput::call_dry_run(
+                        ss58::display_account(call_exec.contract(), ss58_prefix),
+                        dry_run_result,
+                    )
+                }
+                None => ExtrinsicOutput::without_dry_run(),
+            };
+            if self.sudo {
+                ensure_sudo_key(
+                    call_exec.client(),
+                    &call_exec.opts().signer().account_id(),
+                )
+                .await?;
+            }
             if !self.extrinsic_cli_opts.skip_confirm {
-                prompt_confirm_tx(|| {
+                let context = TxContext::query::<C>(
+                    &chain.url(),
+                    self.extrinsic_cli_opts.chain_cli_opts.rpc_options(),
+                    &call_exec.opts().signer().account_id(),
+                    call_exec.estimate_fee(gas_limit),
+                    &token_metadata,
+                )
+                .await?;
+                prompt_confirm_tx(&context, || {
                     name_value_println!(
-                        "Message",
-                        call_exec.message(),
+                        "Contract",
+                        ss58::display_account(call_exec.contract(), ss58_prefix),
                         DEFAULT_KEY_COL_WIDTH
                     );
                     name_value_println!(
-                        "Args",
-                        call_exec.args().join(" "),
+                        "Message",
+                        decoded_message(&call_exec),
                         DEFAULT_KEY_COL_WIDTH
                     );
+                    name_value_println!("Value", self.value(), DEFAULT_KEY_COL_WIDTH);
                     name_value_println!(
                         "Gas limit",
                         gas_limit.to_string(),
                         DEFAULT_KEY_COL_WIDTH
                     );
+                    if let Some(when) = self.schedule_at {
+                        name_value_println!(
+                            "Scheduled",
+                            format!("at block {when}"),
+                            DEFAULT_KEY_COL_WIDTH
+                        );
+                        name_value_println!("Origin", "Signer", DEFAULT_KEY_COL_WIDTH);
+                    } else if self.sudo {
+                        name_value_println!("Origin", "Root", DEFAULT_KEY_COL_WIDTH);
+                    }
+                    if let Some(threshold) = self.as_proposal {
+                        name_value_println!(
+                            "Proposal",
+                            format!("{PROPOSAL_PALLET}, threshold {threshold}"),
+                            DEFAULT_KEY_COL_WIDTH
+                        );
+                    }
                 })?;
             }
-            let events = call_exec.call(Some(gas_limit)).await?;
-            let display_events =
-                DisplayEvents::from_events::<C, C>(&events, None, &metadata)?;
+            if let Some(when) = self.schedule_at {
+                let scheduled = call_exec.schedule(when, Some(gas_limit)).await?;
+                let display_events = DisplayEvents::from_events::<C, C>(
+                    &scheduled.events,
+                    None,
+                    &metadata,
+                    ss58_prefix,
+                )?;
+                let receipt = scheduled.receipt;
+                let explorer_links = ExplorerLinks::new(
+                    &self.extrinsic_cli_opts,
+                    &format!("{:?}", receipt.extrinsic_hash),
+                    &format!("{:?}", receipt.block_hash),
+                    Some(contract_str),
+                );
+                if self.output_json() {
+                    let mut submission = SubmissionOutput::new(
+                        &receipt,
+                        explorer_links,
+                        ss58::display_account(call_exec.contract(), ss58_prefix),
+                    );
+                    submission.scheduled = Some(ScheduledOutput {
+                        when: scheduled.when,
+                        index: scheduled.index,
+                    });
+                    let output = output.submitted(submission, display_events);
+                    println!("{}", output.to_json()?);
+                } else {
+                    let output = display_events.display_events::<C>(
+                        self.extrinsic_cli_opts.verbosity()?,
+                        &token_metadata,
+                    )?;
+                    println!("{output}");
+                    name_value_println!(
+                        "Task",
+                        format!("({}, {})", scheduled.when, scheduled.index),
+                        DEFAULT_KEY_COL_WIDTH
+                    );
+                    display_extrinsic_receipt(&receipt);
+                    explorer_links.print();
+                }
+                return Ok(())
+            }
+            let call_result = call_exec.call(Some(gas_limit)).await?;
+            let display_events = DisplayEvents::from_events::<C, C>(
+                &call_result.events,
+                None,
+                &metadata,
+                ss58_prefix,
+            )?
+            .filter(
+                &self
+                    .extrinsic_cli_opts
+                    .event_filter()
+                    .contract(call_exec.contract()),
+            );
+            let receipt = call_result.receipt;
+            let explorer_links = ExplorerLinks::new(
+                &self.extrinsic_cli_opts,
+                &format!("{:?}", receipt.extrinsic_hash),
+                &format!("{:?}", receipt.block_hash),
+                Some(contract_str),
+            );
 
-            let output = if self.output_json() {
-                display_events.to_json()?
+            if self.output_json() {
+                let submission = SubmissionOutput::new(
+                    &receipt,
+                    explorer_links,
+                    ss58::display_account(call_exec.contract(), ss58_prefix),
+                );
+                println!("{}", output.submitted(submission, display_events).to_json()?);
             } else {
-                display_events.display_events::<C>(
+                let output = display_events.display_events::<C>(
                     self.extrinsic_cli_opts.verbosity().unwrap(),
                     &token_metadata,
+                )?;
+                println!("{output}");
+                display_extrinsic_receipt(&receipt);
+                explorer_links.print();
+            }
+        }
+        Ok(())
+    }
+
+    /// Submits the calls of the `batch` file in a single `utility.batch_all`
+    /// extrinsic, or dry-runs them if not executing.
+    async fn run_batch<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        batch: &Path,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Display,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let contents = fs::read_to_string(batch)
+            .context(format!("Failed to read {}", batch.display()))?;
+        let batch_file: BatchFile = toml::from_str(&contents)
+            .context(format!("Failed to parse {}", batch.display()))?;
+        let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
+
+        let mut calls = Vec::with_capacity(batch_file.call.len());
+        for call in batch_file.call {
+            let address = resolve_contract(&call.contract, &chain)?;
+            let contract: <C as Config>::AccountId = parse_account(&address)
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to parse contract {}: {}", call.contract, e)
+                })?;
+            let value =
+                parse_balance(call.value.as_deref().unwrap_or("0"), &token_metadata)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse value: {}", e))?;
+            let extrinsic_opts = self.extrinsic_opts::<C>(
+                &token_metadata,
+                call.file.or_else(|| self.extrinsic_cli_opts.file.clone()),
+            )?;
+            let call_exec =
+                CallCommandBuilder::new(contract, &call.message, extrinsic_opts)
+                    .args(call.args)
+                    .gas_limit(call.gas)
+                    .proof_size(call.proof_size)
+                    .value(value)
+                    .done()
+                    .await?;
+            calls.push(call_exec);
+        }
+        let batch_exec = BatchCallExec::new(calls)?;
+        let metadata = batch_exec.calls()[0].client().metadata();
+        let ss58_prefix = batch_exec.calls()[0].ss58_prefix();
+
+        if !self.extrinsic_cli_opts.execute {
+            let results = batch_exec.call_dry_run().await?;
+            let mut dry_run_results = Vec::with_capacity(results.len());
+            let mut gas_required = Weight::zero();
+            for (i, (call_exec, result)) in
+                batch_exec.calls().iter().zip(results).enumerate()
+            {
+                let ret_val = match result.result {
+                    Ok(ref ret_val) => ret_val,
+                    Err(ref err) => {
+                        let object = ErrorVariant::from_dispatch_error(err, &metadata)?;
+                        if !self.output_json() {
+                            name_value_println!(
+                                "Call",
+                                format!("#{i} {}", call_exec.message())
+                            );
+                            name_value_println!("Result", object, MAX_KEY_COL_WIDTH);
+                            display_contract_exec_result::<_, MAX_KEY_COL_WIDTH, _>(
+                                &result,
+                            )?;
+                        }
+                        return Err(object)
+                    }
+                };
+                let value = call_exec
+                    .decode_return(&ret_val.data)
+                    .context(format!("Failed to decode return value {:?}", &ret_val))
+                    .map_err(ErrorVariant::decode)?;
+                let reverted = ret_val.did_revert();
+                gas_required = gas_required.saturating_add(result.gas_required);
+                dry_run_results.push(CallDryRunResult::new(
+                    value,
+                    call_exec.returns_lang_result(),
+                    reverted,
+                    &result,
+                ));
+            }
+            if self.output_json() {
+                let json_object = serde_json::json!({
+                    "calls": dry_run_results,
+                    "gas_required": gas_required,
+                });
+                println!("{}", serde_json::to_string_pretty(&json_object)?);
+            } else {
+                for (i, (call_exec, dry_run_result)) in
+                    batch_exec.calls().iter().zip(&dry_run_results).enumerate()
+                {
+                    name_value_println!(
+                        "Call",
+                        format!("#{i} {}", call_exec.message()),
+                        DEFAULT_KEY_COL_WIDTH
+                    );
+                    dry_run_result.print();
+                }
+                name_value_println!(
+                    "Total gas",
+                    gas_required.to_string(),
+                    DEFAULT_KEY_COL_WIDTH
+                );
+                display_dry_run_result_warning("batch");
+            }
+            if let Some(i) = dry_run_results.iter().position(|result| result.reverted) {
+                return Err(ErrorVariant::dry_run_revert(format!(
+                    "Call #{i} of the batch reverted"
+                )))
+            }
+        } else {
+            let mut gas_limits = Vec::with_capacity(batch_exec.calls().len());
+            for call_exec in batch_exec.calls() {
+                let (gas_limit, _) = pre_submit_dry_run_gas_estimate_call(
+                    call_exec,
+                    self.output_json(),
+                    self.extrinsic_cli_opts.skip_dry_run,
+                )
+                .await?;
+                gas_limits.push(gas_limit);
+            }
+            let first = &batch_exec.calls()[0];
+            if self.sudo {
+                ensure_sudo_key(first.client(), &first.opts().signer().account_id())
+                    .await?;
+            }
+            if !self.extrinsic_cli_opts.skip_confirm {
+                let context = TxContext::query::<C>(
+                    &chain.url(),
+                    self.extrinsic_cli_opts.chain_cli_opts.rpc_options(),
+                    &first.opts().signer().account_id(),
+                    batch_exec.estimate_fee(&gas_limits),
+                    &token_metadata,
+                )
+                .await?;
+                prompt_confirm_tx(&context, || {
+                    for (i, (call_exec, gas_limit)) in
+                        batch_exec.calls().iter().zip(&gas_limits).enumerate()
+                    {
+                        name_value_println!(
+                            "Call",
+                            format!("#{i} {}", decoded_message(call_exec)),
+                            DEFAULT_KEY_COL_WIDTH
+                        );
+                        name_value_println!(
+                            "Contract",
+                            ss58::display_account(call_exec.contract(), ss58_prefix),
+                            DEFAULT_KEY_COL_WIDTH
+                        );
+                        name_value_println!(
+                            "Gas limit",
+                            gas_limit.to_string(),
+                            DEFAULT_KEY_COL_WIDTH
+                        );
+                    }
+                })?;
+            }
+            let batch_result = batch_exec.call(Some(gas_limits)).await?;
+
+            // every call decodes the events with the transcoder of its own contract
+            let mut call_events = Vec::with_capacity(batch_exec.calls().len());
+            let mut remaining_events = None;
+            for (i, call_exec) in batch_exec.calls().iter().enumerate() {
+                let (mut items, rest) = DisplayEvents::from_events::<C, C>(
+                    &batch_result.events,
+                    call_exec.transcoder(),
+                    &metadata,
+                    ss58_prefix,
                 )?
-            };
-            println!("{output}");
+                .split_batch_items();
+                if i >= items.len() {
+                    return Err(
+                        anyhow!("No events found for call #{i} of the batch").into()
+                    )
+                }
+                let events = items.swap_remove(i).filter(
+                    &self
+                        .extrinsic_cli_opts
+                        .event_filter()
+                        .contract(call_exec.contract()),
+                );
+                call_events.push(events);
+                remaining_events.get_or_insert(rest);
+            }
+            let remaining_events = remaining_events.expect("a batch is not empty");
+            let receipt = batch_result.receipt;
+            let explorer_links = ExplorerLinks::new(
+                &self.extrinsic_cli_opts,
+                &format!("{:?}", receipt.extrinsic_hash),
+                &format!("{:?}", receipt.block_hash),
+                None,
+            );
+
+            if self.output_json() {
+                let calls = batch_exec
+                    .calls()
+                    .iter()
+                    .zip(&call_events)
+                    .map(|(call_exec, events)| {
+                        let contract =
+                            ss58::display_account(call_exec.contract(), ss58_prefix);
+                        serde_json::json!({
+                            "contract": contract,
+                            "message": call_exec.message(),
+                            "events": events,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let json_object = serde_json::json!({
+                    "calls": calls,
+                    "events": remaining_events,
+                    "extrinsic": receipt,
+                    "explorer_links": explorer_links,
+                });
+                println!("{}", serde_json::to_string_pretty(&json_object)?);
+            } else {
+                let verbosity = self.extrinsic_cli_opts.verbosity()?;
+                for (i, (call_exec, events)) in
+                    batch_exec.calls().iter().zip(&call_events).enumerate()
+                {
+                    name_value_println!(
+                        "Call",
+                        format!("#{i} {}", call_exec.message()),
+                        DEFAULT_KEY_COL_WIDTH
+                    );
+                    println!(
+                        "{}",
+                        events.display_events::<C>(verbosity, &token_metadata)?
+                    );
+                }
+                println!(
+                    "{}",
+                    remaining_events.display_events::<C>(verbosity, &token_metadata)?
+                );
+                display_extrinsic_receipt(&receipt);
+                explorer_links.print();
+            }
         }
         Ok(())
     }
+
+    /// Returns the options of the extrinsics of the command, with the contract artifact
+    /// `file`.
+    fn extrinsic_opts<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        token_metadata: &TokenMetadata,
+        file: Option<PathBuf>,
+    ) -> Result<ExtrinsicOpts<C, C, C::Signer>>
+    where
+        C::Balance: TryFrom<u128> + FromStr,
+    {
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let storage_deposit_limit = self
+            .extrinsic_cli_opts
+            .storage_deposit_limit
+            .clone()
+            .map(|b| parse_balance(&b, token_metadata))
+            .transpose()
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to parse storage_deposit_limit option: {}", e)
+            })?;
+        let mut builder = ExtrinsicOptsBuilder::new(signer)
+            .file(file)
+            .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
+            .url(self.extrinsic_cli_opts.chain_cli_opts.chain().url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
+            .storage_deposit_limit(storage_deposit_limit)
+            .unlimited_deposit(self.extrinsic_cli_opts.unlimited_deposit())
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(token_metadata)?)
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider())
+            .progress(self.extrinsic_cli_opts.progress()?)
+            .verbosity(self.extrinsic_cli_opts.verbosity()?);
+        if self.sudo {
+            builder = builder.wrap_call(call_wrapper::sudo);
+        }
+        if let Some(threshold) = self.as_proposal {
+            builder = builder.wrap_call(call_wrapper::proposal(
+                PROPOSAL_PALLET,
+                threshold,
+                PROPOSAL_LENGTH_BOUND,
+            ));
+        }
+        Ok(builder.done())
+    }
+}
+
+/// Returns the message and the arguments of the call as decoded from its call data,
+/// falling back to the message and the arguments as passed if they can't be decoded.
+fn decoded_message<C: Config + Environment, Signer>(
+    call_exec: &CallExec<C, C, Signer>,
+) -> String
+where
+    Signer: subxt::tx::Signer<C> + Clone,
+    <C as Config>::AccountId: IntoVisitor + EncodeAsType,
+    C::Balance: EncodeAsType,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    // contracts described by a Solidity ABI have no ink! metadata to decode with
+    call_exec
+        .transcoder()
+        .and_then(|transcoder| {
+            transcoder
+                .decode_contract_message(&mut &call_exec.call_data()[..])
+                .ok()
+        })
+        .map(|message| message.to_string())
+        .unwrap_or_else(|| {
+            format!("{} {}", call_exec.message(), call_exec.args().join(" "))
+        })
 }
 
 /// A helper function to estimate the gas required for a contract call.
+///
+/// Also returns the result of the dry-run, unless it was skipped or its return value
+/// could not be decoded.
 async fn pre_submit_dry_run_gas_estimate_call<C: Config + Environment, Signer>(
     call_exec: &CallExec<C, C, Signer>,
     output_json: bool,
     skip_dry_run: bool,
-) -> Result<Weight>
+) -> Result<(Weight, Option<CallDryRunResult<C::Balance>>)>
 where
     Signer: subxt::tx::Signer<C> + Clone,
     <C as Config>::AccountId: IntoVisitor + EncodeAsType,
-    C::Balance: Debug + EncodeAsType,
+    C::Balance: Debug + EncodeAsType + Serialize,
     <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
         From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
 {
     if skip_dry_run {
         return match (call_exec.gas_limit(), call_exec.proof_size()) {
-            (Some(ref_time), Some(proof_size)) => Ok(Weight::from_parts(ref_time, proof_size)),
+            (Some(ref_time), Some(proof_size)) => Ok((Weight::from_parts(ref_time, proof_size), None)),
             _ => {
                 Err(anyhow!(
                 "Weight args `--gas` and `--proof-size` required if `--skip-dry-run` specified"
@@ -275,10 +1010,18 @@ where
     }
     let call_result = call_exec.call_dry_run().await?;
     match call_result.result {
-        Ok(_) => {
+        Ok(ref ret_val) => {
             if !output_json {
                 print_gas_required_success(call_result.gas_required);
             }
+            let dry_run_result = call_exec.decode_return(&ret_val.data).ok().map(|value| {
+                CallDryRunResult::new(
+                    value,
+                    call_exec.returns_lang_result(),
+                    ret_val.did_revert(),
+                    &call_result,
+                )
+            });
             // use user specified values where provided, otherwise use the estimates
             let ref_time = call_exec
                 .gas_limit()
@@ -286,7 +1029,7 @@ where
             let proof_size = call_exec
                 .proof_size()
                 .unwrap_or_else(|| call_result.gas_required.proof_size());
-            Ok(Weight::from_parts(ref_time, proof_size))
+            Ok((Weight::from_parts(ref_time, proof_size), dry_run_result))
         }
         Err(ref err) => {
             let object =
@@ -308,17 +1051,40 @@ where
 pub struct CallDryRunResult<Balance> {
     /// Was the operation reverted
     pub reverted: bool,
+    /// The decoded return value, without the `Result<_, LangError>` wrapper of ink!
+    /// messages
     pub data: Value,
+    /// The error returned by the message, if it reverted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ReturnError>,
     pub gas_consumed: Weight,
     pub gas_required: Weight,
     /// Storage deposit after the operation
     pub storage_deposit: StorageDeposit<Balance>,
 }
 
-impl<Balance: Serialize> CallDryRunResult<Balance> {
-    /// Returns a result in json format
-    pub fn to_json(&self) -> Result<String> {
-        Ok(serde_json::to_string_pretty(self)?)
+impl<Balance: Serialize + Clone> CallDryRunResult<Balance> {
+    /// Creates the result of the dry-run `result` from its decoded return `value`.
+    ///
+    /// `lang_result` is whether `value` is wrapped in the `Result<_, LangError>` of the
+    /// ink! dispatcher.
+    pub fn new(
+        value: Value,
+        lang_result: bool,
+        reverted: bool,
+        result: &ContractExecResult<Balance>,
+    ) -> Self {
+        let error = reverted
+            .then(|| ReturnError::from_return_value(&value, lang_result))
+            .flatten();
+        Self {
+            reverted,
+            data: unwrap_lang_result(value, lang_result),
+            error,
+            gas_consumed: result.gas_consumed,
+            gas_required: result.gas_required,
+            storage_deposit: result.storage_deposit.clone(),
+        }
     }
 
     pub fn print(&self) {
@@ -328,5 +1094,81 @@ impl<Balance: Serialize> CallDryRunResult<Balance> {
             format!("{:?}", self.reverted),
             DEFAULT_KEY_COL_WIDTH
         );
+        if let Some(error) = &self.error {
+            name_value_println!("Error", error.to_string(), DEFAULT_KEY_COL_WIDTH);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        call: CallCommand,
+    }
+
+    #[test]
+    fn options_override_call_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.toml");
+        let file = CallFile {
+            contract: "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+            message: "transfer".to_string(),
+            args: vec!["@bob".to_string(), "10".to_string()],
+            value: Some("1".to_string()),
+            gas: Some(100),
+            proof_size: None,
+            storage_deposit_limit: Some("5".to_string()),
+        };
+        file.save(&path).unwrap();
+        assert_eq!(CallFile::load(&path).unwrap(), file);
+        let call_file = path.to_str().unwrap();
+
+        let cli = Cli::try_parse_from([
+            "call",
+            "--call-file",
+            call_file,
+            "--args",
+            "@charlie",
+            "20",
+            "--proof-size",
+            "50",
+            "--suri",
+            "//Alice",
+        ])
+        .unwrap();
+        let call = cli.call.with_call_file().unwrap();
+
+        assert_eq!(
+            call.call_file(),
+            CallFile {
+                args: vec!["@charlie".to_string(), "20".to_string()],
+                proof_size: Some(50),
+                ..file
+            }
+        );
+    }
+
+    #[test]
+    fn schedule_at_requires_sudo() {
+        let call = [
+            "call",
+            "--contract",
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+            "--message",
+            "flip",
+            "--suri",
+            "//Alice",
+            "--schedule-at",
+            "100",
+        ];
+
+        let err = Cli::try_parse_from(call).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        assert!(Cli::try_parse_from([&call[..], &["--sudo"]].concat()).is_ok());
     }
 }
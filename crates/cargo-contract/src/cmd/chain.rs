@@ -0,0 +1,386 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    config::SignerConfig,
+    CLIChainOpts,
+    ProductionChain,
+    Suri,
+};
+use anyhow::Result;
+use clap::{
+    Args,
+    Subcommand,
+    ValueEnum,
+};
+use colored::Colorize;
+use contract_build::{
+    name_value_println,
+    util::DEFAULT_KEY_COL_WIDTH,
+};
+use contract_extrinsics::{
+    pallet_contracts_primitives::StorageDeposit,
+    BalanceVariant,
+    ContractArtifacts,
+    ExtrinsicOptsBuilder,
+    FeeParameters,
+    InstantiateCommandBuilder,
+    TokenMetadata,
+    UploadCommandBuilder,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use sp_weights::Weight;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    path::PathBuf,
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        codec::Decode,
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    Config,
+};
+use url::Url;
+
+#[derive(Debug, Args)]
+pub struct ChainCommand {
+    #[clap(subcommand)]
+    command: ChainSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ChainSubcommand {
+    /// Report the fees of a chain and the cost of uploading and instantiating the
+    /// contract on it
+    #[clap(name = "fees")]
+    Fees(ChainFeesCommand),
+}
+
+/// Reports the fee parameters of a chain, i.e. the fees of execution time and
+/// extrinsic length and the fee multiplier, which rises while blocks are congested.
+///
+/// If the contract is built, it also reports the approximate fees and storage deposits
+/// of uploading and instantiating it, based on the size of its code and the weight
+/// required by a dry-run of its constructor.
+#[derive(Debug, Args)]
+pub struct ChainFeesCommand {
+    /// Path to the contract build artifact file: a `.contract` bundle or a `.wasm`
+    /// file.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Secret key URI of the account the dry-runs originate from.
+    #[clap(name = "suri", long, short, default_value = "//Alice")]
+    suri: Suri,
+    /// The name of the contract constructor to dry-run.
+    #[clap(name = "constructor", long, default_value = "new")]
+    constructor: String,
+    /// The arguments of the contract constructor to dry-run.
+    #[clap(long, num_args = 0..)]
+    args: Vec<String>,
+    /// Report the fee parameters of all production chains instead, without the costs
+    /// of the contract.
+    #[clap(long, conflicts_with_all = ["chain", "url"])]
+    all_chains: bool,
+    /// Export the fees as JSON.
+    #[clap(long)]
+    output_json: bool,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+/// The fees of a chain and the costs of the contract on it.
+#[derive(Debug, Serialize)]
+struct FeeReport {
+    chain: String,
+    #[serde(flatten)]
+    parameters: FeeParameters,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contract: Option<ContractCosts>,
+}
+
+/// The approximate costs of uploading and instantiating the contract.
+#[derive(Debug, Serialize)]
+struct ContractCosts {
+    /// The size of the code in bytes.
+    code_size: usize,
+    upload_fee: u128,
+    upload_deposit: u128,
+    /// The fee of instantiating the contract, including uploading its code if it is not
+    /// stored on the chain yet.
+    instantiate_fee: u128,
+    instantiate_gas: Weight,
+    instantiate_deposit: u128,
+}
+
+impl ChainCommand {
+    /// Returns whether to export the output in JSON format.
+    pub fn output_json(&self) -> bool {
+        match &self.command {
+            ChainSubcommand::Fees(fees) => fees.output_json,
+        }
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        match &self.command {
+            ChainSubcommand::Fees(fees) => fees.handle().await,
+        }
+    }
+}
+
+impl ChainFeesCommand {
+    async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId:
+            IntoVisitor + FromStr + EncodeAsType + Decode + Display,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Into<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+        <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
+    {
+        if self.all_chains {
+            let mut reports = Vec::new();
+            for chain in ProductionChain::value_variants() {
+                match self.query_fees::<C>(&chain.url()).await {
+                    Ok((parameters, token_metadata)) => {
+                        let report = FeeReport {
+                            chain: chain.to_string(),
+                            parameters,
+                            contract: None,
+                        };
+                        if !self.output_json {
+                            report.print(&token_metadata)?;
+                            println!();
+                        }
+                        reports.push(report);
+                    }
+                    Err(err) => {
+                        contract_build::output::diagnostic(format_args!(
+                            "{} Failed to query the fees of {}: {}",
+                            "Error:".red().bold(),
+                            chain,
+                            err
+                        ));
+                    }
+                }
+            }
+            if self.output_json {
+                println!("{}", serde_json::to_string_pretty(&reports)?);
+            }
+            return Ok(())
+        }
+
+        let chain = self.chain_cli_opts.chain();
+        let (parameters, token_metadata) = self.query_fees::<C>(&chain.url()).await?;
+        let contract = match ContractArtifacts::from_manifest_or_file(
+            self.manifest_path.as_ref(),
+            self.file.as_ref(),
+        ) {
+            Ok(artifacts) if artifacts.code.is_some() => {
+                Some(self.contract_costs::<C>(&chain.url()).await?)
+            }
+            _ => {
+                contract_build::output::diagnostic(format_args!(
+                    "{} Reporting the chain fees only: no contract code artifact found",
+                    "Info:".cyan().bold(),
+                ));
+                None
+            }
+        };
+        let report = FeeReport {
+            chain: match chain.production() {
+                Some(prod) => prod.to_string(),
+                None => chain.url().to_string(),
+            },
+            parameters,
+            contract,
+        };
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            report.print(&token_metadata)?;
+        }
+        Ok(())
+    }
+
+    /// Queries the fee parameters and the token metadata of the chain at `url`.
+    async fn query_fees<C: Config>(
+        &self,
+        url: &Url,
+    ) -> Result<(FeeParameters, TokenMetadata)> {
+        let options = self.chain_cli_opts.rpc_options();
+        let parameters = FeeParameters::query::<C>(url, options.clone()).await?;
+        let token_metadata = TokenMetadata::query_with_options::<C>(url, options).await?;
+        Ok((parameters, token_metadata))
+    }
+
+    /// Estimates the costs of uploading and instantiating the contract on the chain at
+    /// `url` with dry-runs.
+    async fn contract_costs<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        url: &Url,
+    ) -> Result<ContractCosts, ErrorVariant>
+    where
+        <C as Config>::AccountId:
+            IntoVisitor + FromStr + EncodeAsType + Decode + Display,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Into<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+        <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
+    {
+        let signer = self.suri.signer::<C>()?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::<C, C, _>::new(signer)
+            .file(self.file.clone())
+            .manifest_path(self.manifest_path.clone())
+            .url(url.clone())
+            .rpc_options(self.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.chain_cli_opts.ss58_prefix())
+            .done();
+
+        let upload_exec = UploadCommandBuilder::new(extrinsic_opts.clone())
+            .done()
+            .await?;
+        let code_size = upload_exec.code().as_bytes().len();
+        let upload_deposit = match upload_exec.upload_code_rpc().await? {
+            Ok(result) => result.deposit.into(),
+            Err(err) => {
+                let metadata = upload_exec.client().metadata();
+                return Err(ErrorVariant::from_dispatch_error(&err, &metadata)?)
+            }
+        };
+        let upload_fee = upload_exec.estimate_fee().await?;
+
+        let instantiate_exec = InstantiateCommandBuilder::new(extrinsic_opts)
+            .constructor(self.constructor.clone())
+            .args(self.args.clone())
+            .done()
+            .await?;
+        let result = instantiate_exec.instantiate_dry_run().await?;
+        if let Err(err) = result.result {
+            let metadata = instantiate_exec.client().metadata();
+            return Err(ErrorVariant::from_dispatch_error(&err, &metadata)?)
+        }
+        let instantiate_deposit = match result.storage_deposit {
+            StorageDeposit::Charge(charge) => charge.into(),
+            StorageDeposit::Refund(_) => 0,
+        };
+        let instantiate_fee = instantiate_exec.estimate_fee(result.gas_required).await?;
+
+        Ok(ContractCosts {
+            code_size,
+            upload_fee,
+            upload_deposit,
+            instantiate_fee,
+            instantiate_gas: result.gas_required,
+            instantiate_deposit,
+        })
+    }
+}
+
+impl FeeReport {
+    fn print(&self, token_metadata: &TokenMetadata) -> Result<()> {
+        let balance = |amount: u128| -> Result<String> {
+            Ok(BalanceVariant::<u128>::from(amount, Some(token_metadata))?.to_string())
+        };
+        name_value_println!("Chain", self.chain, DEFAULT_KEY_COL_WIDTH);
+        name_value_println!(
+            "Fee per second",
+            balance(self.parameters.weight_fee_per_second)?,
+            DEFAULT_KEY_COL_WIDTH
+        );
+        name_value_println!(
+            "Fee per KiB",
+            balance(self.parameters.length_fee_per_kib)?,
+            DEFAULT_KEY_COL_WIDTH
+        );
+        name_value_println!(
+            "Multiplier",
+            format!("{:.6}", self.parameters.fee_multiplier),
+            DEFAULT_KEY_COL_WIDTH
+        );
+        if let Some(contract) = &self.contract {
+            name_value_println!(
+                "Code size",
+                format!("{:.1} KiB", contract.code_size as f64 / 1024.0),
+                DEFAULT_KEY_COL_WIDTH
+            );
+            name_value_println!(
+                "Upload",
+                format!(
+                    "{} fee, {} deposit",
+                    balance(contract.upload_fee)?,
+                    balance(contract.upload_deposit)?
+                ),
+                DEFAULT_KEY_COL_WIDTH
+            );
+            name_value_println!(
+                "Instantiate",
+                format!(
+                    "{} fee, {} deposit",
+                    balance(contract.instantiate_fee)?,
+                    balance(contract.instantiate_deposit)?
+                ),
+                DEFAULT_KEY_COL_WIDTH
+            );
+            name_value_println!(
+                "Gas required",
+                format!("{}", contract.instantiate_gas),
+                DEFAULT_KEY_COL_WIDTH
+            );
+        }
+        Ok(())
+    }
+}
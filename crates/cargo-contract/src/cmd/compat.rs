@@ -0,0 +1,69 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use contract_build::name_value_println;
+use contract_extrinsics::ContractArtifacts;
+use contract_transcode::{
+    check_compatibility,
+    ink_metadata::InkProject,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    name = "check-compat",
+    about = "Check whether a contract can be upgraded to a new version with `set_code_hash`"
+)]
+pub struct CheckCompatCommand {
+    /// Path to the `.contract` bundle or `.json` metadata of the deployed version.
+    #[clap(long, value_parser)]
+    old: PathBuf,
+    /// Path to the `.contract` bundle or `.json` metadata of the new version.
+    #[clap(long, value_parser)]
+    new: PathBuf,
+    /// Export the result as JSON.
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl CheckCompatCommand {
+    pub fn run(&self) -> Result<()> {
+        let report = check_compatibility(&load(&self.old)?, &load(&self.new)?);
+
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else if report.is_compatible() {
+            name_value_println!("Compatible", "yes");
+        } else {
+            for incompatibility in &report.incompatibilities {
+                name_value_println!("Incompatible", incompatibility.to_string());
+            }
+        }
+        if !report.is_compatible() {
+            anyhow::bail!("The new version is incompatible with the deployed one")
+        }
+        Ok(())
+    }
+}
+
+fn load(path: &Path) -> Result<InkProject> {
+    ContractArtifacts::from_manifest_or_file(None, Some(&path.into()))?
+        .ink_project_metadata()
+}
@@ -0,0 +1,168 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use clap::CommandFactory as _;
+use clap_complete::Shell;
+use contract_build::{
+    CrateMetadata,
+    Target,
+};
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+};
+
+/// Name of the executable the completions are generated for.
+const BIN_NAME: &str = "cargo-contract";
+
+/// Extensions of the contract artifacts offered as completions.
+const ARTIFACT_EXTENSIONS: [&str; 4] = ["contract", "json", "wasm", "riscv"];
+
+/// Completes the artifacts in `target/ink` for positional paths and artifact options.
+const BASH_ARTIFACTS: &str = r#"
+_cargo-contract-artifacts() {
+    _cargo-contract "$@"
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local words=" ${COMP_WORDS[*]} "
+    case "${prev}" in
+        --wasm|--abi|--bundle|--metadata)
+            ;;
+        --contract)
+            [[ "${words}" == *" verify "* ]] || return 0
+            ;;
+        *)
+            [[ "${cur}" == -* || "${prev}" == -* ]] && return 0
+            case "${words}" in
+                *" upload "*|*" instantiate "*|*" remove "*|*" verify-bundle "*|*" publish "*|*" convert "*|*" hash "*)
+                    ;;
+                *)
+                    return 0
+                    ;;
+            esac
+            ;;
+    esac
+    COMPREPLY+=($(compgen -W "$(cargo-contract contract completions --list-artifacts 2>/dev/null)" -- "${cur}"))
+}
+
+complete -F _cargo-contract-artifacts -o bashdefault -o default cargo-contract
+"#;
+
+/// Completes the artifacts in `target/ink` for positional paths and artifact options.
+const FISH_ARTIFACTS: &str = r#"
+function __cargo_contract_artifacts
+    cargo-contract contract completions --list-artifacts 2>/dev/null
+end
+complete -c cargo-contract -n "__fish_seen_subcommand_from upload instantiate remove verify-bundle publish convert hash" -k -a "(__cargo_contract_artifacts)"
+complete -c cargo-contract -n "__fish_seen_subcommand_from verify" -l contract -l wasm -r -k -a "(__cargo_contract_artifacts)"
+complete -c cargo-contract -n "__fish_seen_subcommand_from call instantiate" -l abi -r -k -a "(__cargo_contract_artifacts)"
+complete -c cargo-contract -n "__fish_seen_subcommand_from verify-schema" -l bundle -l metadata -r -k -a "(__cargo_contract_artifacts)"
+"#;
+
+/// Generates shell completions for `cargo-contract`.
+#[derive(Debug, clap::Args)]
+#[clap(name = "completions")]
+pub struct CompletionsCommand {
+    /// The shell to generate the completions for.
+    #[clap(required_unless_present = "list_artifacts")]
+    shell: Option<Shell>,
+    /// List the contract artifacts in `target/ink`, used by the generated completions.
+    #[clap(long, hide = true, conflicts_with = "shell")]
+    list_artifacts: bool,
+}
+
+impl CompletionsCommand {
+    pub fn run(&self) -> Result<()> {
+        let mut stdout = std::io::stdout();
+        if self.list_artifacts {
+            for artifact in artifacts() {
+                writeln!(stdout, "{}", artifact.display())?;
+            }
+            return Ok(())
+        }
+        let shell = self.shell.expect("required unless listing artifacts");
+        generate(shell, &mut stdout)
+    }
+}
+
+/// Writes the completions for `shell` to `out`.
+fn generate(shell: Shell, out: &mut impl Write) -> Result<()> {
+    clap_complete::generate(shell, &mut crate::Opts::command(), BIN_NAME, out);
+    match shell {
+        Shell::Bash => out.write_all(BASH_ARTIFACTS.as_bytes())?,
+        Shell::Fish => out.write_all(FISH_ARTIFACTS.as_bytes())?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns the contract artifacts of the contract in the current directory, or in
+/// `target/ink` if there is none.
+fn artifacts() -> Vec<PathBuf> {
+    let dir = CrateMetadata::from_manifest_path(None, Target::Wasm)
+        .ok()
+        .and_then(|crate_metadata| {
+            crate_metadata
+                .contract_bundle_path()
+                .parent()
+                .map(ToOwned::to_owned)
+        })
+        .unwrap_or_else(|| PathBuf::from("target").join("ink"));
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new()
+    };
+    let mut artifacts = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| {
+                    ARTIFACT_EXTENSIONS.iter().any(|artifact| ext == *artifact)
+                })
+        })
+        .collect::<Vec<_>>();
+    artifacts.sort();
+    artifacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completions(shell: Shell) -> String {
+        let mut out = Vec::new();
+        generate(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn bash_completions_include_subcommands_and_artifacts() {
+        let bash = completions(Shell::Bash);
+
+        assert!(bash.contains("cargo__contract__contract__build"));
+        assert!(bash.contains("aleph-zero"));
+        assert!(bash.ends_with(BASH_ARTIFACTS));
+    }
+
+    #[test]
+    fn zsh_completions_are_generated() {
+        let zsh = completions(Shell::Zsh);
+
+        assert!(zsh.starts_with("#compdef cargo-contract"));
+        assert!(zsh.contains("verify-bundle"));
+    }
+}
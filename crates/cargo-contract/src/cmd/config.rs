@@ -108,6 +108,37 @@ impl SignerConfig<Self> for Substrate {
     type Signer = SignerSR25519<Self>;
 }
 
+/// A runtime configuration for a Substrate based chain with a `u64` balance, e.g. of a
+/// custom ink! `Environment`.
+/// This thing is not meant to be instantiated; it is just a collection of types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubstrateU64 {}
+
+impl Config for SubstrateU64 {
+    type Hash = <SubstrateConfig as Config>::Hash;
+    type AccountId = <SubstrateConfig as Config>::AccountId;
+    type Address = <SubstrateConfig as Config>::Address;
+    type Signature = <SubstrateConfig as Config>::Signature;
+    type Hasher = <SubstrateConfig as Config>::Hasher;
+    type Header = <SubstrateConfig as Config>::Header;
+    type ExtrinsicParams = SubstrateExtrinsicParams<Self>;
+    type AssetId = <SubstrateConfig as Config>::AssetId;
+}
+
+impl Environment for SubstrateU64 {
+    const MAX_EVENT_TOPICS: usize = <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+    type AccountId = <DefaultEnvironment as Environment>::AccountId;
+    type Balance = u64;
+    type Hash = <DefaultEnvironment as Environment>::Hash;
+    type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+    type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+    type ChainExtension = <DefaultEnvironment as Environment>::ChainExtension;
+}
+
+impl SignerConfig<Self> for SubstrateU64 {
+    type Signer = SignerSR25519<Self>;
+}
+
 /// A runtime configuration for the Polkadot based chain.
 /// This thing is not meant to be instantiated; it is just a collection of types.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -244,7 +275,8 @@ macro_rules! call_with_config {
             // All available chain configs need to be specified here
             $crate::cmd::config::Polkadot,
             $crate::cmd::config::Substrate,
-            $crate::cmd::config::Ecdsachain
+            $crate::cmd::config::Ecdsachain,
+            $crate::cmd::config::SubstrateU64
         )
     }};
 }
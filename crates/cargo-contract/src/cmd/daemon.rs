@@ -0,0 +1,54 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::RpcCliOpts;
+use anyhow::Result;
+use contract_build::output;
+use contract_extrinsics::{
+    daemon::{
+        self,
+        DAEMON_SOCKET_ENV,
+    },
+    RpcOptions,
+};
+use std::path::PathBuf;
+
+/// Keeps the connections to nodes open, and shares them with other invocations.
+#[derive(Debug, clap::Args)]
+#[clap(name = "daemon")]
+pub struct DaemonCommand {
+    /// Path of the socket to listen on.
+    ///
+    /// Defaults to `daemon.sock` in the directory `cargo-contract` in `$XDG_RUNTIME_DIR`,
+    /// or in the directory `cargo-contract-<uid>` in the temporary directory if that is
+    /// not set. The directory is created only accessible by the current user.
+    #[clap(long, env = DAEMON_SOCKET_ENV)]
+    socket: Option<PathBuf>,
+    #[clap(flatten)]
+    rpc_cli_opts: RpcCliOpts,
+}
+
+impl DaemonCommand {
+    pub async fn run(&self) -> Result<()> {
+        let socket = self.socket.clone().unwrap_or_else(daemon::socket_path);
+        let options = RpcOptions::from(&self.rpc_cli_opts);
+        output::diagnostic(format_args!(
+            "Listening on {}, press Ctrl-C to stop",
+            socket.display()
+        ));
+        daemon::serve(&socket, options).await
+    }
+}
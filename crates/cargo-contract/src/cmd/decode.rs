@@ -14,8 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::DEFAULT_KEY_COL_WIDTH;
+use super::CLIChainOpts;
 use anyhow::{
+    anyhow,
     Context,
     Result,
 };
@@ -23,12 +24,29 @@ use clap::{
     Args,
     Subcommand,
 };
-use colored::Colorize as _;
 use contract_build::{
+    name_value_println,
+    output,
     util,
     CrateMetadata,
 };
-use contract_transcode::ContractMessageTranscoder;
+use contract_extrinsics::{
+    decode_contract_calls,
+    online_client,
+    rpc_client,
+    url_to_string,
+    ContractArtifacts,
+    ContractCallData,
+};
+use contract_transcode::{
+    ContractMessageTranscoder,
+    Value,
+};
+use std::path::PathBuf;
+use subxt::{
+    backend::legacy::LegacyRpcMethods,
+    SubstrateConfig,
+};
 
 #[derive(Debug, Args)]
 pub struct DecodeCommand {
@@ -47,6 +65,10 @@ pub enum DecodeCommands {
     /// Decode an event as input
     #[clap(name = "event")]
     Event(DecodeEvent),
+    /// Decode the calls of contracts in an extrinsic, with the runtime metadata of the
+    /// chain
+    #[clap(name = "extrinsic")]
+    Extrinsic(DecodeExtrinsic),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -74,8 +96,124 @@ pub struct DecodeEvent {
     data: String,
 }
 
+#[derive(Debug, Args)]
+#[clap(group(clap::ArgGroup::new("source").required(true).args(["data", "block"])))]
+pub struct DecodeExtrinsic {
+    /// The extrinsic to decode, or only its call, e.g. of a multisig operation or a
+    /// proposal; this has to be a hex value starting with `0x`.
+    #[clap(short, long)]
+    data: Option<String>,
+    /// The number of the block containing the extrinsic to decode.
+    #[clap(long, requires = "index")]
+    block: Option<u32>,
+    /// The index of the extrinsic to decode in the `--block`.
+    #[clap(long, requires = "block")]
+    index: Option<usize>,
+    /// Path to a contract build artifact file of the called contract: a `.contract`
+    /// bundle or a `.json` metadata file.
+    #[clap(long, value_parser)]
+    file: Option<PathBuf>,
+    /// Export the decoded calls in JSON format.
+    #[clap(long)]
+    output_json: bool,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+/// A call of a contract decoded with the metadata of the contract.
+#[derive(Debug, serde::Serialize)]
+struct DecodedContractCall {
+    /// The outer calls the call of the contract is wrapped into, outermost first.
+    wrapped_in: Vec<String>,
+    contract: String,
+    value: u128,
+    message: Value,
+}
+
+impl DecodeExtrinsic {
+    fn run(&self) -> Result<()> {
+        let artifacts =
+            ContractArtifacts::from_manifest_or_file(None, self.file.as_ref())?;
+        let (calls, ss58_prefix) =
+            tokio::runtime::Runtime::new()?.block_on(self.fetch_calls())?;
+        let transcoder = artifacts.contract_transcoder()?.with_ss58_prefix(ss58_prefix);
+        if calls.is_empty() {
+            return Err(anyhow!("The extrinsic contains no call of a contract"))
+        }
+        let calls = calls
+            .into_iter()
+            .map(|call| {
+                let message = transcoder
+                    .decode_contract_message(&mut &call.data[..])
+                    .with_context(|| {
+                        format!(
+                            "Failed to decode the call of contract {}",
+                            call.contract_address(ss58_prefix)
+                        )
+                    })?;
+                Ok(DecodedContractCall {
+                    contract: call.contract_address(ss58_prefix),
+                    wrapped_in: call.wrapped_in,
+                    value: call.value,
+                    message,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&calls)?);
+            return Ok(())
+        }
+        for call in calls {
+            if !call.wrapped_in.is_empty() {
+                name_value_println!("Wrapped in", call.wrapped_in.join(" > "));
+            }
+            name_value_println!("Contract", call.contract);
+            name_value_println!("Value", call.value.to_string());
+            name_value_println!("Message", call.message);
+        }
+        Ok(())
+    }
+
+    /// Decodes the calls of contracts in the extrinsic passed with `--data`, or in the
+    /// extrinsic at `--index` of the `--block`.
+    ///
+    /// Returns the calls along with the SS58 prefix their accounts are displayed with.
+    async fn fetch_calls(&self) -> Result<(Vec<ContractCallData>, u16)> {
+        let options = self.chain_cli_opts.rpc_options();
+        let url = url_to_string(&self.chain_cli_opts.chain().url());
+        let rpc_cli = rpc_client(&url, options.clone()).await?;
+        let client = online_client::<SubstrateConfig>(rpc_cli.clone(), options).await?;
+        let metadata = client.metadata();
+        let ss58_prefix = self.chain_cli_opts.ss58_prefix_for(Some(&metadata));
+
+        if let Some(data) = &self.data {
+            let bytes = util::decode_hex(data)
+                .context("Failed to decode specified data as a hex value")?;
+            return Ok((decode_contract_calls(&metadata, &bytes)?, ss58_prefix))
+        }
+        let (Some(block), Some(index)) = (self.block, self.index) else {
+            return Err(anyhow!("Either --data or --block and --index are required"))
+        };
+        let block_hash = LegacyRpcMethods::<SubstrateConfig>::new(rpc_cli)
+            .chain_get_block_hash(Some(block.into()))
+            .await?
+            .ok_or_else(|| anyhow!("Block {block} not found"))?;
+        let extrinsics = client.blocks().at(block_hash).await?.extrinsics().await?;
+        let extrinsic = extrinsics
+            .iter()
+            .nth(index)
+            .ok_or_else(|| anyhow!("Block {block} has no extrinsic at index {index}"))??;
+        let calls = decode_contract_calls(&metadata, extrinsic.call_bytes())?;
+        Ok((calls, ss58_prefix))
+    }
+}
+
 impl DecodeCommand {
     pub fn run(&self) -> Result<()> {
+        if let DecodeCommands::Extrinsic(extrinsic) = &self.commands {
+            return extrinsic.run()
+        }
         let crate_metadata =
             CrateMetadata::from_manifest_path(None, contract_build::Target::Wasm)?;
         let transcoder = ContractMessageTranscoder::load(crate_metadata.metadata_path())?;
@@ -102,14 +240,10 @@ impl DecodeCommand {
                     &mut &util::decode_hex(&constructor.data).context(ERR_MSG)?[..],
                 )?
             }
+            DecodeCommands::Extrinsic(_) => unreachable!("decoded above; qed"),
         };
 
-        println!(
-            "{:>width$} {}",
-            "Decoded data:".bright_green().bold(),
-            decoded_data,
-            width = DEFAULT_KEY_COL_WIDTH
-        );
+        output::renderer().status("Decoded data:", &decoded_data.to_string());
 
         Ok(())
     }
@@ -0,0 +1,167 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use colored::Colorize as _;
+use contract_build::{
+    name_value_println,
+    ArtifactDiff,
+    EntryPointsDiff,
+};
+use contract_extrinsics::ContractArtifacts;
+use serde_json::{
+    Map,
+    Value,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    name = "diff",
+    about = "Compare two versions of a contract, e.g. to review an upgrade"
+)]
+pub struct DiffCommand {
+    /// Path to the old version: a raw `.wasm` file, a `.contract` bundle, or a `.json`
+    /// metadata file.
+    #[clap(value_parser)]
+    old: PathBuf,
+    /// Path to the new version: a raw `.wasm` file, a `.contract` bundle, or a `.json`
+    /// metadata file.
+    #[clap(value_parser)]
+    new: PathBuf,
+    /// Export the differences as JSON.
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl DiffCommand {
+    pub fn run(&self) -> Result<()> {
+        let old = Version::load(&self.old)?;
+        let new = Version::load(&self.new)?;
+        let diff = ArtifactDiff::new(
+            &old.code,
+            old.abi.as_ref(),
+            &new.code,
+            new.abi.as_ref(),
+        )?;
+
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+        } else {
+            print_diff(&diff);
+        }
+        Ok(())
+    }
+}
+
+/// A version of a contract to compare.
+struct Version {
+    /// The code of the contract.
+    code: Vec<u8>,
+    /// The ink! ABI of the metadata of the contract, if available.
+    abi: Option<Map<String, Value>>,
+}
+
+impl Version {
+    /// Loads the code of a contract and the ink! ABI of its metadata, if available.
+    fn load(path: &Path) -> Result<Self> {
+        let artifacts =
+            ContractArtifacts::from_manifest_or_file(None, Some(&path.into()))?;
+        let abi = artifacts.metadata().ok().map(|metadata| metadata.abi);
+        let code = artifacts
+            .code
+            .ok_or_else(|| anyhow!("No contract code found in {}", path.display()))?;
+        Ok(Self {
+            code: code.as_bytes().to_vec(),
+            abi,
+        })
+    }
+}
+
+fn print_diff(diff: &ArtifactDiff) {
+    if diff.code_equal() {
+        name_value_println!("Code hash", format!("{} (unchanged)", diff.old_code_hash));
+    } else {
+        name_value_println!(
+            "Code hash",
+            format!("{} -> {}", diff.old_code_hash, diff.new_code_hash)
+        );
+    }
+
+    for section in diff.sections.iter().filter(|section| section.changed()) {
+        let size = |size: Option<usize>| {
+            size.map_or_else(|| "-".to_string(), |size| size.to_string())
+        };
+        let delta =
+            section.new_size.unwrap_or(0) as i64 - section.old_size.unwrap_or(0) as i64;
+        name_value_println!(
+            "Section",
+            format!(
+                "{}: {} -> {} ({delta:+} bytes)",
+                section.name,
+                size(section.old_size),
+                size(section.new_size)
+            )
+        );
+    }
+
+    for export in &diff.added_exports {
+        name_value_println!("Export", format!("{} {export}", "+".green()));
+    }
+    for export in &diff.removed_exports {
+        name_value_println!("Export", format!("{} {export}", "-".red()));
+    }
+
+    match &diff.abi {
+        Some(abi) if abi.is_empty() => {
+            name_value_println!("ABI", "unchanged");
+        }
+        Some(abi) => {
+            print_entry_points("Constructor", &abi.constructors);
+            print_entry_points("Message", &abi.messages);
+        }
+        None => {
+            name_value_println!("ABI", "not compared, the metadata is unavailable");
+        }
+    }
+}
+
+fn print_entry_points(kind: &str, diff: &EntryPointsDiff) {
+    for label in &diff.added {
+        name_value_println!(kind, format!("{} {label}", "+".green()));
+    }
+    for label in &diff.removed {
+        name_value_println!(kind, format!("{} {label}", "-".red()));
+    }
+    for change in &diff.changed_selectors {
+        name_value_println!(
+            kind,
+            format!(
+                "{} {}: {} -> {}",
+                "~".yellow(),
+                change.label,
+                change.old_selector,
+                change.new_selector
+            )
+        );
+    }
+}
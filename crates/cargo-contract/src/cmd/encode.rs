@@ -16,9 +16,8 @@
 
 use std::path::PathBuf;
 
-use crate::DEFAULT_KEY_COL_WIDTH;
 use anyhow::Result;
-use colored::Colorize as _;
+use contract_build::output;
 use contract_extrinsics::ContractArtifacts;
 
 #[derive(Debug, Clone, clap::Args)]
@@ -40,6 +39,9 @@ pub struct EncodeCommand {
     /// The arguments to encode
     #[clap(long, num_args = 0..)]
     args: Vec<String>,
+    /// Export the encoded call data as JSON, hex encoded with a `0x` prefix.
+    #[clap(long)]
+    output_json: bool,
 }
 
 impl EncodeCommand {
@@ -51,14 +53,17 @@ impl EncodeCommand {
         let transcoder = artifacts.contract_transcoder()?;
 
         let call_data = transcoder.encode(&self.message, &self.args)?;
+        if self.output_json {
+            let json = serde_json::json!({
+                "message": self.message,
+                "data": format!("0x{}", hex::encode(&call_data)),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+            return Ok(())
+        }
         let call_data_encoded = hex::encode_upper(call_data);
 
-        println!(
-            "{:>width$} {}",
-            "Encoded data:".bright_green().bold(),
-            call_data_encoded,
-            width = DEFAULT_KEY_COL_WIDTH
-        );
+        output::renderer().status("Encoded data:", &call_data_encoded);
 
         Ok(())
     }
@@ -0,0 +1,176 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::CLIExtrinsicOpts;
+use contract_build::name_value_println;
+use url::Url;
+
+/// Base URL of the Polkadot.js Apps UI.
+const POLKADOT_JS_APPS: &str = "https://polkadot.js.org/apps/";
+
+/// Links to block explorers for a submitted extrinsic.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExplorerLinks {
+    /// The extrinsic on Subscan, only available if the chain has a Subscan explorer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscan_extrinsic: Option<String>,
+    /// The contract on Subscan, only available if the chain has a Subscan explorer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscan_contract: Option<String>,
+    /// The block including the extrinsic in Polkadot.js Apps, connected to the node the
+    /// extrinsic was submitted to.
+    pub polkadot_js: String,
+}
+
+impl ExplorerLinks {
+    /// Returns the links for the extrinsic with `extrinsic_hash` included in the block
+    /// with `block_hash`, and optionally the affected `contract`.
+    ///
+    /// Subscan is linked to if the chain has an explorer, see
+    /// [`CLIExtrinsicOpts::subscan`].
+    pub fn new(
+        extrinsic_opts: &CLIExtrinsicOpts,
+        extrinsic_hash: &str,
+        block_hash: &str,
+        contract: Option<&str>,
+    ) -> Self {
+        let subscan = extrinsic_opts.subscan();
+        let chain = extrinsic_opts.chain_cli_opts.chain();
+        let mut polkadot_js =
+            Url::parse_with_params(POLKADOT_JS_APPS, &[("rpc", chain.url().as_str())])
+                .expect("Polkadot.js Apps url is valid");
+        polkadot_js.set_fragment(Some(&format!("/explorer/query/{block_hash}")));
+        Self {
+            subscan_extrinsic: subscan
+                .as_ref()
+                .map(|subscan| subscan_page(subscan, &["extrinsic", extrinsic_hash])),
+            subscan_contract: subscan.as_ref().zip(contract).map(|(subscan, contract)| {
+                subscan_page(subscan, &["wasm_contract", contract])
+            }),
+            polkadot_js: polkadot_js.to_string(),
+        }
+    }
+
+    /// Prints the links to stdout.
+    pub fn print(&self) {
+        if let Some(extrinsic) = &self.subscan_extrinsic {
+            name_value_println!("Subscan extrinsic", extrinsic);
+        }
+        if let Some(contract) = &self.subscan_contract {
+            name_value_println!("Subscan contract", contract);
+        }
+        name_value_println!("Polkadot.js", self.polkadot_js);
+    }
+}
+
+/// Returns the page of the Subscan explorer at `subscan` with the path `segments`.
+fn subscan_page(subscan: &Url, segments: &[&str]) -> String {
+    let mut page = subscan.clone();
+    if let Ok(mut path) = page.path_segments_mut() {
+        path.pop_if_empty().extend(segments);
+    }
+    page.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::ProductionChain;
+    use clap::Parser;
+
+    /// Parses the extrinsic options from the `args`.
+    fn extrinsic_opts(args: &[&str]) -> CLIExtrinsicOpts {
+        #[derive(Parser)]
+        struct Cli {
+            #[clap(flatten)]
+            extrinsic_opts: CLIExtrinsicOpts,
+        }
+
+        let args = ["cli", "--suri", "//Alice"].iter().chain(args);
+        Cli::parse_from(args).extrinsic_opts
+    }
+
+    #[test]
+    fn production_chain_links_to_subscan() {
+        let links = ExplorerLinks::new(
+            &extrinsic_opts(&["--chain", "astar"]),
+            "0x01",
+            "0x02",
+            Some("5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM"),
+        );
+
+        assert_eq!(
+            links.subscan_extrinsic.as_deref(),
+            Some("https://astar.subscan.io/extrinsic/0x01")
+        );
+        assert_eq!(
+            links.subscan_contract.as_deref(),
+            Some("https://astar.subscan.io/wasm_contract/5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM")
+        );
+        assert_eq!(
+            links.polkadot_js,
+            "https://polkadot.js.org/apps/?rpc=wss%3A%2F%2Frpc.astar.network%2F#/explorer/query/0x02"
+        );
+    }
+
+    #[test]
+    fn every_production_chain_has_subscan() {
+        for chain in <ProductionChain as clap::ValueEnum>::value_variants() {
+            assert!(chain.subscan().is_some(), "{chain} has no Subscan explorer");
+        }
+    }
+
+    #[test]
+    fn custom_chain_only_links_to_polkadot_js() {
+        let links = ExplorerLinks::new(
+            &extrinsic_opts(&["--url", "ws://localhost:9944"]),
+            "0x01",
+            "0x02",
+            None,
+        );
+
+        assert!(links.subscan_extrinsic.is_none());
+        assert!(links.subscan_contract.is_none());
+        assert_eq!(
+            links.polkadot_js,
+            "https://polkadot.js.org/apps/?rpc=ws%3A%2F%2Flocalhost%3A9944%2F#/explorer/query/0x02"
+        );
+    }
+
+    #[test]
+    fn custom_chain_links_to_its_subscan() {
+        let links = ExplorerLinks::new(
+            &extrinsic_opts(&[
+                "--url",
+                "wss://rpc.example.com",
+                "--subscan",
+                "https://example.com/subscan",
+            ]),
+            "0x01",
+            "0x02",
+            Some("5Contract"),
+        );
+
+        assert_eq!(
+            links.subscan_extrinsic.as_deref(),
+            Some("https://example.com/subscan/extrinsic/0x01")
+        );
+        assert_eq!(
+            links.subscan_contract.as_deref(),
+            Some("https://example.com/subscan/wasm_contract/5Contract")
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    config::SignerConfig,
+    display_extrinsic_receipt,
+    parse_balance,
+    CLIChainOpts,
+    Suri,
+};
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_build::name_value_println;
+use contract_extrinsics::{
+    rpc_client,
+    url_to_string,
+    ExtrinsicOptsBuilder,
+    TransferCommandBuilder,
+    TransferExec,
+};
+use hyper::{
+    header::CONTENT_TYPE,
+    Body,
+    Client,
+    Request,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    str::FromStr,
+};
+use subxt::{
+    backend::rpc::rpc_params,
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    tx::Signer,
+    Config,
+};
+use url::Url;
+
+/// The amount of tokens an account is funded with by default.
+const DEFAULT_AMOUNT: u128 = 1000;
+
+/// Funds the account of the signer.
+///
+/// On development chains the tokens are transferred from a dev account, other chains
+/// require an HTTP faucet, either passed with `--faucet-url` or configured for the
+/// production chain.
+#[derive(Debug, clap::Args)]
+#[clap(name = "faucet")]
+pub struct FaucetCommand {
+    /// Secret key URI of the account to fund.
+    #[clap(name = "suri", long, short)]
+    suri: Suri,
+    /// The amount to fund the account with, defaults to 1000 tokens.
+    #[clap(long)]
+    amount: Option<String>,
+    /// Secret key URI of the dev account the tokens are transferred from on
+    /// development chains.
+    #[clap(long, default_value = "//Alice")]
+    dev_suri: Suri,
+    /// URL of an HTTP faucet, which is sent the address of the account as JSON.
+    #[clap(long, value_parser)]
+    faucet_url: Option<Url>,
+    /// Export the faucet output as JSON.
+    #[clap(long)]
+    output_json: bool,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl FaucetCommand {
+    /// Returns whether to export the faucet output in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + EncodeAsType + Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let account = self.suri.signer::<C>()?.account_id();
+        let chain = self.chain_cli_opts.chain();
+        let faucet_url = self.faucet_url.clone().or_else(|| {
+            chain
+                .production()
+                .and_then(|prod| prod.faucet())
+                .map(|faucet| Url::parse(faucet).expect("Incorrect Url format"))
+        });
+        if let Some(faucet_url) = faucet_url {
+            let response = request_faucet(&faucet_url, &account.to_string()).await?;
+            if self.output_json {
+                let json_object = serde_json::json!({
+                    "account": account.to_string(),
+                    "faucet": faucet_url,
+                    "response": response,
+                });
+                println!("{}", serde_json::to_string_pretty(&json_object)?);
+            } else {
+                name_value_println!("Account", account.to_string());
+                name_value_println!("Faucet", response);
+            }
+            return Ok(())
+        }
+
+        self.chain_cli_opts.ensure_can_submit()?;
+        let url = chain.url();
+        let rpc =
+            rpc_client(&url_to_string(&url), self.chain_cli_opts.rpc_options()).await?;
+        let chain_type: serde_json::Value =
+            rpc.request("system_chainType", rpc_params![]).await?;
+        if !is_dev_chain(&chain_type) {
+            return Err(anyhow!(
+                "{url} is not a development chain and no faucet is known for it, pass \
+                the URL of an HTTP faucet with --faucet-url"
+            )
+            .into())
+        }
+
+        let token_metadata = self.chain_cli_opts.token_metadata::<C>().await?;
+        let amount = match &self.amount {
+            Some(amount) => {
+                parse_balance(amount, &token_metadata)
+                    .map_err(|e| anyhow!("Failed to parse amount option: {}", e))?
+            }
+            None => {
+                let amount =
+                    DEFAULT_AMOUNT * 10u128.pow(token_metadata.token_decimals as u32);
+                C::Balance::try_from(amount).map_err(|_| {
+                    anyhow!("The default amount {amount} exceeds the balance type")
+                })?
+            }
+        };
+        let dev_signer = C::Signer::from_str(self.dev_suri.expose())
+            .map_err(|_| anyhow!("Failed to parse dev-suri option"))?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::new(dev_signer)
+            .url(url)
+            .rpc_options(self.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.chain_cli_opts.ss58_prefix())
+            .done();
+        let transfer_exec: TransferExec<C, C, _> =
+            TransferCommandBuilder::new(account.clone(), amount, extrinsic_opts)
+                .done()
+                .await?;
+        let result = transfer_exec.transfer().await?;
+
+        if self.output_json {
+            let json_object = serde_json::json!({
+                "account": account.to_string(),
+                "amount": amount,
+                "extrinsic": result.receipt,
+            });
+            println!("{}", serde_json::to_string_pretty(&json_object)?);
+        } else {
+            name_value_println!("Account", account.to_string());
+            name_value_println!("Funded", format!("{amount}"));
+            display_extrinsic_receipt(&result.receipt);
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether the `system_chainType` of a node is a development or local chain,
+/// which endow the well known dev accounts.
+fn is_dev_chain(chain_type: &serde_json::Value) -> bool {
+    matches!(chain_type.as_str(), Some("Development" | "Local"))
+}
+
+/// Requests funds for the `account` from the HTTP faucet at `url`, returning its
+/// response.
+async fn request_faucet(url: &Url, account: &str) -> Result<String> {
+    let body = serde_json::json!({ "address": account }).to_string();
+    let request = Request::post(url.as_str())
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))?;
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .https_or_http()
+        .enable_http1()
+        .build();
+    let response = Client::builder()
+        .build::<_, Body>(connector)
+        .request(request)
+        .await
+        .with_context(|| format!("Failed to connect to the faucet at {url}"))?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if !status.is_success() {
+        anyhow::bail!("The faucet responded with status {status}: {body}")
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_dev_chains() {
+        assert!(is_dev_chain(&serde_json::json!("Development")));
+        assert!(is_dev_chain(&serde_json::json!("Local")));
+        assert!(!is_dev_chain(&serde_json::json!("Live")));
+        assert!(!is_dev_chain(&serde_json::json!({ "Custom": "Testnet" })));
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::call_with_config;
+
+use super::{
+    parse_account,
+    CLIChainOpts,
+};
+use anyhow::Result;
+use contract_build::name_value_println;
+use contract_extrinsics::{
+    ContractStorage,
+    ContractStorageRpc,
+    ErrorVariant,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use std::{
+    fmt::Display,
+    path::PathBuf,
+    str::FromStr,
+};
+use subxt::{
+    ext::scale_decode::IntoVisitor,
+    Config,
+};
+
+/// Fork the on-chain state of a contract into a local snapshot.
+///
+/// The snapshot contains the contract info, its Wasm code and the complete raw storage
+/// of the contract, and can be loaded into a local node or test environment to dry-run
+/// upgrades and migrations against real state.
+#[derive(Debug, clap::Args)]
+#[clap(name = "fork")]
+pub struct ForkCommand {
+    /// The address of the contract to fork.
+    #[clap(name = "contract", long, env = "CONTRACT")]
+    contract: String,
+    /// Path of the file the snapshot is written to.
+    ///
+    /// Defaults to `<contract>.fork.json` in the current directory.
+    #[clap(long, short, value_parser, conflicts_with = "output-json")]
+    output: Option<PathBuf>,
+    /// Print the snapshot to stdout in JSON format instead of writing it to a file.
+    #[clap(name = "output-json", long)]
+    output_json: bool,
+    /// Arguments required for communicating with a Substrate node.
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl ForkCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    pub async fn run<C: Config + Environment>(&self) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: Display + IntoVisitor + AsRef<[u8]> + FromStr,
+        <<C as Config>::AccountId as FromStr>::Err:
+            Into<Box<dyn std::error::Error>> + Display,
+        C::Balance: Serialize + IntoVisitor,
+        <C as Config>::Hash: IntoVisitor + AsRef<[u8]> + Display,
+    {
+        let contract: <C as Config>::AccountId = parse_account(&self.contract)?;
+        let rpc = ContractStorageRpc::<C>::with_options(
+            &self.chain_cli_opts.chain().url(),
+            self.chain_cli_opts.rpc_options(),
+        )
+        .await?;
+        let fork = ContractStorage::<C, C>::new(rpc)
+            .fork_contract(&contract)
+            .await?;
+        let json = serde_json::to_string_pretty(&fork)?;
+
+        if self.output_json {
+            println!("{json}");
+            return Ok(())
+        }
+
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("{}.fork.json", self.contract)));
+        std::fs::write(&output, json)?;
+
+        name_value_println!("Contract", fork.contract);
+        name_value_println!("Block", format!("{}", fork.block_hash));
+        name_value_println!("Code hash", format!("{}", fork.info.code_hash()));
+        name_value_println!("Storage items", fork.storage.len().to_string());
+        name_value_println!("Snapshot", output.display().to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use contract_build::{
+    name_value_println,
+    CrateMetadata,
+    Target,
+};
+use contract_transcode::{
+    ContractMessageTranscoder,
+    ExampleValue,
+};
+use std::{
+    fmt::Write,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+const CARGO_TOML: &str = include_str!("../../templates/fuzz/_Cargo.toml");
+const COMMON_RS: &str = include_str!("../../templates/fuzz/common.rs");
+const FUZZ_TARGET_RS: &str = include_str!("../../templates/fuzz/fuzz_target.rs");
+
+/// Generates a `cargo-fuzz` harness for the messages of the contract.
+///
+/// Every message gets a fuzz target, which dry-runs the message with arbitrary
+/// arguments against a deployed instance of the contract. The corpus of each target is
+/// seeded with example arguments derived from the metadata.
+#[derive(Debug, clap::Args)]
+#[clap(name = "fuzz")]
+pub struct FuzzCommand {
+    /// Path to the `Cargo.toml` of the contract
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// The directory to generate the harness in, defaults to `fuzz` next to the
+    /// `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    output: Option<PathBuf>,
+    /// Overwrite an existing harness.
+    #[clap(long)]
+    force: bool,
+    /// Depend on `contract-extrinsics` at the given commit of the cargo-contract
+    /// repository, instead of its released version of this binary, e.g. to use
+    /// unreleased changes.
+    #[clap(long, value_name = "REV")]
+    extrinsics_rev: Option<String>,
+}
+
+impl FuzzCommand {
+    pub fn run(&self) -> Result<()> {
+        let crate_metadata =
+            CrateMetadata::from_manifest_path(self.manifest_path.as_ref(), Target::Wasm)?;
+        let metadata_path = crate_metadata.metadata_path();
+        let transcoder = ContractMessageTranscoder::load(&metadata_path).context(
+            "Failed to load the metadata, build the contract with `cargo contract build` first",
+        )?;
+
+        let dir = match &self.output {
+            Some(output) => output.clone(),
+            None => {
+                crate_metadata
+                    .manifest_path
+                    .absolute_directory()?
+                    .join("fuzz")
+            }
+        };
+        if dir.exists() && !self.force {
+            anyhow::bail!(
+                "{} already exists, use --force to overwrite it",
+                dir.display()
+            )
+        }
+        fs::create_dir_all(dir.join("fuzz_targets"))?;
+
+        let metadata = transcoder.metadata();
+        let mut cargo_toml = cargo_toml(
+            &crate_metadata.contract_artifact_name,
+            self.extrinsics_rev.as_deref(),
+        );
+        for message in metadata.spec().messages() {
+            let target = target_name(message.label());
+            write!(
+                cargo_toml,
+                "\n[[bin]]\nname = \"{target}\"\npath = \"fuzz_targets/{target}.rs\"\n\
+                 test = false\ndoc = false\nbench = false\n"
+            )?;
+            fs::write(
+                dir.join("fuzz_targets").join(format!("{target}.rs")),
+                FUZZ_TARGET_RS.replace("{{message}}", message.label()),
+            )?;
+
+            let corpus_dir = dir.join("corpus").join(&target);
+            fs::create_dir_all(&corpus_dir)?;
+            for example in ExampleValue::ALL {
+                // the input of the targets are the concatenated arguments
+                let args = message
+                    .args()
+                    .iter()
+                    .map(|arg| example.encode(metadata.registry(), arg.ty().ty().id))
+                    .collect::<Result<Vec<_>>>();
+                match args {
+                    Ok(args) => {
+                        fs::write(corpus_dir.join(example.name()), args.concat())?
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            "No {} example for `{}`: {err}",
+                            example.name(),
+                            message.label()
+                        )
+                    }
+                }
+            }
+        }
+        fs::write(dir.join("Cargo.toml"), cargo_toml)?;
+        fs::write(
+            dir.join("fuzz_targets").join("common.rs"),
+            common_rs(&metadata_path),
+        )?;
+        fs::write(
+            dir.join(".gitignore"),
+            "target\ncorpus\nartifacts\ncoverage\n",
+        )?;
+
+        name_value_println!("Harness", dir.display().to_string());
+        name_value_println!("Targets", metadata.spec().messages().len().to_string());
+        name_value_println!(
+            "Run",
+            "CONTRACT_ADDRESS=<address> cargo +nightly fuzz run <target>"
+        );
+        Ok(())
+    }
+}
+
+/// Returns the manifest of the harness of the contract `name`, without its targets.
+fn cargo_toml(name: &str, extrinsics_rev: Option<&str>) -> String {
+    CARGO_TOML
+        .replace("{{name}}", name)
+        .replace("{{extrinsics}}", &extrinsics_dependency(extrinsics_rev))
+}
+
+/// Returns the `contract-extrinsics` dependency of the harness.
+///
+/// The harness depends on the released version of this binary, or on the commit `rev`
+/// of the cargo-contract repository if one is requested.
+fn extrinsics_dependency(rev: Option<&str>) -> String {
+    match rev {
+        Some(rev) => {
+            format!(
+                "{{ git = \"{}\", rev = \"{rev}\" }}",
+                env!("CARGO_PKG_REPOSITORY")
+            )
+        }
+        None => format!("\"{}\"", env!("CARGO_PKG_VERSION")),
+    }
+}
+
+/// Returns the harness shared by the fuzz targets, loading the metadata at
+/// `metadata_path`.
+fn common_rs(metadata_path: &Path) -> String {
+    // the `Debug` output of a path is a quoted and escaped string literal
+    COMMON_RS.replace("{{metadata}}", &format!("{metadata_path:?}"))
+}
+
+/// Returns the name of the fuzz target of a message, e.g. `PSP22_transfer` for the
+/// trait message `PSP22::transfer`.
+fn target_name(label: &str) -> String {
+    label.replace("::", "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harness_depends_on_the_requested_commit() {
+        let commit = "c425db73d30c1c77e13b2a8ad7bd95fed2c68d3b";
+        let manifest =
+            format!("contract-extrinsics = {}", extrinsics_dependency(Some(commit)));
+        let manifest: toml::Table = toml::from_str(&manifest).unwrap();
+
+        assert_eq!(
+            manifest["contract-extrinsics"]["git"].as_str(),
+            Some(env!("CARGO_PKG_REPOSITORY"))
+        );
+        assert_eq!(manifest["contract-extrinsics"]["rev"].as_str(), Some(commit));
+    }
+
+    #[test]
+    fn harness_depends_on_the_released_version() {
+        let manifest = format!("contract-extrinsics = {}", extrinsics_dependency(None));
+        let manifest: toml::Table = toml::from_str(&manifest).unwrap();
+
+        assert_eq!(
+            manifest["contract-extrinsics"].as_str(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[test]
+    fn metadata_path_is_escaped() {
+        let common_rs = common_rs(Path::new(r#"C:\contracts\"flipper".json"#));
+
+        assert!(common_rs.contains(
+            r#"const METADATA: &str = "C:\\contracts\\\"flipper\".json";"#
+        ));
+    }
+}
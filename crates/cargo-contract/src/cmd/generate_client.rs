@@ -0,0 +1,69 @@
+// Copyright 2018-2023 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+
+use anyhow::{
+    Context,
+    Result,
+};
+use contract_codegen::Language;
+use contract_extrinsics::ContractArtifacts;
+
+#[derive(Debug, Clone, clap::Args)]
+#[clap(
+    name = "generate-client",
+    about = "Generates typed client bindings from the contract metadata"
+)]
+pub struct GenerateClientCommand {
+    /// Path to a contract build artifact file: a `.contract` bundle or a `.json`
+    /// metadata file.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// The language of the generated bindings, e.g. `ts`.
+    #[clap(long, default_value = "ts")]
+    lang: Language,
+    /// Path of the file the bindings are written to.
+    ///
+    /// The bindings are printed to stdout if not specified.
+    #[clap(long, short, value_parser)]
+    output: Option<PathBuf>,
+}
+
+impl GenerateClientCommand {
+    pub fn run(&self) -> Result<()> {
+        let artifacts = ContractArtifacts::from_manifest_or_file(
+            self.manifest_path.as_ref(),
+            self.file.as_ref(),
+        )?;
+        let name = artifacts.metadata()?.contract.name;
+        let metadata = artifacts.ink_project_metadata()?;
+
+        let client = contract_codegen::generate_client(&name, &metadata, self.lang)?;
+        match &self.output {
+            Some(output) => {
+                std::fs::write(output, client).with_context(|| {
+                    format!("Failed to write the client to {}", output.display())
+                })?
+            }
+            None => print!("{client}"),
+        }
+        Ok(())
+    }
+}
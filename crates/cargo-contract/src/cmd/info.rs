@@ -17,19 +17,40 @@
 use crate::call_with_config;
 
 use super::{
+    address_book::resolve_contract,
     basic_display_format_extended_contract_info,
     display_all_contracts,
     parse_account,
+    parse_code_hash,
+    progress,
     CLIChainOpts,
 };
 use anyhow::Result;
-use contract_analyze::determine_language;
+use colored::Colorize as _;
+use contract_analyze::{
+    determine_language,
+    determine_language_version,
+    host_functions,
+    metadata_hash,
+};
+use contract_build::{
+    name_value_println,
+    Verbosity,
+    VerbosityFlags,
+};
 use contract_extrinsics::{
-    fetch_all_contracts,
+    fetch_code_info,
     fetch_contract_info,
+    fetch_contract_instances,
     fetch_wasm_code,
+    for_each_contract,
+    online_client,
+    rpc_client,
+    ss58,
     url_to_string,
+    CodeInfo,
     ContractInfo,
+    ContractInstances,
     ErrorVariant,
     TrieId,
 };
@@ -44,38 +65,80 @@ use std::{
     str::FromStr,
 };
 use subxt::{
-    backend::{
-        legacy::LegacyRpcMethods,
-        rpc::RpcClient,
-    },
+    backend::legacy::LegacyRpcMethods,
     ext::{
-        codec::Decode,
+        codec::{
+            Decode,
+            Encode,
+        },
         scale_decode::IntoVisitor,
     },
     Config,
-    OnlineClient,
 };
 
+/// The number of contracts displayed by `--instances-of` if no `--limit` is given.
+const DEFAULT_INSTANCES_LIMIT: u32 = 100;
+
 #[derive(Debug, clap::Args)]
 #[clap(name = "info", about = "Get infos from a contract")]
+#[clap(group(clap::ArgGroup::new("paged").args(["all", "instances-of"])))]
 pub struct InfoCommand {
-    /// The address of the contract to display info of.
+    /// The address of the contract to display info of, or `@<name>` of the address
+    /// book.
     #[clap(
         name = "contract",
         long,
         env = "CONTRACT",
-        required_unless_present = "all"
+        required_unless_present_any = ["all", "code-hash", "instances-of"]
     )]
     contract: Option<String>,
+    /// Display the owner, deposit and reference count of the uploaded code with this
+    /// hash instead, and whether it can currently be removed.
+    #[clap(
+        name = "code-hash",
+        long,
+        conflicts_with_all = ["contract", "all", "binary"]
+    )]
+    code_hash: Option<String>,
+    /// Display the addresses of the contracts instantiated from the code with this hash
+    /// instead.
+    #[clap(
+        name = "instances-of",
+        long,
+        conflicts_with_all = ["contract", "all", "binary", "code-hash"]
+    )]
+    instances_of: Option<String>,
+    /// The maximum number of contracts displayed by `--all` or `--instances-of`.
+    ///
+    /// `--all` displays all contracts and `--instances-of` 100 contracts by default.
+    #[clap(
+        long,
+        requires = "paged",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    limit: Option<u32>,
+    /// The storage key to continue scanning the contracts after, as displayed by a
+    /// previous `--all` or `--instances-of` which stopped at the `--limit`.
+    #[clap(long, requires = "paged")]
+    start_key: Option<String>,
+    /// Display the number of contracts instead of their addresses with `--all`.
+    #[clap(long, requires = "all", conflicts_with = "limit")]
+    count_only: bool,
     /// Export the instantiate output in JSON format.
     #[clap(name = "output-json", long)]
     output_json: bool,
     /// Display the contract's Wasm bytecode.
     #[clap(name = "binary", long, conflicts_with = "all")]
     binary: bool,
+    /// Display the host functions imported by the contract's Wasm bytecode instead,
+    /// flagging those which are not known to be provided by `pallet-contracts`.
+    #[clap(name = "imports", long, requires = "binary")]
+    imports: bool,
     /// Display all contracts addresses
     #[clap(name = "all", long)]
     all: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
     /// Arguments required for communicating with a Substrate node.
     #[clap(flatten)]
     chain_cli_opts: CLIChainOpts,
@@ -90,37 +153,134 @@ impl InfoCommand {
     where
         <C as Config>::AccountId:
             Serialize + Display + IntoVisitor + Decode + AsRef<[u8]> + FromStr,
-        <C as Config>::Hash: IntoVisitor + Display,
-        <C as Environment>::Balance: Serialize + Debug + IntoVisitor,
+        <C as Config>::Hash:
+            IntoVisitor + Display + Serialize + AsRef<[u8]> + From<[u8; 32]>,
+        <C as Environment>::Balance: Serialize + Debug + IntoVisitor + Clone,
         <<C as Config>::AccountId as FromStr>::Err:
             Into<Box<(dyn std::error::Error)>> + Display,
     {
-        let rpc_cli =
-            RpcClient::from_url(url_to_string(&self.chain_cli_opts.chain().url()))
-                .await?;
-        let client = OnlineClient::<C>::from_rpc_client(rpc_cli.clone()).await?;
+        let options = self.chain_cli_opts.rpc_options();
+        let url = url_to_string(&self.chain_cli_opts.chain().url());
+        let rpc_cli = rpc_client(&url, options.clone()).await?;
+        let client = online_client::<C>(rpc_cli.clone(), options).await?;
         let rpc = LegacyRpcMethods::<C>::new(rpc_cli.clone());
+        let ss58_prefix = self.chain_cli_opts.ss58_prefix_for(Some(&client.metadata()));
+
+        if let Some(code_hash) = &self.code_hash {
+            let code_hash = parse_code_hash::<<C as Config>::Hash>(code_hash)?;
+            let code_info = fetch_code_info::<C, C>(&code_hash, &rpc, &client).await?;
+            let info = ExtendedCodeInfo::new(code_hash, code_info, ss58_prefix);
+            if self.output_json {
+                println!("{}", serde_json::to_string_pretty(&info)?);
+            } else {
+                display_code_info(&info);
+            }
+            return Ok(())
+        }
+
+        if let Some(code_hash) = &self.instances_of {
+            let code_hash = parse_code_hash::<<C as Config>::Hash>(code_hash)?;
+            let start_key = self.start_key()?;
+            let limit = self.limit.unwrap_or(DEFAULT_INSTANCES_LIMIT);
+            let instances = fetch_contract_instances(
+                &client,
+                &rpc,
+                &code_hash,
+                limit as usize,
+                start_key.as_deref(),
+                &progress::reporter(&self.verbosity()?),
+            )
+            .await?;
+
+            if self.output_json {
+                let instances = ContractInstances {
+                    contracts: instances
+                        .contracts
+                        .iter()
+                        .map(|contract| ss58::display_account(contract, ss58_prefix))
+                        .collect(),
+                    next_start_key: instances.next_start_key,
+                };
+                println!("{}", serde_json::to_string_pretty(&instances)?);
+            } else {
+                display_all_contracts(&instances.contracts, ss58_prefix);
+                if let Some(next_start_key) = instances.next_start_key {
+                    name_value_println!("Next start key", next_start_key);
+                }
+            }
+            return Ok(())
+        }
 
         // All flag applied
         if self.all {
-            let contracts = fetch_all_contracts(&client, &rpc).await?;
+            let start_key = self.start_key()?;
+            let progress = progress::reporter(&self.verbosity()?);
 
+            if self.count_only {
+                let mut count = 0u64;
+                for_each_contract::<C>(
+                    &client,
+                    &rpc,
+                    start_key.as_deref(),
+                    None,
+                    &progress,
+                    |_| {
+                        count += 1;
+                        Ok(())
+                    },
+                )
+                .await?;
+                if self.output_json {
+                    let json = serde_json::json!({ "count": count });
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                } else {
+                    name_value_println!("Contracts", count.to_string());
+                }
+                return Ok(())
+            }
+
+            // The addresses are printed as soon as they are fetched, instead of holding
+            // all contracts of the chain in memory.
             if self.output_json {
-                let contracts_json = serde_json::json!({
-                    "contracts": contracts
-                });
-                println!("{}", serde_json::to_string_pretty(&contracts_json)?);
-            } else {
-                display_all_contracts(&contracts)
+                print!("{{\n  \"contracts\": [");
+            }
+            let mut first = true;
+            let next_start_key = for_each_contract::<C>(
+                &client,
+                &rpc,
+                start_key.as_deref(),
+                self.limit.map(|limit| limit as usize),
+                &progress,
+                |contract| {
+                    let contract = ss58::display_account(&contract, ss58_prefix);
+                    if self.output_json {
+                        let separator = if first { "" } else { "," };
+                        let contract = serde_json::to_string(&contract)?;
+                        print!("{separator}\n    {contract}");
+                    } else {
+                        println!("{contract}");
+                    }
+                    first = false;
+                    Ok(())
+                },
+            )
+            .await?;
+            if self.output_json {
+                let next_start_key = serde_json::to_string(&next_start_key)?;
+                let end = if first { "" } else { "\n  " };
+                println!("{end}],\n  \"next_start_key\": {next_start_key}\n}}");
+            } else if let Some(next_start_key) = next_start_key {
+                name_value_println!("Next start key", next_start_key);
             }
             Ok(())
         } else {
             // Contract arg shall be always present in this case, it is enforced by
             // clap configuration
+            let chain = self.chain_cli_opts.chain();
             let contract = self
                 .contract
                 .as_ref()
-                .map(|c| parse_account(c))
+                .map(|c| parse_account(&resolve_contract(c, &chain)?))
                 .transpose()?
                 .expect("Contract argument shall be present");
 
@@ -129,8 +289,12 @@ impl InfoCommand {
 
             let wasm_code =
                 fetch_wasm_code(&client, &rpc, info_to_json.code_hash()).await?;
+            let code_info =
+                fetch_code_info::<C, C>(info_to_json.code_hash(), &rpc, &client).await?;
             // Binary flag applied
-            if self.binary {
+            if self.imports {
+                display_host_functions(&wasm_code, self.output_json)?;
+            } else if self.binary {
                 if self.output_json {
                     let wasm = serde_json::json!({
                         "wasm": format!("0x{}", hex::encode(wasm_code))
@@ -144,24 +308,127 @@ impl InfoCommand {
             } else if self.output_json {
                 println!(
                     "{}",
-                    serde_json::to_string_pretty(&ExtendedContractInfo::<
-                        <C as Config>::Hash,
-                        C::Balance,
-                    >::new(
-                        info_to_json, &wasm_code
+                    serde_json::to_string_pretty(&ExtendedContractInfo::new(
+                        info_to_json,
+                        code_info,
+                        &wasm_code,
+                        ss58_prefix,
                     ))?
                 )
             } else {
-                basic_display_format_extended_contract_info(&ExtendedContractInfo::<
-                    <C as Config>::Hash,
-                    C::Balance,
-                >::new(
-                    info_to_json, &wasm_code
+                basic_display_format_extended_contract_info(&ExtendedContractInfo::new(
+                    info_to_json,
+                    code_info,
+                    &wasm_code,
+                    ss58_prefix,
                 ))
             }
             Ok(())
         }
     }
+
+    /// Returns the verbosity.
+    fn verbosity(&self) -> Result<Verbosity> {
+        TryFrom::try_from(&self.verbosity)
+    }
+
+    /// Returns the decoded `--start-key`.
+    fn start_key(&self) -> Result<Option<Vec<u8>>> {
+        self.start_key
+            .as_deref()
+            .map(contract_build::util::decode_hex)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("Failed to parse the start key: {e}"))
+    }
+}
+
+/// Displays the host functions imported by the Wasm `code`.
+fn display_host_functions(code: &[u8], output_json: bool) -> Result<()> {
+    let host_functions = host_functions(code)?;
+    if output_json {
+        let host_functions = host_functions
+            .iter()
+            .map(|function| {
+                serde_json::json!({
+                    "module": function.module,
+                    "name": function.name,
+                    "known": function.is_known(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let json = serde_json::json!({ "host_functions": host_functions });
+        println!("{}", serde_json::to_string_pretty(&json)?);
+    } else {
+        for function in &host_functions {
+            if function.is_known() {
+                name_value_println!("Host function", function.to_string());
+            } else {
+                name_value_println!(
+                    "Host function",
+                    format!("{function} {}", "(unknown)".yellow())
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Displays the info of uploaded code and whether it can be removed.
+fn display_code_info<Hash, Balance>(info: &ExtendedCodeInfo<Hash, Balance>)
+where
+    Hash: Debug,
+    Balance: Debug,
+{
+    name_value_println!("Code Hash", format!("{:?}", info.code_hash));
+    name_value_println!("Owner", info.owner);
+    name_value_println!("Deposit", format!("{:?}", info.deposit));
+    name_value_println!("Refcount", info.refcount.to_string());
+    let removable = if info.removable {
+        "yes, by the owner".to_string()
+    } else {
+        format!("no, {} contract(s) use the code", info.refcount)
+    };
+    name_value_println!("Removable", removable);
+    if let Some(refund) = &info.refund {
+        name_value_println!("Refund", format!("{refund:?}"));
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct ExtendedCodeInfo<Hash, Balance> {
+    pub code_hash: Hash,
+    /// The SS58 address of the owner.
+    pub owner: String,
+    pub deposit: Balance,
+    pub refcount: u64,
+    /// Whether `remove_code` would currently succeed when called by the owner.
+    pub removable: bool,
+    /// The deposit refunded to the owner by `remove_code`, if the code is removable.
+    pub refund: Option<Balance>,
+}
+
+impl<Hash, Balance> ExtendedCodeInfo<Hash, Balance>
+where
+    Balance: Clone,
+{
+    /// Displays the owner of the code with the `ss58_prefix`.
+    pub fn new<AccountId>(
+        code_hash: Hash,
+        code_info: CodeInfo<AccountId, Balance>,
+        ss58_prefix: u16,
+    ) -> Self
+    where
+        AccountId: Encode + Display,
+    {
+        ExtendedCodeInfo {
+            code_hash,
+            removable: code_info.is_removable(),
+            refund: code_info.removal_refund().cloned(),
+            owner: ss58::display_account(&code_info.owner, ss58_prefix),
+            deposit: code_info.deposit,
+            refcount: code_info.refcount,
+        }
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -171,7 +438,14 @@ pub struct ExtendedContractInfo<Hash, Balance> {
     pub storage_items: u32,
     pub storage_items_deposit: Balance,
     pub storage_total_deposit: Balance,
+    /// The SS58 address of the owner of the code.
+    pub code_owner: String,
+    pub code_deposit: Balance,
+    pub code_refcount: u64,
     pub source_language: String,
+    pub source_language_version: String,
+    /// The hash of the ABI embedded into the code by `cargo contract build`.
+    pub metadata_hash: Option<String>,
 }
 
 impl<Hash, Balance> ExtendedContractInfo<Hash, Balance>
@@ -179,18 +453,46 @@ where
     Hash: serde::Serialize + Copy,
     Balance: serde::Serialize + Copy,
 {
-    pub fn new(contract_info: ContractInfo<Hash, Balance>, code: &[u8]) -> Self {
+    /// Displays the owner of the code with the `ss58_prefix`.
+    pub fn new<AccountId>(
+        contract_info: ContractInfo<Hash, Balance>,
+        code_info: CodeInfo<AccountId, Balance>,
+        code: &[u8],
+        ss58_prefix: u16,
+    ) -> Self
+    where
+        AccountId: Encode + Display,
+    {
         let language = match determine_language(code).ok() {
             Some(lang) => lang.to_string(),
             None => "Unknown".to_string(),
         };
+        let language_version = match determine_language_version(code) {
+            Ok(version) if !version.processed_by.is_empty() => {
+                format!(
+                    "{} ({})",
+                    version.release.as_deref().unwrap_or("Unknown"),
+                    version.processed_by.join(", ")
+                )
+            }
+            Ok(version) => version.release.unwrap_or_else(|| "Unknown".to_string()),
+            Err(_) => "Unknown".to_string(),
+        };
         ExtendedContractInfo {
             trie_id: contract_info.trie_id().clone(),
             code_hash: *contract_info.code_hash(),
             storage_items: contract_info.storage_items(),
             storage_items_deposit: contract_info.storage_items_deposit(),
             storage_total_deposit: contract_info.storage_total_deposit(),
+            code_owner: ss58::display_account(&code_info.owner, ss58_prefix),
+            code_deposit: code_info.deposit,
+            code_refcount: code_info.refcount,
             source_language: language,
+            source_language_version: language_version,
+            metadata_hash: metadata_hash(code)
+                .ok()
+                .flatten()
+                .map(|hash| format!("0x{}", hex::encode(hash))),
         }
     }
 }
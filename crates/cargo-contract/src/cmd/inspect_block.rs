@@ -0,0 +1,299 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::call_with_config;
+
+use super::{
+    parse_code_hash,
+    CLIChainOpts,
+};
+use anyhow::{
+    anyhow,
+    Result,
+};
+use contract_build::{
+    name_value_println,
+    Verbosity,
+};
+use contract_extrinsics::{
+    inspect_block,
+    online_client,
+    rpc_client,
+    url_to_string,
+    ContractArtifacts,
+    ContractCallData,
+    ContractsExtrinsic,
+    DisplayEvents,
+    ErrorVariant,
+    EventRecord,
+};
+use contract_transcode::{
+    ContractMessageTranscoder,
+    Value,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    path::PathBuf,
+    str::FromStr,
+};
+use subxt::{
+    backend::legacy::LegacyRpcMethods,
+    ext::scale_decode::IntoVisitor,
+    Config,
+};
+
+/// Lists the extrinsics and events of the contracts pallet in a block, e.g. to
+/// investigate an incident.
+///
+/// The calls of contracts and the events emitted by contracts are decoded with the
+/// metadata of the contracts passed with `--metadata`.
+#[derive(Debug, clap::Args)]
+#[clap(name = "inspect-block")]
+pub struct InspectBlockCommand {
+    /// The hash of the block, starting with `0x`, or its number.
+    block: BlockId,
+    /// Path to a `.contract` bundle or `.json` metadata file of a contract whose calls
+    /// and events are decoded.
+    ///
+    /// Can be passed several times, the first contract which knows a message or event
+    /// decodes it.
+    #[clap(long, value_parser)]
+    metadata: Vec<PathBuf>,
+    /// Export the extrinsics and events in JSON format.
+    #[clap(long)]
+    output_json: bool,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+/// A block passed either by its hash or by its number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum BlockId {
+    Hash([u8; 32]),
+    Number(u32),
+}
+
+impl FromStr for BlockId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("0x") {
+            return parse_code_hash(s).map(BlockId::Hash)
+        }
+        s.parse()
+            .map(BlockId::Number)
+            .map_err(|_| anyhow!("Expected a block number or a block hash, got `{s}`"))
+    }
+}
+
+/// The block in the JSON output.
+#[derive(Serialize)]
+struct BlockOutput {
+    number: u64,
+    hash: String,
+    extrinsics: Vec<ExtrinsicOutput>,
+    events: Vec<EventRecord>,
+}
+
+/// An extrinsic in the JSON output.
+#[derive(Serialize)]
+struct ExtrinsicOutput {
+    index: u32,
+    call: String,
+    contract_calls: Vec<ContractCallOutput>,
+    events: Vec<EventRecord>,
+}
+
+/// A call of a contract in the JSON output.
+#[derive(Serialize)]
+struct ContractCallOutput {
+    wrapped_in: Vec<String>,
+    contract: String,
+    value: u128,
+    /// The decoded message, `None` if none of the contracts knows it.
+    message: Option<Value>,
+    data: String,
+}
+
+impl ContractCallOutput {
+    fn new(
+        call: &ContractCallData,
+        transcoders: &[ContractMessageTranscoder],
+        ss58_prefix: u16,
+    ) -> Self {
+        Self {
+            wrapped_in: call.wrapped_in.clone(),
+            contract: call.contract_address(ss58_prefix),
+            value: call.value,
+            message: call.decode_message(transcoders),
+            data: format!("0x{}", hex::encode(&call.data)),
+        }
+    }
+}
+
+impl InspectBlockCommand {
+    /// Returns whether to export the output in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment>(&self) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor,
+        <C as Config>::Hash: From<[u8; 32]> + Debug,
+        <C as Environment>::Balance: Display + TryFrom<u128>,
+    {
+        let transcoders = self
+            .metadata
+            .iter()
+            .map(|path| {
+                ContractArtifacts::from_manifest_or_file(None, Some(path))?
+                    .contract_transcoder()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let options = self.chain_cli_opts.rpc_options();
+        let url = url_to_string(&self.chain_cli_opts.chain().url());
+        let rpc_cli = rpc_client(&url, options.clone()).await?;
+        let client = online_client::<C>(rpc_cli.clone(), options).await?;
+        let ss58_prefix = self.chain_cli_opts.ss58_prefix_for(Some(&client.metadata()));
+        let transcoders: Vec<_> = transcoders
+            .into_iter()
+            .map(|transcoder| transcoder.with_ss58_prefix(ss58_prefix))
+            .collect();
+
+        let block_hash: <C as Config>::Hash = match &self.block {
+            BlockId::Hash(hash) => (*hash).into(),
+            BlockId::Number(number) => {
+                LegacyRpcMethods::<C>::new(rpc_cli)
+                    .chain_get_block_hash(Some((*number).into()))
+                    .await?
+                    .ok_or_else(|| anyhow!("Block {number} not found"))?
+            }
+        };
+        let block = inspect_block(&client, block_hash, &transcoders, ss58_prefix).await?;
+
+        if self.output_json {
+            let output = BlockOutput {
+                number: block.number,
+                hash: format!("{block_hash:?}"),
+                extrinsics: block
+                    .extrinsics
+                    .into_iter()
+                    .map(|extrinsic| {
+                        ExtrinsicOutput {
+                            index: extrinsic.index,
+                            call: format!("{}::{}", extrinsic.pallet, extrinsic.call),
+                            contract_calls: extrinsic
+                                .contract_calls
+                                .iter()
+                                .map(|call| {
+                                    ContractCallOutput::new(
+                                        call,
+                                        &transcoders,
+                                        ss58_prefix,
+                                    )
+                                })
+                                .collect(),
+                            events: extrinsic.events,
+                        }
+                    })
+                    .collect(),
+                events: block.events,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(())
+        }
+
+        let token_metadata = self.chain_cli_opts.token_metadata::<C>().await?;
+        name_value_println!("Block", format!("#{} {block_hash:?}", block.number));
+        if block.extrinsics.is_empty() && block.events.is_empty() {
+            println!("The block contains no extrinsics or events of contracts");
+            return Ok(())
+        }
+        for extrinsic in &block.extrinsics {
+            display_extrinsic(extrinsic, &transcoders, ss58_prefix);
+            if !extrinsic.events.is_empty() {
+                let events = DisplayEvents::from_records(extrinsic.events.clone());
+                println!(
+                    "{}",
+                    events.display_events::<C>(Verbosity::Verbose, &token_metadata)?
+                );
+            }
+        }
+        if !block.events.is_empty() {
+            name_value_println!("Block events", "");
+            let events = DisplayEvents::from_records(block.events);
+            println!(
+                "{}",
+                events.display_events::<C>(Verbosity::Verbose, &token_metadata)?
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Prints the call of the `extrinsic` and the calls of contracts it contains, with the
+/// addresses of the contracts displayed with the `ss58_prefix`.
+fn display_extrinsic(
+    extrinsic: &ContractsExtrinsic,
+    transcoders: &[ContractMessageTranscoder],
+    ss58_prefix: u16,
+) {
+    name_value_println!(
+        "Extrinsic",
+        format!(
+            "#{} {}::{}",
+            extrinsic.index, extrinsic.pallet, extrinsic.call
+        )
+    );
+    for call in &extrinsic.contract_calls {
+        let call = ContractCallOutput::new(call, transcoders, ss58_prefix);
+        if !call.wrapped_in.is_empty() {
+            name_value_println!("Wrapped in", call.wrapped_in.join(" > "));
+        }
+        name_value_println!("Contract", call.contract);
+        name_value_println!("Value", call.value.to_string());
+        match call.message {
+            Some(message) => name_value_println!("Message", message),
+            None => name_value_println!("Data", call.data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_block_id() {
+        let hash = format!("0x{}", "ab".repeat(32));
+
+        assert_eq!(BlockId::from_str("42").unwrap(), BlockId::Number(42));
+        assert_eq!(BlockId::from_str(&hash).unwrap(), BlockId::Hash([0xab; 32]));
+        assert!(BlockId::from_str("0x1234").is_err());
+        assert!(BlockId::from_str("latest").is_err());
+    }
+}
@@ -15,15 +15,25 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
+    account::ensure_account_mapped,
     config::SignerConfig,
     display_contract_exec_result,
     display_contract_exec_result_debug,
     display_dry_run_result_warning,
+    display_extrinsic_receipt,
+    ensure_sudo_key,
+    json_output::{
+        ExtrinsicOutput,
+        SubmissionOutput,
+    },
     parse_balance,
     print_dry_running_status,
     print_gas_required_success,
     prompt_confirm_tx,
+    sandbox::CLISandboxOpts,
     CLIExtrinsicOpts,
+    ExplorerLinks,
+    TxContext,
     MAX_KEY_COL_WIDTH,
 };
 use crate::{
@@ -44,14 +54,26 @@ use contract_build::{
     Verbosity,
 };
 use contract_extrinsics::{
+    call_wrapper,
+    contracts_backend,
+    derive_contract_address,
+    online_client,
+    rpc_client,
+    ss58,
+    url_to_string,
+    BalanceVariant,
     Code,
+    ContractArtifacts,
     DisplayEvents,
+    EventFilter,
     ExtrinsicOptsBuilder,
     InstantiateCommandBuilder,
     InstantiateDryRunResult,
     InstantiateExecResult,
+    StorageDepositBreakdown,
     TokenMetadata,
 };
+use contract_transcode::SolidityAbi;
 use ink_env::Environment;
 use serde::Serialize;
 use sp_core::Bytes;
@@ -60,6 +82,7 @@ use std::{
         Debug,
         Display,
     },
+    path::PathBuf,
     str::FromStr,
 };
 use subxt::{
@@ -68,16 +91,20 @@ use subxt::{
         ExtrinsicParams,
     },
     ext::{
-        codec::Decode,
+        codec::{
+            Decode,
+            Encode,
+        },
         scale_decode::IntoVisitor,
         scale_encode::EncodeAsType,
     },
+    tx::Signer as _,
     Config,
 };
 
 #[derive(Debug, clap::Args)]
 pub struct InstantiateCommand {
-    /// The name of the contract constructor to call
+    /// The name of the contract constructor to call, or its selector e.g. `0x9bae9d5e`
     #[clap(name = "constructor", long, default_value = "new")]
     constructor: String,
     /// The constructor arguments, encoded as strings
@@ -104,6 +131,42 @@ pub struct InstantiateCommand {
     /// Export the instantiate output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
+    /// Path to a Solidity ABI file of the contract, e.g. produced by `solang`.
+    ///
+    /// The constructor arguments are then encoded according to the Solidity ABI instead
+    /// of the ink! metadata, and the contract code is read from the `.wasm` file.
+    #[clap(long, value_parser)]
+    abi: Option<PathBuf>,
+    /// Always upload the contract code with the instantiation.
+    ///
+    /// By default the contract is instantiated from the code already stored on chain, if
+    /// the code hash of the contract exists there.
+    #[clap(long)]
+    always_upload: bool,
+    /// Map the account to an address before instantiating, if required by the chain.
+    ///
+    /// Chains running `pallet-revive` only allow mapped accounts to interact with
+    /// contracts. Without this flag, the mapping is offered interactively.
+    #[clap(long)]
+    map_account: bool,
+    /// Dispatch the instantiation as the signer through `sudo.sudo_as`, e.g. on
+    /// development chains only allowing privileged origins to instantiate contracts.
+    ///
+    /// The signer must be the sudo key of the chain. Chains requiring accounts to be
+    /// mapped are not supported with this flag.
+    #[clap(long, conflicts_with_all = ["map_account", "sandbox"])]
+    sudo: bool,
+    /// Print the address the contract will be instantiated at and exit, without
+    /// submitting an extrinsic.
+    ///
+    /// The address is derived from the signer, the code hash, the constructor call and
+    /// the salt like `pallet-contracts` does, e.g. to fund the contract in advance. The
+    /// node is only connected to for the runtime metadata, which is usually cached, to
+    /// refuse chains running `pallet-revive`, which derives addresses differently.
+    #[clap(long, conflicts_with_all = ["sandbox", "sudo"])]
+    print_address: bool,
+    #[clap(flatten)]
+    sandbox_opts: CLISandboxOpts,
 }
 
 /// Parse hex encoded bytes.
@@ -119,6 +182,19 @@ impl InstantiateCommand {
     }
 
     pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        if self.sandbox_opts.sandbox {
+            return self.run_in_sandbox()
+        }
+        if self.print_address {
+            return call_with_config!(
+                self,
+                print_address,
+                self.extrinsic_cli_opts.chain_cli_opts.chain().config()
+            )
+        }
+        if self.extrinsic_cli_opts.execute {
+            self.extrinsic_cli_opts.ensure_can_submit()?;
+        }
         call_with_config!(
             self,
             run,
@@ -126,23 +202,193 @@ impl InstantiateCommand {
         )
     }
 
+    /// Prints the address the contract will be instantiated at, derived without
+    /// submitting an extrinsic.
+    async fn print_address<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as SignerConfig<C>>::Signer: subxt::tx::Signer<C> + FromStr,
+        <C as Config>::AccountId: Encode + Decode + Display,
+    {
+        let chain_cli_opts = &self.extrinsic_cli_opts.chain_cli_opts;
+        let options = chain_cli_opts.rpc_options();
+        let url = url_to_string(&chain_cli_opts.chain().url());
+        let rpc = rpc_client(&url, options.clone()).await?;
+        let client = online_client::<C>(rpc, options).await?;
+        if contracts_backend(&client.metadata())?.h160_addresses() {
+            return Err(anyhow!(
+                "The address of a contract can't be derived in advance on chains running \
+                 `pallet-revive`, it is printed after the instantiation"
+            )
+            .into())
+        }
+        let ss58_prefix = chain_cli_opts.ss58_prefix_for(Some(&client.metadata()));
+        let artifacts = ContractArtifacts::from_manifest_or_file(
+            self.extrinsic_cli_opts.manifest_path.as_ref(),
+            self.extrinsic_cli_opts.file.as_ref(),
+        )?;
+        let data = match self.abi.as_ref() {
+            Some(abi) => SolidityAbi::load(abi)?.encode_constructor(&self.args)?,
+            None => {
+                artifacts
+                    .contract_transcoder()?
+                    .encode(&self.constructor, &self.args)?
+            }
+        };
+        let salt = self.salt.clone().map(|salt| salt.0).unwrap_or_default();
+        let deployer = self.extrinsic_cli_opts.signer::<C>()?.account_id();
+        let contract = derive_contract_address(
+            &deployer,
+            &artifacts.code_hash()?,
+            &data,
+            &salt,
+        )?;
+        let contract = ss58::display_account(&contract, ss58_prefix);
+        if self.output_json() {
+            let json = serde_json::json!({ "contract": contract });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            name_value_println!("Contract", contract);
+        }
+        Ok(())
+    }
+
+    /// Instantiates the contract in the sandbox and displays the result like a dry-run.
+    #[cfg(feature = "sandbox")]
+    fn run_in_sandbox(&self) -> Result<(), ErrorVariant> {
+        use super::sandbox::{
+            self,
+            SANDBOX_TOKEN,
+        };
+        use anyhow::Context;
+        use contract_extrinsics::SandboxBackend;
+        use contract_transcode::ReturnError;
+        use ink_env::DefaultEnvironment;
+
+        let ss58_prefix = self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix_for(None);
+        let artifacts = ContractArtifacts::from_manifest_or_file(
+            self.extrinsic_cli_opts.manifest_path.as_ref(),
+            self.extrinsic_cli_opts.file.as_ref(),
+        )?;
+        let transcoder = artifacts.contract_transcoder()?.with_ss58_prefix(ss58_prefix);
+        let code = artifacts
+            .code
+            .as_ref()
+            .ok_or_else(|| anyhow!("Contract code not found"))?
+            .as_bytes()
+            .to_vec();
+        let data = transcoder.encode(&self.constructor, &self.args)?;
+        let value = parse_balance(&self.value, &SANDBOX_TOKEN)
+            .map_err(|e| anyhow!("Failed to parse value option: {}", e))?;
+        let storage_deposit_limit = self
+            .extrinsic_cli_opts
+            .storage_deposit_limit
+            .as_ref()
+            .map(|b| parse_balance(b, &SANDBOX_TOKEN))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse storage_deposit_limit option: {}", e))?;
+        let salt = self.salt.clone().map(|salt| salt.to_vec()).unwrap_or_default();
+        let gas_limit = sandbox::gas_limit(self.gas_limit, self.proof_size)?;
+
+        let mut backend = self.sandbox_opts.load()?;
+        let instantiated = backend.instantiate(
+            code,
+            data,
+            value,
+            salt,
+            gas_limit,
+            storage_deposit_limit,
+        )?;
+        let result = &instantiated.result;
+        let ret_val = match &result.result {
+            Ok(ret_val) => ret_val,
+            Err(err) => {
+                return Err(ErrorVariant::from_dispatch_error(
+                    err,
+                    &SandboxBackend::metadata()?,
+                )?)
+            }
+        };
+        let value = transcoder
+            .decode_constructor_return(&self.constructor, &mut &ret_val.result.data[..])
+            .context(format!("Failed to decode return value {:?}", &ret_val))
+            .map_err(ErrorVariant::decode)?;
+        let reverted = ret_val.result.did_revert();
+        let dry_run_result = InstantiateDryRunResult {
+            error: reverted
+                .then(|| {
+                    let lang_result =
+                        transcoder.constructor_returns_lang_result(&self.constructor);
+                    ReturnError::from_return_value(&value, lang_result)
+                })
+                .flatten(),
+            result: value,
+            contract: ss58::display_account(&ret_val.account_id, ss58_prefix),
+            reverted,
+            gas_consumed: result.gas_consumed,
+            gas_required: result.gas_required,
+            storage_deposit: result.storage_deposit.clone(),
+        };
+        let events =
+            sandbox::display_events(instantiated.events, &transcoder, ss58_prefix)?;
+        if !reverted {
+            self.sandbox_opts.store(&mut backend)?;
+        }
+
+        if self.output_json() {
+            let mut output = ExtrinsicOutput::instantiate_dry_run(dry_run_result);
+            output.events = Some(events);
+            println!("{}", output.to_json()?);
+        } else {
+            print_instantiate_dry_run_result(&dry_run_result);
+            display_contract_exec_result_debug::<_, DEFAULT_KEY_COL_WIDTH, _>(result)?;
+            println!(
+                "{}",
+                events.display_events::<DefaultEnvironment>(
+                    self.extrinsic_cli_opts.verbosity()?,
+                    &SANDBOX_TOKEN
+                )?
+            );
+        }
+        if reverted {
+            return Err(ErrorVariant::dry_run_revert("The constructor reverted"))
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    fn run_in_sandbox(&self) -> Result<(), ErrorVariant> {
+        Err(super::sandbox::sandbox_unsupported())
+    }
+
     async fn run<C: Config + Environment + SignerConfig<C>>(
         &self,
     ) -> Result<(), ErrorVariant>
     where
         <C as SignerConfig<C>>::Signer: subxt::tx::Signer<C> + Clone + FromStr,
-        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Decode + Display,
+        <C as Config>::AccountId:
+            IntoVisitor + FromStr + EncodeAsType + Decode + Display + AsRef<[u8]>,
         <<C as Config>::AccountId as FromStr>::Err: Display,
-        C::Balance:
-            From<u128> + Display + Default + FromStr + Serialize + Debug + EncodeAsType,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
         <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
         <C as Config>::Hash: From<[u8; 32]> + IntoVisitor + EncodeAsType,
     {
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let account_id = subxt::tx::Signer::<C>::account_id(&signer);
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
-        let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
 
         let storage_deposit_limit = self
             .extrinsic_cli_opts
@@ -155,21 +401,39 @@ impl InstantiateCommand {
             })?;
         let value = parse_balance(&self.value, &token_metadata)
             .map_err(|e| anyhow::anyhow!("Failed to parse value option: {}", e))?;
-        let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
+        let mut extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
             .file(self.extrinsic_cli_opts.file.clone())
             .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
             .url(chain.url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
             .storage_deposit_limit(storage_deposit_limit)
-            .done();
+            .unlimited_deposit(self.extrinsic_cli_opts.unlimited_deposit())
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(&token_metadata)?)
+            // The account may be mapped before the instantiation, so both extrinsics
+            // must draw consecutive nonces from the same provider.
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider().tracking())
+            .progress(self.extrinsic_cli_opts.progress()?);
+        if self.sudo {
+            // `pallet-contracts` requires a signed origin, the root origin is rejected
+            extrinsic_opts = extrinsic_opts.wrap_call(call_wrapper::sudo_as(&account_id));
+        }
+        let extrinsic_opts = extrinsic_opts.done();
 
         let instantiate_exec: InstantiateExec<C, C, _> =
-            InstantiateCommandBuilder::new(extrinsic_opts)
+            InstantiateCommandBuilder::new(extrinsic_opts.clone())
                 .constructor(self.constructor.clone())
                 .args(self.args.clone())
                 .value(value)
                 .gas_limit(self.gas_limit)
                 .proof_size(self.proof_size)
                 .salt(self.salt.clone())
+                .abi(self.abi.clone())
+                .always_upload(self.always_upload)
                 .done()
                 .await?;
 
@@ -177,8 +441,10 @@ impl InstantiateCommand {
             let result = instantiate_exec.instantiate_dry_run().await?;
             match instantiate_exec.decode_instantiate_dry_run(&result).await {
                 Ok(dry_run_result) => {
+                    let reverted = dry_run_result.reverted;
                     if self.output_json() {
-                        println!("{}", dry_run_result.to_json()?);
+                        let output = ExtrinsicOutput::instantiate_dry_run(dry_run_result);
+                        println!("{}", output.to_json()?);
                     } else {
                         print_instantiate_dry_run_result(&dry_run_result);
                         display_contract_exec_result_debug::<_, DEFAULT_KEY_COL_WIDTH, _>(
@@ -186,6 +452,11 @@ impl InstantiateCommand {
                         )?;
                         display_dry_run_result_warning("instantiate");
                     }
+                    if reverted {
+                        return Err(ErrorVariant::dry_run_revert(
+                            "The constructor reverted",
+                        ))
+                    }
                     Ok(())
                 }
                 Err(object) => {
@@ -208,16 +479,40 @@ impl InstantiateCommand {
                     prompt_confirm_unverifiable_upload(&chain.to_string())?
                 }
             }
+            if self.sudo {
+                ensure_sudo_key(
+                    instantiate_exec.client(),
+                    &instantiate_exec.opts().signer().account_id(),
+                )
+                .await?;
+            } else {
+                ensure_account_mapped(
+                    extrinsic_opts,
+                    self.map_account,
+                    self.extrinsic_cli_opts.skip_confirm,
+                    self.output_json(),
+                )
+                .await?;
+            }
             tracing::debug!("instantiate data {:?}", instantiate_exec.args().data());
-            let gas_limit = pre_submit_dry_run_gas_estimate_instantiate(
+            let (gas_limit, dry_run_result) = pre_submit_dry_run_gas_estimate_instantiate(
                 &instantiate_exec,
                 self.output_json(),
                 self.extrinsic_cli_opts.skip_dry_run,
             )
             .await?;
             if !self.extrinsic_cli_opts.skip_confirm {
-                prompt_confirm_tx(|| {
+                let context = TxContext::query::<C>(
+                    &chain.url(),
+                    self.extrinsic_cli_opts.chain_cli_opts.rpc_options(),
+                    &instantiate_exec.opts().signer().account_id(),
+                    instantiate_exec.estimate_fee(gas_limit),
+                    &token_metadata,
+                )
+                .await?;
+                prompt_confirm_tx(&context, || {
                     print_default_instantiate_preview(&instantiate_exec, gas_limit);
+                    name_value_println!("Value", self.value, DEFAULT_KEY_COL_WIDTH);
                     if let Code::Existing(code_hash) =
                         instantiate_exec.args().code().clone()
                     {
@@ -229,14 +524,18 @@ impl InstantiateCommand {
                     }
                 })?;
             }
-            let instantiate_result =
+            let mut instantiate_result =
                 instantiate_exec.instantiate(Some(gas_limit)).await?;
+            instantiate_result.return_value =
+                dry_run_result.as_ref().map(|dry_run| dry_run.result.clone());
             display_result(
-                &instantiate_exec,
                 instantiate_result,
+                dry_run_result,
+                &self.extrinsic_cli_opts,
                 &token_metadata,
                 self.output_json(),
                 self.extrinsic_cli_opts.verbosity().unwrap(),
+                self.extrinsic_cli_opts.event_filter(),
             )
             .await?;
             Ok(())
@@ -245,13 +544,16 @@ impl InstantiateCommand {
 }
 
 /// A helper function to estimate the gas required for a contract instantiation.
+///
+/// Also returns the result of the dry-run, unless it was skipped or its return value
+/// could not be decoded.
 async fn pre_submit_dry_run_gas_estimate_instantiate<
     C: Config + Environment + SignerConfig<C>,
 >(
     instantiate_exec: &InstantiateExec<C, C, C::Signer>,
     output_json: bool,
     skip_dry_run: bool,
-) -> Result<Weight>
+) -> Result<(Weight, Option<InstantiateDryRunResult<C::Balance>>)>
 where
     C::Signer: subxt::tx::Signer<C> + Clone,
     <C as Config>::AccountId: IntoVisitor + Display + Decode,
@@ -262,7 +564,7 @@ where
 {
     if skip_dry_run {
         return match (instantiate_exec.args().gas_limit(), instantiate_exec.args().proof_size()) {
-                (Some(ref_time), Some(proof_size)) => Ok(Weight::from_parts(ref_time, proof_size)),
+                (Some(ref_time), Some(proof_size)) => Ok((Weight::from_parts(ref_time, proof_size), None)),
                 _ => {
                     Err(anyhow!(
                         "Weight args `--gas` and `--proof-size` required if `--skip-dry-run` specified"
@@ -279,6 +581,10 @@ where
             if !output_json {
                 print_gas_required_success(instantiate_result.gas_required);
             }
+            let dry_run_result = instantiate_exec
+                .decode_instantiate_dry_run(&instantiate_result)
+                .await
+                .ok();
             // use user specified values where provided, otherwise use the estimates
             let ref_time = instantiate_exec
                 .args()
@@ -288,7 +594,7 @@ where
                 .args()
                 .proof_size()
                 .unwrap_or_else(|| instantiate_result.gas_required.proof_size());
-            Ok(Weight::from_parts(ref_time, proof_size))
+            Ok((Weight::from_parts(ref_time, proof_size), dry_run_result))
         }
         Err(ref err) => {
             let object = ErrorVariant::from_dispatch_error(
@@ -310,46 +616,99 @@ where
 }
 
 /// Displays the results of contract instantiation, including contract address,
-/// events, and optional code hash.
+/// events and code hash, and the result of the preceding `dry_run` in the
+/// JSON output.
+#[allow(clippy::too_many_arguments)]
 pub async fn display_result<C: Config + Environment + SignerConfig<C>>(
-    instantiate_exec: &InstantiateExec<C, C, C::Signer>,
     instantiate_exec_result: InstantiateExecResult<C>,
+    dry_run: Option<InstantiateDryRunResult<C::Balance>>,
+    extrinsic_cli_opts: &CLIExtrinsicOpts,
     token_metadata: &TokenMetadata,
     output_json: bool,
     verbosity: Verbosity,
+    event_filter: EventFilter,
 ) -> Result<(), ErrorVariant>
 where
     <C as Config>::AccountId: IntoVisitor + EncodeAsType + Display + Decode,
     <C as Config>::Hash: IntoVisitor + EncodeAsType,
-    C::Balance: Serialize + From<u128> + Display + EncodeAsType,
+    C::Balance: Serialize + TryFrom<u128> + Display + EncodeAsType,
     <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
         From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
 {
-    let events = DisplayEvents::from_events::<C, C>(
-        &instantiate_exec_result.events,
-        Some(instantiate_exec.transcoder()),
-        &instantiate_exec.client().metadata(),
-    )?;
-    let contract_address = instantiate_exec_result.contract_address.to_string();
+    let contract_address = instantiate_exec_result.contract;
+    let events = DisplayEvents::from_records(instantiate_exec_result.event_records)
+        .filter(&event_filter.contract(&contract_address));
+    let receipt = &instantiate_exec_result.receipt;
+    let explorer_links = ExplorerLinks::new(
+        extrinsic_cli_opts,
+        &format!("{:?}", receipt.extrinsic_hash),
+        &format!("{:?}", receipt.block_hash),
+        Some(&contract_address),
+    );
+    let code_hash = format!("{:?}", instantiate_exec_result.code_hash);
     if output_json {
-        let display_instantiate_result = InstantiateResult {
-            code_hash: instantiate_exec_result
-                .code_hash
-                .map(|ch| format!("{ch:?}")),
-            contract: Some(contract_address),
-            events,
+        let output = match dry_run {
+            Some(dry_run) => ExtrinsicOutput::instantiate_dry_run(dry_run),
+            None => ExtrinsicOutput::without_dry_run(),
         };
-        println!("{}", display_instantiate_result.to_json()?)
+        let mut submission =
+            SubmissionOutput::new(receipt, explorer_links, contract_address);
+        submission.code_hash = Some(code_hash);
+        submission.storage_deposit = instantiate_exec_result.storage_deposit;
+        submission.deposit_charged = instantiate_exec_result.deposit_charged;
+        println!("{}", output.submitted(submission, events).to_json()?)
     } else {
         println!("{}", events.display_events::<C>(verbosity, token_metadata)?);
-        if let Some(code_hash) = instantiate_exec_result.code_hash {
-            name_value_println!("Code hash", format!("{code_hash:?}"));
-        }
+        name_value_println!("Code hash", code_hash);
         name_value_println!("Contract", contract_address);
+        if let Some(return_value) = &instantiate_exec_result.return_value {
+            name_value_println!("Return value", format!("{return_value}"));
+        }
+        if let Some(deposit_charged) = instantiate_exec_result.deposit_charged {
+            name_value_println!(
+                "Deposit charged",
+                BalanceVariant::<u128>::from(deposit_charged, Some(token_metadata))?
+                    .to_string()
+            );
+        }
+        if let Some(storage_deposit) = &instantiate_exec_result.storage_deposit {
+            print_storage_deposit(storage_deposit, token_metadata)?;
+        }
+        display_extrinsic_receipt(receipt);
+        explorer_links.print();
     };
     Ok(())
 }
 
+/// Prints the storage deposit held for the instantiated contract, by what it pays for.
+fn print_storage_deposit(
+    deposit: &StorageDepositBreakdown,
+    token_metadata: &TokenMetadata,
+) -> Result<()> {
+    let balance = |value| BalanceVariant::<u128>::from(value, Some(token_metadata));
+    name_value_println!("Base deposit", balance(deposit.base_deposit)?.to_string());
+    name_value_println!(
+        "Items deposit",
+        format!(
+            "{} ({} items x {})",
+            balance(deposit.items_deposit)?,
+            deposit.storage_items,
+            balance(deposit.deposit_per_item)?
+        )
+    );
+    name_value_println!(
+        "Bytes deposit",
+        format!(
+            "{} ({} bytes x {})",
+            balance(deposit.bytes_deposit)?,
+            deposit.storage_bytes,
+            balance(deposit.deposit_per_byte)?
+        )
+    );
+    name_value_println!("Storage deposit", balance(deposit.total())?.to_string());
+    Ok(())
+}
+
 pub fn print_default_instantiate_preview<C: Config + Environment + SignerConfig<C>>(
     instantiate_exec: &InstantiateExec<C, C, C::Signer>,
     gas_limit: Weight,
@@ -374,25 +733,6 @@ pub fn print_default_instantiate_preview<C: Config + Environment + SignerConfig<
     name_value_println!("Gas limit", gas_limit.to_string(), DEFAULT_KEY_COL_WIDTH);
 }
 
-/// Result of a successful contract instantiation for displaying.
-#[derive(serde::Serialize)]
-pub struct InstantiateResult {
-    /// Instantiated contract hash
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub contract: Option<String>,
-    /// Instantiated code hash
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub code_hash: Option<String>,
-    /// The events emitted from the instantiate extrinsic invocation.
-    pub events: DisplayEvents,
-}
-
-impl InstantiateResult {
-    pub fn to_json(&self) -> Result<String> {
-        Ok(serde_json::to_string_pretty(self)?)
-    }
-}
-
 pub fn print_instantiate_dry_run_result<Balance: Serialize>(
     result: &InstantiateDryRunResult<Balance>,
 ) {
@@ -406,6 +746,9 @@ pub fn print_instantiate_dry_run_result<Balance: Serialize>(
         format!("{:?}", result.reverted),
         DEFAULT_KEY_COL_WIDTH
     );
+    if let Some(error) = &result.error {
+        name_value_println!("Error", error.to_string(), DEFAULT_KEY_COL_WIDTH);
+    }
     name_value_println!("Contract", result.contract, DEFAULT_KEY_COL_WIDTH);
     name_value_println!(
         "Gas consumed",
@@ -413,3 +756,33 @@ pub fn print_instantiate_dry_run_result<Balance: Serialize>(
         DEFAULT_KEY_COL_WIDTH
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        instantiate: InstantiateCommand,
+    }
+
+    #[test]
+    fn sudo_conflicts_with_map_account() {
+        let cli = Cli::try_parse_from(["instantiate", "--sudo", "--suri", "//Alice"])
+            .unwrap();
+        assert!(cli.instantiate.sudo);
+
+        let err = Cli::try_parse_from([
+            "instantiate",
+            "--sudo",
+            "--map-account",
+            "--suri",
+            "//Alice",
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2018-2023 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The versioned JSON output of `call` and `instantiate`.
+
+use super::{
+    call::CallDryRunResult,
+    ExplorerLinks,
+};
+use anyhow::Result;
+use contract_extrinsics::{
+    pallet_contracts_primitives::StorageDeposit,
+    DisplayEvents,
+    ExtrinsicReceipt,
+    InstantiateDryRunResult,
+    StorageDepositBreakdown,
+};
+use contract_transcode::{
+    ReturnError,
+    Value,
+};
+use serde::Serialize;
+use sp_weights::Weight;
+use std::fmt::Debug;
+
+/// The version of the JSON output schema, incremented with every change which can break
+/// parsers of the output, like renaming, removing or retyping fields.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The JSON output of `call` and `instantiate`.
+///
+/// All phases are always present, `null` if they did not take place, e.g. the
+/// `submission` and `events` of a dry-run or the `dry_run` with `--skip-dry-run`.
+#[derive(Serialize)]
+pub struct ExtrinsicOutput<Balance> {
+    pub schema_version: u32,
+    pub dry_run: Option<DryRunOutput<Balance>>,
+    pub submission: Option<SubmissionOutput>,
+    pub events: Option<DisplayEvents>,
+    /// The decoded value returned by the dry-run, without the `Result<_, LangError>`
+    /// wrapper of ink! messages.
+    pub decoded_return: Option<Value>,
+}
+
+impl<Balance: Serialize> ExtrinsicOutput<Balance> {
+    /// Creates the output of a submitted extrinsic which was not dry-run before.
+    pub fn without_dry_run() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            dry_run: None,
+            submission: None,
+            events: None,
+            decoded_return: None,
+        }
+    }
+
+    /// Creates the output of the dry-run of a call of the `contract`.
+    pub fn call_dry_run(contract: String, result: CallDryRunResult<Balance>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            dry_run: Some(DryRunOutput {
+                contract,
+                reverted: result.reverted,
+                error: result.error,
+                gas_consumed: result.gas_consumed,
+                gas_required: result.gas_required,
+                storage_deposit: result.storage_deposit,
+            }),
+            submission: None,
+            events: None,
+            decoded_return: Some(result.data),
+        }
+    }
+
+    /// Creates the output of the dry-run of an instantiation.
+    pub fn instantiate_dry_run(result: InstantiateDryRunResult<Balance>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            dry_run: Some(DryRunOutput {
+                contract: result.contract,
+                reverted: result.reverted,
+                error: result.error,
+                gas_consumed: result.gas_consumed,
+                gas_required: result.gas_required,
+                storage_deposit: result.storage_deposit,
+            }),
+            submission: None,
+            events: None,
+            decoded_return: Some(result.result),
+        }
+    }
+
+    /// Adds the `submission` of the extrinsic and the `events` it emitted.
+    pub fn submitted(
+        mut self,
+        submission: SubmissionOutput,
+        events: DisplayEvents,
+    ) -> Self {
+        self.submission = Some(submission);
+        self.events = Some(events);
+        self
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// The result of dry-running a call or instantiation.
+#[derive(Serialize)]
+pub struct DryRunOutput<Balance> {
+    /// The address of the called contract, or of the contract to be instantiated.
+    pub contract: String,
+    pub reverted: bool,
+    /// The error returned by the message or constructor, if it reverted.
+    pub error: Option<ReturnError>,
+    pub gas_consumed: Weight,
+    pub gas_required: Weight,
+    pub storage_deposit: StorageDeposit<Balance>,
+}
+
+/// The submitted extrinsic of a call or instantiation.
+#[derive(Serialize)]
+pub struct SubmissionOutput {
+    pub extrinsic: ExtrinsicReceipt<String>,
+    pub explorer_links: ExplorerLinks,
+    /// The address of the called or instantiated contract.
+    pub contract: String,
+    /// The hash of the code of the instantiated contract.
+    pub code_hash: Option<String>,
+    /// The storage deposit held for the instantiated contract.
+    pub storage_deposit: Option<StorageDepositBreakdown>,
+    /// The storage deposit charged for the instantiation.
+    pub deposit_charged: Option<u128>,
+    /// The call scheduled with `--schedule-at`.
+    pub scheduled: Option<ScheduledOutput>,
+}
+
+impl SubmissionOutput {
+    /// Creates the output of the submitted extrinsic with the `receipt`.
+    pub fn new<Hash: Debug>(
+        receipt: &ExtrinsicReceipt<Hash>,
+        explorer_links: ExplorerLinks,
+        contract: String,
+    ) -> Self {
+        Self {
+            extrinsic: ExtrinsicReceipt {
+                extrinsic_hash: format!("{:?}", receipt.extrinsic_hash),
+                block_hash: format!("{:?}", receipt.block_hash),
+                block_number: receipt.block_number,
+            },
+            explorer_links,
+            contract,
+            code_hash: None,
+            storage_deposit: None,
+            deposit_charged: None,
+            scheduled: None,
+        }
+    }
+}
+
+/// The id of a call scheduled with the `Scheduler` pallet.
+#[derive(Serialize)]
+pub struct ScheduledOutput {
+    /// The block the call is dispatched at.
+    pub when: u64,
+    /// The index of the call in the agenda of the block.
+    pub index: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contract_extrinsics::{
+        Event,
+        Field,
+    };
+    use primitive_types::H256;
+    use std::{
+        fs,
+        path::Path,
+    };
+
+    /// Compares the `actual` output with the golden file, which is updated instead if
+    /// `UPDATE_SNAPSHOTS` is set.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("golden")
+            .join(name);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&path, actual).unwrap();
+            return
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_default();
+        assert_eq!(
+            expected,
+            actual,
+            "The output differs from {}, run with UPDATE_SNAPSHOTS=1 to update it",
+            path.display()
+        );
+    }
+
+    fn explorer_links() -> ExplorerLinks {
+        ExplorerLinks {
+            subscan_extrinsic: None,
+            subscan_contract: None,
+            polkadot_js: "https://polkadot.js.org/apps/#/explorer/query/42".to_string(),
+        }
+    }
+
+    fn receipt() -> ExtrinsicReceipt<H256> {
+        ExtrinsicReceipt {
+            extrinsic_hash: H256::repeat_byte(0x01),
+            block_hash: H256::repeat_byte(0x02),
+            block_number: 42,
+        }
+    }
+
+    #[test]
+    fn call_dry_run_output() {
+        let dry_run = CallDryRunResult {
+            reverted: false,
+            data: Value::Bool(true),
+            error: None,
+            gas_consumed: Weight::from_parts(1_000, 100),
+            gas_required: Weight::from_parts(2_000, 200),
+            storage_deposit: StorageDeposit::Charge(0u128),
+        };
+
+        let output = ExtrinsicOutput::call_dry_run("5Contract".to_string(), dry_run);
+
+        assert_golden("call_dry_run.json", &output.to_json().unwrap());
+    }
+
+    #[test]
+    fn call_submitted_without_dry_run_output() {
+        let mut submission =
+            SubmissionOutput::new(&receipt(), explorer_links(), "5Contract".to_string());
+        submission.scheduled = Some(ScheduledOutput { when: 50, index: 0 });
+        let events = DisplayEvents::from(vec![Event {
+            pallet: "Scheduler".to_string(),
+            name: "Scheduled".to_string(),
+            fields: vec![
+                Field::new("when".to_string(), Value::UInt(50), None),
+                Field::new("index".to_string(), Value::UInt(0), None),
+            ],
+        }]);
+
+        let output =
+            ExtrinsicOutput::<u128>::without_dry_run().submitted(submission, events);
+
+        assert_golden("call_scheduled.json", &output.to_json().unwrap());
+    }
+
+    #[test]
+    fn instantiate_submitted_output() {
+        let dry_run = InstantiateDryRunResult {
+            result: Value::Unit,
+            contract: "5Contract".to_string(),
+            reverted: false,
+            error: None,
+            gas_consumed: Weight::from_parts(1_000, 100),
+            gas_required: Weight::from_parts(2_000, 200),
+            storage_deposit: StorageDeposit::Charge(3_000u128),
+        };
+        let mut submission =
+            SubmissionOutput::new(&receipt(), explorer_links(), "5Contract".to_string());
+        submission.code_hash = Some("0x0303".to_string());
+        submission.deposit_charged = Some(3_000);
+        let events = DisplayEvents::from(vec![Event {
+            pallet: "Contracts".to_string(),
+            name: "Instantiated".to_string(),
+            fields: vec![
+                Field::new("deployer".into(), Value::Literal("5Alice".into()), None),
+                Field::new("contract".into(), Value::Literal("5Contract".into()), None),
+            ],
+        }]);
+
+        let output =
+            ExtrinsicOutput::instantiate_dry_run(dry_run).submitted(submission, events);
+
+        assert_golden("instantiate.json", &output.to_json().unwrap());
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use contract_build::{
+    linting::{
+        LintArgs,
+        LintResult,
+    },
+    ManifestPath,
+    OutputType,
+    Target,
+    VerbosityFlags,
+};
+use std::{
+    convert::TryFrom,
+    path::PathBuf,
+};
+
+/// Checks the contract for common mistakes using `clippy` and the ink! linting rules.
+///
+/// Lint levels can be configured in the `[package.metadata.contract.lint]` section of
+/// the contract's `Cargo.toml` using the `allow`, `warn` and `deny` lists.
+#[derive(Debug, clap::Args)]
+#[clap(name = "lint")]
+pub struct LintCommand {
+    /// Path to the `Cargo.toml` of the contract to lint
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Only run the mandatory ink! linting rules.
+    #[clap(long)]
+    mandatory_only: bool,
+    /// Which bytecode the contract is linted for.
+    #[clap(long, default_value = "wasm")]
+    target: Target,
+    /// Export the lint diagnostics in JSON format.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+}
+
+impl LintCommand {
+    pub fn exec(&self) -> Result<LintResult> {
+        let manifest_path = ManifestPath::try_from(self.manifest_path.as_ref())?;
+        let verbosity = TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?;
+        let output_type = match self.output_json {
+            true => OutputType::Json,
+            false => OutputType::HumanReadable,
+        };
+
+        let args = LintArgs {
+            manifest_path,
+            verbosity,
+            mandatory_only: self.mandatory_only,
+            target: self.target,
+            output_type,
+        };
+        contract_build::linting::execute(args)
+    }
+}
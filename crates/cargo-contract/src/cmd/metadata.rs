@@ -0,0 +1,98 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use clap::{
+    Args,
+    Subcommand,
+};
+use contract_metadata::{
+    ContractMetadata,
+    MetadataVersion,
+};
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct MetadataCommand {
+    #[clap(subcommand)]
+    commands: MetadataCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MetadataCommands {
+    /// Convert the metadata of a contract to another ink! metadata version
+    #[clap(name = "convert")]
+    Convert(ConvertMetadata),
+    /// Print the BLAKE2b-256 hash of the canonical serialization of the metadata
+    #[clap(name = "hash")]
+    Hash(HashMetadata),
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertMetadata {
+    /// Path to the contract metadata: a `.json` metadata file or a `.contract` bundle.
+    #[clap(value_parser)]
+    metadata: PathBuf,
+    /// The ink! metadata version to convert to, e.g. `4` or `5`.
+    #[clap(long)]
+    to: MetadataVersion,
+    /// Path of the file the converted metadata is written to.
+    ///
+    /// The converted metadata is printed to stdout if not specified.
+    #[clap(long, short, value_parser)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct HashMetadata {
+    /// Path to the contract metadata: a `.json` metadata file or a `.contract` bundle.
+    #[clap(value_parser)]
+    metadata: PathBuf,
+}
+
+impl MetadataCommand {
+    pub fn run(&self) -> Result<()> {
+        match &self.commands {
+            MetadataCommands::Convert(convert) => convert.run(),
+            MetadataCommands::Hash(hash) => hash.run(),
+        }
+    }
+}
+
+impl ConvertMetadata {
+    fn run(&self) -> Result<()> {
+        let metadata = ContractMetadata::load(&self.metadata)?;
+        let conversion = metadata.convert(self.to)?;
+        for incompatibility in &conversion.incompatibilities {
+            contract_build::output::warning(incompatibility);
+        }
+
+        let json = serde_json::to_string_pretty(&conversion.metadata)?;
+        match &self.output {
+            Some(output) => std::fs::write(output, json)?,
+            None => println!("{json}"),
+        }
+        Ok(())
+    }
+}
+
+impl HashMetadata {
+    fn run(&self) -> Result<()> {
+        let metadata = ContractMetadata::load(&self.metadata)?;
+        println!("0x{}", hex::encode(metadata.hash()?));
+        Ok(())
+    }
+}
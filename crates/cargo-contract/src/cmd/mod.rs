@@ -15,43 +15,104 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 mod config;
+mod explorer;
 mod prod_chains;
 
+pub mod account;
+pub mod address_book;
+pub mod analyze;
+pub mod bench;
 pub mod build;
+pub mod bundle;
 pub mod call;
+pub mod chain;
+pub mod compat;
+pub mod completions;
+pub mod daemon;
 pub mod decode;
+pub mod diff;
+pub mod doc;
 pub mod encode;
+pub mod faucet;
+pub mod fork;
+pub mod fuzz;
+pub mod generate_client;
 pub mod info;
+pub mod inspect_block;
 pub mod instantiate;
+pub mod json_output;
+pub mod lint;
+pub mod metadata;
+pub mod progress;
+pub mod publish;
+pub mod query;
 pub mod remove;
+pub mod repl;
 pub mod rpc;
+pub mod sandbox;
 pub mod schema;
+pub mod selector;
+pub mod signature;
 pub mod storage;
+pub mod test;
+pub mod transfer;
+pub mod ui;
 pub mod upload;
+pub mod uploader;
 pub mod verify;
+pub mod xcm;
 
+use self::config::SignerConfig;
 pub(crate) use self::{
+    account::AccountCommand,
+    address_book::AddressBookCommand,
+    analyze::AnalyzeCommand,
+    bench::BenchCommand,
     build::{
         BuildCommand,
         CheckCommand,
     },
+    bundle::BundleCommand,
     call::CallCommand,
+    chain::ChainCommand,
+    compat::CheckCompatCommand,
+    completions::CompletionsCommand,
+    daemon::DaemonCommand,
     decode::DecodeCommand,
+    diff::DiffCommand,
+    doc::DocCommand,
+    explorer::ExplorerLinks,
+    faucet::FaucetCommand,
+    fork::ForkCommand,
+    fuzz::FuzzCommand,
+    generate_client::GenerateClientCommand,
     info::{
         ExtendedContractInfo,
         InfoCommand,
     },
+    inspect_block::InspectBlockCommand,
     instantiate::InstantiateCommand,
+    lint::LintCommand,
+    metadata::MetadataCommand,
     prod_chains::ProductionChain,
+    publish::PublishCommand,
+    query::QueryCommand,
     remove::RemoveCommand,
+    repl::ReplCommand,
     rpc::RpcCommand,
     schema::{
         GenerateSchemaCommand,
         VerifySchemaCommand,
     },
+    selector::SelectorCommand,
+    signature::VerifyBundleCommand,
     storage::StorageCommand,
+    test::TestCommand,
+    transfer::TransferCommand,
+    ui::UiCommand,
     upload::UploadCommand,
     verify::VerifyCommand,
+    xcm::XcmCommand,
 };
 
 use crate::{
@@ -66,27 +127,52 @@ use anyhow::{
 use colored::Colorize;
 use contract_build::{
     name_value_println,
+    output,
+    progress::ProgressReporter,
     Verbosity,
     VerbosityFlags,
     DEFAULT_KEY_COL_WIDTH,
 };
 pub(crate) use contract_extrinsics::ErrorVariant;
 use contract_extrinsics::{
+    call_wrapper::fetch_sudo_key,
     pallet_contracts_primitives::ContractResult,
+    ss58,
+    url_to_string,
     BalanceVariant,
+    ChainInfo,
+    EventFilter,
+    ExtrinsicReceipt,
+    NonceProvider,
+    RpcOptions,
+    SubmitOptions,
     TokenMetadata,
+    DEFAULT_FINALIZATION_TIMEOUT,
+    DEFAULT_NONCE_RETRIES,
+    DEFAULT_RPC_RETRIES,
+    DEFAULT_RPC_TIMEOUT,
 };
+use contract_transcode::AccountId32;
 
+use ink_env::Environment;
 use std::{
     fmt::{
         Debug,
         Display,
     },
-    io::{
-        self,
-        Write,
-    },
+    future::Future,
+    io,
     str::FromStr,
+    sync::OnceLock,
+    time::Duration,
+};
+use subxt::{
+    ext::{
+        codec::Encode,
+        scale_decode::IntoVisitor,
+    },
+    Config,
+    OnlineClient,
 };
 
 /// Arguments required for creating and sending an extrinsic to a Substrate node.
@@ -104,8 +190,22 @@ pub struct CLIExtrinsicOpts {
     /// e.g.
     /// - for a dev account "//Alice"
     /// - with a password "//Alice///SECRET_PASSWORD"
-    #[clap(name = "suri", long, short)]
-    suri: String,
+    ///
+    /// Prefer `--suri-stdin` or the `CARGO_CONTRACT_SURI` environment variable to keep
+    /// the secret out of the shell history and the process listing.
+    #[clap(
+        name = "suri",
+        long,
+        short,
+        env = "CARGO_CONTRACT_SURI",
+        hide_env_values = true,
+        required_unless_present = "suri_stdin"
+    )]
+    suri: Option<Suri>,
+    /// Read the secret key URI, including its password if any, from the first line of
+    /// stdin. Takes precedence over `--suri`.
+    #[clap(long)]
+    suri_stdin: bool,
     #[clap(flatten)]
     verbosity: VerbosityFlags,
     /// Submit the extrinsic for on-chain execution.
@@ -115,12 +215,79 @@ pub struct CLIExtrinsicOpts {
     /// storage. consumed.
     #[clap(long)]
     storage_deposit_limit: Option<String>,
+    /// Submit the extrinsic without a storage deposit limit if `--storage-deposit-limit`
+    /// is not passed.
+    ///
+    /// By default the limit is the deposit charged in the dry-run plus a safety margin of
+    /// 10%. Implied by `--skip-dry-run`.
+    #[clap(long, conflicts_with = "storage_deposit_limit")]
+    unlimited_deposit: bool,
     /// Before submitting a transaction, do not dry-run it via RPC first.
     #[clap(long)]
     skip_dry_run: bool,
     /// Before submitting a transaction, do not ask the user for confirmation.
-    #[clap(short('y'), long)]
+    #[clap(short('y'), long, visible_alias = "yes")]
     skip_confirm: bool,
+    /// Wait for the block including the extrinsic to be finalized, instead of only for
+    /// its inclusion into a block.
+    #[clap(long)]
+    wait_for_finalization: bool,
+    /// Timeout in seconds for the finalization of the extrinsic.
+    #[clap(
+        long,
+        requires = "wait_for_finalization",
+        default_value_t = DEFAULT_FINALIZATION_TIMEOUT.as_secs()
+    )]
+    finalization_timeout: u64,
+    /// Timeout in seconds for the inclusion of the extrinsic into a block.
+    ///
+    /// On timeout the hash of the extrinsic is printed, so that it can be tracked
+    /// manually. By default there is no timeout.
+    #[clap(long)]
+    submit_timeout: Option<u64>,
+    /// Submit a mortal extrinsic, which is only valid for the given number of blocks.
+    ///
+    /// This prevents the extrinsic from being replayed later on. By default extrinsics
+    /// are immortal.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    tx_mortality: Option<u64>,
+    /// Tip paid to the block author to prioritize the extrinsic.
+    #[clap(long)]
+    tip: Option<String>,
+    /// Nonce of the extrinsic, instead of the next nonce of the account queried from the
+    /// node.
+    ///
+    /// Allows submitting an extrinsic while previous ones of the same account are still
+    /// pending.
+    #[clap(long)]
+    nonce: Option<u64>,
+    /// Query the nonce from the node only for the first extrinsic of the command, and
+    /// increment it locally for every further one, e.g. when mapping the account before
+    /// the extrinsic.
+    #[clap(long, conflicts_with = "nonce")]
+    track_nonce: bool,
+    /// How often the extrinsic is resubmitted with a refetched nonce if it is rejected
+    /// because its nonce is stale or in the future, e.g. because another extrinsic of the
+    /// same account was submitted concurrently.
+    ///
+    /// The extrinsic is not resubmitted if `--nonce` is passed.
+    #[clap(long, default_value_t = DEFAULT_NONCE_RETRIES)]
+    nonce_retries: u32,
+    /// Only display the events with the given name, either `Pallet` or
+    /// `Pallet::Variant`, e.g. `Contracts::ContractEmitted`.
+    ///
+    /// Can be passed multiple times.
+    #[clap(long, value_name = "EVENT")]
+    event_filter: Vec<String>,
+    /// Only display the events of the `Contracts` pallet concerning the contract.
+    #[clap(long)]
+    only_contract_events: bool,
+    /// Url of the Subscan explorer of the chain, e.g. `https://astar.subscan.io/`, to
+    /// link to the submitted extrinsic on.
+    ///
+    /// Defaults to the explorer of a production chain.
+    #[clap(long, value_name = "URL")]
+    subscan: Option<url::Url>,
     /// Arguments required for communicating with a Substrate node.
     #[clap(flatten)]
     chain_cli_opts: CLIChainOpts,
@@ -131,37 +298,245 @@ impl CLIExtrinsicOpts {
     pub fn verbosity(&self) -> Result<Verbosity> {
         TryFrom::try_from(&self.verbosity)
     }
+
+    /// Fails if extrinsics can't be submitted through the node at `--url` or any of the
+    /// fallback urls, checked up front so that `--execute` fails before the dry-run.
+    pub fn ensure_can_submit(&self) -> Result<()> {
+        self.chain_cli_opts.ensure_can_submit()
+    }
+
+    /// Returns whether to submit the extrinsic without a storage deposit limit if none is
+    /// given, there is no dry-run to derive it from with `--skip-dry-run`.
+    pub fn unlimited_deposit(&self) -> bool {
+        self.unlimited_deposit || self.skip_dry_run
+    }
+
+    /// Returns the secret key URI, read from stdin if `--suri-stdin` is passed.
+    pub fn suri(&self) -> Result<Suri> {
+        match &self.suri {
+            _ if self.suri_stdin => read_suri_stdin(),
+            Some(suri) => Ok(suri.clone()),
+            None => Err(anyhow!("Either --suri or --suri-stdin is required")),
+        }
+    }
+
+    /// Returns the signer derived from the secret key URI.
+    pub fn signer<C: Config + Environment + SignerConfig<C>>(&self) -> Result<C::Signer> {
+        self.suri()?.signer::<C>()
+    }
+
+    /// Returns the timeout for the finalization of the extrinsic, `None` if only its
+    /// inclusion into a block is awaited.
+    pub fn finalization_timeout(&self) -> Option<Duration> {
+        self.wait_for_finalization
+            .then(|| Duration::from_secs(self.finalization_timeout))
+    }
+
+    /// Returns the timeout for the inclusion of the extrinsic into a block.
+    pub fn submit_timeout(&self) -> Option<Duration> {
+        self.submit_timeout.map(Duration::from_secs)
+    }
+
+    /// Returns the reporter printing the progress of waiting for the extrinsic.
+    pub fn progress(&self) -> Result<ProgressReporter> {
+        Ok(progress::reporter(&self.verbosity()?))
+    }
+
+    /// Returns the provider of the nonce of the extrinsic.
+    pub fn nonce_provider(&self) -> NonceProvider {
+        match self.nonce {
+            Some(nonce) => NonceProvider::starting_at(nonce),
+            None if self.track_nonce => NonceProvider::tracked(),
+            None => NonceProvider::best_block(),
+        }
+    }
+
+    /// Returns the options for the submission of the extrinsic.
+    pub fn submit_options(&self) -> SubmitOptions {
+        if self.nonce.is_some() {
+            return SubmitOptions::no_retries()
+        }
+        SubmitOptions {
+            nonce_retries: self.nonce_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the filter of the displayed events.
+    pub fn event_filter(&self) -> EventFilter {
+        self.event_filter.iter().fold(
+            EventFilter::default().only_contract_events(self.only_contract_events),
+            |filter, name| filter.event(name),
+        )
+    }
+
+    /// Returns the Subscan explorer of the chain, either the one passed with
+    /// `--subscan` or the one of the production chain.
+    pub fn subscan(&self) -> Option<url::Url> {
+        self.subscan
+            .clone()
+            .or_else(|| self.chain_cli_opts.chain().production()?.subscan())
+    }
+
+    /// Returns the tip paid to the block author, denominated using `token_metadata`.
+    pub fn tip(&self, token_metadata: &TokenMetadata) -> Result<u128> {
+        self.tip
+            .as_ref()
+            .map(|tip| parse_balance(tip, token_metadata))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse tip option: {}", e))
+            .map(Option::unwrap_or_default)
+    }
 }
 
 /// Arguments required for communicating with a Substrate node.
 #[derive(Clone, Debug, clap::Args)]
 pub struct CLIChainOpts {
     /// Websockets url of a Substrate node.
+    ///
+    /// HTTP(S) urls are supported for read-only operations like dry-runs and queries.
+    ///
+    /// Pass it several times to fail over to the further nodes of the same chain, in
+    /// order, if the connection to a node fails before an extrinsic is signed.
     #[clap(
         name = "url",
         long,
         value_parser,
+        action = clap::ArgAction::Append,
         default_value = "ws://localhost:9944"
     )]
-    url: url::Url,
+    url: Vec<url::Url>,
     /// Chain config to be used as part of the call.
     #[clap(name = "config", long, default_value = "Polkadot")]
     config: String,
     /// Name of a production chain to be communicated with.
     #[clap(name = "chain", long, conflicts_with_all = ["url", "config"])]
     chain: Option<ProductionChain>,
+    /// Do not cache the runtime metadata of the node on disk.
+    ///
+    /// By default it is cached in `~/.cache/cargo-contract` until the runtime of the
+    /// node is upgraded.
+    #[clap(long)]
+    no_metadata_cache: bool,
+    /// Fetch the runtime metadata from the node even if it is cached.
+    ///
+    /// Use it if the cached metadata is outdated, e.g. after the runtime of a development
+    /// node was changed without incrementing its spec version.
+    #[clap(long, conflicts_with = "no_metadata_cache")]
+    refresh_metadata: bool,
+    /// Connect to the node through a running `cargo contract daemon`, instead of
+    /// connecting directly.
+    ///
+    /// The node is connected to directly if the daemon is not running.
+    #[clap(long)]
+    daemon: bool,
+    /// The SS58 prefix addresses are displayed with, e.g. `0` for Polkadot.
+    ///
+    /// Defaults to the `SS58Prefix` of the chain. Addresses are accepted with any prefix.
+    #[clap(long, value_parser = clap::value_parser!(u16).range(0..=16_383))]
+    ss58_prefix: Option<u16>,
+    #[clap(flatten)]
+    rpc_cli_opts: RpcCliOpts,
 }
 
 impl CLIChainOpts {
     pub fn chain(&self) -> Chain {
+        let url = &self.url[0];
         if let Some(chain) = &self.chain {
             Chain::Production(chain.clone())
-        } else if let Some(prod) = ProductionChain::from_parts(&self.url, &self.config) {
+        } else if let Some(prod) = ProductionChain::from_parts(url, &self.config) {
             Chain::Production(prod)
         } else {
-            Chain::Custom(self.url.clone(), self.config.clone())
+            Chain::Custom(url.clone(), self.config.clone())
         }
     }
+
+    /// Returns the urls of the further nodes passed with `--url`, which are failed over
+    /// to if the connection to the node at the url of the [`Chain`] fails.
+    pub fn fallback_urls(&self) -> Vec<url::Url> {
+        if self.chain.is_some() {
+            return Vec::new()
+        }
+        self.url[1..].to_vec()
+    }
+
+    /// Fails if extrinsics can't be submitted through the node at the url of the
+    /// [`Chain`] or any of the fallback urls.
+    pub fn ensure_can_submit(&self) -> Result<()> {
+        std::iter::once(self.chain().url())
+            .chain(self.fallback_urls())
+            .try_for_each(|url| contract_extrinsics::ensure_can_submit(&url))
+    }
+
+    /// Returns whether the runtime metadata of the node is cached on disk.
+    pub fn metadata_cache(&self) -> bool {
+        !self.no_metadata_cache
+    }
+
+    /// Returns whether the runtime metadata is fetched from the node even if it is
+    /// cached.
+    pub fn refresh_metadata(&self) -> bool {
+        self.refresh_metadata
+    }
+
+    /// Returns whether to connect to the node through a running daemon.
+    pub fn daemon(&self) -> bool {
+        self.daemon
+    }
+
+    /// Returns the SS58 prefix passed with `--ss58-prefix`, if any.
+    pub fn ss58_prefix(&self) -> Option<u16> {
+        self.ss58_prefix
+    }
+
+    /// Returns the SS58 prefix addresses are displayed with, either the one passed with
+    /// `--ss58-prefix` or the `SS58Prefix` of the chain with the runtime `metadata`.
+    pub fn ss58_prefix_for(&self, metadata: Option<&subxt::Metadata>) -> u16 {
+        ss58::ss58_prefix(self.ss58_prefix, metadata)
+    }
+
+    /// Queries the [`TokenMetadata`] of the chain, failing over to the fallback urls.
+    pub async fn token_metadata<C: Config>(&self) -> Result<TokenMetadata> {
+        TokenMetadata::query_with_options::<C>(&self.chain().url(), self.rpc_options())
+            .await
+    }
+
+    /// Returns the options for the communication with the node.
+    pub fn rpc_options(&self) -> RpcOptions {
+        RpcOptions::from(&self.rpc_cli_opts)
+            .with_metadata_cache(self.metadata_cache())
+            .with_refresh_metadata(self.refresh_metadata())
+            .with_daemon(self.daemon())
+            .with_fallback_urls(self.fallback_urls().iter().map(url_to_string).collect())
+    }
+}
+
+/// Arguments for the requests to a Substrate node.
+#[derive(Clone, Debug, clap::Args)]
+pub struct RpcCliOpts {
+    /// Overall timeout in seconds of a request to the node, including all retries.
+    #[clap(
+        long,
+        default_value_t = DEFAULT_RPC_TIMEOUT.as_secs(),
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    rpc_timeout: u64,
+    /// How often a request to the node failing with a transient error, e.g. a dropped
+    /// connection, is retried, at most 10 times.
+    #[clap(
+        long,
+        default_value_t = DEFAULT_RPC_RETRIES,
+        value_parser = clap::value_parser!(u32).range(0..=10)
+    )]
+    rpc_retries: u32,
+}
+
+impl From<&RpcCliOpts> for RpcOptions {
+    fn from(opts: &RpcCliOpts) -> Self {
+        RpcOptions::default()
+            .with_timeout(Duration::from_secs(opts.rpc_timeout))
+            .with_retries(opts.rpc_retries)
+    }
 }
 
 #[derive(Debug)]
@@ -249,46 +624,90 @@ pub fn display_dry_run_result_warning(command: &str) {
         );
 }
 
+/// The chain, the signer and the estimated fee of a transaction, shown when asking for
+/// its confirmation to catch submitting it to the wrong chain or from the wrong account.
+pub struct TxContext {
+    chain: ChainInfo,
+    signer: String,
+    fee: Option<String>,
+}
+
+impl TxContext {
+    /// Queries the chain of the node at `url` and the `fee` of the transaction signed
+    /// by `signer`.
+    ///
+    /// Failing to estimate the fee is not fatal, e.g. on chains without the
+    /// `TransactionPaymentApi`.
+    pub async fn query<C: Config>(
+        url: &url::Url,
+        options: RpcOptions,
+        signer: &C::AccountId,
+        fee: impl Future<Output = Result<u128>>,
+        token_metadata: &TokenMetadata,
+    ) -> Result<Self>
+    where
+        C::AccountId: Display,
+    {
+        let fee = match fee.await {
+            Ok(fee) => Some(BalanceVariant::<u128>::from(fee, Some(token_metadata))?),
+            Err(err) => {
+                tracing::warn!("Failed to estimate the fee: {err}");
+                None
+            }
+        };
+        Ok(Self {
+            chain: ChainInfo::query_with_options::<C>(url, options).await?,
+            signer: signer.to_string(),
+            fee: fee.map(|fee| fee.to_string()),
+        })
+    }
+
+    fn print(&self) {
+        name_value_println!(
+            "Chain",
+            format!("{} ({})", self.chain.name, self.chain.genesis_hash),
+            DEFAULT_KEY_COL_WIDTH
+        );
+        name_value_println!("Signer", self.signer, DEFAULT_KEY_COL_WIDTH);
+        if let Some(fee) = &self.fee {
+            name_value_println!(
+                "Fee",
+                format!("{fee} (estimated)"),
+                DEFAULT_KEY_COL_WIDTH
+            );
+        }
+    }
+}
+
 /// Prompt the user to confirm transaction submission.
-pub fn prompt_confirm_tx<F: FnOnce()>(show_details: F) -> Result<()> {
-    println!(
-        "{} (skip with --skip-confirm or -y)",
-        "Confirm transaction details:".bright_white().bold()
-    );
+pub fn prompt_confirm_tx<F: FnOnce()>(
+    context: &TxContext,
+    show_details: F,
+) -> Result<()> {
+    output::renderer()
+        .status("Confirm", "transaction details (skip with --skip-confirm or -y)");
+    context.print();
     show_details();
-    print!(
-        "{} ({}/n): ",
-        "Submit?".bright_white().bold(),
-        "Y".bright_white().bold()
-    );
-
-    let mut buf = String::new();
-    io::stdout().flush()?;
-    io::stdin().read_line(&mut buf)?;
-    match buf.trim().to_lowercase().as_str() {
-        // default is 'y'
-        "y" | "" => Ok(()),
-        "n" => Err(anyhow!("Transaction not submitted")),
-        c => Err(anyhow!("Expected either 'y' or 'n', got '{}'", c)),
+    if !output::renderer().confirm("Submit?", true)? {
+        return Err(anyhow!("Transaction not submitted"))
     }
+    Ok(())
 }
 
 pub fn print_dry_running_status(msg: &str) {
-    println!(
-        "{:>width$} {} (skip with --skip-dry-run)",
-        "Dry-running".green().bold(),
-        msg.bright_white().bold(),
-        width = DEFAULT_KEY_COL_WIDTH
-    );
+    output::renderer()
+        .status("Dry-running", &format!("{msg} (skip with --skip-dry-run)"));
 }
 
 pub fn print_gas_required_success(gas: Weight) {
-    println!(
-        "{:>width$} Gas required estimated at {}",
-        "Success!".green().bold(),
-        gas.to_string().bright_white(),
-        width = DEFAULT_KEY_COL_WIDTH
-    );
+    output::renderer().status("Success!", &format!("Gas required estimated at {gas}"));
+}
+
+/// Display the identifiers of a submitted extrinsic
+pub fn display_extrinsic_receipt<Hash: Debug>(receipt: &ExtrinsicReceipt<Hash>) {
+    name_value_println!("Extrinsic hash", format!("{:?}", receipt.extrinsic_hash));
+    name_value_println!("Block hash", format!("{:?}", receipt.block_hash));
+    name_value_println!("Block number", receipt.block_number.to_string());
 }
 
 /// Display contract information in a formatted way
@@ -319,23 +738,46 @@ pub fn basic_display_format_extended_contract_info<Hash, Balance>(
         format!("{:?}", info.storage_total_deposit),
         MAX_KEY_COL_WIDTH
     );
+    name_value_println!("Code Owner", info.code_owner, MAX_KEY_COL_WIDTH);
+    name_value_println!(
+        "Code Deposit",
+        format!("{:?}", info.code_deposit),
+        MAX_KEY_COL_WIDTH
+    );
+    name_value_println!(
+        "Code Refcount",
+        info.code_refcount.to_string(),
+        MAX_KEY_COL_WIDTH
+    );
     name_value_println!(
         "Source Language",
         format!("{}", info.source_language),
         MAX_KEY_COL_WIDTH
     );
+    name_value_println!(
+        "Language Version",
+        info.source_language_version,
+        MAX_KEY_COL_WIDTH
+    );
+    name_value_println!(
+        "Metadata Hash",
+        info.metadata_hash.as_deref().unwrap_or("None"),
+        MAX_KEY_COL_WIDTH
+    );
 }
 
-/// Display all contracts addresses in a formatted way
-pub fn display_all_contracts<AccountId>(contracts: &[AccountId])
+/// Display all contracts addresses in a formatted way, with the `ss58_prefix`
+pub fn display_all_contracts<AccountId>(contracts: &[AccountId], ss58_prefix: u16)
 where
-    AccountId: Display,
+    AccountId: Encode + Display,
 {
-    contracts.iter().for_each(|e: &AccountId| println!("{}", e))
+    contracts
+        .iter()
+        .for_each(|e: &AccountId| println!("{}", ss58::display_account(e, ss58_prefix)))
 }
 
 /// Parse a balance from string format
-pub fn parse_balance<Balance: FromStr + From<u128> + Clone>(
+pub fn parse_balance<Balance: FromStr + TryFrom<u128> + Clone>(
     balance: &str,
     token_metadata: &TokenMetadata,
 ) -> Result<Balance> {
@@ -345,11 +787,19 @@ pub fn parse_balance<Balance: FromStr + From<u128> + Clone>(
 }
 
 /// Parse a account from string format
+///
+/// A `0x` prefixed 20 byte address, e.g. of a contract on a chain running
+/// `pallet-revive`, is parsed as the account it is mapped to, see
+/// [`AccountId32::from_eth_address`].
 pub fn parse_account<AccountId: FromStr>(account: &str) -> Result<AccountId>
 where
     <AccountId as FromStr>::Err: Display,
 {
-    AccountId::from_str(account)
+    let mapped = account
+        .strip_prefix("0x")
+        .and_then(|address| <[u8; 20]>::try_from(hex::decode(address).ok()?).ok())
+        .map(|address| AccountId32::from_eth_address(address).to_ss58check());
+    AccountId::from_str(mapped.as_deref().unwrap_or(account))
         .map_err(|e| anyhow::anyhow!("Account address parsing failed: {e}"))
 }
 
@@ -369,35 +819,97 @@ where
 
 /// Prompt the user to confirm the upload of unverifiable code to the production chain.
 pub fn prompt_confirm_unverifiable_upload(chain: &str) -> Result<()> {
-    println!("{}", "Confirm upload:".bright_white().bold());
-    let warning = format!(
-        "Warning: You are about to upload unverifiable code to {} mainnet.\n\
+    output::renderer().status("Confirm", "upload");
+    output::warning(format!(
+        "You are about to upload unverifiable code to {} mainnet.\n\
         A third party won't be able to confirm that your uploaded contract Wasm blob \
         matches a particular contract source code.\n\n\
         You can use `cargo contract build --verifiable` to make the contract verifiable.\n\
         See https://use.ink/basics/contract-verification for more info.",
         chain
-    )
-    .bold()
-    .yellow();
-    print!("{}", warning);
-    println!(
-        "{} ({}): ",
-        "\nContinue?".bright_white().bold(),
-        "y/N".bright_white().bold()
+    ));
+    if !output::renderer().confirm("Continue?", false)? {
+        return Err(anyhow!("Upload cancelled!"))
+    }
+    Ok(())
+}
+
+/// Warns that the extrinsic is dispatched through the `Sudo` pallet, and fails if the
+/// `signer` is not the sudo key of the chain.
+pub async fn ensure_sudo_key<C: Config>(
+    client: &OnlineClient<C>,
+    signer: &C::AccountId,
+) -> Result<()>
+where
+    C::AccountId: IntoVisitor + Display,
+{
+    contract_build::output::warning(
+        "The extrinsic is dispatched through the `Sudo` pallet, which is only meant for \
+         development chains.",
     );
+    match fetch_sudo_key(client).await? {
+        Some(key) if key.encode() == signer.encode() => Ok(()),
+        Some(key) => {
+            Err(anyhow!(
+                "The signer {signer} is not the sudo key {key} of the chain, the \
+                 extrinsic would fail"
+            ))
+        }
+        None => Err(anyhow!("The chain has no `Sudo` pallet or no sudo key")),
+    }
+}
+
+/// A secret key URI, e.g. `//Alice///SECRET_PASSWORD`.
+///
+/// Its `Debug` output is redacted, so that the secret doesn't end up in logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Suri(String);
+
+impl Suri {
+    /// Returns the secret key URI itself.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the signer derived from the secret key URI.
+    pub fn signer<C: Config + Environment + SignerConfig<C>>(&self) -> Result<C::Signer> {
+        C::Signer::from_str(&self.0).map_err(|_| anyhow!("Failed to parse suri option"))
+    }
+}
+
+impl FromStr for Suri {
+    type Err = std::convert::Infallible;
 
-    let mut buf = String::new();
-    io::stdout().flush()?;
-    io::stdin().read_line(&mut buf)?;
-    match buf.trim().to_lowercase().as_str() {
-        // default is 'n'
-        "y" => Ok(()),
-        "n" | "" => Err(anyhow!("Upload cancelled!")),
-        c => Err(anyhow!("Expected either 'y' or 'n', got '{}'", c)),
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_owned()))
     }
 }
 
+impl Debug for Suri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Suri(<redacted>)")
+    }
+}
+
+/// Reads the secret key URI from the first line of stdin.
+///
+/// Stdin is only read once, later calls return the same secret key URI.
+fn read_suri_stdin() -> Result<Suri> {
+    static SURI: OnceLock<String> = OnceLock::new();
+    if let Some(suri) = SURI.get() {
+        return Ok(Suri(suri.clone()))
+    }
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| anyhow!("Failed to read the suri from stdin: {}", e))?;
+    let suri = line.trim_end_matches(['\r', '\n']);
+    if suri.is_empty() {
+        return Err(anyhow!("No suri provided on stdin"))
+    }
+    Ok(Suri(SURI.get_or_init(|| suri.to_owned()).clone()))
+}
+
 #[cfg(test)]
 mod tests {
     use subxt::{
@@ -407,6 +919,14 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn suri_debug_is_redacted() {
+        let suri = Suri::from_str("//Alice///SECRET_PASSWORD").unwrap();
+
+        assert_eq!(format!("{suri:?}"), "Suri(<redacted>)");
+        assert_eq!(suri.expose(), "//Alice///SECRET_PASSWORD");
+    }
+
     #[test]
     fn parse_code_hash_works() {
         // with 0x prefix
@@ -430,6 +950,37 @@ mod tests {
         .is_err())
     }
 
+    #[test]
+    fn parse_account_accepts_mapped_address() {
+        let address = format!("0x{}", "ab".repeat(20));
+        let account: <SubstrateConfig as Config>::AccountId =
+            parse_account(&address).unwrap();
+
+        assert_eq!(account.0, AccountId32::from_eth_address([0xab; 20]).0);
+        assert!(parse_account::<<SubstrateConfig as Config>::AccountId>("0xab").is_err());
+    }
+
+    #[test]
+    fn rpc_cli_opts_are_range_checked() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[clap(flatten)]
+            rpc: RpcCliOpts,
+        }
+
+        let cli = Cli::try_parse_from(["cli", "--rpc-timeout", "1", "--rpc-retries", "10"])
+            .unwrap();
+        assert_eq!(cli.rpc.rpc_timeout, 1);
+        assert_eq!(cli.rpc.rpc_retries, 10);
+
+        let err = Cli::try_parse_from(["cli", "--rpc-timeout", "0"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        let err = Cli::try_parse_from(["cli", "--rpc-retries", "11"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
     #[test]
     fn parse_bad_format_code_hash_fails() {
         // with bad format
@@ -22,13 +22,13 @@ use std::str::FromStr;
 use url::Url;
 
 /// This macro generates enums with the pre-defined production chains and their respective
-/// endpoints.
+/// endpoints, configs, faucets and Subscan explorers.
 ///
 /// It also generates the required trait implementations.
 macro_rules! define_chains {
     (
         $(#[$($attrs:tt)*])*
-        pub enum $root:ident { $( $c:ident = ($ep:tt, $config:tt) ),* $(,)? }
+        pub enum $root:ident { $( $c:ident = ($ep:tt, $config:tt, $faucet:expr, $subscan:expr) ),* $(,)? }
     ) => {
         $(#[$($attrs)*])*
         #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,6 +53,25 @@ macro_rules! define_chains {
                 }
             }
 
+            /// Returns the URL of the HTTP faucet of a chain, if it has one.
+            pub fn faucet(&self) -> Option<&str> {
+                match self {
+                    $(
+                        $root::$c => $faucet
+                    ),*
+                }
+            }
+
+            /// Returns the URL of the Subscan explorer of a chain, if it has one.
+            pub fn subscan(&self) -> Option<url::Url> {
+                let subscan: Option<&str> = match self {
+                    $(
+                        $root::$c => $subscan
+                    ),*
+                };
+                subscan.map(|url| Url::parse(url).expect("Incorrect Url format"))
+            }
+
             /// Returns the production chain.
             ///
             /// If the user specified the endpoint URL and config manually we'll attempt to
@@ -95,9 +114,29 @@ define_chains! {
     /// List of production chains where the contract can be deployed to.
     #[derive(clap::ValueEnum)]
     pub enum ProductionChain {
-        AlephZero = ("wss://ws.azero.dev:443/", "Substrate"),
-        Astar = ("wss://rpc.astar.network:443/", "Polkadot"),
-        Shiden = ("wss://rpc.shiden.astar.network:443/", "Polkadot"),
-        Krest = ("wss://wss-krest.peaq.network:443/", "Polkadot")
+        AlephZero = (
+            "wss://ws.azero.dev:443/",
+            "Substrate",
+            None,
+            Some("https://alephzero.subscan.io/")
+        ),
+        Astar = (
+            "wss://rpc.astar.network:443/",
+            "Polkadot",
+            None,
+            Some("https://astar.subscan.io/")
+        ),
+        Shiden = (
+            "wss://rpc.shiden.astar.network:443/",
+            "Polkadot",
+            None,
+            Some("https://shiden.subscan.io/")
+        ),
+        Krest = (
+            "wss://wss-krest.peaq.network:443/",
+            "Polkadot",
+            None,
+            Some("https://krest.subscan.io/")
+        )
     }
 }
@@ -0,0 +1,184 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use colored::Colorize;
+use contract_build::{
+    output::{
+        self,
+        LogFormat,
+        OUTPUT_TARGET,
+    },
+    progress::{
+        Operation,
+        ProgressEvent,
+        ProgressReporter,
+    },
+    Verbosity,
+    DEFAULT_KEY_COL_WIDTH,
+};
+use std::{
+    io::{
+        self,
+        IsTerminal,
+        Write,
+    },
+    sync::Mutex,
+};
+
+/// Moves the cursor to the start of the line and erases the line.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Returns a reporter printing the progress of long running operations to stderr.
+///
+/// Nothing is printed if the `verbosity` is quiet.
+pub fn reporter(verbosity: &Verbosity) -> ProgressReporter {
+    if !verbosity.is_verbose() {
+        return ProgressReporter::default()
+    }
+    match output::log_format() {
+        LogFormat::Human => {
+            let progress = StderrProgress {
+                terminal: io::stderr().is_terminal(),
+                line: Mutex::new(None),
+            };
+            ProgressReporter::new(move |event| progress.render(event))
+        }
+        LogFormat::Json => ProgressReporter::new(log),
+    }
+}
+
+/// Renders the progress for humans.
+///
+/// Status changes are always printed. The progress of an operation is only rendered
+/// on a terminal, as a single line which is updated in place.
+struct StderrProgress {
+    terminal: bool,
+    /// The progress line currently shown.
+    line: Mutex<Option<String>>,
+}
+
+impl StderrProgress {
+    fn render(&self, event: &ProgressEvent) {
+        let mut line = self.line.lock().expect("progress lock is never poisoned");
+        let mut stderr = io::stderr().lock();
+        let _ = self
+            .write(event, &mut line, &mut stderr)
+            .and_then(|()| stderr.flush());
+    }
+
+    fn write(
+        &self,
+        event: &ProgressEvent,
+        line: &mut Option<String>,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        match event {
+            ProgressEvent::Status {
+                status, details, ..
+            } => {
+                if self.terminal {
+                    write!(out, "{CLEAR_LINE}")?;
+                }
+                writeln!(
+                    out,
+                    "{:>width$} {}",
+                    status.bright_cyan().bold(),
+                    details,
+                    width = DEFAULT_KEY_COL_WIDTH
+                )?;
+                if let Some(line) = line.as_ref().filter(|_| self.terminal) {
+                    write!(out, "{line}")?;
+                }
+            }
+            _ if !self.terminal => {}
+            // cargo renders the progress of the compilation itself
+            ProgressEvent::Started {
+                operation: Operation::Compile,
+            }
+            | ProgressEvent::Finished {
+                operation: Operation::Compile,
+            } => {}
+            ProgressEvent::Started { operation } => {
+                let started = format!(
+                    "{:>width$} ...",
+                    operation.to_string().bright_green().bold(),
+                    width = DEFAULT_KEY_COL_WIDTH
+                );
+                write!(out, "{CLEAR_LINE}{started}")?;
+                *line = Some(started);
+            }
+            ProgressEvent::Advanced {
+                operation,
+                done,
+                total,
+            } => {
+                let total = total.map(|total| format!("/{total}")).unwrap_or_default();
+                let advanced = format!(
+                    "{:>width$} {done}{total}",
+                    operation.to_string().bright_green().bold(),
+                    width = DEFAULT_KEY_COL_WIDTH
+                );
+                write!(out, "{CLEAR_LINE}{advanced}")?;
+                *line = Some(advanced);
+            }
+            ProgressEvent::Finished { .. } => {
+                write!(out, "{CLEAR_LINE}")?;
+                *line = None;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Logs the progress as diagnostics in the JSON format.
+fn log(event: &ProgressEvent) {
+    match event {
+        ProgressEvent::Started { operation } => {
+            tracing::info!(target: OUTPUT_TARGET, progress = "started", %operation, "{operation}")
+        }
+        ProgressEvent::Advanced {
+            operation,
+            done,
+            total,
+        } => {
+            tracing::info!(
+                target: OUTPUT_TARGET,
+                progress = "advanced",
+                %operation,
+                done,
+                total,
+                "{operation}"
+            )
+        }
+        ProgressEvent::Status {
+            operation,
+            status,
+            details,
+        } => {
+            tracing::info!(
+                target: OUTPUT_TARGET,
+                progress = "status",
+                %operation,
+                status,
+                details,
+                "{status} {details}"
+            )
+        }
+        ProgressEvent::Finished { operation } => {
+            tracing::info!(target: OUTPUT_TARGET, progress = "finished", %operation, "{operation}")
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::uploader::{
+    IpfsUploader,
+    Uploader,
+    DEFAULT_IPFS_API,
+};
+use anyhow::Result;
+use contract_build::{
+    name_value_println,
+    CrateMetadata,
+    Target,
+};
+use contract_metadata::ContractMetadata;
+use std::{
+    fs,
+    path::PathBuf,
+};
+use url::Url;
+
+/// Publishes a contract bundle or its metadata.
+#[derive(Debug, clap::Args)]
+#[clap(name = "publish")]
+pub struct PublishCommand {
+    /// Path to the `.contract` bundle or `.json` metadata to publish.
+    ///
+    /// Defaults to the bundle built for the contract at `--manifest-path`.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract to publish the bundle of.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Publish to IPFS.
+    #[clap(long)]
+    ipfs: bool,
+    /// URL of the IPFS HTTP API to upload to, e.g. of a local node or a pinning
+    /// service.
+    #[clap(long, default_value = DEFAULT_IPFS_API)]
+    gateway: Url,
+    /// Value of the `Authorization` header sent to the IPFS HTTP API.
+    #[clap(long, env = "IPFS_AUTH", hide_env_values = true)]
+    auth: Option<String>,
+    /// Only publish the metadata, without the Wasm code.
+    #[clap(long)]
+    metadata_only: bool,
+    /// Write the URL of the published content into `source.metadata_url` of the
+    /// published file.
+    #[clap(long)]
+    write_url: bool,
+}
+
+impl PublishCommand {
+    pub async fn run(&self) -> Result<()> {
+        if !self.ipfs {
+            anyhow::bail!("No publishing target specified, use --ipfs")
+        }
+        let path = match &self.file {
+            Some(file) => file.clone(),
+            None => {
+                CrateMetadata::from_manifest_path(
+                    self.manifest_path.as_ref(),
+                    Target::Wasm,
+                )?
+                .contract_bundle_path()
+            }
+        };
+        let mut metadata = ContractMetadata::load(&path)?;
+        let name = if self.metadata_only {
+            metadata.remove_source_wasm_attribute();
+            format!("{}.json", metadata.contract.name)
+        } else {
+            format!("{}.contract", metadata.contract.name)
+        };
+
+        let uploader = IpfsUploader::new(self.gateway.clone(), self.auth.clone());
+        let published = uploader
+            .upload(&name, serde_json::to_vec(&metadata)?)
+            .await?;
+
+        if self.write_url {
+            let mut local = ContractMetadata::load(&path)?;
+            local.source.metadata_url = Some(published.url.clone());
+            let json = if path.extension().is_some_and(|ext| ext == "json") {
+                serde_json::to_string_pretty(&local)?
+            } else {
+                serde_json::to_string(&local)?
+            };
+            fs::write(&path, json)?;
+        }
+
+        name_value_println!("CID", published.id);
+        name_value_println!("URL", published.url);
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    config::SignerConfig,
+    parse_account,
+    parse_balance,
+    CLIChainOpts,
+    Suri,
+};
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_build::VerbosityFlags;
+use contract_extrinsics::{
+    CallCommandBuilder,
+    ExtrinsicOptsBuilder,
+};
+use contract_transcode::{
+    unwrap_lang_result,
+    ReturnError,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    path::PathBuf,
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    Config,
+};
+
+/// Queries a read-only message of a contract with a dry-run and prints the returned
+/// value.
+///
+/// Messages which mutate the storage of the contract are rejected, they are executed
+/// with `cargo contract call`. The command fails if the message returns an error.
+#[derive(Debug, clap::Args)]
+#[clap(name = "query")]
+pub struct QueryCommand {
+    /// The address of the the contract to query.
+    #[clap(name = "contract", long, env = "CONTRACT")]
+    contract: String,
+    /// The name of the contract message to query, or its selector e.g. `0x633aa551`.
+    #[clap(long, short)]
+    message: String,
+    /// The arguments of the contract message to query.
+    #[clap(long, num_args = 0..)]
+    args: Vec<String>,
+    /// Path to a contract build artifact file: a raw `.wasm` file, a `.contract` bundle,
+    /// or a `.json` metadata file.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Secret key URI of the account the query originates from.
+    #[clap(name = "suri", long, short, default_value = "//Alice")]
+    suri: Suri,
+    /// The value to be transferred as part of the query.
+    #[clap(name = "value", long, default_value = "0")]
+    value: String,
+    /// Path to a Solidity ABI file of the contract, e.g. produced by `solang`.
+    #[clap(long, value_parser)]
+    abi: Option<PathBuf>,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+    /// Export the returned value in JSON format.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl QueryCommand {
+    /// Returns whether to export the query output in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let contract: <C as Config>::AccountId = parse_account(&self.contract)
+            .map_err(|e| anyhow!("Failed to parse contract option: {}", e))?;
+        let signer = self.suri.signer::<C>()?;
+        let chain = self.chain_cli_opts.chain();
+        let token_metadata = self.chain_cli_opts.token_metadata::<C>().await?;
+        let value = parse_balance(&self.value, &token_metadata)
+            .map_err(|e| anyhow!("Failed to parse value option: {}", e))?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::<C, C, _>::new(signer)
+            .file(self.file.clone())
+            .manifest_path(self.manifest_path.clone())
+            .url(chain.url())
+            .rpc_options(self.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.chain_cli_opts.ss58_prefix())
+            .verbosity(TryFrom::try_from(&self.verbosity)?)
+            .done();
+
+        let call_exec = CallCommandBuilder::new(contract, &self.message, extrinsic_opts)
+            .args(self.args.clone())
+            .value(value)
+            .abi(self.abi.clone())
+            .done()
+            .await?;
+        if call_exec.mutates()? {
+            return Err(anyhow!(
+                "The message '{}' mutates the contract, use `cargo contract call` instead",
+                self.message
+            )
+            .into())
+        }
+
+        let result = call_exec.call_dry_run().await?;
+        let ret_val = match result.result {
+            Ok(ret_val) => ret_val,
+            Err(ref err) => {
+                return Err(ErrorVariant::from_dispatch_error(
+                    err,
+                    &call_exec.client().metadata(),
+                )?)
+            }
+        };
+        let value = call_exec
+            .decode_return(&ret_val.data)
+            .context(format!("Failed to decode return value {:?}", &ret_val))
+            .map_err(ErrorVariant::decode)?;
+        let lang_result = call_exec.returns_lang_result();
+        if let Some(error) = ReturnError::from_return_value(&value, lang_result) {
+            return Err(ErrorVariant::dry_run_revert(format!(
+                "The message returned an error: {error}"
+            )))
+        }
+        if ret_val.did_revert() {
+            return Err(ErrorVariant::dry_run_revert(format!(
+                "The message reverted: {value}"
+            )))
+        }
+
+        let value = unwrap_lang_result(value, lang_result);
+        if self.output_json() {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        } else {
+            println!("{value}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        query: QueryCommand,
+    }
+
+    const CONTRACT: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    fn query_defaults_to_alice_without_value() {
+        let cli =
+            Cli::try_parse_from(["query", "--contract", CONTRACT, "--message", "get"])
+                .unwrap();
+
+        assert_eq!(cli.query.suri.expose(), "//Alice");
+        assert_eq!(cli.query.value, "0");
+        assert!(cli.query.args.is_empty());
+        assert!(!cli.query.output_json());
+    }
+
+    #[test]
+    fn query_output_json_conflicts_with_verbose() {
+        let query = [
+            "query",
+            "--contract",
+            CONTRACT,
+            "--message",
+            "get",
+            "--output-json",
+            "--verbose",
+        ];
+
+        let err = Cli::try_parse_from(query).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+}
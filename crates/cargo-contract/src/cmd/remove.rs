@@ -28,6 +28,7 @@ use std::{
 
 use super::{
     config::SignerConfig,
+    display_extrinsic_receipt,
     parse_balance,
     parse_code_hash,
     CLIExtrinsicOpts,
@@ -39,7 +40,6 @@ use contract_extrinsics::{
     ExtrinsicOptsBuilder,
     RemoveCommandBuilder,
     RemoveExec,
-    TokenMetadata,
 };
 use ink_env::Environment;
 use serde::Serialize;
@@ -75,6 +75,7 @@ impl RemoveCommand {
     }
 
     pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        self.extrinsic_cli_opts.ensure_can_submit()?;
         call_with_config!(
             self,
             run,
@@ -89,7 +90,7 @@ impl RemoveCommand {
         <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
         <<C as Config>::AccountId as FromStr>::Err: Display,
         C::Balance: Into<u128>
-            + From<u128>
+            + TryFrom<u128>
             + Display
             + Default
             + FromStr
@@ -100,10 +101,13 @@ impl RemoveCommand {
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
         <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
     {
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
-        let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
         let storage_deposit_limit = self
             .extrinsic_cli_opts
             .storage_deposit_limit
@@ -123,7 +127,16 @@ impl RemoveCommand {
             .file(self.extrinsic_cli_opts.file.clone())
             .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
             .url(chain.url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
             .storage_deposit_limit(storage_deposit_limit)
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(&token_metadata)?)
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider())
+            .progress(self.extrinsic_cli_opts.progress()?)
             .done();
 
         let remove_exec: RemoveExec<C, C, _> = RemoveCommandBuilder::new(extrinsic_opts)
@@ -131,11 +144,16 @@ impl RemoveCommand {
             .done()
             .await?;
         let remove_result = remove_exec.remove_code().await?;
+        let metadata = remove_exec.client().metadata();
         let display_events = DisplayEvents::from_events::<C, C>(
             &remove_result.events,
-            Some(remove_exec.transcoder()),
-            &remove_exec.client().metadata(),
-        )?;
+            remove_exec.transcoder(),
+            &metadata,
+            self.extrinsic_cli_opts
+                .chain_cli_opts
+                .ss58_prefix_for(Some(&metadata)),
+        )?
+        .filter(&self.extrinsic_cli_opts.event_filter());
 
         let output_events = if self.output_json() {
             display_events.to_json()?
@@ -146,19 +164,21 @@ impl RemoveCommand {
             )?
         };
         if let Some(code_removed) = remove_result.code_removed {
-            let remove_result: <C as Config>::Hash = code_removed.code_hash;
+            let code_hash: <C as Config>::Hash = code_removed.code_hash;
 
             if self.output_json() {
                 // Create a JSON object with the events and the removed code hash.
                 let json_object = serde_json::json!({
                     "events": serde_json::from_str::<serde_json::Value>(&output_events)?,
-                    "code_hash": remove_result,
+                    "code_hash": code_hash,
+                    "extrinsic": remove_result.receipt,
                 });
                 let json_object = serde_json::to_string_pretty(&json_object)?;
                 println!("{}", json_object);
             } else {
                 println!("{}", output_events);
-                name_value_println!("Code hash", format!("{remove_result:?}"));
+                name_value_println!("Code hash", format!("{code_hash:?}"));
+                display_extrinsic_receipt(&remove_result.receipt);
             }
             Result::<(), ErrorVariant>::Ok(())
         } else {
@@ -0,0 +1,598 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    address_book::resolve_contract,
+    config::SignerConfig,
+    display_extrinsic_receipt,
+    parse_account,
+    parse_balance,
+    storage::StorageDisplayTable,
+    CLIChainOpts,
+    Suri,
+};
+use anyhow::{
+    anyhow,
+    Context as _,
+    Result,
+};
+use clap::{
+    Parser,
+    Subcommand,
+};
+use colored::Colorize;
+use contract_build::Verbosity;
+use contract_extrinsics::{
+    CallCommandBuilder,
+    CallExec,
+    ContractArtifacts,
+    ContractStorage,
+    ContractStorageRpc,
+    DisplayEvents,
+    ExtrinsicOptsBuilder,
+    TokenMetadata,
+};
+use contract_transcode::{
+    unwrap_lang_result,
+    ContractMessageTranscoder,
+    ReturnError,
+    Value,
+};
+use ink_env::Environment;
+use rustyline::{
+    completion::Completer,
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context,
+    Editor,
+    Helper,
+};
+use serde::Serialize;
+use sp_weights::Weight;
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        Debug,
+        Display,
+    },
+    path::PathBuf,
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    Config,
+};
+
+/// The commands of the REPL, completed at the start of a line.
+const COMMANDS: [&str; 9] = [
+    "call", "query", "storage", "events", "use", "set", "vars", "help", "exit",
+];
+
+/// The variable holding the value returned by the last `call` or `query`.
+const LAST_VAR: &str = "last";
+
+/// The variable holding the address of the contract.
+const CONTRACT_VAR: &str = "contract";
+
+/// Starts an interactive shell to call and query a contract, and to inspect its
+/// storage and the events of calls.
+///
+/// The names of the commands and messages are completed with Tab. Arguments of the form
+/// `$<name>` are replaced by the session variable `name`: `$last` holds the value
+/// returned by the last `call` or `query` and `$contract` the address of the contract.
+#[derive(Debug, clap::Args)]
+#[clap(name = "repl")]
+pub struct ReplCommand {
+    /// Path to the `.contract` bundle or the `.json` metadata file of the contract.
+    #[clap(value_parser)]
+    file: PathBuf,
+    /// The address of the contract, or `@<name>` of the address book. It can also be
+    /// set in the session with `use <contract>`.
+    #[clap(name = "contract", long, env = "CONTRACT")]
+    contract: Option<String>,
+    /// Secret key URI of the account calls originate from.
+    #[clap(name = "suri", long, short, default_value = "//Alice")]
+    suri: Suri,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+/// A line entered in the REPL.
+#[derive(Debug, Parser)]
+#[clap(name = "", no_binary_name = true, disable_help_flag = true)]
+struct ReplLine {
+    #[clap(subcommand)]
+    input: ReplInput,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReplInput {
+    /// Submit a message mutating the contract, after dry-running it
+    Call {
+        /// The name of the message, or its selector e.g. `0x633aa551`.
+        message: String,
+        /// The arguments of the message.
+        args: Vec<String>,
+        /// The value to be transferred as part of the call.
+        #[clap(long, default_value = "0")]
+        value: String,
+    },
+    /// Dry-run a message and print its returned value
+    Query {
+        /// The name of the message, or its selector e.g. `0x633aa551`.
+        message: String,
+        /// The arguments of the message.
+        args: Vec<String>,
+        /// The value to be transferred as part of the query.
+        #[clap(long, default_value = "0")]
+        value: String,
+    },
+    /// Display the storage of the contract
+    Storage,
+    /// Display the events of the last call
+    Events,
+    /// Switch to another contract, by its address or `@<name>` of the address book
+    Use {
+        /// The address of the contract.
+        contract: String,
+    },
+    /// Set a session variable, passed to messages as `$<name>`
+    Set {
+        /// The name of the variable.
+        name: String,
+        /// The value of the variable.
+        value: String,
+    },
+    /// Display the session variables
+    Vars,
+    /// Leave the REPL
+    #[clap(alias = "quit")]
+    Exit,
+}
+
+/// The state of a REPL session.
+#[derive(Debug, Default)]
+struct Session {
+    /// The address of the contract the commands are executed against.
+    contract: Option<String>,
+    /// The session variables, including `last` and `contract`.
+    vars: BTreeMap<String, String>,
+    /// The displayed events of the last call.
+    events: Option<String>,
+}
+
+impl Session {
+    /// Sets the address of the `contract` the commands are executed against.
+    fn set_contract(&mut self, contract: String) {
+        self.vars.insert(CONTRACT_VAR.to_string(), contract.clone());
+        self.contract = Some(contract);
+    }
+
+    /// Returns the address of the contract, failing if none was set yet.
+    fn contract(&self) -> Result<&str> {
+        self.contract.as_deref().ok_or_else(|| {
+            anyhow!("No contract selected, pass `--contract` or run `use <contract>`")
+        })
+    }
+
+    /// Replaces the `tokens` of the form `$<name>` by the value of the variable `name`.
+    fn expand(&self, tokens: Vec<String>) -> Result<Vec<String>> {
+        tokens
+            .into_iter()
+            .map(|token| {
+                match token.strip_prefix('$') {
+                    Some(name) => {
+                        self.vars
+                            .get(name)
+                            .cloned()
+                            .ok_or_else(|| anyhow!("Unknown variable `${name}`"))
+                    }
+                    None => Ok(token),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Completes the commands, the names of the messages and the variables of the REPL.
+struct ReplHelper {
+    messages: Vec<String>,
+    vars: Vec<String>,
+}
+
+impl ReplHelper {
+    /// Returns the start of the last word of the `line` and its completions.
+    fn completions(&self, line: &str) -> (usize, Vec<String>) {
+        let word = line.rsplit(char::is_whitespace).next().unwrap_or_default();
+        let start = line.len() - word.len();
+        let preceding = line[..start].split_whitespace().collect::<Vec<_>>();
+        let candidates = if word.starts_with('$') {
+            self.vars.iter().map(|var| format!("${var}")).collect()
+        } else {
+            match preceding.as_slice() {
+                [] => COMMANDS.iter().map(ToString::to_string).collect(),
+                ["call" | "query"] => self.messages.clone(),
+                _ => Vec::new(),
+            }
+        };
+        let completions = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect();
+        (start, completions)
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completions(&line[..pos]))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+impl ReplCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId:
+            IntoVisitor + FromStr + EncodeAsType + Display + AsRef<[u8]>,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType
+            + IntoVisitor,
+        <C as Config>::Hash: IntoVisitor,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let chain = self.chain_cli_opts.chain();
+        let token_metadata = self.chain_cli_opts.token_metadata::<C>().await?;
+        let transcoder = ContractArtifacts::from_manifest_or_file(None, Some(&self.file))?
+            .contract_transcoder()?;
+        let messages = transcoder
+            .metadata()
+            .spec()
+            .messages()
+            .iter()
+            .map(|message| message.label().to_string())
+            .collect();
+
+        let mut session = Session::default();
+        if let Some(contract) = &self.contract {
+            session.set_contract(resolve_contract(contract, &chain)?);
+        }
+        let mut editor =
+            Editor::<ReplHelper, DefaultHistory>::new().map_err(anyhow::Error::from)?;
+        editor.set_helper(Some(ReplHelper {
+            messages,
+            vars: session.vars.keys().cloned().collect(),
+        }));
+        contract_build::output::diagnostic(format_args!(
+            "Type `help` to list the commands, `exit` or Ctrl-D to leave"
+        ));
+
+        loop {
+            let line = match editor.readline("contract> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(err) => return Err(anyhow::Error::from(err).into()),
+            };
+            if line.trim().is_empty() {
+                continue
+            }
+            let _ = editor.add_history_entry(line.as_str());
+            let tokens = shlex::split(&line)
+                .ok_or_else(|| anyhow!("Unterminated quote in `{line}`"))
+                .and_then(|tokens| session.expand(tokens));
+            let tokens = match tokens {
+                Ok(tokens) => tokens,
+                Err(err) => {
+                    eprintln!("{} {err}", "ERROR:".bright_red().bold());
+                    continue
+                }
+            };
+            let input = match ReplLine::try_parse_from(tokens) {
+                Ok(line) => line.input,
+                Err(err) => {
+                    let _ = err.print();
+                    continue
+                }
+            };
+            if let ReplInput::Exit = input {
+                break
+            }
+            if let Err(err) = self
+                .exec::<C>(input, &mut session, &transcoder, &token_metadata)
+                .await
+            {
+                eprintln!("{} {err}", "ERROR:".bright_red().bold());
+            }
+            if let Some(helper) = editor.helper_mut() {
+                helper.vars = session.vars.keys().cloned().collect();
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes a command of the REPL in the `session`.
+    async fn exec<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        input: ReplInput,
+        session: &mut Session,
+        transcoder: &ContractMessageTranscoder,
+        token_metadata: &TokenMetadata,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId:
+            IntoVisitor + FromStr + EncodeAsType + Display + AsRef<[u8]>,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType
+            + IntoVisitor,
+        <C as Config>::Hash: IntoVisitor,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        match input {
+            ReplInput::Call {
+                message,
+                args,
+                value,
+            } => {
+                self.chain_cli_opts.ensure_can_submit()?;
+                let call_exec = self
+                    .call_exec::<C>(session, &message, args, &value, token_metadata)
+                    .await?;
+                let (value, gas_required) = dry_run(&call_exec).await?;
+                let call_result = call_exec.call(Some(gas_required)).await?;
+                let events = DisplayEvents::from_events::<C, C>(
+                    &call_result.events,
+                    call_exec.transcoder(),
+                    &call_exec.client().metadata(),
+                    call_exec.ss58_prefix(),
+                )?
+                .display_events::<C>(Verbosity::Default, token_metadata)?;
+                println!("{events}");
+                display_extrinsic_receipt(&call_result.receipt);
+                session.vars.insert(LAST_VAR.to_string(), value.to_string());
+                session.events = Some(events);
+            }
+            ReplInput::Query {
+                message,
+                args,
+                value,
+            } => {
+                let call_exec = self
+                    .call_exec::<C>(session, &message, args, &value, token_metadata)
+                    .await?;
+                let (value, _) = dry_run(&call_exec).await?;
+                println!("{value}");
+                session.vars.insert(LAST_VAR.to_string(), value.to_string());
+            }
+            ReplInput::Storage => {
+                let contract: <C as Config>::AccountId =
+                    parse_account(session.contract()?)?;
+                let rpc = ContractStorageRpc::<C>::with_options(
+                    &self.chain_cli_opts.chain().url(),
+                    self.chain_cli_opts.rpc_options(),
+                )
+                .await?;
+                let storage = ContractStorage::<C, C>::new(rpc)
+                    .load_contract_storage_with_layout(&contract, transcoder)
+                    .await?;
+                StorageDisplayTable::new(&storage).display();
+            }
+            ReplInput::Events => {
+                match &session.events {
+                    Some(events) => println!("{events}"),
+                    None => println!("No call was submitted yet"),
+                }
+            }
+            ReplInput::Use { contract } => {
+                let contract =
+                    resolve_contract(&contract, &self.chain_cli_opts.chain())?;
+                parse_account::<<C as Config>::AccountId>(&contract)?;
+                session.set_contract(contract);
+            }
+            ReplInput::Set { name, value } => {
+                session.vars.insert(name, value);
+            }
+            ReplInput::Vars => {
+                for (name, value) in &session.vars {
+                    println!("{} = {value}", format!("${name}").bright_blue().bold());
+                }
+            }
+            ReplInput::Exit => {}
+        }
+        Ok(())
+    }
+
+    /// Builds the call of the `message` with the `args` to the contract of the
+    /// `session`.
+    async fn call_exec<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        session: &Session,
+        message: &str,
+        args: Vec<String>,
+        value: &str,
+        token_metadata: &TokenMetadata,
+    ) -> Result<CallExec<C, C, C::Signer>>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128> + Display + Default + FromStr + Debug + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let contract = parse_account(session.contract()?)
+            .map_err(|e| anyhow!("Failed to parse contract: {}", e))?;
+        let value = parse_balance(value, token_metadata)
+            .map_err(|e| anyhow!("Failed to parse value option: {}", e))?;
+        let signer = self.suri.signer::<C>()?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::<C, C, _>::new(signer)
+            .file(Some(self.file.clone()))
+            .url(self.chain_cli_opts.chain().url())
+            .rpc_options(self.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.chain_cli_opts.ss58_prefix())
+            .done();
+        CallCommandBuilder::new(contract, message, extrinsic_opts)
+            .args(args)
+            .value(value)
+            .done()
+            .await
+    }
+}
+
+/// Dry-runs the call and returns the value returned by the message, failing if the
+/// message reverted or returned an error, and the gas required by the call.
+async fn dry_run<C: Config + Environment, Signer>(
+    call_exec: &CallExec<C, C, Signer>,
+) -> Result<(Value, Weight), ErrorVariant>
+where
+    Signer: subxt::tx::Signer<C> + Clone,
+    <C as Config>::AccountId: IntoVisitor + EncodeAsType,
+    C::Balance: EncodeAsType,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    let result = call_exec.call_dry_run().await?;
+    let ret_val = match result.result {
+        Ok(ret_val) => ret_val,
+        Err(ref err) => {
+            return Err(ErrorVariant::from_dispatch_error(
+                err,
+                &call_exec.client().metadata(),
+            )?)
+        }
+    };
+    let value = call_exec
+        .decode_return(&ret_val.data)
+        .context(format!("Failed to decode return value {:?}", &ret_val))
+        .map_err(ErrorVariant::decode)?;
+    let lang_result = call_exec.returns_lang_result();
+    if let Some(error) = ReturnError::from_return_value(&value, lang_result) {
+        return Err(ErrorVariant::dry_run_revert(format!(
+            "The message returned an error: {error}"
+        )))
+    }
+    if ret_val.did_revert() {
+        return Err(ErrorVariant::dry_run_revert(format!(
+            "The message reverted: {value}"
+        )))
+    }
+    Ok((unwrap_lang_result(value, lang_result), result.gas_required))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn helper() -> ReplHelper {
+        ReplHelper {
+            messages: vec!["get".to_string(), "flip".to_string()],
+            vars: vec!["contract".to_string(), "last".to_string()],
+        }
+    }
+
+    #[test]
+    fn completes_commands() {
+        assert_eq!(helper().completions("q"), (0, vec!["query".to_string()]));
+        assert_eq!(helper().completions("e").1, vec!["events", "exit"]);
+    }
+
+    #[test]
+    fn completes_messages_of_call_and_query() {
+        assert_eq!(helper().completions("call f"), (5, vec!["flip".to_string()]));
+        assert_eq!(helper().completions("query ").1, vec!["get", "flip"]);
+        assert!(helper().completions("storage g").1.is_empty());
+    }
+
+    #[test]
+    fn completes_variables() {
+        assert_eq!(
+            helper().completions("query get $l"),
+            (10, vec!["$last".to_string()])
+        );
+    }
+
+    #[test]
+    fn expands_variables() {
+        let mut session = Session::default();
+        session.set_contract("5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM".into());
+        session.vars.insert("last".into(), "Some(42)".into());
+
+        let tokens = ["query", "get", "$last", "$contract", "1"]
+            .map(ToString::to_string)
+            .to_vec();
+        assert_eq!(
+            session.expand(tokens).unwrap(),
+            vec![
+                "query",
+                "get",
+                "Some(42)",
+                "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM",
+                "1"
+            ]
+        );
+        assert!(session.expand(vec!["$unknown".to_string()]).is_err());
+    }
+}
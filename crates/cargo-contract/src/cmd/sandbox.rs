@@ -0,0 +1,154 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Instantiating and calling contracts in an in-process sandbox instead of on a node.
+
+use contract_extrinsics::ErrorVariant;
+use std::path::PathBuf;
+
+#[cfg(feature = "sandbox")]
+use anyhow::Result;
+#[cfg(feature = "sandbox")]
+use contract_extrinsics::{
+    ss58,
+    DisplayEvents,
+    Event,
+    Field,
+    SandboxBackend,
+    SandboxContractEvent,
+    TokenMetadata,
+};
+#[cfg(feature = "sandbox")]
+use contract_transcode::{
+    ContractMessageTranscoder,
+    Hex,
+    Value,
+};
+#[cfg(feature = "sandbox")]
+use sp_weights::Weight;
+#[cfg(feature = "sandbox")]
+use std::str::FromStr;
+#[cfg(feature = "sandbox")]
+use subxt::ext::codec::Encode;
+
+/// Options to instantiate and call contracts in an in-process sandbox.
+#[derive(Clone, Debug, clap::Args)]
+pub struct CLISandboxOpts {
+    /// Execute the contract in an in-process runtime with `pallet-contracts` instead of
+    /// on a node, e.g. to exercise its logic and gas usage without running a node.
+    ///
+    /// Requires cargo-contract to be installed with the `sandbox` feature.
+    #[clap(long, conflicts_with = "abi")]
+    pub sandbox: bool,
+    /// The file storing the state of the sandbox, to call contracts instantiated by
+    /// earlier invocations. The sandbox starts with an empty state otherwise.
+    #[clap(long, value_name = "FILE", requires = "sandbox")]
+    sandbox_state: Option<PathBuf>,
+}
+
+/// The tokens of the sandbox, balances are passed without a denomination.
+#[cfg(feature = "sandbox")]
+pub const SANDBOX_TOKEN: TokenMetadata = TokenMetadata {
+    token_decimals: 0,
+    symbol: String::new(),
+};
+
+#[cfg(feature = "sandbox")]
+impl CLISandboxOpts {
+    /// Creates the sandbox, with the state of the `--sandbox-state` file if passed.
+    pub fn load(&self) -> Result<SandboxBackend> {
+        match &self.sandbox_state {
+            Some(path) => SandboxBackend::load(path),
+            None => Ok(SandboxBackend::default()),
+        }
+    }
+
+    /// Stores the state of the `sandbox` in the `--sandbox-state` file if passed.
+    pub fn store(&self, sandbox: &mut SandboxBackend) -> Result<()> {
+        match &self.sandbox_state {
+            Some(path) => sandbox.store(path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the error of using `--sandbox` without the `sandbox` feature.
+#[cfg(not(feature = "sandbox"))]
+pub fn sandbox_unsupported() -> ErrorVariant {
+    anyhow::anyhow!(
+        "cargo-contract was installed without the `sandbox` feature, reinstall it with \
+        `cargo install cargo-contract --features sandbox` to use `--sandbox`"
+    )
+    .into()
+}
+
+/// Returns the gas limit of the `--gas` and `--proof-size` options, `None` to use the
+/// default gas limit of the sandbox.
+#[cfg(feature = "sandbox")]
+pub fn gas_limit(
+    gas_limit: Option<u64>,
+    proof_size: Option<u64>,
+) -> Result<Option<Weight>, ErrorVariant> {
+    match (gas_limit, proof_size) {
+        (Some(ref_time), Some(proof_size)) => {
+            Ok(Some(Weight::from_parts(ref_time, proof_size)))
+        }
+        (None, None) => Ok(None),
+        _ => {
+            Err(anyhow::anyhow!(
+                "Weight args `--gas` and `--proof-size` must be passed together"
+            )
+            .into())
+        }
+    }
+}
+
+/// Decodes the `events` emitted by contracts in the sandbox with the `transcoder`, for
+/// displaying them like the events of a submitted extrinsic.
+///
+/// The addresses of the contracts are displayed with the `ss58_prefix`.
+#[cfg(feature = "sandbox")]
+pub fn display_events(
+    events: Vec<SandboxContractEvent>,
+    transcoder: &ContractMessageTranscoder,
+    ss58_prefix: u16,
+) -> Result<DisplayEvents> {
+    let mut display_events = Vec::new();
+    for event in events {
+        let decoded = event.topics.first().and_then(|topic| {
+            transcoder
+                .decode_contract_event(topic, &mut &event.data.encode()[..])
+                .ok()
+        });
+        let data = match decoded {
+            Some(data) => data,
+            None => Value::Hex(Hex::from_str(&hex::encode(&event.data))?),
+        };
+        display_events.push(Event {
+            pallet: "Contracts".to_string(),
+            name: "ContractEmitted".to_string(),
+            fields: vec![
+                Field::new(
+                    "contract".to_string(),
+                    Value::Literal(ss58::display_account(&event.contract, ss58_prefix)),
+                    None,
+                ),
+                Field::new("data".to_string(), data, None),
+            ],
+        });
+    }
+    Ok(DisplayEvents::from(display_events))
+}
@@ -0,0 +1,116 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+
+use crate::DEFAULT_KEY_COL_WIDTH;
+use anyhow::{
+    anyhow,
+    Result,
+};
+use contract_build::name_value_println;
+use contract_extrinsics::ContractArtifacts;
+use contract_transcode::{
+    compute_selector,
+    SelectorKind,
+};
+
+#[derive(Debug, Clone, clap::Args)]
+#[clap(
+    name = "selector",
+    about = "Compute the selector of a message or look up the message of a selector"
+)]
+pub struct SelectorCommand {
+    /// The label of a message or constructor, e.g. `flip` or `Erc20::transfer`, or a
+    /// hex encoded selector to look up in the contract metadata, e.g. `0x633aa551`.
+    input: String,
+    /// Path to a contract build artifact file: a raw `.wasm` file, a `.contract` bundle,
+    /// or a `.json` metadata file.
+    #[clap(long, value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Export the output as JSON.
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl SelectorCommand {
+    pub fn run(&self) -> Result<()> {
+        match self.input.strip_prefix("0x") {
+            Some(hex) => self.lookup(hex),
+            None => self.compute(),
+        }
+    }
+
+    fn compute(&self) -> Result<()> {
+        let selector = format!("0x{}", hex::encode(compute_selector(&self.input)));
+        if self.output_json {
+            let json = serde_json::json!({
+                "label": self.input,
+                "selector": selector,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            name_value_println!("Selector", selector, DEFAULT_KEY_COL_WIDTH);
+        }
+        Ok(())
+    }
+
+    fn lookup(&self, hex: &str) -> Result<()> {
+        let selector: [u8; 4] = hex::decode(hex)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                anyhow!("A selector consists of 4 hex encoded bytes, e.g. `0x633aa551`")
+            })?;
+        let artifacts = ContractArtifacts::from_manifest_or_file(
+            self.manifest_path.as_ref(),
+            self.file.as_ref(),
+        )?;
+        let transcoder = artifacts.contract_transcoder()?;
+
+        let matches = transcoder.lookup_selector(selector);
+        if matches.is_empty() {
+            anyhow::bail!(
+                "No constructor or message with the selector {} found in the contract \
+                 metadata",
+                self.input
+            )
+        }
+        if self.output_json {
+            let matches = matches
+                .iter()
+                .map(|(kind, label)| serde_json::json!({ "kind": kind, "label": label }))
+                .collect::<Vec<_>>();
+            let json = serde_json::json!({
+                "selector": self.input,
+                "matches": matches,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        } else {
+            for (kind, label) in matches {
+                let name = match kind {
+                    SelectorKind::Constructor => "Constructor",
+                    SelectorKind::Message => "Message",
+                };
+                name_value_println!(name, label, DEFAULT_KEY_COL_WIDTH);
+            }
+        }
+        Ok(())
+    }
+}
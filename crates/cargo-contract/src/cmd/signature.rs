@@ -0,0 +1,224 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_build::{
+    code_hash,
+    name_value_println,
+    MetadataArtifacts,
+};
+use contract_metadata::{
+    BundleSignature,
+    ContractMetadata,
+    SignatureScheme,
+};
+use sp_core::{
+    crypto::{
+        ByteArray,
+        Pair as _,
+    },
+    ed25519,
+    sr25519,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+/// Verifies the signature of a contract bundle.
+#[derive(Debug, clap::Args)]
+#[clap(name = "verify-bundle")]
+pub struct VerifyBundleCommand {
+    /// Path to the signed `.contract` bundle or `.json` metadata.
+    #[clap(value_parser)]
+    bundle: PathBuf,
+    /// The public key the bundle is expected to be signed with, hex encoded.
+    #[clap(long)]
+    public_key: Option<String>,
+}
+
+impl VerifyBundleCommand {
+    pub fn run(&self) -> Result<()> {
+        let mut metadata = ContractMetadata::load(&self.bundle)?;
+        metadata.decompress_wasm()?;
+        let signature = verify_signature(&metadata)?;
+        if let Some(public_key) = &self.public_key {
+            let expected = contract_build::util::decode_hex(public_key)
+                .context("Failed to decode the public key")?;
+            if expected != signature.public_key {
+                anyhow::bail!(
+                    "The bundle is signed by 0x{}, not by the expected public key",
+                    hex::encode(&signature.public_key)
+                )
+            }
+        }
+        if let Some(wasm) = &metadata.source.wasm {
+            if code_hash(&wasm.0) != metadata.source.hash.0 {
+                anyhow::bail!("The Wasm code does not match the signed code hash")
+            }
+        }
+
+        name_value_println!("Signature", "valid");
+        name_value_println!("Scheme", signature.scheme.to_string());
+        name_value_println!(
+            "Public key",
+            format!("0x{}", hex::encode(&signature.public_key))
+        );
+        Ok(())
+    }
+}
+
+/// Signs the bundle and the metadata file of a build with the key derived from `suri`.
+pub fn sign_bundle(
+    artifacts: &MetadataArtifacts,
+    suri: &str,
+    scheme: SignatureScheme,
+) -> Result<()> {
+    let mut metadata = ContractMetadata::load(&artifacts.dest_bundle)?;
+    sign_metadata(&mut metadata, suri, scheme)?;
+    fs::write(&artifacts.dest_bundle, serde_json::to_string(&metadata)?)?;
+    metadata.remove_source_wasm_attribute();
+    fs::write(
+        &artifacts.dest_metadata,
+        serde_json::to_string_pretty(&metadata)?,
+    )?;
+    Ok(())
+}
+
+/// Signs the payload of `metadata` with the key derived from `suri`.
+fn sign_metadata(
+    metadata: &mut ContractMetadata,
+    suri: &str,
+    scheme: SignatureScheme,
+) -> Result<()> {
+    let payload = metadata.signing_payload()?;
+    let invalid_suri = |e| anyhow!("Failed to parse the signing key: {e:?}");
+    let (public_key, signature) = match scheme {
+        SignatureScheme::Sr25519 => {
+            let pair = sr25519::Pair::from_string(suri, None).map_err(invalid_suri)?;
+            (pair.public().to_raw_vec(), pair.sign(&payload).0.to_vec())
+        }
+        SignatureScheme::Ed25519 => {
+            let pair = ed25519::Pair::from_string(suri, None).map_err(invalid_suri)?;
+            (pair.public().to_raw_vec(), pair.sign(&payload).0.to_vec())
+        }
+    };
+    metadata.signature = Some(BundleSignature {
+        scheme,
+        public_key,
+        signature,
+    });
+    Ok(())
+}
+
+/// Verifies the signature of `metadata`, returning it if it is valid.
+fn verify_signature(metadata: &ContractMetadata) -> Result<&BundleSignature> {
+    let signature = metadata
+        .signature
+        .as_ref()
+        .ok_or_else(|| anyhow!("The bundle is not signed"))?;
+    let payload = metadata.signing_payload()?;
+    let valid = match signature.scheme {
+        SignatureScheme::Sr25519 => {
+            let public = sr25519::Public::from_slice(&signature.public_key)
+                .map_err(|_| anyhow!("Invalid sr25519 public key"))?;
+            let sig = sr25519::Signature::from_slice(&signature.signature)
+                .ok_or_else(|| anyhow!("Invalid sr25519 signature"))?;
+            sr25519::Pair::verify(&sig, &payload, &public)
+        }
+        SignatureScheme::Ed25519 => {
+            let public = ed25519::Public::from_slice(&signature.public_key)
+                .map_err(|_| anyhow!("Invalid ed25519 public key"))?;
+            let sig = ed25519::Signature::from_slice(&signature.signature)
+                .ok_or_else(|| anyhow!("Invalid ed25519 signature"))?;
+            ed25519::Pair::verify(&sig, &payload, &public)
+        }
+    };
+    if !valid {
+        anyhow::bail!("The signature of the bundle is invalid")
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn metadata() -> ContractMetadata {
+        serde_json::from_value(json!({
+            "source": {
+                "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "language": "ink! 5.0.0",
+                "compiler": "rustc 1.78.0"
+            },
+            "contract": {
+                "name": "flipper",
+                "version": "0.1.0",
+                "authors": ["Use Ink <admin@use.ink>"]
+            },
+            "image": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn signed_metadata_verifies() {
+        for scheme in [SignatureScheme::Sr25519, SignatureScheme::Ed25519] {
+            let mut metadata = metadata();
+            sign_metadata(&mut metadata, "//Alice", scheme).unwrap();
+
+            let signature = verify_signature(&metadata).unwrap();
+
+            assert_eq!(signature.scheme, scheme);
+        }
+    }
+
+    #[test]
+    fn tampered_metadata_does_not_verify() {
+        let mut metadata = metadata();
+        sign_metadata(&mut metadata, "//Alice", SignatureScheme::Sr25519).unwrap();
+        metadata.contract.name = "flopper".to_string();
+
+        let err = verify_signature(&metadata).unwrap_err();
+
+        assert_eq!(err.to_string(), "The signature of the bundle is invalid");
+    }
+
+    #[test]
+    fn tampered_abi_does_not_verify() {
+        let mut metadata = metadata();
+        sign_metadata(&mut metadata, "//Alice", SignatureScheme::Sr25519).unwrap();
+        metadata
+            .abi
+            .insert("spec".into(), json!({ "messages": [{ "selector": "0x00000000" }] }));
+
+        let err = verify_signature(&metadata).unwrap_err();
+
+        assert_eq!(err.to_string(), "The signature of the bundle is invalid");
+    }
+
+    #[test]
+    fn unsigned_metadata_does_not_verify() {
+        let err = verify_signature(&metadata()).unwrap_err();
+
+        assert_eq!(err.to_string(), "The bundle is not signed");
+    }
+}
@@ -20,12 +20,18 @@ use comfy_table::{
     ContentArrangement,
     Table,
 };
+use contract_build::name_value_println;
 use contract_extrinsics::{
     ContractArtifacts,
+    ContractMessageTranscoder,
     ContractStorage,
+    ContractStorageFieldValue,
     ContractStorageLayout,
     ContractStorageRpc,
     ErrorVariant,
+    ForkedContractStorage,
+    MutableContractStorage,
+    StorageLayoutNode,
 };
 use ink_env::Environment;
 use serde::Serialize;
@@ -42,14 +48,23 @@ use subxt::{
 use crate::call_with_config;
 
 use super::{
+    address_book::resolve_contract,
     parse_account,
     CLIChainOpts,
 };
 
 #[derive(Debug, clap::Args)]
-#[clap(name = "storage", about = "Inspect contract storage")]
+#[clap(
+    name = "storage",
+    about = "Inspect contract storage",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct StorageCommand {
-    /// The address of the contract to inspect storage of.
+    #[clap(subcommand)]
+    command: Option<StorageSubcommand>,
+    /// The address of the contract to inspect storage of, or `@<name>` of the address
+    /// book.
     #[clap(
         name = "contract",
         long,
@@ -72,15 +87,233 @@ pub struct StorageCommand {
     manifest_path: Option<PathBuf>,
     /// Fetch the storage version of the pallet contracts (state query:
     /// contracts::palletVersion()).
-    #[clap(long, short)]
+    #[clap(long)]
     version: bool,
     /// Arguments required for communicating with a Substrate node.
     #[clap(flatten)]
     chain_cli_opts: CLIChainOpts,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum StorageSubcommand {
+    /// Display the storage layout of a contract, with the root and storage keys of its
+    /// fields
+    #[clap(name = "layout")]
+    Layout(StorageLayoutCommand),
+    /// Read and decode a single field of the storage of a contract, e.g. `ownable.owner`
+    #[clap(name = "read")]
+    Read(StorageReadCommand),
+    /// Write a single field of the storage of a contract in the sandbox or in a forked
+    /// snapshot, e.g. to test migrations. Live chains are never written.
+    #[clap(name = "write")]
+    Write(StorageWriteCommand),
+}
+
+#[derive(Debug, clap::Args)]
+struct StorageLayoutCommand {
+    /// Path to a contract build artifact file: a `.contract` bundle or a `.json`
+    /// metadata file.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Export the storage layout in JSON format.
+    #[clap(name = "output-json", long)]
+    output_json: bool,
+}
+
+impl StorageLayoutCommand {
+    fn run(&self) -> Result<(), ErrorVariant> {
+        let metadata = ContractArtifacts::from_manifest_or_file(
+            self.manifest_path.as_ref(),
+            self.file.as_ref(),
+        )?
+        .ink_project_metadata()?;
+        let layout = StorageLayoutNode::from_metadata(&metadata);
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&layout)?);
+        } else {
+            print!("{layout}");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, clap::Args)]
+struct StorageReadCommand {
+    /// The address of the contract to read the storage of, or `@<name>` of the address
+    /// book.
+    #[clap(name = "contract", long, env = "CONTRACT")]
+    contract: String,
+    /// The path of the field to read, e.g. `ownable.owner`.
+    ///
+    /// An entry of a `Mapping` is selected with its key in brackets, e.g.
+    /// `balances[5GrwvaEF...]`, and an element of a `StorageVec` with its index, e.g.
+    /// `values[3]`. A `StorageVec` without an index is read as its length.
+    #[clap(long)]
+    key: String,
+    /// Path to a contract build artifact file: a `.contract` bundle or a `.json`
+    /// metadata file.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Export the field in JSON format.
+    #[clap(name = "output-json", long)]
+    output_json: bool,
+    /// Arguments required for communicating with a Substrate node.
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl StorageReadCommand {
+    async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment>(&self) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: Display + IntoVisitor + AsRef<[u8]> + FromStr,
+        <<C as Config>::AccountId as FromStr>::Err:
+            Into<Box<dyn std::error::Error>> + Display,
+        C::Balance: Serialize + IntoVisitor,
+        <C as Config>::Hash: IntoVisitor,
+    {
+        let transcoder = ContractArtifacts::from_manifest_or_file(
+            self.manifest_path.as_ref(),
+            self.file.as_ref(),
+        )?
+        .contract_transcoder()?;
+        let chain = self.chain_cli_opts.chain();
+        let contract = parse_account(&resolve_contract(&self.contract, &chain)?)?;
+        let rpc = ContractStorageRpc::<C>::with_options(
+            &chain.url(),
+            self.chain_cli_opts.rpc_options(),
+        )
+        .await?;
+        let field = ContractStorage::<C, C>::new(rpc)
+            .read_field(&contract, &self.key, &transcoder)
+            .await?;
+        print_field(field, self.output_json)
+    }
+}
+
+#[derive(Debug, clap::Args)]
+struct StorageWriteCommand {
+    /// The path of the field to write, e.g. `ownable.owner` or `balances[5GrwvaEF...]`.
+    ///
+    /// A field within the value of a storage cell, e.g. the field of a packed struct, is
+    /// replaced within the current value of the cell.
+    #[clap(long)]
+    key: String,
+    /// The new value of the field, e.g. `true` or `Some(5GrwvaEF...)`.
+    #[clap(long)]
+    value: String,
+    /// The file storing the state of the sandbox, as passed to `--sandbox-state` of
+    /// `instantiate` and `call`.
+    #[clap(
+        long,
+        value_name = "FILE",
+        required_unless_present = "fork",
+        conflicts_with = "fork"
+    )]
+    sandbox_state: Option<PathBuf>,
+    /// The address of the contract in the sandbox.
+    #[clap(long, required_unless_present = "fork", requires = "sandbox_state")]
+    contract: Option<String>,
+    /// The snapshot of a contract written by `cargo contract fork`.
+    #[clap(long, value_name = "FILE")]
+    fork: Option<PathBuf>,
+    /// Path to a contract build artifact file: a `.contract` bundle or a `.json`
+    /// metadata file.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Export the written field in JSON format.
+    #[clap(name = "output-json", long)]
+    output_json: bool,
+}
+
+impl StorageWriteCommand {
+    fn run(&self) -> Result<(), ErrorVariant> {
+        let transcoder = ContractArtifacts::from_manifest_or_file(
+            self.manifest_path.as_ref(),
+            self.file.as_ref(),
+        )?
+        .contract_transcoder()?;
+        let field = match &self.fork {
+            Some(path) => {
+                let mut fork = ForkedContractStorage::load(path)?;
+                let field = fork.write_field(&self.key, &self.value, &transcoder)?;
+                fork.store(path)?;
+                field
+            }
+            None => self.write_in_sandbox(&transcoder)?,
+        };
+        print_field(field, self.output_json)
+    }
+
+    #[cfg(feature = "sandbox")]
+    fn write_in_sandbox(
+        &self,
+        transcoder: &ContractMessageTranscoder,
+    ) -> Result<ContractStorageFieldValue, ErrorVariant> {
+        use contract_extrinsics::SandboxBackend;
+        use subxt::utils::AccountId32;
+
+        let path = self.sandbox_state.as_ref().expect("required by clap");
+        let contract: AccountId32 =
+            parse_account(self.contract.as_deref().expect("required by clap"))?;
+        let mut sandbox = SandboxBackend::load(path)?;
+        let field = sandbox.contract_storage(&contract)?.write_field(
+            &self.key,
+            &self.value,
+            transcoder,
+        )?;
+        sandbox.store(path)?;
+        Ok(field)
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    fn write_in_sandbox(
+        &self,
+        _transcoder: &ContractMessageTranscoder,
+    ) -> Result<ContractStorageFieldValue, ErrorVariant> {
+        Err(super::sandbox::sandbox_unsupported())
+    }
+}
+
+/// Prints a single field of the storage of a contract.
+fn print_field(
+    field: ContractStorageFieldValue,
+    output_json: bool,
+) -> Result<(), ErrorVariant> {
+    if output_json {
+        println!("{}", serde_json::to_string_pretty(&field)?);
+    } else {
+        name_value_println!("Field", field.path);
+        name_value_println!("Cell", field.cell.to_string());
+        name_value_println!("Storage key", field.storage_key);
+        match field.value {
+            Some(value) => name_value_println!("Value", value.to_string()),
+            None => name_value_println!("Value", "None (empty storage cell)"),
+        }
+    }
+    Ok(())
+}
+
 impl StorageCommand {
     pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        match &self.command {
+            Some(StorageSubcommand::Layout(layout)) => return layout.run(),
+            Some(StorageSubcommand::Read(read)) => return read.handle().await,
+            Some(StorageSubcommand::Write(write)) => return write.run(),
+            None => {}
+        }
         call_with_config!(self, run, self.chain_cli_opts.chain().config())
     }
 
@@ -92,8 +325,11 @@ impl StorageCommand {
         C::Balance: Serialize + IntoVisitor,
         <C as Config>::Hash: IntoVisitor,
     {
-        let rpc =
-            ContractStorageRpc::<C>::new(&self.chain_cli_opts.chain().url()).await?;
+        let rpc = ContractStorageRpc::<C>::with_options(
+            &self.chain_cli_opts.chain().url(),
+            self.chain_cli_opts.rpc_options(),
+        )
+        .await?;
         let storage_layout = ContractStorage::<C, C>::new(rpc);
         if self.version {
             println!("{}", storage_layout.version().await?);
@@ -102,10 +338,11 @@ impl StorageCommand {
 
         // Contract arg shall be always present in this case, it is enforced by
         // clap configuration
+        let chain = self.chain_cli_opts.chain();
         let contract = self
             .contract
             .as_ref()
-            .map(|c| parse_account(c))
+            .map(|c| parse_account(&resolve_contract(c, &chain)?))
             .transpose()?
             .expect("Contract argument shall be present");
 
@@ -141,10 +378,10 @@ impl StorageCommand {
                 }
             }
             Err(_) => {
-                eprintln!(
+                contract_build::output::diagnostic(format_args!(
                     "{} Displaying raw storage: no valid contract metadata artifacts found",
                     "Info:".cyan().bold(),
-                );
+                ));
                 let storage_data =
                     storage_layout.load_contract_storage_data(&contract).await?;
                 println!(
@@ -158,7 +395,7 @@ impl StorageCommand {
     }
 }
 
-struct StorageDisplayTable(Table);
+pub(crate) struct StorageDisplayTable(Table);
 
 impl StorageDisplayTable {
     const INDEX_LABEL: &'static str = "Index";
@@ -166,7 +403,7 @@ impl StorageDisplayTable {
     const PARENT_LABEL: &'static str = "Parent";
     const VALUE_LABEL: &'static str = "Value";
 
-    fn new(storage_layout: &ContractStorageLayout) -> Self {
+    pub(crate) fn new(storage_layout: &ContractStorageLayout) -> Self {
         let mut table = Table::new();
         Self::table_add_header(&mut table);
         Self::table_add_rows(&mut table, storage_layout);
@@ -200,7 +437,37 @@ impl StorageDisplayTable {
         }
     }
 
-    fn display(&self) {
+    pub(crate) fn display(&self) {
         println!("{}", self.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        storage: StorageCommand,
+    }
+
+    #[test]
+    fn write_requires_sandbox_or_fork() {
+        let write = ["storage", "write", "--key", "value", "--value", "true"];
+        let cli =
+            Cli::try_parse_from([&write[..], &["--fork", "fork.json"]].concat()).unwrap();
+        assert!(matches!(cli.storage.command, Some(StorageSubcommand::Write(_))));
+        assert!(Cli::try_parse_from(
+            [&write[..], &["--sandbox-state", "state", "--contract", "5C"]].concat()
+        )
+        .is_ok());
+
+        let err = Cli::try_parse_from(write).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        let url = ["--fork", "fork.json", "--url", "ws://localhost:9944"];
+        let err = Cli::try_parse_from([&write[..], &url].concat()).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::UnknownArgument);
+    }
+}
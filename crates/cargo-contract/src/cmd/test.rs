@@ -0,0 +1,84 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use contract_build::{
+    name_value_println,
+    testing::{
+        CoverageFormat,
+        TestArgs,
+    },
+    Features,
+    ManifestPath,
+    VerbosityFlags,
+};
+use std::{
+    convert::TryFrom,
+    path::PathBuf,
+};
+
+/// Runs the off-chain unit tests of the contract.
+///
+/// With `--coverage` the source-based code coverage of the tests is collected and
+/// reported in `target/ink/coverage`, which requires the `llvm-tools-preview` component
+/// of the toolchain.
+#[derive(Debug, clap::Args)]
+#[clap(name = "test")]
+pub struct TestCommand {
+    /// Path to the `Cargo.toml` of the contract to test
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Collect the code coverage of the tests and generate reports in the given formats.
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        num_args = 0..,
+        default_missing_value = "lcov,html"
+    )]
+    coverage: Option<Vec<CoverageFormat>>,
+    #[clap(flatten)]
+    features: Features,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+    /// Arguments passed through to the test binaries, e.g. a filter for the test names.
+    #[clap(last = true)]
+    test_args: Vec<String>,
+}
+
+impl TestCommand {
+    pub fn run(&self) -> Result<()> {
+        let args = TestArgs {
+            manifest_path: ManifestPath::try_from(self.manifest_path.as_ref())?,
+            verbosity: TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?,
+            features: self.features.clone(),
+            coverage: self.coverage.clone().unwrap_or_default(),
+            test_args: self.test_args.clone(),
+        };
+        let result = contract_build::testing::execute(args)?;
+
+        if let Some(lcov) = result.lcov {
+            name_value_println!("Coverage (lcov)", lcov.display().to_string());
+        }
+        if let Some(html) = result.html {
+            name_value_println!(
+                "Coverage (html)",
+                html.join("index.html").display().to_string()
+            );
+        }
+        Ok(())
+    }
+}
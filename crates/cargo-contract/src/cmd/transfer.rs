@@ -0,0 +1,240 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    config::SignerConfig,
+    display_dry_run_result_warning,
+    display_extrinsic_receipt,
+    parse_account,
+    parse_balance,
+    prompt_confirm_tx,
+    CLIExtrinsicOpts,
+    TxContext,
+};
+use anyhow::{
+    anyhow,
+    Result,
+};
+use contract_build::name_value_println;
+use contract_extrinsics::{
+    DisplayEvents,
+    ExtrinsicOptsBuilder,
+    TransferCommandBuilder,
+    TransferExec,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    tx::Signer,
+    Config,
+};
+
+/// Transfers a value to an account with `Balances::transfer_keep_alive`, e.g. to fund
+/// a freshly derived deployment account.
+#[derive(Debug, clap::Args)]
+#[clap(name = "transfer")]
+pub struct TransferCommand {
+    /// The account to transfer the value to.
+    #[clap(long)]
+    to: String,
+    /// The value to transfer, either raw or denominated, e.g. `10 UNIT`.
+    #[clap(long)]
+    value: String,
+    #[clap(flatten)]
+    extrinsic_cli_opts: CLIExtrinsicOpts,
+    /// Export the transfer output as JSON.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+}
+
+impl TransferCommand {
+    /// Returns whether to export the transfer output in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        if self.extrinsic_cli_opts.execute {
+            self.extrinsic_cli_opts.ensure_can_submit()?;
+        }
+        call_with_config!(
+            self,
+            run,
+            self.extrinsic_cli_opts.chain_cli_opts.chain().config()
+        )
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Display,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let dest = parse_account(&self.to)
+            .map_err(|e| anyhow!("Failed to parse to option: {}", e))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
+        let value = parse_balance(&self.value, &token_metadata)
+            .map_err(|e| anyhow!("Failed to parse value option: {}", e))?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
+            .url(chain.url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(&token_metadata)?)
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider())
+            .progress(self.extrinsic_cli_opts.progress()?)
+            .done();
+        let transfer_exec: TransferExec<C, C, _> =
+            TransferCommandBuilder::new(dest, value, extrinsic_opts)
+                .done()
+                .await?;
+
+        if !self.extrinsic_cli_opts.execute {
+            if !self.output_json {
+                self.print_preview(&transfer_exec);
+                display_dry_run_result_warning("transfer");
+            }
+            return Ok(())
+        }
+        if !self.extrinsic_cli_opts.skip_confirm {
+            let context = TxContext::query::<C>(
+                &chain.url(),
+                self.extrinsic_cli_opts.chain_cli_opts.rpc_options(),
+                &transfer_exec.opts().signer().account_id(),
+                transfer_exec.estimate_fee(),
+                &token_metadata,
+            )
+            .await?;
+            prompt_confirm_tx(&context, || self.print_preview(&transfer_exec))?;
+        }
+        let result = transfer_exec.transfer().await?;
+        let metadata = transfer_exec.client().metadata();
+        let display_events = DisplayEvents::from_events::<C, C>(
+            &result.events,
+            None,
+            &metadata,
+            self.extrinsic_cli_opts
+                .chain_cli_opts
+                .ss58_prefix_for(Some(&metadata)),
+        )?
+        .filter(&self.extrinsic_cli_opts.event_filter());
+
+        if self.output_json {
+            let json_object = serde_json::json!({
+                "events": display_events,
+                "extrinsic": result.receipt,
+            });
+            println!("{}", serde_json::to_string_pretty(&json_object)?);
+        } else {
+            println!(
+                "{}",
+                display_events.display_events::<C>(
+                    self.extrinsic_cli_opts.verbosity()?,
+                    &token_metadata,
+                )?
+            );
+            display_extrinsic_receipt(&result.receipt);
+        }
+        Ok(())
+    }
+
+    fn print_preview<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        transfer_exec: &TransferExec<C, C, C::Signer>,
+    ) where
+        <C as Config>::AccountId: Display,
+    {
+        name_value_println!(
+            "From",
+            transfer_exec.opts().signer().account_id().to_string()
+        );
+        name_value_println!("To", transfer_exec.dest().to_string());
+        name_value_println!("Value", self.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        transfer: TransferCommand,
+    }
+
+    #[test]
+    fn transfer_requires_destination_and_value() {
+        let err = Cli::try_parse_from(["transfer", "--suri", "//Alice", "--value", "1"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+
+        let cli = Cli::try_parse_from([
+            "transfer",
+            "--suri",
+            "//Alice",
+            "--to",
+            "//Bob",
+            "--value",
+            "10 UNIT",
+        ])
+        .unwrap();
+        assert_eq!(cli.transfer.to, "//Bob");
+        assert_eq!(cli.transfer.value, "10 UNIT");
+        assert!(!cli.transfer.extrinsic_cli_opts.execute);
+    }
+}
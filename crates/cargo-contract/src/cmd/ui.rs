@@ -0,0 +1,650 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An interactive dashboard of a contract in the terminal.
+//!
+//! Apart from the options and the address book of the CLI, the dashboard only uses the
+//! public API of `contract-lib`, so that it shows what third-party tools can build on.
+
+use super::{
+    CLIChainOpts,
+    Suri,
+};
+use crate::ErrorVariant;
+use std::path::PathBuf;
+
+#[cfg(feature = "ui")]
+use super::{
+    address_book::contract_addresses,
+    config::SignerConfig,
+    parse_account,
+    Chain,
+};
+#[cfg(feature = "ui")]
+use crate::call_with_config;
+#[cfg(feature = "ui")]
+use anyhow::Result;
+#[cfg(feature = "ui")]
+use contract_lib::{
+    artifacts,
+    extrinsics::{
+        self,
+        CallCommandBuilder,
+        ContractsBlock,
+        EventRecord,
+    },
+};
+#[cfg(feature = "ui")]
+use crossterm::{
+    cursor::{
+        self,
+        MoveTo,
+    },
+    event::{
+        self,
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyEventKind,
+        KeyModifiers,
+    },
+    execute,
+    queue,
+    style::{
+        Print,
+        PrintStyledContent,
+        Stylize,
+    },
+    terminal::{
+        self,
+        Clear,
+        ClearType,
+        EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+#[cfg(feature = "ui")]
+use ink_env::Environment;
+#[cfg(feature = "ui")]
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    io::{
+        self,
+        Stdout,
+        Write,
+    },
+    path::Path,
+    slice,
+    str::FromStr,
+    thread,
+};
+#[cfg(feature = "ui")]
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    Config,
+};
+#[cfg(feature = "ui")]
+use tokio::sync::mpsc::{
+    self,
+    UnboundedReceiver,
+};
+
+/// How many of the latest events of contracts the dashboard keeps.
+#[cfg(feature = "ui")]
+const MAX_EVENTS: usize = 200;
+
+/// The key bindings shown at the bottom of the dashboard.
+#[cfg(feature = "ui")]
+const HELP: &str = "tab: switch list  up/down: select  enter: dry-run  q: quit";
+
+/// Opens an interactive dashboard of a contract in the terminal.
+///
+/// It shows the chain, the artifacts of the contract, its instances in the address book
+/// and the live events of contracts, and dry-runs the messages without arguments of the
+/// instances.
+#[derive(Debug, clap::Args)]
+#[clap(name = "ui")]
+pub struct UiCommand {
+    /// Path to a `.contract` bundle or `.json` metadata file, instead of the artifacts
+    /// of the contract project.
+    #[clap(value_parser, conflicts_with = "manifest_path")]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Secret key URI for the account the dry-runs are performed with.
+    #[clap(
+        name = "suri",
+        long,
+        short,
+        env = "CARGO_CONTRACT_SURI",
+        hide_env_values = true,
+        default_value = "//Alice"
+    )]
+    suri: Suri,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl UiCommand {
+    #[cfg(not(feature = "ui"))]
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        Err(anyhow::anyhow!(
+            "cargo-contract was installed without the `ui` feature, reinstall it with \
+            `cargo install cargo-contract --features ui` to use `cargo contract ui`"
+        )
+        .into())
+    }
+
+    #[cfg(feature = "ui")]
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+}
+
+#[cfg(feature = "ui")]
+impl UiCommand {
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        <C as Environment>::Balance: Default + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let artifacts = match &self.file {
+            Some(file) => artifacts::load(file)?,
+            None => artifacts::load_from_manifest(self.manifest_path.as_deref())?,
+        };
+        let metadata = artifacts.metadata()?;
+        let transcoder = artifacts.contract_transcoder()?;
+        let signer = self.suri.signer::<C>()?;
+
+        let chain = self.chain_cli_opts.chain();
+        let options = self.chain_cli_opts.rpc_options();
+        let url = extrinsics::url_to_string(&chain.url());
+        let rpc = extrinsics::rpc_client(&url, options.clone()).await?;
+        let client = extrinsics::online_client::<C>(rpc, options).await?;
+        let ss58_prefix = self.chain_cli_opts.ss58_prefix_for(Some(&client.metadata()));
+        let transcoder = transcoder.with_ss58_prefix(ss58_prefix);
+        let mut blocks = client.blocks().subscribe_best().await?;
+
+        let mut dashboard = Dashboard {
+            chain: match &chain {
+                Chain::Production(chain) => chain.to_string(),
+                Chain::Custom(url, config) => {
+                    format!("{} ({config})", extrinsics::url_to_string(url))
+                }
+            },
+            contract: format!("{} {}", metadata.contract.name, metadata.contract.version),
+            code_hash: metadata.source.hash.to_string(),
+            instances: contract_addresses(&chain)?.into_iter().collect(),
+            messages: transcoder
+                .metadata()
+                .spec()
+                .messages()
+                .iter()
+                .filter(|message| message.args().is_empty())
+                .map(|message| message.label().clone())
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut terminal = Terminal::enter()?;
+        let mut events = read_events();
+        let mut subscribed = true;
+        loop {
+            terminal.draw(&dashboard)?;
+            tokio::select! {
+                event = events.recv() => {
+                    let Some(event) = event else { break };
+                    match dashboard.handle_event(event) {
+                        Some(Action::Quit) => break,
+                        Some(Action::DryRun { contract, message }) => {
+                            dashboard.status = format!("Dry-running `{message}`...");
+                            terminal.draw(&dashboard)?;
+                            dashboard.status = self
+                                .dry_run::<C>(
+                                    &signer,
+                                    artifacts.artifact_path(),
+                                    &contract,
+                                    &message,
+                                )
+                                .await
+                                .unwrap_or_else(|err| {
+                                    format!("Dry-run of `{message}` failed: {err:#}")
+                                });
+                        }
+                        None => {}
+                    }
+                }
+                block = blocks.next(), if subscribed => {
+                    match block {
+                        Some(Ok(block)) => {
+                            let number: u64 = block.number().into();
+                            dashboard.best_block = Some(number);
+                            match extrinsics::inspect_block(
+                                &client,
+                                block.hash(),
+                                slice::from_ref(&transcoder),
+                                ss58_prefix,
+                            )
+                            .await
+                            {
+                                Ok(block) => dashboard.push_events(block),
+                                Err(err) => {
+                                    dashboard.status = format!(
+                                        "Failed to inspect block #{number}: {err:#}"
+                                    )
+                                }
+                            }
+                        }
+                        Some(Err(err)) => {
+                            dashboard.status = format!("Failed to fetch a block: {err}")
+                        }
+                        None => {
+                            subscribed = false;
+                            dashboard.status =
+                                "The subscription to the blocks ended".to_string();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Dry-runs the `message` of the `contract` and returns its decoded result.
+    async fn dry_run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        signer: &C::Signer,
+        artifact: &Path,
+        contract: &str,
+        message: &str,
+    ) -> Result<String>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        <C as Environment>::Balance: Default + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let opts = extrinsics::opts::<C, C, _>(signer.clone())
+            .file(Some(artifact))
+            .url(self.chain_cli_opts.chain().url())
+            .rpc_options(self.chain_cli_opts.rpc_options())
+            .ss58_prefix(self.chain_cli_opts.ss58_prefix())
+            .done();
+        let call_exec = CallCommandBuilder::new(parse_account(contract)?, message, opts)
+            .done()
+            .await?;
+        let result = call_exec.call_dry_run().await?;
+        match result.result {
+            Ok(ret_val) => {
+                let value = call_exec.decode_return(&ret_val.data)?;
+                let reverted = if ret_val.did_revert() { " (reverted)" } else { "" };
+                Ok(format!("{message}: {value}{reverted}"))
+            }
+            Err(err) => {
+                let err = extrinsics::ErrorVariant::from_dispatch_error(
+                    &err,
+                    &call_exec.client().metadata(),
+                )?;
+                Ok(format!("{message}: {err}"))
+            }
+        }
+    }
+}
+
+/// The list of the dashboard the selection keys apply to.
+#[cfg(feature = "ui")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Focus {
+    #[default]
+    Instances,
+    Messages,
+}
+
+/// An action requested by a key press.
+#[cfg(feature = "ui")]
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Quit,
+    DryRun { contract: String, message: String },
+}
+
+/// How a line of the dashboard is displayed.
+#[cfg(feature = "ui")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Header,
+    Selected,
+}
+
+/// A line of the dashboard.
+#[cfg(feature = "ui")]
+#[derive(Debug, PartialEq, Eq)]
+struct Line {
+    text: String,
+    style: Style,
+}
+
+#[cfg(feature = "ui")]
+impl Line {
+    fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            style: Style::Plain,
+        }
+    }
+
+    fn header(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            style: Style::Header,
+        }
+    }
+}
+
+/// The state of the dashboard.
+#[cfg(feature = "ui")]
+#[derive(Debug, Default)]
+struct Dashboard {
+    chain: String,
+    contract: String,
+    code_hash: String,
+    /// The names and addresses of the instances in the address book.
+    instances: Vec<(String, String)>,
+    /// The messages without arguments, which can be dry-run.
+    messages: Vec<String>,
+    focus: Focus,
+    instance: usize,
+    message: usize,
+    best_block: Option<u64>,
+    /// The latest events of contracts, oldest first.
+    events: VecDeque<String>,
+    status: String,
+}
+
+#[cfg(feature = "ui")]
+impl Dashboard {
+    /// Updates the selection for the `event`, returns the action it requests if any.
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return None
+        };
+        let (selected, len) = match self.focus {
+            Focus::Instances => (&mut self.instance, self.instances.len()),
+            Focus::Messages => (&mut self.message, self.messages.len()),
+        };
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Action::Quit)
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Instances => Focus::Messages,
+                    Focus::Messages => Focus::Instances,
+                };
+            }
+            KeyCode::Up => *selected = selected.saturating_sub(1),
+            KeyCode::Down => *selected = (*selected + 1).min(len.saturating_sub(1)),
+            KeyCode::Enter => {
+                let (Some((_, contract)), Some(message)) = (
+                    self.instances.get(self.instance),
+                    self.messages.get(self.message),
+                ) else {
+                    self.status =
+                        "Select an instance and a message to dry-run".to_string();
+                    return None
+                };
+                return Some(Action::DryRun {
+                    contract: contract.clone(),
+                    message: message.clone(),
+                })
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Appends the events of contracts of the `block`.
+    fn push_events(&mut self, block: ContractsBlock) {
+        let events = block
+            .extrinsics
+            .iter()
+            .flat_map(|extrinsic| &extrinsic.events)
+            .chain(&block.events);
+        for event in events {
+            self.events.push_back(event_line(block.number, event));
+        }
+        while self.events.len() > MAX_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    /// Returns the lines of the dashboard for a terminal `height` rows high.
+    ///
+    /// The latest events fill the rows left by the other sections.
+    fn lines(&self, height: usize) -> Vec<Line> {
+        let best_block = self
+            .best_block
+            .map_or_else(|| "-".to_string(), |number| format!("#{number}"));
+        let mut lines = vec![
+            Line::header(format!(
+                "cargo contract ui | {} | best block {best_block}",
+                self.chain
+            )),
+            Line::plain(""),
+            Line::header("Contract"),
+            Line::plain(format!("  {}", self.contract)),
+            Line::plain(format!("  Code hash {}", self.code_hash)),
+            Line::plain(""),
+            Line::header("Instances"),
+        ];
+        if self.instances.is_empty() {
+            lines.push(Line::plain(
+                "  None, add one with `cargo contract address-book add <NAME> <ADDRESS>`",
+            ));
+        }
+        for (index, (name, address)) in self.instances.iter().enumerate() {
+            lines.push(self.item(
+                Focus::Instances,
+                index == self.instance,
+                format!("@{name} {address}"),
+            ));
+        }
+        lines.push(Line::plain(""));
+        lines.push(Line::header("Dry-run"));
+        if self.messages.is_empty() {
+            lines.push(Line::plain("  No messages without arguments"));
+        }
+        for (index, message) in self.messages.iter().enumerate() {
+            lines.push(self.item(Focus::Messages, index == self.message, message));
+        }
+        lines.push(Line::plain(""));
+        lines.push(Line::header("Events"));
+
+        let rows = height.saturating_sub(2);
+        let skip = self
+            .events
+            .len()
+            .saturating_sub(rows.saturating_sub(lines.len()));
+        lines.extend(self.events.iter().skip(skip).map(Line::plain));
+        lines.truncate(rows);
+        lines.resize_with(rows, || Line::plain(""));
+        lines.push(Line::plain(self.status.clone()));
+        lines.push(Line::plain(HELP));
+        lines
+    }
+
+    /// Returns the line of an item of the `list`, highlighted if the list has the focus.
+    fn item(&self, list: Focus, selected: bool, text: impl Display) -> Line {
+        if !selected {
+            return Line::plain(format!("  {text}"))
+        }
+        Line {
+            text: format!("> {text}"),
+            style: if self.focus == list {
+                Style::Selected
+            } else {
+                Style::Plain
+            },
+        }
+    }
+}
+
+/// Returns the line of the `event` emitted in the block with the `number`.
+#[cfg(feature = "ui")]
+fn event_line(number: u64, event: &EventRecord) -> String {
+    let fields = event
+        .fields
+        .iter()
+        .map(|field| format!("{}: {}", field.name, field.value))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("  #{number} {}::{} {fields}", event.pallet, event.variant)
+}
+
+/// Reads the events of the terminal on a thread of its own, as reading them blocks.
+#[cfg(feature = "ui")]
+fn read_events() -> UnboundedReceiver<Event> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if sender.send(event).is_err() {
+                break
+            }
+        }
+    });
+    receiver
+}
+
+/// The terminal in raw mode showing the alternate screen, restored when dropped.
+#[cfg(feature = "ui")]
+struct Terminal(Stdout);
+
+#[cfg(feature = "ui")]
+impl Terminal {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self(stdout))
+    }
+
+    fn draw(&mut self, dashboard: &Dashboard) -> Result<()> {
+        let (width, height) = terminal::size()?;
+        queue!(self.0, Clear(ClearType::All))?;
+        for (row, line) in (0..height).zip(dashboard.lines(height.into())) {
+            let text: String = line.text.chars().take(width.into()).collect();
+            queue!(self.0, MoveTo(0, row))?;
+            match line.style {
+                Style::Plain => queue!(self.0, Print(text))?,
+                Style::Header => queue!(self.0, PrintStyledContent(text.bold()))?,
+                Style::Selected => queue!(self.0, PrintStyledContent(text.reverse()))?,
+            }
+        }
+        self.0.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ui")]
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.0, LeaveAlternateScreen, cursor::Show);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[cfg(all(test, feature = "ui"))]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn dashboard() -> Dashboard {
+        Dashboard {
+            instances: vec![
+                ("flipper".to_string(), "5Flipper".to_string()),
+                ("other".to_string(), "5Other".to_string()),
+            ],
+            messages: vec!["get".to_string(), "owner".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn selects_the_instance_and_message_to_dry_run() {
+        let mut dashboard = dashboard();
+
+        assert_eq!(dashboard.handle_event(key(KeyCode::Down)), None);
+        assert_eq!(dashboard.handle_event(key(KeyCode::Down)), None);
+        assert_eq!(dashboard.handle_event(key(KeyCode::Tab)), None);
+        assert_eq!(dashboard.handle_event(key(KeyCode::Down)), None);
+
+        assert_eq!(
+            dashboard.handle_event(key(KeyCode::Enter)),
+            Some(Action::DryRun {
+                contract: "5Other".to_string(),
+                message: "owner".to_string(),
+            })
+        );
+        assert_eq!(
+            dashboard.handle_event(key(KeyCode::Char('q'))),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn latest_events_fill_the_remaining_rows() {
+        let mut dashboard = dashboard();
+        dashboard.events = (0..MAX_EVENTS).map(|i| format!("event {i}")).collect();
+        dashboard.status = "status".to_string();
+
+        let lines = dashboard.lines(24);
+
+        assert_eq!(lines.len(), 24);
+        assert_eq!(lines[7].text, "> @flipper 5Flipper");
+        assert_eq!(lines[7].style, Style::Selected);
+        assert_eq!(lines[11].text, "> get");
+        assert_eq!(lines[11].style, Style::Plain);
+        assert_eq!(lines[21].text, format!("event {}", MAX_EVENTS - 1));
+        assert_eq!(lines[22].text, "status");
+        assert_eq!(lines[23].text, HELP);
+    }
+}
@@ -29,16 +29,20 @@ use std::{
 use super::{
     config::SignerConfig,
     display_dry_run_result_warning,
+    display_extrinsic_receipt,
+    ensure_sudo_key,
     parse_balance,
     prompt_confirm_unverifiable_upload,
     CLIExtrinsicOpts,
+    ExplorerLinks,
 };
 use anyhow::Result;
 use contract_build::name_value_println;
 use contract_extrinsics::{
+    call_wrapper,
+    Determinism,
     DisplayEvents,
     ExtrinsicOptsBuilder,
-    TokenMetadata,
     UploadCommandBuilder,
     UploadExec,
 };
@@ -53,6 +57,7 @@ use subxt::{
         scale_decode::IntoVisitor,
         scale_encode::EncodeAsType,
     },
+    tx::Signer as _,
     Config,
 };
 
@@ -64,6 +69,25 @@ pub struct UploadCommand {
     /// Export the call output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
+    /// Whether the code must be deterministic: `enforced` or `relaxed`.
+    ///
+    /// Code uploaded with `relaxed` may use indeterministic instructions, like floating
+    /// point arithmetic, and can only be executed off-chain. It can't be instantiated or
+    /// called on chain.
+    #[clap(long, default_value_t, value_parser = Determinism::from_str)]
+    determinism: Determinism,
+    /// Upload the code without its contract metadata, e.g. a bare `.wasm` file
+    /// downloaded from a registry.
+    ///
+    /// The check of the contract environment types against the node is skipped.
+    #[clap(long)]
+    skip_metadata_check: bool,
+    /// Dispatch the upload as the signer through `sudo.sudo_as`, e.g. on development
+    /// chains only allowing privileged origins to upload code.
+    ///
+    /// The signer must be the sudo key of the chain.
+    #[clap(long)]
+    sudo: bool,
 }
 
 impl UploadCommand {
@@ -73,6 +97,9 @@ impl UploadCommand {
     }
 
     pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        if self.extrinsic_cli_opts.execute {
+            self.extrinsic_cli_opts.ensure_can_submit()?;
+        }
         call_with_config!(
             self,
             run,
@@ -84,10 +111,10 @@ impl UploadCommand {
         &self,
     ) -> Result<(), ErrorVariant>
     where
-        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Display,
         <<C as Config>::AccountId as FromStr>::Err: Display,
         C::Balance: Into<u128>
-            + From<u128>
+            + TryFrom<u128>
             + Display
             + Default
             + FromStr
@@ -98,10 +125,14 @@ impl UploadCommand {
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
         <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
     {
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let account_id = signer.account_id();
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
-        let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
         let storage_deposit_limit = self
             .extrinsic_cli_opts
             .storage_deposit_limit
@@ -111,15 +142,38 @@ impl UploadCommand {
             .map_err(|e| {
                 anyhow::anyhow!("Failed to parse storage_deposit_limit option: {}", e)
             })?;
-        let extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
+        let mut extrinsic_opts = ExtrinsicOptsBuilder::new(signer)
             .file(self.extrinsic_cli_opts.file.clone())
             .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
             .url(chain.url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
             .storage_deposit_limit(storage_deposit_limit)
-            .done();
+            .unlimited_deposit(self.extrinsic_cli_opts.unlimited_deposit())
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(&token_metadata)?)
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider())
+            .progress(self.extrinsic_cli_opts.progress()?);
+        if self.sudo {
+            // `pallet-contracts` requires a signed origin, the root origin is rejected
+            extrinsic_opts = extrinsic_opts.wrap_call(call_wrapper::sudo_as(&account_id));
+        }
+        let extrinsic_opts = extrinsic_opts.done();
 
-        let upload_exec: UploadExec<C, C, _> =
-            UploadCommandBuilder::new(extrinsic_opts).done().await?;
+        let upload_exec: UploadExec<C, C, _> = UploadCommandBuilder::new(extrinsic_opts)
+            .determinism(self.determinism)
+            .skip_metadata_check(self.skip_metadata_check)
+            .done()
+            .await?;
+        if self.determinism == Determinism::Relaxed {
+            contract_build::output::warning(
+                "The code is uploaded with relaxed determinism. It can only be executed \
+                 off-chain and can't be instantiated or called on chain.",
+            );
+        }
         let code_hash = upload_exec.code().code_hash();
         let metadata = upload_exec.client().metadata();
 
@@ -153,12 +207,23 @@ impl UploadCommand {
                     prompt_confirm_unverifiable_upload(&chain.to_string())?
                 }
             }
+            if self.sudo {
+                ensure_sudo_key(
+                    upload_exec.client(),
+                    &upload_exec.opts().signer().account_id(),
+                )
+                .await?;
+            }
             let upload_result = upload_exec.upload_code().await?;
             let display_events = DisplayEvents::from_events::<C, C>(
                 &upload_result.events,
                 None,
                 &metadata,
-            )?;
+                self.extrinsic_cli_opts
+                    .chain_cli_opts
+                    .ss58_prefix_for(Some(&metadata)),
+            )?
+            .filter(&self.extrinsic_cli_opts.event_filter());
             let output_events = if self.output_json() {
                 display_events.to_json()?
             } else {
@@ -167,6 +232,12 @@ impl UploadCommand {
                     &token_metadata,
                 )?
             };
+            let explorer_links = ExplorerLinks::new(
+                &self.extrinsic_cli_opts,
+                &format!("{:?}", upload_result.receipt.extrinsic_hash),
+                &format!("{:?}", upload_result.receipt.block_hash),
+                None,
+            );
             if let Some(code_stored) = upload_result.code_stored {
                 let code_hash: <C as Config>::Hash = code_stored.code_hash;
                 if self.output_json() {
@@ -174,11 +245,15 @@ impl UploadCommand {
                     let json_object = serde_json::json!({
                         "events": serde_json::from_str::<serde_json::Value>(&output_events)?,
                         "code_hash": code_hash,
+                        "extrinsic": upload_result.receipt,
+                        "explorer_links": explorer_links,
                     });
                     println!("{}", serde_json::to_string_pretty(&json_object)?);
                 } else {
                     println!("{}", output_events);
                     name_value_println!("Code hash", format!("{:?}", code_hash));
+                    display_extrinsic_receipt(&upload_result.receipt);
+                    explorer_links.print();
                 }
             } else {
                 let code_hash = hex::encode(code_hash);
@@ -0,0 +1,211 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use hyper::{
+    header::{
+        AUTHORIZATION,
+        CONTENT_TYPE,
+    },
+    Body,
+    Client,
+    Request,
+};
+use std::future::Future;
+use url::Url;
+
+/// Default URL of the HTTP API of a local IPFS node.
+pub const DEFAULT_IPFS_API: &str = "http://127.0.0.1:5001";
+
+/// Content published by an [`Uploader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Published {
+    /// The identifier of the content assigned by the service, e.g. the IPFS CID.
+    pub id: String,
+    /// The URL the content can be retrieved from.
+    pub url: String,
+}
+
+/// A service contract artifacts can be published to.
+pub trait Uploader {
+    /// Uploads `content` as a file named `name`.
+    fn upload(
+        &self,
+        name: &str,
+        content: Vec<u8>,
+    ) -> impl Future<Output = Result<Published>>;
+}
+
+/// Uploads to an IPFS node or pinning service via the `/api/v0/add` endpoint of the
+/// IPFS HTTP API, pinning the content.
+pub struct IpfsUploader {
+    api: Url,
+    auth: Option<String>,
+}
+
+impl IpfsUploader {
+    /// Creates an uploader for the IPFS HTTP API at `api`.
+    ///
+    /// `auth` is sent as the `Authorization` header, as required by most pinning
+    /// services.
+    pub fn new(mut api: Url, auth: Option<String>) -> Self {
+        // the endpoints are joined to the path of the API, e.g. of `https://host/ipfs`,
+        // which would replace its last segment without a trailing slash
+        if !api.path().ends_with('/') {
+            api.set_path(&format!("{}/", api.path()));
+        }
+        Self { api, auth }
+    }
+
+    /// Returns the URL of the `/api/v0/add` endpoint, pinning the added content.
+    fn add_url(&self) -> Result<Url> {
+        let mut url = self.api.join("api/v0/add")?;
+        url.query_pairs_mut().append_pair("pin", "true");
+        Ok(url)
+    }
+}
+
+impl Uploader for IpfsUploader {
+    async fn upload(&self, name: &str, content: Vec<u8>) -> Result<Published> {
+        let url = self.add_url()?;
+
+        let boundary = format!(
+            "cargo-contract-{}",
+            hex::encode(contract_build::code_hash(&content))
+        );
+        let mut request = Request::post(url.as_str()).header(
+            CONTENT_TYPE,
+            format!("multipart/form-data; boundary={boundary}"),
+        );
+        if let Some(auth) = &self.auth {
+            request = request.header(AUTHORIZATION, auth);
+        }
+        let request =
+            request.body(Body::from(multipart_body(&boundary, name, &content)))?;
+
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let response = Client::builder()
+            .build::<_, Body>(connector)
+            .request(request)
+            .await
+            .with_context(|| {
+                format!("Failed to connect to the IPFS API at {}", self.api)
+            })?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        if !status.is_success() {
+            anyhow::bail!(
+                "Uploading to IPFS failed with status {status}: {}",
+                String::from_utf8_lossy(&body)
+            )
+        }
+        parse_add_response(&body)
+    }
+}
+
+/// The response of the `/api/v0/add` endpoint.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AddResponse {
+    hash: String,
+}
+
+fn parse_add_response(body: &[u8]) -> Result<Published> {
+    let response: AddResponse = serde_json::from_slice(body)
+        .context("Failed to parse the response of the IPFS API")?;
+    Ok(Published {
+        url: format!("ipfs://{}", response.hash),
+        id: response.hash,
+    })
+}
+
+/// Encodes `content` as the single file of a `multipart/form-data` body.
+fn multipart_body(boundary: &str, name: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{boundary}\r\n\
+        Content-Disposition: form-data; name=\"file\"; filename=\"{name}\"\r\n\
+        Content-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_body_contains_file() {
+        let body = multipart_body("b", "flipper.contract", b"{}");
+
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--b\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"flipper.contract\"\r\n\
+            Content-Type: application/octet-stream\r\n\r\n\
+            {}\r\n\
+            --b--\r\n"
+        );
+    }
+
+    #[test]
+    fn add_url_keeps_path_of_api() {
+        let add_url = |api: &str| {
+            IpfsUploader::new(api.parse().unwrap(), None)
+                .add_url()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            add_url(DEFAULT_IPFS_API),
+            "http://127.0.0.1:5001/api/v0/add?pin=true"
+        );
+        assert_eq!(
+            add_url("https://pinning.example.com/ipfs"),
+            "https://pinning.example.com/ipfs/api/v0/add?pin=true"
+        );
+        assert_eq!(
+            add_url("https://pinning.example.com/ipfs/"),
+            "https://pinning.example.com/ipfs/api/v0/add?pin=true"
+        );
+    }
+
+    #[test]
+    fn add_response_is_parsed() {
+        let published = parse_add_response(
+            br#"{"Name":"flipper.contract","Hash":"QmHash","Size":"42"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            published,
+            Published {
+                id: "QmHash".to_string(),
+                url: "ipfs://QmHash".to_string(),
+            }
+        );
+    }
+}
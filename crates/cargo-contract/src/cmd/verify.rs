@@ -14,11 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
+use super::progress;
 use anyhow::{
     Context,
     Result,
 };
 use colored::Colorize;
+use contract_analyze::metadata_hash;
 use contract_build::{
     code_hash,
     execute,
@@ -92,6 +94,15 @@ impl VerifyCommand {
             .context(format!("Failed to read contract binary {}", path.display()))?;
 
         let reference_code_hash = CodeHash(code_hash(&ref_buffer));
+        // The hash of the ABI is only embedded into the code if the metadata is built
+        // along with it.
+        let reference_metadata_hash = metadata_hash(&ref_buffer)?;
+        let embed_metadata_hash = reference_metadata_hash.is_some();
+        let build_artifact = if embed_metadata_hash {
+            BuildArtifacts::All
+        } else {
+            BuildArtifacts::CodeOnly
+        };
 
         // 2. Call `cargo contract build` in the release mode.
         let args = ExecuteArgs {
@@ -99,8 +110,10 @@ impl VerifyCommand {
             verbosity,
             optimization_passes: Some(contract_build::OptimizationPasses::Z),
             build_mode: BuildMode::Release,
-            build_artifact: BuildArtifacts::CodeOnly,
+            build_artifact,
+            embed_metadata_hash,
             extra_lints: false,
+            progress: progress::reporter(&verbosity),
             ..Default::default()
         };
 
@@ -142,6 +155,7 @@ impl VerifyCommand {
             image: None,
             contract: built_wasm_path.display().to_string(),
             reference_contract: path.display().to_string(),
+            metadata_hash: reference_metadata_hash.map(hex_hash),
             output_json: self.output_json,
             verbosity,
         })
@@ -158,9 +172,10 @@ impl VerifyCommand {
         let file = File::open(path)
             .context(format!("Failed to open contract bundle {}", path.display()))?;
 
-        let metadata: ContractMetadata = serde_json::from_reader(&file).context(
+        let mut metadata: ContractMetadata = serde_json::from_reader(&file).context(
             format!("Failed to deserialize contract bundle {}", path.display()),
         )?;
+        let reference_metadata_hash = verify_metadata_hash(&mut metadata)?;
         let build_info = if let Some(info) = metadata.source.build_info {
             info
         } else {
@@ -232,9 +247,15 @@ impl VerifyCommand {
             build_mode,
             build_artifact: BuildArtifacts::All,
             optimization_passes: Some(build_info.wasm_opt_settings.optimization_passes),
+            wasm_opt_passes: build_info.wasm_opt_settings.passes.clone(),
             keep_debug_symbols: build_info.wasm_opt_settings.keep_debug_symbols,
+            features: build_info.features.clone(),
+            build_flags: build_info.build_flags.clone(),
+            debug_artifacts: build_info.debug_artifacts,
+            embed_metadata_hash: build_info.embed_metadata_hash,
             image: ImageVariant::from(metadata.image.clone()),
             extra_lints: false,
+            progress: progress::reporter(&verbosity),
             ..Default::default()
         };
 
@@ -290,12 +311,38 @@ impl VerifyCommand {
             image: metadata.image,
             contract: target_bundle.display().to_string(),
             reference_contract: path.display().to_string(),
+            metadata_hash: reference_metadata_hash.map(hex_hash),
             output_json: self.output_json,
             verbosity,
         })
     }
 }
 
+/// Checks that the hash of the ABI embedded into the Wasm code of the bundle, if any,
+/// matches the ABI in the bundle, and returns it.
+fn verify_metadata_hash(metadata: &mut ContractMetadata) -> Result<Option<[u8; 32]>> {
+    metadata.decompress_wasm()?;
+    let Some(wasm) = &metadata.source.wasm else {
+        return Ok(None)
+    };
+    let Some(embedded) = metadata_hash(&wasm.0)? else {
+        return Ok(None)
+    };
+    let abi_hash = metadata.abi_hash()?;
+    anyhow::ensure!(
+        embedded == abi_hash,
+        "The ABI of the bundle does not match the hash {} embedded into its code, \
+        found {}",
+        hex_hash(embedded),
+        hex_hash(abi_hash)
+    );
+    Ok(Some(embedded))
+}
+
+fn hex_hash(hash: [u8; 32]) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
 /// The result of verification process
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct VerificationResult {
@@ -303,6 +350,9 @@ pub struct VerificationResult {
     pub image: Option<String>,
     pub contract: String,
     pub reference_contract: String,
+    /// The hash of the ABI embedded into the code of the verified contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_hash: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
     pub output_json: bool,
     #[serde(skip_serializing, skip_deserializing)]
@@ -312,12 +362,19 @@ pub struct VerificationResult {
 impl VerificationResult {
     /// Display the result in a fancy format
     pub fn display(&self) -> String {
-        format!(
+        let mut display = format!(
             "\n{} {} against reference contract {}",
             "Successfully verified contract".bright_green().bold(),
             format!("`{}`", &self.contract).bold(),
             format!("`{}`!", &self.reference_contract).bold()
-        )
+        );
+        if let Some(metadata_hash) = &self.metadata_hash {
+            display.push_str(&format!(
+                "\nThe code is linked to the ABI with the hash {}",
+                metadata_hash.bold()
+            ));
+        }
+        display
     }
 
     /// Display the build results in a pretty formatted JSON string.
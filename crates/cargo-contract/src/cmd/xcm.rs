@@ -0,0 +1,320 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    address_book::resolve_contract,
+    config::SignerConfig,
+    display_extrinsic_receipt,
+    parse_account,
+    parse_balance,
+    prompt_confirm_tx,
+    CLIExtrinsicOpts,
+    TxContext,
+};
+use anyhow::{
+    anyhow,
+    Result,
+};
+use colored::Colorize;
+use contract_build::{
+    name_value_println,
+    util::DEFAULT_KEY_COL_WIDTH,
+};
+use contract_extrinsics::{
+    CallCommandBuilder,
+    DisplayEvents,
+    ExtrinsicOptsBuilder,
+    OriginKind,
+    TokenMetadata,
+    XcmLocation,
+    XcmSender,
+    XcmTransact,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use sp_weights::Weight;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    tx::Signer as _,
+    Config,
+};
+
+/// Calls a contract from another chain with an XCM `Transact`.
+///
+/// The contract call is encoded for the chain of the contract at `--url`, and wrapped
+/// in an XCM message which buys its execution with the fee asset. The encoded
+/// destination and message are printed, e.g. to submit them with governance, unless
+/// `-x` sends them with the `send` extrinsic of the XCM pallet of the chain at
+/// `--origin-url`.
+#[derive(Debug, clap::Args)]
+#[clap(name = "xcm")]
+pub struct XcmCommand {
+    /// The address of the the contract to call, or `@<name>` of the address book.
+    #[clap(name = "contract", long, env = "CONTRACT")]
+    contract: String,
+    /// The name of the contract message to call, or its selector e.g. `0x633aa551`.
+    #[clap(long, short)]
+    message: String,
+    /// The arguments of the contract message to call.
+    #[clap(long, num_args = 0..)]
+    args: Vec<String>,
+    #[clap(flatten)]
+    extrinsic_cli_opts: CLIExtrinsicOpts,
+    /// Maximum amount of gas (execution time) of the contract call.
+    /// If not specified will perform a dry-run to estimate the gas consumed for the
+    /// call.
+    #[clap(name = "gas", long, requires = "proof_size")]
+    gas_limit: Option<u64>,
+    /// Maximum proof size of the contract call.
+    /// If not specified will perform a dry-run to estimate the proof size required for
+    /// the call.
+    #[clap(long, requires = "gas")]
+    proof_size: Option<u64>,
+    /// The value to be transferred as part of the call.
+    #[clap(name = "value", long, default_value = "0")]
+    value: String,
+    /// The id of the parachain of the contract.
+    #[clap(long)]
+    para_id: u32,
+    /// Send the message from the relay chain instead of a sibling parachain.
+    #[clap(long)]
+    from_relay_chain: bool,
+    /// The location of the asset paying for the execution, as seen from the chain of the
+    /// contract, e.g. `..` for the token of the relay chain or `here` for its native
+    /// token.
+    #[clap(long)]
+    fee_asset: XcmLocation,
+    /// The amount of the fee asset withdrawn to pay for the execution, in its smallest
+    /// unit.
+    #[clap(long)]
+    fee_amount: u128,
+    /// The maximum execution time of the dispatched `Contracts::call` extrinsic,
+    /// including the gas limit of the contract call.
+    #[clap(long)]
+    transact_ref_time: u64,
+    /// The maximum proof size of the dispatched `Contracts::call` extrinsic, including
+    /// the proof size limit of the contract call.
+    #[clap(long)]
+    transact_proof_size: u64,
+    /// The origin the contract is called with: `native`, `sovereign-account`,
+    /// `superuser` or `xcm`.
+    #[clap(long, default_value_t)]
+    origin_kind: OriginKind,
+    /// The location the unused fees are deposited to, as seen from the chain of the
+    /// contract, e.g. `../Parachain(1000)` for the sovereign account of the sending
+    /// parachain. The unused fees are trapped otherwise.
+    #[clap(long)]
+    refund_to: Option<XcmLocation>,
+    /// Websockets url of a node of the chain sending the message, required to send it
+    /// with `-x`.
+    #[clap(long, required_if_eq("execute", "true"))]
+    origin_url: Option<url::Url>,
+}
+
+impl XcmCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        if self.extrinsic_cli_opts.execute {
+            self.extrinsic_cli_opts.ensure_can_submit()?;
+        }
+        call_with_config!(
+            self,
+            run,
+            self.extrinsic_cli_opts.chain_cli_opts.chain().config()
+        )
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Display,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance: TryFrom<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
+        let contract = parse_account(&resolve_contract(&self.contract, &chain)?)
+            .map_err(|e| anyhow!("Failed to parse contract option: {}", e))?;
+        let token_metadata = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .token_metadata::<C>()
+            .await?;
+        let value = parse_balance(&self.value, &token_metadata)
+            .map_err(|e| anyhow!("Failed to parse value option: {}", e))?;
+        // the extrinsic is submitted on the sending chain, so its tip is in its token
+        let origin_token_metadata = match &self.origin_url {
+            Some(origin_url) => TokenMetadata::query::<C>(origin_url).await?,
+            None => token_metadata.clone(),
+        };
+        let storage_deposit_limit = self
+            .extrinsic_cli_opts
+            .storage_deposit_limit
+            .clone()
+            .map(|b| parse_balance(&b, &token_metadata))
+            .transpose()
+            .map_err(|e| {
+                anyhow!("Failed to parse storage_deposit_limit option: {}", e)
+            })?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
+        let extrinsic_opts = ExtrinsicOptsBuilder::<C, C, _>::new(signer)
+            .file(self.extrinsic_cli_opts.file.clone())
+            .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
+            .url(chain.url())
+            .rpc_options(self.extrinsic_cli_opts.chain_cli_opts.rpc_options())
+            .storage_deposit_limit(storage_deposit_limit)
+            .unlimited_deposit(self.extrinsic_cli_opts.unlimited_deposit())
+            .ss58_prefix(self.extrinsic_cli_opts.chain_cli_opts.ss58_prefix())
+            .wait_for_finalization(self.extrinsic_cli_opts.finalization_timeout())
+            .submit_timeout(self.extrinsic_cli_opts.submit_timeout())
+            .submit_options(self.extrinsic_cli_opts.submit_options())
+            .mortality(self.extrinsic_cli_opts.tx_mortality)
+            .tip(self.extrinsic_cli_opts.tip(&origin_token_metadata)?)
+            .nonce_provider(self.extrinsic_cli_opts.nonce_provider())
+            .progress(self.extrinsic_cli_opts.progress()?)
+            .verbosity(self.extrinsic_cli_opts.verbosity()?)
+            .done();
+
+        let call_exec = CallCommandBuilder::new(contract, &self.message, extrinsic_opts)
+            .args(self.args.clone())
+            .value(value)
+            .done()
+            .await?;
+        let gas_limit = self
+            .gas_limit
+            .zip(self.proof_size)
+            .map(|(ref_time, proof_size)| Weight::from_parts(ref_time, proof_size));
+        let call = call_exec.encoded_call(gas_limit).await?;
+        let transact = XcmTransact {
+            dest: XcmLocation::parachain(self.para_id, self.from_relay_chain),
+            fee_asset: self.fee_asset.clone(),
+            fee_amount: self.fee_amount,
+            require_weight_at_most: Weight::from_parts(
+                self.transact_ref_time,
+                self.transact_proof_size,
+            ),
+            origin_kind: self.origin_kind,
+            refund_to: self.refund_to.clone(),
+        };
+
+        if !self.extrinsic_cli_opts.execute {
+            name_value_println!(
+                "Destination",
+                format!("0x{}", hex::encode(transact.versioned_dest())),
+                DEFAULT_KEY_COL_WIDTH
+            );
+            name_value_println!(
+                "Message",
+                format!("0x{}", hex::encode(transact.versioned_message(call))),
+                DEFAULT_KEY_COL_WIDTH
+            );
+            println!(
+                "{}",
+                "Message not sent. Use -x/--execute to send it from the chain at \
+                --origin-url."
+                    .bright_yellow()
+                    .bold()
+            );
+            return Ok(())
+        }
+
+        let origin_url = self.origin_url.as_ref().expect("required by clap");
+        // The fallback urls are the ones of the chain of the contract.
+        let rpc_options = self
+            .extrinsic_cli_opts
+            .chain_cli_opts
+            .rpc_options()
+            .with_fallback_urls(Vec::new());
+        let sender =
+            XcmSender::<C>::new(origin_url.as_str(), rpc_options.clone()).await?;
+        let opts = call_exec.opts();
+        if !self.extrinsic_cli_opts.skip_confirm {
+            let context = TxContext::query::<C>(
+                origin_url,
+                rpc_options,
+                &opts.signer().account_id(),
+                sender.estimate_fee(&transact, call.clone(), opts),
+                &origin_token_metadata,
+            )
+            .await?;
+            prompt_confirm_tx(&context, || {
+                name_value_println!(
+                    "Destination",
+                    transact.dest.to_string(),
+                    DEFAULT_KEY_COL_WIDTH
+                );
+                name_value_println!(
+                    "Contract",
+                    call_exec.contract().to_string(),
+                    DEFAULT_KEY_COL_WIDTH
+                );
+                name_value_println!(
+                    "Message",
+                    format!("{} {}", self.message, self.args.join(" ")),
+                    DEFAULT_KEY_COL_WIDTH
+                );
+                name_value_println!(
+                    "Fees",
+                    format!("{} of {}", self.fee_amount, transact.fee_asset),
+                    DEFAULT_KEY_COL_WIDTH
+                );
+            })?;
+        }
+        let result = sender.send(&transact, call, opts).await?;
+        let metadata = sender.metadata();
+        let display_events = DisplayEvents::from_events::<C, C>(
+            &result.events,
+            None,
+            &metadata,
+            self.extrinsic_cli_opts
+                .chain_cli_opts
+                .ss58_prefix_for(Some(&metadata)),
+        )?;
+        let output = display_events.display_events::<C>(
+            self.extrinsic_cli_opts.verbosity()?,
+            &origin_token_metadata,
+        )?;
+        println!("{output}");
+        display_extrinsic_receipt(&result.receipt);
+        Ok(())
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Defaults of the command line options from environment variables and configuration
+//! files.
+//!
+//! The options selecting the node, the chain and the signer, e.g. `--url`, default to
+//! the environment variables `CARGO_CONTRACT_URL` etc., see [`OPTIONS`]. Other options,
+//! e.g. `--execute` or `--skip-confirm`, must always be passed explicitly. The
+//! variables which are not set can be configured in `.cargo-contract.toml` files, the
+//! one of the project, searched from the current directory upwards, taking precedence
+//! over the one in the home directory of the user:
+//!
+//! ```toml
+//! # the defaults of all commands
+//! url = "wss://rpc.example.com:443"
+//! subscan = "https://example.subscan.io/"
+//!
+//! # the defaults of `cargo contract call`, overriding the ones above
+//! [call]
+//! ss58-prefix = 0
+//! ```
+//!
+//! Since a project could be checked out from an untrusted repository, secrets like the
+//! `suri` can only be configured in the environment or in the file of the user.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use clap::Command;
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// The prefix of the environment variables of the options.
+const ENV_PREFIX: &str = "CARGO_CONTRACT_";
+
+/// The name of the configuration files.
+const CONFIG_FILE: &str = ".cargo-contract.toml";
+
+/// The long options which default to environment variables and configuration files.
+const OPTIONS: [&str; 8] = [
+    "url",
+    "chain",
+    "config",
+    "suri",
+    "ss58-prefix",
+    "rpc-timeout",
+    "rpc-retries",
+    "subscan",
+];
+
+/// The options which can't be configured in the configuration file of a project.
+const SECRET_OPTIONS: [&str; 1] = ["suri"];
+
+/// The options of subcommands which don't have defaults, e.g. the `--url` of `build`
+/// only queries the maximum code size and doesn't select the node of the other
+/// commands.
+const EXCLUDED_OPTIONS: [(&str, &str); 1] = [("build", "url")];
+
+/// The options replacing the defaults of other options when passed explicitly, e.g.
+/// `--chain` replaces a configured `url`, and the Subscan explorer of the configured
+/// chain.
+const REPLACED_OPTIONS: [(&str, &[&str]); 3] = [
+    ("chain", &["url", "config", "subscan"]),
+    ("url", &["chain", "subscan"]),
+    ("config", &["chain"]),
+];
+
+/// Where a configuration file was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scope {
+    /// In the directory of the project or one of its ancestors.
+    Project,
+    /// In the home directory of the user.
+    User,
+}
+
+/// Makes the [`OPTIONS`] of the `command` default to environment variables, and sets
+/// the variables configured for the subcommand invoked by the `args` in the
+/// configuration files.
+pub fn apply(command: Command, args: &[OsString]) -> Result<Command> {
+    let subcommand = subcommand_name(&command, args);
+    let replaced = replaced_options(args);
+    for (file, scope) in config_files(&env::current_dir()?) {
+        let contents = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let config: toml::Table = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", file.display()))?;
+        let defaults = defaults(&config, subcommand.as_deref(), scope)
+            .with_context(|| format!("Invalid configuration in {}", file.display()))?;
+        for (key, value) in defaults {
+            let var = env_var(&key);
+            if !replaced.contains(&key.as_str()) && env::var_os(&var).is_none() {
+                env::set_var(var, value);
+            }
+        }
+    }
+    // the values of the variables are read when they are assigned to the options
+    Ok(with_env(command, &replaced))
+}
+
+/// Returns the name of the environment variable of the option `--{long}`.
+fn env_var(long: &str) -> String {
+    format!("{ENV_PREFIX}{}", long.replace('-', "_").to_uppercase())
+}
+
+/// Makes the [`OPTIONS`] of the `command` and its subcommands, which don't read an
+/// environment variable yet and are not `replaced`, default to one.
+fn with_env(command: Command, replaced: &[&str]) -> Command {
+    let name = command.get_name().to_owned();
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_owned())
+        .collect::<Vec<_>>();
+    let mut command = command.mut_args(|arg| {
+        match arg.get_long() {
+            Some(long)
+                if arg.get_env().is_none()
+                    && OPTIONS.contains(&long)
+                    && !replaced.contains(&long)
+                    && !EXCLUDED_OPTIONS.contains(&(name.as_str(), long)) =>
+            {
+                let var = env_var(long);
+                arg.env(var).hide_env(true)
+            }
+            _ => arg,
+        }
+    });
+    for subcommand in subcommands {
+        command = command.mut_subcommand(subcommand, |sub| with_env(sub, replaced));
+    }
+    command
+}
+
+/// Returns the options whose defaults are replaced by the options passed in the
+/// `args`.
+fn replaced_options(args: &[OsString]) -> Vec<&'static str> {
+    args.iter()
+        .filter_map(|arg| arg.to_str()?.strip_prefix("--"))
+        .map(|arg| arg.split_once('=').map_or(arg, |(long, _)| long))
+        .flat_map(|long| {
+            REPLACED_OPTIONS
+                .iter()
+                .filter(move |(option, _)| *option == long)
+                .flat_map(|(_, replaced)| replaced.iter().copied())
+        })
+        .collect()
+}
+
+/// Returns the name of the `cargo contract` subcommand invoked by the `args`, if any.
+fn subcommand_name(command: &Command, args: &[OsString]) -> Option<String> {
+    let matches = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+        .ok()?;
+    let (_, contract) = matches.subcommand()?;
+    contract.subcommand_name().map(ToOwned::to_owned)
+}
+
+/// Returns the configuration files, in the order of their precedence.
+fn config_files(dir: &Path) -> Vec<(PathBuf, Scope)> {
+    let project = dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|file| file.is_file());
+    let user = env::var_os("HOME").map(|home| PathBuf::from(home).join(CONFIG_FILE));
+    match project {
+        // the file of the user is found as the one of the project inside its home
+        Some(project) if Some(&project) == user.as_ref() => vec![(project, Scope::User)],
+        project => {
+            project
+                .map(|file| (file, Scope::Project))
+                .into_iter()
+                .chain(
+                    user.filter(|file| file.is_file())
+                        .map(|file| (file, Scope::User)),
+                )
+                .collect()
+        }
+    }
+}
+
+/// Returns the options configured for the `subcommand`, in the order of their
+/// precedence.
+///
+/// Fails if options other than the [`OPTIONS`] are configured, or secrets in the
+/// configuration of a project.
+fn defaults(
+    config: &toml::Table,
+    subcommand: Option<&str>,
+    scope: Scope,
+) -> Result<Vec<(String, String)>> {
+    let subcommand_config = subcommand
+        .and_then(|subcommand| config.get(subcommand))
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flatten();
+    let global_config = config.iter().filter(|(_, value)| !value.is_table());
+
+    subcommand_config
+        .chain(global_config)
+        .map(|(key, value)| {
+            if !OPTIONS.contains(&key.as_str()) {
+                anyhow::bail!(
+                    "`{key}` can't be configured, only {}",
+                    OPTIONS.map(|option| format!("`{option}`")).join(", ")
+                )
+            }
+            if scope == Scope::Project && SECRET_OPTIONS.contains(&key.as_str()) {
+                anyhow::bail!(
+                    "`{key}` can't be configured for a project, set `{}` or configure \
+                     it in ~/{CONFIG_FILE} instead",
+                    env_var(key)
+                )
+            }
+            let value = match value {
+                toml::Value::String(value) => value.clone(),
+                toml::Value::Integer(_)
+                | toml::Value::Float(_)
+                | toml::Value::Boolean(_) => value.to_string(),
+                _ => anyhow::bail!("Unsupported value of `{key}`: {value}"),
+            };
+            Ok((key.clone(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Opts;
+    use clap::{
+        Arg,
+        CommandFactory,
+    };
+
+    /// Returns the environment variable of the option `id` of the `cargo contract`
+    /// subcommand `name`.
+    fn env(command: &Command, name: &str, id: &str) -> Option<String> {
+        command
+            .find_subcommand("contract")
+            .and_then(|contract| contract.find_subcommand(name))
+            .and_then(|subcommand| {
+                subcommand
+                    .get_arguments()
+                    .find(|arg| arg.get_id() == id)
+            })
+            .and_then(Arg::get_env)
+            .map(|env| env.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn only_allowed_options_default_to_env_vars() {
+        let command = with_env(
+            Command::new("cargo").subcommand(
+                Command::new("contract").subcommand(
+                    Command::new("call")
+                        .arg(Arg::new("url").long("url"))
+                        .arg(Arg::new("execute").long("execute"))
+                        .arg(Arg::new("contract").long("contract").env("CONTRACT"))
+                        .arg(Arg::new("file")),
+                ),
+            ),
+            &[],
+        );
+
+        assert_eq!(
+            env(&command, "call", "url").as_deref(),
+            Some("CARGO_CONTRACT_URL")
+        );
+        assert_eq!(env(&command, "call", "execute"), None);
+        assert_eq!(env(&command, "call", "contract").as_deref(), Some("CONTRACT"));
+        assert_eq!(env(&command, "call", "file"), None);
+    }
+
+    #[test]
+    fn build_url_has_no_default() {
+        let command = with_env(Opts::command(), &[]);
+
+        assert_eq!(env(&command, "build", "url"), None);
+        assert_eq!(
+            env(&command, "call", "url").as_deref(),
+            Some("CARGO_CONTRACT_URL")
+        );
+    }
+
+    #[test]
+    fn explicit_chain_replaces_configured_url() {
+        let args = [
+            "cargo",
+            "contract",
+            "call",
+            "--chain",
+            "aleph-zero",
+            "--contract",
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+            "--message",
+            "get",
+            "--suri",
+            "//Alice",
+        ]
+        .map(OsString::from);
+        let replaced = replaced_options(&args);
+        let command = with_env(Opts::command(), &replaced);
+
+        assert_eq!(replaced, ["url", "config", "subscan"]);
+        assert_eq!(env(&command, "call", "url"), None);
+        assert_eq!(env(&command, "call", "config"), None);
+        assert_eq!(
+            env(&command, "call", "suri").as_deref(),
+            Some("CARGO_CONTRACT_SURI")
+        );
+
+        // a configured url conflicts with `--chain` unless it is replaced
+        env::set_var("CARGO_CONTRACT_URL", "wss://rpc.example.com:443");
+        command.try_get_matches_from(&args).unwrap();
+        let err = with_env(Opts::command(), &[])
+            .try_get_matches_from(&args)
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn subcommand_defaults_take_precedence() {
+        let config: toml::Table = toml::from_str(
+            r#"
+            url = "wss://rpc.example.com:443"
+            ss58-prefix = 42
+
+            [call]
+            url = "ws://localhost:9944"
+
+            [upload]
+            suri = "//Bob"
+            "#,
+        )
+        .unwrap();
+
+        let defaults = defaults(&config, Some("call"), Scope::Project).unwrap();
+
+        assert_eq!(
+            defaults,
+            vec![
+                ("url".to_owned(), "ws://localhost:9944".to_owned()),
+                ("ss58-prefix".to_owned(), "42".to_owned()),
+                ("url".to_owned(), "wss://rpc.example.com:443".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_options_other_than_allowed() {
+        let configs = ["execute = true", "skip-confirm = true", "[call]\nexecute = 1"];
+        for config in configs {
+            let config: toml::Table = toml::from_str(config).unwrap();
+
+            let err = defaults(&config, Some("call"), Scope::User).unwrap_err();
+            assert!(err.to_string().contains("can't be configured"), "{err}");
+        }
+    }
+
+    #[test]
+    fn rejects_secrets_of_project() {
+        let config: toml::Table = toml::from_str(r#"suri = "//Alice""#).unwrap();
+
+        let err = defaults(&config, None, Scope::Project).unwrap_err();
+        assert!(err.to_string().contains("CARGO_CONTRACT_SURI"), "{err}");
+        assert!(defaults(&config, None, Scope::User).is_ok());
+    }
+
+    #[test]
+    fn finds_invoked_subcommand() {
+        let command = Command::new("cargo").subcommand(
+            Command::new("contract").subcommand(
+                Command::new("call")
+                    .arg(Arg::new("suri").long("suri").required(true))
+                    .arg(Arg::new("message").long("message").required(true)),
+            ),
+        );
+        let args = ["cargo", "contract", "call", "--message", "flip"].map(OsString::from);
+
+        assert_eq!(subcommand_name(&command, &args).as_deref(), Some("call"));
+    }
+
+    #[test]
+    fn rejects_arrays() {
+        let config: toml::Table = toml::from_str(r#"url = ["1", "2"]"#).unwrap();
+
+        assert!(defaults(&config, None, Scope::User).is_err());
+    }
+}
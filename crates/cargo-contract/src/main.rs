@@ -17,22 +17,50 @@
 #![deny(unused_crate_dependencies)]
 
 mod cmd;
+mod defaults;
 
 use self::cmd::{
+    AccountCommand,
+    AddressBookCommand,
+    AnalyzeCommand,
+    BenchCommand,
     BuildCommand,
+    BundleCommand,
     CallCommand,
+    ChainCommand,
     CheckCommand,
+    CheckCompatCommand,
+    CompletionsCommand,
+    DaemonCommand,
     DecodeCommand,
+    DiffCommand,
+    DocCommand,
     ErrorVariant,
+    FaucetCommand,
+    ForkCommand,
+    FuzzCommand,
+    GenerateClientCommand,
     GenerateSchemaCommand,
     InfoCommand,
+    InspectBlockCommand,
     InstantiateCommand,
+    LintCommand,
+    MetadataCommand,
+    PublishCommand,
+    QueryCommand,
     RemoveCommand,
+    ReplCommand,
     RpcCommand,
+    SelectorCommand,
     StorageCommand,
+    TestCommand,
+    TransferCommand,
+    UiCommand,
     UploadCommand,
+    VerifyBundleCommand,
     VerifyCommand,
     VerifySchemaCommand,
+    XcmCommand,
 };
 use anyhow::{
     anyhow,
@@ -40,24 +68,42 @@ use anyhow::{
     Result,
 };
 use clap::{
+    error::ErrorKind,
+    ArgAction,
     Args,
+    CommandFactory,
+    FromArgMatches,
     Parser,
     Subcommand,
 };
 use cmd::encode::EncodeCommand;
 use colored::Colorize;
 use contract_build::{
+    output::{
+        LogFormat,
+        OUTPUT_TARGET,
+    },
     util::DEFAULT_KEY_COL_WIDTH,
+    NewProjectOptions,
     OutputType,
+    Template,
+};
+use contract_extrinsics::{
+    InstantiateExec,
+    EXIT_CODES,
+    EXIT_CODE_GENERIC,
 };
-use contract_extrinsics::InstantiateExec;
 use sp_weights::Weight;
 use std::{
-    fmt::Debug,
+    fmt::{
+        self,
+        Display,
+    },
     path::PathBuf,
     str::FromStr,
 };
 use tokio::runtime::Runtime;
+use tracing_subscriber::EnvFilter;
 // These crates are only used when we run integration tests `--features
 // integration-tests`. However since we can't have optional `dev-dependencies` we pretend
 // to use them during normal test runs in order to satisfy the `unused_crate_dependencies`
@@ -82,13 +128,25 @@ pub(crate) enum Opts {
     #[clap(name = "contract")]
     #[clap(version = env!("CARGO_CONTRACT_CLI_IMPL_VERSION"))]
     #[clap(action = ArgAction::DeriveDisplayOrder)]
+    #[clap(arg_required_else_help = true)]
     Contract(ContractArgs),
 }
 
 #[derive(Debug, Args)]
 pub(crate) struct ContractArgs {
+    /// Format of the diagnostics printed to stderr.
+    #[clap(long, global = true, value_enum, default_value_t)]
+    log_format: LogFormat,
+    /// Log more details of the execution to stderr, `-vv` and `-vvv` for even more.
+    ///
+    /// Overridden by the `RUST_LOG` environment variable.
+    #[clap(short = 'v', global = true, action = ArgAction::Count)]
+    log_verbosity: u8,
+    /// Print the exit codes of cargo-contract and their meaning.
+    #[clap(long, exclusive = true)]
+    help_exit_codes: bool,
     #[clap(subcommand)]
-    cmd: Command,
+    cmd: Option<Command>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -112,6 +170,17 @@ enum Command {
         /// The optional target directory for the contract project
         #[clap(short, long, value_parser)]
         target_dir: Option<PathBuf>,
+        /// The template to create the project from: one of `flipper`, `psp22`,
+        /// `psp34`, `multisig`, `proxy` or the URL of a git repository.
+        #[clap(long, default_value = "flipper")]
+        template: Template,
+        /// Pin the ink! dependencies of the project exactly to this version, and require
+        /// compatible versions of `parity-scale-codec` and `scale-info`.
+        #[clap(long)]
+        ink_version: Option<semver::Version>,
+        /// Only use the bundled templates, which don't require network access.
+        #[clap(long)]
+        offline: bool,
     },
     /// Compiles the contract, generates metadata, bundles both together in a
     /// `<name>.contract` file
@@ -121,6 +190,15 @@ enum Command {
     /// artifact to the `target/` directory
     #[clap(name = "check")]
     Check(CheckCommand),
+    /// Check the contract for common mistakes using clippy and the ink! linting rules
+    #[clap(name = "lint")]
+    Lint(LintCommand),
+    /// Run the off-chain unit tests of the contract, optionally with code coverage
+    #[clap(name = "test")]
+    Test(TestCommand),
+    /// Generate a cargo-fuzz harness for the messages of the contract
+    #[clap(name = "fuzz")]
+    Fuzz(FuzzCommand),
     /// Upload contract code
     #[clap(name = "upload")]
     Upload(UploadCommand),
@@ -130,21 +208,69 @@ enum Command {
     /// Call a contract
     #[clap(name = "call")]
     Call(CallCommand),
+    /// Benchmark the gas consumption of contract messages
+    #[clap(name = "bench")]
+    Bench(BenchCommand),
+    /// Query a read-only message of a contract
+    #[clap(name = "query")]
+    Query(QueryCommand),
+    /// Interactively call and query a contract and inspect its storage and events
+    #[clap(name = "repl")]
+    Repl(ReplCommand),
+    /// Call a contract from another chain with an XCM `Transact`
+    #[clap(name = "xcm")]
+    Xcm(XcmCommand),
+    /// Transfer a value to an account
+    #[clap(name = "transfer")]
+    Transfer(TransferCommand),
+    /// Fund an account on a development chain or from a faucet
+    #[clap(name = "faucet")]
+    Faucet(FaucetCommand),
+    /// Inspect the fees of a chain
+    #[clap(name = "chain")]
+    Chain(ChainCommand),
     /// Encodes a contracts input calls and their arguments
     #[clap(name = "encode")]
     Encode(EncodeCommand),
     /// Decodes a contracts input or output data (supplied in hex-encoding)
     #[clap(name = "decode")]
     Decode(DecodeCommand),
+    /// Computes the selector of a message or looks up the message of a selector
+    #[clap(name = "selector")]
+    Selector(SelectorCommand),
+    /// Generates typed client bindings from the contract metadata
+    #[clap(name = "generate-client")]
+    GenerateClient(GenerateClientCommand),
+    /// Generates the reference documentation of a contract from its metadata
+    #[clap(name = "doc")]
+    Doc(DocCommand),
+    /// Compares two versions of a contract, e.g. to review an upgrade
+    #[clap(name = "diff")]
+    Diff(DiffCommand),
+    /// Checks whether a contract can be upgraded to a new version
+    #[clap(name = "check-compat")]
+    CheckCompat(CheckCompatCommand),
+    /// Scan the Wasm code of a contract for features unsupported by pallet-contracts
+    #[clap(name = "analyze")]
+    Analyze(AnalyzeCommand),
     /// Remove contract code
     #[clap(name = "remove")]
     Remove(RemoveCommand),
     /// Display information about a contract
     #[clap(name = "info")]
     Info(InfoCommand),
+    /// List the extrinsics and events of contracts in a block
+    #[clap(name = "inspect-block")]
+    InspectBlock(InspectBlockCommand),
     /// Inspect the on-chain storage of a contract.
     #[clap(name = "storage")]
     Storage(StorageCommand),
+    /// Open an interactive dashboard of a contract in the terminal
+    #[clap(name = "ui")]
+    Ui(UiCommand),
+    /// Fork the on-chain state of a contract into a local snapshot.
+    #[clap(name = "fork")]
+    Fork(ForkCommand),
     /// Verifies that a given contract binary matches the build result of the specified
     /// workspace.
     #[clap(name = "verify")]
@@ -155,40 +281,154 @@ enum Command {
     /// Verify schema from the current metadata specification.
     #[clap(name = "verify-schema")]
     VerifySchema(VerifySchemaCommand),
+    /// Verify the signature of a signed contract bundle.
+    #[clap(name = "verify-bundle")]
+    VerifyBundle(VerifyBundleCommand),
     /// Make a raw RPC call.
     #[clap(name = "rpc")]
     Rpc(RpcCommand),
+    /// Inspect and transform contract metadata.
+    #[clap(name = "metadata")]
+    Metadata(MetadataCommand),
+    /// Manage the local address book of named contract addresses.
+    #[clap(name = "address-book")]
+    AddressBook(AddressBookCommand),
+    /// Split a `.contract` bundle into its metadata and Wasm code, or combine them.
+    #[clap(name = "bundle")]
+    Bundle(BundleCommand),
+    /// Publish a contract bundle or its metadata, e.g. to IPFS.
+    #[clap(name = "publish")]
+    Publish(PublishCommand),
+    /// Generate shell completions.
+    #[clap(name = "completions")]
+    Completions(CompletionsCommand),
+    /// Keep the connections to nodes open for other invocations.
+    #[clap(name = "daemon")]
+    Daemon(DaemonCommand),
+    /// Manage the account on chain.
+    #[clap(name = "account")]
+    Account(AccountCommand),
 }
 
-fn main() {
-    tracing_subscriber::fmt::init();
+/// The crates of cargo-contract, whose logs are enabled by `-v`.
+const LOG_CRATES: [&str; 6] = [
+    "cargo_contract",
+    "contract_analyze",
+    "contract_build",
+    "contract_extrinsics",
+    "contract_metadata",
+    "contract_transcode",
+];
 
-    let Opts::Contract(args) = Opts::parse();
+fn main() {
+    let cli_args = std::env::args_os().collect::<Vec<_>>();
+    let command = defaults::apply(Opts::command(), &cli_args).unwrap_or_else(|err| {
+        eprintln!("{err:?}");
+        std::process::exit(1);
+    });
+    let Opts::Contract(args) =
+        Opts::from_arg_matches(&command.clone().get_matches_from(cli_args))
+            .unwrap_or_else(|err| err.exit());
+    let Some(cmd) = args.cmd else {
+        if args.help_exit_codes {
+            print_exit_codes();
+            return
+        }
+        let mut command = command;
+        command.build();
+        command
+            .find_subcommand_mut("contract")
+            .expect("the `contract` subcommand is defined")
+            .error(
+                ErrorKind::MissingSubcommand,
+                "a subcommand is required unless `--help-exit-codes` is given",
+            )
+            .exit()
+    };
+    init_logging(args.log_format, args.log_verbosity);
 
-    match exec(args.cmd) {
+    match exec(cmd) {
         Ok(()) => {}
         Err(err) => {
-            eprintln!("{err:?}");
-            std::process::exit(1);
+            match args.log_format {
+                LogFormat::Human => eprintln!("{err:?}"),
+                LogFormat::Json => tracing::error!(target: OUTPUT_TARGET, "{err:?}"),
+            }
+            let exit_code = err
+                .downcast_ref::<CommandError>()
+                .map_or(EXIT_CODE_GENERIC, |err| err.exit_code);
+            std::process::exit(exit_code);
         }
     }
 }
 
+/// Prints the exit codes of cargo-contract and their meaning.
+fn print_exit_codes() {
+    println!("{}", "Exit codes:".bright_white().bold());
+    for (code, description) in EXIT_CODES {
+        println!("  {code:>3}  {description}");
+    }
+}
+
+/// Logs to stderr in the given `format`, with the logs of the cargo-contract crates
+/// enabled according to the `verbosity`.
+fn init_logging(format: LogFormat, verbosity: u8) {
+    contract_build::output::set_log_format(format);
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(log_directives(verbosity)));
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(filter);
+    match format {
+        LogFormat::Human => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+/// Returns the `tracing` filter directives for the `verbosity`.
+fn log_directives(verbosity: u8) -> String {
+    let level = match verbosity {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    let crates = LOG_CRATES.map(|krate| format!("{krate}={level}"));
+    format!("error,{OUTPUT_TARGET}=info,{}", crates.join(","))
+}
+
 fn exec(cmd: Command) -> Result<()> {
     let runtime = Runtime::new().expect("Failed to create Tokio runtime");
     match &cmd {
-        Command::New { name, target_dir } => {
-            contract_build::new_contract_project(name, target_dir.as_ref())?;
+        Command::New {
+            name,
+            target_dir,
+            template,
+            ink_version,
+            offline,
+        } => {
+            let options = NewProjectOptions {
+                template: template.clone(),
+                ink_version: ink_version.clone(),
+                offline: *offline,
+            };
+            contract_build::new_contract_project_with_options(
+                name,
+                target_dir.as_ref(),
+                &options,
+            )?;
             println!("Created contract {name}");
             Ok(())
         }
         Command::Build(build) => {
-            let result = build.exec().map_err(format_err)?;
+            let results = build.exec().map_err(format_err)?;
 
-            if matches!(result.output_type, OutputType::Json) {
-                println!("{}", result.serialize_json()?)
-            } else if result.verbosity.is_verbose() {
-                println!("{}", result.display())
+            for result in results {
+                if matches!(result.output_type, OutputType::Json) {
+                    println!("{}", result.serialize_json()?)
+                } else if result.verbosity.is_verbose() {
+                    println!("{}", result.display())
+                }
             }
             Ok(())
         }
@@ -200,6 +440,21 @@ fn exec(cmd: Command) -> Result<()> {
             );
             Ok(())
         }
+        Command::Lint(lint) => {
+            let result = lint.exec().map_err(format_err)?;
+
+            if matches!(result.output_type, OutputType::Json) {
+                println!("{}", result.serialize_json()?)
+            } else if result.verbosity.is_verbose() {
+                println!("{}", result.display())
+            }
+            if result.has_errors() {
+                anyhow::bail!("The contract violates linting rules")
+            }
+            Ok(())
+        }
+        Command::Test(test) => test.run().map_err(format_err),
+        Command::Fuzz(fuzz) => fuzz.run().map_err(format_err),
         Command::Upload(upload) => {
             runtime.block_on(async {
                 upload
@@ -223,8 +478,67 @@ fn exec(cmd: Command) -> Result<()> {
                     .map_err(|err| map_extrinsic_err(err, call.output_json()))
             })
         }
+        Command::Bench(bench) => {
+            runtime.block_on(async {
+                bench
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, bench.output_json()))
+            })
+        }
+        Command::Query(query) => {
+            runtime.block_on(async {
+                query
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, query.output_json()))
+            })
+        }
+        Command::Repl(repl) => {
+            runtime.block_on(async { repl.handle().await.map_err(format_err) })
+        }
+        Command::Xcm(xcm) => {
+            runtime.block_on(async { xcm.handle().await.map_err(format_err) })
+        }
+        Command::Transfer(transfer) => {
+            runtime.block_on(async {
+                transfer
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, transfer.output_json()))
+            })
+        }
+        Command::Faucet(faucet) => {
+            runtime.block_on(async {
+                faucet
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, faucet.output_json()))
+            })
+        }
+        Command::Chain(chain) => {
+            runtime.block_on(async {
+                chain
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, chain.output_json()))
+            })
+        }
         Command::Encode(encode) => encode.run().map_err(format_err),
         Command::Decode(decode) => decode.run().map_err(format_err),
+        Command::Selector(selector) => selector.run().map_err(format_err),
+        Command::GenerateClient(generate) => generate.run().map_err(format_err),
+        Command::Doc(doc) => doc.run().map_err(format_err),
+        Command::Diff(diff) => diff.run().map_err(format_err),
+        Command::CheckCompat(check) => check.run().map_err(format_err),
+        Command::Analyze(analyze) => analyze.run().map_err(format_err),
+        Command::Metadata(metadata) => metadata.run().map_err(format_err),
+        Command::Bundle(bundle) => bundle.run().map_err(format_err),
+        Command::VerifyBundle(verify) => verify.run().map_err(format_err),
+        Command::Completions(completions) => completions.run().map_err(format_err),
+        Command::Publish(publish) => {
+            runtime.block_on(async { publish.run().await.map_err(format_err) })
+        }
         Command::Remove(remove) => {
             runtime.block_on(async {
                 remove
@@ -236,9 +550,26 @@ fn exec(cmd: Command) -> Result<()> {
         Command::Info(info) => {
             runtime.block_on(async { info.handle().await.map_err(format_err) })
         }
+        Command::InspectBlock(inspect) => {
+            runtime.block_on(async {
+                inspect
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, inspect.output_json()))
+            })
+        }
         Command::Storage(storage) => {
             runtime.block_on(async { storage.handle().await.map_err(format_err) })
         }
+        Command::Ui(ui) => {
+            runtime.block_on(async { ui.handle().await.map_err(format_err) })
+        }
+        Command::AddressBook(address_book) => {
+            runtime.block_on(async { address_book.handle().await.map_err(format_err) })
+        }
+        Command::Fork(fork) => {
+            runtime.block_on(async { fork.handle().await.map_err(format_err) })
+        }
         Command::Verify(verify) => {
             let result = verify.run().map_err(format_err)?;
 
@@ -267,25 +598,54 @@ fn exec(cmd: Command) -> Result<()> {
         Command::Rpc(rpc) => {
             runtime.block_on(async { rpc.run().await.map_err(format_err) })
         }
+        Command::Daemon(daemon) => {
+            runtime.block_on(async { daemon.run().await.map_err(format_err) })
+        }
+        Command::Account(account) => {
+            runtime.block_on(async {
+                account
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, account.output_json()))
+            })
+        }
     }
 }
 
+/// The formatted error of a command, with the exit code of cargo-contract failing with
+/// it.
+#[derive(Debug)]
+struct CommandError {
+    message: String,
+    exit_code: i32,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
 fn map_extrinsic_err(err: ErrorVariant, is_json: bool) -> Error {
-    if is_json {
-        anyhow!(
-            "{}",
-            serde_json::to_string_pretty(&err)
-                .expect("error serialization is infallible; qed")
-        )
+    let message = if is_json {
+        serde_json::to_string_pretty(&err)
+            .expect("error serialization is infallible; qed")
     } else {
-        format_err(err)
+        format!(
+            "{} {}",
+            "ERROR:".bright_red().bold(),
+            format!("{err:?}").bright_red()
+        )
+    };
+    CommandError {
+        message,
+        exit_code: err.exit_code(),
     }
+    .into()
 }
 
-fn format_err<E: Debug>(err: E) -> Error {
-    anyhow!(
-        "{} {}",
-        "ERROR:".bright_red().bold(),
-        format!("{err:?}").bright_red()
-    )
+fn format_err<E: Into<ErrorVariant>>(err: E) -> Error {
+    map_extrinsic_err(err.into(), false)
 }
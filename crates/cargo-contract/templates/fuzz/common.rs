@@ -0,0 +1,110 @@
+//! The harness shared by the fuzz targets generated by `cargo contract fuzz`.
+//!
+//! Each fuzz target passes its input as the SCALE encoded arguments of a message to a
+//! dry-run against the contract at `CONTRACT_ADDRESS`, on the node at `CONTRACT_URL`
+//! (default `ws://localhost:9944`) and signed by `CONTRACT_SURI` (default `//Alice`).
+//! Inputs which don't decode as the arguments of the message are skipped, a dry-run in
+//! which the contract fails is reported as a crash.
+
+use contract_extrinsics::{
+    CallCommandBuilder,
+    CallExec,
+    ExtrinsicOptsBuilder,
+};
+use ink_env::DefaultEnvironment;
+use std::{
+    str::FromStr,
+    sync::OnceLock,
+};
+use subxt::{
+    utils::AccountId32,
+    SubstrateConfig,
+};
+use subxt_signer::{
+    sr25519::Keypair,
+    SecretUri,
+};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// The metadata of the contract the harness was generated for.
+const METADATA: &str = {{metadata}};
+
+struct Harness {
+    runtime: Runtime,
+    exec: CallExec<SubstrateConfig, DefaultEnvironment, Keypair>,
+    selector: Vec<u8>,
+}
+
+static HARNESS: OnceLock<Harness> = OnceLock::new();
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_owned())
+}
+
+fn harness(message: &str) -> &'static Harness {
+    HARNESS.get_or_init(|| {
+        let runtime = Runtime::new().expect("failed to create the tokio runtime");
+        let contract = std::env::var("CONTRACT_ADDRESS")
+            .expect("CONTRACT_ADDRESS must be set to the address of the contract");
+        let contract = AccountId32::from_str(&contract).expect("invalid CONTRACT_ADDRESS");
+        let url = Url::parse(&env_or("CONTRACT_URL", "ws://localhost:9944"))
+            .expect("invalid CONTRACT_URL");
+        let uri = SecretUri::from_str(&env_or("CONTRACT_SURI", "//Alice"))
+            .expect("invalid CONTRACT_SURI");
+        let signer = Keypair::from_uri(&uri).expect("invalid CONTRACT_SURI");
+
+        let opts = ExtrinsicOptsBuilder::new(signer)
+            .file(Some(METADATA))
+            .url(url)
+            .done();
+        let exec = runtime
+            .block_on(
+                CallCommandBuilder::new(contract, message, opts)
+                    .call_data(Some(Vec::new()))
+                    .done(),
+            )
+            .expect("failed to connect to the node");
+        let selector = exec
+            .transcoder()
+            .expect("the contract is described by ink! metadata")
+            .metadata()
+            .spec()
+            .messages()
+            .iter()
+            .find(|spec| spec.label() == message)
+            .expect("the message exists in the metadata")
+            .selector()
+            .to_bytes()
+            .to_vec();
+        Harness {
+            runtime,
+            exec,
+            selector,
+        }
+    })
+}
+
+/// Dry-runs `message` with `args` as its SCALE encoded arguments.
+pub fn fuzz_message(message: &str, args: &[u8]) {
+    let harness = harness(message);
+    let mut input = harness.selector.clone();
+    input.extend_from_slice(args);
+
+    let transcoder = harness
+        .exec
+        .transcoder()
+        .expect("the contract is described by ink! metadata");
+    let mut data = &input[..];
+    if transcoder.decode_contract_message(&mut data).is_err() || !data.is_empty() {
+        return
+    }
+
+    let result = harness
+        .runtime
+        .block_on(harness.exec.call_dry_run_with_input(input))
+        .expect("the dry-run failed");
+    if let Err(error) = result.result {
+        panic!("calling `{message}` failed: {error:?}");
+    }
+}
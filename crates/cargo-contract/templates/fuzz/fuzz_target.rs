@@ -0,0 +1,5 @@
+#![no_main]
+
+mod common;
+
+libfuzzer_sys::fuzz_target!(|args: &[u8]| common::fuzz_message("{{message}}", args));
@@ -106,4 +106,19 @@ fn encode_works() {
         .assert()
         .success()
         .stdout(predicates::str::contains(output));
+    // when
+    let output: &str = r#""data": "0xbabababa05000000""#;
+
+    // then
+    // call data is exported as JSON
+    cargo_contract(&project_dir)
+        .arg("encode")
+        .arg("--message")
+        .arg("inc")
+        .arg("--args")
+        .arg("5")
+        .arg("--output-json")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(output));
 }
@@ -0,0 +1,43 @@
+// Copyright 2018-2020 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Create a `cargo contract` command
+fn cargo_contract() -> assert_cmd::Command {
+    let mut cmd = assert_cmd::Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.arg("contract");
+    cmd
+}
+
+#[test]
+fn help_exit_codes_prints_exit_codes() {
+    cargo_contract()
+        .arg("--help-exit-codes")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Invalid command line arguments"));
+}
+
+#[test]
+fn missing_subcommand_is_a_usage_error() {
+    for args in [&["-v"][..], &["--log-format", "json"]] {
+        cargo_contract()
+            .args(args)
+            .assert()
+            .code(2)
+            .stdout(predicates::str::is_empty())
+            .stderr(predicates::str::contains("a subcommand is required"));
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::path::Path;
+
+/// Create a `cargo contract` command
+fn cargo_contract<P: AsRef<Path>>(path: P) -> assert_cmd::Command {
+    let mut cmd = assert_cmd::Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.current_dir(path).arg("contract");
+    cmd
+}
+
+#[test]
+fn generated_harness_compiles() {
+    // given
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("cargo-contract.cli.test.")
+        .tempdir()
+        .expect("temporary directory creation failed");
+
+    // cargo contract new flipper
+    cargo_contract(tmp_dir.path())
+        .arg("new")
+        .arg("flipper")
+        .assert()
+        .success();
+
+    let project_dir = tmp_dir.path().to_path_buf().join("flipper");
+    cargo_contract(&project_dir).arg("build").assert().success();
+
+    // when
+    cargo_contract(&project_dir)
+        .arg("fuzz")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Targets"));
+
+    // then
+    // the harness compiles against the `contract-extrinsics` of this workspace, which
+    // may not be released yet
+    let extrinsics = Path::new(env!("CARGO_MANIFEST_DIR")).join("../extrinsics");
+    assert_cmd::Command::new("cargo")
+        .current_dir(project_dir.join("fuzz"))
+        .arg("check")
+        .arg("--bins")
+        .arg("--config")
+        .arg(format!(
+            "patch.crates-io.contract-extrinsics.path={:?}",
+            extrinsics.to_string_lossy()
+        ))
+        .assert()
+        .success();
+}
@@ -0,0 +1,517 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generation of the reference documentation of contracts.
+//!
+//! The constructors, messages and events are listed in the order of the metadata and
+//! the output contains nothing else, e.g. no timestamps, so it only changes if the
+//! metadata does and can be committed or diffed in CI.
+
+use crate::DocFormat;
+use anyhow::{
+    Context,
+    Result,
+};
+use ink_metadata::{
+    InkProject,
+    MessageParamSpec,
+    Selector,
+};
+use scale_info::{
+    form::PortableForm,
+    Field,
+    PortableRegistry,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+};
+use std::fmt::Write;
+
+/// Generates the reference documentation of the contract `name` from its `metadata`.
+pub fn generate(name: &str, metadata: &InkProject, format: DocFormat) -> Result<String> {
+    match format {
+        DocFormat::Markdown => render(Markdown::default(), name, metadata),
+        DocFormat::Html => render(Html::new(name), name, metadata),
+    }
+}
+
+fn render<R: Renderer>(mut out: R, name: &str, metadata: &InkProject) -> Result<String> {
+    let spec = metadata.spec();
+    let types = TypeNames {
+        registry: metadata.registry(),
+        lang_error: spec.lang_error().ty().id,
+    };
+
+    out.heading(1, name)?;
+    out.docs(spec.docs())?;
+
+    if !spec.constructors().is_empty() {
+        out.section("Constructors")?;
+    }
+    for constructor in spec.constructors() {
+        out.heading(3, constructor.label())?;
+        out.docs(constructor.docs())?;
+        let mut properties = vec![
+            ("Selector", Cell::Code(selector(constructor.selector()))),
+            ("Payable", yes_no(*constructor.payable())),
+        ];
+        let ret = types.returned(constructor.return_type().ret_type().ty().id)?;
+        if ret != "()" {
+            properties.push(("Returns", Cell::Code(ret)));
+        }
+        out.properties(&properties)?;
+        write_args(&mut out, &types, constructor.args())?;
+    }
+
+    if !spec.messages().is_empty() {
+        out.section("Messages")?;
+    }
+    for message in spec.messages() {
+        out.heading(3, message.label())?;
+        out.docs(message.docs())?;
+        let ret = types.returned(message.return_type().ret_type().ty().id)?;
+        out.properties(&[
+            ("Selector", Cell::Code(selector(message.selector()))),
+            ("Mutates", yes_no(message.mutates())),
+            ("Payable", yes_no(message.payable())),
+            ("Returns", Cell::Code(ret)),
+        ])?;
+        write_args(&mut out, &types, message.args())?;
+    }
+
+    if !spec.events().is_empty() {
+        out.section("Events")?;
+    }
+    for event in spec.events() {
+        out.heading(3, event.label())?;
+        out.docs(event.docs())?;
+        if let Some(topic) = event.signature_topic() {
+            let topic = format!("0x{}", hex::encode(topic.as_bytes()));
+            out.properties(&[("Signature topic", Cell::Code(topic))])?;
+        }
+        let rows = event
+            .args()
+            .iter()
+            .map(|arg| {
+                Ok(vec![
+                    Cell::Code(arg.label().clone()),
+                    Cell::Code(types.name(arg.ty().ty().id)?),
+                    yes_no(arg.indexed()),
+                    Cell::Text(arg.docs().join(" ").trim().to_string()),
+                ])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if !rows.is_empty() {
+            out.table(&["Field", "Type", "Topic", "Description"], &rows)?;
+        }
+    }
+
+    let returned = spec
+        .constructors()
+        .iter()
+        .map(|c| c.return_type().ret_type().ty().id)
+        .chain(spec.messages().iter().map(|m| m.return_type().ret_type().ty().id));
+    let mut errors = Vec::new();
+    for id in returned {
+        if let Some(error) = types.error_type(id)? {
+            if !errors.contains(&error) {
+                errors.push(error);
+            }
+        }
+    }
+    if !errors.contains(&types.lang_error) {
+        errors.push(types.lang_error);
+    }
+
+    out.section("Errors")?;
+    for id in errors {
+        let ty = types.resolve(id)?;
+        out.heading(3, &types.name(id)?)?;
+        out.docs(&ty.docs)?;
+        if let TypeDef::Variant(variant) = &ty.type_def {
+            let rows = variant
+                .variants
+                .iter()
+                .map(|v| {
+                    Ok(vec![
+                        Cell::Code(v.name.clone()),
+                        types.fields(&v.fields)?,
+                        Cell::Text(v.docs.join(" ").trim().to_string()),
+                    ])
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if !rows.is_empty() {
+                out.table(&["Variant", "Fields", "Description"], &rows)?;
+            }
+        }
+    }
+
+    out.finish()
+}
+
+fn write_args<R: Renderer>(
+    out: &mut R,
+    types: &TypeNames,
+    args: &[MessageParamSpec<PortableForm>],
+) -> Result<()> {
+    if args.is_empty() {
+        return Ok(())
+    }
+    let rows = args
+        .iter()
+        .map(|arg| {
+            Ok(vec![
+                Cell::Code(arg.label().clone()),
+                Cell::Code(types.name(arg.ty().ty().id)?),
+            ])
+        })
+        .collect::<Result<Vec<_>>>()?;
+    out.table(&["Argument", "Type"], &rows)
+}
+
+/// The Rust names of the types of the contract, e.g. `Option<AccountId>`.
+struct TypeNames<'a> {
+    registry: &'a PortableRegistry,
+    lang_error: u32,
+}
+
+impl<'a> TypeNames<'a> {
+    fn resolve(&self, id: u32) -> Result<&'a Type<PortableForm>> {
+        self.registry
+            .resolve(id)
+            .with_context(|| format!("Type {id} not found in the registry"))
+    }
+
+    fn name(&self, id: u32) -> Result<String> {
+        let ty = self.resolve(id)?;
+        if let Some(name) = ty.path.segments.last() {
+            let params = ty
+                .type_params
+                .iter()
+                .filter_map(|param| param.ty.as_ref())
+                .map(|param| self.name(param.id))
+                .collect::<Result<Vec<_>>>()?;
+            if params.is_empty() {
+                return Ok(name.clone())
+            }
+            return Ok(format!("{name}<{}>", params.join(", ")))
+        }
+        match &ty.type_def {
+            TypeDef::Composite(composite) => {
+                let fields = composite
+                    .fields
+                    .iter()
+                    .map(|f| self.name(f.ty.id))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(format!("({})", fields.join(", ")))
+            }
+            TypeDef::Variant(_) => Ok("enum".into()),
+            TypeDef::Sequence(sequence) => {
+                Ok(format!("Vec<{}>", self.name(sequence.type_param.id)?))
+            }
+            TypeDef::Array(array) => {
+                Ok(format!("[{}; {}]", self.name(array.type_param.id)?, array.len))
+            }
+            TypeDef::Tuple(tuple) => {
+                let fields = tuple
+                    .fields
+                    .iter()
+                    .map(|f| self.name(f.id))
+                    .collect::<Result<Vec<_>>>()?;
+                match fields.as_slice() {
+                    [field] => Ok(format!("({field},)")),
+                    fields => Ok(format!("({})", fields.join(", "))),
+                }
+            }
+            TypeDef::Primitive(primitive) => Ok(primitive_name(primitive).into()),
+            TypeDef::Compact(compact) => {
+                Ok(format!("Compact<{}>", self.name(compact.type_param.id)?))
+            }
+            TypeDef::BitSequence(_) => Ok("BitVec".into()),
+        }
+    }
+
+    /// Returns the name of the type returned by a message or constructor, without the
+    /// `Result` wrapping the `LangError` of every message and constructor.
+    fn returned(&self, id: u32) -> Result<String> {
+        self.name(self.strip_lang_error(id)?)
+    }
+
+    /// Returns the type `E` returned as `Result<T, E>` by a message or constructor.
+    fn error_type(&self, id: u32) -> Result<Option<u32>> {
+        let ty = self.resolve(self.strip_lang_error(id)?)?;
+        Ok(result_types(ty).map(|(_, err)| err))
+    }
+
+    fn strip_lang_error(&self, id: u32) -> Result<u32> {
+        let ty = self.resolve(id)?;
+        match result_types(ty) {
+            Some((ok, err)) if err == self.lang_error => Ok(ok),
+            _ => Ok(id),
+        }
+    }
+
+    /// Returns the fields of an enum variant, e.g. `(AccountId)` or `{ to: AccountId }`.
+    fn fields(&self, fields: &[Field<PortableForm>]) -> Result<Cell> {
+        if fields.is_empty() {
+            return Ok(Cell::Text(String::new()))
+        }
+        let named = fields.iter().all(|f| f.name.is_some());
+        let fields = fields
+            .iter()
+            .map(|f| {
+                let ty = self.name(f.ty.id)?;
+                Ok(match &f.name {
+                    Some(name) if named => format!("{name}: {ty}"),
+                    _ => ty,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if named {
+            Ok(Cell::Code(format!("{{ {} }}", fields.join(", "))))
+        } else {
+            Ok(Cell::Code(format!("({})", fields.join(", "))))
+        }
+    }
+}
+
+/// Returns the ids of the `Ok` and `Err` types if `ty` is a `Result`.
+fn result_types(ty: &Type<PortableForm>) -> Option<(u32, u32)> {
+    if ty.path.segments.last().map(String::as_str) != Some("Result") {
+        return None
+    }
+    let TypeDef::Variant(variant) = &ty.type_def else {
+        return None
+    };
+    let field = |name: &str| {
+        variant
+            .variants
+            .iter()
+            .find(|v| v.name == name)
+            .and_then(|v| v.fields.first())
+            .map(|f| f.ty.id)
+    };
+    Some((field("Ok")?, field("Err")?))
+}
+
+fn primitive_name(primitive: &TypeDefPrimitive) -> &'static str {
+    match primitive {
+        TypeDefPrimitive::Bool => "bool",
+        TypeDefPrimitive::Char => "char",
+        TypeDefPrimitive::Str => "String",
+        TypeDefPrimitive::U8 => "u8",
+        TypeDefPrimitive::U16 => "u16",
+        TypeDefPrimitive::U32 => "u32",
+        TypeDefPrimitive::U64 => "u64",
+        TypeDefPrimitive::U128 => "u128",
+        TypeDefPrimitive::U256 => "u256",
+        TypeDefPrimitive::I8 => "i8",
+        TypeDefPrimitive::I16 => "i16",
+        TypeDefPrimitive::I32 => "i32",
+        TypeDefPrimitive::I64 => "i64",
+        TypeDefPrimitive::I128 => "i128",
+        TypeDefPrimitive::I256 => "i256",
+    }
+}
+
+fn selector(selector: &Selector) -> String {
+    format!("0x{}", hex::encode(selector.to_bytes()))
+}
+
+fn yes_no(value: bool) -> Cell {
+    Cell::Text(if value { "yes" } else { "no" }.into())
+}
+
+/// Splits the `docs` into paragraphs separated by empty lines.
+fn paragraphs(docs: &[String]) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut paragraph = Vec::new();
+    for line in docs.iter().flat_map(|doc| doc.split('\n')).map(str::trim) {
+        if line.is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph.join(" "));
+                paragraph.clear();
+            }
+        } else {
+            paragraph.push(line);
+        }
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph.join(" "));
+    }
+    paragraphs
+}
+
+/// The content of a table cell or the value of a property.
+enum Cell {
+    Code(String),
+    Text(String),
+}
+
+/// Writes the elements of the documentation in an output format.
+trait Renderer {
+    /// Writes a heading of the `level`, with the `name` of an item formatted as code.
+    fn heading(&mut self, level: usize, name: &str) -> Result<()>;
+    /// Writes the heading of a section, e.g. `Messages`.
+    fn section(&mut self, title: &str) -> Result<()>;
+    fn docs(&mut self, docs: &[String]) -> Result<()>;
+    fn properties(&mut self, properties: &[(&str, Cell)]) -> Result<()>;
+    fn table(&mut self, header: &[&str], rows: &[Vec<Cell>]) -> Result<()>;
+    fn finish(self) -> Result<String>;
+}
+
+#[derive(Default)]
+struct Markdown {
+    out: String,
+}
+
+impl Markdown {
+    fn cell(cell: &Cell) -> String {
+        match cell {
+            Cell::Code(code) => format!("`{}`", code.replace('|', "\\|")),
+            Cell::Text(text) => text.replace('|', "\\|"),
+        }
+    }
+}
+
+impl Renderer for Markdown {
+    fn heading(&mut self, level: usize, name: &str) -> Result<()> {
+        writeln!(self.out, "{} `{name}`\n", "#".repeat(level))?;
+        Ok(())
+    }
+
+    fn section(&mut self, title: &str) -> Result<()> {
+        writeln!(self.out, "## {title}\n")?;
+        Ok(())
+    }
+
+    fn docs(&mut self, docs: &[String]) -> Result<()> {
+        for paragraph in paragraphs(docs) {
+            writeln!(self.out, "{paragraph}\n")?;
+        }
+        Ok(())
+    }
+
+    fn properties(&mut self, properties: &[(&str, Cell)]) -> Result<()> {
+        for (name, value) in properties {
+            writeln!(self.out, "- **{name}:** {}", Self::cell(value))?;
+        }
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    fn table(&mut self, header: &[&str], rows: &[Vec<Cell>]) -> Result<()> {
+        writeln!(self.out, "| {} |", header.join(" | "))?;
+        writeln!(self.out, "|{}", " --- |".repeat(header.len()))?;
+        for row in rows {
+            let cells = row.iter().map(Self::cell).collect::<Vec<_>>();
+            writeln!(self.out, "| {} |", cells.join(" | "))?;
+        }
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<String> {
+        Ok(format!("{}\n", self.out.trim_end()))
+    }
+}
+
+struct Html {
+    out: String,
+}
+
+impl Html {
+    fn new(name: &str) -> Self {
+        Self {
+            out: format!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+                 <title>{}</title>\n</head>\n<body>\n",
+                escape(name)
+            ),
+        }
+    }
+
+    fn cell(cell: &Cell) -> String {
+        match cell {
+            Cell::Code(code) => format!("<code>{}</code>", escape(code)),
+            Cell::Text(text) => escape(text),
+        }
+    }
+}
+
+impl Renderer for Html {
+    fn heading(&mut self, level: usize, name: &str) -> Result<()> {
+        writeln!(self.out, "<h{level}><code>{}</code></h{level}>", escape(name))?;
+        Ok(())
+    }
+
+    fn section(&mut self, title: &str) -> Result<()> {
+        writeln!(self.out, "<h2>{}</h2>", escape(title))?;
+        Ok(())
+    }
+
+    fn docs(&mut self, docs: &[String]) -> Result<()> {
+        for paragraph in paragraphs(docs) {
+            writeln!(self.out, "<p>{}</p>", escape(&paragraph))?;
+        }
+        Ok(())
+    }
+
+    fn properties(&mut self, properties: &[(&str, Cell)]) -> Result<()> {
+        writeln!(self.out, "<ul>")?;
+        for (name, value) in properties {
+            writeln!(
+                self.out,
+                "<li><strong>{}:</strong> {}</li>",
+                escape(name),
+                Self::cell(value)
+            )?;
+        }
+        writeln!(self.out, "</ul>")?;
+        Ok(())
+    }
+
+    fn table(&mut self, header: &[&str], rows: &[Vec<Cell>]) -> Result<()> {
+        writeln!(self.out, "<table>")?;
+        let header = header
+            .iter()
+            .map(|h| format!("<th>{}</th>", escape(h)))
+            .collect::<String>();
+        writeln!(self.out, "<thead><tr>{header}</tr></thead>")?;
+        writeln!(self.out, "<tbody>")?;
+        for row in rows {
+            let cells = row
+                .iter()
+                .map(|cell| format!("<td>{}</td>", Self::cell(cell)))
+                .collect::<String>();
+            writeln!(self.out, "<tr>{cells}</tr>")?;
+        }
+        writeln!(self.out, "</tbody>\n</table>")?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<String> {
+        writeln!(self.out, "</body>\n</html>")?;
+        Ok(self.out)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
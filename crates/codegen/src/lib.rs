@@ -0,0 +1,106 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generation of client bindings and documentation from the metadata of ink! contracts.
+
+mod docs;
+mod typescript;
+
+use anyhow::Result;
+use ink_metadata::InkProject;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    str::FromStr,
+};
+
+/// The language of the generated client bindings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Language {
+    TypeScript,
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::TypeScript => write!(f, "ts"),
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ts" | "typescript" => Ok(Self::TypeScript),
+            _ => Err(format!("Unknown language '{s}', expected ts")),
+        }
+    }
+}
+
+/// The output format of the generated documentation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl Display for DocFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Markdown => write!(f, "md"),
+            Self::Html => write!(f, "html"),
+        }
+    }
+}
+
+impl FromStr for DocFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(format!("Unknown format '{s}', expected md or html")),
+        }
+    }
+}
+
+/// Generates the client bindings in the `language` for the contract `name` from its
+/// `metadata`.
+pub fn generate_client(
+    name: &str,
+    metadata: &InkProject,
+    language: Language,
+) -> Result<String> {
+    match language {
+        Language::TypeScript => typescript::generate(name, metadata),
+    }
+}
+
+/// Generates the reference documentation of the constructors, messages, events and
+/// errors of the contract `name` from its `metadata`.
+pub fn generate_docs(
+    name: &str,
+    metadata: &InkProject,
+    format: DocFormat,
+) -> Result<String> {
+    docs::generate(name, metadata, format)
+}
@@ -0,0 +1,545 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generation of TypeScript modules.
+//!
+//! The values of the types follow the JSON representation accepted by `@polkadot/api`:
+//! structs are objects, enums are either the name of a unit variant or an object with
+//! the variant name as the only key, and `Vec<u8>`, byte arrays and account ids are
+//! strings.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use heck::{
+    ToLowerCamelCase,
+    ToUpperCamelCase,
+};
+use ink_metadata::{
+    InkProject,
+    MessageParamSpec,
+    Selector,
+};
+use scale_info::{
+    form::PortableForm,
+    Field,
+    PortableRegistry,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+    Variant,
+};
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashMap,
+    },
+    fmt::Write,
+};
+
+/// The last path segments of types represented as strings.
+const STRING_TYPES: [&str; 4] = ["AccountId", "Hash", "H160", "H256"];
+
+/// Reserved words of TypeScript which are valid Rust identifiers.
+const RESERVED_WORDS: [&str; 14] = [
+    "case",
+    "catch",
+    "class",
+    "default",
+    "delete",
+    "function",
+    "instanceof",
+    "new",
+    "null",
+    "switch",
+    "this",
+    "typeof",
+    "var",
+    "void",
+];
+
+/// Generates a TypeScript module for the contract `name` from its `metadata`.
+pub fn generate(name: &str, metadata: &InkProject) -> Result<String> {
+    let spec = metadata.spec();
+    let roots = spec
+        .constructors()
+        .iter()
+        .flat_map(|c| c.args().iter().map(param_type))
+        .chain(spec.messages().iter().flat_map(|m| {
+            m.args()
+                .iter()
+                .map(param_type)
+                .chain([m.return_type().ret_type().ty().id])
+        }))
+        .chain(
+            spec.events()
+                .iter()
+                .flat_map(|e| e.args().iter().map(|a| a.ty().ty().id)),
+        );
+    let types = Types::collect(metadata.registry(), roots)?;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// This file was generated by `cargo contract generate-client` from the \
+         metadata\n// of the `{name}` contract. Do not edit it manually."
+    )?;
+
+    for (id, type_name) in &types.names {
+        let ty = types.resolve(*id)?;
+        writeln!(out)?;
+        write_docs(&mut out, "", &ty.docs)?;
+        types.write_declaration(&mut out, type_name, ty)?;
+    }
+
+    for event in spec.events() {
+        writeln!(out)?;
+        write_docs(&mut out, "", event.docs())?;
+        writeln!(out, "export interface {}Event {{", event.label())?;
+        for arg in event.args() {
+            write_docs(&mut out, "  ", arg.docs())?;
+            writeln!(out, "  {}: {};", arg.label(), types.expr(arg.ty().ty().id)?)?;
+        }
+        writeln!(out, "}}")?;
+    }
+
+    out.push_str(
+        r#"
+export interface ConstructorSpec {
+  readonly label: string;
+  readonly selector: string;
+  readonly payable: boolean;
+  readonly args: readonly string[];
+}
+
+export interface MessageSpec extends ConstructorSpec {
+  readonly mutates: boolean;
+}
+"#,
+    );
+
+    writeln!(out, "\nexport const constructors = {{")?;
+    for constructor in spec.constructors() {
+        writeln!(
+            out,
+            "  {:?}: {{ label: {:?}, selector: {:?}, payable: {}, args: [{}] }},",
+            constructor.label(),
+            constructor.label(),
+            selector(constructor.selector()),
+            constructor.payable(),
+            arg_labels(constructor.args()),
+        )?;
+    }
+    writeln!(out, "}} as const;")?;
+
+    writeln!(out, "\nexport const messages = {{")?;
+    for message in spec.messages() {
+        writeln!(
+            out,
+            "  {:?}: {{ label: {:?}, selector: {:?}, mutates: {}, payable: {}, \
+             args: [{}] }},",
+            message.label(),
+            message.label(),
+            selector(message.selector()),
+            message.mutates(),
+            message.payable(),
+            arg_labels(message.args()),
+        )?;
+    }
+    writeln!(out, "}} as const;")?;
+
+    writeln!(out, "\n/** The arguments of the constructors. */")?;
+    writeln!(out, "export interface ConstructorArgs {{")?;
+    for constructor in spec.constructors() {
+        let args = types.arg_tuple(constructor.args())?;
+        writeln!(out, "  {:?}: [{args}];", constructor.label())?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out, "\n/** The arguments of the messages. */")?;
+    writeln!(out, "export interface MessageArgs {{")?;
+    for message in spec.messages() {
+        let args = types.arg_tuple(message.args())?;
+        writeln!(out, "  {:?}: [{args}];", message.label())?;
+    }
+    writeln!(out, "}}")?;
+
+    writeln!(out, "\n/** The return types of the messages. */")?;
+    writeln!(out, "export interface MessageReturns {{")?;
+    for message in spec.messages() {
+        let ret = types.expr(message.return_type().ret_type().ty().id)?;
+        writeln!(out, "  {:?}: {ret};", message.label())?;
+    }
+    writeln!(out, "}}")?;
+
+    out.push_str(
+        r#"
+/**
+ * Dry-runs and submits messages of the contract, e.g. by calling a `ContractPromise` of
+ * `@polkadot/api-contract` or a contract client of `polkadot-api`.
+ */
+export interface ContractCaller {
+  /** Dry-runs the message and returns its decoded result. */
+  query(message: MessageSpec, args: unknown[]): Promise<unknown>;
+  /** Submits the message in an extrinsic. */
+  tx(message: MessageSpec, args: unknown[]): Promise<unknown>;
+}
+"#,
+    );
+
+    writeln!(out, "\nexport class {}Client {{", name.to_upper_camel_case())?;
+    writeln!(out, "  constructor(readonly caller: ContractCaller) {{}}")?;
+    for message in spec.messages() {
+        let label = message.label();
+        let params = types.arg_tuple(message.args())?;
+        let args = message.args().iter().map(arg_name).collect::<Vec<_>>().join(", ");
+        writeln!(out)?;
+        write_docs(&mut out, "  ", message.docs())?;
+        if message.mutates() {
+            writeln!(out, "  {}({params}): Promise<unknown> {{", method_name(label))?;
+            writeln!(out, "    return this.caller.tx(messages[{label:?}], [{args}]);")?;
+        } else {
+            let ret = format!("MessageReturns[{label:?}]");
+            writeln!(out, "  {}({params}): Promise<{ret}> {{", method_name(label))?;
+            writeln!(
+                out,
+                "    return this.caller.query(messages[{label:?}], [{args}]) as \
+                 Promise<{ret}>;"
+            )?;
+        }
+        writeln!(out, "  }}")?;
+    }
+    writeln!(out, "}}")?;
+
+    Ok(out)
+}
+
+/// The TypeScript names of the named types reachable from the constructors, messages
+/// and events.
+struct Types<'a> {
+    registry: &'a PortableRegistry,
+    names: BTreeMap<u32, String>,
+}
+
+impl<'a> Types<'a> {
+    /// Collects the named types reachable from the `roots`.
+    ///
+    /// Types are named after the last segment of their path, suffixed with their id if
+    /// several types share the same name, e.g. different instances of a generic type.
+    fn collect(
+        registry: &'a PortableRegistry,
+        roots: impl IntoIterator<Item = u32>,
+    ) -> Result<Self> {
+        let mut types = Self {
+            registry,
+            names: BTreeMap::new(),
+        };
+        let mut visited = BTreeSet::new();
+        let mut pending: Vec<u32> = roots.into_iter().collect();
+        while let Some(id) = pending.pop() {
+            if !visited.insert(id) {
+                continue
+            }
+            let ty = types.resolve(id)?;
+            if let Some(name) = declared_name(ty) {
+                types.names.insert(id, name.to_upper_camel_case());
+            }
+            pending.extend(referenced_types(ty));
+        }
+
+        let mut counts = HashMap::<String, usize>::new();
+        for name in types.names.values() {
+            *counts.entry(name.clone()).or_default() += 1;
+        }
+        for (id, name) in types.names.iter_mut() {
+            if counts[name.as_str()] > 1 {
+                name.push_str(&id.to_string());
+            }
+        }
+        Ok(types)
+    }
+
+    fn resolve(&self, id: u32) -> Result<&'a Type<PortableForm>> {
+        self.registry
+            .resolve(id)
+            .with_context(|| format!("Type {id} not found in the registry"))
+    }
+
+    /// Returns the TypeScript type of the type `id`.
+    fn expr(&self, id: u32) -> Result<String> {
+        if let Some(name) = self.names.get(&id) {
+            return Ok(name.clone())
+        }
+        let ty = self.resolve(id)?;
+        match ty.path.segments.last().map(String::as_str) {
+            Some("Option") => {
+                return Ok(format!("{} | null", self.variant_field(ty, "Some")?))
+            }
+            Some("Result") => {
+                return Ok(format!(
+                    "{{ Ok: {} }} | {{ Err: {} }}",
+                    self.variant_field(ty, "Ok")?,
+                    self.variant_field(ty, "Err")?
+                ))
+            }
+            Some(name) if STRING_TYPES.contains(&name) => return Ok("string".into()),
+            _ => {}
+        }
+        match &ty.type_def {
+            TypeDef::Composite(composite) => self.fields(&composite.fields),
+            TypeDef::Variant(variant) => {
+                let variants = variant
+                    .variants
+                    .iter()
+                    .map(|v| self.variant(v))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(if variants.is_empty() {
+                    "never".into()
+                } else {
+                    variants.join(" | ")
+                })
+            }
+            TypeDef::Sequence(sequence) => self.array(sequence.type_param.id),
+            TypeDef::Array(array) => self.array(array.type_param.id),
+            TypeDef::Tuple(tuple) if tuple.fields.is_empty() => Ok("null".into()),
+            TypeDef::Tuple(tuple) => {
+                let fields = tuple
+                    .fields
+                    .iter()
+                    .map(|f| self.expr(f.id))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(format!("[{}]", fields.join(", ")))
+            }
+            TypeDef::Primitive(primitive) => Ok(primitive_type(primitive).into()),
+            TypeDef::Compact(compact) => self.expr(compact.type_param.id),
+            TypeDef::BitSequence(_) => Ok("Array<boolean>".into()),
+        }
+    }
+
+    /// Writes the declaration of the named type `ty`.
+    fn write_declaration(
+        &self,
+        out: &mut String,
+        name: &str,
+        ty: &Type<PortableForm>,
+    ) -> Result<()> {
+        match &ty.type_def {
+            TypeDef::Composite(composite)
+                if composite.fields.first().is_some_and(|f| f.name.is_some()) =>
+            {
+                writeln!(out, "export interface {name} {{")?;
+                for field in &composite.fields {
+                    write_docs(out, "  ", &field.docs)?;
+                    let ty = self.expr(field.ty.id)?;
+                    writeln!(out, "  {}: {ty};", field_name(field))?;
+                }
+                writeln!(out, "}}")?;
+            }
+            TypeDef::Variant(variant) if !variant.variants.is_empty() => {
+                writeln!(out, "export type {name} =")?;
+                for (i, v) in variant.variants.iter().enumerate() {
+                    let end = if i + 1 == variant.variants.len() { ";" } else { "" };
+                    writeln!(out, "  | {}{end}", self.variant(v)?)?;
+                }
+            }
+            _ => writeln!(out, "export type {name} = {};", self.definition(ty)?)?,
+        }
+        Ok(())
+    }
+
+    /// Returns the TypeScript type of the definition of `ty`, ignoring its own name.
+    fn definition(&self, ty: &Type<PortableForm>) -> Result<String> {
+        match &ty.type_def {
+            TypeDef::Composite(composite) => self.fields(&composite.fields),
+            TypeDef::Variant(_) => Ok("never".into()),
+            _ => unreachable!("only composites and variants are named types"),
+        }
+    }
+
+    fn fields(&self, fields: &[Field<PortableForm>]) -> Result<String> {
+        match fields {
+            [] => Ok("null".into()),
+            [field] if field.name.is_none() => self.expr(field.ty.id),
+            fields if fields[0].name.is_none() => {
+                let fields = fields
+                    .iter()
+                    .map(|f| self.expr(f.ty.id))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(format!("[{}]", fields.join(", ")))
+            }
+            fields => {
+                let fields = fields
+                    .iter()
+                    .map(|f| Ok(format!("{}: {}", field_name(f), self.expr(f.ty.id)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(format!("{{ {} }}", fields.join("; ")))
+            }
+        }
+    }
+
+    fn variant(&self, variant: &Variant<PortableForm>) -> Result<String> {
+        if variant.fields.is_empty() {
+            return Ok(format!("{:?}", variant.name))
+        }
+        Ok(format!("{{ {}: {} }}", variant.name, self.fields(&variant.fields)?))
+    }
+
+    /// Returns the type of the single field of the variant `name` of `ty`.
+    fn variant_field(&self, ty: &Type<PortableForm>, name: &str) -> Result<String> {
+        let TypeDef::Variant(variant) = &ty.type_def else {
+            anyhow::bail!("Expected {} to be an enum", ty.path)
+        };
+        let field = variant
+            .variants
+            .iter()
+            .find(|v| v.name == name)
+            .and_then(|v| v.fields.first())
+            .with_context(|| format!("Variant {name} of {} not found", ty.path))?;
+        self.expr(field.ty.id)
+    }
+
+    /// Returns the type of a sequence or an array of the type `id`, bytes are strings.
+    fn array(&self, id: u32) -> Result<String> {
+        let ty = self.resolve(id)?;
+        if matches!(ty.type_def, TypeDef::Primitive(TypeDefPrimitive::U8)) {
+            return Ok("string".into())
+        }
+        Ok(format!("Array<{}>", self.expr(id)?))
+    }
+
+    /// Returns the labeled `args`, e.g. `init_value: boolean`, used for both tuples and
+    /// parameters.
+    fn arg_tuple(&self, args: &[MessageParamSpec<PortableForm>]) -> Result<String> {
+        Ok(args
+            .iter()
+            .map(|arg| Ok(format!("{}: {}", arg_name(arg), self.expr(param_type(arg))?)))
+            .collect::<Result<Vec<_>>>()?
+            .join(", "))
+    }
+}
+
+/// Returns the name of the declaration of `ty`, if it is declared as a named type.
+fn declared_name(ty: &Type<PortableForm>) -> Option<&str> {
+    let name = ty.path.segments.last()?;
+    let named = matches!(ty.type_def, TypeDef::Composite(_) | TypeDef::Variant(_))
+        && !["Option", "Result"].contains(&name.as_str())
+        && !STRING_TYPES.contains(&name.as_str());
+    named.then_some(name.as_str())
+}
+
+/// Returns the ids of the types referenced by `ty`.
+fn referenced_types(ty: &Type<PortableForm>) -> Vec<u32> {
+    match &ty.type_def {
+        TypeDef::Composite(composite) => {
+            composite.fields.iter().map(|f| f.ty.id).collect()
+        }
+        TypeDef::Variant(variant) => {
+            variant
+                .variants
+                .iter()
+                .flat_map(|v| v.fields.iter().map(|f| f.ty.id))
+                .collect()
+        }
+        TypeDef::Sequence(sequence) => vec![sequence.type_param.id],
+        TypeDef::Array(array) => vec![array.type_param.id],
+        TypeDef::Tuple(tuple) => tuple.fields.iter().map(|f| f.id).collect(),
+        TypeDef::Compact(compact) => vec![compact.type_param.id],
+        TypeDef::Primitive(_) | TypeDef::BitSequence(_) => Vec::new(),
+    }
+}
+
+fn primitive_type(primitive: &TypeDefPrimitive) -> &'static str {
+    match primitive {
+        TypeDefPrimitive::Bool => "boolean",
+        TypeDefPrimitive::Char | TypeDefPrimitive::Str => "string",
+        TypeDefPrimitive::U8
+        | TypeDefPrimitive::U16
+        | TypeDefPrimitive::U32
+        | TypeDefPrimitive::I8
+        | TypeDefPrimitive::I16
+        | TypeDefPrimitive::I32 => "number",
+        TypeDefPrimitive::U64
+        | TypeDefPrimitive::U128
+        | TypeDefPrimitive::U256
+        | TypeDefPrimitive::I64
+        | TypeDefPrimitive::I128
+        | TypeDefPrimitive::I256 => "bigint",
+    }
+}
+
+fn param_type(arg: &MessageParamSpec<PortableForm>) -> u32 {
+    arg.ty().ty().id
+}
+
+fn arg_name(arg: &MessageParamSpec<PortableForm>) -> String {
+    identifier(arg.label())
+}
+
+fn field_name(field: &Field<PortableForm>) -> &str {
+    field.name.as_deref().unwrap_or_default()
+}
+
+fn arg_labels(args: &[MessageParamSpec<PortableForm>]) -> String {
+    args.iter()
+        .map(|arg| format!("{:?}", arg.label()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the name of the client method of the message `label`, e.g. `balanceOf` for
+/// `PSP22::balance_of`.
+fn method_name(label: &str) -> String {
+    identifier(&label.replace("::", "_").to_lower_camel_case())
+}
+
+/// Appends an `_` to reserved words.
+fn identifier(name: &str) -> String {
+    if RESERVED_WORDS.contains(&name) || name == "constructor" {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+fn selector(selector: &Selector) -> String {
+    format!("0x{}", hex::encode(selector.to_bytes()))
+}
+
+fn write_docs(out: &mut String, indent: &str, docs: &[String]) -> Result<()> {
+    let lines: Vec<_> = docs
+        .iter()
+        .flat_map(|doc| doc.split('\n'))
+        .map(str::trim)
+        .collect();
+    match lines.as_slice() {
+        [] => {}
+        [line] => writeln!(out, "{indent}/** {line} */")?,
+        lines => {
+            writeln!(out, "{indent}/**")?;
+            for line in lines {
+                if line.is_empty() {
+                    writeln!(out, "{indent} *")?;
+                } else {
+                    writeln!(out, "{indent} * {line}")?;
+                }
+            }
+            writeln!(out, "{indent} */")?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use contract_codegen::{
+    generate_client,
+    generate_docs,
+    DocFormat,
+    Language,
+};
+use ink::metadata::InkProject;
+use std::{
+    fs,
+    path::Path,
+};
+
+#[allow(
+    unexpected_cfgs,
+    clippy::extra_unused_lifetimes,
+    clippy::new_without_default
+)]
+#[ink::contract]
+pub mod registry {
+    use ink::{
+        prelude::{
+            string::String,
+            vec::Vec,
+        },
+        storage::Mapping,
+    };
+
+    /// An entry of the registry.
+    #[derive(Clone)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Entry {
+        /// The owner of the entry.
+        owner: AccountId,
+        value: Balance,
+        tags: Vec<String>,
+    }
+
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// The key is already taken.
+        Taken,
+        Unauthorized(AccountId),
+    }
+
+    #[ink(storage)]
+    pub struct Registry {
+        entries: Mapping<u32, Entry>,
+    }
+
+    /// Emitted when an entry is inserted.
+    #[ink(event)]
+    pub struct Inserted {
+        #[ink(topic)]
+        key: u32,
+        value: Balance,
+    }
+
+    impl Registry {
+        /// Creates an empty registry.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                entries: Mapping::new(),
+            }
+        }
+
+        /// Returns the entry of the `key`.
+        #[ink(message)]
+        pub fn get(&self, key: u32) -> Option<Entry> {
+            self.entries.get(key)
+        }
+
+        /// Inserts an entry owned by the caller.
+        ///
+        /// Fails if the `key` is already taken.
+        #[ink(message, payable)]
+        pub fn insert(
+            &mut self,
+            key: u32,
+            tags: Vec<String>,
+            hash: [u8; 32],
+        ) -> Result<(), Error> {
+            let _ = hash;
+            if self.entries.contains(key) {
+                return Err(Error::Taken)
+            }
+            let value = self.env().transferred_value();
+            let owner = self.env().caller();
+            self.entries.insert(key, &Entry { owner, value, tags });
+            self.env().emit_event(Inserted { key, value });
+            Ok(())
+        }
+    }
+}
+
+fn generate_metadata() -> InkProject {
+    extern "Rust" {
+        fn __ink_generate_metadata() -> InkProject;
+    }
+
+    unsafe { __ink_generate_metadata() }
+}
+
+/// Compares the `actual` output with the snapshot file, which is updated instead if
+/// `UPDATE_SNAPSHOTS` is set.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert_eq!(
+        expected,
+        actual,
+        "The output differs from {}, run with UPDATE_SNAPSHOTS=1 to update it",
+        path.display()
+    );
+}
+
+#[test]
+fn typescript_client() {
+    let metadata = generate_metadata();
+
+    let client = generate_client("registry", &metadata, Language::TypeScript).unwrap();
+
+    assert_snapshot("registry.ts", &client);
+}
+
+#[test]
+fn markdown_docs() {
+    let metadata = generate_metadata();
+
+    let docs = generate_docs("registry", &metadata, DocFormat::Markdown).unwrap();
+
+    assert_snapshot("registry.md", &docs);
+}
+
+#[test]
+fn html_docs() {
+    let metadata = generate_metadata();
+
+    let docs = generate_docs("registry", &metadata, DocFormat::Html).unwrap();
+
+    assert_snapshot("registry.html", &docs);
+}
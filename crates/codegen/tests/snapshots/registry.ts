@@ -0,0 +1,87 @@
+// This file was generated by `cargo contract generate-client` from the metadata
+// of the `registry` contract. Do not edit it manually.
+
+export interface Entry {
+  owner: string;
+  value: bigint;
+  tags: Array<string>;
+}
+
+export type LangError =
+  | "CouldNotReadInput";
+
+export type Error =
+  | "Taken"
+  | { Unauthorized: string };
+
+/** Emitted when an entry is inserted. */
+export interface InsertedEvent {
+  key: number;
+  value: bigint;
+}
+
+export interface ConstructorSpec {
+  readonly label: string;
+  readonly selector: string;
+  readonly payable: boolean;
+  readonly args: readonly string[];
+}
+
+export interface MessageSpec extends ConstructorSpec {
+  readonly mutates: boolean;
+}
+
+export const constructors = {
+  "new": { label: "new", selector: "0x9bae9d5e", payable: false, args: [] },
+} as const;
+
+export const messages = {
+  "get": { label: "get", selector: "0x2f865bd9", mutates: false, payable: false, args: ["key"] },
+  "insert": { label: "insert", selector: "0xd7f8b4af", mutates: true, payable: true, args: ["key", "tags", "hash"] },
+} as const;
+
+/** The arguments of the constructors. */
+export interface ConstructorArgs {
+  "new": [];
+}
+
+/** The arguments of the messages. */
+export interface MessageArgs {
+  "get": [key: number];
+  "insert": [key: number, tags: Array<string>, hash: string];
+}
+
+/** The return types of the messages. */
+export interface MessageReturns {
+  "get": { Ok: Entry | null } | { Err: LangError };
+  "insert": { Ok: { Ok: null } | { Err: Error } } | { Err: LangError };
+}
+
+/**
+ * Dry-runs and submits messages of the contract, e.g. by calling a `ContractPromise` of
+ * `@polkadot/api-contract` or a contract client of `polkadot-api`.
+ */
+export interface ContractCaller {
+  /** Dry-runs the message and returns its decoded result. */
+  query(message: MessageSpec, args: unknown[]): Promise<unknown>;
+  /** Submits the message in an extrinsic. */
+  tx(message: MessageSpec, args: unknown[]): Promise<unknown>;
+}
+
+export class RegistryClient {
+  constructor(readonly caller: ContractCaller) {}
+
+  /** Returns the entry of the `key`. */
+  get(key: number): Promise<MessageReturns["get"]> {
+    return this.caller.query(messages["get"], [key]) as Promise<MessageReturns["get"]>;
+  }
+
+  /**
+   * Inserts an entry owned by the caller.
+   *
+   * Fails if the `key` is already taken.
+   */
+  insert(key: number, tags: Array<string>, hash: string): Promise<unknown> {
+    return this.caller.tx(messages["insert"], [key, tags, hash]);
+  }
+}
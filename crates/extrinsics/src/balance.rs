@@ -26,10 +26,7 @@ use rust_decimal::{
 };
 use serde_json::json;
 use subxt::{
-    backend::{
-        legacy::LegacyRpcMethods,
-        rpc::RpcClient,
-    },
+    backend::legacy::LegacyRpcMethods,
     Config,
 };
 
@@ -40,7 +37,11 @@ use anyhow::{
 };
 use url::Url;
 
-use crate::url_to_string;
+use crate::{
+    rpc_client,
+    url_to_string,
+    RpcOptions,
+};
 
 /// Represents different formats of a balance
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -80,7 +81,16 @@ pub enum UnitPrefix {
 impl TokenMetadata {
     /// Query [TokenMetadata] through the node's RPC
     pub async fn query<C: Config>(url: &Url) -> Result<Self> {
-        let rpc_cli = RpcClient::from_url(url_to_string(url)).await?;
+        Self::query_with_options::<C>(url, RpcOptions::default()).await
+    }
+
+    /// Query [TokenMetadata] through the node's RPC, communicating with the node
+    /// according to `options`.
+    pub async fn query_with_options<C: Config>(
+        url: &Url,
+        options: RpcOptions,
+    ) -> Result<Self> {
+        let rpc_cli = rpc_client(&url_to_string(url), options).await?;
         let rpc = LegacyRpcMethods::<C>::new(rpc_cli.clone());
         let sys_props = rpc.system_properties().await?;
 
@@ -168,13 +178,14 @@ impl FromStr for DenominatedBalance {
 
 impl<Balance> BalanceVariant<Balance>
 where
-    Balance: From<u128> + Clone,
+    Balance: TryFrom<u128> + Clone,
 {
     /// Converts BalanceVariant into Balance.
     ///
     /// It is a reverse process of `from<T: Into<u128>>()`
     ///
-    /// Throws Error if `value` is of nigher precision that allowed.
+    /// Throws Error if `value` is of nigher precision that allowed, or exceeds the
+    /// `Balance` type, e.g. a `u64` of a chain with a custom `Environment`.
     ///
     /// ```rust
     /// use contract_extrinsics::{
@@ -236,7 +247,7 @@ where
                     .checked_mul(multiple)
                     .context("error while converting balance to raw format. Overflow during multiplication!")?
                     .try_into()?;
-                Ok(balance.into())
+                balance_from_u128(balance)
             }
         }
     }
@@ -356,11 +367,19 @@ where
 
             Ok(BalanceVariant::Denominated(den_balance))
         } else {
-            Ok(BalanceVariant::Default(n.into()))
+            Ok(BalanceVariant::Default(balance_from_u128(n)?))
         }
     }
 }
 
+/// Converts the `value` into the `Balance` type of the chain, which may be smaller than
+/// `u128`.
+fn balance_from_u128<Balance: TryFrom<u128>>(value: u128) -> Result<Balance> {
+    Balance::try_from(value).map_err(|_| {
+        anyhow!("The balance {value} exceeds the maximum balance of the chain")
+    })
+}
+
 impl<Balance> Display for BalanceVariant<Balance>
 where
     Balance: Display + Clone,
@@ -733,4 +752,18 @@ mod tests {
         });
         assert_eq!(sample, denominated_balance);
     }
+
+    #[test]
+    fn u64_balance_of_custom_environment() {
+        let tm = TokenMetadata {
+            token_decimals: 10,
+            symbol: String::from("DOT"),
+        };
+        let bv = BalanceVariant::<u64>::from_str("500.5MDOT").unwrap();
+        assert_eq!(bv.denominate_balance(&tm).unwrap(), 5_005_000_000_000_000_000);
+
+        let bv = BalanceVariant::<u64>::from_str("5GDOT").unwrap();
+        assert!(bv.denominate_balance(&tm).is_err());
+        assert!(BalanceVariant::<u64>::from(u128::from(u64::MAX) + 1, None).is_err());
+    }
 }
@@ -0,0 +1,234 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    estimate_fee,
+    pallet_contracts_primitives::ContractExecResult,
+    submit_extrinsic,
+    CallExec,
+    ErrorVariant,
+    ExtrinsicReceipt,
+};
+
+use anyhow::Result;
+use ink_env::Environment;
+use scale::{
+    Compact,
+    Encode,
+};
+use sp_weights::Weight;
+use subxt::{
+    blocks::ExtrinsicEvents,
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    tx,
+    Config,
+    Metadata,
+};
+
+/// Several contract calls, submitted together in a single `utility.batch_all`
+/// extrinsic.
+///
+/// The batch is atomic: if any of the calls fails, none of them is executed.
+pub struct BatchCallExec<C: Config, E: Environment, Signer: Clone> {
+    calls: Vec<CallExec<C, E, Signer>>,
+}
+
+impl<C: Config, E: Environment, Signer> BatchCallExec<C, E, Signer>
+where
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    C::AccountId: EncodeAsType + IntoVisitor,
+    E::Balance: EncodeAsType,
+    Signer: tx::Signer<C> + Clone,
+{
+    /// Creates a batch of the `calls`.
+    ///
+    /// The extrinsic is signed and submitted with the options of the first call.
+    pub fn new(calls: Vec<CallExec<C, E, Signer>>) -> Result<Self> {
+        if calls.is_empty() {
+            anyhow::bail!("A batch requires at least one call")
+        }
+        Ok(Self { calls })
+    }
+
+    /// Returns the calls of the batch.
+    pub fn calls(&self) -> &[CallExec<C, E, Signer>] {
+        &self.calls
+    }
+
+    /// Simulates each call of the batch.
+    ///
+    /// The calls are simulated independently against the current state, the effects of
+    /// the preceding calls in the batch are not taken into account.
+    pub async fn call_dry_run(&self) -> Result<Vec<ContractExecResult<E::Balance>>> {
+        let mut results = Vec::with_capacity(self.calls.len());
+        for call in &self.calls {
+            results.push(call.call_dry_run().await?);
+        }
+        Ok(results)
+    }
+
+    /// Estimates the gas required by each call of the batch, see
+    /// [`CallExec::estimate_gas`].
+    pub async fn estimate_gas(&self) -> Result<Vec<Weight>> {
+        let mut gas_limits = Vec::with_capacity(self.calls.len());
+        for call in &self.calls {
+            gas_limits.push(call.estimate_gas().await?);
+        }
+        Ok(gas_limits)
+    }
+
+    /// Submits the calls in a single `utility.batch_all` extrinsic, with the gas limit
+    /// of each call taken from `gas_limits`, or estimated if `None`.
+    ///
+    /// The events of the individual calls can be told apart with
+    /// [`DisplayEvents::split_batch_items`](crate::DisplayEvents::split_batch_items).
+    pub async fn call(
+        &self,
+        gas_limits: Option<Vec<Weight>>,
+    ) -> Result<BatchCallResult<C>, ErrorVariant> {
+        for call in &self.calls {
+            call.ensure_mutates()?;
+        }
+        let gas_limits = match gas_limits {
+            Some(gas_limits) => gas_limits,
+            None => self.estimate_gas().await?,
+        };
+        let mut storage_deposit_limits = Vec::with_capacity(self.calls.len());
+        for call in &self.calls {
+            storage_deposit_limits.push(call.estimate_storage_deposit_limit().await?);
+        }
+        let first = &self.calls[0];
+        let payload = self.payload(&gas_limits, &storage_deposit_limits)?;
+        tracing::debug!("submitting a batch of {} calls", payload.calls.len());
+
+        let (events, receipt) =
+            submit_extrinsic(first.client(), first.rpc(), &payload, first.opts()).await?;
+        Ok(BatchCallResult { events, receipt })
+    }
+
+    /// Estimates the fee of the batch with the gas limit of each call taken from
+    /// `gas_limits`, excluding the tip.
+    pub async fn estimate_fee(&self, gas_limits: &[Weight]) -> Result<u128> {
+        let first = &self.calls[0];
+        let storage_deposit_limits = self
+            .calls
+            .iter()
+            .map(|call| call.opts().storage_deposit_limit())
+            .collect::<Vec<_>>();
+        let payload = self.payload(gas_limits, &storage_deposit_limits)?;
+        Ok(estimate_fee(first.client(), &payload, first.opts()).await?)
+    }
+
+    /// Returns the payload of the `utility.batch_all` extrinsic.
+    fn payload(
+        &self,
+        gas_limits: &[Weight],
+        storage_deposit_limits: &[Option<E::Balance>],
+    ) -> Result<BatchAll> {
+        if gas_limits.len() != self.calls.len() {
+            anyhow::bail!(
+                "Expected {} gas limits, got {}",
+                self.calls.len(),
+                gas_limits.len()
+            )
+        }
+        let tx = self.calls[0].client().tx();
+        let calls = self
+            .calls
+            .iter()
+            .zip(gas_limits.iter().zip(storage_deposit_limits))
+            .map(|(call, (gas_limit, storage_deposit_limit))| {
+                tx.call_data(&call.call_payload(*gas_limit, *storage_deposit_limit))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(BatchAll { calls })
+    }
+}
+
+/// The result of submitting a batch of calls.
+pub struct BatchCallResult<C: Config> {
+    pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+}
+
+/// The payload of a `utility.batch_all` extrinsic, with the already encoded calls.
+struct BatchAll {
+    calls: Vec<Vec<u8>>,
+}
+
+impl tx::TxPayload for BatchAll {
+    fn encode_call_data_to(
+        &self,
+        metadata: &Metadata,
+        out: &mut Vec<u8>,
+    ) -> Result<(), subxt::Error> {
+        let pallet = metadata.pallet_by_name_err("Utility")?;
+        let call = pallet.call_variant_by_name("batch_all").ok_or_else(|| {
+            subxt::Error::Other("The Utility pallet has no batch_all call".into())
+        })?;
+        pallet.index().encode_to(out);
+        call.index.encode_to(out);
+        Compact(self.calls.len() as u32).encode_to(out);
+        for call in &self.calls {
+            out.extend_from_slice(call);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_metadata::{
+        self,
+        remark,
+        RuntimeCall,
+        UtilityCall,
+    };
+    use subxt::tx::TxPayload;
+
+    #[test]
+    fn wraps_calls_into_batch_all() {
+        let metadata = test_metadata::metadata();
+        let batch = BatchAll {
+            calls: vec![remark(&[1]).encode(), remark(&[2, 3]).encode()],
+        };
+
+        let call_data = batch.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Utility(UtilityCall::batch_all {
+            calls: vec![remark(&[1]), remark(&[2, 3])],
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn batch_all_requires_utility_pallet() {
+        let bytes = std::fs::read("src/test_runtime_api/metadata_v15.scale").unwrap();
+        let metadata = <Metadata as scale::Decode>::decode(&mut &bytes[..]).unwrap();
+        let batch = BatchAll { calls: Vec::new() };
+
+        assert!(batch.encode_call_data(&metadata).is_err());
+    }
+}
@@ -15,31 +15,50 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
-    pallet_contracts_primitives::ContractExecResult,
+    estimate_fee,
+    pallet_contracts_primitives::{
+        ContractExecResult,
+        StorageDeposit,
+    },
     state_call,
     submit_extrinsic,
     ContractMessageTranscoder,
+    ContractAddress,
+    ContractsBackend,
     ErrorVariant,
+    ExtrinsicReceipt,
 };
 use crate::{
+    call_wrapper,
     check_env_types,
+    contracts_backend,
     extrinsic_calls::Call,
     extrinsic_opts::ExtrinsicOpts,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
+    schedule::{
+        Schedule,
+        Scheduled,
+        ScheduledCallResult,
+    },
+    ss58,
 };
 
 use anyhow::{
     anyhow,
     Result,
 };
+use contract_transcode::{
+    SolidityAbi,
+    Value,
+};
 use ink_env::Environment;
 use scale::Encode;
 use sp_weights::Weight;
+use std::path::PathBuf;
 
 use subxt::{
-    backend::{
-        legacy::LegacyRpcMethods,
-        rpc::RpcClient,
-    },
     blocks::ExtrinsicEvents,
     config::{
         DefaultExtrinsicParams,
@@ -63,6 +82,8 @@ pub struct CallCommandBuilder<C: Config, E: Environment, Signer: Clone> {
     gas_limit: Option<u64>,
     proof_size: Option<u64>,
     value: E::Balance,
+    abi: Option<PathBuf>,
+    call_data: Option<Vec<u8>>,
 }
 
 impl<C: Config, E: Environment, Signer> CallCommandBuilder<C, E, Signer>
@@ -84,6 +105,8 @@ where
             gas_limit: None,
             proof_size: None,
             value: Default::default(),
+            abi: None,
+            call_data: None,
         }
     }
 
@@ -115,6 +138,26 @@ where
         this
     }
 
+    /// Sets the path to a Solidity ABI file describing the contract.
+    ///
+    /// If set, the message is encoded according to the Solidity ABI instead of the ink!
+    /// metadata, which allows calling non-ink! contracts, e.g. compiled with `solang`.
+    pub fn abi<T: Into<PathBuf>>(self, abi: Option<T>) -> Self {
+        let mut this = self;
+        this.abi = abi.map(Into::into);
+        this
+    }
+
+    /// Sets the encoded call data, the selector of the message followed by its SCALE
+    /// encoded arguments.
+    ///
+    /// If set, it is used as is instead of encoding the message and its arguments.
+    pub fn call_data(self, call_data: Option<Vec<u8>>) -> Self {
+        let mut this = self;
+        this.call_data = call_data;
+        this
+    }
+
     /// Preprocesses contract artifacts and options for subsequent contract calls.
     ///
     /// This function prepares the necessary data for making a contract call based on the
@@ -125,17 +168,42 @@ where
     /// Returns the `CallExec` containing the preprocessed data for the contract call,
     /// or an error in case of failure.
     pub async fn done(self) -> Result<CallExec<C, E, Signer>> {
-        let artifacts = self.extrinsic_opts.contract_artifacts()?;
-        let transcoder = artifacts.contract_transcoder()?;
-
-        let call_data = transcoder.encode(&self.message, &self.args)?;
-        tracing::debug!("Message data: {:?}", hex::encode(&call_data));
+        let (transcoder, abi) = match self.abi.as_ref() {
+            Some(abi_path) => (None, Some(SolidityAbi::load(abi_path)?)),
+            None => {
+                let artifacts = self.extrinsic_opts.contract_artifacts()?;
+                (Some(artifacts.contract_transcoder()?), None)
+            }
+        };
 
         let url = self.extrinsic_opts.url();
-        let rpc = RpcClient::from_url(&url).await?;
-        let client = OnlineClient::from_rpc_client(rpc.clone()).await?;
-        let rpc = LegacyRpcMethods::new(rpc);
-        check_env_types(&client, &transcoder, self.extrinsic_opts.verbosity())?;
+        let rpc = rpc_client(&url, self.extrinsic_opts.rpc_options()).await?;
+        let client =
+            online_client(rpc.clone(), self.extrinsic_opts.rpc_options()).await?;
+        let backend = contracts_backend(&client.metadata())?;
+        let rpc = NodeRpc::new(rpc);
+        if let Some(transcoder) = transcoder.as_ref() {
+            check_env_types(&client, transcoder, self.extrinsic_opts.verbosity())?;
+        }
+        let ss58_prefix = ss58::ss58_prefix(
+            self.extrinsic_opts.ss58_prefix(),
+            Some(&client.metadata()),
+        );
+        // only `pallet-revive` maps Ethereum style addresses to accounts
+        let transcoder = transcoder.map(|transcoder| {
+            transcoder
+                .with_ss58_prefix(ss58_prefix)
+                .with_eth_addresses(backend.requires_account_mapping())
+        });
+        let call_data = match (self.call_data, &transcoder, &abi) {
+            (Some(call_data), _, _) => call_data,
+            (None, Some(transcoder), _) => {
+                transcoder.encode(&self.message, &self.args)?
+            }
+            (None, None, Some(abi)) => abi.encode(&self.message, &self.args)?,
+            (None, None, None) => unreachable!("either a transcoder or an ABI is loaded"),
+        };
+        tracing::debug!("Message data: {:?}", hex::encode(&call_data));
 
         Ok(CallExec {
             contract: self.contract,
@@ -147,8 +215,11 @@ where
             value: self.value,
             rpc,
             client,
+            backend,
             transcoder,
+            abi,
             call_data,
+            ss58_prefix,
         })
     }
 }
@@ -161,10 +232,13 @@ pub struct CallExec<C: Config, E: Environment, Signer: Clone> {
     gas_limit: Option<u64>,
     proof_size: Option<u64>,
     value: E::Balance,
-    rpc: LegacyRpcMethods<C>,
+    rpc: NodeRpc<C>,
     client: OnlineClient<C>,
-    transcoder: ContractMessageTranscoder,
+    backend: &'static dyn ContractsBackend,
+    transcoder: Option<ContractMessageTranscoder>,
+    abi: Option<SolidityAbi>,
     call_data: Vec<u8>,
+    ss58_prefix: u16,
 }
 
 impl<C: Config, E: Environment, Signer> CallExec<C, E, Signer>
@@ -185,16 +259,54 @@ where
     /// Returns the dry run simulation result of type [`ContractExecResult`], which
     /// includes information about the simulated call, or an error in case of failure.
     pub async fn call_dry_run(&self) -> Result<ContractExecResult<E::Balance>> {
+        self.call_dry_run_with_input(self.call_data.clone()).await
+    }
+
+    /// Dry-runs the call and returns the weight and the storage deposit it requires.
+    ///
+    /// Fails with the error of the call if it fails, but not if it reverts.
+    pub async fn call_dry_run_cost(&self) -> Result<DryRunCost, ErrorVariant>
+    where
+        E::Balance: Into<u128>,
+    {
+        let result = self.call_dry_run().await?;
+        if let Err(ref err) = result.result {
+            return Err(ErrorVariant::from_dispatch_error(
+                err,
+                &self.client.metadata(),
+            )?)
+        }
+        Ok(DryRunCost {
+            ref_time: result.gas_required.ref_time(),
+            proof_size: result.gas_required.proof_size(),
+            storage_deposit: match result.storage_deposit {
+                StorageDeposit::Charge(charge) => charge.into() as i128,
+                StorageDeposit::Refund(refund) => -(refund.into() as i128),
+            },
+        })
+    }
+
+    /// Simulates the call like [`Self::call_dry_run`], but with the given `input_data`
+    /// instead of the encoded message, e.g. to fuzz the contract with arbitrary inputs.
+    pub async fn call_dry_run_with_input(
+        &self,
+        input_data: Vec<u8>,
+    ) -> Result<ContractExecResult<E::Balance>> {
         let storage_deposit_limit = self.opts.storage_deposit_limit();
         let call_request = CallRequest {
             origin: self.opts.signer().account_id(),
-            dest: self.contract.clone(),
+            dest: self.backend.contract_address(&self.contract.encode()),
             value: self.value,
             gas_limit: None,
             storage_deposit_limit,
-            input_data: self.call_data.clone(),
+            input_data,
         };
-        state_call(&self.rpc, "ContractsApi_call", call_request).await
+        state_call(
+            &self.rpc,
+            &self.backend.runtime_api_method("call"),
+            call_request,
+        )
+        .await
     }
 
     /// Calls a contract on the blockchain with a specified gas limit.
@@ -203,50 +315,191 @@ where
     /// limit for the operation. It interacts with the blockchain's runtime API to
     /// execute the contract call and provides the resulting events from the call.
     ///
-    /// Returns the events generated from the contract call, or an error in case of
-    /// failure.
+    /// Returns the `CallResult` containing the events generated from the contract call,
+    /// or an error in case of failure.
     pub async fn call(
         &self,
         gas_limit: Option<Weight>,
-    ) -> Result<ExtrinsicEvents<C>, ErrorVariant> {
-        if !self
-            .transcoder()
-            .metadata()
-            .spec()
-            .messages()
-            .iter()
-            .find(|msg| msg.label() == &self.message)
-            .expect("message exist after calling CallExec::done()")
-            .mutates()
-        {
-            let inner = anyhow!(
-                "Tried to execute a call on the immutable contract message '{}'. Please do a dry-run instead.",
-                &self.message
-            );
-            return Err(inner.into())
-        }
+    ) -> Result<CallResult<C>, ErrorVariant> {
+        self.ensure_mutates()?;
 
         // use user specified values where provided, otherwise estimate
         let gas_limit = match gas_limit {
             Some(gas_limit) => gas_limit,
             None => self.estimate_gas().await?,
         };
+        let storage_deposit_limit = self.estimate_storage_deposit_limit().await?;
         tracing::debug!("calling contract {:?}", self.contract);
-        let storage_deposit_limit = self.opts.storage_deposit_limit();
+        let call = self.call_payload(gas_limit, storage_deposit_limit);
+
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
 
-        let call = Call::new(
-            self.contract.clone().into(),
+        Ok(CallResult { events, receipt })
+    }
+
+    /// Schedules the call for execution at block `when` with a `scheduler.schedule`
+    /// extrinsic, instead of executing it right away.
+    ///
+    /// `scheduler.schedule` is restricted to the root origin, so the extrinsic must be
+    /// wrapped into a `sudo` call with
+    /// [`ExtrinsicOptsBuilder::wrap_call`](crate::ExtrinsicOptsBuilder::wrap_call).
+    ///
+    /// The scheduler dispatches calls with the root origin, so the contract call is
+    /// wrapped into a `utility.dispatch_as` call to execute it with the signed origin of
+    /// the signer instead, i.e. the contract sees the signer as its `caller()`. Fails if
+    /// the chain has no `Utility` pallet.
+    pub async fn schedule(
+        &self,
+        when: u64,
+        gas_limit: Option<Weight>,
+    ) -> Result<ScheduledCallResult<C>, ErrorVariant> {
+        self.ensure_mutates()?;
+        if self.opts.call_wrapper().is_none() {
+            return Err(anyhow!(
+                "Scheduling a call requires the root origin, wrap it into a `sudo` call"
+            )
+            .into())
+        }
+        let latest: u64 = self.client.blocks().at_latest().await?.number().into();
+        if when <= latest {
+            return Err(anyhow!(
+                "Cannot schedule the call at block {when}, the latest block is {latest}"
+            )
+            .into())
+        }
+
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas().await?,
+        };
+        let storage_deposit_limit = self.estimate_storage_deposit_limit().await?;
+        tracing::debug!("scheduling call of contract {:?} at {when}", self.contract);
+        let call = call_wrapper::into_dynamic(
+            &self.client,
+            &self.call_payload(gas_limit, storage_deposit_limit),
+        )?;
+        let signer = tx::Signer::account_id(self.opts.signer());
+        let call = self
+            .client
+            .tx()
+            .call_data(&call_wrapper::dispatch_as(&signer)(call))?;
+
+        let (events, receipt) = submit_extrinsic(
+            &self.client,
+            &self.rpc,
+            &Schedule { when, call },
+            &self.opts,
+        )
+        .await?;
+        let scheduled = events
+            .find_first::<Scheduled>()?
+            .ok_or_else(|| anyhow!("No Scheduler::Scheduled event found"))?;
+
+        Ok(ScheduledCallResult {
+            events,
+            receipt,
+            when: scheduled.when,
+            index: scheduled.index,
+        })
+    }
+
+    /// Returns the `Contracts::call` extrinsic of the call as encoded for the chain,
+    /// e.g. to dispatch it from another chain with an XCM `Transact`.
+    ///
+    /// The gas limit and the storage deposit limit are estimated with a dry-run unless
+    /// they are passed.
+    pub async fn encoded_call(
+        &self,
+        gas_limit: Option<Weight>,
+    ) -> Result<Vec<u8>, ErrorVariant> {
+        self.ensure_mutates()?;
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas().await?,
+        };
+        let storage_deposit_limit = self.estimate_storage_deposit_limit().await?;
+        let call = self.call_payload(gas_limit, storage_deposit_limit);
+        Ok(self.client.tx().call_data(&call)?)
+    }
+
+    /// Returns whether the called message mutates the storage of the contract.
+    pub fn mutates(&self) -> Result<bool> {
+        match (self.transcoder.as_ref(), self.abi()) {
+            (Some(transcoder), _) => {
+                Ok(transcoder
+                    .find_message_spec(&self.message)
+                    .expect("message exist after calling CallExec::done()")
+                    .mutates())
+            }
+            (None, Some(abi)) => Ok(abi.find_function(&self.message)?.mutates()),
+            (None, None) => unreachable!("either metadata or an ABI is loaded"),
+        }
+    }
+
+    /// Returns an error if the called message is immutable, executing it would have no
+    /// effect.
+    pub(crate) fn ensure_mutates(&self) -> Result<(), ErrorVariant> {
+        if !self.mutates()? {
+            let inner = anyhow!(
+                "Tried to execute a call on the immutable contract message '{}'. Please do a dry-run instead.",
+                &self.message
+            );
+            return Err(inner.into())
+        }
+        Ok(())
+    }
+
+    /// Returns the payload of the extrinsic calling the contract.
+    pub(crate) fn call_payload(
+        &self,
+        gas_limit: Weight,
+        storage_deposit_limit: Option<E::Balance>,
+    ) -> subxt::tx::Payload<Call<C::AccountId, E::Balance>> {
+        Call::new(
+            self.contract.clone(),
             self.value,
             gas_limit,
             storage_deposit_limit,
             self.call_data.clone(),
         )
-        .build();
+        .build(self.backend)
+    }
 
-        let result =
-            submit_extrinsic(&self.client, &self.rpc, &call, self.opts.signer()).await?;
+    /// Estimates the fee of calling the contract with the `gas_limit`, excluding the
+    /// tip.
+    pub async fn estimate_fee(&self, gas_limit: Weight) -> Result<u128> {
+        let call = self.call_payload(gas_limit, self.opts.storage_deposit_limit());
+        Ok(estimate_fee(&self.client, &call, &self.opts).await?)
+    }
 
-        Ok(result)
+    /// Returns the storage deposit limit to submit the call with.
+    ///
+    /// Unless a limit is set or the deposit is unlimited, the call is dry-run to derive
+    /// the limit from the charged deposit, see
+    /// [`ExtrinsicOpts::storage_deposit_limit_for`].
+    pub async fn estimate_storage_deposit_limit(&self) -> Result<Option<E::Balance>> {
+        if !self.opts.estimates_storage_deposit_limit() {
+            return Ok(self.opts.storage_deposit_limit())
+        }
+        let call_result = self.call_dry_run().await?;
+        match call_result.result {
+            Ok(_) => {
+                Ok(self
+                    .opts
+                    .storage_deposit_limit_for(&call_result.storage_deposit))
+            }
+            Err(ref err) => {
+                let object =
+                    ErrorVariant::from_dispatch_error(err, &self.client.metadata())?;
+                Err(anyhow!("Pre-submission dry-run failed. Error: {}", object))
+            }
+        }
+    }
+
+    /// Returns the RPC methods of the node.
+    pub(crate) fn rpc(&self) -> &NodeRpc<C> {
+        &self.rpc
     }
 
     /// Estimates the gas required for a contract call without modifying the blockchain.
@@ -328,9 +581,45 @@ where
         &self.client
     }
 
-    /// Returns the contract message transcoder.
-    pub fn transcoder(&self) -> &ContractMessageTranscoder {
-        &self.transcoder
+    /// Returns the pallet implementing smart contracts on the chain.
+    pub fn backend(&self) -> &'static dyn ContractsBackend {
+        self.backend
+    }
+
+    /// Returns the SS58 prefix accounts are displayed with, see
+    /// [`ExtrinsicOpts::ss58_prefix`].
+    pub fn ss58_prefix(&self) -> u16 {
+        self.ss58_prefix
+    }
+
+    /// Returns the contract message transcoder, if the contract is described by ink!
+    /// metadata instead of a Solidity ABI, see [`Self::abi`].
+    pub fn transcoder(&self) -> Option<&ContractMessageTranscoder> {
+        self.transcoder.as_ref()
+    }
+
+    /// Returns the Solidity ABI of the contract, if one was provided.
+    pub fn abi(&self) -> Option<&SolidityAbi> {
+        self.abi.as_ref()
+    }
+
+    /// Decodes the data returned by the called message.
+    pub fn decode_return(&self, data: &[u8]) -> Result<Value> {
+        match (self.transcoder.as_ref(), self.abi()) {
+            (Some(transcoder), _) => {
+                transcoder.decode_message_return(&self.message, &mut &data[..])
+            }
+            (None, Some(abi)) => abi.decode_output(&self.message, data),
+            (None, None) => unreachable!("either metadata or an ABI is loaded"),
+        }
+    }
+
+    /// Returns whether the decoded return value of the message is wrapped in the
+    /// `Result<_, LangError>` of the ink! dispatcher.
+    pub fn returns_lang_result(&self) -> bool {
+        self.transcoder.as_ref().is_some_and(|transcoder| {
+            transcoder.message_returns_lang_result(&self.message)
+        })
     }
 
     /// Returns the call data.
@@ -339,13 +628,31 @@ where
     }
 }
 
+/// The weight and the storage deposit required by a call, as measured by a dry-run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DryRunCost {
+    /// The `ref_time` of the required weight.
+    pub ref_time: u64,
+    /// The `proof_size` of the required weight, i.e. the size of the PoV.
+    pub proof_size: u64,
+    /// The storage deposit charged, negative if it is refunded.
+    pub storage_deposit: i128,
+}
+
+/// A struct representing the result of a call command execution.
+pub struct CallResult<C: Config> {
+    pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+}
+
 /// A struct that encodes RPC parameters required for a call to a smart contract.
 ///
-/// Copied from `pallet-contracts-rpc-runtime-api`.
+/// Copied from `pallet-contracts-rpc-runtime-api`. The contract is addressed by an
+/// `H160` for `pallet-revive`, otherwise the request is the same.
 #[derive(Encode)]
 struct CallRequest<AccountId, Balance> {
     origin: AccountId,
-    dest: AccountId,
+    dest: ContractAddress,
     value: Balance,
     gas_limit: Option<Weight>,
     storage_deposit_limit: Option<Balance>,
@@ -0,0 +1,287 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Wrapping of the calls of submitted extrinsics into outer calls, e.g. to dispatch them
+//! through `sudo` or to submit them as a council proposal.
+//!
+//! A wrapper is set with
+//! [`ExtrinsicOptsBuilder::wrap_call`](crate::ExtrinsicOptsBuilder::wrap_call):
+//!
+//! ```ignore
+//! let opts = ExtrinsicOptsBuilder::new(signer)
+//!     .wrap_call(call_wrapper::sudo)
+//!     .done();
+//! ```
+
+use scale::Encode;
+use std::sync::Arc;
+use subxt::{
+    blocks::ExtrinsicEvents,
+    error::DispatchError,
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_value::{
+            self,
+            Composite,
+            Value,
+            ValueDef,
+            Variant,
+        },
+    },
+    tx::{
+        DynamicPayload,
+        TxPayload,
+    },
+    Config,
+    Metadata,
+    OnlineClient,
+};
+
+/// Wraps the call of an extrinsic into an outer call before it is submitted.
+pub type CallWrapper = Arc<dyn Fn(DynamicPayload) -> DynamicPayload + Send + Sync>;
+
+/// Wraps the `call` into a `Sudo::sudo` call, dispatching it with the root origin.
+pub fn sudo(call: DynamicPayload) -> DynamicPayload {
+    subxt::dynamic::tx("Sudo", "sudo", vec![call.into_value()])
+}
+
+/// Returns a wrapper turning a call into a `Sudo::sudo_as` call, dispatching it with the
+/// signed origin of `who`.
+///
+/// Unlike [`sudo`] this works for calls requiring a signed origin, like uploading code
+/// and instantiating contracts with `pallet-contracts`.
+pub fn sudo_as<AccountId: Encode>(
+    who: &AccountId,
+) -> impl Fn(DynamicPayload) -> DynamicPayload + Send + Sync + 'static {
+    let who = Value::unnamed_variant("Id", [Value::from_bytes(who.encode())]);
+    move |call| {
+        subxt::dynamic::tx("Sudo", "sudo_as", vec![who.clone(), call.into_value()])
+    }
+}
+
+/// Returns a wrapper turning a call into a `Utility::dispatch_as` call, dispatching it
+/// with the signed origin of `who`.
+///
+/// `Utility::dispatch_as` requires the root origin itself, e.g. the origin of a call
+/// dispatched by the `Scheduler` pallet.
+pub fn dispatch_as<AccountId: Encode>(
+    who: &AccountId,
+) -> impl Fn(DynamicPayload) -> DynamicPayload + Send + Sync + 'static {
+    // `OriginCaller::system(RawOrigin::Signed(who))`
+    let origin = Value::unnamed_variant(
+        "system",
+        [Value::unnamed_variant(
+            "Signed",
+            [Value::from_bytes(who.encode())],
+        )],
+    );
+    move |call| {
+        subxt::dynamic::tx(
+            "Utility",
+            "dispatch_as",
+            vec![origin.clone(), call.into_value()],
+        )
+    }
+}
+
+/// Fetches the account of the sudo key of the chain, `None` if the chain has no `Sudo`
+/// pallet or no sudo key is set.
+pub async fn fetch_sudo_key<C>(
+    client: &OnlineClient<C>,
+) -> Result<Option<C::AccountId>, subxt::Error>
+where
+    C: Config,
+    C::AccountId: IntoVisitor,
+{
+    if client.metadata().pallet_by_name("Sudo").is_none() {
+        return Ok(None)
+    }
+    let key = client
+        .storage()
+        .at_latest()
+        .await?
+        .fetch(&subxt::dynamic::storage("Sudo", "Key", ()))
+        .await?;
+    Ok(key.map(|key| key.as_type::<C::AccountId>()).transpose()?)
+}
+
+/// Returns the error of a call dispatched by [`sudo`] or [`sudo_as`], which does not
+/// fail the extrinsic itself but is reported in the `Sudo::Sudid` or `Sudo::SudoAsDone`
+/// event.
+pub(crate) fn sudo_result<C: Config>(
+    events: &ExtrinsicEvents<C>,
+    metadata: Metadata,
+) -> Result<(), subxt::Error> {
+    for event in events.iter() {
+        let event = event?;
+        if event.pallet_name() != "Sudo"
+            || !matches!(event.variant_name(), "Sudid" | "SudoAsDone")
+        {
+            continue
+        }
+        // `{ sudo_result: DispatchResult }`, with `1` encoding the `Err` variant
+        if let [1, error @ ..] = event.field_bytes() {
+            return Err(DispatchError::decode_from(error, metadata.clone())?.into())
+        }
+    }
+    Ok(())
+}
+
+/// Returns a wrapper turning a call into a proposal of the collective `pallet`, e.g.
+/// `Council`, which is executed once `threshold` members approved it.
+///
+/// The encoded proposal must not be longer than `length_bound` bytes.
+pub fn proposal(
+    pallet: impl Into<String>,
+    threshold: u32,
+    length_bound: u32,
+) -> impl Fn(DynamicPayload) -> DynamicPayload + Send + Sync + 'static {
+    let pallet = pallet.into();
+    move |call| {
+        subxt::dynamic::tx(
+            pallet.as_str(),
+            "propose",
+            vec![
+                Value::u128(threshold.into()),
+                call.into_value(),
+                Value::u128(length_bound.into()),
+            ],
+        )
+    }
+}
+
+/// Converts the `call` into a dynamic payload, which can be passed to a
+/// [`CallWrapper`].
+pub(crate) fn into_dynamic<C, Call>(
+    client: &OnlineClient<C>,
+    call: &Call,
+) -> Result<DynamicPayload, subxt::Error>
+where
+    C: Config,
+    Call: TxPayload,
+{
+    let metadata = client.metadata();
+    let call_data = client.tx().call_data(call)?;
+    let call_ty = metadata.outer_enums().call_enum_ty();
+    let value =
+        scale_value::scale::decode_as_type(&mut &call_data[..], &call_ty, metadata.types())
+            .map_err(|err| {
+                subxt::Error::Other(format!("Failed to decode the call: {err}"))
+            })?
+            .remove_context();
+    // The outer call is the variant of the pallet, holding the variant of the call.
+    let ValueDef::Variant(Variant {
+        name: pallet_name,
+        values: Composite::Unnamed(mut pallet_call),
+    }) = value.value
+    else {
+        return Err(subxt::Error::Other("Unexpected encoding of the call".into()))
+    };
+    match pallet_call.pop().map(|call| call.value) {
+        Some(ValueDef::Variant(Variant { name, values })) if pallet_call.is_empty() => {
+            Ok(subxt::dynamic::tx(pallet_name, name, values))
+        }
+        _ => Err(subxt::Error::Other("Unexpected encoding of the call".into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_metadata::{
+        self,
+        OriginCaller,
+        RawOrigin,
+        RuntimeCall,
+        SudoCall,
+        UtilityCall,
+    };
+    use sp_runtime::MultiAddress;
+
+    fn remark() -> DynamicPayload {
+        subxt::dynamic::tx("System", "remark", vec![Value::from_bytes([1, 2])])
+    }
+
+    #[test]
+    fn encodes_sudo_call() {
+        let call_data = sudo(remark())
+            .encode_call_data(&test_metadata::metadata())
+            .unwrap();
+
+        let expected = RuntimeCall::Sudo(SudoCall::sudo {
+            call: Box::new(test_metadata::remark(&[1, 2])),
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn encodes_sudo_as_call() {
+        let who = sp_runtime::AccountId32::from([1; 32]);
+
+        let call_data = sudo_as(&who)(remark())
+            .encode_call_data(&test_metadata::metadata())
+            .unwrap();
+
+        let expected = RuntimeCall::Sudo(SudoCall::sudo_as {
+            who: MultiAddress::Id(who),
+            call: Box::new(test_metadata::remark(&[1, 2])),
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn encodes_dispatch_as_call() {
+        let who = sp_runtime::AccountId32::from([1; 32]);
+
+        let call_data = dispatch_as(&who)(remark())
+            .encode_call_data(&test_metadata::metadata())
+            .unwrap();
+
+        let expected = RuntimeCall::Utility(UtilityCall::dispatch_as {
+            as_origin: Box::new(OriginCaller::system(RawOrigin::Signed(who))),
+            call: Box::new(test_metadata::remark(&[1, 2])),
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn wraps_call_into_sudo() {
+        let call = sudo(remark());
+
+        assert_eq!(call.pallet_name(), "Sudo");
+        assert_eq!(call.call_name(), "sudo");
+        assert_eq!(
+            call.call_data(),
+            &Composite::Unnamed(vec![remark().into_value()])
+        );
+    }
+
+    #[test]
+    fn wraps_call_into_proposal() {
+        let call = proposal("Council", 2, 1024)(remark());
+
+        assert_eq!(call.pallet_name(), "Council");
+        assert_eq!(call.call_name(), "propose");
+        assert_eq!(
+            call.call_data(),
+            &Composite::Unnamed(vec![
+                Value::u128(2),
+                remark().into_value(),
+                Value::u128(1024),
+            ])
+        );
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2018-2023 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_client,
+    url_to_string,
+    RpcOptions,
+};
+use anyhow::Result;
+use subxt::{
+    backend::legacy::LegacyRpcMethods,
+    Config,
+};
+use url::Url;
+
+/// The name and the genesis hash of the chain a node is running, which identify the
+/// chain an extrinsic is submitted to.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChainInfo {
+    /// The name of the chain, e.g. `Development`.
+    pub name: String,
+    /// The hex encoded hash of the genesis block of the chain.
+    pub genesis_hash: String,
+}
+
+impl ChainInfo {
+    /// Query [ChainInfo] through the node's RPC
+    pub async fn query<C: Config>(url: &Url) -> Result<Self> {
+        Self::query_with_options::<C>(url, RpcOptions::default()).await
+    }
+
+    /// Query [ChainInfo] through the node's RPC, communicating with the node according
+    /// to `options`.
+    pub async fn query_with_options<C: Config>(
+        url: &Url,
+        options: RpcOptions,
+    ) -> Result<Self> {
+        let rpc_cli = rpc_client(&url_to_string(url), options).await?;
+        let rpc = LegacyRpcMethods::<C>::new(rpc_cli);
+        let (name, genesis_hash) =
+            futures::try_join!(rpc.system_chain(), rpc.genesis_hash())?;
+        Ok(Self {
+            name,
+            genesis_hash: format!("0x{}", hex::encode(genesis_hash)),
+        })
+    }
+}
@@ -15,6 +15,7 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
+    ArtifactNotFound,
     ContractMessageTranscoder,
     ContractMetadata,
     CrateMetadata,
@@ -24,7 +25,7 @@ use anyhow::{
     Context,
     Result,
 };
-use colored::Colorize;
+use contract_build::fingerprint::SourcesStatus;
 use ink_metadata::InkProject;
 use std::path::{
     Path,
@@ -57,15 +58,19 @@ impl ContractArtifacts {
                     contract_build::Target::Wasm,
                 )?;
 
+                ensure_up_to_date(&crate_metadata)?;
+
                 if crate_metadata.contract_bundle_path().exists() {
                     crate_metadata.contract_bundle_path()
                 } else if crate_metadata.metadata_path().exists() {
                     crate_metadata.metadata_path()
                 } else {
-                    anyhow::bail!(
+                    return Err(ArtifactNotFound(
                         "Failed to find any contract artifacts in target directory. \n\
                         Run `cargo contract build --release` to generate the artifacts."
+                            .to_string(),
                     )
+                    .into())
                 }
             }
             (None, Some(artifact_file)) => artifact_file.clone(),
@@ -75,14 +80,23 @@ impl ContractArtifacts {
         };
         Self::from_artifact_path(artifact_path.as_path())
     }
+
     /// Given a contract artifact path, load the contract code and metadata where
     /// possible.
     fn from_artifact_path(path: &Path) -> Result<Self> {
         tracing::debug!("Loading contracts artifacts from `{}`", path.display());
+        if !path.exists() {
+            return Err(ArtifactNotFound(format!(
+                "Contract artifact `{}` not found",
+                path.display()
+            ))
+            .into())
+        }
         let (metadata_path, metadata, code) =
             match path.extension().and_then(|ext| ext.to_str()) {
                 Some("contract") | Some("json") => {
-                    let metadata = ContractMetadata::load(path)?;
+                    let mut metadata = ContractMetadata::load(path)?;
+                    metadata.decompress_wasm()?;
                     let code = metadata.clone().source.wasm.map(|wasm| WasmCode(wasm.0));
                     (PathBuf::from(path), Some(metadata), code)
                 }
@@ -113,7 +127,7 @@ impl ContractArtifacts {
 
         if let Some(contract_metadata) = metadata.as_ref() {
             if let Err(e) = contract_metadata.check_ink_compatibility() {
-                eprintln!("{} {}", "warning:".yellow().bold(), e.to_string().bold());
+                contract_build::output::warning(e);
             }
         }
         Ok(Self {
@@ -136,10 +150,11 @@ impl ContractArtifacts {
     /// - Invalid contract metadata.
     pub fn metadata(&self) -> Result<ContractMetadata> {
         self.metadata.clone().ok_or_else(|| {
-            anyhow::anyhow!(
+            ArtifactNotFound(format!(
                 "No contract metadata found. Expected file {}",
                 self.metadata_path.as_path().display()
-            )
+            ))
+            .into()
         })
     }
 
@@ -157,10 +172,14 @@ impl ContractArtifacts {
         Ok(ink_project)
     }
 
-    /// Get the code hash from the contract metadata.
+    /// Get the code hash from the contract metadata, or from the contract code if there
+    /// is no metadata.
     pub fn code_hash(&self) -> Result<[u8; 32]> {
-        let metadata = self.metadata()?;
-        Ok(metadata.source.hash.0)
+        match (&self.metadata, &self.code) {
+            (Some(metadata), _) => Ok(metadata.source.hash.0),
+            (None, Some(code)) => Ok(code.code_hash()),
+            (None, None) => Ok(self.metadata()?.source.hash.0),
+        }
     }
 
     /// Construct a [`ContractMessageTranscoder`] from contract metadata.
@@ -180,3 +199,39 @@ impl ContractArtifacts {
         }
     }
 }
+
+/// Fails if the contract sources changed since the artifacts in the target directory
+/// were built, using the fingerprint persisted by `cargo contract build`. Artifacts built
+/// from the same sources with another Rust toolchain or `cargo-contract` version are
+/// accepted with a warning.
+///
+/// Artifacts without a fingerprint, e.g. those built by older versions, are accepted.
+fn ensure_up_to_date(crate_metadata: &CrateMetadata) -> Result<()> {
+    match contract_build::fingerprint::sources_status(crate_metadata) {
+        Ok(Some(SourcesStatus::UpToDate)) => {
+            tracing::debug!("Contract artifacts up to date")
+        }
+        Ok(Some(SourcesStatus::SourcesChanged)) => {
+            anyhow::bail!(
+                "The contract sources changed since the artifacts were built. \
+                Run `cargo contract build` to update them, or pass the artifact with \
+                `--file` to use it anyway."
+            )
+        }
+        Ok(Some(SourcesStatus::ToolsChanged { toolchain, version })) => {
+            let built_with = toolchain
+                .map(|toolchain| format!("the Rust toolchain {toolchain}"))
+                .into_iter()
+                .chain(version.map(|version| format!("cargo-contract {version}")))
+                .collect::<Vec<_>>()
+                .join(" and ");
+            contract_build::output::warning(format!(
+                "The contract artifacts were built with {built_with}. Run \
+                `cargo contract build` to rebuild them with the current one."
+            ));
+        }
+        Ok(None) => tracing::debug!("No build fingerprint found"),
+        Err(err) => tracing::debug!("Failed to check the build fingerprint: {err}"),
+    }
+    Ok(())
+}
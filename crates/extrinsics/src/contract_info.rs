@@ -14,19 +14,34 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
-use super::get_best_block;
+use super::{
+    contracts_backend,
+    eth_account,
+    get_best_block,
+    ContractsBackend,
+};
 use anyhow::{
     anyhow,
     Result,
 };
+use contract_build::progress::{
+    Operation,
+    ProgressReporter,
+};
 use contract_metadata::byte_str::serialize_as_byte_str;
-use std::fmt::{
-    Display,
-    Formatter,
+use std::{
+    collections::HashMap,
+    fmt::{
+        Display,
+        Formatter,
+    },
 };
 
 use ink_env::Environment;
-use scale::Decode;
+use scale::{
+    Decode,
+    Encode,
+};
 use std::option::Option;
 use subxt::{
     backend::legacy::LegacyRpcMethods,
@@ -38,6 +53,7 @@ use subxt::{
         },
         scale_value::Value,
     },
+    metadata::DecodeWithMetadata,
     storage::dynamic,
     Config,
     OnlineClient,
@@ -46,7 +62,7 @@ use subxt::{
 /// Return the account data for an account ID.
 async fn get_account_balance<C: Config, E: Environment>(
     account: &C::AccountId,
-    rpc: &LegacyRpcMethods<C>,
+    block_hash: C::Hash,
     client: &OnlineClient<C>,
 ) -> Result<AccountData<E::Balance>>
 where
@@ -55,11 +71,10 @@ where
 {
     let storage_query =
         subxt::dynamic::storage("System", "Account", vec![Value::from_bytes(account)]);
-    let best_block = get_best_block(rpc).await?;
 
     let account = client
         .storage()
-        .at(best_block)
+        .at(block_hash)
         .fetch(&storage_query)
         .await?
         .ok_or_else(|| anyhow::anyhow!("Failed to fetch account data"))?;
@@ -80,15 +95,30 @@ where
     E::Balance: IntoVisitor,
 {
     let best_block = get_best_block(rpc).await?;
+    fetch_contract_info_at::<C, E>(contract, best_block, client).await
+}
+
+/// Fetch the contract info from the storage at the block with the `block_hash`.
+pub async fn fetch_contract_info_at<C: Config, E: Environment>(
+    contract: &C::AccountId,
+    block_hash: C::Hash,
+    client: &OnlineClient<C>,
+) -> Result<ContractInfo<C::Hash, E::Balance>>
+where
+    C::AccountId: AsRef<[u8]> + Display + IntoVisitor,
+    C::Hash: IntoVisitor,
+    E::Balance: IntoVisitor,
+{
+    let backend = contracts_backend(&client.metadata())?;
 
     let contract_info_address = dynamic(
-        "Contracts",
-        "ContractInfoOf",
-        vec![Value::from_bytes(contract)],
+        backend.pallet(),
+        backend.contract_info_storage(),
+        vec![Value::from_bytes(backend.contract_address(contract.as_ref()))],
     );
     let contract_info_value = client
         .storage()
-        .at(best_block)
+        .at(block_hash)
         .fetch(&contract_info_address)
         .await?
         .ok_or_else(|| {
@@ -103,7 +133,7 @@ where
     let deposit_account = contract_info_raw.get_deposit_account();
 
     let deposit_account_data =
-        get_account_balance::<C, E>(deposit_account, rpc, client).await?;
+        get_account_balance::<C, E>(deposit_account, block_hash, client).await?;
     Ok(contract_info_raw.into_contract_info(deposit_account_data))
 }
 
@@ -267,12 +297,28 @@ where
     C::Hash: AsRef<[u8]> + Display + IntoVisitor,
 {
     let best_block = get_best_block(rpc).await?;
+    fetch_wasm_code_at(client, best_block, hash).await
+}
 
-    let pristine_code_address =
-        dynamic("Contracts", "PristineCode", vec![Value::from_bytes(hash)]);
+/// Fetch the contract Wasm code from the storage at the block with the `block_hash`.
+pub async fn fetch_wasm_code_at<C: Config>(
+    client: &OnlineClient<C>,
+    block_hash: C::Hash,
+    hash: &C::Hash,
+) -> Result<Vec<u8>>
+where
+    C::Hash: AsRef<[u8]> + Display + IntoVisitor,
+{
+    let backend = contracts_backend(&client.metadata())?;
+
+    let pristine_code_address = dynamic(
+        backend.pallet(),
+        backend.code_storage(),
+        vec![Value::from_bytes(hash)],
+    );
     let pristine_code = client
         .storage()
-        .at(best_block)
+        .at(block_hash)
         .fetch(&pristine_code_address)
         .await?
         .ok_or_else(|| anyhow!("No WASM code was found for code hash {}", hash))?;
@@ -282,15 +328,211 @@ where
     pristine_code.map(|v| v.0)
 }
 
+/// Fetch the owner, deposit and reference count of the uploaded code with the
+/// `code_hash` from the storage.
+pub async fn fetch_code_info<C: Config, E: Environment>(
+    code_hash: &C::Hash,
+    rpc: &LegacyRpcMethods<C>,
+    client: &OnlineClient<C>,
+) -> Result<CodeInfo<C::AccountId, E::Balance>>
+where
+    C::Hash: AsRef<[u8]> + Display,
+    C::AccountId: IntoVisitor,
+    E::Balance: IntoVisitor,
+{
+    let best_block = get_best_block(rpc).await?;
+    let backend = contracts_backend(&client.metadata())?;
+
+    let code_info_address = dynamic(
+        backend.pallet(),
+        backend.code_info_storage(),
+        vec![Value::from_bytes(code_hash)],
+    );
+    let code_info = client
+        .storage()
+        .at(best_block)
+        .fetch(&code_info_address)
+        .await?
+        .ok_or_else(|| anyhow!("No code info was found for code hash {}", code_hash))?
+        .as_type::<CodeInfoOf<C::AccountId, E::Balance>>()
+        .map_err(|e| anyhow!("Code info could not be parsed: {e}"))?;
+    Ok(CodeInfo {
+        owner: code_info.owner,
+        deposit: code_info.deposit,
+        refcount: code_info.refcount,
+    })
+}
+
+/// The owner, deposit and reference count of uploaded contract code.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct CodeInfo<AccountId, Balance> {
+    /// The account which uploaded the code and paid its deposit.
+    pub owner: AccountId,
+    /// The deposit held for storing the code.
+    pub deposit: Balance,
+    /// The number of contracts instantiated from the code.
+    pub refcount: u64,
+}
+
+impl<AccountId, Balance> CodeInfo<AccountId, Balance> {
+    /// Whether `remove_code` would currently succeed when called by the owner, which
+    /// requires that no contract is instantiated from the code.
+    pub fn is_removable(&self) -> bool {
+        self.refcount == 0
+    }
+
+    /// The deposit refunded to the owner by `remove_code`, if the code is removable.
+    pub fn removal_refund(&self) -> Option<&Balance> {
+        self.is_removable().then_some(&self.deposit)
+    }
+}
+
+/// Returns whether the contract code with the given hash is stored on chain, without
+/// fetching the code itself.
+pub async fn code_exists<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    code_hash: impl AsRef<[u8]>,
+) -> Result<bool> {
+    let best_block = get_best_block(rpc).await?;
+    let backend = contracts_backend(&client.metadata())?;
+
+    let pristine_code_address = dynamic(
+        backend.pallet(),
+        backend.code_storage(),
+        vec![Value::from_bytes(code_hash)],
+    );
+    let key = client.storage().address_bytes(&pristine_code_address)?;
+    let keys = rpc
+        .state_get_keys_paged(&key, 1, None, Some(best_block))
+        .await?;
+    Ok(keys.first() == Some(&key))
+}
+
+/// Returns the maximum size of contract code accepted by the chain, in bytes.
+pub fn max_code_len<C: Config>(client: &OnlineClient<C>) -> Result<u32> {
+    let backend = contracts_backend(&client.metadata())?;
+    let address = subxt::dynamic::constant(backend.pallet(), "MaxCodeLen");
+    let max_code_len = client
+        .constants()
+        .at(&address)
+        .map_err(|e| anyhow!("Failed to fetch the maximum code size: {e}"))?
+        .as_type::<u32>()?;
+    Ok(max_code_len)
+}
+
+/// The storage deposit held for a contract, broken down by what it pays for.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct StorageDepositBreakdown {
+    /// The deposit for the contract info and the reference to its code.
+    pub base_deposit: u128,
+    /// The number of items in the contract storage.
+    pub storage_items: u32,
+    /// The chain's deposit per storage item (`DepositPerItem`).
+    pub deposit_per_item: u128,
+    /// The deposit for the storage items.
+    pub items_deposit: u128,
+    /// The number of bytes in the contract storage.
+    pub storage_bytes: u32,
+    /// The chain's deposit per byte of storage (`DepositPerByte`).
+    pub deposit_per_byte: u128,
+    /// The deposit for the storage bytes.
+    pub bytes_deposit: u128,
+}
+
+impl StorageDepositBreakdown {
+    /// Computes the deposit for the items and bytes of the contract storage from the
+    /// chain's deposit rates.
+    pub fn new(
+        base_deposit: u128,
+        storage_items: u32,
+        deposit_per_item: u128,
+        storage_bytes: u32,
+        deposit_per_byte: u128,
+    ) -> Self {
+        Self {
+            base_deposit,
+            storage_items,
+            deposit_per_item,
+            items_deposit: deposit_per_item.saturating_mul(storage_items.into()),
+            storage_bytes,
+            deposit_per_byte,
+            bytes_deposit: deposit_per_byte.saturating_mul(storage_bytes.into()),
+        }
+    }
+
+    /// The total storage deposit of the contract.
+    pub fn total(&self) -> u128 {
+        self.base_deposit
+            .saturating_add(self.items_deposit)
+            .saturating_add(self.bytes_deposit)
+    }
+}
+
+/// Fetch the storage deposit of a contract, broken down using the deposit rates of the
+/// chain.
+pub async fn fetch_storage_deposit<C: Config>(
+    contract: &C::AccountId,
+    rpc: &LegacyRpcMethods<C>,
+    client: &OnlineClient<C>,
+) -> Result<StorageDepositBreakdown> {
+    let best_block = get_best_block(rpc).await?;
+    let backend = contracts_backend(&client.metadata())?;
+
+    let contract_info_address = dynamic(
+        backend.pallet(),
+        backend.contract_info_storage(),
+        vec![Value::from_bytes(backend.contract_address(&contract.encode()))],
+    );
+    let deposit = client
+        .storage()
+        .at(best_block)
+        .fetch(&contract_info_address)
+        .await?
+        .ok_or_else(|| anyhow!("No contract information was found for {contract:?}"))?
+        .as_type::<ContractDepositInfo>()?;
+
+    Ok(StorageDepositBreakdown::new(
+        deposit.storage_base_deposit,
+        deposit.storage_items,
+        deposit_constant(client, "DepositPerItem")?,
+        deposit.storage_bytes,
+        deposit_constant(client, "DepositPerByte")?,
+    ))
+}
+
+/// Returns the value of a storage deposit rate constant of the contracts pallet.
+fn deposit_constant<C: Config>(client: &OnlineClient<C>, name: &str) -> Result<u128> {
+    let backend = contracts_backend(&client.metadata())?;
+    let address = subxt::dynamic::constant(backend.pallet(), name);
+    let deposit = client
+        .constants()
+        .at(&address)
+        .map_err(|e| anyhow!("Failed to fetch the {name} constant: {e}"))?
+        .as_type::<u128>()?;
+    Ok(deposit)
+}
+
 /// Parse a contract account address from a storage key. Returns error if a key is
 /// malformated.
+///
+/// The keys of `pallet-revive` end with the `H160` address of the contract, which is
+/// converted to the account of the contract.
 fn parse_contract_account_address<C: Config>(
     storage_contract_account_key: &[u8],
     storage_contract_root_key_len: usize,
+    backend: &dyn ContractsBackend,
 ) -> Result<C::AccountId>
 where
     C::AccountId: Decode,
 {
+    if backend.h160_addresses() {
+        let address = storage_contract_account_key
+            .get(storage_contract_root_key_len..)
+            .and_then(|key| key.get(key.len().checked_sub(20)?..))
+            .ok_or(anyhow!("Unexpected storage key size"))?;
+        return eth_account(address.try_into()?)
+    }
     // storage_contract_account_key is a concatenation of contract_info_of root key and
     // Twox64Concat(AccountId)
     let mut account = storage_contract_account_key
@@ -300,7 +542,170 @@ where
         .map_err(|err| anyhow!("AccountId deserialization error: {}", err))
 }
 
+/// The number of storage keys requested at once when scanning the contract infos.
+const KEYS_PAGE_SIZE: u32 = 1000;
+
+/// Pages through the storage keys of the contract infos at the best block.
+struct ContractInfoKeys<'a, C: Config> {
+    rpc: &'a LegacyRpcMethods<C>,
+    backend: &'static dyn ContractsBackend,
+    block: C::Hash,
+    root_key: Vec<u8>,
+    start_key: Option<Vec<u8>>,
+}
+
+impl<'a, C: Config> ContractInfoKeys<'a, C> {
+    /// Starts the scan after the storage key `start_key`, or at the first contract.
+    async fn new(
+        client: &OnlineClient<C>,
+        rpc: &'a LegacyRpcMethods<C>,
+        start_key: Option<&[u8]>,
+    ) -> Result<Self> {
+        let block = get_best_block(rpc).await?;
+        let backend = contracts_backend(&client.metadata())?;
+        let root_key =
+            subxt::dynamic::storage(backend.pallet(), backend.contract_info_storage(), ())
+                .to_root_bytes();
+        Ok(Self {
+            rpc,
+            backend,
+            block,
+            root_key,
+            start_key: start_key.map(<[u8]>::to_vec),
+        })
+    }
+
+    /// Returns the next page of keys, which is empty once all keys were scanned.
+    async fn next_page(&mut self) -> Result<Vec<Vec<u8>>> {
+        let keys = self
+            .rpc
+            .state_get_keys_paged(
+                &self.root_key,
+                KEYS_PAGE_SIZE,
+                self.start_key.as_deref(),
+                Some(self.block),
+            )
+            .await?;
+        if let Some(last_key) = keys.last() {
+            self.start_key = Some(last_key.clone());
+        }
+        Ok(keys)
+    }
+
+    /// Returns the address of the contract with the storage `key`.
+    fn contract_address(&self, key: &[u8]) -> Result<C::AccountId>
+    where
+        C::AccountId: Decode,
+    {
+        parse_contract_account_address::<C>(key, self.root_key.len(), self.backend)
+    }
+}
+
+/// A page of the contracts instantiated from a code hash.
+#[derive(Debug, serde::Serialize)]
+pub struct ContractInstances<AccountId> {
+    /// The addresses of the contracts.
+    pub contracts: Vec<AccountId>,
+    /// The hex encoded storage key to continue the scan after, if it stopped at the
+    /// limit.
+    pub next_start_key: Option<String>,
+}
+
+/// Fetch the addresses of up to `limit` contracts instantiated from the code with the
+/// `code_hash`, scanning the contract infos after the storage key `start_key`.
+///
+/// The number of contracts scanned so far is reported to `progress`.
+pub async fn fetch_contract_instances<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    code_hash: &C::Hash,
+    limit: usize,
+    start_key: Option<&[u8]>,
+    progress: &ProgressReporter,
+) -> Result<ContractInstances<C::AccountId>>
+where
+    C::AccountId: Decode,
+    C::Hash: IntoVisitor,
+{
+    progress.started(Operation::FetchContracts);
+    let result =
+        scan_contract_instances::<C>(client, rpc, code_hash, limit, start_key, progress)
+            .await;
+    progress.finished(Operation::FetchContracts);
+    result
+}
+
+async fn scan_contract_instances<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    code_hash: &C::Hash,
+    limit: usize,
+    start_key: Option<&[u8]>,
+    progress: &ProgressReporter,
+) -> Result<ContractInstances<C::AccountId>>
+where
+    C::AccountId: Decode,
+    C::Hash: IntoVisitor,
+{
+    let metadata = client.metadata();
+    let backend = contracts_backend(&metadata)?;
+    let storage = backend.contract_info_storage();
+    let contract_info_type = metadata
+        .pallet_by_name(backend.pallet())
+        .and_then(|pallet| pallet.storage())
+        .and_then(|entries| entries.entry_by_name(storage))
+        .map(|entry| entry.entry_type().value_ty())
+        .ok_or_else(|| anyhow!("The {storage} storage was not found in the metadata"))?;
+
+    let mut keys = ContractInfoKeys::new(client, rpc, start_key).await?;
+    let mut contracts = Vec::new();
+    let mut scanned = 0;
+    loop {
+        let page = keys.next_page().await?;
+        if page.is_empty() {
+            return Ok(ContractInstances {
+                contracts,
+                next_start_key: None,
+            })
+        }
+        let values: HashMap<_, _> = rpc
+            .state_query_storage_at(page.iter().map(Vec::as_slice), Some(keys.block))
+            .await?
+            .into_iter()
+            .flat_map(|change_set| change_set.changes)
+            .filter_map(|(key, value)| Some((key.0, value?.0)))
+            .collect();
+
+        for key in &page {
+            scanned += 1;
+            let Some(value) = values.get(key) else {
+                continue
+            };
+            let contract_info = DecodedValueThunk::decode_with_metadata(
+                &mut &value[..],
+                contract_info_type,
+                &metadata,
+            )?
+            .as_type::<ContractCodeHash<C::Hash>>()?;
+            if contract_info.code_hash != *code_hash {
+                continue
+            }
+            contracts.push(keys.contract_address(key)?);
+            if contracts.len() >= limit {
+                return Ok(ContractInstances {
+                    contracts,
+                    next_start_key: Some(format!("0x{}", hex::encode(key))),
+                })
+            }
+        }
+        progress.advanced(Operation::FetchContracts, scanned, None);
+    }
+}
+
 /// Fetch all contract addresses from the storage using the provided client.
+///
+/// Use [`for_each_contract`] to report the progress of the scan, or to avoid holding all
+/// addresses in memory.
 pub async fn fetch_all_contracts<C: Config>(
     client: &OnlineClient<C>,
     rpc: &LegacyRpcMethods<C>,
@@ -308,23 +713,66 @@ pub async fn fetch_all_contracts<C: Config>(
 where
     C::AccountId: Decode,
 {
-    let best_block = get_best_block(rpc).await?;
-    let root_key =
-        subxt::dynamic::storage("Contracts", "ContractInfoOf", ()).to_root_bytes();
-    let mut keys = client
-        .storage()
-        .at(best_block)
-        .fetch_raw_keys(root_key.clone())
-        .await?;
+    let progress = ProgressReporter::default();
+    let mut contracts = Vec::new();
+    for_each_contract::<C>(client, rpc, None, None, &progress, |contract| {
+        contracts.push(contract);
+        Ok(())
+    })
+    .await?;
+    Ok(contracts)
+}
 
-    let mut contract_accounts = Vec::new();
-    while let Some(result) = keys.next().await {
-        let key = result?;
-        let contract_account = parse_contract_account_address::<C>(&key, root_key.len())?;
-        contract_accounts.push(contract_account);
-    }
+/// Calls `f` with the address of every contract, in the order of their storage keys,
+/// without holding all addresses in memory.
+///
+/// The scan starts after the storage key `start_key` and stops after `limit` contracts,
+/// returning the hex encoded storage key to continue the scan after in that case. The
+/// number of addresses fetched so far is reported to `progress`.
+pub async fn for_each_contract<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    start_key: Option<&[u8]>,
+    limit: Option<usize>,
+    progress: &ProgressReporter,
+    f: impl FnMut(C::AccountId) -> Result<()>,
+) -> Result<Option<String>>
+where
+    C::AccountId: Decode,
+{
+    progress.started(Operation::FetchContracts);
+    let result = scan_contracts::<C>(client, rpc, start_key, limit, progress, f).await;
+    progress.finished(Operation::FetchContracts);
+    result
+}
 
-    Ok(contract_accounts)
+async fn scan_contracts<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    start_key: Option<&[u8]>,
+    limit: Option<usize>,
+    progress: &ProgressReporter,
+    mut f: impl FnMut(C::AccountId) -> Result<()>,
+) -> Result<Option<String>>
+where
+    C::AccountId: Decode,
+{
+    let mut keys = ContractInfoKeys::new(client, rpc, start_key).await?;
+    let mut fetched = 0;
+    loop {
+        let page = keys.next_page().await?;
+        if page.is_empty() {
+            return Ok(None)
+        }
+        for key in &page {
+            f(keys.contract_address(key)?)?;
+            fetched += 1;
+            if limit.is_some_and(|limit| fetched >= limit) {
+                return Ok(Some(format!("0x{}", hex::encode(key))))
+            }
+        }
+        progress.advanced(Operation::FetchContracts, fetched as u64, None);
+    }
 }
 
 /// A struct used in the storage reads to access account info.
@@ -357,6 +805,31 @@ struct ContractInfoOf<Hash, Balance> {
     storage_item_deposit: Balance,
 }
 
+/// A struct used in the storage reads to access code info.
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct CodeInfoOf<AccountId, Balance> {
+    owner: AccountId,
+    deposit: Balance,
+    refcount: u64,
+}
+
+/// A struct used in the storage reads to access the code hash of a contract.
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct ContractCodeHash<Hash> {
+    code_hash: Hash,
+}
+
+/// A struct used in the storage reads to access the storage usage of a contract.
+#[derive(Debug, DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct ContractDepositInfo {
+    storage_bytes: u32,
+    storage_items: u32,
+    storage_base_deposit: u128,
+}
+
 /// A struct used in storage reads to access the deposit account from contract info.
 #[derive(Debug, DecodeAsType)]
 #[decode_as_type(crate_path = "subxt::ext::scale_decode")]
@@ -367,6 +840,10 @@ struct DepositAccount<AccountId> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        PalletContracts,
+        PalletRevive,
+    };
     use ink_env::DefaultEnvironment;
     use scale::Encode;
     use scale_info::{
@@ -518,6 +995,16 @@ mod tests {
         )
         .expect("the contract info must be decoded");
 
+        let deposit_info = contract_info_thunk
+            .as_type::<ContractDepositInfo>()
+            .expect("the deposit info must be decoded");
+        assert_eq!(deposit_info.storage_bytes, contract_info_v15.storage_bytes);
+        assert_eq!(deposit_info.storage_items, contract_info_v15.storage_items);
+        assert_eq!(
+            deposit_info.storage_base_deposit,
+            contract_info_v15.storage_base_deposit
+        );
+
         let contract = AccountId32([0u8; 32]);
         let contract_info_raw =
             ContractInfoRaw::<DefaultConfig, DefaultEnvironment>::new(
@@ -542,4 +1029,86 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn code_info_decode_works() {
+        #[subxt::subxt(runtime_metadata_path = "src/test_runtime_api/metadata_v15.scale")]
+        mod api_v15 {}
+
+        use api_v15::runtime_types::pallet_contracts::wasm::{
+            CodeInfo as CodeInfoV15,
+            Determinism,
+        };
+
+        let metadata_bytes = std::fs::read("src/test_runtime_api/metadata_v15.scale")
+            .expect("the metadata must be present");
+        let metadata =
+            Metadata::decode(&mut &*metadata_bytes).expect("the metadata must decode");
+        let code_info_type_id =
+            get_metadata_type_index("CodeInfo", "pallet_contracts::wasm", &metadata)
+                .expect("the code info type must be present in the metadata");
+
+        let code_info_v15 = CodeInfoV15 {
+            owner: AccountId32([7u8; 32]),
+            deposit: 1_000,
+            refcount: 0,
+            determinism: Determinism::Enforced,
+            code_len: 100,
+        };
+
+        let code_info = DecodedValueThunk::decode_with_metadata(
+            &mut &*code_info_v15.encode(),
+            code_info_type_id as u32,
+            &metadata.into(),
+        )
+        .expect("the code info must be decoded")
+        .as_type::<CodeInfoOf<AccountId32, u128>>()
+        .expect("the code info must be converted");
+
+        assert_eq!(code_info.owner, code_info_v15.owner);
+        assert_eq!(code_info.deposit, code_info_v15.deposit);
+        assert_eq!(code_info.refcount, code_info_v15.refcount);
+    }
+
+    #[test]
+    fn code_is_only_removable_without_contracts() {
+        let mut code_info = CodeInfo {
+            owner: AccountId32([7u8; 32]),
+            deposit: 1_000u128,
+            refcount: 0,
+        };
+        assert!(code_info.is_removable());
+        assert_eq!(code_info.removal_refund(), Some(&1_000));
+
+        code_info.refcount = 2;
+        assert!(!code_info.is_removable());
+        assert_eq!(code_info.removal_refund(), None);
+    }
+
+    #[test]
+    fn storage_deposit_breakdown_adds_up() {
+        let deposit = StorageDepositBreakdown::new(1_000, 3, 20, 100, 5);
+
+        assert_eq!(deposit.items_deposit, 60);
+        assert_eq!(deposit.bytes_deposit, 500);
+        assert_eq!(deposit.total(), 1_560);
+    }
+
+    #[test]
+    fn contract_address_is_parsed_from_storage_key() {
+        let root_key = [9u8; 32];
+        let account = AccountId32([1u8; 32]);
+        let key = [&root_key[..], &[0u8; 8], &account.0].concat();
+
+        let parsed =
+            parse_contract_account_address::<DefaultConfig>(&key, 32, &PalletContracts)
+                .unwrap();
+        assert_eq!(parsed, account);
+
+        let key = [&root_key[..], &[2u8; 20]].concat();
+        let parsed =
+            parse_contract_account_address::<DefaultConfig>(&key, 32, &PalletRevive)
+                .unwrap();
+        assert_eq!(parsed, eth_account::<AccountId32>([2u8; 20]).unwrap());
+    }
 }
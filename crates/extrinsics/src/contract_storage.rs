@@ -16,16 +16,30 @@
 
 use anyhow::{
     anyhow,
+    bail,
+    Context,
     Result,
 };
+use blake2::{
+    digest::{
+        consts::U16,
+        Digest as _,
+    },
+    Blake2b,
+};
+use contract_metadata::byte_str::serialize_as_byte_str;
 use contract_transcode::{
     ContractMessageTranscoder,
     Value,
 };
 use ink_env::Environment;
-use ink_metadata::layout::{
-    Layout,
-    StructLayout,
+use ink_metadata::{
+    layout::{
+        Layout,
+        RootLayout,
+        StructLayout,
+    },
+    InkProject,
 };
 use itertools::Itertools;
 use scale::{
@@ -34,9 +48,11 @@ use scale::{
 };
 use scale_info::{
     form::PortableForm,
+    PortableRegistry,
     Type,
 };
 use serde::{
+    Deserialize,
     Serialize,
     Serializer,
 };
@@ -51,7 +67,9 @@ use std::{
         Display,
         Formatter,
     },
+    fs,
     marker::PhantomData,
+    path::Path,
 };
 use subxt::{
     backend::{
@@ -70,9 +88,16 @@ use subxt::{
 };
 
 use super::{
+    contracts_backend,
     fetch_contract_info,
+    fetch_contract_info_at,
+    fetch_wasm_code_at,
+    get_best_block,
+    online_client,
+    rpc_client,
     url_to_string,
     ContractInfo,
+    RpcOptions,
     TrieId,
 };
 
@@ -98,12 +123,13 @@ where
     ///
     /// This is the result of a state query to the function `contracts::palletVersion())`.
     pub async fn version(&self) -> Result<u16> {
+        let backend = contracts_backend(&self.rpc.client.metadata())?;
         self.rpc
             .client
             .storage()
             .at_latest()
             .await?
-            .storage_version("Contracts")
+            .storage_version(backend.pallet())
             .await
             .map_err(|e| {
                 anyhow!("The storage version for the contracts pallet could not be determined: {e}")
@@ -116,8 +142,63 @@ where
         contract_account: &C::AccountId,
     ) -> Result<ContractStorageData> {
         let contract_info = self.rpc.fetch_contract_info::<E>(contract_account).await?;
-        let trie_id = contract_info.trie_id();
+        self.load_storage_data_at(contract_info.trie_id(), None)
+            .await
+    }
+
+    /// Fork the on-chain state of a contract at the best block: its info, code and the
+    /// complete raw storage of its child trie.
+    pub async fn fork_contract(
+        &self,
+        contract_account: &C::AccountId,
+    ) -> Result<ContractFork<C::Hash, E::Balance>>
+    where
+        C::Hash: AsRef<[u8]> + Display,
+    {
+        let block_hash = get_best_block(&self.rpc.rpc_methods).await?;
+        self.fork_contract_at(contract_account, block_hash).await
+    }
 
+    /// Fork the on-chain state of a contract at the block with the `block_hash`.
+    ///
+    /// The info, the code and all storage pages are read at that block, so the snapshot
+    /// is consistent even if the contract is being called concurrently.
+    pub async fn fork_contract_at(
+        &self,
+        contract_account: &C::AccountId,
+        block_hash: C::Hash,
+    ) -> Result<ContractFork<C::Hash, E::Balance>>
+    where
+        C::Hash: AsRef<[u8]> + Display,
+    {
+        let info = fetch_contract_info_at::<C, E>(
+            contract_account,
+            block_hash,
+            &self.rpc.client,
+        )
+        .await?;
+        let code =
+            fetch_wasm_code_at(&self.rpc.client, block_hash, info.code_hash()).await?;
+        let storage = self
+            .load_storage_data_at(info.trie_id(), Some(block_hash))
+            .await?;
+
+        Ok(ContractFork {
+            block_hash,
+            contract: contract_account.to_string(),
+            info,
+            code,
+            storage,
+        })
+    }
+
+    /// Load the raw key/value storage of the child trie at the given block, or the
+    /// latest block if `None`.
+    async fn load_storage_data_at(
+        &self,
+        trie_id: &TrieId,
+        block_hash: Option<C::Hash>,
+    ) -> Result<ContractStorageData> {
         let mut storage_keys = Vec::new();
         let mut storage_values = Vec::new();
         const KEYS_COUNT: u32 = 1000;
@@ -129,11 +210,14 @@ where
                     None,
                     KEYS_COUNT,
                     storage_keys.last().map(|k: &Bytes| k.as_bytes_ref()),
-                    None,
+                    block_hash,
                 )
                 .await?;
             let keys_count = keys.len();
-            let mut values = self.rpc.fetch_storage_entries(trie_id, &keys, None).await?;
+            let mut values = self
+                .rpc
+                .fetch_storage_entries(trie_id, &keys, block_hash)
+                .await?;
             assert_eq!(
                 keys_count,
                 values.len(),
@@ -165,10 +249,37 @@ where
         let data = self.load_contract_storage_data(contract_account).await?;
         ContractStorageLayout::new(data, decoder)
     }
+
+    /// Fetches the single storage cell of the field at `path`, e.g. `ownable.owner`,
+    /// and decodes the value of the field, see [`ContractStorageField::resolve`].
+    pub async fn read_field(
+        &self,
+        contract_account: &C::AccountId,
+        path: &str,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<ContractStorageFieldValue> {
+        let field = ContractStorageField::resolve(path, decoder)?;
+        let contract_info = self.rpc.fetch_contract_info::<E>(contract_account).await?;
+        let raw = self
+            .rpc
+            .fetch_contract_storage(
+                contract_info.trie_id(),
+                &Bytes::from(field.key.hashed()),
+                None,
+            )
+            .await?;
+        let value = field.decode(raw.as_ref().map(|raw| &raw.0[..]), decoder)?;
+        Ok(ContractStorageFieldValue {
+            path: field.path,
+            cell: field.cell,
+            storage_key: field.key.hashed_to_hex(),
+            value,
+        })
+    }
 }
 
 /// Represents the raw key/value storage for the contract.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ContractStorageData(BTreeMap<Bytes, Bytes>);
 
 impl ContractStorageData {
@@ -176,6 +287,33 @@ impl ContractStorageData {
     pub fn new(data: BTreeMap<Bytes, Bytes>) -> Self {
         Self(data)
     }
+
+    /// Returns the number of storage entries.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the contract storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Snapshot of the on-chain state of a contract, which can be loaded into a local node
+/// or test environment to dry-run upgrades and migrations against real state.
+#[derive(Serialize, Debug)]
+pub struct ContractFork<Hash, Balance> {
+    /// The block the state was forked at.
+    pub block_hash: Hash,
+    /// The address of the forked contract.
+    pub contract: String,
+    /// The contract info, including the trie id of the contract storage.
+    pub info: ContractInfo<Hash, Balance>,
+    /// The Wasm code of the contract.
+    #[serde(serialize_with = "serialize_as_byte_str")]
+    pub code: Vec<u8>,
+    /// The raw key/value storage of the contract.
+    pub storage: ContractStorageData,
 }
 
 /// Represents the RootLayout storage entry for the contract.
@@ -604,6 +742,610 @@ impl ContractStorageLayout {
     }
 }
 
+/// The key of a storage cell of a contract, under which its value is stored in the
+/// child trie of the contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractStorageKey {
+    raw: Vec<u8>,
+}
+
+impl ContractStorageKey {
+    /// The key of the storage cell with the `root_key`.
+    pub fn new(root_key: u32) -> Self {
+        Self {
+            raw: root_key.encode(),
+        }
+    }
+
+    /// The key of the entry of a `Mapping` with the `key`, or of the element of a
+    /// `StorageVec` with the `u32` index `key`, stored under the root key.
+    pub fn with_key(mut self, key: &impl Encode) -> Self {
+        key.encode_to(&mut self.raw);
+        self
+    }
+
+    /// The key of the entry of a `Mapping` with the already SCALE encoded `key`.
+    pub fn with_encoded_key(mut self, key: &[u8]) -> Self {
+        self.raw.extend_from_slice(key);
+        self
+    }
+
+    /// The key hashed with `Blake2_128Concat`, as stored in the child trie.
+    pub fn hashed(&self) -> Vec<u8> {
+        let mut hashed = Blake2b::<U16>::digest(&self.raw).to_vec();
+        hashed.extend_from_slice(&self.raw);
+        hashed
+    }
+
+    /// The hashed key, hex encoded, e.g. to query it with `childstate_getStorage`.
+    pub fn hashed_to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.hashed()))
+    }
+}
+
+/// How a field of the storage layout is stored.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageCellKind {
+    /// Stored together with the other packed fields under the root key.
+    Packed,
+    /// Stored in a cell of its own, loaded only when accessed.
+    Lazy,
+    /// Each entry is stored under the root key, followed by the key of the entry.
+    Mapping,
+    /// The length is stored under the root key, each element under the root key
+    /// followed by its index.
+    StorageVec,
+}
+
+impl StorageCellKind {
+    fn from_type(type_def: &Type<PortableForm>) -> Self {
+        match type_def.path.to_string().as_str() {
+            "ink_storage::lazy::mapping::Mapping" => Self::Mapping,
+            "ink_storage::lazy::vec::StorageVec" => Self::StorageVec,
+            "ink_storage::lazy::Lazy" => Self::Lazy,
+            _ => Self::Packed,
+        }
+    }
+}
+
+impl Display for StorageCellKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Packed => write!(f, "packed"),
+            Self::Lazy => write!(f, "lazy"),
+            Self::Mapping => write!(f, "mapping"),
+            Self::StorageVec => write!(f, "storage vec"),
+        }
+    }
+}
+
+/// A field of the storage layout of a contract, together with the storage cell it is
+/// stored in.
+#[derive(Serialize, Debug)]
+pub struct StorageLayoutNode {
+    pub name: String,
+    /// The root key of the storage cell.
+    #[serde(serialize_with = "RootKeyEntry::key_as_hex")]
+    pub root_key: u32,
+    pub cell: StorageCellKind,
+    /// The hashed key of the storage cell, see [`ContractStorageKey::hashed_to_hex`].
+    ///
+    /// `None` for a `Mapping`, its entries are stored under keys of their own.
+    pub storage_key: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<StorageLayoutNode>,
+}
+
+impl StorageLayoutNode {
+    /// Creates the tree of the storage layout of a contract from its metadata.
+    pub fn from_metadata(metadata: &InkProject) -> Self {
+        let layout = metadata.layout();
+        let name = match layout {
+            Layout::Root(root) => {
+                match root.layout() {
+                    Layout::Struct(struct_layout) => struct_layout.name().to_string(),
+                    _ => "root".to_string(),
+                }
+            }
+            _ => "root".to_string(),
+        };
+        Self::new(
+            name,
+            layout,
+            metadata.registry(),
+            0,
+            StorageCellKind::Packed,
+        )
+    }
+
+    fn new(
+        name: String,
+        layout: &Layout<PortableForm>,
+        registry: &PortableRegistry,
+        root_key: u32,
+        cell: StorageCellKind,
+    ) -> Self {
+        let mut node = Self::leaf(name, root_key, cell);
+        match layout {
+            Layout::Root(root) => {
+                let cell = registry
+                    .resolve(root.ty().id)
+                    .map(StorageCellKind::from_type)
+                    .unwrap_or(StorageCellKind::Packed);
+                let root_key = *root.root_key().key();
+                return Self::new(node.name, root.layout(), registry, root_key, cell)
+            }
+            Layout::Struct(struct_layout) => {
+                node.fields = Self::struct_fields(struct_layout, registry, root_key, cell)
+            }
+            Layout::Enum(enum_layout) => {
+                node.fields = enum_layout
+                    .variants()
+                    .values()
+                    .map(|variant| {
+                        let mut field =
+                            Self::leaf(variant.name().to_string(), root_key, cell);
+                        field.fields =
+                            Self::struct_fields(variant, registry, root_key, cell);
+                        field
+                    })
+                    .collect()
+            }
+            Layout::Array(array) => {
+                node.fields = vec![Self::new(
+                    format!("[0..{}]", array.len()),
+                    array.layout(),
+                    registry,
+                    root_key,
+                    cell,
+                )]
+            }
+            Layout::Hash(hash) => {
+                return Self::new(node.name, hash.layout(), registry, root_key, cell)
+            }
+            Layout::Leaf(_) => {}
+        }
+        node
+    }
+
+    fn leaf(name: String, root_key: u32, cell: StorageCellKind) -> Self {
+        Self {
+            name,
+            root_key,
+            cell,
+            storage_key: (cell != StorageCellKind::Mapping)
+                .then(|| ContractStorageKey::new(root_key).hashed_to_hex()),
+            fields: Vec::new(),
+        }
+    }
+
+    fn struct_fields(
+        struct_layout: &StructLayout<PortableForm>,
+        registry: &PortableRegistry,
+        root_key: u32,
+        cell: StorageCellKind,
+    ) -> Vec<Self> {
+        struct_layout
+            .fields()
+            .iter()
+            .map(|field| {
+                Self::new(
+                    field.name().to_string(),
+                    field.layout(),
+                    registry,
+                    root_key,
+                    cell,
+                )
+            })
+            .collect()
+    }
+
+    fn fmt_tree(
+        &self,
+        f: &mut Formatter<'_>,
+        prefix: &str,
+        parent: Option<(u32, StorageCellKind)>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        // only the fields starting a new storage cell are annotated
+        if parent != Some((self.root_key, self.cell)) {
+            let root_key = hex::encode(self.root_key.encode());
+            write!(f, " [{}] root key 0x{root_key}", self.cell)?;
+            match &self.storage_key {
+                Some(storage_key) => write!(f, ", storage key {storage_key}")?,
+                None => {
+                    write!(
+                        f,
+                        ", storage key blake2_128(0x{root_key} ++ key) ++ 0x{root_key} \
+                         ++ key"
+                    )?
+                }
+            }
+        }
+        writeln!(f)?;
+        for (i, field) in self.fields.iter().enumerate() {
+            let last = i == self.fields.len() - 1;
+            write!(f, "{prefix}{}", if last { "└── " } else { "├── " })?;
+            let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+            field.fmt_tree(f, &prefix, Some((self.root_key, self.cell)))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for StorageLayoutNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_tree(f, "", None)
+    }
+}
+
+/// A field of the storage of a contract, resolved from its path through the storage
+/// layout to the storage cell holding it.
+#[derive(Clone, Debug)]
+pub struct ContractStorageField {
+    path: String,
+    cell: StorageCellKind,
+    key: ContractStorageKey,
+    /// The type of the value stored in the cell, `None` for the length of a
+    /// `StorageVec`.
+    type_id: Option<u32>,
+    /// The path of the field within the value stored in the cell.
+    fields: Vec<String>,
+}
+
+impl ContractStorageField {
+    /// Resolves the field at `path` through the storage layout of the contract.
+    ///
+    /// The path consists of the names of the fields separated by `.`, starting with a
+    /// field of the contract storage struct, e.g. `ownable.owner`. An entry of a
+    /// `Mapping` is selected with its key in brackets, e.g. `balances[5GrwvaEF...]`,
+    /// and an element of a `StorageVec` with its index, e.g. `values[3]`. A
+    /// `StorageVec` without an index resolves to its length.
+    pub fn resolve(path: &str, decoder: &ContractMessageTranscoder) -> Result<Self> {
+        let mut field = Self {
+            path: path.to_string(),
+            cell: StorageCellKind::Packed,
+            key: ContractStorageKey::new(0),
+            type_id: None,
+            fields: Vec::new(),
+        };
+        let mut layout = match decoder.metadata().layout() {
+            Layout::Root(root) => field.enter_cell(root, "root", None, decoder)?,
+            layout => layout,
+        };
+        for (name, key) in parse_field_path(path)? {
+            let Layout::Struct(struct_layout) = layout else {
+                bail!("`{name}` of `{path}` is not the field of a struct")
+            };
+            let field_layout = struct_layout
+                .fields()
+                .iter()
+                .find(|field| *field.name() == name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "`{}` has no field `{name}`, expected one of: {}",
+                        struct_layout.name(),
+                        struct_layout.fields().iter().map(|f| f.name()).join(", ")
+                    )
+                })?;
+            layout = match field_layout.layout() {
+                Layout::Root(root) => field.enter_cell(root, &name, key, decoder)?,
+                layout => {
+                    if key.is_some() {
+                        bail!("`{name}` is neither a `Mapping` nor a `StorageVec`")
+                    }
+                    field.fields.push(name);
+                    layout
+                }
+            };
+        }
+        if field.type_id.is_none() && !field.fields.is_empty() {
+            bail!("The length of a `StorageVec` has no fields")
+        }
+        Ok(field)
+    }
+
+    /// Enters the storage cell of the `root` layout of the field `name`, and returns
+    /// the layout of the value stored in the cell.
+    fn enter_cell<'a>(
+        &mut self,
+        root: &'a RootLayout<PortableForm>,
+        name: &str,
+        key: Option<String>,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<&'a Layout<PortableForm>> {
+        let type_def = decoder
+            .metadata()
+            .registry()
+            .resolve(root.ty().id)
+            .ok_or_else(|| anyhow!("Type {} not found in the registry", root.ty().id))?;
+        let param = |param: &str| {
+            ContractStorageLayout::param_type_id(type_def, param)
+                .ok_or_else(|| anyhow!("Param `{param}` not found in type registry"))
+        };
+        self.cell = StorageCellKind::from_type(type_def);
+        self.key = ContractStorageKey::new(*root.root_key().key());
+        self.fields.clear();
+        self.type_id = match (self.cell, key) {
+            (StorageCellKind::Mapping, Some(key)) => {
+                let encoded = decoder
+                    .encode_value(param("K")?, &key)
+                    .context(format!("Invalid key `{key}` of `{name}`"))?;
+                self.key = self.key.clone().with_encoded_key(&encoded);
+                Some(param("V")?)
+            }
+            (StorageCellKind::Mapping, None) => {
+                bail!("`{name}` is a `Mapping`, select an entry with `{name}[<key>]`")
+            }
+            (StorageCellKind::StorageVec, Some(index)) => {
+                let index: u32 = index
+                    .parse()
+                    .context(format!("Invalid index `{index}` of `{name}`"))?;
+                self.key = self.key.clone().with_key(&index);
+                Some(param("V")?)
+            }
+            (StorageCellKind::StorageVec, None) => None,
+            (StorageCellKind::Lazy, None) => Some(param("V")?),
+            (StorageCellKind::Packed, None) => Some(root.ty().id),
+            (_, Some(_)) => {
+                bail!("`{name}` is neither a `Mapping` nor a `StorageVec`")
+            }
+        };
+        Ok(root.layout())
+    }
+
+    /// Decodes the value of the field from the `raw` value of its storage cell, `None`
+    /// if the cell is empty.
+    pub fn decode(
+        &self,
+        raw: Option<&[u8]>,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<Option<Value>> {
+        let Some(mut raw) = raw else { return Ok(None) };
+        let mut value = match self.type_id {
+            Some(type_id) => decoder.decode(type_id, &mut raw)?,
+            None => Value::UInt(u32::decode(&mut raw)?.into()),
+        };
+        for field in &self.fields {
+            let inner = match &value {
+                Value::Map(map) => map.get_by_str(field).cloned(),
+                Value::Tuple(tuple) => {
+                    field
+                        .parse()
+                        .ok()
+                        .and_then(|index| tuple.values().nth(index).cloned())
+                }
+                _ => None,
+            };
+            value = inner
+                .ok_or_else(|| anyhow!("Field `{field}` not found in `{value}`"))?;
+        }
+        Ok(Some(value))
+    }
+
+    /// Encodes the `value` of the field, e.g. `{ a: 1 }`, into the new raw value of its
+    /// storage cell.
+    ///
+    /// If the field is only a part of the value stored in the cell, e.g. `inner.c`, it
+    /// is replaced within the `current` raw value of the cell, which must not be empty.
+    pub fn encode(
+        &self,
+        value: &str,
+        current: Option<&[u8]>,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<Vec<u8>> {
+        let value: Value = value
+            .parse()
+            .context(format!("Invalid value `{value}` of `{}`", self.path))?;
+        let Some(type_id) = self.type_id else {
+            let Value::UInt(len) = value else {
+                bail!("The length of a `StorageVec` must be an unsigned integer")
+            };
+            return Ok(u32::try_from(len)?.encode())
+        };
+        if self.fields.is_empty() {
+            return decoder.encode_scon(type_id, &value)
+        }
+        let mut raw = current.ok_or_else(|| {
+            anyhow!(
+                "The storage cell of `{}` is empty, its fields can not be written",
+                self.path
+            )
+        })?;
+        let mut cell = decoder.decode(type_id, &mut raw)?;
+        let mut target = &mut cell;
+        for field in &self.fields {
+            target = match target {
+                Value::Map(map) => map.get_mut_by_str(field),
+                Value::Tuple(tuple) => {
+                    field
+                        .parse()
+                        .ok()
+                        .and_then(|index| tuple.values_mut().nth(index))
+                }
+                _ => None,
+            }
+            .ok_or_else(|| anyhow!("Field `{field}` not found in `{}`", self.path))?;
+        }
+        *target = value;
+        decoder.encode_scon(type_id, &cell)
+    }
+
+    /// Returns the key of the storage cell of the field.
+    pub fn key(&self) -> &ContractStorageKey {
+        &self.key
+    }
+}
+
+/// Splits the path of a storage field into the names of the fields and the keys of
+/// their entries, e.g. `balances[{ a: 1 }].b` into `(balances, Some({ a: 1 }))` and
+/// `(b, None)`.
+fn parse_field_path(path: &str) -> Result<Vec<(String, Option<String>)>> {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in path.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '(' | '{' if !quoted => depth += 1,
+            ']' | ')' | '}' if !quoted => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| anyhow!("Unbalanced `{c}` in `{path}`"))?
+            }
+            '.' if !quoted && depth == 0 => {
+                segments.push(&path[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 || quoted {
+        bail!("Unbalanced brackets or quotes in `{path}`")
+    }
+    segments.push(&path[start..]);
+    segments
+        .into_iter()
+        .map(|segment| {
+            let (name, key) = match segment.split_once('[') {
+                Some((name, key)) => {
+                    let key = key.strip_suffix(']').ok_or_else(|| {
+                        anyhow!("Expected `{segment}` to end with the key in brackets")
+                    })?;
+                    (name, Some(key.trim().to_string()))
+                }
+                None => (segment, None),
+            };
+            if name.is_empty() {
+                bail!("Empty field name in `{path}`")
+            }
+            Ok((name.to_string(), key))
+        })
+        .collect()
+}
+
+/// The decoded value of a field of the storage of a contract.
+#[derive(Serialize, Debug)]
+pub struct ContractStorageFieldValue {
+    /// The path of the field, e.g. `ownable.owner`.
+    pub path: String,
+    /// The storage cell the field is stored in.
+    pub cell: StorageCellKind,
+    /// The hashed key of the storage cell, see [`ContractStorageKey::hashed_to_hex`].
+    pub storage_key: String,
+    /// The value of the field, `None` if the storage cell is empty.
+    pub value: Option<Value>,
+}
+
+/// The raw storage of a single contract, which can be modified in place.
+///
+/// It is implemented for local environments only, i.e. the in-process sandbox and
+/// forked snapshots of the state of a contract, and never for live chains. Writing the
+/// storage directly bypasses the contract, e.g. to set up the state for testing a
+/// migration, and does not update the storage deposit of the contract.
+pub trait MutableContractStorage {
+    /// Returns the raw value of the storage cell with the `hashed_key`, `None` if the
+    /// cell is empty.
+    fn read_raw(&mut self, hashed_key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Writes the raw `value` of the storage cell with the `hashed_key`, removing the
+    /// cell if `value` is `None`.
+    fn write_raw(&mut self, hashed_key: &[u8], value: Option<Vec<u8>>) -> Result<()>;
+
+    /// Encodes the `value` of the field at `path` with the storage layout and writes it
+    /// to its storage cell, see [`ContractStorageField::encode`].
+    ///
+    /// Returns the field with the value it was written with.
+    fn write_field(
+        &mut self,
+        path: &str,
+        value: &str,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<ContractStorageFieldValue> {
+        let field = ContractStorageField::resolve(path, decoder)?;
+        let hashed_key = field.key.hashed();
+        let current = self.read_raw(&hashed_key)?;
+        let raw = field.encode(value, current.as_deref(), decoder)?;
+        let value = field.decode(Some(&raw), decoder)?;
+        self.write_raw(&hashed_key, Some(raw))?;
+        Ok(ContractStorageFieldValue {
+            path: field.path,
+            cell: field.cell,
+            storage_key: field.key.hashed_to_hex(),
+            value,
+        })
+    }
+}
+
+impl MutableContractStorage for ContractStorageData {
+    fn read_raw(&mut self, hashed_key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .0
+            .get(&Bytes::from(hashed_key.to_vec()))
+            .map(|value| value.0.clone()))
+    }
+
+    fn write_raw(&mut self, hashed_key: &[u8], value: Option<Vec<u8>>) -> Result<()> {
+        let key = Bytes::from(hashed_key.to_vec());
+        match value {
+            Some(value) => self.0.insert(key, Bytes::from(value)),
+            None => self.0.remove(&key),
+        };
+        Ok(())
+    }
+}
+
+/// The storage of a snapshot written by [`ContractStorage::fork_contract`], which is
+/// modified in place.
+pub struct ForkedContractStorage {
+    snapshot: serde_json::Value,
+    storage: ContractStorageData,
+}
+
+impl ForkedContractStorage {
+    /// Loads the storage of the snapshot at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read(path)
+            .with_context(|| format!("Failed to read the snapshot {}", path.display()))?;
+        let mut snapshot: serde_json::Value = serde_json::from_slice(&json)?;
+        let storage = snapshot
+            .get_mut("storage")
+            .map(serde_json::Value::take)
+            .ok_or_else(|| anyhow!("{} is not a contract snapshot", path.display()))?;
+        Ok(Self {
+            storage: serde_json::from_value(storage).with_context(|| {
+                format!("Invalid storage in the snapshot {}", path.display())
+            })?,
+            snapshot,
+        })
+    }
+
+    /// Writes the snapshot with the modified storage to `path`.
+    pub fn store(&mut self, path: &Path) -> Result<()> {
+        self.snapshot["storage"] = serde_json::to_value(&self.storage)?;
+        let json = serde_json::to_string_pretty(&self.snapshot)?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write the snapshot {}", path.display()))
+    }
+
+    /// The address of the forked contract.
+    pub fn contract(&self) -> Option<&str> {
+        self.snapshot.get("contract")?.as_str()
+    }
+}
+
+impl MutableContractStorage for ForkedContractStorage {
+    fn read_raw(&mut self, hashed_key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.storage.read_raw(hashed_key)
+    }
+
+    fn write_raw(&mut self, hashed_key: &[u8], value: Option<Vec<u8>>) -> Result<()> {
+        self.storage.write_raw(hashed_key, value)
+    }
+}
+
 /// Methods for querying contracts over RPC.
 pub struct ContractStorageRpc<C: Config> {
     rpc_client: RpcClient,
@@ -618,8 +1360,14 @@ where
 {
     /// Create a new instance of the ContractsRpc.
     pub async fn new(url: &url::Url) -> Result<Self> {
-        let rpc_client = RpcClient::from_url(url_to_string(url)).await?;
-        let client = OnlineClient::from_rpc_client(rpc_client.clone()).await?;
+        Self::with_options(url, RpcOptions::default()).await
+    }
+
+    /// Create a new instance of the ContractsRpc, which communicates with the node
+    /// according to `options`.
+    pub async fn with_options(url: &url::Url, options: RpcOptions) -> Result<Self> {
+        let rpc_client = rpc_client(&url_to_string(url), options.clone()).await?;
+        let client = online_client(rpc_client.clone(), options).await?;
         let rpc_methods = LegacyRpcMethods::new(rpc_client.clone());
 
         Ok(Self {
@@ -703,3 +1451,65 @@ where
         Ok(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_path_is_split_into_fields_and_keys() {
+        assert_eq!(
+            parse_field_path("ownable.owner").unwrap(),
+            vec![("ownable".to_string(), None), ("owner".to_string(), None)]
+        );
+        assert_eq!(
+            parse_field_path("balances[{ a: 1.5, b: \"x.y]\" }].b").unwrap(),
+            vec![
+                (
+                    "balances".to_string(),
+                    Some("{ a: 1.5, b: \"x.y]\" }".to_string())
+                ),
+                ("b".to_string(), None)
+            ]
+        );
+        assert!(parse_field_path("balances[1").is_err());
+        assert!(parse_field_path("a..b").is_err());
+    }
+
+    #[test]
+    fn hashed_storage_key_is_split_into_its_parts() {
+        let key = ContractStorageKey::new(0x12345678).with_key(&7u32);
+        let hashed = Bytes::from(key.hashed());
+
+        assert_eq!(hashed.0.len(), 24);
+        assert_eq!(
+            ContractStorageLayout::key_parts(&hashed),
+            (0x12345678, Some(Bytes::from(vec![7, 0, 0, 0])))
+        );
+        assert!(key.hashed_to_hex().ends_with("7856341207000000"));
+    }
+
+    #[test]
+    fn forked_storage_is_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flipper.fork.json");
+        let snapshot = r#"{
+            "block_hash": "0x00",
+            "contract": "5Flipper",
+            "storage": { "0x0102": "0x00" }
+        }"#;
+        fs::write(&path, snapshot).unwrap();
+
+        let mut fork = ForkedContractStorage::load(&path).unwrap();
+        assert_eq!(fork.contract(), Some("5Flipper"));
+        assert_eq!(fork.read_raw(&[1, 2]).unwrap(), Some(vec![0]));
+        fork.write_raw(&[1, 2], Some(vec![1])).unwrap();
+        fork.write_raw(&[3], Some(vec![4])).unwrap();
+        fork.store(&path).unwrap();
+
+        let mut fork = ForkedContractStorage::load(&path).unwrap();
+        assert_eq!(fork.snapshot["block_hash"], "0x00");
+        assert_eq!(fork.read_raw(&[1, 2]).unwrap(), Some(vec![1]));
+        assert_eq!(fork.read_raw(&[3]).unwrap(), Some(vec![4]));
+    }
+}
@@ -16,9 +16,15 @@
 
 use crate::contract_storage::{
     ContractStorageData,
+    ContractStorageField,
+    ContractStorageKey,
     ContractStorageLayout,
+    MutableContractStorage,
+};
+use contract_transcode::{
+    ContractMessageTranscoder,
+    Value,
 };
-use contract_transcode::ContractMessageTranscoder;
 
 use ink::{
     metadata::{
@@ -236,3 +242,118 @@ fn storage_decode_mapping_type_works() {
     );
     assert_eq!(cell.root_key(), hex::encode(lazy_type_root_encoded));
 }
+
+#[test]
+fn storage_field_is_resolved_and_decoded() {
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Inner {
+        b: u32,
+        c: bool,
+    }
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Data {
+        inner: Inner,
+        a: Mapping<u8, u8, ManualKey<LAZY_TYPE_ROOT_KEY>>,
+    }
+
+    let Struct(data_layout) = <Data as StorageLayout>::layout(&ROOT_KEY) else {
+        panic!("Layout shall be created");
+    };
+    let storage_layout: Layout = RootLayout::new(
+        LayoutKey::from(ROOT_KEY),
+        data_layout,
+        scale_info::meta_type::<Data>(),
+    )
+    .into();
+
+    let metadata = InkProject::new(storage_layout, contract_default_spec());
+    let decoder = ContractMessageTranscoder::new(metadata);
+
+    let value = Data {
+        inner: Inner { b: 1, c: true },
+        a: Mapping::new(),
+    };
+    let field = ContractStorageField::resolve("inner.c", &decoder)
+        .expect("Packed field shall be resolved");
+    assert_eq!(field.key(), &ContractStorageKey::new(ROOT_KEY));
+    assert_eq!(
+        field
+            .decode(Some(&encode_storage_value(&value).0), &decoder)
+            .unwrap(),
+        Some(Value::Bool(true))
+    );
+
+    let field = ContractStorageField::resolve("a[4]", &decoder)
+        .expect("Mapping entry shall be resolved");
+    assert_eq!(
+        field.key(),
+        &ContractStorageKey::new(LAZY_TYPE_ROOT_KEY).with_key(&4u8)
+    );
+    assert_eq!(
+        field.decode(Some(&[8]), &decoder).unwrap(),
+        Some(Value::UInt(8))
+    );
+    assert_eq!(field.decode(None, &decoder).unwrap(), None);
+
+    assert!(ContractStorageField::resolve("a", &decoder).is_err());
+    assert!(ContractStorageField::resolve("inner[1]", &decoder).is_err());
+    assert!(ContractStorageField::resolve("inner.d", &decoder).is_err());
+}
+
+#[test]
+fn storage_field_is_encoded_and_written() {
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Inner {
+        b: u32,
+        c: bool,
+    }
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Data {
+        inner: Inner,
+        a: Mapping<u8, u8, ManualKey<LAZY_TYPE_ROOT_KEY>>,
+    }
+
+    let Struct(data_layout) = <Data as StorageLayout>::layout(&ROOT_KEY) else {
+        panic!("Layout shall be created");
+    };
+    let storage_layout: Layout = RootLayout::new(
+        LayoutKey::from(ROOT_KEY),
+        data_layout,
+        scale_info::meta_type::<Data>(),
+    )
+    .into();
+
+    let metadata = InkProject::new(storage_layout, contract_default_spec());
+    let decoder = ContractMessageTranscoder::new(metadata);
+
+    let mut storage = ContractStorageData::new(BTreeMap::new());
+    assert!(storage.write_field("inner.c", "false", &decoder).is_err());
+    let value = Data {
+        inner: Inner { b: 0, c: false },
+        a: Mapping::new(),
+    };
+    let root_key = ContractStorageKey::new(ROOT_KEY).hashed();
+    storage
+        .write_raw(&root_key, Some(encode_storage_value(&value).0))
+        .unwrap();
+
+    let written = storage
+        .write_field("inner", "Inner { b: 1, c: true }", &decoder)
+        .expect("Packed field shall be written");
+    assert_eq!(written.storage_key, ContractStorageKey::new(ROOT_KEY).hashed_to_hex());
+    let written = storage
+        .write_field("inner.c", "false", &decoder)
+        .expect("Field of a packed field shall be written");
+    assert_eq!(written.value, Some(Value::Bool(false)));
+    let raw = storage.read_raw(&root_key).unwrap();
+    assert_eq!(raw, Some(Encode::encode(&(1u32, false))));
+
+    storage
+        .write_field("a[4]", "8", &decoder)
+        .expect("Mapping entry shall be written");
+    let key = ContractStorageKey::new(LAZY_TYPE_ROOT_KEY).with_key(&4u8);
+    assert_eq!(storage.read_raw(&key.hashed()).unwrap(), Some(vec![8]));
+
+    assert!(storage.write_field("a[4]", "256", &decoder).is_err());
+    assert!(storage.write_field("inner.b", "true", &decoder).is_err());
+}
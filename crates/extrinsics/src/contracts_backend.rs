@@ -0,0 +1,262 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use contract_transcode::AccountId32;
+use scale::{
+    Decode,
+    Encode,
+    Output,
+};
+use subxt::Metadata;
+
+/// The pallet implementing smart contracts on a chain.
+///
+/// Besides their names, the calls and runtime APIs of `pallet-revive` differ from the
+/// ones of `pallet-contracts`: contracts are addressed by an `H160` instead of their
+/// account, see [`ContractsBackend::contract_address`], and code is uploaded without a
+/// `determinism`.
+pub trait ContractsBackend: Send + Sync {
+    /// The name of the pallet in the runtime, e.g. `Contracts`.
+    fn pallet(&self) -> &'static str;
+
+    /// The name of the runtime API of the pallet, e.g. `ContractsApi`.
+    fn runtime_api(&self) -> &'static str;
+
+    /// The storage map of the contract infos, keyed by the contract address.
+    fn contract_info_storage(&self) -> &'static str {
+        "ContractInfoOf"
+    }
+
+    /// The storage map of the uploaded codes, keyed by the code hash.
+    fn code_storage(&self) -> &'static str {
+        "PristineCode"
+    }
+
+    /// The storage map of the owners, deposits and reference counts of the uploaded
+    /// codes, keyed by the code hash.
+    fn code_info_storage(&self) -> &'static str {
+        "CodeInfoOf"
+    }
+
+    /// Whether accounts must be mapped to an address with the `map_account` extrinsic
+    /// before they can interact with contracts.
+    fn requires_account_mapping(&self) -> bool {
+        false
+    }
+
+    /// Whether contracts are addressed by an `H160` instead of their account, see
+    /// [`eth_address`].
+    fn h160_addresses(&self) -> bool {
+        false
+    }
+
+    /// Whether code is uploaded with a `determinism`, i.e. whether code can be uploaded
+    /// for off-chain execution only.
+    fn upload_determinism(&self) -> bool {
+        true
+    }
+
+    /// Returns the address the contract with the SCALE encoded `account` is addressed by
+    /// in the calls, runtime APIs and storage of the pallet.
+    fn contract_address(&self, account: &[u8]) -> ContractAddress {
+        if self.h160_addresses() {
+            ContractAddress(eth_address(account).to_vec())
+        } else {
+            ContractAddress(account.to_vec())
+        }
+    }
+
+    /// Returns the name of the runtime API `method` as passed to `state_call`, e.g.
+    /// `ContractsApi_call`.
+    fn runtime_api_method(&self, method: &str) -> String {
+        format!("{}_{method}", self.runtime_api())
+    }
+}
+
+/// The backend of chains running `pallet-contracts`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PalletContracts;
+
+impl ContractsBackend for PalletContracts {
+    fn pallet(&self) -> &'static str {
+        "Contracts"
+    }
+
+    fn runtime_api(&self) -> &'static str {
+        "ContractsApi"
+    }
+}
+
+/// The backend of chains running `pallet-revive`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PalletRevive;
+
+impl ContractsBackend for PalletRevive {
+    fn pallet(&self) -> &'static str {
+        "Revive"
+    }
+
+    fn runtime_api(&self) -> &'static str {
+        "ReviveApi"
+    }
+
+    fn requires_account_mapping(&self) -> bool {
+        true
+    }
+
+    fn h160_addresses(&self) -> bool {
+        true
+    }
+
+    fn upload_determinism(&self) -> bool {
+        false
+    }
+}
+
+const BACKENDS: [&dyn ContractsBackend; 2] = [&PalletContracts, &PalletRevive];
+
+/// Returns the backend of the chain with the runtime `metadata`, failing if the chain
+/// runs neither of the contracts pallets.
+///
+/// `pallet-contracts` is preferred if a runtime includes both pallets.
+pub fn contracts_backend(metadata: &Metadata) -> Result<&'static dyn ContractsBackend> {
+    BACKENDS
+        .into_iter()
+        .find(|backend| metadata.pallet_by_name(backend.pallet()).is_some())
+        .ok_or_else(|| {
+            anyhow!(
+                "The runtime of the chain includes neither the `Contracts` nor the \
+                 `Revive` pallet"
+            )
+        })
+}
+
+/// Returns `true` if `pallet` is the name of a pallet implementing smart contracts.
+pub fn is_contracts_pallet(pallet: &str) -> bool {
+    BACKENDS.iter().any(|backend| backend.pallet() == pallet)
+}
+
+/// The SCALE encoded address of a contract, see [`ContractsBackend::contract_address`].
+///
+/// It is encoded as is, so that it can be passed to the runtime APIs of either pallet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractAddress(Vec<u8>);
+
+impl AsRef<[u8]> for ContractAddress {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Encode for ContractAddress {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&self.0)
+    }
+}
+
+/// Returns the address of an account, as derived by `pallet-revive`.
+///
+/// Accounts derived from an address, which are padded with `0xEE`, map back to it.
+/// Any other account maps to the last 20 bytes of its Keccak-256 hash.
+pub fn eth_address(account: &[u8]) -> [u8; 20] {
+    let mut address = [0u8; 20];
+    if account.len() == 32 && account[20..].iter().all(|byte| *byte == 0xEE) {
+        address.copy_from_slice(&account[..20]);
+    } else {
+        address.copy_from_slice(&sp_core::keccak_256(account)[12..]);
+    }
+    address
+}
+
+/// Returns the account an `H160` `address` is mapped to by `pallet-revive`, i.e. the
+/// address padded with `0xEE`, e.g. the account of a contract.
+pub fn eth_account<AccountId: Decode>(address: [u8; 20]) -> Result<AccountId> {
+    let account = AccountId32::from_eth_address(address);
+    AccountId::decode(&mut &account.0[..])
+        .map_err(|err| anyhow!("Failed to decode the account of an address: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_pallet_contracts() {
+        let bytes = std::fs::read("src/test_runtime_api/metadata_v15.scale").unwrap();
+        let metadata = Metadata::decode(&mut &bytes[..]).unwrap();
+
+        let backend = contracts_backend(&metadata).unwrap();
+
+        assert_eq!(backend.pallet(), "Contracts");
+        assert_eq!(backend.runtime_api_method("call"), "ContractsApi_call");
+        assert!(backend.upload_determinism());
+        assert_eq!(backend.contract_address(&[1; 32]).encode(), vec![1; 32]);
+    }
+
+    #[test]
+    fn detects_pallet_revive() {
+        let metadata = crate::test_metadata::revive_metadata();
+
+        let backend = contracts_backend(&metadata).unwrap();
+
+        assert_eq!(backend.pallet(), "Revive");
+        assert!(backend.h160_addresses());
+        assert!(!backend.upload_determinism());
+    }
+
+    #[test]
+    fn revive_contracts_are_addressed_by_h160() {
+        let account = AccountId32::from_eth_address([1; 20]);
+
+        assert_eq!(PalletRevive.contract_address(&account.0).encode(), vec![1; 20]);
+        assert_eq!(eth_account::<AccountId32>([1; 20]).unwrap(), account);
+    }
+
+    #[test]
+    fn eth_derived_account_maps_to_its_address() {
+        let mut account = [0xEE; 32];
+        account[..20].copy_from_slice(&[1; 20]);
+
+        assert_eq!(eth_address(&account), [1; 20]);
+    }
+
+    #[test]
+    fn account_maps_to_hash() {
+        let account = [1; 32];
+
+        assert_eq!(
+            eth_address(&account).to_vec(),
+            sp_core::keccak_256(&account)[12..].to_vec()
+        );
+        assert_ne!(eth_address(&account), [1; 20]);
+    }
+
+    #[test]
+    fn revive_names() {
+        assert_eq!(PalletRevive.runtime_api_method("call"), "ReviveApi_call");
+        assert_eq!(PalletRevive.contract_info_storage(), "ContractInfoOf");
+        assert!(is_contracts_pallet("Revive"));
+        assert!(!is_contracts_pallet("Balances"));
+    }
+}
@@ -0,0 +1,852 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A daemon keeping the connections to nodes open between invocations.
+//!
+//! The daemon listens on a local socket. A client sends the URL of the node as the first
+//! line, `{"url":"ws://localhost:9944"}`, which the daemon answers with `"ok"` once it
+//! is connected to the node, or with `{"error":"<reason>"}`. Afterwards, the client
+//! and the daemon exchange JSON-RPC messages, one per line, which the daemon forwards
+//! over its connection to the node.
+//!
+//! Responses which only change with the runtime of the node, like its metadata, are
+//! cached until the runtime is upgraded.
+//!
+//! Clients only use the daemon if they opt in with [`RpcOptions::daemon`] and its socket
+//! exists, and otherwise connect to the node directly. The socket is created in a
+//! directory only accessible by the user running the daemon, and clients refuse to
+//! connect to a socket owned by another user. The daemon is only supported on Unix.
+//!
+//! [`RpcOptions::daemon`]: crate::RpcOptions::daemon
+
+use std::{
+    env,
+    path::PathBuf,
+};
+
+#[cfg(unix)]
+pub(crate) use self::unix::connect;
+#[cfg(unix)]
+pub use self::unix::serve;
+
+/// The environment variable overriding the path of the socket of the daemon.
+pub const DAEMON_SOCKET_ENV: &str = "CARGO_CONTRACT_DAEMON_SOCKET";
+
+/// Returns the path of the socket of the daemon.
+///
+/// Defaults to `daemon.sock` in the directory `cargo-contract` in `$XDG_RUNTIME_DIR`, or
+/// in the directory `cargo-contract-<uid>` in the temporary directory if that is not
+/// set.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = env::var_os(DAEMON_SOCKET_ENV) {
+        return PathBuf::from(path)
+    }
+    let dir = match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("cargo-contract"),
+        None => env::temp_dir().join(format!("cargo-contract-{}", user_id())),
+    };
+    dir.join("daemon.sock")
+}
+
+/// Returns the id of the current user.
+#[cfg(unix)]
+fn user_id() -> String {
+    nix::unistd::geteuid().to_string()
+}
+
+/// Returns the name of the current user.
+#[cfg(not(unix))]
+fn user_id() -> String {
+    env::var("USERNAME").unwrap_or_default()
+}
+
+/// Returns the method cancelling a subscription started with `method`, or `None` if
+/// `method` does not start a subscription.
+fn unsubscribe_method(method: &str) -> Option<String> {
+    if method == "author_submitAndWatchExtrinsic" {
+        return Some("author_unwatchExtrinsic".to_string())
+    }
+    let (module, name) = method.split_once('_')?;
+    name.strip_prefix("subscribe")
+        .map(|event| format!("{module}_unsubscribe{event}"))
+}
+
+/// Returns `true` if the response to the request only changes with the runtime of
+/// the node.
+fn is_cacheable(method: &str, params: &[serde_json::Value]) -> bool {
+    let latest = |at: Option<&serde_json::Value>| at.map_or(true, |at| at.is_null());
+    match method {
+        "system_chain" | "system_properties" => true,
+        "state_getMetadata" | "state_getRuntimeVersion" => latest(params.first()),
+        "state_call" => {
+            params
+                .first()
+                .and_then(|call| call.as_str())
+                .is_some_and(|call| call.starts_with("Metadata_"))
+                && latest(params.get(2))
+        }
+        // the genesis hash
+        "chain_getBlockHash" => params == [serde_json::json!(0)],
+        _ => false,
+    }
+}
+
+/// Fails, since the daemon is only supported on Unix.
+#[cfg(not(unix))]
+pub async fn serve(
+    _path: &std::path::Path,
+    _options: crate::RpcOptions,
+) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!("The daemon is only supported on Unix"))
+}
+
+/// The first message of a client.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Handshake {
+    url: String,
+}
+
+/// The answer of the daemon to the [`Handshake`].
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum HandshakeResponse {
+    Ok,
+    Error(String),
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use crate::{
+        RetryingRpcClient,
+        RpcOptions,
+    };
+    use anyhow::{
+        anyhow,
+        Context,
+        Result,
+    };
+    use futures::{
+        future,
+        Stream,
+        StreamExt,
+    };
+    use jsonrpsee::{
+        core::{
+            async_trait,
+            client::{
+                Client,
+                Error,
+                ReceivedMessage,
+                TransportReceiverT,
+                TransportSenderT,
+            },
+        },
+        types::{
+            error::INTERNAL_ERROR_CODE,
+            ErrorObjectOwned,
+        },
+    };
+    use nix::unistd::geteuid;
+    use serde::Serialize;
+    use serde_json::value::RawValue;
+    use std::{
+        collections::HashMap,
+        fs::{
+            DirBuilder,
+            Permissions,
+        },
+        io,
+        os::unix::fs::{
+            DirBuilderExt,
+            MetadataExt,
+            PermissionsExt,
+        },
+        path::Path,
+        sync::{
+            atomic::{
+                AtomicU64,
+                Ordering,
+            },
+            Arc,
+            Mutex,
+            MutexGuard,
+        },
+    };
+    use subxt::backend::rpc::RpcClientT;
+    use tokio::{
+        io::{
+            AsyncBufReadExt,
+            AsyncWriteExt,
+            BufReader,
+            Lines,
+        },
+        net::{
+            unix::{
+                OwnedReadHalf,
+                OwnedWriteHalf,
+            },
+            UnixListener,
+            UnixStream,
+        },
+        sync::{
+            mpsc,
+            oneshot,
+        },
+        task::JoinHandle,
+    };
+
+    /// Connects to the node at `url` through the daemon, if it is running.
+    pub(crate) async fn connect(url: &str) -> Option<Client> {
+        let path = socket_path();
+        if !path.exists() {
+            return None
+        }
+        if let Err(err) = ensure_owned(&path) {
+            tracing::warn!("Not connecting through the daemon: {err}");
+            return None
+        }
+        match connect_to(&path, url).await {
+            Ok(client) => {
+                tracing::debug!(
+                    "Connected to {url} through the daemon at {}",
+                    path.display()
+                );
+                Some(client)
+            }
+            Err(err) => {
+                tracing::debug!(
+                    "Connecting through the daemon at {} failed: {err}",
+                    path.display()
+                );
+                None
+            }
+        }
+    }
+
+    pub(super) async fn connect_to(path: &Path, url: &str) -> Result<Client> {
+        let (read, mut write) = UnixStream::connect(path).await?.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let handshake = serde_json::to_string(&Handshake {
+            url: url.to_string(),
+        })?;
+        write_line(&mut write, &handshake).await?;
+        let response = lines
+            .next_line()
+            .await?
+            .context("The daemon closed the connection")?;
+        if let HandshakeResponse::Error(err) = serde_json::from_str(&response)? {
+            return Err(anyhow!(err))
+        }
+        Ok(Client::builder()
+            .max_buffer_capacity_per_subscription(4096)
+            .build_with_tokio(Sender(write), Receiver(lines)))
+    }
+
+    async fn write_line(write: &mut OwnedWriteHalf, line: &str) -> io::Result<()> {
+        write.write_all(line.as_bytes()).await?;
+        write.write_all(b"\n").await
+    }
+
+    /// Sends the messages of a client to the daemon.
+    struct Sender(OwnedWriteHalf);
+
+    #[async_trait]
+    impl TransportSenderT for Sender {
+        type Error = io::Error;
+
+        async fn send(&mut self, msg: String) -> io::Result<()> {
+            write_line(&mut self.0, &msg).await
+        }
+
+        async fn close(&mut self) -> io::Result<()> {
+            self.0.shutdown().await
+        }
+    }
+
+    /// Receives the messages of the daemon for a client.
+    struct Receiver(Lines<BufReader<OwnedReadHalf>>);
+
+    #[async_trait]
+    impl TransportReceiverT for Receiver {
+        type Error = io::Error;
+
+        async fn receive(&mut self) -> io::Result<ReceivedMessage> {
+            match self.0.next_line().await? {
+                Some(line) => Ok(ReceivedMessage::Text(line)),
+                None => {
+                    Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "The daemon closed the connection",
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Fails unless `path` is owned by the current user.
+    fn ensure_owned(path: &Path) -> Result<()> {
+        let owner = std::fs::symlink_metadata(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .uid();
+        if owner != geteuid().as_raw() {
+            return Err(anyhow!("{} is owned by another user", path.display()))
+        }
+        Ok(())
+    }
+
+    /// Creates the directory `dir` of the socket, only accessible by the current user.
+    ///
+    /// An existing directory is only used if it is owned by the current user and not
+    /// accessible by other users.
+    fn create_socket_dir(dir: &Path) -> Result<()> {
+        match DirBuilder::new().mode(0o700).create(dir) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                ensure_owned(dir)?;
+                let mode = std::fs::symlink_metadata(dir)
+                    .with_context(|| format!("Failed to read {}", dir.display()))?
+                    .mode();
+                if mode & 0o077 != 0 {
+                    return Err(anyhow!(
+                        "{} is accessible by other users, restrict it with `chmod 700`",
+                        dir.display()
+                    ))
+                }
+                Ok(())
+            }
+            Err(err) => {
+                Err(err).with_context(|| format!("Failed to create {}", dir.display()))
+            }
+        }
+    }
+
+    /// Serves clients on the socket at `path`, until the process is interrupted.
+    ///
+    /// The socket is only accessible by the current user, and its directory is created
+    /// only accessible by the current user if it does not exist. The connections to the
+    /// nodes are established with the given `options`.
+    pub async fn serve(path: &Path, options: RpcOptions) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            create_socket_dir(dir)?;
+        }
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(anyhow!(
+                "A daemon is already listening on {}",
+                path.display()
+            ))
+        }
+        // the socket of a daemon which was not shut down cleanly
+        let _ = std::fs::remove_file(path);
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        std::fs::set_permissions(path, Permissions::from_mode(0o600))?;
+        let daemon = Arc::new(Daemon {
+            options: RpcOptions {
+                daemon: false,
+                ..options
+            },
+            nodes: Default::default(),
+        });
+        let accept = async {
+            loop {
+                let (stream, _) = listener.accept().await?;
+                let daemon = daemon.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = daemon.serve_client(stream).await {
+                        tracing::debug!("Serving a client failed: {err}");
+                    }
+                });
+            }
+        };
+        let result = tokio::select! {
+            result = accept => result,
+            result = tokio::signal::ctrl_c() => result.map_err(Into::into),
+        };
+        let _ = std::fs::remove_file(path);
+        result
+    }
+
+    struct Daemon {
+        options: RpcOptions,
+        /// The connected nodes, by URL.
+        nodes: tokio::sync::Mutex<HashMap<String, Arc<Node>>>,
+    }
+
+    impl Daemon {
+        /// Returns the node at `url`, connecting to it if necessary.
+        async fn node(self: &Arc<Self>, url: &str) -> Result<Arc<Node>> {
+            let mut nodes = self.nodes.lock().await;
+            if let Some(node) = nodes.get(url) {
+                return Ok(node.clone())
+            }
+            let node = Arc::new(Node {
+                rpc: RetryingRpcClient::new(url, self.options.clone()).await?,
+                cache: Mutex::new(None),
+            });
+            match node
+                .rpc
+                .subscribe_raw(
+                    "state_subscribeRuntimeVersion",
+                    None,
+                    "state_unsubscribeRuntimeVersion",
+                )
+                .await
+            {
+                Ok(versions) => {
+                    *node.cache() = Some(HashMap::new());
+                    let (daemon, node, url) =
+                        (self.clone(), node.clone(), url.to_string());
+                    tokio::spawn(async move {
+                        node.clear_cache_on_upgrades(versions.stream).await;
+                        // reconnect with the next client, to watch the runtime again
+                        daemon.nodes.lock().await.remove(&url);
+                    });
+                }
+                Err(err) => tracing::debug!("Not caching the responses of {url}: {err}"),
+            }
+            nodes.insert(url.to_string(), node.clone());
+            Ok(node)
+        }
+
+        async fn serve_client(self: Arc<Self>, stream: UnixStream) -> Result<()> {
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let (sender, mut outgoing) = mpsc::unbounded_channel::<String>();
+            let writer = tokio::spawn(async move {
+                while let Some(line) = outgoing.recv().await {
+                    if write_line(&mut write, &line).await.is_err() {
+                        break
+                    }
+                }
+            });
+
+            let Some(handshake) = lines.next_line().await? else {
+                return Ok(())
+            };
+            let Handshake { url } = serde_json::from_str(&handshake)?;
+            let (response, node) = match self.node(&url).await {
+                Ok(node) => (HandshakeResponse::Ok, Some(node)),
+                Err(err) => (HandshakeResponse::Error(format!("{err:#}")), None),
+            };
+            let _ = sender.send(serde_json::to_string(&response)?);
+            let Some(node) = node else {
+                drop(sender);
+                let _ = writer.await;
+                return Ok(())
+            };
+
+            let session = Arc::new(Session {
+                node,
+                sender,
+                subscriptions: Default::default(),
+                next_subscription: AtomicU64::new(0),
+            });
+            while let Some(line) = lines.next_line().await? {
+                let session = session.clone();
+                tokio::spawn(async move { session.handle(&line).await });
+            }
+            for (_, relay) in session.subscriptions().drain() {
+                relay.abort();
+            }
+            Ok(())
+        }
+    }
+
+    /// Responses by request.
+    type Cache = HashMap<String, Box<RawValue>>;
+
+    /// A connection to a node shared by all clients.
+    struct Node {
+        rpc: RetryingRpcClient,
+        /// The cached responses by request, `None` if the runtime upgrades of the node
+        /// are not observed.
+        cache: Mutex<Option<Cache>>,
+    }
+
+    impl Node {
+        fn cache(&self) -> MutexGuard<'_, Option<Cache>> {
+            self.cache.lock().expect("cache lock is never poisoned")
+        }
+
+        async fn clear_cache_on_upgrades(
+            &self,
+            mut versions: impl Stream<Item = Result<Box<RawValue>, subxt::error::RpcError>>
+                + Unpin,
+        ) {
+            // the first notification is the current version
+            let _ = versions.next().await;
+            while let Some(Ok(_)) = versions.next().await {
+                tracing::debug!("The runtime was upgraded, clearing the cache");
+                if let Some(cache) = self.cache().as_mut() {
+                    cache.clear()
+                }
+            }
+            *self.cache() = None;
+        }
+
+        async fn request(
+            &self,
+            method: &str,
+            params: Option<Box<RawValue>>,
+        ) -> Result<Box<RawValue>, ErrorObjectOwned> {
+            let values: Vec<serde_json::Value> = params
+                .as_ref()
+                .and_then(|params| serde_json::from_str(params.get()).ok())
+                .unwrap_or_default();
+            let key = is_cacheable(method, &values)
+                .then(|| format!("{method}{}", params.as_ref().map_or("", |p| p.get())));
+            if let Some(key) = &key {
+                if let Some(cached) = self.cache().as_ref().and_then(|c| c.get(key)) {
+                    return Ok(cached.clone())
+                }
+            }
+            let result = self.rpc.request(method, params).await.map_err(|err| {
+                match err {
+                    Error::Call(err) => err,
+                    err => internal_error(err),
+                }
+            })?;
+            if let (Some(key), Some(cache)) = (key, self.cache().as_mut()) {
+                cache.insert(key, result.clone());
+            }
+            Ok(result)
+        }
+    }
+
+    /// The connection of a client.
+    struct Session {
+        node: Arc<Node>,
+        /// Sends lines to the client.
+        sender: mpsc::UnboundedSender<String>,
+        /// The tasks relaying the notifications of the subscriptions, by their id.
+        subscriptions: Mutex<HashMap<String, JoinHandle<()>>>,
+        next_subscription: AtomicU64,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Request {
+        id: serde_json::Value,
+        method: String,
+        params: Option<Box<RawValue>>,
+    }
+
+    #[derive(Serialize)]
+    struct Response<'a> {
+        jsonrpc: &'static str,
+        id: &'a serde_json::Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<&'a RawValue>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a ErrorObjectOwned>,
+    }
+
+    #[derive(Serialize)]
+    struct Notification<'a> {
+        jsonrpc: &'static str,
+        method: &'a str,
+        params: NotificationParams<'a>,
+    }
+
+    #[derive(Serialize)]
+    struct NotificationParams<'a> {
+        subscription: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<&'a RawValue>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a ErrorObjectOwned>,
+    }
+
+    impl Session {
+        fn subscriptions(&self) -> MutexGuard<'_, HashMap<String, JoinHandle<()>>> {
+            self.subscriptions
+                .lock()
+                .expect("subscriptions lock is never poisoned")
+        }
+
+        /// Handles a line sent by the client, a request or a batch of requests.
+        async fn handle(self: &Arc<Self>, line: &str) {
+            let (response, relays) = match serde_json::from_str::<Vec<Request>>(line) {
+                Ok(batch) => {
+                    let (responses, relays): (Vec<_>, Vec<_>) =
+                        future::join_all(batch.into_iter().map(|req| self.respond(req)))
+                            .await
+                            .into_iter()
+                            .unzip();
+                    (format!("[{}]", responses.join(",")), relays)
+                }
+                Err(_) => {
+                    match serde_json::from_str::<Request>(line) {
+                        Ok(request) => {
+                            let (response, relay) = self.respond(request).await;
+                            (response, vec![relay])
+                        }
+                        Err(err) => {
+                            tracing::debug!("Ignoring invalid request {line}: {err}");
+                            return
+                        }
+                    }
+                }
+            };
+            let _ = self.sender.send(response);
+            // the client has to receive the id of a subscription before its notifications
+            for start in relays.into_iter().flatten() {
+                let _ = start.send(());
+            }
+        }
+
+        /// Performs the `request` and returns the response to it, and for a subscription
+        /// the trigger to start relaying its notifications.
+        async fn respond(
+            self: &Arc<Self>,
+            request: Request,
+        ) -> (String, Option<oneshot::Sender<()>>) {
+            let Request { id, method, params } = request;
+            let (result, relay) = if let Some(relay) = self.unsubscribed(&method, &params)
+            {
+                relay.abort();
+                let result = RawValue::from_string("true".to_string())
+                    .expect("`true` is valid JSON");
+                (Ok(result), None)
+            } else if let Some(unsubscribe) = unsubscribe_method(&method) {
+                match self.subscribe(&method, params, &unsubscribe).await {
+                    Ok((result, start)) => (Ok(result), Some(start)),
+                    Err(err) => (Err(err), None),
+                }
+            } else {
+                (self.node.request(&method, params).await, None)
+            };
+            (response(&id, result), relay)
+        }
+
+        /// Returns the relay of the subscription cancelled by the request, if it
+        /// cancels one.
+        fn unsubscribed(
+            &self,
+            method: &str,
+            params: &Option<Box<RawValue>>,
+        ) -> Option<JoinHandle<()>> {
+            if !(method.contains("_unsubscribe") || method == "author_unwatchExtrinsic") {
+                return None
+            }
+            let [id]: [String; 1] = serde_json::from_str(params.as_ref()?.get()).ok()?;
+            self.subscriptions().remove(&id)
+        }
+
+        /// Subscribes to the node, and relays the notifications to the client once
+        /// triggered.
+        ///
+        /// Returns the id of the subscription.
+        async fn subscribe(
+            self: &Arc<Self>,
+            method: &str,
+            params: Option<Box<RawValue>>,
+            unsubscribe: &str,
+        ) -> Result<(Box<RawValue>, oneshot::Sender<()>), ErrorObjectOwned> {
+            let mut notifications = self
+                .node
+                .rpc
+                .subscribe_raw(method, params, unsubscribe)
+                .await
+                .map_err(internal_error)?
+                .stream;
+            let id = self
+                .next_subscription
+                .fetch_add(1, Ordering::Relaxed)
+                .to_string();
+            let (start, started) = oneshot::channel();
+            let relay = tokio::spawn({
+                let (session, method, id) =
+                    (self.clone(), method.to_string(), id.clone());
+                async move {
+                    if started.await.is_err() {
+                        return
+                    }
+                    let error = loop {
+                        match notifications.next().await {
+                            Some(Ok(result)) => {
+                                session.notify(&method, &id, Some(&result), None)
+                            }
+                            Some(Err(err)) => break internal_error(err),
+                            None => {
+                                break internal_error("The node closed the subscription")
+                            }
+                        }
+                    };
+                    session.notify(&method, &id, None, Some(&error));
+                    session.subscriptions().remove(&id);
+                }
+            });
+            self.subscriptions().insert(id.clone(), relay);
+            let id =
+                serde_json::value::to_raw_value(&id).expect("a string is valid JSON");
+            Ok((id, start))
+        }
+
+        fn notify(
+            &self,
+            method: &str,
+            subscription: &str,
+            result: Option<&RawValue>,
+            error: Option<&ErrorObjectOwned>,
+        ) {
+            let notification = Notification {
+                jsonrpc: "2.0",
+                method,
+                params: NotificationParams {
+                    subscription,
+                    result,
+                    error,
+                },
+            };
+            let _ = self.sender.send(to_line(&notification));
+        }
+    }
+
+    fn response(
+        id: &serde_json::Value,
+        result: Result<Box<RawValue>, ErrorObjectOwned>,
+    ) -> String {
+        let (result, error) = match &result {
+            Ok(result) => (Some(&**result), None),
+            Err(error) => (None, Some(error)),
+        };
+        to_line(&Response {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        })
+    }
+
+    /// Serializes a message on a single line.
+    fn to_line(message: &impl Serialize) -> String {
+        let line = serde_json::to_string(message).expect("messages are valid JSON");
+        if !line.contains('\n') {
+            return line
+        }
+        // the raw values returned by the node may span multiple lines
+        let value: serde_json::Value =
+            serde_json::from_str(&line).expect("messages are valid JSON");
+        value.to_string()
+    }
+
+    fn internal_error(err: impl ToString) -> ErrorObjectOwned {
+        ErrorObjectOwned::owned::<()>(INTERNAL_ERROR_CODE, err.to_string(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn subscriptions_are_detected() {
+        assert_eq!(
+            unsubscribe_method("chain_subscribeFinalizedHeads").as_deref(),
+            Some("chain_unsubscribeFinalizedHeads")
+        );
+        assert_eq!(
+            unsubscribe_method("author_submitAndWatchExtrinsic").as_deref(),
+            Some("author_unwatchExtrinsic")
+        );
+        assert_eq!(unsubscribe_method("chain_getBlockHash"), None);
+        assert_eq!(unsubscribe_method("rpc_methods"), None);
+    }
+
+    #[test]
+    fn only_runtime_dependent_responses_are_cached() {
+        assert!(is_cacheable("state_getMetadata", &[]));
+        assert!(is_cacheable(
+            "state_call",
+            &[json!("Metadata_metadata_at_version"), json!("0x0f000000")]
+        ));
+        assert!(is_cacheable("chain_getBlockHash", &[json!(0)]));
+        assert!(!is_cacheable("chain_getBlockHash", &[]));
+        assert!(!is_cacheable("state_getMetadata", &[json!("0x1234")]));
+        assert!(!is_cacheable(
+            "state_call",
+            &[json!("ContractsApi_call"), json!("0x00")]
+        ));
+        assert!(!is_cacheable("system_accountNextIndex", &[json!("5Foo")]));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn handshake_reports_connection_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cargo-contract").join("daemon.sock");
+        let daemon = tokio::spawn({
+            let path = path.clone();
+            async move { serve(&path, Default::default()).await }
+        });
+        while !path.exists() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        let err = unix::connect_to(&path, "ftp://localhost:9944")
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("`ftp`"), "{err}");
+        daemon.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_is_only_accessible_by_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cargo-contract").join("daemon.sock");
+        let daemon = tokio::spawn({
+            let path = path.clone();
+            async move { serve(&path, Default::default()).await }
+        });
+        while !path.exists() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        // the daemon accepts clients only after restricting the socket
+        let _ = unix::connect_to(&path, "ftp://localhost:9944").await;
+        let mode = |path: &std::path::Path| {
+            std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+        };
+
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+        assert_eq!(mode(&path), 0o600);
+        daemon.abort();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_dir_accessible_by_others_is_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket_dir = dir.path().join("cargo-contract");
+        std::fs::create_dir(&socket_dir).unwrap();
+        std::fs::set_permissions(&socket_dir, PermissionsExt::from_mode(0o755))
+            .unwrap();
+
+        let err = serve(&socket_dir.join("daemon.sock"), Default::default())
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("accessible by other users"), "{err}");
+        assert!(!socket_dir.join("daemon.sock").exists());
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::contracts_backend::is_contracts_pallet;
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_transcode::{
+    AccountId32,
+    ContractMessageTranscoder,
+};
+use subxt::{
+    ext::{
+        codec::{
+            Compact,
+            Decode,
+        },
+        scale_decode::visitor::{
+            decode_with_visitor,
+            IgnoreVisitor,
+        },
+        scale_value::{
+            self,
+            Composite,
+            Primitive,
+            Value,
+            ValueDef,
+            Variant,
+        },
+    },
+    Metadata,
+};
+
+/// The version of the extrinsic format which can be decoded.
+const EXTRINSIC_VERSION: u8 = 4;
+
+/// A call of a contract found in an extrinsic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractCallData {
+    /// The outer calls the call of the contract is wrapped into, outermost first, e.g.
+    /// `Sudo::sudo`.
+    pub wrapped_in: Vec<String>,
+    /// The pallet of the call, `Contracts` or `Revive`.
+    pub pallet: String,
+    /// The address of the called contract.
+    pub contract: Vec<u8>,
+    /// The balance transferred to the contract.
+    pub value: u128,
+    /// The input data of the contract: the selector of the message followed by its
+    /// encoded arguments.
+    pub data: Vec<u8>,
+}
+
+impl ContractCallData {
+    /// Returns the address of the called contract, as SS58 address with the
+    /// `ss58_prefix` if it is 32 bytes long and hex encoded otherwise.
+    pub fn contract_address(&self, ss58_prefix: u16) -> String {
+        match <[u8; 32]>::try_from(self.contract.as_slice()) {
+            Ok(account) => AccountId32(account).to_ss58check_with_prefix(ss58_prefix),
+            Err(_) => format!("0x{}", hex::encode(&self.contract)),
+        }
+    }
+
+    /// Decodes the message and arguments of the call with the first of the
+    /// `transcoders` which knows the selector, `None` if none of them does.
+    pub fn decode_message<'a>(
+        &self,
+        transcoders: impl IntoIterator<Item = &'a ContractMessageTranscoder>,
+    ) -> Option<contract_transcode::Value> {
+        transcoders.into_iter().find_map(|transcoder| {
+            transcoder
+                .decode_contract_message(&mut &self.data[..])
+                .ok()
+        })
+    }
+
+    fn from_fields(
+        pallet: &str,
+        wrapped_in: Vec<String>,
+        fields: &Composite<u32>,
+    ) -> Result<Self> {
+        let field = |name: &str| {
+            let Composite::Named(fields) = fields else {
+                return None
+            };
+            fields
+                .iter()
+                .find_map(|(field, value)| (field == name).then_some(value))
+        };
+        let contract = field("dest")
+            .and_then(bytes)
+            .ok_or_else(|| anyhow!("Failed to decode the `dest` of the call"))?;
+        let value = field("value")
+            .and_then(Value::as_u128)
+            .ok_or_else(|| anyhow!("Failed to decode the `value` of the call"))?;
+        let data = field("data")
+            .and_then(bytes)
+            .ok_or_else(|| anyhow!("Failed to decode the `data` of the call"))?;
+        Ok(Self {
+            wrapped_in,
+            pallet: pallet.to_string(),
+            contract,
+            value,
+            data,
+        })
+    }
+}
+
+/// Decodes the calls of contracts in an extrinsic of the chain with the runtime
+/// `metadata`.
+///
+/// The `bytes` are either the encoded extrinsic, as submitted to the node, or only its
+/// call, e.g. the call of a multisig operation or of a proposal. Calls of contracts
+/// wrapped into other calls, e.g. `Sudo::sudo` or `Utility::batch`, are found as well.
+pub fn decode_contract_calls(
+    metadata: &Metadata,
+    bytes: &[u8],
+) -> Result<Vec<ContractCallData>> {
+    let call_data = extrinsic_call_data(metadata, bytes).unwrap_or(bytes);
+    let call_ty = metadata.outer_enums().call_enum_ty();
+    let cursor = &mut &call_data[..];
+    let call = scale_value::scale::decode_as_type(cursor, &call_ty, metadata.types())
+        .context("Failed to decode the call with the runtime metadata of the chain")?;
+    if !cursor.is_empty() {
+        return Err(anyhow!(
+            "Failed to decode the call, {} bytes were left unread",
+            cursor.len()
+        ))
+    }
+    let mut calls = Vec::new();
+    collect_contract_calls(&call, call_ty, &mut Vec::new(), &mut calls)?;
+    Ok(calls)
+}
+
+/// Returns the call of the encoded `extrinsic`, `None` if the bytes are no extrinsic.
+fn extrinsic_call_data<'a>(metadata: &Metadata, extrinsic: &'a [u8]) -> Option<&'a [u8]> {
+    const SIGNED_MASK: u8 = 0b1000_0000;
+    const VERSION_MASK: u8 = 0b0111_1111;
+
+    let cursor = &mut &extrinsic[..];
+    let len = Compact::<u32>::decode(cursor).ok()?.0;
+    if len as usize != cursor.len() {
+        return None
+    }
+    let version = u8::decode(cursor).ok()?;
+    if version & VERSION_MASK != EXTRINSIC_VERSION {
+        return None
+    }
+    if version & SIGNED_MASK != 0 {
+        // Skip the address, signature and signed extensions of the signer
+        let extrinsic = metadata.extrinsic();
+        for ty in [
+            extrinsic.address_ty(),
+            extrinsic.signature_ty(),
+            extrinsic.extra_ty(),
+        ] {
+            decode_with_visitor(cursor, &ty, metadata.types(), IgnoreVisitor::new())
+                .ok()?;
+        }
+    }
+    Some(cursor)
+}
+
+/// Collects the calls of contracts in the `value`, descending into the calls it wraps.
+fn collect_contract_calls(
+    value: &Value<u32>,
+    call_ty: u32,
+    wrapped_in: &mut Vec<String>,
+    calls: &mut Vec<ContractCallData>,
+) -> Result<()> {
+    if value.context == call_ty {
+        // The outer call is the variant of the pallet, holding the variant of the call.
+        if let ValueDef::Variant(Variant {
+            name: pallet,
+            values: Composite::Unnamed(pallet_call),
+        }) = &value.value
+        {
+            if let [Value {
+                value: ValueDef::Variant(Variant { name, values }),
+                ..
+            }] = &pallet_call[..]
+            {
+                if is_contracts_pallet(pallet) && name == "call" {
+                    let call =
+                        ContractCallData::from_fields(pallet, wrapped_in.clone(), values)?;
+                    calls.push(call);
+                    return Ok(())
+                }
+                wrapped_in.push(format!("{pallet}::{name}"));
+                for value in values.values() {
+                    collect_contract_calls(value, call_ty, wrapped_in, calls)?;
+                }
+                wrapped_in.pop();
+                return Ok(())
+            }
+        }
+    }
+    let values = match &value.value {
+        ValueDef::Composite(composite) => composite,
+        ValueDef::Variant(variant) => &variant.values,
+        _ => return Ok(()),
+    };
+    for value in values.values() {
+        collect_contract_calls(value, call_ty, wrapped_in, calls)?;
+    }
+    Ok(())
+}
+
+/// Returns the bytes of a value composed of `u8`s, e.g. of an account or a `Vec<u8>`.
+///
+/// The bytes of a variant are those of its fields, e.g. of `MultiAddress::Id`.
+fn bytes(value: &Value<u32>) -> Option<Vec<u8>> {
+    let values = match &value.value {
+        ValueDef::Primitive(Primitive::U128(byte)) => {
+            return u8::try_from(*byte).ok().map(|byte| vec![byte])
+        }
+        ValueDef::Composite(composite) => composite,
+        ValueDef::Variant(variant) => &variant.values,
+        _ => return None,
+    };
+    let mut bytes = Vec::new();
+    for value in values.values() {
+        bytes.extend(self::bytes(value)?);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use subxt::{
+        ext::codec::Encode,
+        tx::TxPayload,
+    };
+
+    fn metadata() -> Metadata {
+        let bytes = fs::read("src/test_runtime_api/metadata_v15.scale").unwrap();
+        Metadata::decode(&mut &bytes[..]).unwrap()
+    }
+
+    fn contract_call(metadata: &Metadata) -> Vec<u8> {
+        subxt::dynamic::tx(
+            "Contracts",
+            "call",
+            vec![
+                Value::unnamed_variant("Id", [Value::from_bytes([1u8; 32])]),
+                Value::u128(5),
+                Value::named_composite([
+                    ("ref_time", Value::u128(1_000)),
+                    ("proof_size", Value::u128(2_000)),
+                ]),
+                Value::unnamed_variant("None", []),
+                Value::from_bytes([0x63, 0x3a, 0xa5, 0x51, 0x01]),
+            ],
+        )
+        .encode_call_data(metadata)
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_contract_call_from_call_data() {
+        let metadata = metadata();
+
+        let calls = decode_contract_calls(&metadata, &contract_call(&metadata)).unwrap();
+
+        assert_eq!(
+            calls,
+            vec![ContractCallData {
+                wrapped_in: Vec::new(),
+                pallet: "Contracts".to_string(),
+                contract: vec![1; 32],
+                value: 5,
+                data: vec![0x63, 0x3a, 0xa5, 0x51, 0x01],
+            }]
+        );
+        assert_eq!(
+            calls[0].contract_address(0),
+            AccountId32([1; 32]).to_ss58check_with_prefix(0)
+        );
+    }
+
+    #[test]
+    fn decodes_contract_call_from_unsigned_extrinsic() {
+        let metadata = metadata();
+        let mut extrinsic = vec![EXTRINSIC_VERSION];
+        extrinsic.extend(contract_call(&metadata));
+
+        let calls = decode_contract_calls(&metadata, &extrinsic.encode()).unwrap();
+
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].data, vec![0x63, 0x3a, 0xa5, 0x51, 0x01]);
+    }
+
+    #[test]
+    fn fails_on_trailing_bytes() {
+        let metadata = metadata();
+        let mut call = contract_call(&metadata);
+        call.push(0);
+
+        assert!(decode_contract_calls(&metadata, &call).is_err());
+    }
+}
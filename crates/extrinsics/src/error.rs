@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
+use itertools::Itertools;
 use sp_runtime::DispatchError;
 use std::fmt::{
     self,
@@ -21,10 +22,55 @@ use std::fmt::{
     Display,
 };
 
+/// The exit code of cargo-contract failing with any error not covered by another code.
+pub const EXIT_CODE_GENERIC: i32 = 1;
+/// The exit code of cargo-contract failing to parse the command line arguments.
+pub const EXIT_CODE_USAGE: i32 = 2;
+/// The exit code of cargo-contract if the dry-run of a message or constructor reverted.
+pub const EXIT_CODE_DRY_RUN_REVERT: i32 = 3;
+/// The exit code of cargo-contract if an extrinsic or its dry-run failed with an error
+/// of a pallet.
+pub const EXIT_CODE_MODULE_ERROR: i32 = 4;
+/// The exit code of cargo-contract if a request to the node failed.
+pub const EXIT_CODE_RPC_ERROR: i32 = 5;
+/// The exit code of cargo-contract if the contract artifacts were not found.
+pub const EXIT_CODE_ARTIFACT_MISSING: i32 = 6;
+/// The exit code of cargo-contract if data, e.g. the return value of a message, could
+/// not be decoded.
+pub const EXIT_CODE_DECODE_ERROR: i32 = 7;
+
+/// The exit codes of cargo-contract with their meaning.
+///
+/// The codes are stable, so that scripts can branch on the kind of a failure.
+pub const EXIT_CODES: [(i32, &str); 8] = [
+    (0, "Success"),
+    (EXIT_CODE_GENERIC, "Any error not covered by another exit code"),
+    (EXIT_CODE_USAGE, "Invalid command line arguments"),
+    (EXIT_CODE_DRY_RUN_REVERT, "The dry-run of a message or constructor reverted"),
+    (
+        EXIT_CODE_MODULE_ERROR,
+        "The extrinsic or its dry-run failed with an error of a pallet",
+    ),
+    (EXIT_CODE_RPC_ERROR, "A request to the node failed"),
+    (EXIT_CODE_ARTIFACT_MISSING, "The contract artifacts were not found"),
+    (
+        EXIT_CODE_DECODE_ERROR,
+        "Failed to decode data, e.g. the return value of a message",
+    ),
+];
+
 #[derive(serde::Serialize)]
 pub enum ErrorVariant {
     #[serde(rename = "module_error")]
     Module(ModuleError),
+    #[serde(rename = "dry_run_revert")]
+    DryRunRevert(GenericError),
+    #[serde(rename = "rpc_error")]
+    Rpc(GenericError),
+    #[serde(rename = "artifact_missing")]
+    ArtifactMissing(GenericError),
+    #[serde(rename = "decode_error")]
+    Decode(GenericError),
     #[serde(rename = "generic_error")]
     Generic(GenericError),
 }
@@ -33,21 +79,13 @@ impl From<subxt::Error> for ErrorVariant {
     fn from(error: subxt::Error) -> Self {
         match error {
             subxt::Error::Runtime(subxt::error::DispatchError::Module(module_err)) => {
-                module_err
-                    .details()
-                    .map(|details| {
-                        ErrorVariant::Module(ModuleError {
-                            pallet: details.pallet.name().to_string(),
-                            error: details.variant.name.to_string(),
-                            docs: details.variant.docs.clone(),
-                        })
-                    })
-                    .unwrap_or_else(|err| {
-                        ErrorVariant::Generic(GenericError::from_message(format!(
-                            "Error extracting subxt error details: {}",
-                            err
-                        )))
-                    })
+                ErrorVariant::from_module_error(&module_err)
+            }
+            err @ (subxt::Error::Io(_) | subxt::Error::Rpc(_)) => {
+                ErrorVariant::Rpc(GenericError::from_message(err.to_string()))
+            }
+            err @ (subxt::Error::Codec(_) | subxt::Error::Decode(_)) => {
+                ErrorVariant::Decode(GenericError::from_message(err.to_string()))
             }
             err => ErrorVariant::Generic(GenericError::from_message(err.to_string())),
         }
@@ -56,8 +94,48 @@ impl From<subxt::Error> for ErrorVariant {
 
 impl From<anyhow::Error> for ErrorVariant {
     fn from(error: anyhow::Error) -> Self {
-        Self::Generic(GenericError::from_message(format!("{error:?}")))
+        let module_err = error.chain().find_map(|cause| {
+            match cause.downcast_ref::<subxt::Error>() {
+                Some(subxt::Error::Runtime(subxt::error::DispatchError::Module(err))) => {
+                    Some(err)
+                }
+                _ => None,
+            }
+        });
+        if let Some(module_err) = module_err {
+            return ErrorVariant::from_module_error(module_err)
+        }
+        let variant = classify(&error);
+        variant(GenericError::from_message(format!("{error:?}")))
+    }
+}
+
+/// Returns the variant for the first error in the chain of `error` indicating the kind
+/// of the failure, [`ErrorVariant::Generic`] if there is none.
+fn classify(error: &anyhow::Error) -> fn(GenericError) -> ErrorVariant {
+    for cause in error.chain() {
+        if let Some(err) = cause.downcast_ref::<subxt::Error>() {
+            match err {
+                subxt::Error::Io(_) | subxt::Error::Rpc(_) => return ErrorVariant::Rpc,
+                subxt::Error::Codec(_) | subxt::Error::Decode(_) => {
+                    return ErrorVariant::Decode
+                }
+                _ => {}
+            }
+        }
+        if cause.is::<subxt::error::RpcError>()
+            || cause.is::<jsonrpsee::core::client::Error>()
+        {
+            return ErrorVariant::Rpc
+        }
+        if cause.is::<scale::Error>() {
+            return ErrorVariant::Decode
+        }
+        if cause.is::<ArtifactNotFound>() {
+            return ErrorVariant::ArtifactMissing
+        }
     }
+    ErrorVariant::Generic
 }
 
 impl From<&str> for ErrorVariant {
@@ -78,6 +156,18 @@ impl From<serde_json::Error> for ErrorVariant {
     }
 }
 
+/// The contract artifacts were not found at the expected location.
+#[derive(Debug)]
+pub struct ArtifactNotFound(pub String);
+
+impl Display for ArtifactNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ArtifactNotFound {}
+
 #[derive(serde::Serialize)]
 pub struct ModuleError {
     pub pallet: String,
@@ -97,17 +187,65 @@ impl GenericError {
 }
 
 impl ErrorVariant {
+    /// Returns the exit code of cargo-contract failing with the error, see
+    /// [`EXIT_CODES`].
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorVariant::Module(_) => EXIT_CODE_MODULE_ERROR,
+            ErrorVariant::DryRunRevert(_) => EXIT_CODE_DRY_RUN_REVERT,
+            ErrorVariant::Rpc(_) => EXIT_CODE_RPC_ERROR,
+            ErrorVariant::ArtifactMissing(_) => EXIT_CODE_ARTIFACT_MISSING,
+            ErrorVariant::Decode(_) => EXIT_CODE_DECODE_ERROR,
+            ErrorVariant::Generic(_) => EXIT_CODE_GENERIC,
+        }
+    }
+
+    /// Returns an [`ErrorVariant::DryRunRevert`] with the `message`.
+    pub fn dry_run_revert(message: impl Into<String>) -> Self {
+        Self::DryRunRevert(GenericError::from_message(message.into()))
+    }
+
+    /// Returns an [`ErrorVariant::Decode`] for the `error` of decoding some data.
+    pub fn decode(error: anyhow::Error) -> Self {
+        Self::Decode(GenericError::from_message(format!("{error:?}")))
+    }
+
+    /// Returns an [`ErrorVariant::Module`] with the names of the pallet and the error
+    /// of the `error` of a submitted extrinsic.
+    fn from_module_error(error: &subxt::error::ModuleError) -> Self {
+        match error.details() {
+            Ok(details) => {
+                ErrorVariant::Module(ModuleError {
+                    pallet: details.pallet.name().to_string(),
+                    error: details.variant.name.to_string(),
+                    docs: details.variant.docs.clone(),
+                })
+            }
+            Err(err) => {
+                unresolved_module_error(error.pallet_index(), error.error_index(), err)
+            }
+        }
+    }
+
     pub fn from_dispatch_error(
         error: &DispatchError,
         metadata: &subxt::Metadata,
     ) -> anyhow::Result<ErrorVariant> {
         match error {
             DispatchError::Module(err) => {
-                let pallet = metadata.pallet_by_index_err(err.index)?;
-                let variant =
-                    pallet.error_variant_by_index(err.error[0]).ok_or_else(|| {
-                        anyhow::anyhow!("Error variant {} not found", err.error[0])
-                    })?;
+                let pallet = match metadata.pallet_by_index_err(err.index) {
+                    Ok(pallet) => pallet,
+                    Err(e) => {
+                        return Ok(unresolved_module_error(err.index, err.error[0], e))
+                    }
+                };
+                let Some(variant) = pallet.error_variant_by_index(err.error[0]) else {
+                    return Ok(unresolved_module_error(
+                        err.index,
+                        err.error[0],
+                        format!("Error variant {} not found", err.error[0]),
+                    ))
+                };
                 Ok(ErrorVariant::Module(ModuleError {
                     pallet: pallet.name().to_string(),
                     error: variant.name.to_owned(),
@@ -123,6 +261,19 @@ impl ErrorVariant {
     }
 }
 
+/// Returns a generic error with the indices of a pallet error which could not be
+/// resolved against the runtime metadata, e.g. because it is outdated.
+fn unresolved_module_error(
+    pallet_index: u8,
+    error_index: u8,
+    reason: impl Display,
+) -> ErrorVariant {
+    ErrorVariant::Generic(GenericError::from_message(format!(
+        "ModuleError: error {error_index} of the pallet with index {pallet_index}, \
+        not found in the runtime metadata: {reason}"
+    )))
+}
+
 impl Debug for ErrorVariant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         <Self as Display>::fmt(self, f)
@@ -133,12 +284,72 @@ impl Display for ErrorVariant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ErrorVariant::Module(err) => {
-                f.write_fmt(format_args!(
-                    "ModuleError: {}::{}: {:?}",
-                    err.pallet, err.error, err.docs
-                ))
+                write!(f, "ModuleError: {}::{}", err.pallet, err.error)?;
+                let docs = err.docs.iter().map(|line| line.trim()).join(" ");
+                if !docs.is_empty() {
+                    write!(f, ": {docs}")?;
+                }
+                Ok(())
             }
-            ErrorVariant::Generic(err) => write!(f, "{}", err.error),
+            ErrorVariant::DryRunRevert(err)
+            | ErrorVariant::Rpc(err)
+            | ErrorVariant::ArtifactMissing(err)
+            | ErrorVariant::Decode(err)
+            | ErrorVariant::Generic(err) => write!(f, "{}", err.error),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn anyhow_errors_are_classified_by_their_chain() {
+        let err = anyhow::Error::from(ArtifactNotFound("not found".to_string()))
+            .context("Failed to load the contract");
+        assert_eq!(ErrorVariant::from(err).exit_code(), EXIT_CODE_ARTIFACT_MISSING);
+
+        let err: anyhow::Result<()> =
+            Err(scale::Error::from("invalid data")).context("Failed to decode");
+        assert_eq!(
+            ErrorVariant::from(err.unwrap_err()).exit_code(),
+            EXIT_CODE_DECODE_ERROR
+        );
+
+        let err = anyhow::Error::from(subxt::Error::Rpc(
+            subxt::error::RpcError::SubscriptionDropped,
+        ));
+        assert_eq!(ErrorVariant::from(err).exit_code(), EXIT_CODE_RPC_ERROR);
+
+        let err = anyhow::anyhow!("Something went wrong");
+        assert_eq!(ErrorVariant::from(err).exit_code(), EXIT_CODE_GENERIC);
+    }
+
+    #[test]
+    fn module_errors_display_pallet_and_error_names_with_docs() {
+        let err = ErrorVariant::Module(ModuleError {
+            pallet: "Contracts".to_string(),
+            error: "CodeTooLarge".to_string(),
+            docs: vec![
+                "The code supplied to `upload_code` exceeds the limit specified in the"
+                    .to_string(),
+                " current schedule.".to_string(),
+            ],
+        });
+        assert_eq!(
+            err.to_string(),
+            "ModuleError: Contracts::CodeTooLarge: The code supplied to `upload_code` \
+            exceeds the limit specified in the current schedule."
+        );
+        assert_eq!(err.exit_code(), EXIT_CODE_MODULE_ERROR);
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let mut codes = EXIT_CODES.map(|(code, _)| code);
+        codes.sort();
+        assert!(codes.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+}
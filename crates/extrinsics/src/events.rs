@@ -15,15 +15,21 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
+    eth_account,
+    is_contracts_pallet,
+    ss58,
     BalanceVariant,
+    ContractsBackend,
     TokenMetadata,
 };
 use crate::DEFAULT_KEY_COL_WIDTH;
 use colored::Colorize as _;
 use contract_build::Verbosity;
+use contract_metadata::byte_str::serialize_as_byte_str;
 use contract_transcode::{
     ContractMessageTranscoder,
     Hex,
+    Transcoder,
     TranscoderBuilder,
     Value,
 };
@@ -38,10 +44,14 @@ use std::{
     },
     str::FromStr,
 };
+use scale::Decode;
 use subxt::{
     self,
     blocks::ExtrinsicEvents,
-    events::StaticEvent,
+    events::{
+        EventDetails,
+        StaticEvent,
+    },
     ext::{
         scale_decode::{
             self,
@@ -52,6 +62,35 @@ use subxt::{
     Config,
 };
 
+/// Returns the events `Ev` emitted by the contracts pallet of the `backend`.
+///
+/// Unlike [`ExtrinsicEvents::find`], the pallet is not taken from
+/// [`StaticEvent::PALLET`], so the event types are shared by all backends.
+pub(crate) fn find_contracts_events<'a, C: Config, Ev: StaticEvent>(
+    events: &'a ExtrinsicEvents<C>,
+    backend: &'a dyn ContractsBackend,
+    metadata: &'a subxt::Metadata,
+) -> impl Iterator<Item = Result<Ev>> + 'a {
+    events.iter().filter_map(move |event| {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => return Some(Err(err.into())),
+        };
+        if event.pallet_name() != backend.pallet() || event.variant_name() != Ev::EVENT {
+            return None
+        }
+        let event_metadata = event.event_metadata();
+        let mut fields =
+            event_metadata.variant.fields.iter().map(|field| {
+                scale_decode::Field::new(&field.ty.id, field.name.as_deref())
+            });
+        Some(
+            Ev::decode_as_fields(&mut event.field_bytes(), &mut fields, metadata.types())
+                .map_err(Into::into),
+        )
+    })
+}
+
 /// A custom event emitted by the contract.
 #[derive(
     scale::Decode,
@@ -100,6 +139,32 @@ where
     const EVENT: &'static str = "Instantiated";
 }
 
+/// A contract was successfully instantiated on a chain running `pallet-revive`, which
+/// addresses the deployer and the contract by an `H160`.
+#[derive(Debug, scale_decode::DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+pub(crate) struct ReviveContractInstantiated {
+    deployer: [u8; 20],
+    contract: [u8; 20],
+}
+
+impl ReviveContractInstantiated {
+    /// Converts the addresses to the accounts they are mapped to, see [`eth_account`].
+    pub fn into_accounts<AccountId: Decode>(
+        self,
+    ) -> Result<ContractInstantiated<AccountId>> {
+        Ok(ContractInstantiated {
+            deployer: eth_account(self.deployer)?,
+            contract: eth_account(self.contract)?,
+        })
+    }
+}
+
+impl StaticEvent for ReviveContractInstantiated {
+    const PALLET: &'static str = "Revive";
+    const EVENT: &'static str = "Instantiated";
+}
+
 /// An event triggered by either the `instantiate_with_code` or the `upload_code` call.
 #[derive(
     Debug,
@@ -149,8 +214,191 @@ where
     const EVENT: &'static str = "CodeRemoved";
 }
 
+/// A typed event of the contracts pallet, see [`EventRecord::contracts_event`].
+#[derive(Debug)]
+pub enum ContractsEvent<AccountId, Hash> {
+    /// The code of a contract was uploaded.
+    CodeStored(CodeStored<Hash>),
+    /// A contract was instantiated.
+    Instantiated(ContractInstantiated<AccountId>),
+    /// A contract emitted an event.
+    ContractEmitted(ContractEmitted<AccountId>),
+}
+
+/// An event emitted by an extrinsic, decoded with the runtime metadata.
+///
+/// Embedding applications can react to the events of an extrinsic through the records
+/// returned by [`decode_events`], instead of parsing the output of [`DisplayEvents`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct EventRecord {
+    /// The name of the pallet which emitted the event, e.g. `Contracts`.
+    pub pallet: String,
+    /// The name of the event, e.g. `Instantiated`.
+    pub variant: String,
+    /// The decoded fields of the event.
+    ///
+    /// The `data` of a `ContractEmitted` event is decoded as an event of the contract if
+    /// it was emitted by the contract of the transcoder, and is raw hex otherwise.
+    pub fields: Vec<Field>,
+    /// The SCALE encoded fields of the event.
+    #[serde(serialize_with = "serialize_as_byte_str")]
+    pub bytes: Vec<u8>,
+}
+
+impl EventRecord {
+    /// Returns `true` if the event is the `variant` of the `pallet`.
+    ///
+    /// The `Contracts` pallet matches the events of all contracts pallets, e.g. of
+    /// `Revive`.
+    pub fn is(&self, pallet: &str, variant: &str) -> bool {
+        let pallet_matches = self.pallet == pallet
+            || (is_contracts_pallet(pallet) && is_contracts_pallet(&self.pallet));
+        pallet_matches && self.variant == variant
+    }
+
+    /// Returns the decoded value of the field with the given `name`.
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| &field.value)
+    }
+
+    /// Decodes the fields of the event as the event `Ev`, `None` if it is another event.
+    pub fn as_event<Ev: StaticEvent + Decode>(&self) -> Result<Option<Ev>> {
+        if !self.is(Ev::PALLET, Ev::EVENT) {
+            return Ok(None)
+        }
+        Ok(Some(Ev::decode(&mut &self.bytes[..])?))
+    }
+
+    /// Decodes the event as one of the [`ContractsEvent`]s, `None` if it is another
+    /// event.
+    pub fn contracts_event<AccountId, Hash>(
+        &self,
+    ) -> Result<Option<ContractsEvent<AccountId, Hash>>>
+    where
+        AccountId: IntoVisitor + Decode,
+        Hash: IntoVisitor + Decode,
+    {
+        if let Some(event) = self.as_event()? {
+            return Ok(Some(ContractsEvent::CodeStored(event)))
+        }
+        if let Some(event) = self.as_event()? {
+            return Ok(Some(ContractsEvent::Instantiated(event)))
+        }
+        Ok(self.as_event()?.map(ContractsEvent::ContractEmitted))
+    }
+}
+
+/// Decodes the `events` of an extrinsic into [`EventRecord`]s.
+///
+/// The events emitted by contracts are decoded with the `transcoder` if available. The
+/// accounts in the fields of the runtime events are displayed with the `ss58_prefix`,
+/// see [`ContractMessageTranscoder::with_ss58_prefix`] for the events of contracts.
+pub fn decode_events<'a, C: Config>(
+    events: &'a ExtrinsicEvents<C>,
+    transcoder: Option<&'a ContractMessageTranscoder>,
+    metadata: &'a subxt::Metadata,
+    ss58_prefix: u16,
+) -> impl Iterator<Item = Result<EventRecord>> + 'a
+where
+    C::AccountId: IntoVisitor,
+{
+    let events_transcoder = events_transcoder(metadata, ss58_prefix);
+    let transcoders: Vec<_> = transcoder.into_iter().collect();
+    events.iter().map(move |event| {
+        decode_event::<C>(event?, &transcoders, &events_transcoder, metadata)
+    })
+}
+
+/// Returns the transcoder of the fields of the events of the runtime, which displays
+/// accounts with the `ss58_prefix`.
+pub(crate) fn events_transcoder(
+    metadata: &subxt::Metadata,
+    ss58_prefix: u16,
+) -> Transcoder {
+    TranscoderBuilder::new(metadata.types())
+        .ss58_prefix(ss58_prefix)
+        .with_default_custom_type_transcoders()
+        .done()
+}
+
+/// Decodes a single event, see [`decode_events`].
+///
+/// The events emitted by contracts are decoded with the first of the `transcoders`
+/// which knows the event.
+pub(crate) fn decode_event<C: Config>(
+    event: EventDetails<C>,
+    transcoders: &[&ContractMessageTranscoder],
+    events_transcoder: &Transcoder,
+    metadata: &subxt::Metadata,
+) -> Result<EventRecord>
+where
+    C::AccountId: IntoVisitor,
+{
+    tracing::debug!(
+        "decoding event {}:{}",
+        event.pallet_name(),
+        event.variant_name()
+    );
+
+    let event_metadata = event.event_metadata();
+    let event_fields = &event_metadata.variant.fields;
+
+    let mut record = EventRecord {
+        pallet: event.pallet_name().to_string(),
+        variant: event.variant_name().to_string(),
+        fields: vec![],
+        bytes: event.field_bytes().to_vec(),
+    };
+
+    let event_data = &mut event.field_bytes();
+    let event_sig_topic = event.topics().iter().next();
+    let mut unnamed_field_name = 0;
+    for field_metadata in event_fields {
+        if is_contracts_pallet(event.pallet_name())
+            && event.variant_name()
+                == <ContractEmitted<C::AccountId> as StaticEvent>::EVENT
+            && field_metadata.name == Some("data".to_string())
+        {
+            tracing::debug!("event data: {:?}", hex::encode(&event_data));
+            let field = contract_event_data_field::<C>(
+                transcoders,
+                field_metadata,
+                event_sig_topic,
+                event_data,
+            )?;
+            record.fields.push(field);
+        } else {
+            let field_name = field_metadata
+                .name
+                .as_ref()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| {
+                    let name = unnamed_field_name.to_string();
+                    unnamed_field_name += 1;
+                    name
+                });
+
+            let decoded_field = events_transcoder.decode(
+                metadata.types(),
+                field_metadata.ty.id,
+                event_data,
+            )?;
+            let field = Field::new(
+                field_name,
+                decoded_field,
+                field_metadata.type_name.as_ref().map(|s| s.to_string()),
+            );
+            record.fields.push(field);
+        }
+    }
+    Ok(record)
+}
+
 /// Field that represent data of an event from invoking a contract extrinsic.
-#[derive(serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
 pub struct Field {
     /// name of a field
     pub name: String,
@@ -182,92 +430,143 @@ pub struct Event {
     pub fields: Vec<Field>,
 }
 
+impl From<EventRecord> for Event {
+    fn from(record: EventRecord) -> Self {
+        Event {
+            pallet: record.pallet,
+            name: record.variant,
+            fields: record.fields,
+        }
+    }
+}
+
 /// Events produced from invoking a contract extrinsic.
 #[derive(serde::Serialize)]
 pub struct Events(Vec<Event>);
 
+/// Selects the events which are displayed.
+///
+/// By default all events are displayed.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    /// The `Pallet` or `Pallet::Variant` names of the displayed events.
+    events: Vec<String>,
+    /// Only display the events of the `Contracts` pallet.
+    only_contract_events: bool,
+    /// The address of the contract whose events are displayed.
+    contract: Option<String>,
+}
+
+impl EventFilter {
+    /// Only displays the events with the given name, either `Pallet` or
+    /// `Pallet::Variant`, e.g. `Contracts::ContractEmitted`.
+    ///
+    /// Can be called multiple times to display the events matching any of the names.
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.events.push(name.into());
+        self
+    }
+
+    /// Only displays the events of the `Contracts` pallet.
+    ///
+    /// If a [`contract`](Self::contract) is set, the events concerning other contracts
+    /// are not displayed either.
+    pub fn only_contract_events(mut self, only_contract_events: bool) -> Self {
+        self.only_contract_events = only_contract_events;
+        self
+    }
+
+    /// Sets the address of the contract the extrinsic was submitted for.
+    pub fn contract(mut self, contract: impl Display) -> Self {
+        self.contract = Some(contract.to_string());
+        self
+    }
+
+    /// Returns `true` if the `event` is displayed.
+    fn matches(&self, event: &Event) -> bool {
+        let name_matches = self.events.is_empty()
+            || self.events.iter().any(|name| {
+                *name == event.pallet
+                    || *name == format!("{}::{}", event.pallet, event.name)
+            });
+        name_matches && (!self.only_contract_events || self.is_contract_event(event))
+    }
+
+    /// Returns `true` if the `event` is an event of a contracts pallet concerning the
+    /// contract.
+    fn is_contract_event(&self, event: &Event) -> bool {
+        if !is_contracts_pallet(&event.pallet) {
+            return false
+        }
+        let contract_field = event.fields.iter().find(|field| field.name == "contract");
+        match (&self.contract, contract_field) {
+            (Some(contract), Some(field)) => {
+                ss58::is_same_account(&field.value.to_string(), contract)
+            }
+            _ => true,
+        }
+    }
+}
+
 /// Displays events produced from invoking a contract extrinsic.
 #[derive(serde::Serialize)]
 pub struct DisplayEvents(Vec<Event>);
 
+impl From<Vec<Event>> for DisplayEvents {
+    fn from(events: Vec<Event>) -> Self {
+        DisplayEvents(events)
+    }
+}
+
 impl DisplayEvents {
     /// Parses events and returns an object which can be serialised
+    ///
+    /// Accounts are displayed with the `ss58_prefix`, see [`decode_events`].
     pub fn from_events<C: Config, E: Environment>(
         result: &ExtrinsicEvents<C>,
         transcoder: Option<&ContractMessageTranscoder>,
         subxt_metadata: &subxt::Metadata,
+        ss58_prefix: u16,
     ) -> Result<DisplayEvents>
     where
         C::AccountId: IntoVisitor,
     {
-        let mut events: Vec<Event> = vec![];
-
-        let events_transcoder = TranscoderBuilder::new(subxt_metadata.types())
-            .with_default_custom_type_transcoders()
-            .done();
-
-        for event in result.iter() {
-            let event = event?;
-            tracing::debug!(
-                "displaying event {}:{}",
-                event.pallet_name(),
-                event.variant_name()
-            );
+        let records =
+            decode_events::<C>(result, transcoder, subxt_metadata, ss58_prefix)
+                .collect::<Result<Vec<_>>>()?;
+        Ok(Self::from_records(records))
+    }
 
-            let event_metadata = event.event_metadata();
-            let event_fields = &event_metadata.variant.fields;
-
-            let mut event_entry = Event {
-                pallet: event.pallet_name().to_string(),
-                name: event.variant_name().to_string(),
-                fields: vec![],
-            };
-
-            let event_data = &mut event.field_bytes();
-            let event_sig_topic = event.topics().iter().next();
-            let mut unnamed_field_name = 0;
-            for field_metadata in event_fields {
-                if <ContractEmitted<C::AccountId> as StaticEvent>::is_event(
-                    event.pallet_name(),
-                    event.variant_name(),
-                ) && field_metadata.name == Some("data".to_string())
-                {
-                    tracing::debug!("event data: {:?}", hex::encode(&event_data));
-                    let field = contract_event_data_field::<C>(
-                        transcoder,
-                        field_metadata,
-                        event_sig_topic,
-                        event_data,
-                    )?;
-                    event_entry.fields.push(field);
-                } else {
-                    let field_name = field_metadata
-                        .name
-                        .as_ref()
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| {
-                            let name = unnamed_field_name.to_string();
-                            unnamed_field_name += 1;
-                            name
-                        });
-
-                    let decoded_field = events_transcoder.decode(
-                        subxt_metadata.types(),
-                        field_metadata.ty.id,
-                        event_data,
-                    )?;
-                    let field = Field::new(
-                        field_name,
-                        decoded_field,
-                        field_metadata.type_name.as_ref().map(|s| s.to_string()),
-                    );
-                    event_entry.fields.push(field);
-                }
+    /// Creates the events to display from the decoded `records` of an extrinsic.
+    pub fn from_records(records: impl IntoIterator<Item = EventRecord>) -> Self {
+        DisplayEvents(records.into_iter().map(Event::from).collect())
+    }
+
+    /// Removes the events which are not selected by the `filter`.
+    ///
+    /// Applies to both the human readable and the JSON output.
+    pub fn filter(mut self, filter: &EventFilter) -> Self {
+        self.0.retain(|event| filter.matches(event));
+        self
+    }
+
+    /// Splits the events of a `utility.batch_all` extrinsic into the events of each of
+    /// the batched calls, delimited by the `Utility::ItemCompleted` events, and the
+    /// remaining events of the extrinsic.
+    ///
+    /// Events emitted before the first call, like the withdrawal of the fee, are
+    /// attributed to it.
+    pub fn split_batch_items(self) -> (Vec<DisplayEvents>, DisplayEvents) {
+        let mut items = Vec::new();
+        let mut current = Vec::new();
+        for event in self.0 {
+            if event.pallet == "Utility" && event.name == "ItemCompleted" {
+                items.push(DisplayEvents(std::mem::take(&mut current)));
+            } else {
+                current.push(event);
             }
-            events.push(event_entry);
         }
-
-        Ok(DisplayEvents(events))
+        (items, DisplayEvents(current))
     }
 
     /// Displays events in a human readable format
@@ -277,7 +576,7 @@ impl DisplayEvents {
         token_metadata: &TokenMetadata,
     ) -> Result<String>
     where
-        E::Balance: Display + From<u128>,
+        E::Balance: Display + TryFrom<u128>,
     {
         let event_field_indent: usize = DEFAULT_KEY_COL_WIDTH - 3;
         let mut out = format!(
@@ -330,31 +629,37 @@ impl DisplayEvents {
 }
 
 /// Construct the contract event data field, attempting to decode the event using the
-/// [`ContractMessageTranscoder`] if available.
+/// [`ContractMessageTranscoder`]s if available.
 fn contract_event_data_field<C: Config>(
-    transcoder: Option<&ContractMessageTranscoder>,
+    transcoders: &[&ContractMessageTranscoder],
     field_metadata: &scale_info::Field<PortableForm>,
     event_sig_topic: Option<&C::Hash>,
     event_data: &mut &[u8],
 ) -> Result<Field> {
-    let event_value = if let Some(transcoder) = transcoder {
-        if let Some(event_sig_topic) = event_sig_topic {
-            match transcoder.decode_contract_event(event_sig_topic, event_data) {
-                Ok(contract_event) => contract_event,
+    let mut event_value = None;
+    if let Some(event_sig_topic) = event_sig_topic {
+        for transcoder in transcoders {
+            let data = &mut &event_data[..];
+            match transcoder.decode_contract_event(event_sig_topic, data) {
+                Ok(contract_event) => {
+                    *event_data = data;
+                    event_value = Some(contract_event);
+                    break
+                }
                 Err(err) => {
                     tracing::warn!(
                         "Decoding contract event failed: {:?}. It might have come from another contract.",
                         err
                     );
-                    Value::Hex(Hex::from_str(&hex::encode(&event_data))?)
                 }
             }
-        } else {
-            tracing::info!("Anonymous event not decoded. Data displayed as raw hex.");
-            Value::Hex(Hex::from_str(&hex::encode(event_data))?)
         }
-    } else {
-        Value::Hex(Hex::from_str(&hex::encode(event_data))?)
+    } else if !transcoders.is_empty() {
+        tracing::info!("Anonymous event not decoded. Data displayed as raw hex.");
+    }
+    let event_value = match event_value {
+        Some(event_value) => event_value,
+        None => Value::Hex(Hex::from_str(&hex::encode(&event_data))?),
     };
     Ok(Field::new(
         String::from("data"),
@@ -362,3 +667,164 @@ fn contract_event_data_field<C: Config>(
         field_metadata.type_name.as_ref().map(|s| s.to_string()),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(pallet: &str, name: &str, contract: Option<&str>) -> Event {
+        Event {
+            pallet: pallet.to_string(),
+            name: name.to_string(),
+            fields: contract
+                .map(|contract| {
+                    Field::new(
+                        "contract".to_string(),
+                        Value::Literal(contract.to_string()),
+                        None,
+                    )
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn displayed(filter: &EventFilter) -> Vec<String> {
+        DisplayEvents(vec![
+            event("System", "ExtrinsicSuccess", None),
+            event("Balances", "Transfer", None),
+            event("Contracts", "Called", Some("5Foo")),
+            event("Contracts", "ContractEmitted", Some("5Bar")),
+            event("Contracts", "CodeStored", None),
+        ])
+        .filter(filter)
+        .0
+        .into_iter()
+        .map(|event| format!("{}::{}", event.pallet, event.name))
+        .collect()
+    }
+
+    #[test]
+    fn default_filter_displays_all_events() {
+        assert_eq!(displayed(&EventFilter::default()).len(), 5);
+    }
+
+    #[test]
+    fn filter_by_pallet_and_variant() {
+        let filter = EventFilter::default()
+            .event("Balances")
+            .event("Contracts::ContractEmitted");
+
+        assert_eq!(
+            displayed(&filter),
+            ["Balances::Transfer", "Contracts::ContractEmitted"]
+        );
+    }
+
+    #[test]
+    fn filter_contract_events() {
+        let filter = EventFilter::default().only_contract_events(true);
+        assert_eq!(
+            displayed(&filter),
+            [
+                "Contracts::Called",
+                "Contracts::ContractEmitted",
+                "Contracts::CodeStored"
+            ]
+        );
+
+        let filter = filter.contract("5Foo");
+        assert_eq!(
+            displayed(&filter),
+            ["Contracts::Called", "Contracts::CodeStored"]
+        );
+    }
+
+    #[test]
+    fn record_is_decoded_as_contracts_event() {
+        use subxt::utils::{
+            AccountId32,
+            H256,
+        };
+
+        let record = |pallet: &str, variant: &str, bytes: Vec<u8>| {
+            EventRecord {
+                pallet: pallet.to_string(),
+                variant: variant.to_string(),
+                fields: vec![Field::new("contract".to_string(), Value::UInt(1), None)],
+                bytes,
+            }
+        };
+        let instantiated = record(
+            "Revive",
+            "Instantiated",
+            scale::Encode::encode(&(AccountId32([1; 32]), AccountId32([2; 32]))),
+        );
+
+        assert!(instantiated.is("Contracts", "Instantiated"));
+        assert_eq!(instantiated.field("contract"), Some(&Value::UInt(1)));
+        assert!(instantiated.field("data").is_none());
+        match instantiated.contracts_event::<AccountId32, H256>().unwrap() {
+            Some(ContractsEvent::Instantiated(event)) => {
+                assert_eq!(event.deployer, AccountId32([1; 32]));
+                assert_eq!(event.contract, AccountId32([2; 32]));
+            }
+            event => panic!("Expected an `Instantiated` event, got {event:?}"),
+        }
+
+        let emitted = record(
+            "Contracts",
+            "ContractEmitted",
+            scale::Encode::encode(&(AccountId32([2; 32]), vec![7u8])),
+        );
+        let event = emitted
+            .as_event::<ContractEmitted<AccountId32>>()
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.data, vec![7]);
+        assert!(emitted
+            .as_event::<CodeStored<H256>>()
+            .unwrap()
+            .is_none());
+
+        let transfer = record("Balances", "Transfer", Vec::new());
+        assert!(!transfer.is("Contracts", "Transfer"));
+        assert!(transfer
+            .contracts_event::<AccountId32, H256>()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn split_events_of_batch_items() {
+        let events = DisplayEvents(vec![
+            event("Contracts", "Called", Some("5Foo")),
+            event("Utility", "ItemCompleted", None),
+            event("Contracts", "Called", Some("5Bar")),
+            event("Contracts", "ContractEmitted", Some("5Bar")),
+            event("Utility", "ItemCompleted", None),
+            event("Utility", "BatchCompleted", None),
+            event("System", "ExtrinsicSuccess", None),
+        ]);
+
+        let (items, rest) = events.split_batch_items();
+
+        let names = |events: &DisplayEvents| {
+            events
+                .0
+                .iter()
+                .map(|event| format!("{}::{}", event.pallet, event.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(items.len(), 2);
+        assert_eq!(names(&items[0]), ["Contracts::Called"]);
+        assert_eq!(
+            names(&items[1]),
+            ["Contracts::Called", "Contracts::ContractEmitted"]
+        );
+        assert_eq!(
+            names(&rest),
+            ["Utility::BatchCompleted", "System::ExtrinsicSuccess"]
+        );
+    }
+}
@@ -15,17 +15,94 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
+    eth_address,
     upload::Determinism,
+    ContractsBackend,
     WasmCode,
 };
+use scale::Encode;
 use subxt::{
     ext::{
         codec::Compact,
-        scale_encode::EncodeAsType,
+        scale_encode::{
+            self,
+            EncodeAsType,
+            TypeResolver,
+        },
     },
     utils::MultiAddress,
 };
 
+/// A field of a call whose type differs between the contracts pallets, encoded as the
+/// type of the pallet the call is built for.
+#[derive(Debug)]
+pub(crate) enum PalletField<Contracts, Revive> {
+    /// The field of a call to `pallet-contracts`.
+    Contracts(Contracts),
+    /// The field of a call to `pallet-revive`.
+    Revive(Revive),
+}
+
+impl<Contracts, Revive> PalletField<Contracts, Revive> {
+    /// Converts the field to the one of a call to `pallet-revive` with `f`.
+    fn revive(self, f: impl FnOnce(Contracts) -> Revive) -> Self {
+        match self {
+            Self::Contracts(field) => Self::Revive(f(field)),
+            revive => revive,
+        }
+    }
+}
+
+impl<Contracts, Revive> EncodeAsType for PalletField<Contracts, Revive>
+where
+    Contracts: EncodeAsType,
+    Revive: EncodeAsType,
+{
+    fn encode_as_type_to<R: TypeResolver>(
+        &self,
+        type_id: &R::TypeId,
+        types: &R,
+        out: &mut Vec<u8>,
+    ) -> Result<(), scale_encode::Error> {
+        match self {
+            Self::Contracts(field) => field.encode_as_type_to(type_id, types, out),
+            Self::Revive(field) => field.encode_as_type_to(type_id, types, out),
+        }
+    }
+}
+
+/// The storage deposit limit of a call to `pallet-revive`, which is not optional.
+/// Without a limit the maximum balance is passed.
+#[derive(Debug)]
+pub(crate) struct DepositLimit<Balance>(Option<Compact<Balance>>);
+
+impl<Balance: EncodeAsType> EncodeAsType for DepositLimit<Balance> {
+    fn encode_as_type_to<R: TypeResolver>(
+        &self,
+        type_id: &R::TypeId,
+        types: &R,
+        out: &mut Vec<u8>,
+    ) -> Result<(), scale_encode::Error> {
+        match &self.0 {
+            Some(limit) => limit.encode_as_type_to(type_id, types, out),
+            None => Compact(u128::MAX).encode_as_type_to(type_id, types, out),
+        }
+    }
+}
+
+/// The optional storage deposit limit of a call to `pallet-contracts`, or the one of a
+/// call to `pallet-revive`.
+type StorageDepositLimit<Balance> =
+    PalletField<Option<Compact<Balance>>, DepositLimit<Balance>>;
+
+/// The salt of an instantiation: any bytes for `pallet-contracts`, and optionally 32
+/// bytes for `pallet-revive`. An empty salt is passed as `None` to `pallet-revive`.
+type Salt = PalletField<Vec<u8>, Option<[u8; 32]>>;
+
+fn revive_salt(salt: Vec<u8>) -> Option<[u8; 32]> {
+    <[u8; 32]>::try_from(salt).ok()
+}
+
 /// Copied from `sp_weight` to additionally implement `scale_encode::EncodeAsType`.
 #[derive(Debug, EncodeAsType)]
 #[encode_as_type(crate_path = "subxt::ext::scale_encode")]
@@ -69,8 +146,8 @@ impl<Hash> RemoveCode<Hash> {
         Self { code_hash }
     }
 
-    pub fn build(self) -> subxt::tx::Payload<Self> {
-        subxt::tx::Payload::new("Contracts", "remove_code", self)
+    pub fn build(self, backend: &dyn ContractsBackend) -> subxt::tx::Payload<Self> {
+        subxt::tx::Payload::new(backend.pallet(), "remove_code", self)
     }
 }
 
@@ -79,7 +156,9 @@ impl<Hash> RemoveCode<Hash> {
 #[encode_as_type(crate_path = "subxt::ext::scale_encode")]
 pub(crate) struct UploadCode<Balance> {
     code: Vec<u8>,
-    storage_deposit_limit: Option<Compact<Balance>>,
+    storage_deposit_limit: StorageDepositLimit<Balance>,
+    /// Only passed to `pallet-contracts`, the field is skipped for `pallet-revive`,
+    /// since the fields are encoded by name.
     determinism: Determinism,
 }
 
@@ -91,13 +170,23 @@ impl<Balance> UploadCode<Balance> {
     ) -> Self {
         Self {
             code: code.0,
-            storage_deposit_limit: storage_deposit_limit.map(Into::into),
+            storage_deposit_limit: PalletField::Contracts(
+                storage_deposit_limit.map(Into::into),
+            ),
             determinism,
         }
     }
 
-    pub fn build(self) -> subxt::tx::Payload<Self> {
-        subxt::tx::Payload::new("Contracts", "upload_code", self)
+    pub fn build(self, backend: &dyn ContractsBackend) -> subxt::tx::Payload<Self> {
+        let call = if backend.h160_addresses() {
+            Self {
+                storage_deposit_limit: self.storage_deposit_limit.revive(DepositLimit),
+                ..self
+            }
+        } else {
+            self
+        };
+        subxt::tx::Payload::new(backend.pallet(), "upload_code", call)
     }
 }
 
@@ -108,10 +197,10 @@ pub(crate) struct InstantiateWithCode<Balance> {
     #[codec(compact)]
     value: Balance,
     gas_limit: Weight,
-    storage_deposit_limit: Option<Compact<Balance>>,
+    storage_deposit_limit: StorageDepositLimit<Balance>,
     code: Vec<u8>,
     data: Vec<u8>,
-    salt: Vec<u8>,
+    salt: Salt,
 }
 
 impl<Balance> InstantiateWithCode<Balance> {
@@ -126,15 +215,26 @@ impl<Balance> InstantiateWithCode<Balance> {
         Self {
             value,
             gas_limit: gas_limit.into(),
-            storage_deposit_limit: storage_deposit_limit.map(Into::into),
+            storage_deposit_limit: PalletField::Contracts(
+                storage_deposit_limit.map(Into::into),
+            ),
             code,
             data,
-            salt,
+            salt: PalletField::Contracts(salt),
         }
     }
 
-    pub fn build(self) -> subxt::tx::Payload<Self> {
-        subxt::tx::Payload::new("Contracts", "instantiate_with_code", self)
+    pub fn build(self, backend: &dyn ContractsBackend) -> subxt::tx::Payload<Self> {
+        let call = if backend.h160_addresses() {
+            Self {
+                storage_deposit_limit: self.storage_deposit_limit.revive(DepositLimit),
+                salt: self.salt.revive(revive_salt),
+                ..self
+            }
+        } else {
+            self
+        };
+        subxt::tx::Payload::new(backend.pallet(), "instantiate_with_code", call)
     }
 }
 
@@ -148,10 +248,10 @@ where
     #[codec(compact)]
     value: Balance,
     gas_limit: Weight,
-    storage_deposit_limit: Option<Compact<Balance>>,
+    storage_deposit_limit: StorageDepositLimit<Balance>,
     code_hash: Hash,
     data: Vec<u8>,
-    salt: Vec<u8>,
+    salt: Salt,
 }
 
 impl<Hash, Balance> Instantiate<Hash, Balance>
@@ -169,15 +269,26 @@ where
         Self {
             value,
             gas_limit: gas_limit.into(),
-            storage_deposit_limit: storage_deposit_limit.map(Into::into),
+            storage_deposit_limit: PalletField::Contracts(
+                storage_deposit_limit.map(Into::into),
+            ),
             code_hash,
             data,
-            salt,
+            salt: PalletField::Contracts(salt),
         }
     }
 
-    pub fn build(self) -> subxt::tx::Payload<Self> {
-        subxt::tx::Payload::new("Contracts", "instantiate", self)
+    pub fn build(self, backend: &dyn ContractsBackend) -> subxt::tx::Payload<Self> {
+        let call = if backend.h160_addresses() {
+            Self {
+                storage_deposit_limit: self.storage_deposit_limit.revive(DepositLimit),
+                salt: self.salt.revive(revive_salt),
+                ..self
+            }
+        } else {
+            self
+        };
+        subxt::tx::Payload::new(backend.pallet(), "instantiate", call)
     }
 }
 
@@ -185,32 +296,195 @@ where
 #[derive(EncodeAsType)]
 #[encode_as_type(crate_path = "subxt::ext::scale_encode")]
 pub(crate) struct Call<AccountId, Balance> {
-    dest: MultiAddress<AccountId, ()>,
+    /// The account of the contract for `pallet-contracts`, its `H160` address for
+    /// `pallet-revive`.
+    dest: PalletField<MultiAddress<AccountId, ()>, [u8; 20]>,
     #[codec(compact)]
     value: Balance,
     gas_limit: Weight,
-    storage_deposit_limit: Option<Compact<Balance>>,
+    storage_deposit_limit: StorageDepositLimit<Balance>,
     data: Vec<u8>,
 }
 
-impl<AccountId, Balance> Call<AccountId, Balance> {
+impl<AccountId: Encode, Balance> Call<AccountId, Balance> {
     pub fn new(
-        dest: MultiAddress<AccountId, ()>,
+        dest: AccountId,
         value: Balance,
         gas_limit: sp_weights::Weight,
         storage_deposit_limit: Option<Balance>,
         data: Vec<u8>,
     ) -> Self {
         Self {
-            dest,
+            dest: PalletField::Contracts(MultiAddress::Id(dest)),
             value,
             gas_limit: gas_limit.into(),
-            storage_deposit_limit: storage_deposit_limit.map(Into::into),
+            storage_deposit_limit: PalletField::Contracts(
+                storage_deposit_limit.map(Into::into),
+            ),
             data,
         }
     }
 
+    pub fn build(self, backend: &dyn ContractsBackend) -> subxt::tx::Payload<Self> {
+        let call = if backend.h160_addresses() {
+            Self {
+                dest: self.dest.revive(|dest| {
+                    match dest {
+                        MultiAddress::Id(account) => eth_address(&account.encode()),
+                        _ => unreachable!("contracts are called by their account id"),
+                    }
+                }),
+                storage_deposit_limit: self.storage_deposit_limit.revive(DepositLimit),
+                ..self
+            }
+        } else {
+            self
+        };
+        subxt::tx::Payload::new(backend.pallet(), "call", call)
+    }
+}
+
+/// A raw call to `pallet-revive`'s `map_account`.
+#[derive(Debug, EncodeAsType)]
+#[encode_as_type(crate_path = "subxt::ext::scale_encode")]
+pub(crate) struct MapAccount {}
+
+impl MapAccount {
+    pub fn build(self, backend: &dyn ContractsBackend) -> subxt::tx::Payload<Self> {
+        subxt::tx::Payload::new(backend.pallet(), "map_account", self)
+    }
+}
+
+/// A raw call to `pallet-balances`'s `transfer_keep_alive`.
+#[derive(Debug, EncodeAsType)]
+#[encode_as_type(crate_path = "subxt::ext::scale_encode")]
+pub(crate) struct TransferKeepAlive<AccountId, Balance> {
+    dest: MultiAddress<AccountId, ()>,
+    #[codec(compact)]
+    value: Balance,
+}
+
+impl<AccountId, Balance> TransferKeepAlive<AccountId, Balance> {
+    pub fn new(dest: MultiAddress<AccountId, ()>, value: Balance) -> Self {
+        Self { dest, value }
+    }
+
     pub fn build(self) -> subxt::tx::Payload<Self> {
-        subxt::tx::Payload::new("Contracts", "call", self)
+        subxt::tx::Payload::new("Balances", "transfer_keep_alive", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_metadata::{
+            self,
+            ReviveCall,
+            RuntimeCall,
+        },
+        PalletRevive,
+    };
+    use sp_core::{
+        H160,
+        H256,
+    };
+    use subxt::{
+        tx::TxPayload,
+        utils::AccountId32,
+    };
+
+    #[test]
+    fn encodes_revive_call_with_h160_dest() {
+        let metadata = test_metadata::revive_metadata();
+        let contract = contract_transcode::AccountId32::from_eth_address([1; 20]);
+        let gas_limit = sp_weights::Weight::from_parts(1, 2);
+        let call = Call::new(AccountId32(contract.0), 10u128, gas_limit, None, vec![3])
+            .build(&PalletRevive);
+
+        let call_data = call.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Revive(ReviveCall::call {
+            dest: H160([1; 20]),
+            value: 10,
+            gas_limit,
+            storage_deposit_limit: u128::MAX,
+            data: vec![3],
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn encodes_revive_instantiate_with_optional_salt() {
+        let metadata = test_metadata::revive_metadata();
+        let gas_limit = sp_weights::Weight::from_parts(1, 2);
+        let call =
+            Instantiate::new(10u128, gas_limit, Some(5), H256([4; 32]), vec![3], vec![])
+                .build(&PalletRevive);
+
+        let call_data = call.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Revive(ReviveCall::instantiate {
+            value: 10,
+            gas_limit,
+            storage_deposit_limit: 5,
+            code_hash: H256([4; 32]),
+            data: vec![3],
+            salt: None,
+        });
+        assert_eq!(call_data, expected.encode());
+
+        let call =
+            InstantiateWithCode::new(0u128, gas_limit, None, vec![1], vec![], vec![7; 32])
+                .build(&PalletRevive);
+
+        let call_data = call.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Revive(ReviveCall::instantiate_with_code {
+            value: 0,
+            gas_limit,
+            storage_deposit_limit: u128::MAX,
+            code: vec![1],
+            data: vec![],
+            salt: Some([7; 32]),
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn encodes_revive_remove_code_and_map_account() {
+        let metadata = test_metadata::revive_metadata();
+
+        let call_data = RemoveCode::new(H256([4; 32]))
+            .build(&PalletRevive)
+            .encode_call_data(&metadata)
+            .unwrap();
+        let expected = RuntimeCall::Revive(ReviveCall::remove_code {
+            code_hash: H256([4; 32]),
+        });
+        assert_eq!(call_data, expected.encode());
+
+        let call_data = MapAccount {}
+            .build(&PalletRevive)
+            .encode_call_data(&metadata)
+            .unwrap();
+        let expected = RuntimeCall::Revive(ReviveCall::map_account {});
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn encodes_revive_upload_without_determinism() {
+        let metadata = test_metadata::revive_metadata();
+        let code = WasmCode(vec![1, 2]);
+        let call = UploadCode::new(code, Some(5u128), Determinism::Enforced)
+            .build(&PalletRevive);
+
+        let call_data = call.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Revive(ReviveCall::upload_code {
+            code: vec![1, 2],
+            storage_deposit_limit: 5,
+        });
+        assert_eq!(call_data, expected.encode());
     }
 }
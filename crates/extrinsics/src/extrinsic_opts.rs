@@ -15,25 +15,45 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use anyhow::Result;
-use contract_build::Verbosity;
+use contract_build::{
+    progress::ProgressReporter,
+    Verbosity,
+};
 use derivative::Derivative;
 use ink_env::Environment;
 use subxt::{
-    tx,
+    tx::{
+        self,
+        DynamicPayload,
+    },
     Config,
 };
 use url::Url;
 
 use crate::{
+    pallet_contracts_primitives::StorageDeposit,
+    CallWrapper,
     url_to_string,
     ContractArtifacts,
+    NonceProvider,
+    RpcOptions,
+    SubmitOptions,
 };
 use std::{
     marker::PhantomData,
     option::Option,
     path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
 
+/// Default time to wait for the finalization of a submitted extrinsic.
+pub const DEFAULT_FINALIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The safety margin in percent added to the storage deposit charged in a dry-run, to
+/// derive the storage deposit limit of the submitted extrinsic.
+pub const STORAGE_DEPOSIT_MARGIN_PERCENT: u128 = 10;
+
 /// Arguments required for creating and sending an extrinsic to a Substrate node.
 #[derive(Derivative)]
 #[derivative(Clone(bound = "E::Balance: Clone"))]
@@ -43,7 +63,18 @@ pub struct ExtrinsicOpts<C: Config, E: Environment, Signer: Clone> {
     url: url::Url,
     signer: Signer,
     storage_deposit_limit: Option<E::Balance>,
+    unlimited_deposit: bool,
     verbosity: Verbosity,
+    rpc_options: RpcOptions,
+    finalization_timeout: Option<Duration>,
+    submit_timeout: Option<Duration>,
+    submit_options: SubmitOptions,
+    mortality: Option<u64>,
+    tip: u128,
+    nonce_provider: NonceProvider,
+    progress: ProgressReporter,
+    call_wrapper: Option<CallWrapper>,
+    ss58_prefix: Option<u16>,
     _marker: PhantomData<C>,
 }
 
@@ -65,7 +96,18 @@ where
                 url: url::Url::parse("ws://localhost:9944").unwrap(),
                 signer,
                 storage_deposit_limit: None,
+                unlimited_deposit: false,
                 verbosity: Verbosity::Default,
+                rpc_options: RpcOptions::default(),
+                finalization_timeout: None,
+                submit_timeout: None,
+                submit_options: SubmitOptions::default(),
+                mortality: None,
+                tip: 0,
+                nonce_provider: NonceProvider::default(),
+                progress: ProgressReporter::default(),
+                call_wrapper: None,
+                ss58_prefix: None,
                 _marker: PhantomData,
             },
         }
@@ -85,13 +127,24 @@ where
         this
     }
 
-    /// Sets the websockets url of a Substrate node.
+    /// Sets the url of a Substrate node.
+    ///
+    /// HTTP endpoints only support dry-runs, submitting an extrinsic requires a
+    /// websocket endpoint.
     pub fn url<T: Into<Url>>(self, url: T) -> Self {
         let mut this = self;
         this.opts.url = url.into();
         this
     }
 
+    /// Sets further endpoints of nodes of the same chain, which are failed over to if
+    /// the connection to the node at the [`url`](Self::url) fails.
+    pub fn fallback_urls(self, urls: Vec<Url>) -> Self {
+        let mut this = self;
+        this.opts.rpc_options.fallback_urls = urls.iter().map(url_to_string).collect();
+        this
+    }
+
     /// Sets the maximum amount of balance that can be charged from the caller to pay for
     /// storage.
     pub fn storage_deposit_limit(
@@ -103,6 +156,15 @@ where
         this
     }
 
+    /// Sets whether to submit extrinsics without a storage deposit limit if none is set,
+    /// instead of limiting the deposit to the one charged in a dry-run plus a safety
+    /// margin.
+    pub fn unlimited_deposit(self, unlimited_deposit: bool) -> Self {
+        let mut this = self;
+        this.opts.unlimited_deposit = unlimited_deposit;
+        this
+    }
+
     /// Set the verbosity level.
     pub fn verbosity(self, verbosity: Verbosity) -> Self {
         let mut this = self;
@@ -110,6 +172,133 @@ where
         this
     }
 
+    /// Sets all options for the requests to the node at once, e.g. the ones passed on
+    /// the command line.
+    pub fn rpc_options(self, rpc_options: RpcOptions) -> Self {
+        let mut this = self;
+        this.opts.rpc_options = rpc_options;
+        this
+    }
+
+    /// Sets the overall timeout of a request to the node, including all of its retries.
+    pub fn rpc_timeout(self, timeout: Duration) -> Self {
+        let mut this = self;
+        this.opts.rpc_options.timeout = timeout;
+        this
+    }
+
+    /// Sets how often a request to the node failing with a transient error is retried.
+    pub fn rpc_retries(self, retries: u32) -> Self {
+        let mut this = self;
+        this.opts.rpc_options.retries = retries;
+        this
+    }
+
+    /// Sets whether to connect through a running [daemon](crate::daemon).
+    pub fn daemon(self, daemon: bool) -> Self {
+        let mut this = self;
+        this.opts.rpc_options.daemon = daemon;
+        this
+    }
+
+    /// Sets whether the runtime metadata of the node is cached on disk.
+    pub fn metadata_cache(self, metadata_cache: bool) -> Self {
+        let mut this = self;
+        this.opts.rpc_options.metadata_cache = metadata_cache;
+        this
+    }
+
+    /// Sets whether the runtime metadata is fetched from the node even if it is cached.
+    pub fn refresh_metadata(self, refresh_metadata: bool) -> Self {
+        let mut this = self;
+        this.opts.rpc_options.refresh_metadata = refresh_metadata;
+        this
+    }
+
+    /// Wait for the block including a submitted extrinsic to be finalized, instead of
+    /// only for its inclusion into a block.
+    ///
+    /// Submitting fails if the block is not finalized within `timeout`.
+    pub fn wait_for_finalization(self, timeout: Option<Duration>) -> Self {
+        let mut this = self;
+        this.opts.finalization_timeout = timeout;
+        this
+    }
+
+    /// Sets the time to wait for a submitted extrinsic to be included into a block.
+    ///
+    /// Submitting fails with the hash of the extrinsic if it is not included within
+    /// `timeout`, so that it can be tracked manually. Waits indefinitely if `None`.
+    pub fn submit_timeout(self, timeout: Option<Duration>) -> Self {
+        let mut this = self;
+        this.opts.submit_timeout = timeout;
+        this
+    }
+
+    /// Sets the options for the submission of extrinsics, e.g. how often an extrinsic
+    /// rejected because of its nonce is resubmitted.
+    pub fn submit_options(self, submit_options: SubmitOptions) -> Self {
+        let mut this = self;
+        this.opts.submit_options = submit_options;
+        this
+    }
+
+    /// Sets the number of blocks, starting from the current best block, a submitted
+    /// extrinsic is valid for. Extrinsics are immortal if `None`.
+    ///
+    /// The runtime rounds the period up to a power of two between 4 and 65536.
+    pub fn mortality(self, mortality: Option<u64>) -> Self {
+        let mut this = self;
+        this.opts.mortality = mortality;
+        this
+    }
+
+    /// Sets the tip paid to the block author to prioritize a submitted extrinsic.
+    pub fn tip(self, tip: u128) -> Self {
+        let mut this = self;
+        this.opts.tip = tip;
+        this
+    }
+
+    /// Sets the provider of the nonces of submitted extrinsics.
+    pub fn nonce_provider(self, nonce_provider: NonceProvider) -> Self {
+        let mut this = self;
+        this.opts.nonce_provider = nonce_provider;
+        this
+    }
+
+    /// Sets the reporter receiving the progress of waiting for submitted extrinsics.
+    pub fn progress(self, progress: ProgressReporter) -> Self {
+        let mut this = self;
+        this.opts.progress = progress;
+        this
+    }
+
+    /// Wraps the calls of submitted extrinsics into outer calls, e.g. with
+    /// [`call_wrapper::sudo`](crate::call_wrapper::sudo).
+    ///
+    /// Wrappers set by repeated calls are applied in order, so the last one wraps the
+    /// outermost call. Dry-runs are not affected.
+    pub fn wrap_call<F>(self, wrapper: F) -> Self
+    where
+        F: Fn(DynamicPayload) -> DynamicPayload + Send + Sync + 'static,
+    {
+        let mut this = self;
+        this.opts.call_wrapper = Some(match this.opts.call_wrapper.take() {
+            Some(inner) => Arc::new(move |call| wrapper(inner(call))),
+            None => Arc::new(wrapper),
+        });
+        this
+    }
+
+    /// Sets the SS58 prefix accounts are displayed with, e.g. in the decoded events.
+    /// Defaults to the `SS58Prefix` of the chain if `None`.
+    pub fn ss58_prefix(self, ss58_prefix: Option<u16>) -> Self {
+        let mut this = self;
+        this.opts.ss58_prefix = ss58_prefix;
+        this
+    }
+
     pub fn done(self) -> ExtrinsicOpts<C, E, Signer> {
         self.opts
     }
@@ -152,8 +341,114 @@ where
         self.storage_deposit_limit
     }
 
+    /// Returns whether the storage deposit limit of a submitted extrinsic is derived
+    /// from the deposit charged in its dry-run.
+    pub fn estimates_storage_deposit_limit(&self) -> bool {
+        self.storage_deposit_limit.is_none() && !self.unlimited_deposit
+    }
+
+    /// Returns the storage deposit limit to submit an extrinsic with, given the
+    /// `deposit` of its dry-run.
+    ///
+    /// A limit set explicitly takes precedence. Otherwise a charged deposit plus
+    /// [`STORAGE_DEPOSIT_MARGIN_PERCENT`] is used, unless the deposit is unlimited.
+    pub fn storage_deposit_limit_for(
+        &self,
+        deposit: &StorageDeposit<E::Balance>,
+    ) -> Option<E::Balance> {
+        if !self.estimates_storage_deposit_limit() {
+            return self.storage_deposit_limit
+        }
+        match deposit {
+            StorageDeposit::Charge(charge) => Some(with_margin(*charge)),
+            StorageDeposit::Refund(_) => None,
+        }
+    }
+
     /// Verbosity for message reporting.
     pub fn verbosity(&self) -> &Verbosity {
         &self.verbosity
     }
+
+    /// Return the options for the communication with the node.
+    pub fn rpc_options(&self) -> RpcOptions {
+        self.rpc_options.clone()
+    }
+
+    /// Return the timeout for the finalization of a submitted extrinsic, `None` if only
+    /// its inclusion into a block is awaited.
+    pub fn finalization_timeout(&self) -> Option<Duration> {
+        self.finalization_timeout
+    }
+
+    /// Return the timeout for the inclusion of a submitted extrinsic into a block, `None`
+    /// if it is awaited indefinitely.
+    pub fn submit_timeout(&self) -> Option<Duration> {
+        self.submit_timeout
+    }
+
+    /// Return the options for the submission of extrinsics.
+    pub fn submit_options(&self) -> &SubmitOptions {
+        &self.submit_options
+    }
+
+    /// Return the number of blocks a submitted extrinsic is valid for, `None` if it is
+    /// immortal.
+    pub fn mortality(&self) -> Option<u64> {
+        self.mortality
+    }
+
+    /// Return the tip paid to the block author.
+    pub fn tip(&self) -> u128 {
+        self.tip
+    }
+
+    /// Return the provider of the nonces of submitted extrinsics.
+    pub fn nonce_provider(&self) -> &NonceProvider {
+        &self.nonce_provider
+    }
+
+    /// Return the reporter receiving the progress of waiting for submitted extrinsics.
+    pub fn progress(&self) -> &ProgressReporter {
+        &self.progress
+    }
+
+    /// Return the wrapper of the calls of submitted extrinsics, if any.
+    pub fn call_wrapper(&self) -> Option<&CallWrapper> {
+        self.call_wrapper.as_ref()
+    }
+
+    /// Return the SS58 prefix accounts are displayed with, `None` for the prefix of the
+    /// chain.
+    pub fn ss58_prefix(&self) -> Option<u16> {
+        self.ss58_prefix
+    }
+}
+
+/// Adds [`STORAGE_DEPOSIT_MARGIN_PERCENT`] to the `charge`, rounded up.
+fn with_margin<Balance>(charge: Balance) -> Balance
+where
+    Balance: Copy + TryInto<u128> + TryFrom<u128>,
+{
+    let Ok(value) = charge.try_into() else {
+        return charge
+    };
+    let margin = value
+        .saturating_mul(STORAGE_DEPOSIT_MARGIN_PERCENT)
+        .saturating_add(99)
+        / 100;
+    Balance::try_from(value.saturating_add(margin)).unwrap_or(charge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn margin_is_added_to_the_charge() {
+        assert_eq!(with_margin(1_000u128), 1_100);
+        assert_eq!(with_margin(1u128), 2);
+        assert_eq!(with_margin(0u128), 0);
+        assert_eq!(with_margin(u128::MAX), u128::MAX);
+    }
 }
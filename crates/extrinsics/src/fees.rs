@@ -0,0 +1,97 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    rpc_client,
+    state_call,
+    url_to_string,
+    RpcOptions,
+};
+
+use anyhow::Result;
+use scale::Decode;
+use sp_weights::{
+    constants::WEIGHT_REF_TIME_PER_SECOND,
+    Weight,
+};
+use subxt::{
+    backend::legacy::LegacyRpcMethods,
+    Config,
+};
+use url::Url;
+
+/// The weight the execution fee is reported for: one second of execution time.
+const REFERENCE_WEIGHT: Weight = Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND, 0);
+
+/// The length the length fee is reported for: one KiB.
+const REFERENCE_LENGTH: u32 = 1024;
+
+/// The precision of the `FixedU128` fee multiplier.
+const FEE_MULTIPLIER_PRECISION: f64 = 1_000_000_000_000_000_000.0;
+
+/// The parameters of the fees of a chain charged by `pallet-transaction-payment`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FeeParameters {
+    /// The fee of one second of execution time.
+    pub weight_fee_per_second: u128,
+    /// The fee of one KiB of extrinsic length.
+    pub length_fee_per_kib: u128,
+    /// The multiplier of the weight fees of the next block, which rises above `1.0`
+    /// while blocks are congested.
+    pub fee_multiplier: f64,
+}
+
+impl FeeParameters {
+    /// Queries the fee parameters of the chain of the node at `url`.
+    ///
+    /// Requires version 2 or higher of the `TransactionPaymentApi` of the node.
+    pub async fn query<C: Config>(url: &Url, options: RpcOptions) -> Result<Self> {
+        let rpc_cli = rpc_client(&url_to_string(url), options).await?;
+        let rpc = LegacyRpcMethods::<C>::new(rpc_cli);
+        let weight_fee = state_call::<C, _, u128>(
+            &rpc,
+            "TransactionPaymentApi_query_weight_to_fee",
+            REFERENCE_WEIGHT,
+        );
+        let length_fee = state_call::<C, _, u128>(
+            &rpc,
+            "TransactionPaymentApi_query_length_to_fee",
+            REFERENCE_LENGTH,
+        );
+        let (weight_fee_per_second, length_fee_per_kib, fee_multiplier) =
+            futures::try_join!(weight_fee, length_fee, fee_multiplier(&rpc))?;
+        Ok(Self {
+            weight_fee_per_second,
+            length_fee_per_kib,
+            fee_multiplier,
+        })
+    }
+}
+
+/// Fetches the `TransactionPayment::NextFeeMultiplier` storage value, `1.0` if it is not
+/// set.
+async fn fee_multiplier<C: Config>(rpc: &LegacyRpcMethods<C>) -> Result<f64> {
+    let key = [
+        sp_core::twox_128(b"TransactionPayment"),
+        sp_core::twox_128(b"NextFeeMultiplier"),
+    ]
+    .concat();
+    let multiplier = match rpc.state_get_storage(&key, None).await? {
+        Some(bytes) => u128::decode(&mut &bytes[..])? as f64 / FEE_MULTIPLIER_PRECISION,
+        None => 1.0,
+    };
+    Ok(multiplier)
+}
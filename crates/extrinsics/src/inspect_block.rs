@@ -0,0 +1,136 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    contracts_backend::is_contracts_pallet,
+    decode_contract_calls,
+    events::{
+        decode_event,
+        events_transcoder,
+    },
+    ContractCallData,
+    EventRecord,
+};
+use anyhow::Result;
+use contract_transcode::ContractMessageTranscoder;
+use std::collections::HashMap;
+use subxt::{
+    events::Phase,
+    ext::scale_decode::IntoVisitor,
+    Config,
+    OnlineClient,
+};
+
+/// An extrinsic of a block which calls a contracts pallet, or during which one of them
+/// emitted events.
+#[derive(Clone, Debug)]
+pub struct ContractsExtrinsic {
+    /// The index of the extrinsic in the block.
+    pub index: u32,
+    /// The pallet of the call of the extrinsic, e.g. `Contracts` or `Sudo`.
+    pub pallet: String,
+    /// The name of the call of the extrinsic, e.g. `call`.
+    pub call: String,
+    /// The calls of contracts in the extrinsic, including the wrapped ones, see
+    /// [`decode_contract_calls`].
+    pub contract_calls: Vec<ContractCallData>,
+    /// The events of the contracts pallets emitted by the extrinsic.
+    pub events: Vec<EventRecord>,
+}
+
+/// The extrinsics and events of a block concerning contracts.
+#[derive(Clone, Debug, Default)]
+pub struct ContractsBlock {
+    /// The number of the block.
+    pub number: u64,
+    /// The extrinsics concerning contracts, in the order of the block.
+    pub extrinsics: Vec<ContractsExtrinsic>,
+    /// The events of the contracts pallets which were not emitted by an extrinsic, but
+    /// during the initialization or finalization of the block.
+    pub events: Vec<EventRecord>,
+}
+
+/// Fetches the extrinsics and events concerning contracts of the block with the
+/// `block_hash`.
+///
+/// The events emitted by contracts are decoded with the first of the `transcoders`
+/// which knows the event, and are raw hex otherwise. The accounts in the fields of the
+/// other events are displayed with the `ss58_prefix`.
+pub async fn inspect_block<C: Config>(
+    client: &OnlineClient<C>,
+    block_hash: C::Hash,
+    transcoders: &[ContractMessageTranscoder],
+    ss58_prefix: u16,
+) -> Result<ContractsBlock>
+where
+    C::AccountId: IntoVisitor,
+{
+    let block = client.blocks().at(block_hash).await?;
+    let metadata = client.metadata();
+    let transcoders: Vec<_> = transcoders.iter().collect();
+    let events_transcoder = events_transcoder(&metadata, ss58_prefix);
+
+    let mut inspected = ContractsBlock {
+        number: block.number().into(),
+        ..Default::default()
+    };
+    let mut extrinsic_events = HashMap::<u32, Vec<EventRecord>>::new();
+    for event in block.events().await?.iter() {
+        let event = event?;
+        if !is_contracts_pallet(event.pallet_name()) {
+            continue
+        }
+        let phase = event.phase();
+        let record =
+            decode_event::<C>(event, &transcoders, &events_transcoder, &metadata)?;
+        match phase {
+            Phase::ApplyExtrinsic(index) => {
+                extrinsic_events.entry(index).or_default().push(record)
+            }
+            Phase::Initialization | Phase::Finalization => inspected.events.push(record),
+        }
+    }
+
+    for extrinsic in block.extrinsics().await?.iter() {
+        let extrinsic = extrinsic?;
+        let pallet = extrinsic.pallet_name()?;
+        // Extrinsics the runtime metadata does not describe, e.g. of an older runtime,
+        // are only listed if they emitted events of contracts.
+        let contract_calls = decode_contract_calls(&metadata, extrinsic.call_bytes())
+            .unwrap_or_else(|err| {
+                tracing::debug!(
+                    "Failed to decode extrinsic {}: {err:?}",
+                    extrinsic.index()
+                );
+                Vec::new()
+            });
+        let events = extrinsic_events
+            .remove(&extrinsic.index())
+            .unwrap_or_default();
+        if !is_contracts_pallet(pallet) && contract_calls.is_empty() && events.is_empty()
+        {
+            continue
+        }
+        inspected.extrinsics.push(ContractsExtrinsic {
+            index: extrinsic.index(),
+            pallet: pallet.to_string(),
+            call: extrinsic.variant_name()?.to_string(),
+            contract_calls,
+            events,
+        });
+    }
+    Ok(inspected)
+}
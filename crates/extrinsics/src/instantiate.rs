@@ -15,9 +15,14 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
+    estimate_fee,
     events::{
+        decode_events,
+        find_contracts_events,
         CodeStored,
         ContractInstantiated,
+        EventRecord,
+        ReviveContractInstantiated,
     },
     pallet_contracts_primitives::{
         ContractInstantiateResult,
@@ -26,22 +31,37 @@ use super::{
     state_call,
     submit_extrinsic,
     ContractMessageTranscoder,
+    ContractsBackend,
     ErrorVariant,
+    ExtrinsicReceipt,
 };
 use crate::{
     check_env_types,
+    code_exists,
+    contracts_backend,
+    eth_account,
     extrinsic_calls::{
         Instantiate,
         InstantiateWithCode,
     },
     extrinsic_opts::ExtrinsicOpts,
+    fetch_storage_deposit,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
+    ss58,
+    StorageDepositBreakdown,
 };
 use anyhow::{
     anyhow,
     Context,
     Result,
 };
-use contract_transcode::Value;
+use contract_transcode::{
+    ReturnError,
+    SolidityAbi,
+    Value,
+};
 use ink_env::Environment;
 use serde::Serialize;
 
@@ -50,13 +70,13 @@ use scale::{
     Encode,
 };
 use sp_core::Bytes;
+use sp_runtime::traits::TrailingZeroInput;
 use sp_weights::Weight;
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    path::PathBuf,
+};
 use subxt::{
-    backend::{
-        legacy::LegacyRpcMethods,
-        rpc::RpcClient,
-    },
     blocks::ExtrinsicEvents,
     config::{
         DefaultExtrinsicParams,
@@ -80,6 +100,8 @@ pub struct InstantiateCommandBuilder<C: Config, E: Environment, Signer: Clone> {
     gas_limit: Option<u64>,
     proof_size: Option<u64>,
     salt: Option<Bytes>,
+    abi: Option<PathBuf>,
+    always_upload: bool,
 }
 
 impl<C: Config, E: Environment, Signer> InstantiateCommandBuilder<C, E, Signer>
@@ -100,6 +122,8 @@ where
             gas_limit: None,
             proof_size: None,
             salt: None,
+            abi: None,
+            always_upload: false,
         }
     }
 
@@ -145,6 +169,27 @@ where
         this
     }
 
+    /// Sets the path to a Solidity ABI file describing the contract.
+    ///
+    /// If set, the constructor arguments are encoded according to the Solidity ABI
+    /// instead of the ink! metadata. The contract code has to be provided as a `.wasm`
+    /// file in this case.
+    pub fn abi<T: Into<PathBuf>>(self, abi: Option<T>) -> Self {
+        let mut this = self;
+        this.abi = abi.map(Into::into);
+        this
+    }
+
+    /// Sets whether to always upload the contract code with the instantiation.
+    ///
+    /// By default the code is only uploaded if it is not stored on chain yet, otherwise
+    /// the contract is instantiated from the existing code.
+    pub fn always_upload(self, always_upload: bool) -> Self {
+        let mut this = self;
+        this.always_upload = always_upload;
+        this
+    }
+
     /// Preprocesses contract artifacts and options for instantiation.
     ///
     /// This function prepares the required data for instantiating a contract based on the
@@ -156,21 +201,63 @@ where
     /// instantiation, or an error in case of failure.
     pub async fn done(self) -> Result<InstantiateExec<C, E, Signer>> {
         let artifacts = self.extrinsic_opts.contract_artifacts()?;
-        let transcoder = artifacts.contract_transcoder()?;
-        let data = transcoder.encode(&self.constructor, &self.args)?;
-        let url = self.extrinsic_opts.url();
-        let code = if let Some(code) = artifacts.code {
-            Code::Upload(code.0)
-        } else {
-            let code_hash = artifacts.code_hash()?;
-            Code::Existing(code_hash.into())
+        let (transcoder, abi) = match self.abi.as_ref() {
+            Some(abi_path) => (None, Some(SolidityAbi::load(abi_path)?)),
+            None => (Some(artifacts.contract_transcoder()?), None),
         };
+        let url = self.extrinsic_opts.url();
         let salt = self.salt.clone().map(|s| s.0).unwrap_or_default();
 
-        let rpc_cli = RpcClient::from_url(&url).await?;
-        let client = OnlineClient::from_rpc_client(rpc_cli.clone()).await?;
-        check_env_types(&client, &transcoder, self.extrinsic_opts.verbosity())?;
-        let rpc = LegacyRpcMethods::new(rpc_cli);
+        let rpc_cli = rpc_client(&url, self.extrinsic_opts.rpc_options()).await?;
+        let client =
+            online_client(rpc_cli.clone(), self.extrinsic_opts.rpc_options()).await?;
+        let backend = contracts_backend(&client.metadata())?;
+        if backend.h160_addresses() && !salt.is_empty() && salt.len() != 32 {
+            return Err(anyhow!(
+                "The salt has to be 32 bytes long on chains running the `{}` pallet, \
+                 but it is {} bytes long",
+                backend.pallet(),
+                salt.len()
+            ))
+        }
+        if let Some(transcoder) = transcoder.as_ref() {
+            check_env_types(&client, transcoder, self.extrinsic_opts.verbosity())?;
+        }
+        let ss58_prefix = ss58::ss58_prefix(
+            self.extrinsic_opts.ss58_prefix(),
+            Some(&client.metadata()),
+        );
+        // only `pallet-revive` maps Ethereum style addresses to accounts
+        let transcoder = transcoder.map(|transcoder| {
+            transcoder
+                .with_ss58_prefix(ss58_prefix)
+                .with_eth_addresses(backend.requires_account_mapping())
+        });
+        let data = match (&transcoder, &abi) {
+            (Some(transcoder), _) => transcoder.encode(&self.constructor, &self.args)?,
+            (None, Some(abi)) => abi.encode_constructor(&self.args)?,
+            (None, None) => unreachable!("either a transcoder or an ABI is loaded"),
+        };
+        let rpc = NodeRpc::new(rpc_cli);
+
+        let (code, code_hash) = match artifacts.code {
+            Some(code) => {
+                let code_hash = code.code_hash();
+                if !self.always_upload && code_exists(&client, &rpc, code_hash).await? {
+                    tracing::debug!(
+                        "Code with hash 0x{} is already stored on chain, skipping upload",
+                        hex::encode(code_hash)
+                    );
+                    (Code::Existing(code_hash.into()), code_hash.into())
+                } else {
+                    (Code::Upload(code.0), code_hash.into())
+                }
+            }
+            None => {
+                let code_hash = artifacts.code_hash()?;
+                (Code::Existing(code_hash.into()), code_hash.into())
+            }
+        };
 
         let args = InstantiateArgs {
             constructor: self.constructor.clone(),
@@ -180,6 +267,7 @@ where
             proof_size: self.proof_size,
             storage_deposit_limit: self.extrinsic_opts.storage_deposit_limit(),
             code,
+            code_hash,
             data,
             salt,
         };
@@ -189,7 +277,10 @@ where
             opts: self.extrinsic_opts,
             rpc,
             client,
+            backend,
             transcoder,
+            abi,
+            ss58_prefix,
         })
     }
 }
@@ -202,6 +293,7 @@ pub struct InstantiateArgs<C: Config, E: Environment> {
     proof_size: Option<u64>,
     storage_deposit_limit: Option<E::Balance>,
     code: Code<C::Hash>,
+    code_hash: C::Hash,
     data: Vec<u8>,
     salt: Vec<u8>,
 }
@@ -241,6 +333,11 @@ impl<C: Config, E: Environment> InstantiateArgs<C, E> {
         &self.code
     }
 
+    /// Returns the hash of the code the contract is instantiated from.
+    pub fn code_hash(&self) -> &C::Hash {
+        &self.code_hash
+    }
+
     /// Returns the input data for the contract constructor.
     pub fn data(&self) -> &[u8] {
         &self.data
@@ -255,9 +352,12 @@ impl<C: Config, E: Environment> InstantiateArgs<C, E> {
 pub struct InstantiateExec<C: Config, E: Environment, Signer: Clone> {
     opts: ExtrinsicOpts<C, E, Signer>,
     args: InstantiateArgs<C, E>,
-    rpc: LegacyRpcMethods<C>,
+    rpc: NodeRpc<C>,
     client: OnlineClient<C>,
-    transcoder: ContractMessageTranscoder,
+    backend: &'static dyn ContractsBackend,
+    transcoder: Option<ContractMessageTranscoder>,
+    abi: Option<SolidityAbi>,
+    ss58_prefix: u16,
 }
 
 impl<C: Config, E: Environment, Signer> InstantiateExec<C, E, Signer>
@@ -285,17 +385,37 @@ where
         tracing::debug!("instantiate data {:?}", self.args.data);
         match result.result {
             Ok(ref ret_val) => {
-                let value = self
-                    .transcoder
-                    .decode_constructor_return(
-                        &self.args.constructor,
-                        &mut &ret_val.result.data[..],
-                    )
-                    .context(format!("Failed to decode return value {:?}", &ret_val))?;
+                // Solidity constructors do not return any data.
+                let value = match self.transcoder.as_ref() {
+                    Some(transcoder) => {
+                        transcoder
+                            .decode_constructor_return(
+                                &self.args.constructor,
+                                &mut &ret_val.result.data[..],
+                            )
+                            .context(format!(
+                                "Failed to decode return value {:?}",
+                                &ret_val
+                            ))
+                            .map_err(ErrorVariant::decode)?
+                    }
+                    None => Value::Unit,
+                };
+                let reverted = ret_val.result.did_revert();
+                let lang_result = self.transcoder.as_ref().is_some_and(|transcoder| {
+                    transcoder.constructor_returns_lang_result(&self.args.constructor)
+                });
+                let error = reverted
+                    .then(|| ReturnError::from_return_value(&value, lang_result))
+                    .flatten();
                 let dry_run_result = InstantiateDryRunResult {
                     result: value,
-                    contract: ret_val.account_id.to_string(),
-                    reverted: ret_val.result.did_revert(),
+                    contract: ss58::display_account(
+                        &ret_val.account_id,
+                        self.ss58_prefix,
+                    ),
+                    reverted,
+                    error,
                     gas_consumed: result.gas_consumed,
                     gas_required: result.gas_required,
                     storage_deposit: result.storage_deposit.clone(),
@@ -320,83 +440,172 @@ where
     pub async fn instantiate_dry_run(
         &self,
     ) -> Result<ContractInstantiateResult<C::AccountId, E::Balance>> {
-        let storage_deposit_limit = self.args.storage_deposit_limit;
-        let call_request = InstantiateRequest::<C, E> {
+        let method = self.backend.runtime_api_method("instantiate");
+        let salt = self.args.salt.clone();
+        if !self.backend.h160_addresses() {
+            return state_call(&self.rpc, &method, self.instantiate_request(salt)).await
+        }
+        // `pallet-revive` takes an optional 32 byte salt and returns the `H160` address
+        // of the contract
+        let salt = <[u8; 32]>::try_from(salt).ok();
+        let result: ContractInstantiateResult<[u8; 20], E::Balance> =
+            state_call(&self.rpc, &method, self.instantiate_request(salt)).await?;
+        result.try_map_account(eth_account)
+    }
+
+    fn instantiate_request<Salt>(&self, salt: Salt) -> InstantiateRequest<C, E, Salt> {
+        InstantiateRequest {
             origin: self.opts.signer().account_id(),
             value: self.args.value,
             gas_limit: None,
-            storage_deposit_limit,
+            storage_deposit_limit: self.args.storage_deposit_limit,
             code: self.args.code.clone(),
             data: self.args.data.clone(),
-            salt: self.args.salt.clone(),
-        };
-        state_call(&self.rpc, "ContractsApi_instantiate", &call_request).await
+            salt,
+        }
     }
 
     async fn instantiate_with_code(
         &self,
         code: Vec<u8>,
         gas_limit: Weight,
+        storage_deposit_limit: Option<E::Balance>,
     ) -> Result<InstantiateExecResult<C>, ErrorVariant> {
         let call = InstantiateWithCode::new(
             self.args.value,
             gas_limit,
-            self.args.storage_deposit_limit,
+            storage_deposit_limit,
             code,
             self.args.data.clone(),
             self.args.salt.clone(),
         )
-        .build();
+        .build(self.backend);
 
-        let events =
-            submit_extrinsic(&self.client, &self.rpc, &call, self.opts.signer()).await?;
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
 
         // The CodeStored event is only raised if the contract has not already been
         // uploaded.
-        let code_hash = events
-            .find_first::<CodeStored<C::Hash>>()?
-            .map(|code_stored| code_stored.code_hash);
+        let metadata = self.client.metadata();
+        let code_stored = find_contracts_events::<_, CodeStored<C::Hash>>(
+            &events,
+            self.backend,
+            &metadata,
+        )
+        .next()
+        .transpose()?
+        .map(|code_stored| code_stored.code_hash);
 
-        let instantiated = events
-            .find_last::<ContractInstantiated<C::AccountId>>()?
-            .ok_or_else(|| anyhow!("Failed to find Instantiated event"))?;
+        let instantiated = self.find_instantiated(&events)?;
 
-        Ok(InstantiateExecResult {
-            events,
-            code_hash,
-            contract_address: instantiated.contract,
-        })
+        let code_hash = code_stored.unwrap_or(self.args.code_hash);
+        self.exec_result(events, receipt, instantiated, code_hash, code_stored.is_some())
+            .await
     }
 
     async fn instantiate_with_code_hash(
         &self,
         code_hash: C::Hash,
         gas_limit: Weight,
+        storage_deposit_limit: Option<E::Balance>,
     ) -> Result<InstantiateExecResult<C>, ErrorVariant> {
         let call = Instantiate::<C::Hash, E::Balance>::new(
             self.args.value,
             gas_limit,
-            self.args.storage_deposit_limit,
+            storage_deposit_limit,
             code_hash,
             self.args.data.clone(),
             self.args.salt.clone(),
         )
-        .build();
+        .build(self.backend);
 
-        let events =
-            submit_extrinsic(&self.client, &self.rpc, &call, self.opts.signer()).await?;
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
 
-        let instantiated = events
-            .find_first::<ContractInstantiated<C::AccountId>>()?
-            .ok_or_else(|| anyhow!("Failed to find Instantiated event"))?;
+        let instantiated = self.find_instantiated(&events)?;
+
+        self.exec_result(events, receipt, instantiated, code_hash, false)
+            .await
+    }
 
+    /// Returns the last `Instantiated` event of the `events`, the one of the instantiated
+    /// contract, since the events of contracts instantiated by its constructor precede
+    /// it.
+    fn find_instantiated(
+        &self,
+        events: &ExtrinsicEvents<C>,
+    ) -> Result<ContractInstantiated<C::AccountId>> {
+        let metadata = self.client.metadata();
+        let instantiated = if self.backend.h160_addresses() {
+            find_contracts_events::<_, ReviveContractInstantiated>(
+                events,
+                self.backend,
+                &metadata,
+            )
+            .last()
+            .transpose()?
+            .map(ReviveContractInstantiated::into_accounts)
+            .transpose()?
+        } else {
+            find_contracts_events::<_, ContractInstantiated<C::AccountId>>(
+                events,
+                self.backend,
+                &metadata,
+            )
+            .last()
+            .transpose()?
+        };
+        instantiated.ok_or_else(|| anyhow!("Failed to find Instantiated event"))
+    }
+
+    /// Assembles the result of the submitted instantiation from its `events`.
+    async fn exec_result(
+        &self,
+        events: ExtrinsicEvents<C>,
+        receipt: ExtrinsicReceipt<C::Hash>,
+        instantiated: ContractInstantiated<C::AccountId>,
+        code_hash: C::Hash,
+        code_stored: bool,
+    ) -> Result<InstantiateExecResult<C>, ErrorVariant> {
+        let event_records = decode_events(
+            &events,
+            self.transcoder.as_ref(),
+            &self.client.metadata(),
+            self.ss58_prefix,
+        )
+        .collect::<Result<Vec<_>>>()?;
+        let deposit_charged = deposit_charged(&event_records);
+        let storage_deposit = self.storage_deposit(&instantiated.contract).await;
         Ok(InstantiateExecResult {
             events,
-            code_hash: None,
+            event_records,
+            receipt,
+            code_hash,
+            code_stored,
+            contract: ss58::display_account(&instantiated.contract, self.ss58_prefix),
             contract_address: instantiated.contract,
+            storage_deposit,
+            deposit_charged,
+            return_value: None,
         })
     }
 
+    /// Fetches the storage deposit breakdown of the instantiated `contract`.
+    ///
+    /// The breakdown is informational, so failing to fetch it does not fail the
+    /// instantiation.
+    async fn storage_deposit(
+        &self,
+        contract: &C::AccountId,
+    ) -> Option<StorageDepositBreakdown> {
+        fetch_storage_deposit(contract, &self.rpc, &self.client)
+            .await
+            .map_err(|err| {
+                tracing::debug!("Failed to fetch the storage deposit breakdown: {err}")
+            })
+            .ok()
+    }
+
     /// Initiates the deployment of a smart contract on the blockchain.
     ///
     /// This function can be used to deploy a contract using either its source code or an
@@ -412,18 +621,85 @@ where
         gas_limit: Option<Weight>,
     ) -> Result<InstantiateExecResult<C>, ErrorVariant> {
         // use user specified values where provided, otherwise estimate
-        let gas_limit = match gas_limit {
-            Some(gas_limit) => gas_limit,
-            None => self.estimate_gas().await?,
+        let (gas_limit, return_value) = match gas_limit {
+            Some(gas_limit) => (gas_limit, None),
+            None => self.dry_run_gas_estimate().await?,
         };
-        match self.args.code.clone() {
-            Code::Upload(code) => self.instantiate_with_code(code, gas_limit).await,
+        let storage_deposit_limit = self.estimate_storage_deposit_limit().await?;
+        let mut result = match self.args.code.clone() {
+            Code::Upload(code) => {
+                self.instantiate_with_code(code, gas_limit, storage_deposit_limit)
+                    .await?
+            }
             Code::Existing(code_hash) => {
-                self.instantiate_with_code_hash(code_hash, gas_limit).await
+                self.instantiate_with_code_hash(
+                    code_hash,
+                    gas_limit,
+                    storage_deposit_limit,
+                )
+                .await?
+            }
+        };
+        result.return_value = return_value;
+        Ok(result)
+    }
+
+    /// Returns the storage deposit limit to submit the instantiation with.
+    ///
+    /// Unless a limit is set or the deposit is unlimited, the instantiation is dry-run
+    /// to derive the limit from the charged deposit, see
+    /// [`ExtrinsicOpts::storage_deposit_limit_for`].
+    pub async fn estimate_storage_deposit_limit(&self) -> Result<Option<E::Balance>> {
+        if !self.opts.estimates_storage_deposit_limit() {
+            return Ok(self.args.storage_deposit_limit)
+        }
+        let instantiate_result = self.instantiate_dry_run().await?;
+        match instantiate_result.result {
+            Ok(_) => {
+                Ok(self
+                    .opts
+                    .storage_deposit_limit_for(&instantiate_result.storage_deposit))
+            }
+            Err(ref err) => {
+                let object =
+                    ErrorVariant::from_dispatch_error(err, &self.client.metadata())?;
+                Err(anyhow!("Pre-submission dry-run failed. Error: {}", object))
             }
         }
     }
 
+    /// Estimates the fee of instantiating the contract with the `gas_limit`, excluding
+    /// the tip.
+    pub async fn estimate_fee(&self, gas_limit: Weight) -> Result<u128> {
+        let fee = match self.args.code.clone() {
+            Code::Upload(code) => {
+                let call = InstantiateWithCode::new(
+                    self.args.value,
+                    gas_limit,
+                    self.args.storage_deposit_limit,
+                    code,
+                    self.args.data.clone(),
+                    self.args.salt.clone(),
+                )
+                .build(self.backend);
+                estimate_fee(&self.client, &call, &self.opts).await?
+            }
+            Code::Existing(code_hash) => {
+                let call = Instantiate::<C::Hash, E::Balance>::new(
+                    self.args.value,
+                    gas_limit,
+                    self.args.storage_deposit_limit,
+                    code_hash,
+                    self.args.data.clone(),
+                    self.args.salt.clone(),
+                )
+                .build(self.backend);
+                estimate_fee(&self.client, &call, &self.opts).await?
+            }
+        };
+        Ok(fee)
+    }
+
     /// Estimates the gas required for the contract instantiation process without
     /// modifying the blockchain.
     ///
@@ -433,9 +709,17 @@ where
     /// Returns the estimated gas weight of type [`Weight`] for contract instantiation, or
     /// an error.
     pub async fn estimate_gas(&self) -> Result<Weight> {
+        Ok(self.dry_run_gas_estimate().await?.0)
+    }
+
+    /// Estimates the gas required for the instantiation, see [`Self::estimate_gas`].
+    ///
+    /// Also returns the decoded return value of the constructor if the instantiation
+    /// was dry-run for the estimate.
+    async fn dry_run_gas_estimate(&self) -> Result<(Weight, Option<Value>)> {
         match (self.args.gas_limit, self.args.proof_size) {
             (Some(ref_time), Some(proof_size)) => {
-                Ok(Weight::from_parts(ref_time, proof_size))
+                Ok((Weight::from_parts(ref_time, proof_size), None))
             }
             _ => {
                 let instantiate_result = self.instantiate_dry_run().await?;
@@ -449,7 +733,12 @@ where
                         let proof_size = self.args.proof_size.unwrap_or_else(|| {
                             instantiate_result.gas_required.proof_size()
                         });
-                        Ok(Weight::from_parts(ref_time, proof_size))
+                        let return_value = self
+                            .decode_instantiate_dry_run(&instantiate_result)
+                            .await
+                            .ok()
+                            .map(|dry_run| dry_run.result);
+                        Ok((Weight::from_parts(ref_time, proof_size), return_value))
                     }
                     Err(ref err) => {
                         let object = ErrorVariant::from_dispatch_error(
@@ -478,17 +767,92 @@ where
         &self.client
     }
 
-    /// Returns the contract message transcoder.
-    pub fn transcoder(&self) -> &ContractMessageTranscoder {
-        &self.transcoder
+    /// Returns the pallet implementing smart contracts on the chain.
+    pub fn backend(&self) -> &'static dyn ContractsBackend {
+        self.backend
+    }
+
+    /// Returns the SS58 prefix accounts are displayed with, see
+    /// [`ExtrinsicOpts::ss58_prefix`].
+    pub fn ss58_prefix(&self) -> u16 {
+        self.ss58_prefix
+    }
+
+    /// Returns the contract message transcoder, if the contract is described by ink!
+    /// metadata instead of a Solidity ABI, see [`Self::abi`].
+    pub fn transcoder(&self) -> Option<&ContractMessageTranscoder> {
+        self.transcoder.as_ref()
+    }
+
+    /// Returns the Solidity ABI of the contract, if one was provided.
+    pub fn abi(&self) -> Option<&SolidityAbi> {
+        self.abi.as_ref()
     }
 }
 
-/// A struct representing the result of an instantiate command execution.
+/// The result of a submitted contract instantiation.
+///
+/// Holds everything derived from the events of the extrinsic, so that it can be
+/// serialized as is, e.g. to JSON.
+#[derive(serde::Serialize)]
+#[serde(bound(serialize = "C::Hash: Serialize, C::AccountId: Display"))]
 pub struct InstantiateExecResult<C: Config> {
+    /// The raw events of the extrinsic.
+    #[serde(skip)]
     pub events: ExtrinsicEvents<C>,
-    pub code_hash: Option<C::Hash>,
+    /// The decoded events of the extrinsic.
+    #[serde(rename = "events")]
+    pub event_records: Vec<EventRecord>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+    /// The hash of the code of the instantiated contract.
+    pub code_hash: C::Hash,
+    /// Whether the code was uploaded by the extrinsic, i.e. `CodeStored` was emitted.
+    pub code_stored: bool,
+    #[serde(skip)]
     pub contract_address: C::AccountId,
+    /// The address of the instantiated contract as SS58 address with the prefix of the
+    /// chain, see [`InstantiateExec::ss58_prefix`].
+    #[serde(rename = "contract_address")]
+    pub contract: String,
+    /// The storage deposit held for the instantiated contract, if it could be fetched.
+    pub storage_deposit: Option<StorageDepositBreakdown>,
+    /// The storage deposit charged for the instantiation, `None` if the runtime does
+    /// not emit `StorageDepositTransferredAndHeld` events.
+    pub deposit_charged: Option<u128>,
+    /// The decoded value returned by the constructor.
+    ///
+    /// The return value is not part of the events, it is the one of the dry-run the
+    /// gas was estimated with, `None` if no dry-run took place.
+    pub return_value: Option<Value>,
+}
+
+impl<C: Config> InstantiateExecResult<C>
+where
+    C::AccountId: Display,
+{
+    /// Returns the result as JSON.
+    pub fn to_json(&self) -> Result<String>
+    where
+        C::Hash: Serialize,
+    {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Sums the `amount`s of the `StorageDepositTransferredAndHeld` events, `None` if there
+/// are none.
+fn deposit_charged(records: &[EventRecord]) -> Option<u128> {
+    let amounts = records
+        .iter()
+        .filter(|record| record.is("Contracts", "StorageDepositTransferredAndHeld"))
+        .map(|record| {
+            match record.field("amount") {
+                Some(Value::UInt(amount)) => Some(*amount),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (!amounts.is_empty()).then(|| amounts.iter().sum())
 }
 
 /// Result of the contract call
@@ -500,6 +864,9 @@ pub struct InstantiateDryRunResult<Balance: Serialize> {
     pub contract: String,
     /// Was the operation reverted
     pub reverted: bool,
+    /// The error returned by the constructor, if it reverted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ReturnError>,
     pub gas_consumed: Weight,
     pub gas_required: Weight,
     /// Storage deposit after the operation
@@ -514,15 +881,18 @@ impl<Balance: Serialize> InstantiateDryRunResult<Balance> {
 }
 
 /// A struct that encodes RPC parameters required to instantiate a new smart contract.
+///
+/// The `salt` is any bytes for `pallet-contracts`, and an optional 32 byte salt for
+/// `pallet-revive`.
 #[derive(Encode)]
-struct InstantiateRequest<C: Config, E: Environment> {
+struct InstantiateRequest<C: Config, E: Environment, Salt> {
     origin: C::AccountId,
     value: E::Balance,
     gas_limit: Option<Weight>,
     storage_deposit_limit: Option<E::Balance>,
     code: Code<C::Hash>,
     data: Vec<u8>,
-    salt: Vec<u8>,
+    salt: Salt,
 }
 
 /// Reference to an existing code hash or a new Wasm module.
@@ -536,3 +906,51 @@ where
     /// The code hash of an on-chain Wasm blob.
     Existing(Hash),
 }
+
+/// Derives the address of the contract instantiated by the `deployer` from the code with
+/// the `code_hash`, with the constructor call `input_data` and the `salt`.
+///
+/// Matches the address derivation of `pallet-contracts`, so that the address is known
+/// before the contract is instantiated, e.g. to fund it in advance. Chains running
+/// `pallet-revive` derive the addresses of contracts differently.
+pub fn derive_contract_address<AccountId, Hash>(
+    deployer: &AccountId,
+    code_hash: &Hash,
+    input_data: &[u8],
+    salt: &[u8],
+) -> Result<AccountId>
+where
+    AccountId: Encode + Decode,
+    Hash: Encode,
+{
+    let entropy = (b"contract_addr_v1", deployer, code_hash, input_data, salt)
+        .using_encoded(sp_core::blake2_256);
+    Ok(AccountId::decode(&mut TrailingZeroInput::new(&entropy))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Field;
+
+    fn record(pallet: &str, variant: &str, amount: u128) -> EventRecord {
+        EventRecord {
+            pallet: pallet.to_string(),
+            variant: variant.to_string(),
+            fields: vec![Field::new("amount".to_string(), Value::UInt(amount), None)],
+            bytes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn deposit_charged_sums_transferred_deposits() {
+        let records = [
+            record("Balances", "Withdraw", 1),
+            record("Contracts", "StorageDepositTransferredAndHeld", 100),
+            record("Contracts", "StorageDepositTransferredAndHeld", 20),
+        ];
+
+        assert_eq!(deposit_charged(&records), Some(120));
+        assert_eq!(deposit_charged(&records[..1]), None);
+    }
+}
@@ -17,12 +17,18 @@
 use crate::{
     CallCommandBuilder,
     CallExec,
+    ContractStorage,
+    ContractStorageRpc,
+    ContractsEvent,
     DisplayEvents,
     ExtrinsicOptsBuilder,
     InstantiateCommandBuilder,
+    InstantiateExec,
     InstantiateExecResult,
     RemoveCommandBuilder,
     RemoveExec,
+    TransferCommandBuilder,
+    TransferExec,
     UploadCommandBuilder,
     UploadExec,
 };
@@ -39,6 +45,10 @@ use std::{
     time,
 };
 use subxt::{
+    utils::{
+        AccountId32,
+        H256,
+    },
     OnlineClient,
     PolkadotConfig as DefaultConfig,
 };
@@ -475,7 +485,7 @@ async fn api_build_upload_instantiate_call() {
     let uri = <SecretUri as std::str::FromStr>::from_str("//Alice").unwrap();
     let signer = Keypair::from_uri(&uri).unwrap();
     let opts = ExtrinsicOptsBuilder::new(signer)
-        .file(Some(contract_file))
+        .file(Some(contract_file.clone()))
         .done();
     let upload: UploadExec<DefaultConfig, DefaultEnvironment, Keypair> =
         UploadCommandBuilder::new(opts.clone())
@@ -493,12 +503,34 @@ async fn api_build_upload_instantiate_call() {
         .done()
         .await
         .unwrap();
+    // the uploaded code is reused instead of being uploaded again
+    assert!(matches!(
+        instantiate.args().code(),
+        crate::Code::Existing(_)
+    ));
     let instantiate_result = instantiate.instantiate(None).await;
     assert!(instantiate_result.is_ok(), "instantiate code failed");
     let instantiate_result: InstantiateExecResult<DefaultConfig> =
         instantiate_result.unwrap();
     let contract_account = instantiate_result.contract_address.to_string();
     assert_eq!(48, contract_account.len(), "{contract_account:?}");
+    assert_eq!(&instantiate_result.code_hash, instantiate.args().code_hash());
+    assert!(!instantiate_result.code_stored);
+    assert!(instantiate_result.return_value.is_some());
+    let json = instantiate_result.to_json().unwrap();
+    assert!(json.contains(&contract_account), "{json}");
+    // the instantiation is reported as a typed event
+    let instantiated = instantiate_result.event_records.iter().find_map(|record| {
+        record.contracts_event::<AccountId32, H256>().unwrap()
+    });
+    assert!(
+        matches!(
+            &instantiated,
+            Some(ContractsEvent::Instantiated(event))
+                if event.contract == instantiate_result.contract_address
+        ),
+        "{instantiated:?}"
+    );
 
     // call the contract
     // the value should be true
@@ -515,11 +547,7 @@ async fn api_build_upload_instantiate_call() {
     assert!(result.is_ok(), "call failed");
     let result = result.unwrap();
     let ret_val = result.result.unwrap();
-    let value = call
-        .transcoder()
-        .decode_message_return(call.message(), &mut &ret_val.data[..])
-        .unwrap()
-        .to_string();
+    let value = call.decode_return(&ret_val.data).unwrap().to_string();
     assert!(value.contains("true"), "{:#?}", value);
 
     // call the contract on the immutable "get" message trying to execute
@@ -544,15 +572,47 @@ async fn api_build_upload_instantiate_call() {
     assert!(call_result.is_ok(), "call failed");
     let call_result = call_result.unwrap();
     let output = DisplayEvents::from_events::<DefaultConfig, DefaultEnvironment>(
-        &call_result,
+        &call_result.events,
         None,
         &call.client().metadata(),
+        crate::ss58::SUBSTRATE_SS58_PREFIX,
     )
     .unwrap()
     .to_json()
     .unwrap();
     assert!(output.contains("ExtrinsicSuccess"), "{:#?}", output);
 
+    // the call of the contract is listed and decoded in its block
+    let transcoders = [crate::ContractArtifacts::from_manifest_or_file(
+        None,
+        Some(&contract_file),
+    )
+    .unwrap()
+    .contract_transcoder()
+    .unwrap()];
+    let block = crate::inspect_block(
+        call.client(),
+        call_result.receipt.block_hash,
+        &transcoders,
+        crate::ss58::SUBSTRATE_SS58_PREFIX,
+    )
+    .await
+    .unwrap();
+    assert_eq!(block.number, call_result.receipt.block_number);
+    let extrinsic = block
+        .extrinsics
+        .iter()
+        .find(|extrinsic| extrinsic.pallet == "Contracts" && extrinsic.call == "call")
+        .expect("the call is listed");
+    let message = extrinsic.contract_calls[0]
+        .decode_message(&transcoders)
+        .unwrap();
+    assert!(message.to_string().contains("flip"), "{message}");
+    assert!(extrinsic
+        .events
+        .iter()
+        .any(|event| event.is("Contracts", "Called")));
+
     // call the contract
     // make sure the value has been flipped
     let call: CallExec<DefaultConfig, DefaultEnvironment, Keypair> =
@@ -568,11 +628,7 @@ async fn api_build_upload_instantiate_call() {
     assert!(result.is_ok(), "call failed");
     let result = result.unwrap();
     let ret_val = result.result.unwrap();
-    let value = call
-        .transcoder()
-        .decode_message_return(call.message(), &mut &ret_val.data[..])
-        .unwrap()
-        .to_string();
+    let value = call.decode_return(&ret_val.data).unwrap().to_string();
     assert!(value.contains("false"), "{:#?}", value);
 
     // prevent the node_process from being dropped and killed
@@ -791,3 +847,352 @@ async fn build_upload_instantiate_storage() {
     // prevent the node_process from being dropped and killed
     let _ = node_process;
 }
+
+/// Sanity test forking the state of a contract with the contract extrinsics API.
+///
+/// # Note
+///
+/// Requires [`substrate-contracts-node`](https://github.com/paritytech/substrate-contracts-node/) to
+/// be installed and available on the `PATH`, and the no other process running using the
+/// default port `9944`.
+#[tokio::test]
+async fn api_build_upload_instantiate_fork() {
+    init_tracing_subscriber();
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("cargo-contract.cli.test.")
+        .tempdir()
+        .expect("temporary directory creation failed");
+
+    cargo_contract(tmp_dir.path())
+        .arg("new")
+        .arg("flipper")
+        .assert()
+        .success();
+
+    let project_path = tmp_dir.path().join("flipper");
+
+    cargo_contract(project_path.as_path())
+        .arg("build")
+        .assert()
+        .success();
+
+    let node_process = ContractsNodeProcess::spawn(CONTRACTS_NODE)
+        .await
+        .expect("Error spawning contracts node");
+
+    let contract_file = project_path.join("target/ink/flipper.contract");
+    let uri = <SecretUri as std::str::FromStr>::from_str("//Alice").unwrap();
+    let signer = Keypair::from_uri(&uri).unwrap();
+    let opts = ExtrinsicOptsBuilder::new(signer)
+        .file(Some(contract_file))
+        .done();
+    let instantiate: InstantiateExec<DefaultConfig, DefaultEnvironment, Keypair> =
+        InstantiateCommandBuilder::new(opts.clone())
+            .constructor("new")
+            .args(["true"].to_vec())
+            .done()
+            .await
+            .unwrap();
+    let contract = instantiate
+        .instantiate(None)
+        .await
+        .expect("instantiate failed")
+        .contract_address;
+
+    let url = url::Url::parse("ws://localhost:9944").unwrap();
+    let storage = ContractStorage::<DefaultConfig, DefaultEnvironment>::new(
+        ContractStorageRpc::new(&url).await.unwrap(),
+    );
+    let before = storage.fork_contract(&contract).await.unwrap();
+    assert_eq!(before.contract, contract.to_string());
+    assert_eq!(before.info.code_hash(), instantiate.args().code_hash());
+    assert_eq!(code_hash(&before.code), before.info.code_hash().0);
+    assert_eq!(before.storage.len(), 1);
+
+    let flip: CallExec<DefaultConfig, DefaultEnvironment, Keypair> =
+        CallCommandBuilder::new(contract.clone(), "flip", opts)
+            .done()
+            .await
+            .unwrap();
+    flip.call(None).await.expect("call failed");
+
+    // the state changed at the best block, but not at the block forked before
+    let after = storage.fork_contract(&contract).await.unwrap();
+    let past = storage
+        .fork_contract_at(&contract, before.block_hash)
+        .await
+        .unwrap();
+    assert_ne!(after.block_hash, before.block_hash);
+    assert_ne!(
+        serde_json::to_value(&after.storage).unwrap(),
+        serde_json::to_value(&before.storage).unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(&past).unwrap(),
+        serde_json::to_value(&before).unwrap()
+    );
+
+    // prevent the node_process from being dropped and killed
+    let _ = node_process;
+}
+
+/// Uploading and instantiating through `sudo.sudo_as` is refused for signers other than
+/// the sudo key, before submitting the extrinsic, and succeeds for the sudo key.
+#[tokio::test]
+async fn build_upload_instantiate_sudo() {
+    init_tracing_subscriber();
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("cargo-contract.cli.test.")
+        .tempdir()
+        .expect("temporary directory creation failed");
+
+    cargo_contract(tmp_dir.path())
+        .arg("new")
+        .arg("flipper")
+        .assert()
+        .success();
+
+    let mut project_path = tmp_dir.path().to_path_buf();
+    project_path.push("flipper");
+
+    cargo_contract(project_path.as_path())
+        .arg("build")
+        .assert()
+        .success();
+
+    let node_process = ContractsNodeProcess::spawn(CONTRACTS_NODE)
+        .await
+        .expect("Error spawning contracts node");
+
+    cargo_contract(project_path.as_path())
+        .arg("upload")
+        .args(["--suri", "//Bob"])
+        .arg("--sudo")
+        .arg("-x")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not the sudo key"));
+
+    cargo_contract(project_path.as_path())
+        .arg("instantiate")
+        .args(["--constructor", "new"])
+        .args(["--args", "true"])
+        .args(["--suri", "//Bob"])
+        .arg("--sudo")
+        .arg("-x")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not the sudo key"));
+
+    // `//Alice` is the sudo key of the development chain
+    cargo_contract(project_path.as_path())
+        .arg("upload")
+        .args(["--suri", "//Alice"])
+        .arg("--sudo")
+        .arg("-x")
+        .arg("--skip-confirm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SudoAsDone"))
+        .stdout(predicate::str::contains("CodeStored"));
+
+    cargo_contract(project_path.as_path())
+        .arg("instantiate")
+        .args(["--constructor", "new"])
+        .args(["--args", "true"])
+        .args(["--suri", "//Alice"])
+        .arg("--sudo")
+        .arg("-x")
+        .arg("--skip-confirm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("SudoAsDone"))
+        .stdout(predicate::str::contains("Instantiated"));
+
+    // prevent the node_process from being dropped and killed
+    let _ = node_process;
+}
+
+/// Sanity test submitting several calls in a single `utility.batch_all` extrinsic.
+#[tokio::test]
+async fn build_upload_instantiate_batch_call() {
+    init_tracing_subscriber();
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("cargo-contract.cli.test.")
+        .tempdir()
+        .expect("temporary directory creation failed");
+
+    cargo_contract(tmp_dir.path())
+        .arg("new")
+        .arg("flipper")
+        .assert()
+        .success();
+
+    let mut project_path = tmp_dir.path().to_path_buf();
+    project_path.push("flipper");
+
+    cargo_contract(project_path.as_path())
+        .arg("build")
+        .assert()
+        .success();
+
+    let node_process = ContractsNodeProcess::spawn(CONTRACTS_NODE)
+        .await
+        .expect("Error spawning contracts node");
+
+    let output = cargo_contract(project_path.as_path())
+        .arg("instantiate")
+        .args(["--constructor", "new"])
+        .args(["--args", "true"])
+        .args(["--suri", "//Alice"])
+        .arg("-x")
+        .arg("--skip-confirm")
+        .output()
+        .expect("failed to execute process");
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(output.status.success(), "instantiate failed: {stderr}");
+    let contract_account = extract_contract_address(stdout);
+
+    let batch_file = tmp_dir.path().join("batch.toml");
+    let call =
+        format!("[[call]]\ncontract = \"{contract_account}\"\nmessage = \"flip\"\n");
+    std::fs::write(&batch_file, call.repeat(2)).unwrap();
+
+    // flipping twice in the batch leaves the value unchanged
+    cargo_contract(project_path.as_path())
+        .arg("call")
+        .arg("--batch")
+        .arg(&batch_file)
+        .args(["--suri", "//Alice"])
+        .arg("-x")
+        .arg("--skip-confirm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("BatchCompleted"));
+
+    cargo_contract(project_path.as_path())
+        .arg("call")
+        .args(["--message", "get"])
+        .args(["--contract", contract_account])
+        .args(["--suri", "//Alice"])
+        .assert()
+        .stdout(predicate::str::contains("true"));
+
+    // prevent the node_process from being dropped and killed
+    let _ = node_process;
+}
+
+/// Sanity test querying read-only messages, and rejecting messages which mutate the
+/// contract.
+#[tokio::test]
+async fn build_upload_instantiate_query() {
+    init_tracing_subscriber();
+
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("cargo-contract.cli.test.")
+        .tempdir()
+        .expect("temporary directory creation failed");
+
+    cargo_contract(tmp_dir.path())
+        .arg("new")
+        .arg("flipper")
+        .assert()
+        .success();
+
+    let mut project_path = tmp_dir.path().to_path_buf();
+    project_path.push("flipper");
+
+    cargo_contract(project_path.as_path())
+        .arg("build")
+        .assert()
+        .success();
+
+    let node_process = ContractsNodeProcess::spawn(CONTRACTS_NODE)
+        .await
+        .expect("Error spawning contracts node");
+
+    let output = cargo_contract(project_path.as_path())
+        .arg("instantiate")
+        .args(["--constructor", "new"])
+        .args(["--args", "true"])
+        .args(["--suri", "//Alice"])
+        .arg("-x")
+        .arg("--skip-confirm")
+        .output()
+        .expect("failed to execute process");
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(output.status.success(), "instantiate failed: {stderr}");
+    let contract_account = extract_contract_address(stdout);
+
+    cargo_contract(project_path.as_path())
+        .arg("query")
+        .args(["--message", "get"])
+        .args(["--contract", contract_account])
+        .arg("--output-json")
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("true\n"));
+
+    cargo_contract(project_path.as_path())
+        .arg("query")
+        .args(["--message", "flip"])
+        .args(["--contract", contract_account])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use `cargo contract call` instead"));
+
+    // prevent the node_process from being dropped and killed
+    let _ = node_process;
+}
+
+/// Sanity test transferring a value with `Balances::transfer_keep_alive`.
+#[tokio::test]
+async fn api_transfer() {
+    init_tracing_subscriber();
+
+    let node_process = ContractsNodeProcess::spawn(CONTRACTS_NODE)
+        .await
+        .expect("Error spawning contracts node");
+
+    let uri = <SecretUri as std::str::FromStr>::from_str("//Alice").unwrap();
+    let signer = Keypair::from_uri(&uri).unwrap();
+    let opts = ExtrinsicOptsBuilder::new(signer).done();
+    let dest: AccountId32 = subxt_signer::sr25519::dev::bob().public_key().into();
+    let transfer: TransferExec<DefaultConfig, DefaultEnvironment, Keypair> =
+        TransferCommandBuilder::new(dest.clone(), 1_000_000, opts)
+            .done()
+            .await
+            .unwrap();
+
+    let result = transfer.transfer().await.expect("transfer failed");
+    let event = result
+        .events
+        .find_first::<TransferEvent>()
+        .unwrap()
+        .expect("no Balances::Transfer event");
+    assert_eq!(event.to, dest);
+    assert_eq!(event.amount, 1_000_000);
+
+    // prevent the node_process from being dropped and killed
+    let _ = node_process;
+}
+
+/// A `Balances::Transfer` event.
+#[derive(Debug, subxt::ext::scale_decode::DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct TransferEvent {
+    #[allow(dead_code)]
+    from: AccountId32,
+    to: AccountId32,
+    amount: u128,
+}
+
+impl subxt::events::StaticEvent for TransferEvent {
+    const PALLET: &'static str = "Balances";
+    const EVENT: &'static str = "Transfer";
+}
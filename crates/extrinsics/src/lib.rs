@@ -15,40 +15,73 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 mod balance;
+mod batch_call;
 mod call;
+pub mod call_wrapper;
+mod chain_info;
 mod contract_artifacts;
 mod contract_info;
 mod contract_storage;
+mod contracts_backend;
+mod decode_extrinsic;
+pub mod daemon;
 mod env_check;
 mod error;
 mod events;
 mod extrinsic_calls;
 mod extrinsic_opts;
+mod fees;
+mod inspect_block;
 mod instantiate;
+mod map_account;
+mod metadata_cache;
+mod nonce;
 pub mod pallet_contracts_primitives;
 mod remove;
 mod rpc;
+mod rpc_client;
+#[cfg(feature = "sandbox")]
+mod sandbox;
+mod schedule;
+pub mod ss58;
+mod submit_options;
+mod transfer;
 mod upload;
+mod xcm;
 
 #[cfg(test)]
 mod contract_storage_tests;
 
+#[cfg(test)]
+mod test_metadata;
+
 #[cfg(test)]
 #[cfg(feature = "integration-tests")]
 mod integration_tests;
 
 use env_check::compare_node_env_with_contract;
+use rpc_client::NodeRpc;
+use submit_options::is_nonce_error;
 
 use anyhow::Result;
 use contract_build::{
+    progress::Operation,
     CrateMetadata,
     Verbosity,
     DEFAULT_KEY_COL_WIDTH,
 };
+use ink_env::Environment;
 use scale::{
     Decode,
     Encode,
 };
+use std::{
+    future,
+    sync::atomic::{
+        AtomicBool,
+        Ordering,
+    },
+};
 use subxt::{
     backend::legacy::LegacyRpcMethods,
     blocks,
@@ -56,7 +89,10 @@ use subxt::{
         DefaultExtrinsicParams,
         DefaultExtrinsicParamsBuilder,
         ExtrinsicParams,
+        Hasher,
+        Header,
     },
+    error::DispatchError,
     tx,
     Config,
     OnlineClient,
@@ -66,33 +102,100 @@ pub use balance::{
     BalanceVariant,
     TokenMetadata,
 };
+pub use batch_call::{
+    BatchCallExec,
+    BatchCallResult,
+};
 pub use call::{
     CallCommandBuilder,
     CallExec,
+    CallResult,
+    DryRunCost,
 };
+pub use call_wrapper::CallWrapper;
+pub use chain_info::ChainInfo;
 pub use contract_artifacts::ContractArtifacts;
 pub use contract_info::{
+    code_exists,
     fetch_all_contracts,
+    fetch_code_info,
     fetch_contract_info,
+    fetch_contract_info_at,
+    fetch_contract_instances,
+    fetch_storage_deposit,
     fetch_wasm_code,
+    fetch_wasm_code_at,
+    for_each_contract,
+    max_code_len,
+    CodeInfo,
     ContractInfo,
+    ContractInstances,
+    StorageDepositBreakdown,
     TrieId,
 };
 use contract_metadata::ContractMetadata;
 pub use contract_storage::{
+    ContractFork,
     ContractStorage,
     ContractStorageCell,
+    ContractStorageField,
+    ContractStorageFieldValue,
+    ContractStorageKey,
     ContractStorageLayout,
     ContractStorageRpc,
+    ForkedContractStorage,
+    MutableContractStorage,
+    StorageCellKind,
+    StorageLayoutNode,
 };
 pub use contract_transcode::ContractMessageTranscoder;
+pub use contracts_backend::{
+    contracts_backend,
+    eth_account,
+    eth_address,
+    is_contracts_pallet,
+    ContractAddress,
+    ContractsBackend,
+    PalletContracts,
+    PalletRevive,
+};
+pub use decode_extrinsic::{
+    decode_contract_calls,
+    ContractCallData,
+};
 pub use error::{
+    ArtifactNotFound,
     ErrorVariant,
     GenericError,
+    EXIT_CODES,
+    EXIT_CODE_GENERIC,
+};
+pub use events::{
+    decode_events,
+    CodeStored,
+    ContractEmitted,
+    ContractInstantiated,
+    ContractsEvent,
+    DisplayEvents,
+    Event,
+    EventFilter,
+    EventRecord,
+    Field,
+};
+pub use extrinsic_opts::{
+    ExtrinsicOpts,
+    ExtrinsicOptsBuilder,
+    DEFAULT_FINALIZATION_TIMEOUT,
+    STORAGE_DEPOSIT_MARGIN_PERCENT,
+};
+pub use fees::FeeParameters;
+pub use inspect_block::{
+    inspect_block,
+    ContractsBlock,
+    ContractsExtrinsic,
 };
-pub use events::DisplayEvents;
-pub use extrinsic_opts::ExtrinsicOptsBuilder;
 pub use instantiate::{
+    derive_contract_address,
     Code,
     InstantiateArgs,
     InstantiateCommandBuilder,
@@ -100,6 +203,29 @@ pub use instantiate::{
     InstantiateExec,
     InstantiateExecResult,
 };
+pub use map_account::{
+    MapAccountCommandBuilder,
+    MapAccountExec,
+    MapAccountResult,
+};
+pub use metadata_cache::{
+    online_client,
+    refresh_on_runtime_upgrade,
+    RuntimeUpgrade,
+};
+pub use nonce::NonceProvider;
+pub use submit_options::{
+    SubmitOptions,
+    DEFAULT_NONCE_RETRIES,
+    DEFAULT_NONCE_RETRY_BACKOFF,
+};
+#[cfg(feature = "sandbox")]
+pub use sandbox::{
+    SandboxBackend,
+    SandboxContractEvent,
+    SandboxContractStorage,
+    SandboxResult,
+};
 pub use remove::{
     RemoveCommandBuilder,
     RemoveExec,
@@ -107,6 +233,7 @@ pub use remove::{
 };
 
 pub use upload::{
+    Determinism,
     UploadCommandBuilder,
     UploadExec,
     UploadResult,
@@ -116,6 +243,28 @@ pub use rpc::{
     RawParams,
     RpcRequest,
 };
+pub use rpc_client::{
+    ensure_can_submit,
+    rpc_client,
+    RetryingRpcClient,
+    RpcOptions,
+    DEFAULT_RPC_RETRIES,
+    DEFAULT_RPC_TIMEOUT,
+};
+pub use schedule::ScheduledCallResult;
+pub use transfer::{
+    TransferCommandBuilder,
+    TransferExec,
+    TransferResult,
+};
+pub use xcm::{
+    Junction,
+    OriginKind,
+    XcmLocation,
+    XcmSendResult,
+    XcmSender,
+    XcmTransact,
+};
 
 /// The Wasm code of a contract.
 #[derive(Debug, Clone)]
@@ -126,9 +275,62 @@ impl WasmCode {
     pub fn code_hash(&self) -> [u8; 32] {
         contract_build::code_hash(&self.0)
     }
+
+    /// The raw bytes of the contract code.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Identifiers of a submitted extrinsic, which allow tracking its finality.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExtrinsicReceipt<Hash> {
+    /// The hash of the extrinsic.
+    pub extrinsic_hash: Hash,
+    /// The hash of the block the extrinsic was included in.
+    pub block_hash: Hash,
+    /// The number of the block the extrinsic was included in.
+    pub block_number: u64,
+}
+
+/// Estimates the fee of the extrinsic, excluding the tip, through the
+/// `TransactionPaymentApi` of the node.
+///
+/// The fee is the one of the `call` as wrapped by the [`CallWrapper`] of the `opts`.
+async fn estimate_fee<C, E, Call, Signer>(
+    client: &OnlineClient<C>,
+    call: &Call,
+    opts: &ExtrinsicOpts<C, E, Signer>,
+) -> core::result::Result<u128, subxt::Error>
+where
+    C: Config,
+    E: Environment,
+    Call: tx::TxPayload,
+    Signer: tx::Signer<C> + Clone,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    // The nonce doesn't affect the fee, it is left out so that a tracked nonce is not
+    // used up by the estimation.
+    let params = DefaultExtrinsicParamsBuilder::new().tip(opts.tip()).build();
+    let extrinsic = match opts.call_wrapper() {
+        Some(wrap) => {
+            let call = wrap(call_wrapper::into_dynamic(client, call)?);
+            client
+                .tx()
+                .create_signed_offline(&call, opts.signer(), params.into())?
+        }
+        None => {
+            client
+                .tx()
+                .create_signed_offline(call, opts.signer(), params.into())?
+        }
+    };
+    extrinsic.partial_fee_estimate().await
 }
 
-/// Wait for the transaction to be included successfully into a block.
+/// Wait for the transaction to be included successfully into a block, or for the block
+/// to be finalized if configured in `opts`.
 ///
 /// # Errors
 ///
@@ -138,87 +340,391 @@ impl WasmCode {
 ///
 /// # Finality
 ///
-/// Currently this will report success once the transaction is included in a block. In the
-/// future there could be a flag to wait for finality before reporting success.
-async fn submit_extrinsic<C, Call, Signer>(
+/// By default this will report success once the transaction is included in a block.
+/// When waiting for finalization, an error is returned if the block including the
+/// transaction is not finalized within the configured timeout.
+///
+/// # Connection loss
+///
+/// If the subscription to the status of the transaction is lost, e.g. because the
+/// connection to the node dropped, the new blocks are searched for the transaction
+/// instead, see [`follow_blocks`]. An error naming the hash of the transaction is
+/// returned if it is not included in a block within the configured submit timeout.
+///
+/// # Runtime upgrades
+///
+/// If the runtime of the node was upgraded since the `client` was created, e.g. between
+/// the dry-run and the submission, its metadata is refreshed and the `call` is
+/// re-validated against it, see [`RuntimeUpgrade::revalidate`]. Only the encoding of the
+/// call is checked again, the dry-run is not repeated.
+///
+/// # Nonce recovery
+///
+/// If the extrinsic is rejected because its nonce is stale or in the future, it is
+/// signed again with a nonce refetched from the node and resubmitted according to the
+/// [`SubmitOptions`] of the `opts`. A nonce set explicitly with
+/// [`NonceProvider::starting_at`] is never replaced, the error is returned instead.
+///
+/// # Call wrapping
+///
+/// If the `opts` have a [`CallWrapper`], the extrinsic is submitted with the wrapped
+/// `call`, e.g. a `Sudo::sudo` call dispatching it. An error is returned if a call
+/// dispatched by `Sudo::sudo` failed, even though the extrinsic itself succeeded.
+async fn submit_extrinsic<C, E, Call, Signer>(
     client: &OnlineClient<C>,
-    rpc: &LegacyRpcMethods<C>,
+    rpc: &NodeRpc<C>,
     call: &Call,
-    signer: &Signer,
-) -> core::result::Result<blocks::ExtrinsicEvents<C>, subxt::Error>
+    opts: &ExtrinsicOpts<C, E, Signer>,
+) -> core::result::Result<
+    (blocks::ExtrinsicEvents<C>, ExtrinsicReceipt<C::Hash>),
+    subxt::Error,
+>
 where
     C: Config,
+    E: Environment,
     Call: tx::TxPayload,
-    Signer: tx::Signer<C>,
+    Signer: tx::Signer<C> + Clone,
     <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
         From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
 {
-    let account_id = Signer::account_id(signer);
-    let account_nonce = get_account_nonce(client, rpc, &account_id).await?;
+    match opts.call_wrapper() {
+        Some(wrap) => {
+            let call = wrap(call_wrapper::into_dynamic(client, call)?);
+            let (events, receipt) =
+                submit_signed_extrinsic(client, rpc, &call, opts).await?;
+            call_wrapper::sudo_result(&events, client.metadata())?;
+            Ok((events, receipt))
+        }
+        None => submit_signed_extrinsic(client, rpc, call, opts).await,
+    }
+}
 
-    let params = DefaultExtrinsicParamsBuilder::new()
-        .nonce(account_nonce)
-        .build();
-    let mut tx = client
-        .tx()
-        .create_signed_offline(call, signer, params.into())?
-        .submit_and_watch()
-        .await?;
+/// Signs and submits the extrinsic of the `call` as is, see [`submit_extrinsic`].
+async fn submit_signed_extrinsic<C, E, Call, Signer>(
+    client: &OnlineClient<C>,
+    rpc: &NodeRpc<C>,
+    call: &Call,
+    opts: &ExtrinsicOpts<C, E, Signer>,
+) -> core::result::Result<
+    (blocks::ExtrinsicEvents<C>, ExtrinsicReceipt<C::Hash>),
+    subxt::Error,
+>
+where
+    C: Config,
+    E: Environment,
+    Call: tx::TxPayload,
+    Signer: tx::Signer<C> + Clone,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    if let Some(upgrade) = refresh_on_runtime_upgrade(client, rpc).await? {
+        tracing::warn!(
+            "The runtime of the node was upgraded from spec version {} to {}, the \
+            extrinsic is submitted with the metadata of the new runtime",
+            upgrade.from,
+            upgrade.to
+        );
+        upgrade.revalidate(call, &client.metadata())?;
+    }
+
+    let submit_options = opts.submit_options();
+    let mut attempt = 0;
+    let (mut tx, extrinsic_hash, best_block) = loop {
+        match sign_and_submit(client, rpc, call, opts).await {
+            Err(err)
+                if is_nonce_error(&err)
+                    && attempt < submit_options.nonce_retries
+                    && opts.nonce_provider().is_refetchable() =>
+            {
+                let backoff = submit_options.backoff(attempt);
+                attempt += 1;
+                tracing::warn!(
+                    "The extrinsic was rejected because of its nonce, resubmitting it \
+                    with a refetched nonce in {}ms (retry {attempt} of {}): {err}",
+                    backoff.as_millis(),
+                    submit_options.nonce_retries
+                );
+                opts.nonce_provider().reset();
+                tokio::time::sleep(backoff).await;
+            }
+            result => break result?,
+        }
+    };
 
     // Below we use the low level API to replicate the `wait_for_in_block` behaviour which
     // was removed in subxt 0.33.0. See https://github.com/paritytech/subxt/pull/1237.
     //
     // We require this because we use `substrate-contracts-node` as our development node,
-    // which does not currently support finality, so we just want to wait until it is
-    // included in a block.
-    use subxt::error::{
-        RpcError,
-        TransactionError,
-    };
+    // which does not currently support finality, so by default we just want to wait
+    // until it is included in a block.
+    use subxt::error::TransactionError;
     use tx::TxStatus;
 
-    while let Some(status) = tx.next().await {
-        match status? {
-            TxStatus::InBestBlock(tx_in_block)
-            | TxStatus::InFinalizedBlock(tx_in_block) => {
-                let events = tx_in_block.wait_for_success().await?;
-                return Ok(events)
+    let finalization_timeout = opts.finalization_timeout();
+    let progress = opts.progress();
+    let included = AtomicBool::new(false);
+    let watch = async {
+        while let Some(status) = tx.next().await {
+            let status = match status {
+                Ok(status) => status,
+                Err(subxt::Error::Rpc(err)) => {
+                    tracing::debug!("Watching the extrinsic failed: {err}");
+                    break
+                }
+                Err(err) => return Some(Err(err)),
+            };
+            match status {
+                TxStatus::InBestBlock(tx_in_block) if finalization_timeout.is_some() => {
+                    included.store(true, Ordering::Relaxed);
+                    progress.status(
+                        Operation::WaitForExtrinsic,
+                        "InBlock",
+                        format!("{:?}", tx_in_block.block_hash()),
+                    );
+                }
+                TxStatus::InBestBlock(tx_in_block)
+                | TxStatus::InFinalizedBlock(tx_in_block) => {
+                    included.store(true, Ordering::Relaxed);
+                    if finalization_timeout.is_some() {
+                        progress.status(
+                            Operation::WaitForExtrinsic,
+                            "Finalized",
+                            format!("{:?}", tx_in_block.block_hash()),
+                        );
+                    }
+                    let result = async {
+                        let events = tx_in_block.wait_for_success().await?;
+                        let header = rpc
+                            .chain_get_header(Some(events.block_hash()))
+                            .await?
+                            .ok_or(subxt::Error::Other("Block header not found".into()))?;
+                        let receipt = ExtrinsicReceipt {
+                            extrinsic_hash: events.extrinsic_hash(),
+                            block_hash: events.block_hash(),
+                            block_number: header.number().into(),
+                        };
+                        Ok((events, receipt))
+                    };
+                    return Some(result.await)
+                }
+                TxStatus::Error { message } => {
+                    return Some(Err(TransactionError::Error(message).into()))
+                }
+                TxStatus::Invalid { message } => {
+                    return Some(Err(TransactionError::Invalid(message).into()))
+                }
+                TxStatus::Dropped { message } => {
+                    return Some(Err(TransactionError::Dropped(message).into()))
+                }
+                _ => continue,
             }
-            TxStatus::Error { message } => {
-                return Err(TransactionError::Error(message).into())
+        }
+        None
+    };
+    let wait = async {
+        match watch.await {
+            Some(result) => result,
+            None => {
+                // The subscription to the status of the extrinsic can't be resumed, since
+                // subscribing again would submit the extrinsic a second time.
+                tracing::warn!(
+                    "Lost the connection while watching the extrinsic \
+                    {extrinsic_hash:?}, looking for it in the new blocks instead"
+                );
+                follow_blocks(
+                    client,
+                    rpc,
+                    extrinsic_hash,
+                    best_block,
+                    finalization_timeout.is_some(),
+                )
+                .await
             }
-            TxStatus::Invalid { message } => {
-                return Err(TransactionError::Invalid(message).into())
+        }
+    };
+    let submit_timeout = async {
+        match opts.submit_timeout() {
+            Some(timeout) => {
+                tokio::time::sleep(timeout).await;
+                if included.load(Ordering::Relaxed) {
+                    future::pending::<()>().await
+                }
+                timeout
             }
-            TxStatus::Dropped { message } => {
-                return Err(TransactionError::Dropped(message).into())
+            None => future::pending().await,
+        }
+    };
+    let finalization_timeout = async {
+        match finalization_timeout {
+            Some(timeout) => {
+                tokio::time::sleep(timeout).await;
+                timeout
             }
-            _ => continue,
+            None => future::pending().await,
         }
-    }
-    Err(RpcError::SubscriptionDropped.into())
+    };
+
+    progress.started(Operation::WaitForExtrinsic);
+    let result = tokio::select! {
+        result = wait => result,
+        timeout = submit_timeout => {
+            Err(subxt::Error::Other(format!(
+                "The extrinsic {extrinsic_hash:?} was not included in a block within \
+                {}s, it might still be included later on",
+                timeout.as_secs()
+            )))
+        }
+        timeout = finalization_timeout => {
+            Err(subxt::Error::Other(format!(
+                "The extrinsic {extrinsic_hash:?} was not finalized within {}s",
+                timeout.as_secs()
+            )))
+        }
+    };
+    progress.finished(Operation::WaitForExtrinsic);
+    result
 }
 
-/// Return the account nonce at the *best* block for an account ID.
-async fn get_account_nonce<C>(
+/// Signs the extrinsic of the `call` with the next nonce of the signer and submits it.
+///
+/// Returns the progress of the extrinsic, its hash and the number of the best block it
+/// was signed at.
+async fn sign_and_submit<C, E, Call, Signer>(
     client: &OnlineClient<C>,
-    rpc: &LegacyRpcMethods<C>,
-    account_id: &C::AccountId,
-) -> core::result::Result<u64, subxt::Error>
+    rpc: &NodeRpc<C>,
+    call: &Call,
+    opts: &ExtrinsicOpts<C, E, Signer>,
+) -> core::result::Result<
+    (tx::TxProgress<C, OnlineClient<C>>, C::Hash, u64),
+    subxt::Error,
+>
 where
     C: Config,
+    E: Environment,
+    Call: tx::TxPayload,
+    Signer: tx::Signer<C> + Clone,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
 {
+    let signer = opts.signer();
+    let account_id = Signer::account_id(signer);
+    let account_nonce = opts.nonce_provider().next(rpc, &account_id).await?;
+
     let best_block = rpc
-        .chain_get_block_hash(None)
+        .chain_get_header(None)
         .await?
         .ok_or(subxt::Error::Other("Best block not found".into()))?;
-    let account_nonce = client
-        .blocks()
-        .at(best_block)
-        .await?
-        .account_nonce(account_id)
-        .await?;
-    Ok(account_nonce)
+    let mut params = DefaultExtrinsicParamsBuilder::new()
+        .nonce(account_nonce)
+        .tip(opts.tip());
+    if let Some(mortality) = opts.mortality() {
+        params = params.mortal(&best_block, mortality);
+    }
+    let params = params.build();
+    let extrinsic = client
+        .tx()
+        .create_signed_offline(call, signer, params.into())?;
+    let extrinsic_hash = extrinsic.hash();
+    let tx = extrinsic.submit_and_watch().await?;
+    Ok((tx, extrinsic_hash, best_block.number().into()))
+}
+
+/// Follows the best blocks, or the finalized blocks if `finalized`, until the extrinsic
+/// with the given hash is included in one of them, starting at block number `from`.
+///
+/// This is used once the subscription to the status of a submitted extrinsic is lost.
+/// The blocks are followed through a new subscription, which is re-established if it is
+/// lost as well, and blocks missed in the meantime are fetched one by one.
+async fn follow_blocks<C>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    extrinsic_hash: C::Hash,
+    from: u64,
+    finalized: bool,
+) -> core::result::Result<
+    (blocks::ExtrinsicEvents<C>, ExtrinsicReceipt<C::Hash>),
+    subxt::Error,
+>
+where
+    C: Config,
+{
+    let mut next = from;
+    loop {
+        // Subscriptions are not retried by the RPC client, the request re-establishes the
+        // connection to the node first.
+        rpc.system_health().await?;
+        let mut blocks = if finalized {
+            client.blocks().subscribe_finalized().await?
+        } else {
+            client.blocks().subscribe_best().await?
+        };
+        while let Some(block) = blocks.next().await {
+            let block = match block {
+                Ok(block) => block,
+                Err(subxt::Error::Rpc(err)) => {
+                    tracing::debug!("Following the blocks failed: {err}");
+                    break
+                }
+                Err(err) => return Err(err),
+            };
+            let number: u64 = block.number().into();
+            while next < number {
+                let hash = rpc
+                    .chain_get_block_hash(Some(next.into()))
+                    .await?
+                    .ok_or(subxt::Error::Other(format!("Block {next} not found")))?;
+                let missed = client.blocks().at(hash).await?;
+                let found = find_extrinsic(client, &missed, extrinsic_hash).await?;
+                if let Some(found) = found {
+                    return Ok(found)
+                }
+                next += 1;
+            }
+            if let Some(found) = find_extrinsic(client, &block, extrinsic_hash).await? {
+                return Ok(found)
+            }
+            next = next.max(number + 1);
+        }
+    }
+}
+
+/// Looks for the extrinsic with the given hash in a block, failing if it was included
+/// but failed.
+async fn find_extrinsic<C>(
+    client: &OnlineClient<C>,
+    block: &blocks::Block<C, OnlineClient<C>>,
+    extrinsic_hash: C::Hash,
+) -> core::result::Result<
+    Option<(blocks::ExtrinsicEvents<C>, ExtrinsicReceipt<C::Hash>)>,
+    subxt::Error,
+>
+where
+    C: Config,
+{
+    for extrinsic in block.extrinsics().await?.iter() {
+        let extrinsic = extrinsic?;
+        if C::Hasher::hash(extrinsic.bytes()) != extrinsic_hash {
+            continue
+        }
+        let events = extrinsic.events().await?;
+        for event in events.iter() {
+            let event = event?;
+            if event.pallet_name() == "System"
+                && event.variant_name() == "ExtrinsicFailed"
+            {
+                let dispatch_error =
+                    DispatchError::decode_from(event.field_bytes(), client.metadata())?;
+                return Err(dispatch_error.into())
+            }
+        }
+        let receipt = ExtrinsicReceipt {
+            extrinsic_hash,
+            block_hash: block.hash(),
+            block_number: block.number().into(),
+        };
+        return Ok(Some((events, receipt)))
+    }
+    Ok(None)
 }
 
 async fn state_call<C, A: Encode, R: Decode>(
@@ -301,5 +807,9 @@ mod tests {
         // with default port, domain and path
         let url = url::Url::parse("wss://test.io/test/1").unwrap();
         assert_eq!(url_to_string(&url), "wss://test.io:443/test/1");
+
+        // with http and default port
+        let url = url::Url::parse("https://test.io").unwrap();
+        assert_eq!(url_to_string(&url), "https://test.io:443/");
     }
 }
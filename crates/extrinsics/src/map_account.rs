@@ -0,0 +1,177 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    estimate_fee,
+    get_best_block,
+    submit_extrinsic,
+    ContractsBackend,
+    ErrorVariant,
+    ExtrinsicReceipt,
+};
+use crate::{
+    contracts_backend,
+    eth_address,
+    extrinsic_calls::MapAccount,
+    extrinsic_opts::ExtrinsicOpts,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
+};
+
+use anyhow::Result;
+use ink_env::Environment;
+use subxt::{
+    blocks::ExtrinsicEvents,
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    dynamic::Value,
+    storage::dynamic,
+    tx,
+    Config,
+    OnlineClient,
+};
+
+/// A builder for the map account command.
+pub struct MapAccountCommandBuilder<C: Config, E: Environment, Signer: Clone> {
+    extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
+}
+
+impl<C: Config, E: Environment, Signer> MapAccountCommandBuilder<C, E, Signer>
+where
+    Signer: tx::Signer<C> + Clone,
+{
+    /// Returns a clean builder for [`MapAccountExec`].
+    pub fn new(
+        extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
+    ) -> MapAccountCommandBuilder<C, E, Signer> {
+        MapAccountCommandBuilder { extrinsic_opts }
+    }
+
+    /// Sets up the client for mapping the account of the signer.
+    ///
+    /// Returns the `MapAccountExec`, or an error in case of failure.
+    pub async fn done(self) -> Result<MapAccountExec<C, E, Signer>> {
+        let url = self.extrinsic_opts.url();
+        let rpc_cli = rpc_client(&url, self.extrinsic_opts.rpc_options()).await?;
+        let client =
+            online_client::<C>(rpc_cli.clone(), self.extrinsic_opts.rpc_options())
+                .await?;
+        let backend = contracts_backend(&client.metadata())?;
+        let rpc = NodeRpc::new(rpc_cli);
+
+        Ok(MapAccountExec {
+            opts: self.extrinsic_opts,
+            rpc,
+            client,
+            backend,
+        })
+    }
+}
+
+pub struct MapAccountExec<C: Config, E: Environment, Signer: Clone> {
+    opts: ExtrinsicOpts<C, E, Signer>,
+    rpc: NodeRpc<C>,
+    client: OnlineClient<C>,
+    backend: &'static dyn ContractsBackend,
+}
+
+impl<C: Config, E: Environment, Signer> MapAccountExec<C, E, Signer>
+where
+    C::AccountId: AsRef<[u8]>,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    Signer: tx::Signer<C> + Clone,
+{
+    /// Returns whether the account of the signer can interact with contracts.
+    ///
+    /// This is always the case unless the chain requires accounts to be mapped to an
+    /// address first, like chains running `pallet-revive`.
+    pub async fn is_mapped(&self) -> Result<bool> {
+        if !self.backend.requires_account_mapping() {
+            return Ok(true)
+        }
+        let best_block = get_best_block(&self.rpc).await?;
+        let address = eth_address(self.opts.signer().account_id().as_ref());
+        let original_account = dynamic(
+            self.backend.pallet(),
+            "OriginalAccount",
+            vec![Value::from_bytes(address)],
+        );
+        let account = self
+            .client
+            .storage()
+            .at(best_block)
+            .fetch(&original_account)
+            .await?;
+        Ok(account.is_some())
+    }
+
+    /// Maps the account of the signer to its address by submitting the `map_account`
+    /// extrinsic.
+    ///
+    /// Returns the `MapAccountResult` containing the events of the extrinsic, or an
+    /// error in case of failure.
+    pub async fn map_account(&self) -> Result<MapAccountResult<C>, ErrorVariant> {
+        if !self.backend.requires_account_mapping() {
+            return Err(anyhow::anyhow!(
+                "Accounts don't need to be mapped on chains running the `{}` pallet",
+                self.backend.pallet()
+            )
+            .into())
+        }
+        let call = MapAccount {}.build(self.backend);
+
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
+
+        Ok(MapAccountResult { events, receipt })
+    }
+
+    /// Estimates the fee of mapping the account, excluding the tip.
+    pub async fn estimate_fee(&self) -> Result<u128> {
+        let call = MapAccount {}.build(self.backend);
+        Ok(estimate_fee(&self.client, &call, &self.opts).await?)
+    }
+
+    /// Returns the address the account of the signer is mapped to.
+    pub fn address(&self) -> [u8; 20] {
+        eth_address(self.opts.signer().account_id().as_ref())
+    }
+
+    /// Returns the extrinsic options.
+    pub fn opts(&self) -> &ExtrinsicOpts<C, E, Signer> {
+        &self.opts
+    }
+
+    /// Returns the client.
+    pub fn client(&self) -> &OnlineClient<C> {
+        &self.client
+    }
+
+    /// Returns the pallet implementing smart contracts on the chain.
+    pub fn backend(&self) -> &'static dyn ContractsBackend {
+        self.backend
+    }
+}
+
+/// A struct representing the result of a map account command execution.
+pub struct MapAccountResult<C: Config> {
+    pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+}
@@ -0,0 +1,301 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::RpcOptions;
+use anyhow::Result;
+use scale::{
+    Decode,
+    Encode,
+};
+use std::{
+    env,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        rpc::RpcClient,
+        BackendExt,
+        RuntimeVersion,
+    },
+    error::MetadataError,
+    tx::TxPayload,
+    Config,
+    Metadata,
+    OnlineClient,
+};
+
+/// The version of the runtime metadata fetched after a runtime upgrade, falling back
+/// to the legacy version 14 if the node does not provide it.
+const METADATA_VERSION: u32 = 15;
+
+/// Creates a client for the node behind `rpc`.
+///
+/// Unless disabled in the `options`, the runtime metadata of the node is cached on
+/// disk, keyed by the genesis hash of the chain and the spec version of its runtime.
+/// Fetching it from the node again is only required once its runtime is upgraded, or
+/// if `refresh_metadata` is set in the `options`.
+pub async fn online_client<C: Config>(
+    rpc: RpcClient,
+    options: RpcOptions,
+) -> Result<OnlineClient<C>> {
+    let Some(dir) = options.metadata_cache.then(cache_dir).flatten() else {
+        return Ok(OnlineClient::from_rpc_client(rpc).await?)
+    };
+    let methods = LegacyRpcMethods::<C>::new(rpc.clone());
+    let (genesis_hash, runtime_version) = futures::try_join!(
+        methods.genesis_hash(),
+        methods.state_get_runtime_version(None)
+    )?;
+    let chain = hex::encode(genesis_hash);
+    let cached = (!options.refresh_metadata)
+        .then(|| load(&dir, &chain, runtime_version.spec_version))
+        .flatten();
+    if let Some(metadata) = cached {
+        tracing::debug!(
+            "Using the cached metadata of spec version {}",
+            runtime_version.spec_version
+        );
+        let runtime_version = RuntimeVersion {
+            spec_version: runtime_version.spec_version,
+            transaction_version: runtime_version.transaction_version,
+        };
+        return Ok(OnlineClient::from_rpc_client_with(
+            genesis_hash,
+            runtime_version,
+            metadata,
+            rpc,
+        )?)
+    }
+
+    let client = OnlineClient::<C>::from_rpc_client(rpc).await?;
+    let spec_version = client.runtime_version().spec_version;
+    if let Err(err) = store(&dir, &chain, spec_version, &client.metadata()) {
+        tracing::debug!("Caching the metadata failed: {err}");
+    }
+    Ok(client)
+}
+
+/// An upgrade of the runtime of a node, see [`refresh_on_runtime_upgrade`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RuntimeUpgrade {
+    /// The spec version of the runtime the client was created with.
+    pub from: u32,
+    /// The spec version of the current runtime of the node.
+    pub to: u32,
+}
+
+impl RuntimeUpgrade {
+    /// Returns the upgrade from the spec version `from` to `to`, `None` if they are
+    /// equal.
+    pub fn detect(from: u32, to: u32) -> Option<Self> {
+        (from != to).then_some(Self { from, to })
+    }
+
+    /// Checks that the `call` is still valid with the `metadata` of the upgraded runtime.
+    ///
+    /// Only the encoding of the call is re-validated: it fails if its pallet or call were
+    /// removed, or if their signature changed. The call is not dry-run again, so changes
+    /// to its weight or storage deposit are not detected.
+    pub fn revalidate<Call: TxPayload>(
+        &self,
+        call: &Call,
+        metadata: &Metadata,
+    ) -> Result<(), subxt::Error> {
+        let validate = || {
+            if let Some(details) = call.validation_details() {
+                let hash = metadata
+                    .pallet_by_name_err(details.pallet_name)?
+                    .call_hash(details.call_name)
+                    .ok_or_else(|| {
+                        MetadataError::CallNameNotFound(details.call_name.to_owned())
+                    })?;
+                if details.hash != hash {
+                    return Err(MetadataError::IncompatibleCodegen.into())
+                }
+            }
+            call.encode_call_data(metadata).map(|_| ())
+        };
+        validate().map_err(|err: subxt::Error| {
+            subxt::Error::Other(format!(
+                "The extrinsic is invalid after the runtime upgrade to spec version {}, \
+                it was not submitted: {err}",
+                self.to
+            ))
+        })
+    }
+}
+
+/// Updates the runtime metadata and version of the `client` if the runtime of the node
+/// was upgraded since they were fetched, e.g. between the dry-run and the submission of
+/// an extrinsic.
+///
+/// This costs a single request for the runtime version of the best block, the metadata
+/// is only fetched if the runtime was upgraded.
+///
+/// Returns the upgrade, `None` if the spec version of the runtime did not change.
+pub async fn refresh_on_runtime_upgrade<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+) -> Result<Option<RuntimeUpgrade>, subxt::Error> {
+    let runtime_version = rpc.state_get_runtime_version(None).await?;
+    let Some(upgrade) = RuntimeUpgrade::detect(
+        client.runtime_version().spec_version,
+        runtime_version.spec_version,
+    ) else {
+        return Ok(None)
+    };
+    let best_block = rpc
+        .chain_get_block_hash(None)
+        .await?
+        .ok_or(subxt::Error::Other("Best block not found".into()))?;
+    let metadata = match client
+        .backend()
+        .metadata_at_version(METADATA_VERSION, best_block)
+        .await
+    {
+        Ok(metadata) => metadata,
+        Err(_) => client.backend().legacy_metadata(best_block).await?,
+    };
+    client.set_metadata(metadata);
+    client.set_runtime_version(RuntimeVersion {
+        spec_version: runtime_version.spec_version,
+        transaction_version: runtime_version.transaction_version,
+    });
+    Ok(Some(upgrade))
+}
+
+/// Returns the directory of the cached metadata, `None` if the home directory is
+/// unknown.
+fn cache_dir() -> Option<PathBuf> {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("cargo-contract").join("metadata"))
+}
+
+fn file_name(chain: &str, spec_version: u32) -> String {
+    format!("{chain}-{spec_version}.scale")
+}
+
+/// Loads the cached metadata of the `spec_version` of the `chain`, if present and valid.
+fn load(dir: &Path, chain: &str, spec_version: u32) -> Option<Metadata> {
+    let bytes = fs::read(dir.join(file_name(chain, spec_version))).ok()?;
+    Metadata::decode(&mut &bytes[..])
+        .map_err(|err| tracing::debug!("Ignoring the invalid cached metadata: {err}"))
+        .ok()
+}
+
+/// Caches the metadata of the `spec_version` of the `chain`, replacing the metadata of
+/// its previous versions.
+fn store(dir: &Path, chain: &str, spec_version: u32, metadata: &Metadata) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let file_name = file_name(chain, spec_version);
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let outdated =
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(&format!("{chain}-")) && name != file_name
+                });
+        if outdated {
+            let _ = fs::remove_file(path);
+        }
+    }
+    // concurrent invocations must never read a partially written file
+    let partial = dir.join(format!("{file_name}.{}", std::process::id()));
+    fs::write(&partial, (**metadata).encode())?;
+    fs::rename(partial, dir.join(file_name))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> Metadata {
+        let bytes = fs::read("src/test_runtime_api/metadata_v15.scale").unwrap();
+        Metadata::decode(&mut &bytes[..]).unwrap()
+    }
+
+    #[test]
+    fn cached_metadata_is_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = metadata();
+
+        assert!(load(dir.path(), "aa", 1).is_none());
+        store(dir.path(), "aa", 1, &metadata).unwrap();
+
+        let cached = load(dir.path(), "aa", 1).unwrap();
+        assert_eq!((*cached).encode(), (*metadata).encode());
+        assert!(load(dir.path(), "aa", 2).is_none());
+        assert!(load(dir.path(), "bb", 1).is_none());
+    }
+
+    #[test]
+    fn new_spec_version_replaces_cached_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = metadata();
+
+        store(dir.path(), "aa", 1, &metadata).unwrap();
+        store(dir.path(), "bb", 1, &metadata).unwrap();
+        store(dir.path(), "aa", 2, &metadata).unwrap();
+
+        assert!(load(dir.path(), "aa", 1).is_none());
+        assert!(load(dir.path(), "aa", 2).is_some());
+        assert!(load(dir.path(), "bb", 1).is_some());
+    }
+
+    #[test]
+    fn unchanged_spec_version_is_no_upgrade() {
+        assert_eq!(RuntimeUpgrade::detect(1, 1), None);
+        assert_eq!(
+            RuntimeUpgrade::detect(1, 2),
+            Some(RuntimeUpgrade { from: 1, to: 2 })
+        );
+    }
+
+    #[test]
+    fn call_is_revalidated_with_upgraded_metadata() {
+        // spec version 2 removed `pallet-contracts` and added `frame-system`
+        let upgrade = RuntimeUpgrade::detect(1, 2).unwrap();
+        let old = metadata();
+        let new = crate::test_metadata::metadata();
+        let remark = subxt::dynamic::tx(
+            "System",
+            "remark",
+            vec![subxt::dynamic::Value::from_bytes([1])],
+        );
+        let remove_code = subxt::dynamic::tx(
+            "Contracts",
+            "remove_code",
+            vec![subxt::dynamic::Value::from_bytes([0; 32])],
+        );
+
+        assert!(upgrade.revalidate(&remove_code, &old).is_ok());
+        assert!(upgrade.revalidate(&remark, &old).is_err());
+        assert!(upgrade.revalidate(&remark, &new).is_ok());
+        let err = upgrade.revalidate(&remove_code, &new).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("invalid after the runtime upgrade to spec version 2"));
+    }
+}
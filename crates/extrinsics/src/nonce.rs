@@ -0,0 +1,166 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::rpc_client::NodeRpc;
+use std::sync::{
+    Arc,
+    Mutex,
+};
+use subxt::Config;
+
+/// Provides the nonces of the extrinsics submitted for an account.
+///
+/// By default the nonce is queried from the node for every extrinsic. Extrinsics
+/// sent in quick succession, e.g. for batch deployments, would then reuse the nonce of a
+/// pending extrinsic and be rejected with `Priority is too low`. A tracking provider
+/// instead queries the nonce only once and increments it locally for every further
+/// extrinsic. The tracked nonce is shared between clones of the provider.
+#[derive(Clone, Debug, Default)]
+pub struct NonceProvider {
+    /// The nonce of the next extrinsic if tracked locally, `None` inside the mutex until
+    /// it was queried from the chain.
+    tracked: Option<Arc<Mutex<Option<u64>>>>,
+    /// Whether the nonce was set explicitly with [`NonceProvider::starting_at`].
+    explicit: bool,
+}
+
+impl NonceProvider {
+    /// Queries the nonce from the node for every extrinsic.
+    pub fn best_block() -> Self {
+        Self::default()
+    }
+
+    /// Queries the nonce from the node for the first extrinsic, and increments it
+    /// locally for every further extrinsic.
+    pub fn tracked() -> Self {
+        Self {
+            tracked: Some(Arc::new(Mutex::new(None))),
+            explicit: false,
+        }
+    }
+
+    /// Uses `nonce` for the first extrinsic, and increments it locally for every
+    /// further extrinsic.
+    ///
+    /// An extrinsic rejected because of its nonce is not resubmitted with a refetched
+    /// nonce, since that would override the explicit one.
+    pub fn starting_at(nonce: u64) -> Self {
+        Self {
+            tracked: Some(Arc::new(Mutex::new(Some(nonce)))),
+            explicit: true,
+        }
+    }
+
+    /// Returns a provider tracking the nonce locally, e.g. for several extrinsics
+    /// submitted in sequence. A tracked or starting nonce is kept.
+    pub fn tracking(self) -> Self {
+        if self.tracked.is_some() {
+            return self
+        }
+        Self::tracked()
+    }
+
+    /// Returns the nonce for the next extrinsic of `account_id`.
+    ///
+    /// A tracked nonce is incremented even if the extrinsic is not submitted
+    /// successfully afterwards.
+    pub(crate) async fn next<C: Config>(
+        &self,
+        rpc: &NodeRpc<C>,
+        account_id: &C::AccountId,
+    ) -> Result<u64, subxt::Error> {
+        let Some(tracked) = &self.tracked else {
+            return rpc.account_next_index(account_id).await
+        };
+        if let Some(nonce) = self.next_tracked() {
+            return Ok(nonce)
+        }
+        let queried = rpc.account_next_index(account_id).await?;
+        let mut next = tracked.lock().expect("nonce lock poisoned");
+        // Another clone may have queried the nonce in the meantime.
+        let nonce = next.unwrap_or(queried);
+        *next = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    /// Returns whether an extrinsic rejected because of its nonce may be resubmitted
+    /// with a nonce refetched from the node, which is not the case for an explicit one.
+    pub(crate) fn is_refetchable(&self) -> bool {
+        !self.explicit
+    }
+
+    /// Forgets the tracked nonce, so that it is queried from the chain again for the
+    /// next extrinsic, e.g. after an extrinsic was rejected because of its nonce.
+    pub(crate) fn reset(&self) {
+        if let Some(tracked) = &self.tracked {
+            *tracked.lock().expect("nonce lock poisoned") = None;
+        }
+    }
+
+    /// Returns and increments the tracked nonce, `None` if it is not known yet.
+    fn next_tracked(&self) -> Option<u64> {
+        let mut next = self.tracked.as_ref()?.lock().expect("nonce lock poisoned");
+        let nonce = (*next)?;
+        *next = Some(nonce + 1);
+        Some(nonce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starting_nonce_is_incremented_across_clones() {
+        let provider = NonceProvider::starting_at(5);
+        let clone = provider.clone();
+
+        assert_eq!(provider.next_tracked(), Some(5));
+        assert_eq!(clone.next_tracked(), Some(6));
+        assert_eq!(provider.next_tracked(), Some(7));
+    }
+
+    #[test]
+    fn reset_nonce_is_not_known() {
+        let provider = NonceProvider::starting_at(5);
+        provider.reset();
+
+        assert_eq!(provider.clone().next_tracked(), None);
+    }
+
+    #[test]
+    fn tracking_keeps_starting_nonce() {
+        let provider = NonceProvider::starting_at(5).tracking();
+
+        assert_eq!(provider.next_tracked(), Some(5));
+        assert_eq!(provider.tracking().next_tracked(), Some(6));
+        assert!(NonceProvider::best_block().tracking().tracked.is_some());
+    }
+
+    #[test]
+    fn explicit_nonce_is_not_refetched() {
+        assert!(!NonceProvider::starting_at(5).is_refetchable());
+        assert!(!NonceProvider::starting_at(5).tracking().is_refetchable());
+        assert!(NonceProvider::tracked().is_refetchable());
+        assert!(NonceProvider::best_block().tracking().is_refetchable());
+    }
+
+    #[test]
+    fn untracked_nonce_is_not_known() {
+        assert_eq!(NonceProvider::best_block().next_tracked(), None);
+        assert_eq!(NonceProvider::tracked().next_tracked(), None);
+    }
+}
@@ -80,6 +80,32 @@ pub struct ContractResult<R, Balance> {
     pub result: R,
 }
 
+impl<AccountId, Balance> ContractInstantiateResult<AccountId, Balance> {
+    /// Converts the address of the instantiated contract with `f`, e.g. the `H160`
+    /// returned by `ReviveApi::instantiate` to the account of the contract.
+    pub fn try_map_account<T, E>(
+        self,
+        f: impl FnOnce(AccountId) -> Result<T, E>,
+    ) -> Result<ContractInstantiateResult<T, Balance>, E> {
+        let result = match self.result {
+            Ok(value) => {
+                Ok(InstantiateReturnValue {
+                    result: value.result,
+                    account_id: f(value.account_id)?,
+                })
+            }
+            Err(err) => Err(err),
+        };
+        Ok(ContractResult {
+            gas_consumed: self.gas_consumed,
+            gas_required: self.gas_required,
+            storage_deposit: self.storage_deposit,
+            debug_message: self.debug_message,
+            result,
+        })
+    }
+}
+
 /// Result type of a `bare_call` call as well as `ContractsApi::call`.
 pub type ContractExecResult<Balance> =
     ContractResult<Result<ExecReturnValue, DispatchError>, Balance>;
@@ -129,7 +155,7 @@ impl ExecReturnValue {
 pub struct InstantiateReturnValue<AccountId> {
     /// The output of the called constructor.
     pub result: ExecReturnValue,
-    /// The account id of the new contract.
+    /// The account id of the new contract, its `H160` address for `pallet-revive`.
     pub account_id: AccountId,
 }
 
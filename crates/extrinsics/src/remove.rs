@@ -15,23 +15,28 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
-    events::CodeRemoved,
+    events::{
+        find_contracts_events,
+        CodeRemoved,
+    },
     submit_extrinsic,
     ContractMessageTranscoder,
+    ContractsBackend,
     ErrorVariant,
+    ExtrinsicReceipt,
 };
 use crate::{
+    contracts_backend,
     extrinsic_calls::RemoveCode,
     extrinsic_opts::ExtrinsicOpts,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
 };
 
 use anyhow::Result;
 use ink_env::Environment;
 use subxt::{
-    backend::{
-        legacy::LegacyRpcMethods,
-        rpc::RpcClient,
-    },
     blocks::ExtrinsicEvents,
     config::{
         DefaultExtrinsicParams,
@@ -85,37 +90,44 @@ where
     /// This function prepares the necessary data for removing contract code based on the
     /// provided contract artifacts and options. It ensures that the required code hash is
     /// available and sets up the client, signer, and other relevant parameters for the
-    /// contract code removal operation.
+    /// contract code removal operation. No artifacts are loaded if the code hash is
+    /// given.
     ///
     /// Returns the `RemoveExec` containing the preprocessed data for the contract code
     /// removal, or an error in case of failure.
     pub async fn done(self) -> Result<RemoveExec<C, E, Signer>> {
-        let artifacts = self.extrinsic_opts.contract_artifacts()?;
-        let transcoder = artifacts.contract_transcoder()?;
-
-        let artifacts_path = artifacts.artifact_path().to_path_buf();
-
-        let final_code_hash = match (self.code_hash.as_ref(), artifacts.code.as_ref()) {
-            (Some(code_h), _) => Ok(*code_h),
-            (None, Some(_)) => artifacts.code_hash().map(|h| h.into() ),
-            (None, None) => Err(anyhow::anyhow!(
-                "No code_hash was provided or contract code was not found from artifact \
-                file {}. Please provide a code hash with --code-hash argument or specify the \
-                path for artifacts files with --manifest-path",
-                artifacts_path.display()
-            )),
-        }?;
+        let (final_code_hash, transcoder) = match self.code_hash {
+            Some(code_hash) => (code_hash, None),
+            None => {
+                let artifacts = self.extrinsic_opts.contract_artifacts()?;
+                if artifacts.code.is_none() {
+                    anyhow::bail!(
+                        "No code_hash was provided or contract code was not found from \
+                        artifact file {}. Please provide a code hash with --code-hash \
+                        argument or specify the path for artifacts files with \
+                        --manifest-path",
+                        artifacts.artifact_path().display()
+                    )
+                }
+                // the transcoder is only used to decode the events
+                (artifacts.code_hash()?.into(), artifacts.contract_transcoder().ok())
+            }
+        };
 
         let url = self.extrinsic_opts.url();
-        let rpc_cli = RpcClient::from_url(&url).await?;
-        let client = OnlineClient::<C>::from_rpc_client(rpc_cli.clone()).await?;
-        let rpc = LegacyRpcMethods::<C>::new(rpc_cli);
+        let rpc_cli = rpc_client(&url, self.extrinsic_opts.rpc_options()).await?;
+        let client =
+            online_client::<C>(rpc_cli.clone(), self.extrinsic_opts.rpc_options())
+                .await?;
+        let backend = contracts_backend(&client.metadata())?;
+        let rpc = NodeRpc::new(rpc_cli);
 
         Ok(RemoveExec {
             final_code_hash,
             opts: self.extrinsic_opts,
             rpc,
             client,
+            backend,
             transcoder,
         })
     }
@@ -124,9 +136,10 @@ where
 pub struct RemoveExec<C: Config, E: Environment, Signer: Clone> {
     final_code_hash: C::Hash,
     opts: ExtrinsicOpts<C, E, Signer>,
-    rpc: LegacyRpcMethods<C>,
+    rpc: NodeRpc<C>,
     client: OnlineClient<C>,
-    transcoder: ContractMessageTranscoder,
+    backend: &'static dyn ContractsBackend,
+    transcoder: Option<ContractMessageTranscoder>,
 }
 
 impl<C: Config, E: Environment, Signer> RemoveExec<C, E, Signer>
@@ -152,16 +165,21 @@ where
     {
         let code_hash = self.final_code_hash;
 
-        let call = RemoveCode::new(code_hash).build();
+        let call = RemoveCode::new(code_hash).build(self.backend);
 
-        let events =
-            submit_extrinsic(&self.client, &self.rpc, &call, self.opts.signer()).await?;
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
 
-        let code_removed =
-            events.find_first::<CodeRemoved<C::Hash, C::AccountId, E::Balance>>()?;
+        let code_removed = find_contracts_events::<
+            _,
+            CodeRemoved<C::Hash, C::AccountId, E::Balance>,
+        >(&events, self.backend, &self.client.metadata())
+        .next()
+        .transpose()?;
         Ok(RemoveResult {
             code_removed,
             events,
+            receipt,
         })
     }
 
@@ -180,9 +198,14 @@ where
         &self.client
     }
 
-    /// Returns the contract message transcoder.
-    pub fn transcoder(&self) -> &ContractMessageTranscoder {
-        &self.transcoder
+    /// Returns the pallet implementing smart contracts on the chain.
+    pub fn backend(&self) -> &'static dyn ContractsBackend {
+        self.backend
+    }
+
+    /// Returns the contract message transcoder, if the contract metadata was loaded.
+    pub fn transcoder(&self) -> Option<&ContractMessageTranscoder> {
+        self.transcoder.as_ref()
     }
 }
 
@@ -190,4 +213,5 @@ where
 pub struct RemoveResult<C: Config, E: Environment> {
     pub code_removed: Option<CodeRemoved<C::Hash, C::AccountId, E::Balance>>,
     pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
 }
@@ -20,7 +20,7 @@ use contract_transcode::AccountId32;
 use subxt::{
     backend::rpc::{
         RawValue,
-        RpcClient,
+        RpcClientT,
         RpcParams,
     },
     ext::scale_value::{
@@ -32,7 +32,11 @@ use subxt::{
     },
 };
 
-use crate::url_to_string;
+use crate::{
+    url_to_string,
+    RetryingRpcClient,
+    RpcOptions,
+};
 use anyhow::{
     anyhow,
     bail,
@@ -75,12 +79,18 @@ impl RawParams {
     }
 }
 
-pub struct RpcRequest(RpcClient);
+pub struct RpcRequest(RetryingRpcClient);
 
 impl RpcRequest {
     /// Creates a new `RpcRequest` instance.
     pub async fn new(url: &url::Url) -> Result<Self> {
-        let rpc = RpcClient::from_url(url_to_string(url)).await?;
+        Self::with_options(url, RpcOptions::default()).await
+    }
+
+    /// Creates a new `RpcRequest` instance which retries and times out requests
+    /// according to `options`.
+    pub async fn with_options(url: &url::Url, options: RpcOptions) -> Result<Self> {
+        let rpc = RetryingRpcClient::new(&url_to_string(url), options).await?;
         Ok(Self(rpc))
     }
 
@@ -105,6 +115,33 @@ impl RpcRequest {
             .map_err(|e| anyhow!("Raw RPC call failed: {e}"))
     }
 
+    /// Performs multiple raw RPC calls in a single round-trip to the node.
+    /// Returns a `Result` containing the raw results in the order of the calls, or an
+    /// error if any of the calls fails.
+    pub async fn batch_call(
+        &self,
+        calls: Vec<(&str, RawParams)>,
+    ) -> Result<Vec<Box<RawValue>>> {
+        let methods = self.get_supported_methods().await?;
+        if let Some((method, _)) = calls
+            .iter()
+            .find(|(method, _)| !methods.iter().any(|e| e == method))
+        {
+            bail!(
+                "Method '{method}' not found, supported methods: {}",
+                methods.join(", ")
+            );
+        }
+        let calls = calls
+            .into_iter()
+            .map(|(method, params)| (method, params.0))
+            .collect::<Vec<_>>();
+        self.0
+            .batch_request(&calls)
+            .await
+            .map_err(|e| anyhow!("Batch RPC call failed: {e}"))
+    }
+
     /// Retrieves the supported RPC methods.
     /// Returns a `Result` containing a vector of supported RPC methods or an error if the
     /// call fails.
@@ -0,0 +1,729 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    future::Future,
+    ops::Deref,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use futures::{
+    StreamExt,
+    TryStreamExt,
+};
+use jsonrpsee::{
+    client_transport::ws::WsTransportClientBuilder,
+    core::{
+        client::{
+            async_client::PingConfig,
+            BatchResponse,
+            Client,
+            ClientT,
+            Error,
+            SubscriptionClientT,
+            SubscriptionKind,
+        },
+        params::BatchRequestBuilder,
+        traits::ToRpcParams,
+    },
+    http_client::{
+        HttpClient,
+        HttpClientBuilder,
+    },
+    types::SubscriptionId,
+};
+use scale::Encode;
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        rpc::{
+            rpc_params,
+            RawRpcFuture,
+            RawRpcSubscription,
+            RawValue,
+            RpcClient,
+            RpcClientT,
+        },
+    },
+    error::RpcError,
+    Config,
+};
+
+/// Default timeout for a single RPC request, including all of its retries.
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(60);
+/// Default number of times a request failing with a transient error is retried.
+pub const DEFAULT_RPC_RETRIES: u32 = 3;
+
+/// Prefixes of the RPC methods which only query the node, and can hence be repeated.
+///
+/// Other methods, like `author_submitExtrinsic`, are never retried: the failed attempt
+/// might still have reached the node, repeating it could e.g. execute an extrinsic twice.
+const IDEMPOTENT_METHODS: &[&str] = &[
+    "archive_",
+    "chain_",
+    "chainSpec_",
+    "childstate_",
+    "payment_",
+    "rpc_methods",
+    "state_",
+    "system_",
+];
+/// Lower bound of the timeout of a single attempt of a request, so that many retries
+/// don't leave too little time for slow requests like `state_getMetadata`.
+const MIN_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Delay before the first retry, doubled with every further attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+/// Upper bound of the delay between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+/// Interval of the websocket pings to the node. A connection which doesn't answer
+/// within [`HEARTBEAT_TIMEOUT`] is closed, ending its subscriptions instead of leaving
+/// them hanging.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// Time after which a connection without any message from the node is considered dead.
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Options for the communication with the RPC endpoint of a node.
+///
+/// Constructed with [`RpcOptions::default`] and the `with_*` methods of its fields.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct RpcOptions {
+    /// Overall timeout of a request, including all of its retries.
+    pub timeout: Duration,
+    /// How often a request failing with a transient error is retried.
+    ///
+    /// Only requests which merely query the node are retried, submitting an extrinsic
+    /// is never repeated.
+    pub retries: u32,
+    /// Connect through a running [daemon](crate::daemon) if there is one, instead of
+    /// connecting to the node directly. Disabled by default.
+    pub daemon: bool,
+    /// Cache the runtime metadata of the node on disk, see [`online_client`].
+    ///
+    /// [`online_client`]: crate::online_client
+    pub metadata_cache: bool,
+    /// Fetch the runtime metadata from the node even if it is cached, replacing the
+    /// cached metadata.
+    pub refresh_metadata: bool,
+    /// Further endpoints of nodes of the same chain, which are failed over to in order
+    /// if the connection to a node fails.
+    ///
+    /// Requests are only sent to one node at a time, they are not raced across the
+    /// endpoints.
+    pub fallback_urls: Vec<String>,
+}
+
+impl RpcOptions {
+    /// Sets the overall timeout of a request, including all of its retries.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how often a request failing with a transient error is retried.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets whether to connect through a running [daemon](crate::daemon).
+    pub fn with_daemon(mut self, daemon: bool) -> Self {
+        self.daemon = daemon;
+        self
+    }
+
+    /// Sets whether to cache the runtime metadata of the node on disk.
+    pub fn with_metadata_cache(mut self, metadata_cache: bool) -> Self {
+        self.metadata_cache = metadata_cache;
+        self
+    }
+
+    /// Sets whether to replace the cached runtime metadata of the node.
+    pub fn with_refresh_metadata(mut self, refresh_metadata: bool) -> Self {
+        self.refresh_metadata = refresh_metadata;
+        self
+    }
+
+    /// Sets the further endpoints of nodes of the same chain to fail over to.
+    pub fn with_fallback_urls(mut self, fallback_urls: Vec<String>) -> Self {
+        self.fallback_urls = fallback_urls;
+        self
+    }
+}
+
+impl Default for RpcOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_RPC_TIMEOUT,
+            retries: DEFAULT_RPC_RETRIES,
+            daemon: false,
+            metadata_cache: true,
+            refresh_metadata: false,
+            fallback_urls: Vec::new(),
+        }
+    }
+}
+
+/// Creates an [`RpcClient`] for the node at `url`, which retries and times out requests
+/// according to `options`.
+///
+/// If the node can't be connected to, or the connection fails later on, the client
+/// fails over to the [`RpcOptions::fallback_urls`].
+///
+/// Both websocket (`ws://`, `wss://`) and HTTP (`http://`, `https://`) endpoints are
+/// supported. HTTP endpoints can only be used for read-only operations like dry-runs
+/// and queries, since watching the status of a submitted extrinsic requires a
+/// subscription.
+///
+/// Errors if an insecure URL is provided for a remote node.
+pub async fn rpc_client(url: &str, options: RpcOptions) -> Result<RpcClient> {
+    let client = RetryingRpcClient::new(url, options).await?;
+    Ok(RpcClient::new(client))
+}
+
+/// The legacy RPC methods of a node, together with the client to call the methods which
+/// are not available for every [`Config`].
+pub(crate) struct NodeRpc<C: Config> {
+    methods: LegacyRpcMethods<C>,
+    client: RpcClient,
+}
+
+impl<C: Config> NodeRpc<C> {
+    pub fn new(client: RpcClient) -> Self {
+        Self {
+            methods: LegacyRpcMethods::new(client.clone()),
+            client,
+        }
+    }
+
+    /// Returns the nonce of the next extrinsic of `account_id`, taking its extrinsics
+    /// pending in the transaction pool of the node into account.
+    pub async fn account_next_index(
+        &self,
+        account_id: &C::AccountId,
+    ) -> Result<u64, subxt::Error> {
+        self.client
+            .request(
+                "system_accountNextIndex",
+                rpc_params![rpc_account(&account_id.encode())],
+            )
+            .await
+    }
+}
+
+impl<C: Config> Deref for NodeRpc<C> {
+    type Target = LegacyRpcMethods<C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.methods
+    }
+}
+
+/// Returns the SCALE `encoded` account in the format the node deserializes it from, an
+/// SS58 address for 32 byte accounts and a hex string otherwise, e.g. for Ethereum
+/// style addresses.
+fn rpc_account(encoded: &[u8]) -> String {
+    match <[u8; 32]>::try_from(encoded) {
+        Ok(bytes) => {
+            contract_transcode::AccountId32(bytes)
+                .to_ss58check_with_prefix(contract_transcode::SUBSTRATE_SS58_PREFIX)
+        }
+        Err(_) => format!("0x{}", hex::encode(encoded)),
+    }
+}
+
+/// Fails unless extrinsics can be submitted through the node at `url`.
+///
+/// Watching the status of a submitted extrinsic requires a subscription, which is only
+/// supported by websocket endpoints. Checking this before connecting avoids failing only
+/// after the dry-run, when the extrinsic is submitted.
+pub fn ensure_can_submit(url: &url::Url) -> Result<()> {
+    match url.scheme() {
+        "ws" | "wss" => Ok(()),
+        scheme => {
+            anyhow::bail!(
+                "Submitting extrinsics requires a websocket url (`ws://` or `wss://`), \
+                the `{scheme}` url {url} only supports read-only operations like dry-runs"
+            )
+        }
+    }
+}
+
+/// An RPC client which retries requests failing with transient errors using an
+/// exponential backoff, and re-establishes the connection if it was dropped, failing
+/// over to the next endpoint of its [`EndpointPool`].
+pub struct RetryingRpcClient {
+    options: RpcOptions,
+    endpoints: EndpointPool,
+}
+
+impl RetryingRpcClient {
+    /// Connects to the node at `url`, or to the first reachable one of the
+    /// [`RpcOptions::fallback_urls`] if it can't be connected to.
+    pub async fn new(url: &str, options: RpcOptions) -> Result<Self> {
+        let urls = std::iter::once(url.to_string())
+            .chain(options.fallback_urls.iter().cloned())
+            .collect::<Vec<_>>();
+        for url in &urls {
+            subxt::utils::validate_url_is_secure(url)?;
+        }
+        let endpoints = EndpointPool::connect(urls, &options).await?;
+        Ok(Self { options, endpoints })
+    }
+
+    /// Returns the URL of the node currently connected to.
+    pub fn url(&self) -> String {
+        self.endpoints.current().0
+    }
+
+    /// Performs all `calls` in a single JSON-RPC batch request.
+    ///
+    /// The results are returned in the same order as the calls. Fails if any of the
+    /// calls failed.
+    pub async fn batch_request(
+        &self,
+        calls: &[(&str, Option<Box<RawValue>>)],
+    ) -> Result<Vec<Box<RawValue>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new())
+        }
+        let idempotent = calls.iter().all(|(method, _)| is_idempotent(method));
+        let response = self
+            .with_retries(idempotent, |client| {
+                async move {
+                    let mut batch = BatchRequestBuilder::new();
+                    for (method, params) in calls {
+                        batch.insert(method, Params(params.clone()))?;
+                    }
+                    client.batch_request(batch).await
+                }
+            })
+            .await?;
+        response
+            .into_iter()
+            .zip(calls)
+            .map(|(res, (method, _))| {
+                res.map_err(|e| anyhow!("Rpc call '{method}' failed: {e}"))
+            })
+            .collect()
+    }
+
+    /// Performs a request of `method`, retrying it on transient errors if the method is
+    /// idempotent.
+    pub(crate) async fn request(
+        &self,
+        method: &str,
+        params: Option<Box<RawValue>>,
+    ) -> Result<Box<RawValue>, Error> {
+        self.with_retries(is_idempotent(method), |client| {
+            let params = params.clone();
+            async move { client.request(method, params).await }
+        })
+        .await
+    }
+
+    /// Runs `request` against the current connection. If `retry` is set, it is retried
+    /// on transient errors until the retries are exhausted or the overall timeout is hit.
+    ///
+    /// Every attempt gets an equal share of the overall timeout, so that a timed out
+    /// attempt leaves time for the retries.
+    async fn with_retries<'a, T, F, Fut>(
+        &'a self,
+        retry: bool,
+        request: F,
+    ) -> Result<T, Error>
+    where
+        F: Fn(Arc<Connection>) -> Fut + 'a,
+        Fut: Future<Output = Result<T, Error>> + 'a,
+    {
+        let retries = if retry { self.options.retries } else { 0 };
+        let attempt_timeout = attempt_timeout(self.options.timeout, retries);
+        let attempts = async {
+            let mut attempt = 0;
+            loop {
+                let res = tokio::time::timeout(attempt_timeout, request(self.client()))
+                    .await
+                    .unwrap_or(Err(Error::RequestTimeout));
+                match res {
+                    Err(err) if is_transient(&err) && attempt < retries => {
+                        tracing::debug!("RPC request failed, retrying: {err}");
+                        tokio::time::sleep(backoff(attempt)).await;
+                        attempt += 1;
+                        if is_connection_error(&err) {
+                            self.endpoints.failover(&self.options).await;
+                        }
+                    }
+                    res => return res,
+                }
+            }
+        };
+        tokio::time::timeout(self.options.timeout, attempts)
+            .await
+            .map_err(|_| Error::RequestTimeout)?
+    }
+
+    fn client(&self) -> Arc<Connection> {
+        self.endpoints.current().1
+    }
+}
+
+impl RpcClientT for RetryingRpcClient {
+    fn request_raw<'a>(
+        &'a self,
+        method: &'a str,
+        params: Option<Box<RawValue>>,
+    ) -> RawRpcFuture<'a, Box<RawValue>> {
+        Box::pin(async move {
+            self.request(method, params)
+                .await
+                .map_err(|e| RpcError::ClientError(Box::new(e)))
+        })
+    }
+
+    fn subscribe_raw<'a>(
+        &'a self,
+        sub: &'a str,
+        params: Option<Box<RawValue>>,
+        unsub: &'a str,
+    ) -> RawRpcFuture<'a, RawRpcSubscription> {
+        // Subscriptions are not retried: re-subscribing e.g. to the status of a submitted
+        // extrinsic would submit it a second time.
+        Box::pin(async move {
+            let (url, connection) = self.endpoints.current();
+            let Connection::Ws(client) = &*connection else {
+                return Err(RpcError::ClientError(
+                    anyhow!(
+                        "The node at {} was connected to via HTTP, which does not \
+                        support subscriptions. Use a `ws://` or `wss://` URL to submit \
+                        extrinsics.",
+                        url
+                    )
+                    .into(),
+                ))
+            };
+            let subscribe =
+                client.subscribe::<Box<RawValue>, _>(sub, Params(params), unsub);
+            let stream = tokio::time::timeout(self.options.timeout, subscribe)
+                .await
+                .map_err(|_| Error::RequestTimeout)
+                .and_then(|res| res)
+                .map_err(|e| RpcError::ClientError(Box::new(e)))?;
+
+            let id = match stream.kind() {
+                SubscriptionKind::Subscription(SubscriptionId::Str(id)) => {
+                    Some(id.clone().into_owned())
+                }
+                _ => None,
+            };
+            let stream = stream
+                .map_err(|e| RpcError::ClientError(Box::new(e)))
+                .boxed();
+            Ok(RawRpcSubscription { stream, id })
+        })
+    }
+}
+
+/// The endpoints of the nodes of a chain, with the connection to the one currently
+/// used.
+struct EndpointPool {
+    urls: Vec<String>,
+    /// The index of the endpoint currently connected to, and its connection.
+    current: Mutex<(usize, Arc<Connection>)>,
+}
+
+impl EndpointPool {
+    /// Connects to the first reachable one of the `urls`.
+    async fn connect(urls: Vec<String>, options: &RpcOptions) -> Result<Self> {
+        let mut last_err = None;
+        for (index, url) in urls.iter().enumerate() {
+            match connect(url, options).await {
+                Ok(connection) => {
+                    return Ok(Self {
+                        urls,
+                        current: Mutex::new((index, Arc::new(connection))),
+                    })
+                }
+                Err(err) => {
+                    tracing::debug!("Connecting to {url} failed: {err}");
+                    last_err = Some(err);
+                }
+            }
+        }
+        let err = last_err.expect("the pool has at least one endpoint");
+        Err(err).with_context(|| {
+            format!("Failed to connect to node at {}", urls.join(" or "))
+        })
+    }
+
+    /// Returns the URL of the endpoint currently connected to, and its connection.
+    fn current(&self) -> (String, Arc<Connection>) {
+        let (index, connection) = &*self.current.lock().expect("pool lock poisoned");
+        (self.urls[*index].clone(), connection.clone())
+    }
+
+    /// Replaces a failed connection with one to the next reachable endpoint, trying the
+    /// endpoint which failed last. Failures are only logged, the next attempt will
+    /// then fail on the old connection.
+    async fn failover(&self, options: &RpcOptions) {
+        let (failed, _) = *self.current.lock().expect("pool lock poisoned");
+        for index in failover_order(failed, self.urls.len()) {
+            let url = &self.urls[index];
+            match connect(url, options).await {
+                Ok(connection) => {
+                    if index != failed {
+                        tracing::debug!("Failing over to {url}");
+                    }
+                    *self.current.lock().expect("pool lock poisoned") =
+                        (index, Arc::new(connection));
+                    return
+                }
+                Err(err) => tracing::debug!("Reconnecting to {url} failed: {err}"),
+            }
+        }
+    }
+}
+
+/// Returns the indices of the endpoints to try after the one at `failed`, ending with
+/// the failed one itself.
+fn failover_order(failed: usize, len: usize) -> impl Iterator<Item = usize> {
+    (1..=len).map(move |offset| (failed + offset) % len)
+}
+
+/// Parameters of a request, already serialized.
+struct Params(Option<Box<RawValue>>);
+
+impl ToRpcParams for Params {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+/// A connection to the RPC endpoint of a node.
+enum Connection {
+    /// A websocket connection, supporting subscriptions.
+    Ws(Client),
+    /// An HTTP connection, only supporting plain requests.
+    Http(Box<HttpClient>),
+}
+
+impl Connection {
+    async fn request(
+        &self,
+        method: &str,
+        params: Option<Box<RawValue>>,
+    ) -> Result<Box<RawValue>, Error> {
+        match self {
+            Self::Ws(client) => client.request(method, Params(params)).await,
+            Self::Http(client) => client.request(method, Params(params)).await,
+        }
+    }
+
+    async fn batch_request<'a>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, Box<RawValue>>, Error> {
+        match self {
+            Self::Ws(client) => client.batch_request(batch).await,
+            Self::Http(client) => client.batch_request(batch).await,
+        }
+    }
+}
+
+/// Establishes a new connection to `url`, through the daemon if one is running, or
+/// else using the transport matching its scheme.
+async fn connect(url: &str, options: &RpcOptions) -> Result<Connection, Error> {
+    #[cfg(unix)]
+    if options.daemon {
+        if let Some(client) = crate::daemon::connect(url).await {
+            return Ok(Connection::Ws(client))
+        }
+    }
+    let url: url::Url = url.parse().map_err(|e| Error::Transport(anyhow!("{e}")))?;
+    match url.scheme() {
+        "http" | "https" => {
+            let client = HttpClientBuilder::default()
+                .request_timeout(options.timeout)
+                .build(url)?;
+            Ok(Connection::Http(Box::new(client)))
+        }
+        "ws" | "wss" => {
+            let (sender, receiver) = WsTransportClientBuilder::default()
+                .build(url)
+                .await
+                .map_err(|e| Error::Transport(e.into()))?;
+            let heartbeat = PingConfig::new()
+                .ping_interval(HEARTBEAT_INTERVAL)
+                .inactive_limit(HEARTBEAT_TIMEOUT);
+            let client = Client::builder()
+                .max_buffer_capacity_per_subscription(4096)
+                .enable_ws_ping(heartbeat)
+                .build_with_tokio(sender, receiver);
+            Ok(Connection::Ws(client))
+        }
+        scheme => {
+            Err(Error::Transport(anyhow!(
+                "Unsupported URL scheme `{scheme}`, expected one of `ws`, `wss`, `http` \
+                or `https`"
+            )))
+        }
+    }
+}
+
+/// Returns `true` for errors which may not occur again when the request is repeated.
+fn is_transient(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::Transport(_)
+            | Error::RestartNeeded(_)
+            | Error::RequestTimeout
+            | Error::MaxSlotsExceeded
+    )
+}
+
+/// Returns `true` if a request of `method` can be repeated without side effects.
+fn is_idempotent(method: &str) -> bool {
+    IDEMPOTENT_METHODS
+        .iter()
+        .any(|prefix| method.starts_with(prefix))
+}
+
+/// Timeout of a single attempt of a request, if the `timeout` of the request is split
+/// across the first attempt and its `retries`.
+///
+/// An attempt gets at least [`MIN_ATTEMPT_TIMEOUT`], or the whole `timeout` if it is
+/// shorter. The `timeout` still bounds all attempts together, so later retries may be
+/// cut short.
+fn attempt_timeout(timeout: Duration, retries: u32) -> Duration {
+    (timeout / retries.saturating_add(1)).max(MIN_ATTEMPT_TIMEOUT.min(timeout))
+}
+
+/// Returns `true` for errors caused by the connection to the node, after which the
+/// connection is re-established.
+fn is_connection_error(err: &Error) -> bool {
+    matches!(err, Error::Transport(_) | Error::RestartNeeded(_))
+}
+
+/// Delay before the retry following the given (zero based) failed attempt.
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        assert_eq!(backoff(0), Duration::from_millis(250));
+        assert_eq!(backoff(1), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(1));
+        assert_eq!(backoff(5), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn only_connection_errors_are_transient() {
+        assert!(is_transient(&Error::RequestTimeout));
+        assert!(is_transient(&Error::Transport(anyhow!("connection reset"))));
+        assert!(!is_transient(&Error::Custom("invalid params".into())));
+        assert!(!is_transient(&Error::HttpNotImplemented));
+    }
+
+    #[test]
+    fn only_queries_are_idempotent() {
+        assert!(is_idempotent("state_call"));
+        assert!(is_idempotent("system_accountNextIndex"));
+        assert!(is_idempotent("rpc_methods"));
+        assert!(!is_idempotent("author_submitExtrinsic"));
+        assert!(!is_idempotent("author_submitAndWatchExtrinsic"));
+    }
+
+    #[test]
+    fn attempts_share_the_timeout() {
+        let timeout = Duration::from_secs(60);
+        assert_eq!(attempt_timeout(timeout, 0), timeout);
+        assert_eq!(attempt_timeout(timeout, 3), Duration::from_secs(15));
+        assert_eq!(attempt_timeout(timeout, u32::MAX), MIN_ATTEMPT_TIMEOUT);
+        assert_eq!(
+            attempt_timeout(Duration::from_secs(5), 3),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn rpc_account_is_ss58_or_hex() {
+        assert_eq!(
+            rpc_account(&[0; 32]),
+            "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM"
+        );
+        assert_eq!(
+            rpc_account(&[0xab; 20]),
+            "0xabababababababababababababababababababab"
+        );
+    }
+
+    #[test]
+    fn only_websocket_urls_can_submit() {
+        for url in ["ws://localhost:9944", "wss://rpc.polkadot.io"] {
+            assert!(ensure_can_submit(&url.parse().unwrap()).is_ok(), "{url}");
+        }
+        let err = ensure_can_submit(&"http://localhost:9933".parse().unwrap());
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Submitting extrinsics requires a websocket url (`ws://` or `wss://`), the \
+            `http` url http://localhost:9933/ only supports read-only operations like \
+            dry-runs"
+        );
+    }
+
+    #[test]
+    fn failover_tries_next_endpoints_first() {
+        assert_eq!(failover_order(1, 3).collect::<Vec<_>>(), vec![2, 0, 1]);
+        assert_eq!(failover_order(0, 1).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn connects_to_first_reachable_endpoint() {
+        let options = RpcOptions {
+            daemon: false,
+            fallback_urls: vec!["http://localhost:9934".to_string()],
+            ..Default::default()
+        };
+        let client = RetryingRpcClient::new("ws://localhost:1", options)
+            .await
+            .unwrap();
+        assert_eq!(client.url(), "http://localhost:9934");
+    }
+
+    #[tokio::test]
+    async fn unsupported_scheme_is_rejected() {
+        let res = connect("ftp://localhost:9944", &RpcOptions::default()).await;
+        assert!(
+            matches!(res, Err(Error::Transport(err)) if err.to_string().contains("`ftp`"))
+        );
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Execution of contracts in an in-process runtime with `pallet-contracts`, without a
+//! node, e.g. to exercise the logic and gas usage of a contract during development.
+
+use crate::{
+    pallet_contracts_primitives::{
+        ContractExecResult,
+        ContractInstantiateResult,
+    },
+    MutableContractStorage,
+};
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use ink_sandbox::{
+    api::prelude::{
+        ContractAPI,
+        SystemAPI,
+    },
+    frame_support::{
+        storage::{
+            child::{
+                self,
+                ChildInfo,
+            },
+            storage_prefix,
+            unhashed,
+        },
+        StorageHasher,
+        Twox64Concat,
+    },
+    macros::RuntimeEvent,
+    pallet_contracts::{
+        Determinism,
+        Event as ContractsEvent,
+    },
+    DefaultSandbox,
+    Sandbox,
+    Snapshot,
+    H256,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use sp_weights::Weight;
+use std::{
+    fs,
+    path::Path,
+};
+use subxt::{
+    utils::AccountId32,
+    Metadata,
+};
+
+/// The result of executing a contract in the sandbox.
+#[derive(Debug)]
+pub struct SandboxResult<R> {
+    /// The result of the instantiation or call.
+    pub result: R,
+    /// The events emitted by contracts during the execution.
+    pub events: Vec<SandboxContractEvent>,
+}
+
+/// An event emitted by a contract executed in the sandbox.
+#[derive(Debug)]
+pub struct SandboxContractEvent {
+    /// The address of the contract which emitted the event.
+    pub contract: AccountId32,
+    /// The topics of the event, starting with its signature topic unless it is
+    /// anonymous.
+    pub topics: Vec<[u8; 32]>,
+    /// The encoded fields of the event.
+    pub data: Vec<u8>,
+}
+
+/// An in-process runtime with `pallet-contracts`, in which contracts are instantiated
+/// and called instead of on a node.
+///
+/// All extrinsics are signed by a single account, which is endowed with funds.
+#[derive(Default)]
+pub struct SandboxBackend {
+    sandbox: DefaultSandbox,
+}
+
+impl SandboxBackend {
+    /// Creates a sandbox with the state stored at `path` by [`Self::store`], or with a
+    /// fresh state if there is no file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut backend = Self::default();
+        if path.exists() {
+            let bytes = fs::read(path).with_context(|| {
+                format!("Failed to read the sandbox state {}", path.display())
+            })?;
+            let (storage, storage_root) = <(_, [u8; 32])>::decode(&mut &bytes[..])
+                .with_context(|| {
+                    format!("Invalid sandbox state {}", path.display())
+                })?;
+            backend.sandbox.restore_snapshot(Snapshot {
+                storage,
+                storage_root: H256(storage_root),
+            });
+        }
+        Ok(backend)
+    }
+
+    /// Stores the state of the sandbox at `path`, to continue with it later.
+    pub fn store(&mut self, path: &Path) -> Result<()> {
+        let snapshot = self.sandbox.take_snapshot();
+        let bytes = (snapshot.storage, snapshot.storage_root.0).encode();
+        fs::write(path, bytes).with_context(|| {
+            format!("Failed to write the sandbox state {}", path.display())
+        })
+    }
+
+    /// The account which signs all extrinsics executed in the sandbox.
+    pub fn actor() -> AccountId32 {
+        AccountId32(DefaultSandbox::default_actor().into())
+    }
+
+    /// The metadata of the runtime of the sandbox, e.g. to decode its errors.
+    pub fn metadata() -> Result<Metadata> {
+        let metadata = DefaultSandbox::get_metadata().encode();
+        Ok(Metadata::decode(&mut &metadata[..])?)
+    }
+
+    /// Uploads the `code` and instantiates it with the constructor call `data`.
+    ///
+    /// Uses the default gas limit of the sandbox if `gas_limit` is `None`.
+    pub fn instantiate(
+        &mut self,
+        code: Vec<u8>,
+        data: Vec<u8>,
+        value: u128,
+        salt: Vec<u8>,
+        gas_limit: Option<Weight>,
+        storage_deposit_limit: Option<u128>,
+    ) -> Result<SandboxResult<ContractInstantiateResult<AccountId32, u128>>> {
+        self.sandbox.reset_events();
+        let result = self.sandbox.deploy_contract(
+            code,
+            value,
+            data,
+            salt,
+            DefaultSandbox::default_actor(),
+            sandbox_gas_limit(gas_limit),
+            storage_deposit_limit,
+        );
+        Ok(SandboxResult {
+            events: contract_events(result.events.as_deref().unwrap_or_default()),
+            result: convert(&result)?,
+        })
+    }
+
+    /// Calls the `contract` with the message call `data`.
+    ///
+    /// Uses the default gas limit of the sandbox if `gas_limit` is `None`.
+    pub fn call(
+        &mut self,
+        contract: &AccountId32,
+        data: Vec<u8>,
+        value: u128,
+        gas_limit: Option<Weight>,
+        storage_deposit_limit: Option<u128>,
+    ) -> Result<SandboxResult<ContractExecResult<u128>>> {
+        self.sandbox.reset_events();
+        let result = self.sandbox.call_contract(
+            contract.0.into(),
+            value,
+            data,
+            DefaultSandbox::default_actor(),
+            sandbox_gas_limit(gas_limit),
+            storage_deposit_limit,
+            Determinism::Enforced,
+        );
+        Ok(SandboxResult {
+            events: contract_events(result.events.as_deref().unwrap_or_default()),
+            result: convert(&result)?,
+        })
+    }
+
+    /// Returns the storage of the `contract`, to modify it directly.
+    pub fn contract_storage(
+        &mut self,
+        contract: &AccountId32,
+    ) -> Result<SandboxContractStorage<'_>> {
+        let key = [
+            &storage_prefix(b"Contracts", b"ContractInfoOf")[..],
+            &Twox64Concat::hash(&contract.0),
+        ]
+        .concat();
+        let info = self
+            .sandbox
+            .execute_with(|| unhashed::get_raw(&key))
+            .ok_or_else(|| anyhow!("No contract found at {contract} in the sandbox"))?;
+        // The trie id is the first field of the `ContractInfo` of `pallet-contracts`.
+        let trie_id = Vec::<u8>::decode(&mut &info[..])?;
+        Ok(SandboxContractStorage {
+            sandbox: &mut self.sandbox,
+            child_info: ChildInfo::new_default(&trie_id),
+        })
+    }
+}
+
+/// The storage of a contract in the sandbox, see [`SandboxBackend::contract_storage`].
+pub struct SandboxContractStorage<'a> {
+    sandbox: &'a mut DefaultSandbox,
+    child_info: ChildInfo,
+}
+
+impl MutableContractStorage for SandboxContractStorage<'_> {
+    fn read_raw(&mut self, hashed_key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .sandbox
+            .execute_with(|| child::get_raw(&self.child_info, hashed_key)))
+    }
+
+    fn write_raw(&mut self, hashed_key: &[u8], value: Option<Vec<u8>>) -> Result<()> {
+        let child_info = &self.child_info;
+        self.sandbox.execute_with(|| {
+            match value {
+                Some(value) => child::put_raw(child_info, hashed_key, &value),
+                None => child::kill(child_info, hashed_key),
+            }
+        });
+        Ok(())
+    }
+}
+
+fn sandbox_gas_limit(gas_limit: Option<Weight>) -> ink_sandbox::Weight {
+    match gas_limit {
+        Some(gas_limit) => {
+            ink_sandbox::Weight::from_parts(gas_limit.ref_time(), gas_limit.proof_size())
+        }
+        None => DefaultSandbox::default_gas_limit(),
+    }
+}
+
+/// Converts a result of `pallet-contracts` into the copy of its type in
+/// [`pallet_contracts_primitives`](crate::pallet_contracts_primitives), which have the
+/// same encoding.
+fn convert<T: Encode, U: Decode>(value: &T) -> Result<U> {
+    Ok(U::decode(&mut &value.encode()[..])?)
+}
+
+/// Returns the events emitted by contracts among the runtime `events`.
+fn contract_events(
+    events: &[ink_sandbox::EventRecordOf<<DefaultSandbox as Sandbox>::Runtime>],
+) -> Vec<SandboxContractEvent> {
+    events
+        .iter()
+        .filter_map(|record| {
+            match &record.event {
+                RuntimeEvent::Contracts(ContractsEvent::ContractEmitted {
+                    contract,
+                    data,
+                }) => {
+                    Some(SandboxContractEvent {
+                        contract: AccountId32(contract.clone().into()),
+                        topics: record.topics.iter().map(|topic| topic.0).collect(),
+                        data: data.clone(),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A contract which returns its input and emits it as an event.
+    const ECHO: &str = r#"
+        (module
+            (import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+            (import "seal0" "seal_deposit_event"
+                (func $seal_deposit_event (param i32 i32 i32 i32)))
+            (import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+            (import "env" "memory" (memory 1 1))
+            (data (i32.const 0) "\40")
+            (func (export "deploy"))
+            (func (export "call")
+                (call $seal_input (i32.const 4) (i32.const 0))
+                (call $seal_deposit_event
+                    (i32.const 0) (i32.const 0) (i32.const 4) (i32.load (i32.const 0)))
+                (call $seal_return (i32.const 0) (i32.const 4) (i32.load (i32.const 0)))
+            )
+        )
+    "#;
+
+    #[test]
+    fn sandbox_state_is_stored() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().join("sandbox.state");
+        let code = wat::parse_str(ECHO).unwrap();
+
+        let mut sandbox = SandboxBackend::load(&state).unwrap();
+        let instantiated = sandbox
+            .instantiate(code, Vec::new(), 0, Vec::new(), None, None)
+            .unwrap();
+        let contract = instantiated.result.result.unwrap().account_id;
+        sandbox.store(&state).unwrap();
+
+        let mut sandbox = SandboxBackend::load(&state).unwrap();
+        let called = sandbox
+            .call(&contract, vec![1, 2, 3], 0, None, None)
+            .unwrap();
+
+        let returned = called.result.result.unwrap();
+        assert!(!returned.did_revert());
+        assert_eq!(returned.data, vec![1, 2, 3]);
+        assert_eq!(called.events.len(), 1);
+        assert_eq!(called.events[0].contract, contract);
+        assert_eq!(called.events[0].data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn chain_ss58_prefix_is_read_from_metadata() {
+        let metadata = SandboxBackend::metadata().unwrap();
+
+        // the runtime of the sandbox doesn't configure a prefix
+        assert_eq!(crate::ss58::chain_ss58_prefix(&metadata), Some(0));
+    }
+
+    #[test]
+    fn derived_contract_address_matches_instantiated() {
+        let code = wat::parse_str(ECHO).unwrap();
+        let code_hash = contract_build::code_hash(&code);
+
+        let mut sandbox = SandboxBackend::default();
+        let instantiated = sandbox
+            .instantiate(code, vec![1, 2], 0, vec![3], None, None)
+            .unwrap();
+        let contract = instantiated.result.result.unwrap().account_id;
+
+        let derived = crate::derive_contract_address(
+            &SandboxBackend::actor(),
+            &code_hash,
+            &[1, 2],
+            &[3],
+        )
+        .unwrap();
+        assert_eq!(derived, contract);
+    }
+
+    #[test]
+    fn contract_storage_is_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().join("sandbox.state");
+        let code = wat::parse_str(ECHO).unwrap();
+
+        let mut sandbox = SandboxBackend::default();
+        let instantiated = sandbox
+            .instantiate(code, Vec::new(), 0, Vec::new(), None, None)
+            .unwrap();
+        let contract = instantiated.result.result.unwrap().account_id;
+        let mut storage = sandbox.contract_storage(&contract).unwrap();
+        assert_eq!(storage.read_raw(&[1; 20]).unwrap(), None);
+        storage.write_raw(&[1; 20], Some(vec![4, 2])).unwrap();
+        sandbox.store(&state).unwrap();
+
+        let mut sandbox = SandboxBackend::load(&state).unwrap();
+        let mut storage = sandbox.contract_storage(&contract).unwrap();
+        assert_eq!(storage.read_raw(&[1; 20]).unwrap(), Some(vec![4, 2]));
+        storage.write_raw(&[1; 20], None).unwrap();
+        assert_eq!(storage.read_raw(&[1; 20]).unwrap(), None);
+        assert!(sandbox
+            .contract_storage(&AccountId32([7; 32]))
+            .is_err());
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::ExtrinsicReceipt;
+
+use scale::Encode;
+use subxt::{
+    blocks::ExtrinsicEvents,
+    events::StaticEvent,
+    ext::{
+        scale_decode,
+        scale_encode::EncodeAsType,
+    },
+    tx,
+    Config,
+    Metadata,
+};
+
+/// The priority of scheduled calls, in the middle between the highest (`0`) and the
+/// lowest (`255`) priority.
+const SCHEDULE_PRIORITY: u8 = 127;
+
+/// The payload of a `scheduler.schedule` extrinsic, dispatching the already encoded
+/// `call` once at block `when`.
+pub(crate) struct Schedule {
+    pub when: u64,
+    pub call: Vec<u8>,
+}
+
+impl tx::TxPayload for Schedule {
+    fn encode_call_data_to(
+        &self,
+        metadata: &Metadata,
+        out: &mut Vec<u8>,
+    ) -> Result<(), subxt::Error> {
+        let pallet = metadata.pallet_by_name_err("Scheduler")?;
+        let call = pallet.call_variant_by_name("schedule").ok_or_else(|| {
+            subxt::Error::Other("The Scheduler pallet has no schedule call".into())
+        })?;
+        // `schedule(when, maybe_periodic, priority, call)`
+        let [when, _, _, _] = &call.fields[..] else {
+            return Err(subxt::Error::Other(
+                "Unsupported signature of the Scheduler::schedule call".into(),
+            ))
+        };
+        pallet.index().encode_to(out);
+        call.index.encode_to(out);
+        // the block number type differs between chains
+        self.when
+            .encode_as_type_to(&when.ty.id, metadata.types(), out)?;
+        None::<()>.encode_to(out);
+        SCHEDULE_PRIORITY.encode_to(out);
+        out.extend_from_slice(&self.call);
+        Ok(())
+    }
+}
+
+/// A call was scheduled.
+#[derive(Debug, scale_decode::DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+pub(crate) struct Scheduled {
+    pub when: u64,
+    pub index: u32,
+}
+
+impl StaticEvent for Scheduled {
+    const PALLET: &'static str = "Scheduler";
+    const EVENT: &'static str = "Scheduled";
+}
+
+/// The result of scheduling a contract call.
+pub struct ScheduledCallResult<C: Config> {
+    pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+    /// The block the call is dispatched at.
+    pub when: u64,
+    /// The index of the call in the agenda of the block, together with `when` the id
+    /// of the scheduled task.
+    pub index: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_metadata::{
+        self,
+        remark,
+        RuntimeCall,
+        SchedulerCall,
+    };
+    use subxt::tx::TxPayload;
+
+    #[test]
+    fn encodes_schedule_call() {
+        let metadata = test_metadata::metadata();
+        let schedule = Schedule {
+            when: 100,
+            call: remark(&[1, 2]).encode(),
+        };
+
+        let call_data = schedule.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Scheduler(SchedulerCall::schedule {
+            when: 100,
+            maybe_periodic: None,
+            priority: SCHEDULE_PRIORITY,
+            call: Box::new(remark(&[1, 2])),
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+
+    #[test]
+    fn block_out_of_range_is_rejected() {
+        let metadata = test_metadata::metadata();
+        let schedule = Schedule {
+            when: u64::from(u32::MAX) + 1,
+            call: remark(&[]).encode(),
+        };
+
+        assert!(schedule.encode_call_data(&metadata).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Display of accounts in the SS58 address format of the target network.
+//!
+//! The prefix is passed explicitly, see [`ss58_prefix`] for the prefix of a chain.
+//! Addresses are accepted with any prefix.
+
+use crate::eth_address;
+use contract_transcode::AccountId32;
+use scale::{
+    Decode,
+    Encode,
+};
+use std::{
+    fmt::Display,
+    str::FromStr,
+};
+
+pub use contract_transcode::SUBSTRATE_SS58_PREFIX;
+
+/// Returns the `SS58Prefix` of the `System` pallet of the chain, `None` if the runtime
+/// does not define it.
+pub fn chain_ss58_prefix(metadata: &subxt::Metadata) -> Option<u16> {
+    let constant = metadata
+        .pallet_by_name("System")?
+        .constant_by_name("SS58Prefix")?;
+    u16::decode(&mut constant.value()).ok()
+}
+
+/// Returns the SS58 prefix to display accounts with: the explicitly given `prefix`,
+/// otherwise the one of the chain with the runtime `metadata`, and the generic
+/// Substrate prefix `42` if neither is known.
+pub fn ss58_prefix(prefix: Option<u16>, metadata: Option<&subxt::Metadata>) -> u16 {
+    prefix
+        .or_else(|| metadata.and_then(chain_ss58_prefix))
+        .unwrap_or(SUBSTRATE_SS58_PREFIX)
+}
+
+/// Displays the `account` as SS58 address with the given `prefix`.
+///
+/// Accounts which are not 32 bytes long, e.g. Ethereum style addresses, are displayed
+/// as is. Accounts mapped from an `H160` address by `pallet-revive`, like the ones of
+/// its contracts, are displayed as the address, see [`eth_address`].
+pub fn display_account<AccountId>(account: &AccountId, prefix: u16) -> String
+where
+    AccountId: Encode + Display,
+{
+    match <[u8; 32]>::try_from(account.encode()) {
+        Ok(bytes) if bytes[20..].iter().all(|byte| *byte == 0xEE) => {
+            format!("0x{}", hex::encode(eth_address(&bytes)))
+        }
+        Ok(bytes) => AccountId32(bytes).to_ss58check_with_prefix(prefix),
+        Err(_) => account.to_string(),
+    }
+}
+
+/// Returns `true` if both addresses denote the same account, regardless of the SS58
+/// prefix they are displayed with.
+pub fn is_same_account(address: &str, other: &str) -> bool {
+    match (AccountId32::from_str(address), AccountId32::from_str(other)) {
+        (Ok(account), Ok(other)) => account == other,
+        _ => address == other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn chain_ss58_prefix_is_none_without_system_pallet() {
+        // the test metadata only contains the `Contracts` pallet
+        let bytes = fs::read("src/test_runtime_api/metadata_v15.scale").unwrap();
+        let metadata = subxt::Metadata::decode(&mut &bytes[..]).unwrap();
+
+        assert_eq!(chain_ss58_prefix(&metadata), None);
+    }
+
+    #[test]
+    fn explicit_ss58_prefix_takes_precedence() {
+        let bytes = fs::read("src/test_runtime_api/metadata_v15.scale").unwrap();
+        let metadata = subxt::Metadata::decode(&mut &bytes[..]).unwrap();
+
+        assert_eq!(ss58_prefix(Some(0), Some(&metadata)), 0);
+        assert_eq!(ss58_prefix(None, Some(&metadata)), SUBSTRATE_SS58_PREFIX);
+        assert_eq!(ss58_prefix(None, None), SUBSTRATE_SS58_PREFIX);
+    }
+
+    #[test]
+    fn account_is_displayed_with_prefix() {
+        let alice = AccountId32([1; 32]);
+
+        assert_eq!(display_account(&alice, 0), alice.to_ss58check_with_prefix(0));
+        assert_eq!(display_account(&alice, SUBSTRATE_SS58_PREFIX), alice.to_string());
+    }
+
+    #[test]
+    fn mapped_account_is_displayed_as_address() {
+        let contract = AccountId32::from_eth_address([0xab; 20]);
+
+        assert_eq!(display_account(&contract, 0), format!("0x{}", "ab".repeat(20)));
+    }
+
+    #[test]
+    fn same_account_is_detected_across_prefixes() {
+        let alice = AccountId32([1; 32]);
+        let bob = AccountId32([2; 32]);
+
+        assert!(is_same_account(
+            &alice.to_ss58check_with_prefix(0),
+            &alice.to_ss58check_with_prefix(SUBSTRATE_SS58_PREFIX)
+        ));
+        assert!(!is_same_account(
+            &alice.to_ss58check_with_prefix(0),
+            &bob.to_ss58check_with_prefix(0)
+        ));
+        assert!(is_same_account("0x01", "0x01"));
+    }
+}
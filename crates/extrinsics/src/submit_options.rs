@@ -0,0 +1,119 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+/// Default number of times an extrinsic rejected because of its nonce is submitted
+/// again.
+pub const DEFAULT_NONCE_RETRIES: u32 = 3;
+/// Default delay before the first resubmission, doubled with every further attempt.
+pub const DEFAULT_NONCE_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound of the delay between two submissions.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Options for the submission of extrinsics.
+///
+/// An extrinsic is rejected by the node if its nonce was already used, e.g. by an
+/// extrinsic of the same account submitted concurrently (`Transaction is outdated`), or
+/// if its nonce is ahead of the nonce of the account, e.g. because a previously tracked
+/// extrinsic was not submitted (`Transaction will be valid in the future`). Such an
+/// extrinsic is signed again with a refetched nonce and resubmitted, unless its nonce
+/// was set explicitly with [`NonceProvider::starting_at`].
+///
+/// [`NonceProvider::starting_at`]: crate::NonceProvider::starting_at
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmitOptions {
+    /// How often an extrinsic rejected because of its nonce is submitted again.
+    pub nonce_retries: u32,
+    /// Delay before the first resubmission, doubled with every further attempt.
+    pub nonce_retry_backoff: Duration,
+}
+
+impl Default for SubmitOptions {
+    fn default() -> Self {
+        Self {
+            nonce_retries: DEFAULT_NONCE_RETRIES,
+            nonce_retry_backoff: DEFAULT_NONCE_RETRY_BACKOFF,
+        }
+    }
+}
+
+impl SubmitOptions {
+    /// Options which never resubmit an extrinsic.
+    pub fn no_retries() -> Self {
+        Self {
+            nonce_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before the resubmission following the given (zero based) rejected attempt.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        self.nonce_retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF.max(self.nonce_retry_backoff))
+    }
+}
+
+/// Returns `true` if the submission of an extrinsic was rejected because its nonce is
+/// either stale or in the future.
+pub(crate) fn is_nonce_error(err: &subxt::Error) -> bool {
+    let subxt::Error::Rpc(err) = err else {
+        return false
+    };
+    let err = err.to_string();
+    err.contains("Transaction is outdated")
+        || err.contains("Transaction will be valid in the future")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use subxt::error::RpcError;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let opts = SubmitOptions::default();
+
+        assert_eq!(opts.backoff(0), Duration::from_secs(1));
+        assert_eq!(opts.backoff(1), Duration::from_secs(2));
+        assert_eq!(opts.backoff(3), Duration::from_secs(8));
+        assert_eq!(opts.backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn detects_nonce_errors() {
+        let rpc_error = |message: &str| {
+            subxt::Error::Rpc(RpcError::ClientError(Box::new(std::io::Error::other(
+                message.to_string(),
+            ))))
+        };
+
+        assert!(is_nonce_error(&rpc_error(
+            "Invalid Transaction (1010): Transaction is outdated"
+        )));
+        assert!(is_nonce_error(&rpc_error(
+            "Invalid Transaction (1010): Transaction will be valid in the future"
+        )));
+        assert!(!is_nonce_error(&rpc_error(
+            "Invalid Transaction (1010): Inability to pay some fees"
+        )));
+        assert!(!is_nonce_error(&subxt::Error::Other(
+            "Transaction is outdated".into()
+        )));
+    }
+}
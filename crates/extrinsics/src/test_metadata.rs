@@ -0,0 +1,257 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime metadata of the pallets wrapping the calls of contracts, for testing the
+//! encoding of their extrinsics.
+//!
+//! `src/test_runtime_api/metadata_v15.scale` only includes the `Contracts` pallet. The
+//! calls below mirror the signatures and indices of the pallets of `polkadot-sdk`, and
+//! the metadata is generated from their type information like a runtime does. The
+//! expected call data is the SCALE encoding of the same types. The `Revive` pallet is
+//! only included in [`revive_metadata`].
+
+#![allow(non_camel_case_types)]
+
+use frame_metadata::{
+    v15::{
+        CustomMetadata,
+        ExtrinsicMetadata,
+        OuterEnums,
+        PalletCallMetadata,
+        PalletMetadata,
+        RuntimeMetadataV15,
+    },
+    RuntimeMetadataPrefixed,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use scale_info::{
+    meta_type,
+    TypeInfo,
+};
+use sp_core::{
+    H160,
+    H256,
+};
+use sp_runtime::{
+    AccountId32,
+    MultiAddress,
+    MultiSignature,
+};
+use sp_weights::Weight;
+use subxt::Metadata;
+
+/// The outer call of the runtime.
+#[derive(Encode, TypeInfo)]
+pub enum RuntimeCall {
+    #[codec(index = 0)]
+    System(SystemCall),
+    #[codec(index = 4)]
+    Balances(BalancesCall),
+    #[codec(index = 6)]
+    Utility(UtilityCall),
+    #[codec(index = 7)]
+    Scheduler(SchedulerCall),
+    #[codec(index = 8)]
+    Sudo(SudoCall),
+    #[codec(index = 60)]
+    Revive(ReviveCall),
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum SystemCall {
+    #[codec(index = 0)]
+    remark { remark: Vec<u8> },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum BalancesCall {
+    #[codec(index = 3)]
+    transfer_keep_alive {
+        dest: MultiAddress<AccountId32, ()>,
+        #[codec(compact)]
+        value: u128,
+    },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum UtilityCall {
+    #[codec(index = 2)]
+    batch_all { calls: Vec<RuntimeCall> },
+    #[codec(index = 3)]
+    dispatch_as {
+        as_origin: Box<OriginCaller>,
+        call: Box<RuntimeCall>,
+    },
+}
+
+/// The outer origin of the runtime.
+#[derive(Encode, TypeInfo)]
+pub enum OriginCaller {
+    #[codec(index = 0)]
+    system(RawOrigin),
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum RawOrigin {
+    #[codec(index = 1)]
+    Signed(AccountId32),
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum SchedulerCall {
+    #[codec(index = 0)]
+    schedule {
+        when: u32,
+        maybe_periodic: Option<(u32, u32)>,
+        priority: u8,
+        call: Box<RuntimeCall>,
+    },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum SudoCall {
+    #[codec(index = 0)]
+    sudo { call: Box<RuntimeCall> },
+    #[codec(index = 3)]
+    sudo_as {
+        who: MultiAddress<AccountId32, ()>,
+        call: Box<RuntimeCall>,
+    },
+}
+
+#[derive(Encode, TypeInfo)]
+pub enum ReviveCall {
+    #[codec(index = 1)]
+    call {
+        dest: H160,
+        #[codec(compact)]
+        value: u128,
+        gas_limit: Weight,
+        #[codec(compact)]
+        storage_deposit_limit: u128,
+        data: Vec<u8>,
+    },
+    #[codec(index = 2)]
+    instantiate {
+        #[codec(compact)]
+        value: u128,
+        gas_limit: Weight,
+        #[codec(compact)]
+        storage_deposit_limit: u128,
+        code_hash: H256,
+        data: Vec<u8>,
+        salt: Option<[u8; 32]>,
+    },
+    #[codec(index = 3)]
+    instantiate_with_code {
+        #[codec(compact)]
+        value: u128,
+        gas_limit: Weight,
+        #[codec(compact)]
+        storage_deposit_limit: u128,
+        code: Vec<u8>,
+        data: Vec<u8>,
+        salt: Option<[u8; 32]>,
+    },
+    #[codec(index = 4)]
+    upload_code {
+        code: Vec<u8>,
+        #[codec(compact)]
+        storage_deposit_limit: u128,
+    },
+    #[codec(index = 5)]
+    remove_code { code_hash: H256 },
+    #[codec(index = 7)]
+    map_account {},
+}
+
+/// The outer event and error of the runtime, which are not used by the tests.
+#[derive(Encode, TypeInfo)]
+pub enum RuntimeEvent {}
+
+/// Returns a `System::remark` call of `remark`.
+pub fn remark(remark: &[u8]) -> RuntimeCall {
+    RuntimeCall::System(SystemCall::remark {
+        remark: remark.to_vec(),
+    })
+}
+
+/// Returns the metadata of a runtime with the pallets of [`RuntimeCall`], except for
+/// the `Revive` pallet.
+pub fn metadata() -> Metadata {
+    runtime_metadata(false)
+}
+
+/// Returns the metadata of a runtime with the pallets of [`RuntimeCall`], including the
+/// `Revive` pallet.
+pub fn revive_metadata() -> Metadata {
+    runtime_metadata(true)
+}
+
+fn runtime_metadata(revive: bool) -> Metadata {
+    fn pallet<Call: TypeInfo + 'static>(name: &'static str, index: u8) -> PalletMetadata {
+        PalletMetadata {
+            name,
+            storage: None,
+            calls: Some(PalletCallMetadata {
+                ty: meta_type::<Call>(),
+            }),
+            event: None,
+            constants: Vec::new(),
+            error: None,
+            index,
+            docs: Vec::new(),
+        }
+    }
+    let mut pallets = vec![
+        pallet::<SystemCall>("System", 0),
+        pallet::<BalancesCall>("Balances", 4),
+        pallet::<UtilityCall>("Utility", 6),
+        pallet::<SchedulerCall>("Scheduler", 7),
+        pallet::<SudoCall>("Sudo", 8),
+    ];
+    if revive {
+        pallets.push(pallet::<ReviveCall>("Revive", 60));
+    }
+    let extrinsic = ExtrinsicMetadata {
+        version: 4,
+        address_ty: meta_type::<MultiAddress<AccountId32, ()>>(),
+        call_ty: meta_type::<RuntimeCall>(),
+        signature_ty: meta_type::<MultiSignature>(),
+        extra_ty: meta_type::<()>(),
+        signed_extensions: Vec::new(),
+    };
+    let outer_enums = OuterEnums {
+        call_enum_ty: meta_type::<RuntimeCall>(),
+        event_enum_ty: meta_type::<RuntimeEvent>(),
+        error_enum_ty: meta_type::<RuntimeEvent>(),
+    };
+    let metadata = RuntimeMetadataV15::new(
+        pallets,
+        extrinsic,
+        meta_type::<()>(),
+        Vec::new(),
+        outer_enums,
+        CustomMetadata {
+            map: Default::default(),
+        },
+    );
+    let bytes = RuntimeMetadataPrefixed::from(metadata).encode();
+    Metadata::decode(&mut &bytes[..]).expect("the metadata is valid")
+}
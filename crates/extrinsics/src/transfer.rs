@@ -0,0 +1,188 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    estimate_fee,
+    submit_extrinsic,
+    ErrorVariant,
+    ExtrinsicReceipt,
+};
+use crate::{
+    extrinsic_calls::TransferKeepAlive,
+    extrinsic_opts::ExtrinsicOpts,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
+};
+
+use anyhow::Result;
+use ink_env::Environment;
+use subxt::{
+    blocks::ExtrinsicEvents,
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::scale_encode::EncodeAsType,
+    tx,
+    Config,
+    OnlineClient,
+};
+
+/// A builder for the transfer command.
+pub struct TransferCommandBuilder<C: Config, E: Environment, Signer: Clone> {
+    dest: C::AccountId,
+    value: E::Balance,
+    extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
+}
+
+impl<C: Config, E: Environment, Signer> TransferCommandBuilder<C, E, Signer>
+where
+    Signer: tx::Signer<C> + Clone,
+{
+    /// Returns a clean builder for [`TransferExec`], transferring `value` to `dest`.
+    pub fn new(
+        dest: C::AccountId,
+        value: E::Balance,
+        extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
+    ) -> TransferCommandBuilder<C, E, Signer> {
+        TransferCommandBuilder {
+            dest,
+            value,
+            extrinsic_opts,
+        }
+    }
+
+    /// Sets up the client for the transfer.
+    ///
+    /// Returns the `TransferExec`, or an error in case of failure.
+    pub async fn done(self) -> Result<TransferExec<C, E, Signer>> {
+        let url = self.extrinsic_opts.url();
+        let rpc_cli = rpc_client(&url, self.extrinsic_opts.rpc_options()).await?;
+        let client =
+            online_client::<C>(rpc_cli.clone(), self.extrinsic_opts.rpc_options())
+                .await?;
+        let rpc = NodeRpc::new(rpc_cli);
+
+        Ok(TransferExec {
+            dest: self.dest,
+            value: self.value,
+            opts: self.extrinsic_opts,
+            rpc,
+            client,
+        })
+    }
+}
+
+pub struct TransferExec<C: Config, E: Environment, Signer: Clone> {
+    dest: C::AccountId,
+    value: E::Balance,
+    opts: ExtrinsicOpts<C, E, Signer>,
+    rpc: NodeRpc<C>,
+    client: OnlineClient<C>,
+}
+
+impl<C: Config, E: Environment, Signer> TransferExec<C, E, Signer>
+where
+    C::AccountId: EncodeAsType,
+    E::Balance: EncodeAsType,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    Signer: tx::Signer<C> + Clone,
+{
+    /// Transfers the value from the signer to the destination account by submitting
+    /// the `Balances::transfer_keep_alive` extrinsic.
+    ///
+    /// The transfer fails if it would reap the account of the signer.
+    ///
+    /// Returns the `TransferResult` containing the events of the extrinsic, or an
+    /// error in case of failure.
+    pub async fn transfer(&self) -> Result<TransferResult<C>, ErrorVariant> {
+        let call = TransferKeepAlive::new(self.dest.clone().into(), self.value).build();
+
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
+
+        Ok(TransferResult { events, receipt })
+    }
+
+    /// Estimates the fee of the transfer, excluding the tip.
+    pub async fn estimate_fee(&self) -> Result<u128> {
+        let call = TransferKeepAlive::new(self.dest.clone().into(), self.value).build();
+        Ok(estimate_fee(&self.client, &call, &self.opts).await?)
+    }
+}
+
+impl<C: Config, E: Environment, Signer: Clone> TransferExec<C, E, Signer> {
+    /// Returns the destination account.
+    pub fn dest(&self) -> &C::AccountId {
+        &self.dest
+    }
+
+    /// Returns the transferred value.
+    pub fn value(&self) -> E::Balance {
+        self.value
+    }
+
+    /// Returns the extrinsic options.
+    pub fn opts(&self) -> &ExtrinsicOpts<C, E, Signer> {
+        &self.opts
+    }
+
+    /// Returns the client.
+    pub fn client(&self) -> &OnlineClient<C> {
+        &self.client
+    }
+}
+
+/// A struct representing the result of a transfer command execution.
+pub struct TransferResult<C: Config> {
+    pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        extrinsic_calls::TransferKeepAlive,
+        test_metadata::{
+            self,
+            BalancesCall,
+            RuntimeCall,
+        },
+    };
+    use scale::Encode;
+    use sp_runtime::MultiAddress;
+    use subxt::{
+        tx::TxPayload,
+        utils::AccountId32,
+    };
+
+    #[test]
+    fn encodes_transfer_keep_alive() {
+        let metadata = test_metadata::metadata();
+        let dest = AccountId32([1; 32]);
+        let call = TransferKeepAlive::new(dest.into(), 1_000_000_000_000u128).build();
+
+        let call_data = call.encode_call_data(&metadata).unwrap();
+
+        let expected = RuntimeCall::Balances(BalancesCall::transfer_keep_alive {
+            dest: MultiAddress::Id([1; 32].into()),
+            value: 1_000_000_000_000,
+        });
+        assert_eq!(call_data, expected.encode());
+    }
+}
@@ -15,27 +15,46 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
-    events::CodeStored,
-    pallet_contracts_primitives::CodeUploadResult,
+    estimate_fee,
+    events::{
+        find_contracts_events,
+        CodeStored,
+    },
+    pallet_contracts_primitives::{
+        CodeUploadResult,
+        StorageDeposit,
+    },
     state_call,
     submit_extrinsic,
+    ContractsBackend,
     ErrorVariant,
+    ExtrinsicReceipt,
     WasmCode,
 };
 use crate::{
     check_env_types,
+    contracts_backend,
     extrinsic_calls::UploadCode,
     extrinsic_opts::ExtrinsicOpts,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
+};
+use anyhow::{
+    anyhow,
+    Result,
 };
-use anyhow::Result;
 use contract_transcode::ContractMessageTranscoder;
 use ink_env::Environment;
 use scale::Encode;
-use subxt::{
-    backend::{
-        legacy::LegacyRpcMethods,
-        rpc::RpcClient,
+use std::{
+    fmt::{
+        Display,
+        Formatter,
     },
+    str::FromStr,
+};
+use subxt::{
     blocks::ExtrinsicEvents,
     config::{
         DefaultExtrinsicParams,
@@ -53,6 +72,8 @@ use subxt::{
 /// A builder for the upload command.
 pub struct UploadCommandBuilder<C: Config, E: Environment, Signer: Clone> {
     extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
+    determinism: Determinism,
+    skip_metadata_check: bool,
 }
 
 impl<C: Config, E: Environment, Signer> UploadCommandBuilder<C, E, Signer>
@@ -63,7 +84,30 @@ where
     pub fn new(
         extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
     ) -> UploadCommandBuilder<C, E, Signer> {
-        UploadCommandBuilder { extrinsic_opts }
+        UploadCommandBuilder {
+            extrinsic_opts,
+            determinism: Determinism::default(),
+            skip_metadata_check: false,
+        }
+    }
+
+    /// Sets whether the uploaded code must be deterministic.
+    ///
+    /// Code uploaded with [`Determinism::Relaxed`] can only be executed off-chain.
+    pub fn determinism(self, determinism: Determinism) -> Self {
+        let mut this = self;
+        this.determinism = determinism;
+        this
+    }
+
+    /// Sets whether to upload the code without its contract metadata.
+    ///
+    /// Allows uploading a bare `.wasm` file, e.g. downloaded from a registry, skipping
+    /// the check of the contract environment types against the node.
+    pub fn skip_metadata_check(self, skip_metadata_check: bool) -> Self {
+        let mut this = self;
+        this.skip_metadata_check = skip_metadata_check;
+        this
     }
 
     /// Preprocesses contract artifacts and options for subsequent upload.
@@ -77,7 +121,10 @@ where
     /// execution.
     pub async fn done(self) -> Result<UploadExec<C, E, Signer>> {
         let artifacts = self.extrinsic_opts.contract_artifacts()?;
-        let transcoder = artifacts.contract_transcoder()?;
+        let transcoder = match self.skip_metadata_check {
+            true => None,
+            false => Some(artifacts.contract_transcoder()?),
+        };
 
         let artifacts_path = artifacts.artifact_path().to_path_buf();
         let code = artifacts.code.ok_or_else(|| {
@@ -88,16 +135,29 @@ where
         })?;
 
         let url = self.extrinsic_opts.url();
-        let rpc_cli = RpcClient::from_url(&url).await?;
-        let client = OnlineClient::from_rpc_client(rpc_cli.clone()).await?;
-        check_env_types(&client, &transcoder, self.extrinsic_opts.verbosity())?;
-        let rpc = LegacyRpcMethods::new(rpc_cli);
+        let rpc_cli = rpc_client(&url, self.extrinsic_opts.rpc_options()).await?;
+        let client =
+            online_client(rpc_cli.clone(), self.extrinsic_opts.rpc_options()).await?;
+        let backend = contracts_backend(&client.metadata())?;
+        if !backend.upload_determinism() && self.determinism == Determinism::Relaxed {
+            return Err(anyhow!(
+                "Code cannot be uploaded for off-chain execution only on chains running \
+                 the `{}` pallet",
+                backend.pallet()
+            ))
+        }
+        if let Some(transcoder) = &transcoder {
+            check_env_types(&client, transcoder, self.extrinsic_opts.verbosity())?;
+        }
+        let rpc = NodeRpc::new(rpc_cli);
 
         Ok(UploadExec {
             opts: self.extrinsic_opts,
             rpc,
             client,
+            backend,
             code,
+            determinism: self.determinism,
             transcoder,
         })
     }
@@ -105,10 +165,12 @@ where
 
 pub struct UploadExec<C: Config, E: Environment, Signer: Clone> {
     opts: ExtrinsicOpts<C, E, Signer>,
-    rpc: LegacyRpcMethods<C>,
+    rpc: NodeRpc<C>,
     client: OnlineClient<C>,
+    backend: &'static dyn ContractsBackend,
     code: WasmCode,
-    transcoder: ContractMessageTranscoder,
+    determinism: Determinism,
+    transcoder: Option<ContractMessageTranscoder>,
 }
 
 impl<C: Config, E: Environment, Signer> UploadExec<C, E, Signer>
@@ -127,14 +189,24 @@ where
     /// then sends the request using the provided URL. This operation does not modify
     /// the state of the blockchain.
     pub async fn upload_code_rpc(&self) -> Result<CodeUploadResult<C::Hash, E::Balance>> {
-        let storage_deposit_limit = self.opts.storage_deposit_limit();
-        let call_request = CodeUploadRequest {
+        let method = self.backend.runtime_api_method("upload_code");
+        if self.backend.upload_determinism() {
+            state_call(&self.rpc, &method, self.upload_request(self.determinism)).await
+        } else {
+            state_call(&self.rpc, &method, self.upload_request(())).await
+        }
+    }
+
+    fn upload_request<D>(
+        &self,
+        determinism: D,
+    ) -> CodeUploadRequest<C::AccountId, E::Balance, D> {
+        CodeUploadRequest {
             origin: self.opts.signer().account_id(),
             code: self.code.0.clone(),
-            storage_deposit_limit,
-            determinism: Determinism::Enforced,
-        };
-        state_call(&self.rpc, "ContractsApi_upload_code", call_request).await
+            storage_deposit_limit: self.opts.storage_deposit_limit(),
+            determinism,
+        }
     }
 
     /// Uploads contract code to the blockchain with specified options.
@@ -144,25 +216,62 @@ where
     /// The function handles the necessary interactions with the blockchain's runtime
     /// API to ensure the successful upload of the code.
     pub async fn upload_code(&self) -> Result<UploadResult<C>, ErrorVariant> {
-        let storage_deposit_limit = self.opts.storage_deposit_limit();
+        let storage_deposit_limit = self.estimate_storage_deposit_limit().await?;
 
-        let call = UploadCode::new(
-            self.code.clone(),
-            storage_deposit_limit,
-            Determinism::Enforced,
-        )
-        .build();
+        let call =
+            UploadCode::new(self.code.clone(), storage_deposit_limit, self.determinism)
+                .build(self.backend);
 
-        let events =
-            submit_extrinsic(&self.client, &self.rpc, &call, self.opts.signer()).await?;
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &call, &self.opts).await?;
 
-        let code_stored = events.find_first::<CodeStored<C::Hash>>()?;
+        let code_stored = find_contracts_events::<_, CodeStored<C::Hash>>(
+            &events,
+            self.backend,
+            &self.client.metadata(),
+        )
+        .next()
+        .transpose()?;
         Ok(UploadResult {
             code_stored,
             events,
+            receipt,
         })
     }
 
+    /// Estimates the fee of uploading the code, excluding the tip.
+    pub async fn estimate_fee(&self) -> Result<u128> {
+        let call = UploadCode::new(
+            self.code.clone(),
+            self.opts.storage_deposit_limit(),
+            self.determinism,
+        )
+        .build(self.backend);
+        Ok(estimate_fee(&self.client, &call, &self.opts).await?)
+    }
+
+    /// Returns the storage deposit limit to submit the upload with.
+    ///
+    /// Unless a limit is set or the deposit is unlimited, the upload is dry-run to derive
+    /// the limit from the charged deposit, see
+    /// [`ExtrinsicOpts::storage_deposit_limit_for`].
+    pub async fn estimate_storage_deposit_limit(&self) -> Result<Option<E::Balance>> {
+        if !self.opts.estimates_storage_deposit_limit() {
+            return Ok(self.opts.storage_deposit_limit())
+        }
+        match self.upload_code_rpc().await? {
+            Ok(result) => {
+                let deposit = StorageDeposit::Charge(result.deposit);
+                Ok(self.opts.storage_deposit_limit_for(&deposit))
+            }
+            Err(ref err) => {
+                let object =
+                    ErrorVariant::from_dispatch_error(err, &self.client.metadata())?;
+                Err(anyhow!("Pre-submission dry-run failed. Error: {}", object))
+            }
+        }
+    }
+
     /// Returns the extrinsic options.
     pub fn opts(&self) -> &ExtrinsicOpts<C, E, Signer> {
         &self.opts
@@ -173,20 +282,32 @@ where
         &self.client
     }
 
+    /// Returns the pallet implementing smart contracts on the chain.
+    pub fn backend(&self) -> &'static dyn ContractsBackend {
+        self.backend
+    }
+
     /// Returns the code.
     pub fn code(&self) -> &WasmCode {
         &self.code
     }
 
-    /// Returns the contract message transcoder.
-    pub fn transcoder(&self) -> &ContractMessageTranscoder {
-        &self.transcoder
+    /// Returns whether the uploaded code must be deterministic.
+    pub fn determinism(&self) -> Determinism {
+        self.determinism
+    }
+
+    /// Returns the contract message transcoder, if the metadata was checked.
+    pub fn transcoder(&self) -> Option<&ContractMessageTranscoder> {
+        self.transcoder.as_ref()
     }
 }
 
 /// A struct that encodes RPC parameters required for a call to upload a new code.
+///
+/// `pallet-revive` takes no `determinism`, it is passed as `()` then.
 #[derive(Encode)]
-struct CodeUploadRequest<AccountId, Balance> {
+struct CodeUploadRequest<AccountId, Balance, Determinism> {
     origin: AccountId,
     code: Vec<u8>,
     storage_deposit_limit: Option<Balance>,
@@ -197,26 +318,52 @@ struct CodeUploadRequest<AccountId, Balance> {
 pub struct UploadResult<C: Config> {
     pub code_stored: Option<CodeStored<C::Hash>>,
     pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
 }
 
 /// Copied from `pallet-contracts` to additionally implement `scale_encode::EncodeAsType`.
-#[allow(dead_code)]
-#[derive(Debug, Encode, EncodeAsType)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Encode, EncodeAsType)]
 #[encode_as_type(crate_path = "subxt::ext::scale_encode")]
-pub(crate) enum Determinism {
+pub enum Determinism {
     /// The execution should be deterministic and hence no indeterministic instructions
     /// are allowed.
     ///
     /// Dispatchables always use this mode in order to make on-chain execution
     /// deterministic.
+    #[default]
     Enforced,
     /// Allow calling or uploading an indeterministic code.
     ///
     /// This is only possible when calling into `pallet-contracts` directly via
-    /// [`crate::Pallet::bare_call`].
+    /// `Pallet::bare_call`.
     ///
     /// # Note
     ///
     /// **Never** use this mode for on-chain execution.
     Relaxed,
 }
+
+impl FromStr for Determinism {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "enforced" => Ok(Self::Enforced),
+            "relaxed" => Ok(Self::Relaxed),
+            _ => {
+                Err(anyhow::anyhow!(
+                    "Unknown determinism `{s}`, expected `enforced` or `relaxed`"
+                ))
+            }
+        }
+    }
+}
+
+impl Display for Determinism {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enforced => write!(f, "enforced"),
+            Self::Relaxed => write!(f, "relaxed"),
+        }
+    }
+}
@@ -0,0 +1,527 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dispatching contract calls on another chain with an XCM `Transact`.
+
+use super::{
+    estimate_fee,
+    submit_extrinsic,
+    ErrorVariant,
+    ExtrinsicReceipt,
+};
+use crate::{
+    extrinsic_opts::ExtrinsicOpts,
+    online_client,
+    rpc_client,
+    rpc_client::NodeRpc,
+    RpcOptions,
+};
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+use ink_env::Environment;
+use scale::{
+    Encode,
+    Output,
+};
+use sp_weights::Weight;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    str::FromStr,
+};
+use subxt::{
+    blocks::ExtrinsicEvents,
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    tx,
+    Config,
+    Metadata,
+    OnlineClient,
+};
+
+/// The pallets sending XCM messages: `PolkadotXcm` on parachains and `XcmPallet` on
+/// relay chains.
+const XCM_PALLETS: [&str; 2] = ["PolkadotXcm", "XcmPallet"];
+
+/// The maximum number of junctions of an XCM location.
+const MAX_JUNCTIONS: usize = 8;
+
+/// A location of the XCM consensus system, relative to a chain.
+///
+/// Written as its junctions separated by `/`, each `..` leading to the parent, e.g.
+/// `../Parachain(1000)/PalletInstance(50)/GeneralIndex(1984)`, or `here` for the chain
+/// itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XcmLocation {
+    pub parents: u8,
+    pub junctions: Vec<Junction>,
+}
+
+impl XcmLocation {
+    /// Returns the location of the parachain `para_id`, as seen from a sibling
+    /// parachain or, if `from_relay_chain`, from the relay chain.
+    pub fn parachain(para_id: u32, from_relay_chain: bool) -> Self {
+        Self {
+            parents: if from_relay_chain { 0 } else { 1 },
+            junctions: vec![Junction::Parachain(para_id)],
+        }
+    }
+}
+
+impl Encode for XcmLocation {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        self.parents.encode_to(dest);
+        // the variants `Here`, `X1`, ..., `X8` of `Junctions` are indexed by the number
+        // of junctions
+        dest.push_byte(self.junctions.len() as u8);
+        for junction in &self.junctions {
+            junction.encode_to(dest);
+        }
+    }
+}
+
+impl Display for XcmLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        if self.parents == 0 && self.junctions.is_empty() {
+            return write!(f, "here")
+        }
+        let segments = (0..self.parents)
+            .map(|_| "..".to_string())
+            .chain(self.junctions.iter().map(ToString::to_string))
+            .collect::<Vec<_>>();
+        write!(f, "{}", segments.join("/"))
+    }
+}
+
+impl FromStr for XcmLocation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut location = Self {
+            parents: 0,
+            junctions: Vec::new(),
+        };
+        if s == "here" {
+            return Ok(location)
+        }
+        for segment in s.split('/') {
+            if segment == ".." {
+                if !location.junctions.is_empty() {
+                    return Err(anyhow!(
+                        "`..` must precede the junctions of the location '{s}'"
+                    ))
+                }
+                location.parents += 1;
+            } else {
+                location.junctions.push(segment.parse()?);
+            }
+        }
+        if location.junctions.len() > MAX_JUNCTIONS {
+            return Err(anyhow!(
+                "The location '{s}' has more than {MAX_JUNCTIONS} junctions"
+            ))
+        }
+        Ok(location)
+    }
+}
+
+/// A junction of an [`XcmLocation`].
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub enum Junction {
+    #[codec(index = 0)]
+    Parachain(#[codec(compact)] u32),
+    #[codec(index = 4)]
+    PalletInstance(u8),
+    #[codec(index = 5)]
+    GeneralIndex(#[codec(compact)] u128),
+}
+
+impl Display for Junction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Parachain(id) => write!(f, "Parachain({id})"),
+            Self::PalletInstance(index) => write!(f, "PalletInstance({index})"),
+            Self::GeneralIndex(index) => write!(f, "GeneralIndex({index})"),
+        }
+    }
+}
+
+impl FromStr for Junction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(|| {
+                anyhow!("Invalid junction '{s}', expected e.g. Parachain(1000)")
+            })?;
+        let invalid = |_| anyhow!("Invalid value of the junction '{s}'");
+        match name {
+            "Parachain" => Ok(Self::Parachain(value.parse().map_err(invalid)?)),
+            "PalletInstance" => {
+                Ok(Self::PalletInstance(value.parse().map_err(invalid)?))
+            }
+            "GeneralIndex" => Ok(Self::GeneralIndex(value.parse().map_err(invalid)?)),
+            _ => {
+                Err(anyhow!(
+                    "Unsupported junction '{name}', expected Parachain, \
+                    PalletInstance or GeneralIndex"
+                ))
+            }
+        }
+    }
+}
+
+/// The origin a call dispatched by an XCM `Transact` is executed with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode)]
+pub enum OriginKind {
+    /// The native origin of the sending chain, e.g. a parachain origin.
+    #[codec(index = 0)]
+    Native,
+    /// The account derived from the location of the sender, e.g. the sovereign account
+    /// of the sending chain.
+    #[codec(index = 1)]
+    #[default]
+    SovereignAccount,
+    /// The root origin, if the sender is trusted as such.
+    #[codec(index = 2)]
+    Superuser,
+    /// The XCM origin of the sender.
+    #[codec(index = 3)]
+    Xcm,
+}
+
+impl Display for OriginKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Native => write!(f, "native"),
+            Self::SovereignAccount => write!(f, "sovereign-account"),
+            Self::Superuser => write!(f, "superuser"),
+            Self::Xcm => write!(f, "xcm"),
+        }
+    }
+}
+
+impl FromStr for OriginKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native" => Ok(Self::Native),
+            "sovereign-account" => Ok(Self::SovereignAccount),
+            "superuser" => Ok(Self::Superuser),
+            "xcm" => Ok(Self::Xcm),
+            _ => {
+                Err(anyhow!(
+                    "Unknown origin kind '{s}', expected native, sovereign-account, \
+                    superuser or xcm"
+                ))
+            }
+        }
+    }
+}
+
+/// An XCM message dispatching a call on the destination chain with a `Transact`, after
+/// buying its execution with the fee asset.
+#[derive(Clone, Debug)]
+pub struct XcmTransact {
+    /// The destination chain, as seen from the sending chain.
+    pub dest: XcmLocation,
+    /// The asset paying for the execution, as seen from the destination chain.
+    pub fee_asset: XcmLocation,
+    /// The amount of the fee asset withdrawn to pay for the execution.
+    pub fee_amount: u128,
+    /// The maximum weight of the dispatched call.
+    pub require_weight_at_most: Weight,
+    /// The origin the call is dispatched with.
+    pub origin_kind: OriginKind,
+    /// The location the unused fees are deposited to, as seen from the destination
+    /// chain. The unused fees are trapped otherwise.
+    pub refund_to: Option<XcmLocation>,
+}
+
+impl XcmTransact {
+    /// Returns the SCALE encoded `VersionedLocation` of the destination.
+    pub fn versioned_dest(&self) -> Vec<u8> {
+        VersionedLocation::V3(self.dest.clone()).encode()
+    }
+
+    /// Returns the SCALE encoded `VersionedXcm` message dispatching the encoded `call`.
+    pub fn versioned_message(&self, call: Vec<u8>) -> Vec<u8> {
+        let fees = Asset {
+            id: AssetId::Concrete(self.fee_asset.clone()),
+            fun: Fungibility::Fungible(self.fee_amount),
+        };
+        let mut instructions = vec![
+            Instruction::WithdrawAsset(vec![fees.clone()]),
+            Instruction::BuyExecution {
+                fees,
+                weight_limit: WeightLimit::Unlimited,
+            },
+            Instruction::Transact {
+                origin_kind: self.origin_kind,
+                require_weight_at_most: self.require_weight_at_most,
+                call,
+            },
+        ];
+        if let Some(beneficiary) = &self.refund_to {
+            instructions.push(Instruction::RefundSurplus);
+            instructions.push(Instruction::DepositAsset {
+                assets: AssetFilter::Wild(WildAsset::AllCounted(1)),
+                beneficiary: beneficiary.clone(),
+            });
+        }
+        VersionedXcm::V3(instructions).encode()
+    }
+}
+
+// The following types mirror the types of XCM v3 from `staging-xcm`, with only the
+// variants needed to encode the messages of `XcmTransact`.
+
+#[derive(Encode)]
+enum VersionedLocation {
+    #[codec(index = 3)]
+    V3(XcmLocation),
+}
+
+#[derive(Encode)]
+enum VersionedXcm {
+    #[codec(index = 3)]
+    V3(Vec<Instruction>),
+}
+
+#[derive(Encode)]
+enum Instruction {
+    #[codec(index = 0)]
+    WithdrawAsset(Vec<Asset>),
+    #[codec(index = 6)]
+    Transact {
+        origin_kind: OriginKind,
+        require_weight_at_most: Weight,
+        call: Vec<u8>,
+    },
+    #[codec(index = 13)]
+    DepositAsset {
+        assets: AssetFilter,
+        beneficiary: XcmLocation,
+    },
+    #[codec(index = 19)]
+    BuyExecution {
+        fees: Asset,
+        weight_limit: WeightLimit,
+    },
+    #[codec(index = 20)]
+    RefundSurplus,
+}
+
+#[derive(Clone, Encode)]
+struct Asset {
+    id: AssetId,
+    fun: Fungibility,
+}
+
+#[derive(Clone, Encode)]
+enum AssetId {
+    #[codec(index = 0)]
+    Concrete(XcmLocation),
+}
+
+#[derive(Clone, Encode)]
+enum Fungibility {
+    #[codec(index = 0)]
+    Fungible(#[codec(compact)] u128),
+}
+
+#[derive(Encode)]
+enum WeightLimit {
+    #[codec(index = 0)]
+    Unlimited,
+}
+
+#[derive(Encode)]
+enum AssetFilter {
+    #[codec(index = 1)]
+    Wild(WildAsset),
+}
+
+#[derive(Encode)]
+enum WildAsset {
+    #[codec(index = 2)]
+    AllCounted(#[codec(compact)] u32),
+}
+
+/// The payload of a `send` extrinsic of the XCM pallet, sending the already encoded
+/// `message` to `dest`.
+struct XcmSend {
+    dest: Vec<u8>,
+    message: Vec<u8>,
+}
+
+impl tx::TxPayload for XcmSend {
+    fn encode_call_data_to(
+        &self,
+        metadata: &Metadata,
+        out: &mut Vec<u8>,
+    ) -> Result<(), subxt::Error> {
+        let pallet = XCM_PALLETS
+            .iter()
+            .find_map(|name| metadata.pallet_by_name(name))
+            .ok_or_else(|| {
+                subxt::Error::Other(format!(
+                    "The chain has none of the XCM pallets {}",
+                    XCM_PALLETS.join(", ")
+                ))
+            })?;
+        let call = pallet.call_variant_by_name("send").ok_or_else(|| {
+            subxt::Error::Other(format!("The {} pallet has no send call", pallet.name()))
+        })?;
+        pallet.index().encode_to(out);
+        call.index.encode_to(out);
+        out.extend_from_slice(&self.dest);
+        out.extend_from_slice(&self.message);
+        Ok(())
+    }
+}
+
+/// Sends XCM messages with the `send` extrinsic of the XCM pallet of a chain.
+pub struct XcmSender<C: Config> {
+    rpc: NodeRpc<C>,
+    client: OnlineClient<C>,
+}
+
+impl<C: Config> XcmSender<C>
+where
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+{
+    /// Connects to the node of the sending chain at `url`.
+    pub async fn new(url: &str, rpc_options: RpcOptions) -> Result<Self> {
+        let rpc = rpc_client(url, rpc_options.clone()).await?;
+        let client = online_client(rpc.clone(), rpc_options).await?;
+        Ok(Self {
+            rpc: NodeRpc::new(rpc),
+            client,
+        })
+    }
+
+    /// Estimates the fee of sending the `transact` of the encoded `call`, excluding the
+    /// tip.
+    pub async fn estimate_fee<E: Environment, Signer: tx::Signer<C> + Clone>(
+        &self,
+        transact: &XcmTransact,
+        call: Vec<u8>,
+        opts: &ExtrinsicOpts<C, E, Signer>,
+    ) -> Result<u128> {
+        let payload = XcmSend {
+            dest: transact.versioned_dest(),
+            message: transact.versioned_message(call),
+        };
+        Ok(estimate_fee(&self.client, &payload, opts).await?)
+    }
+
+    /// Sends the `transact` of the encoded `call`, signed by the signer of the `opts`.
+    pub async fn send<E: Environment, Signer: tx::Signer<C> + Clone>(
+        &self,
+        transact: &XcmTransact,
+        call: Vec<u8>,
+        opts: &ExtrinsicOpts<C, E, Signer>,
+    ) -> Result<XcmSendResult<C>, ErrorVariant> {
+        let payload = XcmSend {
+            dest: transact.versioned_dest(),
+            message: transact.versioned_message(call),
+        };
+        let (events, receipt) =
+            submit_extrinsic(&self.client, &self.rpc, &payload, opts).await?;
+        Ok(XcmSendResult { events, receipt })
+    }
+
+    /// Returns the runtime metadata of the sending chain, e.g. to decode the events.
+    pub fn metadata(&self) -> Metadata {
+        self.client.metadata()
+    }
+}
+
+/// The result of sending an XCM message.
+pub struct XcmSendResult<C: Config> {
+    pub events: ExtrinsicEvents<C>,
+    pub receipt: ExtrinsicReceipt<C::Hash>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_roundtrips() {
+        let locations = [
+            "here",
+            "../Parachain(1000)",
+            "PalletInstance(50)/GeneralIndex(1984)",
+        ];
+        for location in locations {
+            assert_eq!(location.parse::<XcmLocation>().unwrap().to_string(), location);
+        }
+        assert!("Parachain(1000)/..".parse::<XcmLocation>().is_err());
+        assert!("AccountKey20(0x00)".parse::<XcmLocation>().is_err());
+    }
+
+    #[test]
+    fn encodes_versioned_dest() {
+        let transact = XcmTransact {
+            dest: XcmLocation::parachain(1000, false),
+            fee_asset: "here".parse().unwrap(),
+            fee_amount: 100,
+            require_weight_at_most: Weight::from_parts(1000, 0),
+            origin_kind: OriginKind::default(),
+            refund_to: None,
+        };
+
+        assert_eq!(hex::encode(transact.versioned_dest()), "03010100a10f");
+    }
+
+    #[test]
+    fn encodes_versioned_message() {
+        let mut transact = XcmTransact {
+            dest: XcmLocation::parachain(1000, false),
+            fee_asset: "here".parse().unwrap(),
+            fee_amount: 100,
+            require_weight_at_most: Weight::from_parts(1000, 0),
+            origin_kind: OriginKind::default(),
+            refund_to: None,
+        };
+        let fees = "000000009101";
+
+        assert_eq!(
+            hex::encode(transact.versioned_message(vec![1, 2])),
+            format!("030c0004{fees}13{fees}000601a10f00080102")
+        );
+
+        transact.refund_to = Some("../Parachain(2000)".parse().unwrap());
+        assert_eq!(
+            hex::encode(transact.versioned_message(vec![1, 2])),
+            format!("03140004{fees}13{fees}000601a10f00080102140d010204010100411f")
+        );
+    }
+}
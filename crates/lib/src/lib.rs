@@ -0,0 +1,281 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The library facade of `cargo-contract` for third-party tooling, like IDEs and GUIs
+//! building, deploying and interacting with ink! contracts.
+//!
+//! The modules group the items needed for each workflow:
+//!
+//! - [`artifacts`]: loading the code and metadata of a contract.
+//! - [`transcode`]: encoding and decoding of messages, constructors and events.
+//! - [`build`]: building contracts.
+//! - [`extrinsics`]: connecting to a node, dry-runs and submissions of `upload`,
+//!   `instantiate`, `call` and `remove` extrinsics, and inspecting blocks.
+//! - [`output`]: rendering of the output, e.g. of warnings.
+//!
+//! # Stability
+//!
+//! The items of this crate follow semantic versioning, breaking changes to them are only
+//! made in major releases. Items of the underlying `contract-*` crates which are not
+//! exported here are internal to `cargo-contract` and may change in any release.
+//!
+//! # Side effects
+//!
+//! Nothing is printed to stdout. The defaults of this crate use
+//! [`build::Verbosity::Quiet`], so nothing is printed to stderr either, apart from
+//! warnings like the one about an unsupported ink! version of a contract. Call
+//! [`diagnostics_to_tracing`] to emit those as `tracing` events instead, or install an
+//! [`output::Renderer`] with [`output::set_renderer`] to receive them, e.g. in a TUI or
+//! a web UI. The progress of long running operations is reported to a
+//! [`build::ProgressReporter`].
+
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+use tempfile as _;
+
+/// Emits the diagnostics of the libraries as `tracing` events, instead of printing them
+/// to stderr.
+///
+/// Also disables colored output. Has no effect if the format of the diagnostics was
+/// already set.
+pub fn diagnostics_to_tracing() {
+    contract_build::output::set_log_format(contract_build::output::LogFormat::Json)
+}
+
+pub mod artifacts {
+    //! Loading the code and metadata of a contract.
+
+    pub use contract_extrinsics::{
+        ContractArtifacts,
+        WasmCode,
+    };
+    pub use contract_metadata::{
+        ContractMetadata,
+        SourceCompiler,
+        SourceLanguage,
+    };
+
+    use anyhow::Result;
+    use std::path::{
+        Path,
+        PathBuf,
+    };
+
+    /// Loads the artifacts from a `.contract` bundle, a `.json` metadata file or a
+    /// `.wasm` file, together with the metadata next to it.
+    pub fn load(path: impl AsRef<Path>) -> Result<ContractArtifacts> {
+        ContractArtifacts::from_manifest_or_file(None, Some(&path.as_ref().to_owned()))
+    }
+
+    /// Loads the artifacts of the contract built from the `Cargo.toml` at
+    /// `manifest_path`, or in the current directory if `None`.
+    pub fn load_from_manifest(manifest_path: Option<&Path>) -> Result<ContractArtifacts> {
+        ContractArtifacts::from_manifest_or_file(
+            manifest_path.map(PathBuf::from).as_ref(),
+            None,
+        )
+    }
+}
+
+pub mod transcode {
+    //! Encoding and decoding of messages, constructors and events.
+
+    pub use contract_transcode::{
+        check_compatibility,
+        compute_selector,
+        ink_metadata,
+        AccountId32,
+        CompatibilityReport,
+        ContractMessageTranscoder,
+        EnvType,
+        ExampleValue,
+        Hex,
+        Incompatibility,
+        Map,
+        ReturnError,
+        ReturnErrorSource,
+        SelectorKind,
+        Tuple,
+        TypeMappings,
+        Value,
+    };
+}
+
+pub mod build {
+    //! Building contracts.
+
+    pub use contract_build::{
+        audit::AuditConfig,
+        execute,
+        execute_workspace,
+        progress::{
+            Operation,
+            ProgressEvent,
+            ProgressReporter,
+        },
+        BuildArtifacts,
+        BuildMode,
+        BuildResult,
+        ExecuteArgs,
+        Features,
+        ManifestPath,
+        MetadataArtifacts,
+        Network,
+        OptimizationPasses,
+        OptimizationResult,
+        Target,
+        Verbosity,
+    };
+
+    /// Returns the arguments to build the contract at `manifest_path` in release mode,
+    /// without printing anything.
+    ///
+    /// The build is run with [`execute`].
+    pub fn args(manifest_path: ManifestPath) -> ExecuteArgs {
+        ExecuteArgs {
+            manifest_path,
+            verbosity: Verbosity::Quiet,
+            build_mode: BuildMode::Release,
+            ..Default::default()
+        }
+    }
+}
+
+pub mod extrinsics {
+    //! Connecting to a node, dry-runs and submissions of `upload`, `instantiate`, `call`
+    //! and `remove` extrinsics, and inspecting blocks.
+
+    pub use contract_extrinsics::{
+        call_wrapper,
+        code_exists,
+        decode_contract_calls,
+        decode_events,
+        derive_contract_address,
+        fetch_all_contracts,
+        fetch_contract_info,
+        inspect_block,
+        online_client,
+        pallet_contracts_primitives::{
+            CodeUploadResult,
+            ContractExecResult,
+            ContractInstantiateResult,
+            StorageDeposit,
+        },
+        rpc_client,
+        ss58,
+        url_to_string,
+        BalanceVariant,
+        BatchCallExec,
+        BatchCallResult,
+        CallCommandBuilder,
+        CallExec,
+        CallResult,
+        CallWrapper,
+        Code,
+        CodeStored,
+        ContractCallData,
+        ContractEmitted,
+        ContractInfo,
+        ContractInstantiated,
+        ContractsBlock,
+        ContractsEvent,
+        ContractsExtrinsic,
+        Determinism,
+        DisplayEvents,
+        DryRunCost,
+        ErrorVariant,
+        EventFilter,
+        EventRecord,
+        ExtrinsicOpts,
+        ExtrinsicOptsBuilder,
+        ExtrinsicReceipt,
+        InstantiateArgs,
+        InstantiateCommandBuilder,
+        InstantiateDryRunResult,
+        InstantiateExec,
+        InstantiateExecResult,
+        NonceProvider,
+        RemoveCommandBuilder,
+        RemoveExec,
+        RemoveResult,
+        RpcOptions,
+        TokenMetadata,
+        UploadCommandBuilder,
+        UploadExec,
+        UploadResult,
+        DEFAULT_FINALIZATION_TIMEOUT,
+    };
+
+    use contract_build::Verbosity;
+    use ink_env::Environment;
+    use subxt::{
+        tx,
+        Config,
+    };
+
+    /// Returns a builder for the options of an extrinsic signed by `signer`, which
+    /// doesn't print anything.
+    pub fn opts<C, E, Signer>(signer: Signer) -> ExtrinsicOptsBuilder<C, E, Signer>
+    where
+        C: Config,
+        E: Environment,
+        Signer: tx::Signer<C> + Clone,
+    {
+        ExtrinsicOptsBuilder::new(signer).verbosity(Verbosity::Quiet)
+    }
+}
+
+pub mod output {
+    //! Rendering of the output, e.g. of warnings, by a [`Renderer`].
+
+    pub use contract_build::output::{
+        renderer,
+        set_renderer,
+        JsonRenderer,
+        Renderer,
+        TerminalRenderer,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_wasm_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contract.wasm");
+        std::fs::write(&path, [0, 97, 115, 109, 1, 0, 0, 0]).unwrap();
+
+        let artifacts = artifacts::load(&path).unwrap();
+
+        assert_eq!(artifacts.artifact_path(), path);
+        assert!(artifacts.metadata().is_err());
+        assert_eq!(
+            artifacts.code.as_ref().unwrap().code_hash(),
+            contract_build::code_hash(&[0, 97, 115, 109, 1, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn build_args_are_quiet() {
+        let args = build::args(build::ManifestPath::new("Cargo.toml").unwrap());
+
+        assert_eq!(args.verbosity, build::Verbosity::Quiet);
+        assert_eq!(args.build_mode, build::BuildMode::Release);
+    }
+}
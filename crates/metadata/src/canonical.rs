@@ -0,0 +1,171 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Canonical serialization of the contract metadata.
+//!
+//! The canonical form is the JSON serialization of the metadata where
+//!
+//! - the keys of all objects are sorted by their UTF-8 bytes,
+//! - no whitespace is emitted outside of strings,
+//! - strings only escape `"`, `\` and control characters, all other characters are
+//!   emitted as UTF-8,
+//! - integers are emitted without exponent, fraction or leading zeros.
+//!
+//! The hash of the metadata is the 32 byte BLAKE2b hash of its canonical form.
+
+use crate::ContractMetadata;
+use anyhow::Result;
+use blake2::digest::{
+    consts::U32,
+    Digest as _,
+};
+use serde_json::{
+    Map,
+    Value,
+};
+
+impl ContractMetadata {
+    /// Returns the canonical serialization of the metadata.
+    ///
+    /// See the [module documentation](self) for the format.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        to_canonical_bytes(serde_json::to_value(self)?)
+    }
+
+    /// Returns the BLAKE2b-256 hash of the [canonical bytes](Self::canonical_bytes).
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        let bytes = self.canonical_bytes()?;
+        Ok(blake2::Blake2b::<U32>::digest(bytes).into())
+    }
+
+    /// Returns the BLAKE2b-256 hash of the canonical serialization of the ABI, i.e. the
+    /// ink! metadata without the `source`, `contract` and `user` sections.
+    ///
+    /// Unlike [`Self::hash`] it does not depend on the Wasm code, so it can be embedded
+    /// into the code to link it to its ABI.
+    pub fn abi_hash(&self) -> Result<[u8; 32]> {
+        let bytes = to_canonical_bytes(Value::Object(self.abi.clone()))?;
+        Ok(blake2::Blake2b::<U32>::digest(bytes).into())
+    }
+}
+
+/// Returns the canonical serialization of `value`.
+pub(crate) fn to_canonical_bytes(value: Value) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&canonicalize(value))?)
+}
+
+/// Sorts the keys of all objects in `value`.
+///
+/// The order is established explicitly, since `serde_json` preserves the insertion
+/// order of keys if its `preserve_order` feature is enabled by any crate in the
+/// dependency graph.
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(canonicalize).collect())
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeHash;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn metadata(json: &str) -> ContractMetadata {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const METADATA: &str = r#"{
+        "source": {
+            "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "language": "ink! 5.0.0",
+            "compiler": "rustc 1.78.0"
+        },
+        "contract": {
+            "name": "flipper",
+            "version": "0.1.0",
+            "authors": ["Use Ink <admin@use.ink>"]
+        },
+        "image": null,
+        "version": 5,
+        "spec": { "messages": [{ "label": "flip", "docs": ["Flips ✓"] }] }
+    }"#;
+
+    #[test]
+    fn canonical_bytes_are_sorted_and_compact() {
+        let bytes = to_canonical_bytes(json!({
+            "b": [{ "d": 1, "c": "x y" }],
+            "a": null
+        }))
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            r#"{"a":null,"b":[{"c":"x y","d":1}]}"#
+        );
+    }
+
+    #[test]
+    fn canonical_bytes_ignore_formatting() {
+        let original = metadata(METADATA);
+        let reformatted = metadata(&serde_json::to_string(&original).unwrap());
+
+        assert_eq!(
+            original.canonical_bytes().unwrap(),
+            reformatted.canonical_bytes().unwrap()
+        );
+        assert_eq!(original.hash().unwrap(), reformatted.hash().unwrap());
+    }
+
+    #[test]
+    fn canonical_bytes_emit_utf8() {
+        let bytes = metadata(METADATA).canonical_bytes().unwrap();
+
+        assert!(String::from_utf8(bytes).unwrap().contains("Flips ✓"));
+    }
+
+    #[test]
+    fn hash_changes_with_content() {
+        let mut modified = metadata(METADATA);
+        modified.abi.insert("version".into(), Value::from(4));
+
+        assert_ne!(metadata(METADATA).hash().unwrap(), modified.hash().unwrap());
+    }
+
+    #[test]
+    fn abi_hash_ignores_source() {
+        let original = metadata(METADATA);
+        let mut rebuilt = original.clone();
+        rebuilt.source.hash = CodeHash([1; 32]);
+
+        assert_eq!(original.abi_hash().unwrap(), rebuilt.abi_hash().unwrap());
+        assert_ne!(original.hash().unwrap(), rebuilt.hash().unwrap());
+    }
+}
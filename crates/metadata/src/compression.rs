@@ -0,0 +1,220 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compression of the Wasm code embedded in `.contract` bundles.
+//!
+//! The encoding of the compressed code is recorded in `source.wasm_encoding`, bundles
+//! without it contain the plain Wasm code.
+
+use crate::{
+    ContractMetadata,
+    SourceWasm,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    io::{
+        Read,
+        Write,
+    },
+    str::FromStr,
+};
+
+/// Compression level of zstd, bundles are compressed once but stored and transferred
+/// many times.
+const ZSTD_LEVEL: i32 = 19;
+
+/// The maximum size of decompressed Wasm code, far above the code size limits of chains,
+/// so that a malicious bundle can't exhaust the memory.
+const MAX_DECOMPRESSED_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The compression of the Wasm code in `source.wasm`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WasmEncoding {
+    Gzip,
+    Zstd,
+}
+
+impl WasmEncoding {
+    /// Compresses the `wasm` code.
+    pub fn compress(&self, wasm: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(
+                    Vec::new(),
+                    flate2::Compression::best(),
+                );
+                encoder.write_all(wasm)?;
+                Ok(encoder.finish()?)
+            }
+            Self::Zstd => Ok(zstd::encode_all(wasm, ZSTD_LEVEL)?),
+        }
+    }
+
+    /// Decompresses the `compressed` Wasm code.
+    ///
+    /// Fails if the decompressed code exceeds [`MAX_DECOMPRESSED_SIZE`].
+    pub fn decompress(&self, compressed: &[u8]) -> Result<Vec<u8>> {
+        let decoder: Box<dyn Read> = match self {
+            Self::Gzip => Box::new(flate2::read::GzDecoder::new(compressed)),
+            Self::Zstd => Box::new(zstd::stream::read::Decoder::new(compressed)?),
+        };
+        let mut wasm = Vec::new();
+        decoder
+            .take(MAX_DECOMPRESSED_SIZE + 1)
+            .read_to_end(&mut wasm)
+            .with_context(|| {
+                format!("Failed to decompress the {self} encoded Wasm code")
+            })?;
+        if wasm.len() as u64 > MAX_DECOMPRESSED_SIZE {
+            anyhow::bail!(
+                "The {self} encoded Wasm code exceeds {MAX_DECOMPRESSED_SIZE} bytes when \
+                 decompressed"
+            )
+        }
+        Ok(wasm)
+    }
+}
+
+impl Display for WasmEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl FromStr for WasmEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(format!("Unknown Wasm encoding '{s}', expected gzip or zstd")),
+        }
+    }
+}
+
+impl ContractMetadata {
+    /// Compresses the Wasm code in `source.wasm` with the `encoding`.
+    ///
+    /// Compressed code is decompressed first, metadata without the Wasm code is left
+    /// unchanged.
+    pub fn compress_wasm(&mut self, encoding: WasmEncoding) -> Result<()> {
+        self.decompress_wasm()?;
+        if let Some(wasm) = &self.source.wasm {
+            let compressed = encoding.compress(&wasm.0)?;
+            self.source.wasm = Some(SourceWasm::new(compressed));
+            self.source.wasm_encoding = Some(encoding);
+        }
+        Ok(())
+    }
+
+    /// Decompresses the Wasm code in `source.wasm` if it is compressed.
+    pub fn decompress_wasm(&mut self) -> Result<()> {
+        let Some(encoding) = self.source.wasm_encoding else {
+            return Ok(())
+        };
+        if let Some(compressed) = &self.source.wasm {
+            let wasm = encoding.decompress(&compressed.0)?;
+            self.source.wasm = Some(SourceWasm::new(wasm));
+        }
+        self.source.wasm_encoding = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CodeHash,
+        Compiler,
+        Contract,
+        Language,
+        Source,
+        SourceCompiler,
+        SourceLanguage,
+    };
+    use semver::Version;
+    use serde_json::Map;
+
+    fn metadata(wasm: Vec<u8>) -> ContractMetadata {
+        let source = Source::new(
+            Some(SourceWasm::new(wasm)),
+            CodeHash([0u8; 32]),
+            SourceLanguage::new(Language::Ink, Version::new(5, 0, 0)),
+            SourceCompiler::new(Compiler::RustC, Version::parse("1.78.0").unwrap()),
+            None,
+        );
+        let contract = Contract::builder()
+            .name("flipper")
+            .version(Version::new(0, 1, 0))
+            .authors(vec!["Use Ink <admin@use.ink>".to_string()])
+            .build()
+            .unwrap();
+        ContractMetadata::new(source, contract, None, None, Map::new())
+    }
+
+    #[test]
+    fn decompressed_size_is_limited() {
+        let wasm = vec![0; MAX_DECOMPRESSED_SIZE as usize + 1];
+        for encoding in [WasmEncoding::Gzip, WasmEncoding::Zstd] {
+            let compressed = encoding.compress(&wasm).unwrap();
+
+            let err = encoding.decompress(&compressed).unwrap_err();
+            assert!(err.to_string().contains("exceeds"), "{err}");
+        }
+    }
+
+    #[test]
+    fn compressed_wasm_roundtrips() {
+        let wasm = b"\0asm\x01\0\0\0".repeat(100);
+        for encoding in [WasmEncoding::Gzip, WasmEncoding::Zstd] {
+            let mut metadata = metadata(wasm.clone());
+            metadata.compress_wasm(encoding).unwrap();
+
+            let json = serde_json::to_value(&metadata).unwrap();
+            assert_eq!(json["source"]["wasm_encoding"], encoding.to_string());
+            let mut metadata: ContractMetadata = serde_json::from_value(json).unwrap();
+            assert!(metadata.source.wasm.as_ref().unwrap().0.len() < wasm.len());
+
+            metadata.decompress_wasm().unwrap();
+            assert_eq!(metadata.source.wasm.unwrap().0, wasm);
+            assert_eq!(metadata.source.wasm_encoding, None);
+        }
+    }
+
+    #[test]
+    fn uncompressed_wasm_has_no_encoding() {
+        let json = serde_json::to_value(metadata(vec![0, 1, 2])).unwrap();
+        assert!(json["source"].get("wasm_encoding").is_none());
+    }
+}
@@ -0,0 +1,384 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversion of the ink! ABI metadata between its versions.
+//!
+//! Only the versions `4` and `5` can be converted into each other. The metadata of ink!
+//! 3 contracts (`V3`) uses a different storage layout and type registry format, which
+//! can not be converted.
+
+use crate::ContractMetadata;
+use anyhow::{
+    anyhow,
+    bail,
+    Result,
+};
+use serde_json::{
+    Map,
+    Value,
+};
+use std::{
+    collections::HashSet,
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    str::FromStr,
+};
+
+/// Size of the static buffer of ink! 4 contracts, which was not configurable.
+const DEFAULT_STATIC_BUFFER_SIZE: u64 = 16384;
+
+/// Version of the ink! ABI metadata.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum MetadataVersion {
+    /// Metadata of ink! 3 contracts.
+    V3,
+    /// Metadata of ink! 4 contracts.
+    V4,
+    /// Metadata of ink! 5 contracts.
+    V5,
+}
+
+impl MetadataVersion {
+    /// Detects the version of the ink! ABI metadata.
+    pub fn detect(abi: &Map<String, Value>) -> Result<Self> {
+        if abi.contains_key("V3") {
+            return Ok(Self::V3)
+        }
+        match abi.get("version") {
+            Some(Value::String(version)) if version == "4" => Ok(Self::V4),
+            Some(Value::Number(version)) if version.as_u64() == Some(5) => Ok(Self::V5),
+            Some(version) => bail!("Unsupported ink! metadata version {version}"),
+            None => bail!("The ink! metadata does not specify a version"),
+        }
+    }
+}
+
+impl Display for MetadataVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::V3 => write!(f, "3"),
+            Self::V4 => write!(f, "4"),
+            Self::V5 => write!(f, "5"),
+        }
+    }
+}
+
+impl FromStr for MetadataVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_start_matches(['v', 'V']) {
+            "3" => Ok(Self::V3),
+            "4" => Ok(Self::V4),
+            "5" => Ok(Self::V5),
+            _ => Err(format!("Unknown ink! metadata version '{s}'")),
+        }
+    }
+}
+
+/// The result of converting contract metadata to another version.
+#[derive(Debug)]
+pub struct Conversion {
+    /// The converted metadata.
+    pub metadata: ContractMetadata,
+    /// Incompatibilities between the versions which could not be resolved by the
+    /// conversion.
+    pub incompatibilities: Vec<String>,
+}
+
+impl ContractMetadata {
+    /// Converts the ink! ABI metadata to version `to`.
+    ///
+    /// Errors if no conversion between the versions exists, or the metadata can't be
+    /// represented in the target version.
+    pub fn convert(&self, to: MetadataVersion) -> Result<Conversion> {
+        let from = MetadataVersion::detect(&self.abi)?;
+        let mut metadata = self.clone();
+        let incompatibilities = match (from, to) {
+            (from, to) if from == to => Vec::new(),
+            (MetadataVersion::V4, MetadataVersion::V5) => {
+                v4_to_v5(&mut metadata.abi, &self.contract.name)?
+            }
+            (MetadataVersion::V5, MetadataVersion::V4) => v5_to_v4(&mut metadata.abi)?,
+            (from, to) => {
+                bail!("Converting ink! metadata from version {from} to {to} is not supported")
+            }
+        };
+        Ok(Conversion {
+            metadata,
+            incompatibilities,
+        })
+    }
+}
+
+fn v4_to_v5(abi: &mut Map<String, Value>, contract_name: &str) -> Result<Vec<String>> {
+    let mut incompatibilities = Vec::new();
+    abi.insert("version".into(), Value::from(5));
+
+    if let Some(environment) = spec_mut(abi)?.get_mut("environment") {
+        object_mut(environment, "environment")?
+            .entry("staticBufferSize")
+            .or_insert(DEFAULT_STATIC_BUFFER_SIZE.into());
+    }
+
+    let events = events_mut(abi)?;
+    for event in events.iter_mut() {
+        let event = object_mut(event, "event")?;
+        let label = event_label(event);
+        event.insert("module_path".into(), Value::from(contract_name));
+        event.insert("signature_topic".into(), Value::Null);
+        incompatibilities.push(format!(
+            "Event `{label}` is marked as anonymous, since its signature topic can't \
+            be derived from version 4 metadata"
+        ));
+    }
+    if !events.is_empty() {
+        incompatibilities.push(
+            "Version 4 contracts prefix events with their variant index, which version 5 \
+            decoders don't expect"
+                .into(),
+        );
+    }
+    Ok(incompatibilities)
+}
+
+fn v5_to_v4(abi: &mut Map<String, Value>) -> Result<Vec<String>> {
+    let mut incompatibilities = Vec::new();
+    abi.insert("version".into(), Value::from("4"));
+
+    if let Some(environment) = spec_mut(abi)?.get_mut("environment") {
+        let environment = object_mut(environment, "environment")?;
+        match environment.remove("staticBufferSize") {
+            Some(size) if size.as_u64() != Some(DEFAULT_STATIC_BUFFER_SIZE) => {
+                incompatibilities.push(format!(
+                    "The static buffer size of {size} bytes can't be represented, \
+                    version 4 contracts use {DEFAULT_STATIC_BUFFER_SIZE} bytes"
+                ))
+            }
+            _ => {}
+        }
+    }
+
+    let events = events_mut(abi)?;
+    let mut labels = HashSet::new();
+    for event in events.iter_mut() {
+        let event = object_mut(event, "event")?;
+        event.remove("module_path");
+        event.remove("signature_topic");
+        let label = event_label(event);
+        if !labels.insert(label.clone()) {
+            bail!(
+                "Event `{label}` is defined more than once, which version 4 metadata \
+                can't represent"
+            )
+        }
+    }
+    if !events.is_empty() {
+        incompatibilities.push(
+            "Version 5 contracts don't prefix events with their variant index, which \
+            version 4 decoders expect"
+                .into(),
+        );
+    }
+    Ok(incompatibilities)
+}
+
+fn spec_mut(abi: &mut Map<String, Value>) -> Result<&mut Map<String, Value>> {
+    let spec = abi
+        .get_mut("spec")
+        .ok_or_else(|| anyhow!("The ink! metadata is missing the `spec`"))?;
+    object_mut(spec, "spec")
+}
+
+fn events_mut(abi: &mut Map<String, Value>) -> Result<&mut Vec<Value>> {
+    match spec_mut(abi)?.get_mut("events") {
+        Some(Value::Array(events)) => Ok(events),
+        _ => bail!("The `events` of the ink! metadata are not an array"),
+    }
+}
+
+fn object_mut<'a>(
+    value: &'a mut Value,
+    name: &str,
+) -> Result<&'a mut Map<String, Value>> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("The `{name}` of the ink! metadata is not an object"))
+}
+
+fn event_label(event: &Map<String, Value>) -> String {
+    event
+        .get("label")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn metadata(abi: Value) -> ContractMetadata {
+        let mut metadata = json!({
+            "source": {
+                "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "language": "ink! 5.0.0",
+                "compiler": "rustc 1.78.0"
+            },
+            "contract": {
+                "name": "flipper",
+                "version": "0.1.0",
+                "authors": ["Use Ink <admin@use.ink>"]
+            },
+            "image": null
+        });
+        metadata
+            .as_object_mut()
+            .unwrap()
+            .extend(abi.as_object().unwrap().clone());
+        serde_json::from_value(metadata).unwrap()
+    }
+
+    fn v4() -> Value {
+        json!({
+            "spec": {
+                "environment": { "maxEventTopics": 4 },
+                "events": [{
+                    "args": [],
+                    "docs": [],
+                    "label": "Flipped"
+                }]
+            },
+            "version": "4"
+        })
+    }
+
+    fn v5() -> Value {
+        json!({
+            "spec": {
+                "environment": { "maxEventTopics": 4, "staticBufferSize": 16384 },
+                "events": [{
+                    "args": [],
+                    "docs": [],
+                    "label": "Flipped",
+                    "module_path": "flipper",
+                    "signature_topic": null
+                }]
+            },
+            "version": 5
+        })
+    }
+
+    fn v3() -> Value {
+        json!({ "V3": { "spec": {}, "storage": {}, "types": [] } })
+    }
+
+    fn convert(abi: Value, to: MetadataVersion) -> Result<Conversion> {
+        metadata(abi).convert(to)
+    }
+
+    fn abi(conversion: Conversion) -> Value {
+        Value::Object(conversion.metadata.abi)
+    }
+
+    #[test]
+    fn detects_versions() {
+        let version = |abi: Value| MetadataVersion::detect(abi.as_object().unwrap());
+        assert_eq!(version(v3()).unwrap(), MetadataVersion::V3);
+        assert_eq!(version(v4()).unwrap(), MetadataVersion::V4);
+        assert_eq!(version(v5()).unwrap(), MetadataVersion::V5);
+        assert!(version(json!({ "version": "2" })).is_err());
+        assert!(version(json!({})).is_err());
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!("5".parse(), Ok(MetadataVersion::V5));
+        assert_eq!("v4".parse(), Ok(MetadataVersion::V4));
+        assert_eq!("V3".parse(), Ok(MetadataVersion::V3));
+        assert!("6".parse::<MetadataVersion>().is_err());
+    }
+
+    #[test]
+    fn same_version_is_unchanged() {
+        for (abi, version) in [
+            (v3(), MetadataVersion::V3),
+            (v4(), MetadataVersion::V4),
+            (v5(), MetadataVersion::V5),
+        ] {
+            let conversion = convert(abi.clone(), version).unwrap();
+            assert!(conversion.incompatibilities.is_empty());
+            assert_eq!(self::abi(conversion), abi);
+        }
+    }
+
+    #[test]
+    fn v4_to_v5() {
+        let conversion = convert(v4(), MetadataVersion::V5).unwrap();
+
+        assert_eq!(conversion.incompatibilities.len(), 2);
+        assert_eq!(abi(conversion), v5());
+    }
+
+    #[test]
+    fn v5_to_v4() {
+        let conversion = convert(v5(), MetadataVersion::V4).unwrap();
+
+        assert_eq!(conversion.incompatibilities.len(), 1);
+        assert_eq!(abi(conversion), v4());
+    }
+
+    #[test]
+    fn v5_to_v4_reports_custom_static_buffer_size() {
+        let mut abi = v5();
+        abi["spec"]["environment"]["staticBufferSize"] = json!(1024);
+
+        let conversion = convert(abi, MetadataVersion::V4).unwrap();
+
+        assert!(conversion.incompatibilities[0].contains("1024 bytes"));
+    }
+
+    #[test]
+    fn v5_to_v4_fails_for_duplicate_event_labels() {
+        let mut abi = v5();
+        let event = abi["spec"]["events"][0].clone();
+        abi["spec"]["events"].as_array_mut().unwrap().push(event);
+
+        let err = convert(abi, MetadataVersion::V4).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("`Flipped` is defined more than once"));
+    }
+
+    #[test]
+    fn v3_can_not_be_converted() {
+        for (abi, to) in [
+            (v3(), MetadataVersion::V4),
+            (v3(), MetadataVersion::V5),
+            (v4(), MetadataVersion::V3),
+            (v5(), MetadataVersion::V3),
+        ] {
+            let err = convert(abi, to).unwrap_err();
+            assert!(err.to_string().contains("is not supported"), "{err}");
+        }
+    }
+}
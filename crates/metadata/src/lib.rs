@@ -67,7 +67,21 @@
 #![deny(unused_crate_dependencies)]
 
 pub mod byte_str;
+mod canonical;
 pub mod compatibility;
+mod compression;
+mod convert;
+mod signature;
+
+pub use compression::WasmEncoding;
+pub use convert::{
+    Conversion,
+    MetadataVersion,
+};
+pub use signature::{
+    BundleSignature,
+    SignatureScheme,
+};
 
 use anyhow::{
     Context,
@@ -109,6 +123,9 @@ pub struct ContractMetadata {
     /// Additional user-defined metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<User>,
+    /// Signature establishing the provenance of the contract bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<BundleSignature>,
     /// Raw JSON of the contract's abi metadata, generated during contract compilation.
     #[serde(flatten)]
     pub abi: Map<String, Value>,
@@ -128,12 +145,14 @@ impl ContractMetadata {
             contract,
             image,
             user,
+            signature: None,
             abi,
         }
     }
 
     pub fn remove_source_wasm_attribute(&mut self) {
         self.source.wasm = None;
+        self.source.wasm_encoding = None;
     }
 
     /// Reads the file and tries to parse it as instance of `ContractMetadata`.
@@ -193,7 +212,11 @@ impl Display for CodeHash {
 }
 
 /// Information about the contract's Wasm code.
+///
+/// Constructed with [`Source::new`] and the `with_*` methods of the optional fields,
+/// since further fields may be added.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct Source {
     /// The hash of the contract's Wasm code.
     pub hash: CodeHash,
@@ -205,11 +228,28 @@ pub struct Source {
     /// with the metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wasm: Option<SourceWasm>,
+    /// The compression of the Wasm code in `wasm`, which is uncompressed if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm_encoding: Option<WasmEncoding>,
     /// Extra information about the environment in which the contract was built.
     ///
     /// Useful for producing deterministic builds.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub build_info: Option<Map<String, Value>>,
+    /// The URL the metadata was published at, e.g. `ipfs://<cid>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata_url: Option<String>,
+    /// The digest of the image the contract was built in by a verifiable build, e.g.
+    /// `sha256:<hash>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_digest: Option<String>,
+    /// The URL of an archive of the sources the contract was built from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_archive_url: Option<Url>,
+    /// The hash of the VCS commit the contract was built from, suffixed with `-dirty`
+    /// if the sources had uncommitted changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs_commit: Option<String>,
 }
 
 impl Source {
@@ -226,9 +266,44 @@ impl Source {
             language,
             compiler,
             wasm,
+            wasm_encoding: None,
             build_info,
+            metadata_url: None,
+            image_digest: None,
+            source_archive_url: None,
+            vcs_commit: None,
         }
     }
+
+    /// Sets the compression of the Wasm code in `wasm`.
+    pub fn with_wasm_encoding(mut self, wasm_encoding: Option<WasmEncoding>) -> Self {
+        self.wasm_encoding = wasm_encoding;
+        self
+    }
+
+    /// Sets the URL the metadata was published at.
+    pub fn with_metadata_url(mut self, metadata_url: Option<String>) -> Self {
+        self.metadata_url = metadata_url;
+        self
+    }
+
+    /// Sets the digest of the image of a verifiable build.
+    pub fn with_image_digest(mut self, image_digest: Option<String>) -> Self {
+        self.image_digest = image_digest;
+        self
+    }
+
+    /// Sets the URL of an archive of the sources.
+    pub fn with_source_archive_url(mut self, source_archive_url: Option<Url>) -> Self {
+        self.source_archive_url = source_archive_url;
+        self
+    }
+
+    /// Sets the hash of the VCS commit the contract was built from.
+    pub fn with_vcs_commit(mut self, vcs_commit: Option<String>) -> Self {
+        self.vcs_commit = vcs_commit;
+        self
+    }
 }
 
 /// The bytes of the compiled Wasm smart contract.
@@ -734,7 +809,12 @@ mod tests {
             language,
             compiler,
             Some(build_info),
-        );
+        )
+        .with_image_digest(Some("sha256:0123".to_string()))
+        .with_source_archive_url(Some(
+            Url::parse("https://example.com/incrementer.tar.gz").unwrap(),
+        ))
+        .with_vcs_commit(Some("4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string()));
 
         let contract = Contract::builder()
             .name("incrementer")
@@ -790,7 +870,10 @@ mod tests {
                         "example_compiler_version": 42,
                         "example_settings": [],
                         "example_name": "increment"
-                    }
+                    },
+                    "image_digest": "sha256:0123",
+                    "source_archive_url": "https://example.com/incrementer.tar.gz",
+                    "vcs_commit": "4b825dc642cb6eb9a060e54bf8d69288fbee4904"
                 },
                 "image": "paritytech/contracts-verifiable:3.0.1",
                 "contract": {
@@ -0,0 +1,216 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signatures establishing the provenance of contract bundles.
+
+use crate::{
+    byte_str::{
+        deserialize_from_byte_str,
+        serialize_as_byte_str,
+    },
+    canonical::to_canonical_bytes,
+    ContractMetadata,
+};
+use anyhow::Result;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    str::FromStr,
+};
+
+/// The scheme of a [`BundleSignature`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    #[default]
+    Sr25519,
+    Ed25519,
+}
+
+impl Display for SignatureScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Sr25519 => write!(f, "sr25519"),
+            Self::Ed25519 => write!(f, "ed25519"),
+        }
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sr25519" => Ok(Self::Sr25519),
+            "ed25519" => Ok(Self::Ed25519),
+            _ => Err(format!("Unknown signature scheme '{s}'")),
+        }
+    }
+}
+
+/// A signature over the `source`, `contract` and ABI sections of the contract metadata.
+///
+/// See [`ContractMetadata::signing_payload`] for the signed payload.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct BundleSignature {
+    /// The scheme of the signature.
+    pub scheme: SignatureScheme,
+    /// The public key of the signer.
+    #[serde(
+        serialize_with = "serialize_as_byte_str",
+        deserialize_with = "deserialize_from_byte_str"
+    )]
+    pub public_key: Vec<u8>,
+    /// The signature of the payload.
+    #[serde(
+        serialize_with = "serialize_as_byte_str",
+        deserialize_with = "deserialize_from_byte_str"
+    )]
+    pub signature: Vec<u8>,
+}
+
+impl ContractMetadata {
+    /// Returns the payload signed by a [`BundleSignature`].
+    ///
+    /// The payload is the canonical serialization of the `source` and `contract`
+    /// sections and of the ABI, see [`ContractMetadata::canonical_bytes`]. The Wasm code and its
+    /// encoding are omitted since the `source` contains its hash, so the same signature
+    /// is valid for the `.contract` bundle, compressed or not, and the `.json` metadata.
+    /// The `metadata_url` is omitted so that it can be recorded after signing.
+    pub fn signing_payload(&self) -> Result<Vec<u8>> {
+        let mut source = self.source.clone();
+        source.wasm = None;
+        source.wasm_encoding = None;
+        source.metadata_url = None;
+        let payload = serde_json::json!({
+            "abi": self.abi,
+            "contract": self.contract,
+            "source": source,
+        });
+        to_canonical_bytes(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CodeHash,
+        Compiler,
+        Contract,
+        Language,
+        Source,
+        SourceCompiler,
+        SourceLanguage,
+        SourceWasm,
+    };
+    use semver::Version;
+    use serde_json::{
+        Map,
+        Value,
+    };
+
+    fn metadata() -> ContractMetadata {
+        let source = Source::new(
+            Some(SourceWasm::new(vec![0u8, 1u8, 2u8])),
+            CodeHash([0u8; 32]),
+            SourceLanguage::new(Language::Ink, Version::new(5, 0, 0)),
+            SourceCompiler::new(Compiler::RustC, Version::parse("1.78.0").unwrap()),
+            None,
+        );
+        let contract = Contract::builder()
+            .name("flipper")
+            .version(Version::new(0, 1, 0))
+            .authors(vec!["Use Ink <admin@use.ink>".to_string()])
+            .build()
+            .unwrap();
+        ContractMetadata::new(source, contract, None, None, Map::new())
+    }
+
+    #[test]
+    fn signing_payload_is_canonical() {
+        let payload = metadata().signing_payload().unwrap();
+        let payload = String::from_utf8(payload).unwrap();
+
+        assert!(payload.starts_with(r#"{"abi":{},"contract":{"authors":["Use Ink"#));
+        assert!(!payload.contains("wasm"));
+    }
+
+    #[test]
+    fn signing_payload_ignores_wasm() {
+        let bundle = metadata();
+        let mut json = metadata();
+        json.remove_source_wasm_attribute();
+
+        assert_eq!(
+            bundle.signing_payload().unwrap(),
+            json.signing_payload().unwrap()
+        );
+    }
+
+    #[test]
+    fn signing_payload_covers_abi() {
+        let mut modified = metadata();
+        modified.abi.insert("version".into(), Value::from(5));
+
+        assert_ne!(
+            metadata().signing_payload().unwrap(),
+            modified.signing_payload().unwrap()
+        );
+    }
+
+    #[test]
+    fn signing_payload_covers_contract() {
+        let mut modified = metadata();
+        modified.contract.version = Version::new(0, 2, 0);
+
+        assert_ne!(
+            metadata().signing_payload().unwrap(),
+            modified.signing_payload().unwrap()
+        );
+    }
+
+    #[test]
+    fn signature_roundtrips_as_hex() {
+        let signature = BundleSignature {
+            scheme: SignatureScheme::Ed25519,
+            public_key: vec![1, 2],
+            signature: vec![3, 4],
+        };
+
+        let json = serde_json::to_value(&signature).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "scheme": "ed25519",
+                "public_key": "0x0102",
+                "signature": "0x0304"
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<BundleSignature>(json).unwrap(),
+            signature
+        );
+    }
+}
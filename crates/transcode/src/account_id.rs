@@ -23,6 +23,12 @@ use serde::{
     Serialize,
 };
 
+/// The SS58 prefix of the generic Substrate address format.
+pub const SUBSTRATE_SS58_PREFIX: u16 = 42;
+
+/// The highest prefix which can be encoded in an SS58 address.
+const MAX_SS58_PREFIX: u16 = 16_383;
+
 /// A 32-byte cryptographic identifier. This is a simplified version of Substrate's
 /// `sp_core::crypto::AccountId32`.
 ///
@@ -92,6 +98,14 @@ impl<'a> TryFrom<&'a [u8]> for AccountId32 {
 }
 
 impl AccountId32 {
+    /// Returns the account an Ethereum style `address` is mapped to by `pallet-revive`,
+    /// i.e. the address padded with `0xEE` bytes.
+    pub fn from_eth_address(address: [u8; 20]) -> Self {
+        let mut account = [0xEE; 32];
+        account[..20].copy_from_slice(&address);
+        AccountId32(account)
+    }
+
     // Return the ss58-check string for this key. Adapted from `sp_core::crypto`. We need
     // this to serialize our account appropriately but otherwise don't care.
     pub fn to_ss58check(&self) -> String {
@@ -99,9 +113,26 @@ impl AccountId32 {
         // substrate prefix (since we have no way to otherwise pick one). It
         // doesn't really matter, since when it's deserialized back in
         // system_accountNextIndex, we ignore this (so long as it's valid).
-        const SUBSTRATE_SS58_PREFIX: u8 = 42;
-        // prefix <= 63 just take up one byte at the start:
-        let mut v = vec![SUBSTRATE_SS58_PREFIX];
+        self.to_ss58check_with_prefix(SUBSTRATE_SS58_PREFIX)
+    }
+
+    /// Returns the ss58-check string for this key with the given `prefix`. Adapted from
+    /// `sp_core::crypto`.
+    pub fn to_ss58check_with_prefix(&self, prefix: u16) -> String {
+        // prefix <= 63 just take up one byte at the start, higher prefixes two bytes
+        let ident = prefix & MAX_SS58_PREFIX;
+        let mut v = match ident {
+            0..=63 => vec![ident as u8],
+            _ => {
+                // upper six bits of the lower byte(!)
+                let first = ((ident & 0b0000_0000_1111_1100) as u8) >> 2;
+                // lower two bits of the lower byte in the high pos,
+                // lower bits of the upper byte in the low pos
+                let second =
+                    ((ident >> 8) as u8) | ((ident & 0b0000_0000_0000_0011) as u8) << 6;
+                vec![first | 0b0100_0000, second]
+            }
+        };
         // then push the account ID bytes.
         v.extend(self.0);
         // then push a 2 byte checksum of what we have so far.
@@ -211,7 +242,10 @@ impl std::str::FromStr for AccountId32 {
 mod test {
     use super::*;
 
-    use sp_core::crypto::Ss58Codec;
+    use sp_core::crypto::{
+        Ss58AddressFormat,
+        Ss58Codec,
+    };
     use sp_keyring::AccountKeyring;
 
     #[test]
@@ -243,4 +277,20 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn ss58_with_prefix_is_compatible_with_substrate_impl() {
+        let substrate_account = AccountKeyring::Alice.to_account_id();
+        let local_account = AccountId32(substrate_account.clone().into());
+
+        for prefix in [0, 2, 63, 64, 1284, MAX_SS58_PREFIX] {
+            let substrate_ss58 = substrate_account
+                .to_ss58check_with_version(Ss58AddressFormat::custom(prefix));
+            assert_eq!(substrate_ss58, local_account.to_ss58check_with_prefix(prefix));
+            assert_eq!(
+                AccountId32::from_ss58check(&substrate_ss58).unwrap(),
+                local_account
+            );
+        }
+    }
 }
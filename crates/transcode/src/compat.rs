@@ -0,0 +1,427 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use ink_metadata::{
+    layout::{
+        Layout,
+        StructLayout,
+    },
+    InkProject,
+    MessageSpec,
+};
+use itertools::Itertools;
+use scale_info::{
+    form::PortableForm,
+    PortableRegistry,
+    TypeDef,
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        Display,
+        Formatter,
+        Result,
+    },
+};
+
+/// A change between two versions of a contract, which breaks a contract upgraded from
+/// the old to the new version with `set_code_hash`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Incompatibility {
+    /// The data stored under the root key is no longer accessible.
+    StorageRootRemoved { root_key: String, path: String },
+    /// The fields stored under the root key changed, the stored data can no longer be
+    /// decoded.
+    StorageLayoutChanged {
+        root_key: String,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+    /// The message no longer exists, calls to it fail.
+    MessageRemoved { label: String, selector: String },
+    /// The selector of the message changed, calls with the old selector fail.
+    MessageSelectorChanged {
+        label: String,
+        old_selector: String,
+        new_selector: String,
+    },
+    /// The arguments or the return type of the message changed.
+    MessageSignatureChanged {
+        selector: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::StorageRootRemoved { root_key, path } => {
+                write!(f, "storage root {root_key} of `{path}` was removed")
+            }
+            Self::StorageLayoutChanged { root_key, old, new } => {
+                write!(
+                    f,
+                    "storage layout under root {root_key} changed from [{}] to [{}]",
+                    old.join(", "),
+                    new.join(", ")
+                )
+            }
+            Self::MessageRemoved { label, selector } => {
+                write!(f, "message `{label}` ({selector}) was removed")
+            }
+            Self::MessageSelectorChanged {
+                label,
+                old_selector,
+                new_selector,
+            } => {
+                write!(
+                    f,
+                    "selector of message `{label}` changed from {old_selector} to \
+                     {new_selector}"
+                )
+            }
+            Self::MessageSignatureChanged { selector, old, new } => {
+                write!(
+                    f,
+                    "signature of message {selector} changed from `{old}` to `{new}`"
+                )
+            }
+        }
+    }
+}
+
+/// The result of checking whether a contract can be upgraded to a new version.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct CompatibilityReport {
+    pub incompatibilities: Vec<Incompatibility>,
+}
+
+impl CompatibilityReport {
+    /// Returns `true` if the new version is compatible with the old one.
+    pub fn is_compatible(&self) -> bool {
+        self.incompatibilities.is_empty()
+    }
+}
+
+/// Checks whether a contract instantiated from the `old` version can be upgraded to the
+/// `new` version with `set_code_hash`.
+///
+/// Every storage root key of the old version has to exist in the new one, with the same
+/// fields stored under it. Every message has to keep its selector and signature, new
+/// messages and storage roots may be added.
+pub fn check_compatibility(old: &InkProject, new: &InkProject) -> CompatibilityReport {
+    let mut incompatibilities = Vec::new();
+
+    let new_roots = storage_roots(new);
+    for (key, old_root) in storage_roots(old) {
+        let root_key = format!("0x{}", hex::encode(key.to_le_bytes()));
+        match new_roots.get(&key) {
+            None => {
+                incompatibilities.push(Incompatibility::StorageRootRemoved {
+                    root_key,
+                    path: old_root.path,
+                })
+            }
+            Some(new_root) if new_root.fields != old_root.fields => {
+                incompatibilities.push(Incompatibility::StorageLayoutChanged {
+                    root_key,
+                    old: old_root.fields,
+                    new: new_root.fields.clone(),
+                })
+            }
+            Some(_) => (),
+        }
+    }
+
+    let new_messages = new.spec().messages();
+    for old_message in old.spec().messages() {
+        let label = old_message.label().to_string();
+        let selector = format!("0x{}", hex::encode(old_message.selector().to_bytes()));
+        let by_selector = new_messages
+            .iter()
+            .find(|message| message.selector() == old_message.selector());
+        match by_selector {
+            Some(new_message) => {
+                let old_signature = signature(old.registry(), old_message);
+                let new_signature = signature(new.registry(), new_message);
+                if old_signature != new_signature {
+                    incompatibilities.push(Incompatibility::MessageSignatureChanged {
+                        selector,
+                        old: old_signature,
+                        new: new_signature,
+                    })
+                }
+            }
+            None => {
+                let by_label = new_messages
+                    .iter()
+                    .find(|message| message.label() == old_message.label());
+                match by_label {
+                    Some(new_message) => {
+                        incompatibilities.push(Incompatibility::MessageSelectorChanged {
+                            label,
+                            old_selector: selector,
+                            new_selector: format!(
+                                "0x{}",
+                                hex::encode(new_message.selector().to_bytes())
+                            ),
+                        })
+                    }
+                    None => {
+                        incompatibilities
+                            .push(Incompatibility::MessageRemoved { label, selector })
+                    }
+                }
+            }
+        }
+    }
+
+    CompatibilityReport { incompatibilities }
+}
+
+/// A storage cell of a contract, holding the fields stored under its root key.
+struct StorageRoot {
+    /// The path of the field stored under the root key, e.g. `Erc20.balances`.
+    path: String,
+    /// The fields stored in the cell, together with their types.
+    fields: Vec<String>,
+}
+
+/// Returns the storage roots of the contract by their root key.
+fn storage_roots(project: &InkProject) -> BTreeMap<u32, StorageRoot> {
+    let mut roots = BTreeMap::new();
+    collect_roots(project.registry(), project.layout(), "", None, &mut roots);
+    roots
+}
+
+fn collect_roots(
+    registry: &PortableRegistry,
+    layout: &Layout<PortableForm>,
+    path: &str,
+    root_key: Option<u32>,
+    roots: &mut BTreeMap<u32, StorageRoot>,
+) {
+    match layout {
+        Layout::Root(root) => {
+            let key = *root.root_key().key();
+            // the root of the contract storage is named after the storage struct
+            let path = match root.layout() {
+                Layout::Struct(layout) if path.is_empty() => layout.name().as_str(),
+                _ => path,
+            };
+            roots.insert(
+                key,
+                StorageRoot {
+                    path: path.to_string(),
+                    fields: Vec::new(),
+                },
+            );
+            collect_roots(registry, root.layout(), path, Some(key), roots)
+        }
+        Layout::Leaf(leaf) => {
+            if let Some(root) = root_key.and_then(|key| roots.get_mut(&key)) {
+                root.fields
+                    .push(format!("{path}: {}", type_name(registry, leaf.ty().id)))
+            }
+        }
+        Layout::Struct(layout) => {
+            collect_struct_roots(registry, layout, path, root_key, roots)
+        }
+        Layout::Enum(layout) => {
+            for variant in layout.variants().values() {
+                let path = format!("{path}::{}", variant.name());
+                collect_struct_roots(registry, variant, &path, root_key, roots)
+            }
+        }
+        Layout::Array(layout) => {
+            let path = format!("{path}[{}]", layout.len());
+            collect_roots(registry, layout.layout(), &path, root_key, roots)
+        }
+        Layout::Hash(layout) => {
+            collect_roots(registry, layout.layout(), path, root_key, roots)
+        }
+    }
+}
+
+fn collect_struct_roots(
+    registry: &PortableRegistry,
+    layout: &StructLayout<PortableForm>,
+    path: &str,
+    root_key: Option<u32>,
+    roots: &mut BTreeMap<u32, StorageRoot>,
+) {
+    for field in layout.fields() {
+        let path = format!("{path}.{}", field.name());
+        collect_roots(registry, field.layout(), &path, root_key, roots)
+    }
+}
+
+/// Returns the types of the arguments and the return value of a message, e.g.
+/// `(AccountId, u128) -> Result<(), Error>`.
+///
+/// The labels are omitted, renaming a message or its arguments doesn't affect callers.
+fn signature(registry: &PortableRegistry, message: &MessageSpec<PortableForm>) -> String {
+    let args = message
+        .args()
+        .iter()
+        .map(|arg| type_name(registry, arg.ty().ty().id))
+        .join(", ");
+    let return_type = type_name(registry, message.return_type().ret_type().ty().id);
+    format!("({args}) -> {return_type}")
+}
+
+/// Returns a readable name of the type, which is comparable across the type registries
+/// of different versions of a contract.
+fn type_name(registry: &PortableRegistry, id: u32) -> String {
+    let Some(ty) = registry.resolve(id) else {
+        return format!("#{id}")
+    };
+    let params = ty
+        .type_params
+        .iter()
+        .filter_map(|param| param.ty.map(|ty| type_name(registry, ty.id)))
+        .join(", ");
+    if !ty.path.segments.is_empty() {
+        let path = ty.path.segments.join("::");
+        return if params.is_empty() {
+            path
+        } else {
+            format!("{path}<{params}>")
+        }
+    }
+    match &ty.type_def {
+        TypeDef::Primitive(primitive) => format!("{primitive:?}").to_lowercase(),
+        TypeDef::Sequence(sequence) => {
+            format!("Vec<{}>", type_name(registry, sequence.type_param.id))
+        }
+        TypeDef::Array(array) => {
+            format!(
+                "[{}; {}]",
+                type_name(registry, array.type_param.id),
+                array.len
+            )
+        }
+        TypeDef::Tuple(tuple) => {
+            format!(
+                "({})",
+                tuple
+                    .fields
+                    .iter()
+                    .map(|field| type_name(registry, field.id))
+                    .join(", ")
+            )
+        }
+        TypeDef::Compact(compact) => {
+            format!("Compact<{}>", type_name(registry, compact.type_param.id))
+        }
+        TypeDef::BitSequence(_) => "BitVec".to_string(),
+        TypeDef::Composite(_) | TypeDef::Variant(_) => format!("#{id}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn generate_metadata() -> Value {
+        extern "Rust" {
+            fn __ink_generate_metadata() -> InkProject;
+        }
+
+        serde_json::to_value(unsafe { __ink_generate_metadata() }).unwrap()
+    }
+
+    fn project(metadata: Value) -> InkProject {
+        serde_json::from_value(metadata).unwrap()
+    }
+
+    fn message_mut<'a>(metadata: &'a mut Value, label: &str) -> &'a mut Value {
+        metadata["spec"]["messages"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|message| message["label"] == label)
+            .unwrap()
+    }
+
+    #[test]
+    fn same_version_is_compatible() {
+        let metadata = generate_metadata();
+
+        let report = check_compatibility(&project(metadata.clone()), &project(metadata));
+
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn reports_changed_storage_root_key() {
+        let old = generate_metadata();
+        let mut new = old.clone();
+        new["storage"]["root"]["root_key"] = "0x01000000".into();
+
+        let report = check_compatibility(&project(old), &project(new));
+
+        assert_eq!(
+            report.incompatibilities,
+            vec![Incompatibility::StorageRootRemoved {
+                root_key: "0x00000000".into(),
+                path: "Transcode".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_changed_and_removed_messages() {
+        let old = generate_metadata();
+        let mut new = old.clone();
+        message_mut(&mut new, "flip")["selector"] = "0x01020304".into();
+        let flip_return_type = message_mut(&mut new, "flip")["returnType"].clone();
+        message_mut(&mut new, "get")["returnType"] = flip_return_type;
+        let removed = message_mut(&mut new, "uint_array_args")["selector"].clone();
+        new["spec"]["messages"]
+            .as_array_mut()
+            .unwrap()
+            .retain(|message| message["selector"] != removed);
+
+        let report = check_compatibility(&project(old), &project(new));
+
+        assert_eq!(report.incompatibilities.len(), 3);
+        assert!(report.incompatibilities.contains(
+            &Incompatibility::MessageSelectorChanged {
+                label: "flip".into(),
+                old_selector: "0x633aa551".into(),
+                new_selector: "0x01020304".into(),
+            }
+        ));
+        assert!(report.incompatibilities.contains(
+            &Incompatibility::MessageSignatureChanged {
+                selector: "0x2f865bd9".into(),
+                old: "() -> Result<bool, ink_primitives::LangError>".into(),
+                new: "() -> Result<(), ink_primitives::LangError>".into(),
+            }
+        ));
+        assert!(report
+            .incompatibilities
+            .contains(&Incompatibility::MessageRemoved {
+                label: "uint_array_args".into(),
+                selector: removed.as_str().unwrap().into(),
+            }));
+    }
+}
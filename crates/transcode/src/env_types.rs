@@ -18,6 +18,7 @@ use crate::{
     AccountId32,
     Hex,
     Value,
+    SUBSTRATE_SS58_PREFIX,
 };
 use anyhow::{
     Context,
@@ -121,6 +122,13 @@ impl PathKey {
     }
 }
 
+impl From<&str> for PathKey {
+    /// Parses a path like `ink_primitives::types::AccountId`.
+    fn from(path: &str) -> Self {
+        PathKey(path.split("::").map(ToOwned::to_owned).collect())
+    }
+}
+
 impl From<&Path<PortableForm>> for PathKey {
     fn from(path: &Path<PortableForm>) -> Self {
         PathKey(path.segments.to_vec())
@@ -145,14 +153,91 @@ pub trait CustomTypeDecoder: Send + Sync {
 ///
 /// Enables an `AccountId` to be input/ouput as an SS58 Encoded literal e.g.
 /// 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY
-#[derive(Clone)]
-pub struct AccountId;
+///
+/// It can also be input as `0x` prefixed hex of the 32 bytes of the account. For chains
+/// running `pallet-revive`, a 20 byte Ethereum style address is accepted as well, which
+/// is converted to the account it is mapped to, see [`AccountId::with_eth_addresses`].
+///
+/// Decoded accounts are output as SS58 address with the prefix of the target network,
+/// the generic Substrate prefix `42` by default.
+#[derive(Clone, Copy, Debug)]
+pub struct AccountId {
+    ss58_prefix: u16,
+    eth_addresses: bool,
+}
+
+impl AccountId {
+    /// Creates a transcoder which outputs accounts with the given SS58 `prefix`.
+    pub fn new(ss58_prefix: u16) -> Self {
+        Self {
+            ss58_prefix,
+            eth_addresses: false,
+        }
+    }
+
+    /// Sets the SS58 `prefix` accounts are output with.
+    pub fn with_ss58_prefix(self, ss58_prefix: u16) -> Self {
+        Self {
+            ss58_prefix,
+            ..self
+        }
+    }
+
+    /// Sets whether 20 byte Ethereum style addresses are accepted, and converted to the
+    /// account they are mapped to by `pallet-revive`, see
+    /// [`AccountId32::from_eth_address`].
+    ///
+    /// Only enable this for chains running `pallet-revive`, other chains don't map the
+    /// addresses to these accounts.
+    pub fn with_eth_addresses(self, eth_addresses: bool) -> Self {
+        Self {
+            eth_addresses,
+            ..self
+        }
+    }
+
+    /// Parses an `AccountId` from an SS58 address or `0x` prefixed hex.
+    fn parse_account(&self, input: &str) -> Result<AccountId32> {
+        if input.starts_with("0x") {
+            let hex = Hex::from_str(input)?;
+            return self.account_from_bytes(hex.bytes())
+        }
+        Ok(AccountId32::from_str(input)?)
+    }
+
+    /// Converts the bytes of an account, or of an Ethereum style address if enabled, to
+    /// an `AccountId`.
+    fn account_from_bytes(&self, bytes: &[u8]) -> Result<AccountId32> {
+        if self.eth_addresses {
+            if let Ok(address) = <[u8; 20]>::try_from(bytes) {
+                return Ok(AccountId32::from_eth_address(address))
+            }
+        }
+        AccountId32::try_from(bytes).map_err(|_| {
+            let expected = if self.eth_addresses {
+                "32 bytes or a 20 byte Ethereum style address"
+            } else {
+                "32 bytes"
+            };
+            anyhow::anyhow!(
+                "Error converting hex bytes `{:?}` to AccountId, expected {expected}",
+                bytes
+            )
+        })
+    }
+}
+
+impl Default for AccountId {
+    fn default() -> Self {
+        Self::new(SUBSTRATE_SS58_PREFIX)
+    }
+}
 
 impl CustomTypeEncoder for AccountId {
     fn encode_value(&self, value: &Value) -> Result<Vec<u8>> {
         let account_id = match value {
             Value::Literal(literal) => {
-                AccountId32::from_str(literal).map_err(|e| {
+                self.parse_account(literal).map_err(|e| {
                     anyhow::anyhow!(
                         "Error parsing AccountId from literal `{}`: {}",
                         literal,
@@ -161,7 +246,7 @@ impl CustomTypeEncoder for AccountId {
                 })?
             }
             Value::String(string) => {
-                AccountId32::from_str(string).map_err(|e| {
+                self.parse_account(string).map_err(|e| {
                     anyhow::anyhow!(
                         "Error parsing AccountId from string '{}': {}",
                         string,
@@ -169,14 +254,7 @@ impl CustomTypeEncoder for AccountId {
                     )
                 })?
             }
-            Value::Hex(hex) => {
-                AccountId32::try_from(hex.bytes()).map_err(|_| {
-                    anyhow::anyhow!(
-                        "Error converting hex bytes `{:?}` to AccountId",
-                        hex.bytes()
-                    )
-                })?
-            }
+            Value::Hex(hex) => self.account_from_bytes(hex.bytes())?,
             _ => {
                 return Err(anyhow::anyhow!(
                     "Expected a string or a literal for an AccountId"
@@ -190,7 +268,9 @@ impl CustomTypeEncoder for AccountId {
 impl CustomTypeDecoder for AccountId {
     fn decode_value(&self, input: &mut &[u8]) -> Result<Value> {
         let account_id = AccountId32::decode(input)?;
-        Ok(Value::Literal(account_id.to_ss58check()))
+        Ok(Value::Literal(
+            account_id.to_ss58check_with_prefix(self.ss58_prefix),
+        ))
     }
 }
 
@@ -0,0 +1,264 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use scale::{
+    Compact,
+    Encode,
+};
+use scale_info::{
+    form::PortableForm,
+    Field,
+    PortableRegistry,
+    TypeDef,
+    TypeDefPrimitive,
+};
+
+/// Nesting depth after which only the minimal values of types are generated, to
+/// terminate for recursive types.
+const MAX_DEPTH: usize = 16;
+
+/// An example value of a type, derived from its metadata, e.g. to seed the corpus of a
+/// fuzzer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExampleValue {
+    /// The smallest value of the type: the minimum of numbers, `false`, empty
+    /// collections and the first variant of enums.
+    Min,
+    /// The largest value of the type: the maximum of numbers, `true`, collections with
+    /// a single element and the last variant of enums.
+    Max,
+}
+
+impl ExampleValue {
+    /// All example values.
+    pub const ALL: [ExampleValue; 2] = [ExampleValue::Min, ExampleValue::Max];
+
+    /// Returns the name of the example value, e.g. `min`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExampleValue::Min => "min",
+            ExampleValue::Max => "max",
+        }
+    }
+
+    /// Returns the SCALE encoding of the example value of the type with `type_id`.
+    pub fn encode(&self, registry: &PortableRegistry, type_id: u32) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        self.encode_to(registry, type_id, 0, &mut output)?;
+        Ok(output)
+    }
+
+    fn encode_to(
+        &self,
+        registry: &PortableRegistry,
+        type_id: u32,
+        depth: usize,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        let ty = registry.resolve(type_id).ok_or_else(|| {
+            anyhow::anyhow!("Failed to resolve type with id '{:?}'", type_id)
+        })?;
+        if depth > 2 * MAX_DEPTH {
+            anyhow::bail!("Type '{:?}' is nested too deeply", ty.path)
+        }
+        let example = if depth > MAX_DEPTH {
+            ExampleValue::Min
+        } else {
+            *self
+        };
+
+        let encode_fields =
+            |fields: &[Field<PortableForm>], output: &mut Vec<u8>| -> Result<()> {
+                for field in fields {
+                    example.encode_to(registry, field.ty.id, depth + 1, output)?;
+                }
+                Ok(())
+            };
+        match &ty.type_def {
+            TypeDef::Composite(composite) => encode_fields(&composite.fields, output)?,
+            TypeDef::Variant(variant) => {
+                let variant = match example {
+                    ExampleValue::Min => variant.variants.first(),
+                    ExampleValue::Max => variant.variants.last(),
+                }
+                .ok_or_else(|| anyhow::anyhow!("Enum '{:?}' has no variants", ty.path))?;
+                variant.index.encode_to(output);
+                encode_fields(&variant.fields, output)?;
+            }
+            TypeDef::Sequence(sequence) => {
+                match example {
+                    ExampleValue::Min => Compact(0u32).encode_to(output),
+                    ExampleValue::Max => {
+                        Compact(1u32).encode_to(output);
+                        example.encode_to(
+                            registry,
+                            sequence.type_param.id,
+                            depth + 1,
+                            output,
+                        )?;
+                    }
+                }
+            }
+            TypeDef::Array(array) => {
+                for _ in 0..array.len {
+                    example.encode_to(
+                        registry,
+                        array.type_param.id,
+                        depth + 1,
+                        output,
+                    )?;
+                }
+            }
+            TypeDef::Tuple(tuple) => {
+                for field in &tuple.fields {
+                    example.encode_to(registry, field.id, depth + 1, output)?;
+                }
+            }
+            TypeDef::Primitive(primitive) => example.encode_primitive(primitive, output),
+            TypeDef::Compact(compact) => {
+                example.encode_compact(registry, compact.type_param.id, output)?
+            }
+            TypeDef::BitSequence(_) => Compact(0u32).encode_to(output),
+        }
+        Ok(())
+    }
+
+    fn encode_primitive(&self, primitive: &TypeDefPrimitive, output: &mut Vec<u8>) {
+        let min = matches!(self, ExampleValue::Min);
+        match primitive {
+            TypeDefPrimitive::Bool => (!min).encode_to(output),
+            TypeDefPrimitive::Char => {
+                (if min { 0 } else { char::MAX as u32 }).encode_to(output)
+            }
+            TypeDefPrimitive::Str => (if min { "" } else { "ink!" }).encode_to(output),
+            TypeDefPrimitive::U8 => (if min { 0 } else { u8::MAX }).encode_to(output),
+            TypeDefPrimitive::U16 => (if min { 0 } else { u16::MAX }).encode_to(output),
+            TypeDefPrimitive::U32 => (if min { 0 } else { u32::MAX }).encode_to(output),
+            TypeDefPrimitive::U64 => (if min { 0 } else { u64::MAX }).encode_to(output),
+            TypeDefPrimitive::U128 => (if min { 0 } else { u128::MAX }).encode_to(output),
+            TypeDefPrimitive::U256 => output.extend([if min { 0x00 } else { 0xff }; 32]),
+            TypeDefPrimitive::I8 => {
+                (if min { i8::MIN } else { i8::MAX }).encode_to(output)
+            }
+            TypeDefPrimitive::I16 => {
+                (if min { i16::MIN } else { i16::MAX }).encode_to(output)
+            }
+            TypeDefPrimitive::I32 => {
+                (if min { i32::MIN } else { i32::MAX }).encode_to(output)
+            }
+            TypeDefPrimitive::I64 => {
+                (if min { i64::MIN } else { i64::MAX }).encode_to(output)
+            }
+            TypeDefPrimitive::I128 => {
+                (if min { i128::MIN } else { i128::MAX }).encode_to(output)
+            }
+            TypeDefPrimitive::I256 => {
+                // little endian, the sign is in the most significant bit of the last byte
+                let (low, high) = if min { (0x00, 0x80) } else { (0xff, 0x7f) };
+                output.extend([low; 31]);
+                output.push(high);
+            }
+        }
+    }
+
+    fn encode_compact(
+        &self,
+        registry: &PortableRegistry,
+        type_id: u32,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        let ty = registry.resolve(type_id).ok_or_else(|| {
+            anyhow::anyhow!("Failed to resolve type with id '{:?}'", type_id)
+        })?;
+        match &ty.type_def {
+            TypeDef::Primitive(primitive) => {
+                let max = match self {
+                    ExampleValue::Min => 0,
+                    ExampleValue::Max => {
+                        match primitive {
+                            TypeDefPrimitive::U8 => u8::MAX as u128,
+                            TypeDefPrimitive::U16 => u16::MAX as u128,
+                            TypeDefPrimitive::U32 => u32::MAX as u128,
+                            TypeDefPrimitive::U64 => u64::MAX as u128,
+                            TypeDefPrimitive::U128 => u128::MAX,
+                            _ => {
+                                anyhow::bail!(
+                                    "Compact encoding not supported for {:?}",
+                                    primitive
+                                )
+                            }
+                        }
+                    }
+                };
+                Compact(max).encode_to(output);
+                Ok(())
+            }
+            TypeDef::Composite(composite) if composite.fields.len() == 1 => {
+                self.encode_compact(registry, composite.fields[0].ty.id, output)
+            }
+            _ => anyhow::bail!("Compact encoding not supported for {:?}", ty.path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scale::Decode;
+    use scale_info::{
+        MetaType,
+        Registry,
+        TypeInfo,
+    };
+
+    fn examples<T>() -> Vec<T>
+    where
+        T: TypeInfo + Decode + 'static,
+    {
+        let mut registry = Registry::new();
+        let type_id = registry.register_type(&MetaType::new::<T>()).id;
+        let registry: PortableRegistry = registry.into();
+
+        ExampleValue::ALL
+            .iter()
+            .map(|example| {
+                let encoded = example.encode(&registry, type_id).unwrap();
+                T::decode(&mut &encoded[..]).expect("example must decode")
+            })
+            .collect()
+    }
+
+    #[derive(Debug, PartialEq, TypeInfo, Decode)]
+    enum Op {
+        Noop,
+        Add(#[codec(compact)] u64, Option<Box<Op>>),
+    }
+
+    #[test]
+    fn encodes_decodable_examples() {
+        assert_eq!(examples::<bool>(), vec![false, true]);
+        assert_eq!(examples::<i32>(), vec![i32::MIN, i32::MAX]);
+        assert_eq!(
+            examples::<(String, [u8; 2])>(),
+            vec![(String::new(), [0, 0]), ("ink!".to_string(), [255, 255])]
+        );
+        assert_eq!(examples::<Vec<u16>>(), vec![vec![], vec![u16::MAX]]);
+        let ops = examples::<Op>();
+        assert_eq!(ops[0], Op::Noop);
+        assert!(matches!(ops[1], Op::Add(u64::MAX, Some(_))));
+    }
+}
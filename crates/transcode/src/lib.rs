@@ -99,25 +99,58 @@
 //! ```
 
 mod account_id;
+mod compat;
 mod decode;
 mod encode;
 pub mod env_types;
+mod examples;
+mod return_error;
 mod scon;
+mod selector;
+mod sol_abi;
 mod transcoder;
+mod type_mappings;
 mod util;
 
 pub use self::{
-    account_id::AccountId32,
+    account_id::{
+        AccountId32,
+        SUBSTRATE_SS58_PREFIX,
+    },
+    compat::{
+        check_compatibility,
+        CompatibilityReport,
+        Incompatibility,
+    },
+    examples::ExampleValue,
+    return_error::{
+        unwrap_lang_result,
+        ReturnError,
+        ReturnErrorSource,
+    },
     scon::{
         Hex,
         Map,
         Tuple,
         Value,
     },
+    selector::{
+        compute_selector,
+        SelectorKind,
+    },
+    sol_abi::{
+        AbiEntry,
+        AbiParam,
+        SolidityAbi,
+    },
     transcoder::{
         Transcoder,
         TranscoderBuilder,
     },
+    type_mappings::{
+        EnvType,
+        TypeMappings,
+    },
 };
 
 use anyhow::{
@@ -153,6 +186,9 @@ use std::{
 /// Decode SCALE encoded smart contract events and return values into `Value` objects.
 pub struct ContractMessageTranscoder {
     metadata: InkProject,
+    mappings: TypeMappings,
+    ss58_prefix: u16,
+    eth_addresses: bool,
     transcoder: Transcoder,
 }
 
@@ -174,18 +210,80 @@ where
     candidates.into_iter().map(|(_, pv)| pv).collect()
 }
 
+/// Parses a hex encoded selector like `0x9bae9d5e`, returns `None` if `name` is not one.
+fn parse_selector(name: &str) -> Option<Vec<u8>> {
+    let hex = name.strip_prefix("0x")?;
+    hex::decode(hex).ok()
+}
+
 impl ContractMessageTranscoder {
     pub fn new(metadata: InkProject) -> Self {
-        let transcoder = TranscoderBuilder::new(metadata.registry())
-            .register_custom_type_transcoder::<<ink_env::DefaultEnvironment as ink_env::Environment>::AccountId, _>(env_types::AccountId)
-            .register_custom_type_decoder::<<ink_env::DefaultEnvironment as ink_env::Environment>::Hash, _>(env_types::Hash)
-            .done();
+        Self::with_type_mappings(metadata, &TypeMappings::default())
+    }
+
+    /// Creates a transcoder which additionally transcodes the types of the `mappings`
+    /// like the environment types they are mapped to.
+    pub fn with_type_mappings(metadata: InkProject, mappings: &TypeMappings) -> Self {
+        let transcoder =
+            Self::transcoder(&metadata, mappings, SUBSTRATE_SS58_PREFIX, false);
         Self {
             metadata,
+            mappings: mappings.clone(),
+            ss58_prefix: SUBSTRATE_SS58_PREFIX,
+            eth_addresses: false,
             transcoder,
         }
     }
 
+    /// Returns the transcoder which decodes accounts, e.g. the arguments of events, as
+    /// SS58 addresses with the `prefix` of the target network instead of the generic
+    /// Substrate prefix `42`.
+    pub fn with_ss58_prefix(self, prefix: u16) -> Self {
+        Self {
+            ss58_prefix: prefix,
+            ..self
+        }
+        .rebuild()
+    }
+
+    /// Returns the transcoder which also accepts 20 byte Ethereum style addresses as
+    /// accounts, see [`env_types::AccountId::with_eth_addresses`]. Only enable this for
+    /// chains running `pallet-revive`.
+    pub fn with_eth_addresses(self, eth_addresses: bool) -> Self {
+        Self {
+            eth_addresses,
+            ..self
+        }
+        .rebuild()
+    }
+
+    fn rebuild(self) -> Self {
+        let transcoder = Self::transcoder(
+            &self.metadata,
+            &self.mappings,
+            self.ss58_prefix,
+            self.eth_addresses,
+        );
+        Self { transcoder, ..self }
+    }
+
+    fn transcoder(
+        metadata: &InkProject,
+        mappings: &TypeMappings,
+        ss58_prefix: u16,
+        eth_addresses: bool,
+    ) -> Transcoder {
+        let builder = TranscoderBuilder::new(metadata.registry())
+            .ss58_prefix(ss58_prefix)
+            .eth_addresses(eth_addresses);
+        let account_id = builder.account_id_transcoder();
+        builder
+            .register_custom_type_transcoder::<<ink_env::DefaultEnvironment as ink_env::Environment>::AccountId, _>(account_id)
+            .register_custom_type_decoder::<<ink_env::DefaultEnvironment as ink_env::Environment>::Hash, _>(env_types::Hash)
+            .register_type_mappings(mappings)
+            .done()
+    }
+
     /// Attempt to create a [`ContractMessageTranscoder`] from the metadata file at the
     /// given path.
     pub fn load<P>(metadata_path: P) -> Result<Self>
@@ -195,17 +293,16 @@ impl ContractMessageTranscoder {
         let path = metadata_path.as_ref();
         let metadata: contract_metadata::ContractMetadata =
             contract_metadata::ContractMetadata::load(&metadata_path)?;
-        let ink_metadata = serde_json::from_value(serde_json::Value::Object(
-            metadata.abi,
-        ))
-        .context(format!(
+        Self::try_from(metadata).context(format!(
             "Failed to deserialize ink project metadata from file {}",
             path.display()
-        ))?;
-
-        Ok(Self::new(ink_metadata))
+        ))
     }
 
+    /// Encodes the call of the constructor or message `name` with the given arguments.
+    ///
+    /// Instead of its label, the constructor or message can also be identified by its
+    /// hex encoded selector, e.g. `0x9bae9d5e`.
     pub fn encode<I, S>(&self, name: &str, args: I) -> Result<Vec<u8>>
     where
         I: IntoIterator<Item = S>,
@@ -262,6 +359,25 @@ impl ContractMessageTranscoder {
         Ok(encoded)
     }
 
+    /// Encodes a single `value`, e.g. `5GrwvaEF...` or `{ a: 1 }`, as the type with the
+    /// `type_id` of the registry of the metadata.
+    pub fn encode_value(&self, type_id: u32, value: &str) -> Result<Vec<u8>> {
+        self.encode_scon(type_id, &scon::parse_value(value)?)
+    }
+
+    /// Encodes a parsed or decoded `value` as the type with the `type_id` of the
+    /// registry of the metadata.
+    pub fn encode_scon(&self, type_id: u32, value: &Value) -> Result<Vec<u8>> {
+        let mut encoded = Vec::new();
+        self.transcoder.encode(
+            self.metadata.registry(),
+            type_id,
+            value,
+            &mut encoded,
+        )?;
+        Ok(encoded)
+    }
+
     pub fn decode(&self, type_id: u32, input: &mut &[u8]) -> Result<Value> {
         self.transcoder
             .decode(self.metadata.registry(), type_id, input)
@@ -279,16 +395,38 @@ impl ContractMessageTranscoder {
         self.metadata.spec().messages().iter()
     }
 
-    fn find_message_spec(&self, name: &str) -> Option<&MessageSpec<PortableForm>> {
-        self.messages().find(|msg| msg.label() == &name.to_string())
+    /// Returns the message with the given label or hex encoded selector.
+    pub fn find_message_spec(&self, name: &str) -> Option<&MessageSpec<PortableForm>> {
+        let selector = parse_selector(name);
+        self.messages().find(|msg| {
+            msg.label() == &name.to_string()
+                || selector.as_deref() == Some(msg.selector().to_bytes())
+        })
     }
 
-    fn find_constructor_spec(
+    /// Returns the constructor with the given label or hex encoded selector.
+    pub fn find_constructor_spec(
         &self,
         name: &str,
     ) -> Option<&ConstructorSpec<PortableForm>> {
-        self.constructors()
-            .find(|msg| msg.label() == &name.to_string())
+        let selector = parse_selector(name);
+        self.constructors().find(|msg| {
+            msg.label() == &name.to_string()
+                || selector.as_deref() == Some(msg.selector().to_bytes())
+        })
+    }
+
+    /// Returns the labels of the constructors and messages with the given `selector`.
+    pub fn lookup_selector(&self, selector: [u8; 4]) -> Vec<(SelectorKind, &str)> {
+        let constructors = self
+            .constructors()
+            .filter(|spec| spec.selector().to_bytes() == selector)
+            .map(|spec| (SelectorKind::Constructor, spec.label().as_str()));
+        let messages = self
+            .messages()
+            .filter(|spec| spec.selector().to_bytes() == selector)
+            .map(|spec| (SelectorKind::Message, spec.label().as_str()));
+        constructors.chain(messages).collect()
     }
 
     pub fn decode_contract_event<Hash>(
@@ -416,6 +554,39 @@ impl ContractMessageTranscoder {
         self.decode(return_ty.ty().id, data)
     }
 
+    /// Returns whether the return value of the message is wrapped in the
+    /// `Result<_, LangError>` of the ink! dispatcher, see [`unwrap_lang_result`].
+    pub fn message_returns_lang_result(&self, name: &str) -> bool {
+        self.find_message_spec(name).is_some_and(|msg_spec| {
+            self.is_lang_result(msg_spec.return_type().ret_type().ty().id)
+        })
+    }
+
+    /// Returns whether the return value of the constructor is wrapped in the
+    /// `Result<_, LangError>` of the ink! dispatcher, see [`unwrap_lang_result`].
+    pub fn constructor_returns_lang_result(&self, name: &str) -> bool {
+        self.find_constructor_spec(name).is_some_and(|ctor_spec| {
+            self.is_lang_result(ctor_spec.return_type().ret_type().ty().id)
+        })
+    }
+
+    /// Checks if the type is a `Result` with the `LangError` of ink! as its error.
+    fn is_lang_result(&self, type_id: u32) -> bool {
+        let registry = self.metadata.registry();
+        let Some(ty) = registry.resolve(type_id) else {
+            return false
+        };
+        if ty.path.segments != ["Result"] {
+            return false
+        }
+        ty.type_params
+            .iter()
+            .find(|param| param.name == "E")
+            .and_then(|param| param.ty)
+            .and_then(|error| registry.resolve(error.id))
+            .is_some_and(|error| error.path.segments == ["ink_primitives", "LangError"])
+    }
+
     /// Checks if buffer empty, otherwise returns am error
     fn validate_length(data: &[u8], label: &str, args: &[(Value, Value)]) -> Result<()> {
         if !data.is_empty() {
@@ -441,9 +612,14 @@ impl TryFrom<contract_metadata::ContractMetadata> for ContractMessageTranscoder
     fn try_from(
         metadata: contract_metadata::ContractMetadata,
     ) -> Result<Self, Self::Error> {
-        Ok(Self::new(serde_json::from_value(
-            serde_json::Value::Object(metadata.abi),
-        )?))
+        let mappings = match &metadata.user {
+            Some(user) => TypeMappings::from_user_metadata(user)?,
+            None => TypeMappings::default(),
+        };
+        Ok(Self::with_type_mappings(
+            serde_json::from_value(serde_json::Value::Object(metadata.abi))?,
+            &mappings,
+        ))
     }
 }
 
@@ -550,6 +726,11 @@ mod tests {
                 self.value
             }
 
+            #[ink(message)]
+            pub fn checked_get(&self) -> Result<bool, u8> {
+                Ok(self.value)
+            }
+
             #[ink(message)]
             pub fn set_account_id(&self, account_id: AccountId) {
                 let _ = account_id;
@@ -604,6 +785,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_by_selector() -> Result<()> {
+        let metadata = generate_metadata();
+        let transcoder = ContractMessageTranscoder::new(metadata);
+        let selector = transcoder
+            .find_constructor_spec("new")
+            .map(|c| hex::encode(c.selector().to_bytes()))
+            .unwrap();
+
+        let encoded = transcoder.encode(&format!("0x{selector}"), ["true"])?;
+
+        assert_eq!(transcoder.encode("new", ["true"])?, encoded);
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_selector() {
+        let metadata = generate_metadata();
+        let transcoder = ContractMessageTranscoder::new(metadata);
+
+        assert_eq!(
+            transcoder.lookup_selector(compute_selector("flip")),
+            vec![(SelectorKind::Message, "flip")]
+        );
+        assert_eq!(
+            transcoder.lookup_selector(compute_selector("new")),
+            vec![(SelectorKind::Constructor, "new")]
+        );
+        assert!(transcoder.lookup_selector([0; 4]).is_empty());
+    }
+
     #[test]
     fn encode_misspelled_arg() {
         let metadata = generate_metadata();
@@ -654,6 +866,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn encode_account_id_from_hex() -> Result<()> {
+        let metadata = generate_metadata();
+        let transcoder = ContractMessageTranscoder::new(metadata);
+        let expected =
+            AccountId32::from_str("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+                .unwrap();
+        let hex = format!("0x{}", hex::encode(expected.0));
+
+        let encoded = transcoder.encode("set_account_id", [&hex])?;
+        assert_eq!(expected.encode(), &encoded[4..]);
+
+        let encoded = transcoder.encode("set_account_id", [format!("\"{hex}\"")])?;
+        assert_eq!(expected.encode(), &encoded[4..]);
+        Ok(())
+    }
+
+    #[test]
+    fn encode_account_id_from_eth_address() -> Result<()> {
+        let metadata = generate_metadata();
+        let transcoder =
+            ContractMessageTranscoder::new(metadata).with_eth_addresses(true);
+
+        let encoded = transcoder
+            .encode("set_account_id", ["0x0101010101010101010101010101010101010101"])?;
+
+        let mut expected = [0xEE; 32];
+        expected[..20].copy_from_slice(&[1; 20]);
+        assert_eq!(AccountId32(expected).encode(), &encoded[4..]);
+
+        let result = transcoder.encode("set_account_id", ["0x0101"]);
+        assert!(result.is_err(), "Should return an error");
+        Ok(())
+    }
+
+    #[test]
+    fn eth_address_is_rejected_by_default() {
+        let metadata = generate_metadata();
+        let transcoder = ContractMessageTranscoder::new(metadata);
+
+        let result = transcoder
+            .encode("set_account_id", ["0x0101010101010101010101010101010101010101"]);
+
+        assert!(result.is_err(), "Should return an error");
+    }
+
     #[test]
     fn encode_account_ids_vec_args() -> Result<()> {
         let metadata = generate_metadata();
@@ -779,6 +1037,39 @@ mod tests {
         assert_eq!(expected, decoded);
     }
 
+    #[test]
+    fn detects_lang_result_return_type() {
+        let transcoder = ContractMessageTranscoder::new(generate_metadata());
+
+        assert!(transcoder.message_returns_lang_result("checked_get"));
+        assert!(transcoder.constructor_returns_lang_result("new"));
+
+        // declare the `Result<bool, u8>` returned by the message without the
+        // `LangError` wrapper
+        let mut metadata = serde_json::to_value(generate_metadata()).unwrap();
+        let index = metadata["spec"]["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|message| message["label"] == "checked_get")
+            .unwrap();
+        let wrapper = metadata["spec"]["messages"][index]["returnType"]["type"]
+            .as_u64()
+            .unwrap() as usize;
+        let result = metadata["types"][wrapper]["type"]["params"][0]["type"].clone();
+        metadata["spec"]["messages"][index]["returnType"]["type"] = result;
+        let transcoder =
+            ContractMessageTranscoder::new(serde_json::from_value(metadata).unwrap());
+
+        assert!(!transcoder.message_returns_lang_result("checked_get"));
+        let encoded = Result::<bool, u8>::Ok(true).encode();
+        let decoded = transcoder
+            .decode_message_return("checked_get", &mut &encoded[..])
+            .unwrap();
+        let expected = Value::Tuple(Tuple::new(Some("Ok"), vec![Value::Bool(true)]));
+        assert_eq!(unwrap_lang_result(decoded, false), expected);
+    }
+
     #[test]
     fn decode_contract_event() -> Result<()> {
         let metadata = generate_metadata();
@@ -847,6 +1138,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn decode_account_id_with_ss58_prefix() -> Result<()> {
+        let metadata = generate_metadata();
+        let transcoder = ContractMessageTranscoder::new(metadata).with_ss58_prefix(0);
+        let alice =
+            AccountId32::from_str("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+                .unwrap();
+
+        let encoded = transcoder.encode("set_account_id", [alice.to_string()])?;
+        let decoded = transcoder.decode_contract_message(&mut &encoded[..])?;
+
+        let decoded = decoded.to_string();
+        assert!(decoded.contains(&alice.to_ss58check_with_prefix(0)));
+        assert!(!decoded.contains(&alice.to_string()));
+        Ok(())
+    }
+
     #[test]
     #[should_panic(
         expected = "input length was longer than expected by 1 byte(s).\nManaged to decode `flip` but `00` bytes were left unread"
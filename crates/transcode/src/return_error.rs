@@ -0,0 +1,207 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::scon::Value;
+use serde::Serialize;
+use std::fmt::{
+    Display,
+    Formatter,
+    Result,
+};
+
+/// Where a [`ReturnError`] originates from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReturnErrorSource {
+    /// A `LangError` of the ink! dispatcher, e.g. if the selector is unknown.
+    Lang,
+    /// An error returned by the message or constructor itself.
+    Contract,
+}
+
+/// The error returned by a message or constructor, decoded from its return value.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ReturnError {
+    pub source: ReturnErrorSource,
+    /// The name of the error variant, if the error is an enum.
+    pub variant: Option<String>,
+    /// The decoded error, including the fields of the variant.
+    pub value: Value,
+}
+
+impl ReturnError {
+    /// Extracts the error from the decoded return value of an ink! message or
+    /// constructor.
+    ///
+    /// If `lang_result` is set, the return value is wrapped in the `Result<_, LangError>`
+    /// of the ink! dispatcher, so `Err(e)` is a `LangError` and `Ok(Err(e))` an error
+    /// returned by the contract. Otherwise `Err(e)` is an error returned by the contract.
+    /// Returns `None` for any other value.
+    pub fn from_return_value(value: &Value, lang_result: bool) -> Option<Self> {
+        let (source, value) = match result_variant(value)? {
+            ("Err", error) if lang_result => (ReturnErrorSource::Lang, error),
+            ("Err", error) => (ReturnErrorSource::Contract, error),
+            ("Ok", inner) if lang_result => {
+                match result_variant(inner)? {
+                    ("Err", error) => (ReturnErrorSource::Contract, error),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        let variant = match value {
+            Value::Tuple(tuple) => tuple.ident(),
+            Value::Map(map) => map.ident(),
+            _ => None,
+        };
+        Some(Self {
+            source,
+            variant,
+            value: value.clone(),
+        })
+    }
+}
+
+impl Display for ReturnError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.source {
+            ReturnErrorSource::Lang => write!(f, "LangError::{}", self.value),
+            ReturnErrorSource::Contract => write!(f, "{}", self.value),
+        }
+    }
+}
+
+/// Unwraps the `Ok` of the `Result<_, LangError>` the return values of ink! messages
+/// and constructors are wrapped in, any other value is returned as is.
+///
+/// `lang_result` is whether the value is of this wrapper type, see
+/// [`ContractMessageTranscoder::message_returns_lang_result`]. A message returning a
+/// plain `Result` keeps its `Ok`.
+///
+/// [`ContractMessageTranscoder::message_returns_lang_result`]:
+/// crate::ContractMessageTranscoder::message_returns_lang_result
+pub fn unwrap_lang_result(value: Value, lang_result: bool) -> Value {
+    match result_variant(&value) {
+        Some(("Ok", inner)) if lang_result => inner.clone(),
+        _ => value,
+    }
+}
+
+/// Returns the name and the value of a decoded `Result` variant.
+fn result_variant(value: &Value) -> Option<(&str, &Value)> {
+    let Value::Tuple(tuple) = value else {
+        return None
+    };
+    let mut values = tuple.values();
+    match (tuple.ident().as_deref(), values.next(), values.next()) {
+        (Some("Ok"), Some(value), None) => Some(("Ok", value)),
+        (Some("Err"), Some(value), None) => Some(("Err", value)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Map,
+        Tuple,
+    };
+
+    fn variant(name: &str, value: Value) -> Value {
+        Value::Tuple(Tuple::new(Some(name), vec![value]))
+    }
+
+    #[test]
+    fn extracts_contract_error() {
+        let error = Value::Map(Map::new(
+            Some("InsufficientBalance"),
+            vec![(Value::String("needed".into()), Value::UInt(5))]
+                .into_iter()
+                .collect(),
+        ));
+        let value = variant("Ok", variant("Err", error.clone()));
+
+        let return_error = ReturnError::from_return_value(&value, true).unwrap();
+
+        assert_eq!(return_error.source, ReturnErrorSource::Contract);
+        assert_eq!(return_error.variant.as_deref(), Some("InsufficientBalance"));
+        assert_eq!(return_error.value, error);
+        assert_eq!(
+            return_error.to_string(),
+            "InsufficientBalance { needed: 5 }"
+        );
+    }
+
+    #[test]
+    fn extracts_lang_error() {
+        let error = Value::Tuple(Tuple::new(Some("CouldNotReadInput"), Vec::new()));
+        let value = variant("Err", error);
+
+        let return_error = ReturnError::from_return_value(&value, true).unwrap();
+
+        assert_eq!(return_error.source, ReturnErrorSource::Lang);
+        assert_eq!(return_error.variant.as_deref(), Some("CouldNotReadInput"));
+        assert_eq!(return_error.to_string(), "LangError::CouldNotReadInput");
+    }
+
+    #[test]
+    fn extracts_error_of_plain_result() {
+        let value = variant("Err", Value::UInt(1));
+
+        let return_error = ReturnError::from_return_value(&value, false).unwrap();
+
+        assert_eq!(return_error.source, ReturnErrorSource::Contract);
+        assert_eq!(return_error.value, Value::UInt(1));
+        assert_eq!(
+            ReturnError::from_return_value(&variant("Ok", value), false),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_successful_return_value() {
+        assert_eq!(
+            ReturnError::from_return_value(
+                &variant("Ok", variant("Ok", Value::Bool(true))),
+                true
+            ),
+            None
+        );
+        assert_eq!(
+            ReturnError::from_return_value(&variant("Ok", Value::UInt(1)), true),
+            None
+        );
+    }
+
+    #[test]
+    fn unwraps_lang_result() {
+        let ok = variant("Ok", Value::UInt(1));
+        assert_eq!(
+            unwrap_lang_result(variant("Ok", Value::Bool(true)), true),
+            Value::Bool(true)
+        );
+        assert_eq!(unwrap_lang_result(variant("Ok", ok.clone()), true), ok);
+        let lang_error = variant("Err", Value::UInt(1));
+        assert_eq!(unwrap_lang_result(lang_error.clone(), true), lang_error);
+    }
+
+    #[test]
+    fn keeps_ok_of_plain_result() {
+        let ok = variant("Ok", Value::Bool(true));
+        assert_eq!(unwrap_lang_result(ok.clone(), false), ok);
+    }
+}
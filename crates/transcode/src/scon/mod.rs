@@ -61,6 +61,14 @@ pub enum Value {
     Unit,
 }
 
+impl FromStr for Value {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_value(s)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Map {
     ident: Option<String>,
@@ -163,6 +171,12 @@ impl Map {
     pub fn get_by_str(&self, key: &str) -> Option<&Value> {
         self.map.get(&Value::String(key.to_string()))
     }
+
+    /// Return a mutable reference to the value stored for string key, if it is present,
+    /// else None.
+    pub fn get_mut_by_str(&mut self, key: &str) -> Option<&mut Value> {
+        self.map.get_mut(&Value::String(key.to_string()))
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
@@ -196,6 +210,11 @@ impl Tuple {
     pub fn values(&self) -> impl Iterator<Item = &Value> {
         self.values.iter()
     }
+
+    /// Returns an iterator over mutable references to the tuple's values
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.values.iter_mut()
+    }
 }
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
@@ -0,0 +1,69 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use blake2::{
+    digest::{
+        consts::U32,
+        Digest as _,
+    },
+    Blake2b,
+};
+use serde::Serialize;
+use std::fmt::{
+    Display,
+    Formatter,
+    Result,
+};
+
+/// Computes the ink! selector of a message or constructor from its label.
+///
+/// The selector consists of the first four bytes of the BLAKE2b-256 hash of the label.
+/// Trait messages are labeled with the name of the trait, e.g. `Erc20::transfer`.
+pub fn compute_selector(label: &str) -> [u8; 4] {
+    let hash = Blake2b::<U32>::digest(label.as_bytes());
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hash[..4]);
+    selector
+}
+
+/// Whether a selector belongs to a constructor or a message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectorKind {
+    Constructor,
+    Message,
+}
+
+impl Display for SelectorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Constructor => write!(f, "constructor"),
+            Self::Message => write!(f, "message"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_ink_selectors() {
+        assert_eq!(compute_selector("new"), [0x9b, 0xae, 0x9d, 0x5e]);
+        assert_eq!(compute_selector("flip"), [0x63, 0x3a, 0xa5, 0x51]);
+        assert_eq!(compute_selector("get"), [0x2f, 0x86, 0x5b, 0xd9]);
+    }
+}
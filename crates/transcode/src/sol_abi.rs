@@ -0,0 +1,781 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Encoding of calls to contracts described by a Solidity ABI, e.g. contracts compiled
+//! with `solang`.
+//!
+//! Arguments are given in the same syntax as for ink! contracts, and encoded according
+//! to the [Solidity ABI specification](https://docs.soliditylang.org/en/latest/abi-spec.html)
+//! instead of SCALE.
+
+use crate::{
+    scon,
+    Hex,
+    Tuple,
+    Value,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use itertools::Itertools;
+use primitive_types::U256;
+use sha3::{
+    Digest as _,
+    Keccak256,
+};
+use std::{
+    fmt::Debug,
+    path::Path,
+    str::FromStr,
+};
+
+/// Size of a single ABI word in bytes.
+const WORD: usize = 32;
+
+/// A Solidity ABI, as found in the `.abi` or `.json` files produced by `solc` and
+/// `solang`.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(transparent)]
+pub struct SolidityAbi {
+    entries: Vec<AbiEntry>,
+}
+
+/// A single item of a Solidity ABI.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbiEntry {
+    /// The kind of the item, e.g. `function` or `constructor`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The name of the function, empty for constructors.
+    #[serde(default)]
+    pub name: String,
+    /// The parameters of the function.
+    #[serde(default)]
+    pub inputs: Vec<AbiParam>,
+    /// The return values of the function.
+    #[serde(default)]
+    pub outputs: Vec<AbiParam>,
+    /// One of `pure`, `view`, `nonpayable` and `payable`.
+    #[serde(default)]
+    pub state_mutability: Option<String>,
+}
+
+/// A parameter or return value of a function.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AbiParam {
+    #[serde(default)]
+    pub name: String,
+    /// The Solidity type, e.g. `uint256` or `tuple[]`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The fields of a `tuple` type.
+    #[serde(default)]
+    pub components: Vec<AbiParam>,
+}
+
+impl AbiEntry {
+    /// Returns the canonical signature of the function, e.g. `transfer(address,uint256)`.
+    pub fn signature(&self) -> Result<String> {
+        let types: Vec<_> = self
+            .inputs
+            .iter()
+            .map(|param| ParamType::parse(param).map(|ty| ty.canonical()))
+            .try_collect()?;
+        Ok(format!("{}({})", self.name, types.join(",")))
+    }
+
+    /// Returns the 4 byte selector of the function.
+    pub fn selector(&self) -> Result<[u8; 4]> {
+        let hash = Keccak256::digest(self.signature()?.as_bytes());
+        Ok([hash[0], hash[1], hash[2], hash[3]])
+    }
+
+    /// Returns `true` if calling the function may modify the contract's state.
+    pub fn mutates(&self) -> bool {
+        !matches!(self.state_mutability.as_deref(), Some("view" | "pure"))
+    }
+
+    fn input_types(&self) -> Result<Vec<ParamType>> {
+        self.inputs.iter().map(ParamType::parse).collect()
+    }
+
+    fn output_types(&self) -> Result<Vec<ParamType>> {
+        self.outputs.iter().map(ParamType::parse).collect()
+    }
+}
+
+impl SolidityAbi {
+    /// Loads the ABI from the JSON file at `path`.
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .context(format!("Failed to open ABI file {}", path.display()))?;
+        serde_json::from_reader(file).context(format!(
+            "Failed to deserialize ABI from file {}",
+            path.display()
+        ))
+    }
+
+    /// Encodes the input data of the constructor.
+    ///
+    /// Constructors have no selector, the data only consists of the encoded arguments.
+    pub fn encode_constructor<I, S>(&self, args: I) -> Result<Vec<u8>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str> + Debug,
+    {
+        let types = match self.entries.iter().find(|e| e.kind == "constructor") {
+            Some(constructor) => constructor.input_types()?,
+            // Contracts without an explicit constructor have an implicit one without
+            // any arguments.
+            None => Vec::new(),
+        };
+        encode_args(&types, args)
+    }
+
+    /// Encodes the call of the function `name` with the given arguments.
+    ///
+    /// Overloaded functions can be identified by their full signature, e.g.
+    /// `transfer(address,uint256)`.
+    pub fn encode<I, S>(&self, name: &str, args: I) -> Result<Vec<u8>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str> + Debug,
+    {
+        let function = self.find_function(name)?;
+        let mut encoded = function.selector()?.to_vec();
+        encoded.extend(encode_args(&function.input_types()?, args)?);
+        Ok(encoded)
+    }
+
+    /// Decodes the data returned by the function `name`.
+    pub fn decode_output(&self, name: &str, data: &[u8]) -> Result<Value> {
+        let function = self.find_function(name)?;
+        let mut values = decode_params(&function.output_types()?, data)?;
+        match values.len() {
+            0 => Ok(Value::Unit),
+            1 => Ok(values.remove(0)),
+            _ => Ok(Value::Tuple(Tuple::new(None, values))),
+        }
+    }
+
+    /// Returns the function with the given name or signature.
+    pub fn find_function(&self, name: &str) -> Result<&AbiEntry> {
+        let functions: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|e| e.kind == "function")
+            .collect();
+        let matching: Vec<_> = if name.contains('(') {
+            functions
+                .iter()
+                .copied()
+                .filter(|f| f.signature().ok().as_deref() == Some(name))
+                .collect()
+        } else {
+            functions
+                .iter()
+                .copied()
+                .filter(|f| f.name == name)
+                .collect()
+        };
+        match matching.as_slice() {
+            [function] => Ok(function),
+            [] => {
+                anyhow::bail!(
+                    "No function with the name '{name}' found.\nShould be one of: {}",
+                    functions.iter().map(|f| &f.name).unique().join(", ")
+                )
+            }
+            overloads => {
+                let signatures: Vec<_> =
+                    overloads.iter().map(|f| f.signature()).try_collect()?;
+                anyhow::bail!(
+                    "The function '{name}' is overloaded, use one of the signatures \
+                    instead: {}",
+                    signatures.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// A Solidity type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParamType {
+    Address,
+    Bool,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<ParamType>),
+    FixedArray(Box<ParamType>, usize),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    fn parse(param: &AbiParam) -> Result<Self> {
+        Self::parse_type(&param.ty, &param.components)
+    }
+
+    fn parse_type(ty: &str, components: &[AbiParam]) -> Result<Self> {
+        if let Some(inner) = ty.strip_suffix(']') {
+            let (inner, len) = inner
+                .rsplit_once('[')
+                .context(format!("Invalid array type '{ty}'"))?;
+            let inner = Box::new(Self::parse_type(inner, components)?);
+            return match len {
+                "" => Ok(Self::Array(inner)),
+                len => {
+                    let len = len
+                        .parse()
+                        .context(format!("Invalid array length in '{ty}'"))?;
+                    Ok(Self::FixedArray(inner, len))
+                }
+            }
+        }
+        let bits = |digits: &str| -> Result<usize> {
+            let bits = if digits.is_empty() {
+                256
+            } else {
+                digits.parse()?
+            };
+            if bits == 0 || bits > 256 || bits % 8 != 0 {
+                anyhow::bail!("Invalid integer type '{ty}'")
+            }
+            Ok(bits)
+        };
+        match ty {
+            "address" => Ok(Self::Address),
+            "bool" => Ok(Self::Bool),
+            "string" => Ok(Self::String),
+            "bytes" => Ok(Self::Bytes),
+            "tuple" => {
+                let fields = components.iter().map(Self::parse).try_collect()?;
+                Ok(Self::Tuple(fields))
+            }
+            _ => {
+                if let Some(digits) = ty.strip_prefix("uint") {
+                    Ok(Self::Uint(bits(digits)?))
+                } else if let Some(digits) = ty.strip_prefix("int") {
+                    Ok(Self::Int(bits(digits)?))
+                } else if let Some(len) = ty.strip_prefix("bytes") {
+                    match len.parse() {
+                        Ok(len @ 1..=32) => Ok(Self::FixedBytes(len)),
+                        _ => anyhow::bail!("Invalid bytes type '{ty}'"),
+                    }
+                } else {
+                    anyhow::bail!("Unsupported Solidity type '{ty}'")
+                }
+            }
+        }
+    }
+
+    /// The type as used in function signatures.
+    fn canonical(&self) -> String {
+        match self {
+            Self::Address => "address".to_string(),
+            Self::Bool => "bool".to_string(),
+            Self::Uint(bits) => format!("uint{bits}"),
+            Self::Int(bits) => format!("int{bits}"),
+            Self::FixedBytes(len) => format!("bytes{len}"),
+            Self::Bytes => "bytes".to_string(),
+            Self::String => "string".to_string(),
+            Self::Array(inner) => format!("{}[]", inner.canonical()),
+            Self::FixedArray(inner, len) => format!("{}[{len}]", inner.canonical()),
+            Self::Tuple(fields) => {
+                format!("({})", fields.iter().map(Self::canonical).join(","))
+            }
+        }
+    }
+
+    /// Dynamic types are encoded in the tail, and referenced by an offset in the head.
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Self::Bytes | Self::String | Self::Array(_) => true,
+            Self::FixedArray(inner, _) => inner.is_dynamic(),
+            Self::Tuple(fields) => fields.iter().any(Self::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Size of the type in the head of the encoding.
+    fn head_size(&self) -> Result<usize> {
+        match self {
+            _ if self.is_dynamic() => Ok(WORD),
+            Self::FixedArray(inner, len) => {
+                inner.head_size()?.checked_mul(*len).with_context(|| {
+                    format!("Invalid array length in '{}'", self.canonical())
+                })
+            }
+            Self::Tuple(fields) => heads_size(fields),
+            _ => Ok(WORD),
+        }
+    }
+}
+
+/// Size of the heads of `types` in the encoding.
+fn heads_size(types: &[ParamType]) -> Result<usize> {
+    types.iter().try_fold(0usize, |size, ty| {
+        size.checked_add(ty.head_size()?)
+            .context("The ABI encoded size overflows")
+    })
+}
+
+fn encode_args<I, S>(types: &[ParamType], args: I) -> Result<Vec<u8>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str> + Debug,
+{
+    let args: Vec<_> = args.into_iter().collect();
+    if types.len() != args.len() {
+        anyhow::bail!(
+            "Invalid number of input arguments: expected {}, {} provided",
+            types.len(),
+            args.len()
+        )
+    }
+    let values: Vec<_> = args
+        .iter()
+        .map(|arg| scon::parse_value(arg.as_ref()))
+        .try_collect()?;
+    encode_params(types, &values)
+}
+
+fn encode_params(types: &[ParamType], values: &[Value]) -> Result<Vec<u8>> {
+    let heads_size = heads_size(types)?;
+    let mut head = Vec::with_capacity(heads_size);
+    let mut tail = Vec::new();
+    for (ty, value) in types.iter().zip(values) {
+        let encoded = encode_value(ty, value)?;
+        if ty.is_dynamic() {
+            head.extend(encode_uint(U256::from(heads_size + tail.len())));
+            tail.extend(encoded);
+        } else {
+            head.extend(encoded);
+        }
+    }
+    head.extend(tail);
+    Ok(head)
+}
+
+fn encode_value(ty: &ParamType, value: &Value) -> Result<Vec<u8>> {
+    match (ty, value) {
+        (ParamType::Address, Value::Hex(hex)) if hex.bytes().len() == 20 => {
+            Ok(pad_left(hex.bytes()))
+        }
+        (ParamType::Bool, Value::Bool(b)) => Ok(encode_uint(U256::from(*b as u8))),
+        (ParamType::Uint(bits), value) => {
+            let uint = uint_from_value(value)?;
+            if uint.bits() > *bits {
+                anyhow::bail!("{value} does not fit into uint{bits}")
+            }
+            Ok(encode_uint(uint))
+        }
+        (ParamType::Int(bits), value) => encode_int(*bits, value),
+        (ParamType::FixedBytes(len), Value::Hex(hex)) if hex.bytes().len() == *len => {
+            Ok(pad_right(hex.bytes()))
+        }
+        (ParamType::Bytes, Value::Hex(hex)) => Ok(encode_bytes(hex.bytes())),
+        (ParamType::String, Value::String(s)) => Ok(encode_bytes(s.as_bytes())),
+        (ParamType::Array(inner), Value::Seq(seq)) => {
+            let types = vec![(**inner).clone(); seq.len()];
+            let mut encoded = encode_uint(U256::from(seq.len()));
+            encoded.extend(encode_params(&types, seq.elems())?);
+            Ok(encoded)
+        }
+        (ParamType::FixedArray(inner, len), Value::Seq(seq)) if seq.len() == *len => {
+            encode_params(&vec![(**inner).clone(); *len], seq.elems())
+        }
+        (ParamType::Tuple(fields), Value::Tuple(tuple)) => {
+            let values: Vec<_> = tuple.values().cloned().collect();
+            if values.len() != fields.len() {
+                anyhow::bail!(
+                    "Expected a tuple with {} fields, got {value}",
+                    fields.len()
+                )
+            }
+            encode_params(fields, &values)
+        }
+        (ty, value) => anyhow::bail!("Cannot encode {value} as {}", ty.canonical()),
+    }
+}
+
+fn uint_from_value(value: &Value) -> Result<U256> {
+    match value {
+        Value::UInt(uint) => Ok(U256::from(*uint)),
+        Value::Hex(hex) => {
+            if hex.bytes().len() > WORD {
+                anyhow::bail!("{value} does not fit into 256 bits")
+            }
+            Ok(U256::from_big_endian(hex.bytes()))
+        }
+        Value::String(s) | Value::Literal(s) => {
+            U256::from_dec_str(&s.replace('_', ""))
+                .map_err(|e| anyhow::anyhow!("Invalid unsigned integer {s}: {e:?}"))
+        }
+        _ => anyhow::bail!("Expected an unsigned integer, got {value}"),
+    }
+}
+
+fn encode_int(bits: usize, value: &Value) -> Result<Vec<u8>> {
+    let int = match value {
+        Value::Int(int) => *int,
+        Value::UInt(uint) => {
+            i128::try_from(*uint)
+                .context(format!("{value} does not fit into int{bits}"))?
+        }
+        _ => anyhow::bail!("Expected an integer, got {value}"),
+    };
+    if bits < 128 && (int >> (bits - 1)) != 0 && (int >> (bits - 1)) != -1 {
+        anyhow::bail!("{value} does not fit into int{bits}")
+    }
+    // Sign extend the two's complement representation to the full word.
+    let fill = if int < 0 { 0xff } else { 0x00 };
+    let mut encoded = vec![fill; WORD - 16];
+    encoded.extend(int.to_be_bytes());
+    Ok(encoded)
+}
+
+fn encode_uint(uint: U256) -> Vec<u8> {
+    let mut encoded = vec![0; WORD];
+    uint.to_big_endian(&mut encoded);
+    encoded
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = encode_uint(U256::from(bytes.len()));
+    encoded.extend(pad_right(bytes));
+    encoded
+}
+
+fn pad_left(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = vec![0; WORD - bytes.len()];
+    padded.extend(bytes);
+    padded
+}
+
+fn pad_right(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.resize(bytes.len().div_ceil(WORD) * WORD, 0);
+    padded
+}
+
+fn decode_params(types: &[ParamType], data: &[u8]) -> Result<Vec<Value>> {
+    let mut offset = 0;
+    let mut values = Vec::with_capacity(types.len());
+    for ty in types {
+        let value = if ty.is_dynamic() {
+            let tail_offset = read_usize(data, offset)?;
+            decode_value(ty, data.get(tail_offset..).context("Invalid offset")?)?
+        } else {
+            decode_value(ty, data.get(offset..).context("Invalid offset")?)?
+        };
+        offset += ty.head_size()?;
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn decode_value(ty: &ParamType, data: &[u8]) -> Result<Value> {
+    let word = read_word(data, 0)?;
+    match ty {
+        ParamType::Address => hex_value(&word[WORD - 20..]),
+        ParamType::Bool => Ok(Value::Bool(word[WORD - 1] != 0)),
+        ParamType::Uint(_) => {
+            let uint = U256::from_big_endian(word);
+            Ok(match u128::try_from(uint) {
+                Ok(uint) => Value::UInt(uint),
+                Err(_) => Value::Literal(uint.to_string()),
+            })
+        }
+        ParamType::Int(_) => {
+            let uint = U256::from_big_endian(word);
+            if uint.bits() <= 127 {
+                Ok(Value::Int(uint.low_u128() as i128))
+            } else if word[..WORD - 16].iter().all(|b| *b == 0xff)
+                && word[WORD - 16] >= 0x80
+            {
+                let low: [u8; 16] = word[WORD - 16..].try_into()?;
+                Ok(Value::Int(i128::from_be_bytes(low)))
+            } else if word[0] >= 0x80 {
+                let abs = (!uint).overflowing_add(U256::one()).0;
+                Ok(Value::Literal(format!("-{abs}")))
+            } else {
+                Ok(Value::Literal(uint.to_string()))
+            }
+        }
+        ParamType::FixedBytes(len) => hex_value(&word[..*len]),
+        ParamType::Bytes => hex_value(read_bytes(data)?),
+        ParamType::String => {
+            let s = std::str::from_utf8(read_bytes(data)?)?;
+            Ok(Value::String(s.to_string()))
+        }
+        ParamType::Array(inner) => {
+            let len = read_usize(data, 0)?;
+            // every element occupies at least one word, a longer array cannot be valid
+            anyhow::ensure!(
+                len <= data.len().saturating_sub(WORD) / WORD,
+                "Invalid array length {len}"
+            );
+            let types = vec![(**inner).clone(); len];
+            let elems = decode_params(&types, &data[WORD..])?;
+            Ok(Value::Seq(elems.into()))
+        }
+        ParamType::FixedArray(inner, len) => {
+            // every element occupies at least one word, a longer array cannot be valid
+            anyhow::ensure!(*len <= data.len() / WORD, "Invalid array length {len}");
+            let elems = decode_params(&vec![(**inner).clone(); *len], data)?;
+            Ok(Value::Seq(elems.into()))
+        }
+        ParamType::Tuple(fields) => {
+            Ok(Value::Tuple(Tuple::new(None, decode_params(fields, data)?)))
+        }
+    }
+}
+
+fn hex_value(bytes: &[u8]) -> Result<Value> {
+    Ok(Value::Hex(Hex::from_str(&hex::encode(bytes))?))
+}
+
+fn read_word(data: &[u8], offset: usize) -> Result<&[u8]> {
+    data.get(offset..offset + WORD)
+        .context("Unexpected end of the ABI encoded data")
+}
+
+fn read_usize(data: &[u8], offset: usize) -> Result<usize> {
+    let uint = U256::from_big_endian(read_word(data, offset)?);
+    usize::try_from(uint).map_err(|_| anyhow::anyhow!("Invalid length or offset {uint}"))
+}
+
+fn read_bytes(data: &[u8]) -> Result<&[u8]> {
+    let len = read_usize(data, 0)?;
+    let end = WORD
+        .checked_add(len)
+        .with_context(|| format!("Invalid bytes length {len}"))?;
+    data.get(WORD..end)
+        .context("Unexpected end of the ABI encoded data")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = r#"[
+        {
+            "type": "constructor",
+            "inputs": [{ "name": "initial", "type": "uint256" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "amount", "type": "uint256" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "function",
+            "name": "name",
+            "inputs": [],
+            "outputs": [{ "name": "", "type": "string" }],
+            "stateMutability": "view"
+        },
+        {
+            "type": "function",
+            "name": "sam",
+            "inputs": [
+                { "name": "a", "type": "bytes" },
+                { "name": "b", "type": "bool" },
+                { "name": "c", "type": "uint256[]" }
+            ],
+            "outputs": [],
+            "stateMutability": "pure"
+        }
+    ]"#;
+
+    fn abi() -> SolidityAbi {
+        serde_json::from_str(ABI).expect("valid ABI")
+    }
+
+    #[test]
+    fn encode_function_call() -> Result<()> {
+        let encoded = abi().encode(
+            "transfer",
+            ["0x00000000000000000000000000000000000000aa", "1000"],
+        )?;
+
+        let expected = "a9059cbb\
+            00000000000000000000000000000000000000000000000000000000000000aa\
+            00000000000000000000000000000000000000000000000000000000000003e8";
+        assert_eq!(hex::encode(encoded), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn encode_dynamic_args() -> Result<()> {
+        // Example from the Solidity ABI specification.
+        let encoded = abi().encode("sam", ["0x64617665", "true", "[1, 2, 3]"])?;
+
+        let expected = "a5643bf2\
+            0000000000000000000000000000000000000000000000000000000000000060\
+            0000000000000000000000000000000000000000000000000000000000000001\
+            00000000000000000000000000000000000000000000000000000000000000a0\
+            0000000000000000000000000000000000000000000000000000000000000004\
+            6461766500000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000003\
+            0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000002\
+            0000000000000000000000000000000000000000000000000000000000000003";
+        assert_eq!(hex::encode(encoded), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn encode_constructor_without_selector() -> Result<()> {
+        let encoded = abi().encode_constructor(["42"])?;
+
+        assert_eq!(encoded, encode_uint(U256::from(42)));
+        Ok(())
+    }
+
+    #[test]
+    fn encode_hex_uint_longer_than_a_word_fails() {
+        let err = abi()
+            .encode_constructor([format!("0x{}", "ff".repeat(WORD + 1))])
+            .unwrap_err();
+
+        assert!(
+            err.to_string().contains("does not fit into 256 bits"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn encode_negative_int() -> Result<()> {
+        let encoded = encode_int(256, &Value::Int(-1))?;
+
+        assert_eq!(encoded, vec![0xff; WORD]);
+        assert!(encode_int(8, &Value::Int(128)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn decode_string_output() -> Result<()> {
+        let data = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000006\
+            736f6c616e670000000000000000000000000000000000000000000000000000",
+        )?;
+
+        let value = abi().decode_output("name", &data)?;
+
+        assert_eq!(value, Value::String("solang".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_array_output() -> Result<()> {
+        let data = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000002\
+            0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000002",
+        )?;
+
+        let value =
+            decode_value(&ParamType::Array(Box::new(ParamType::Uint(256))), &data)?;
+
+        assert_eq!(
+            value,
+            Value::Seq(vec![Value::UInt(1), Value::UInt(2)].into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decode_array_with_huge_length_fails() {
+        let data = hex::decode(
+            "00000000000000000000000000000000000000000000000000000000ffffffff\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .expect("valid hex");
+
+        let err = decode_value(&ParamType::Array(Box::new(ParamType::Uint(256))), &data)
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Invalid array length 4294967295");
+    }
+
+    #[test]
+    fn decode_fixed_array_with_huge_length_fails() {
+        let ty = ParamType::parse_type("uint256[18446744073709551615]", &[])
+            .expect("valid type");
+        let data = [0; WORD];
+
+        assert_eq!(
+            ty.head_size().unwrap_err().to_string(),
+            "Invalid array length in 'uint256[18446744073709551615]'"
+        );
+        assert_eq!(
+            decode_value(&ty, &data).unwrap_err().to_string(),
+            "Invalid array length 18446744073709551615"
+        );
+    }
+
+    #[test]
+    fn decode_bytes_with_huge_length_fails() {
+        let mut data = vec![0; WORD - std::mem::size_of::<usize>()];
+        data.extend(usize::MAX.to_be_bytes());
+        data.extend([0; WORD]);
+
+        let err = decode_value(&ParamType::Bytes, &data).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("Invalid bytes length {}", usize::MAX)
+        );
+    }
+
+    #[test]
+    fn unknown_function_lists_available_ones() {
+        let err = abi().encode("transfr", Vec::<&str>::new()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "No function with the name 'transfr' found.\nShould be one of: transfer, \
+            name, sam"
+        );
+    }
+
+    #[test]
+    fn view_functions_do_not_mutate() -> Result<()> {
+        let abi = abi();
+
+        assert!(abi.find_function("transfer")?.mutates());
+        assert!(!abi.find_function("name")?.mutates());
+        Ok(())
+    }
+}
@@ -26,6 +26,10 @@ use super::{
         TypesByPath,
     },
     scon::Value,
+    type_mappings::{
+        EnvType,
+        TypeMappings,
+    },
     AccountId32,
 };
 
@@ -81,6 +85,7 @@ pub struct TranscoderBuilder {
     types_by_path: TypesByPath,
     encoders: HashMap<u32, Box<dyn CustomTypeEncoder>>,
     decoders: HashMap<u32, Box<dyn CustomTypeDecoder>>,
+    account_id: env_types::AccountId,
 }
 
 impl TranscoderBuilder {
@@ -94,11 +99,35 @@ impl TranscoderBuilder {
             types_by_path,
             encoders: HashMap::new(),
             decoders: HashMap::new(),
+            account_id: env_types::AccountId::default(),
         }
     }
 
+    /// Sets the SS58 `prefix` the accounts are decoded with by the transcoders of the
+    /// `AccountId` registered afterwards, e.g. by
+    /// [`Self::with_default_custom_type_transcoders`].
+    pub fn ss58_prefix(self, prefix: u16) -> Self {
+        let mut this = self;
+        this.account_id = this.account_id.with_ss58_prefix(prefix);
+        this
+    }
+
+    /// Sets whether the transcoders of the `AccountId` registered afterwards accept
+    /// Ethereum style addresses, see [`env_types::AccountId::with_eth_addresses`].
+    pub fn eth_addresses(self, eth_addresses: bool) -> Self {
+        let mut this = self;
+        this.account_id = this.account_id.with_eth_addresses(eth_addresses);
+        this
+    }
+
+    /// Returns the transcoder of the `AccountId`, see [`Self::ss58_prefix`].
+    pub fn account_id_transcoder(&self) -> env_types::AccountId {
+        self.account_id
+    }
+
     pub fn with_default_custom_type_transcoders(self) -> Self {
-        self.register_custom_type_transcoder::<AccountId32, _>(env_types::AccountId)
+        let account_id = self.account_id;
+        self.register_custom_type_transcoder::<AccountId32, _>(account_id)
             .register_custom_type_decoder::<primitive_types::H256, _>(env_types::Hash)
     }
 
@@ -117,58 +146,89 @@ impl TranscoderBuilder {
         U: CustomTypeEncoder + 'static,
     {
         let mut this = self;
-
         let path_key = PathKey::from_type::<T>();
-        let type_id = this.types_by_path.get(&path_key);
-
-        match type_id {
-            Some(type_id) => {
-                let existing = this.encoders.insert(*type_id, Box::new(encoder));
-                tracing::debug!("Registered custom encoder for type `{:?}`", type_id);
-                if existing.is_some() {
-                    panic!(
-                        "Attempted to register encoder with existing type id {type_id:?}"
-                    );
-                }
-            }
-            None => {
-                // if the type is not present in the registry, it just means it has not
-                // been used.
-                tracing::debug!("No matching type in registry for path {:?}.", path_key);
-            }
+        if this.insert_encoder(path_key.clone(), Box::new(encoder)) {
+            panic!("Attempted to register encoder with existing type {path_key:?}");
         }
         this
     }
 
-    pub fn register_custom_type_decoder<T, U>(self, encoder: U) -> Self
+    pub fn register_custom_type_decoder<T, U>(self, decoder: U) -> Self
     where
         T: TypeInfo + 'static,
         U: CustomTypeDecoder + 'static,
     {
         let mut this = self;
-
         let path_key = PathKey::from_type::<T>();
-        let type_id = this.types_by_path.get(&path_key);
-
-        match type_id {
-            Some(type_id) => {
-                let existing = this.decoders.insert(*type_id, Box::new(encoder));
-                tracing::debug!("Registered custom decoder for type `{:?}`", type_id);
-                if existing.is_some() {
-                    panic!(
-                        "Attempted to register decoder with existing type id {type_id:?}"
-                    );
+        if this.insert_decoder(path_key.clone(), Box::new(decoder)) {
+            panic!("Attempted to register decoder with existing type {path_key:?}");
+        }
+        this
+    }
+
+    /// Registers the transcoders of the environment types for the types of the
+    /// `mappings`.
+    ///
+    /// Types which already have a custom transcoder keep it.
+    pub fn register_type_mappings(self, mappings: &TypeMappings) -> Self {
+        let mut this = self;
+        for (path, env_type) in mappings.iter() {
+            let path_key = PathKey::from(path);
+            let existing = match env_type {
+                EnvType::AccountId => {
+                    let account_id = this.account_id;
+                    this.insert_encoder(path_key.clone(), Box::new(account_id))
+                        | this.insert_decoder(path_key, Box::new(account_id))
                 }
-            }
-            None => {
-                // if the type is not present in the registry, it just means it has not
-                // been used.
-                tracing::debug!("No matching type in registry for path {:?}.", path_key);
+                EnvType::Hash => this.insert_decoder(path_key, Box::new(env_types::Hash)),
+            };
+            if existing {
+                tracing::warn!("The type `{path}` already has a custom transcoder");
             }
         }
         this
     }
 
+    /// Registers the `encoder` for the type at `path_key`, unless it has one already.
+    ///
+    /// Returns `true` if the type already has an encoder.
+    fn insert_encoder(
+        &mut self,
+        path_key: PathKey,
+        encoder: Box<dyn CustomTypeEncoder>,
+    ) -> bool {
+        let Some(type_id) = self.types_by_path.get(&path_key) else {
+            tracing::debug!("No matching type in registry for path {:?}.", path_key);
+            return false
+        };
+        if self.encoders.contains_key(type_id) {
+            return true
+        }
+        self.encoders.insert(*type_id, encoder);
+        tracing::debug!("Registered custom encoder for type `{:?}`", type_id);
+        false
+    }
+
+    /// Registers the `decoder` for the type at `path_key`, unless it has one already.
+    ///
+    /// Returns `true` if the type already has a decoder.
+    fn insert_decoder(
+        &mut self,
+        path_key: PathKey,
+        decoder: Box<dyn CustomTypeDecoder>,
+    ) -> bool {
+        let Some(type_id) = self.types_by_path.get(&path_key) else {
+            tracing::debug!("No matching type in registry for path {:?}.", path_key);
+            return false
+        };
+        if self.decoders.contains_key(type_id) {
+            return true
+        }
+        self.decoders.insert(*type_id, decoder);
+        tracing::debug!("Registered custom decoder for type `{:?}`", type_id);
+        false
+    }
+
     pub fn done(self) -> Transcoder {
         let env_types_transcoder = EnvTypesTranscoder::new(self.encoders, self.decoders);
         Transcoder::new(env_types_transcoder)
@@ -0,0 +1,156 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use contract_metadata::User;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// The key of the type mappings in the user defined metadata.
+const USER_METADATA_KEY: &str = "transcoder";
+
+/// An environment type whose transcoder can be used for other types.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub enum EnvType {
+    /// Encoded from and decoded to an SS58 address.
+    AccountId,
+    /// Decoded to a hex encoded string.
+    Hash,
+}
+
+/// Additional types transcoded like environment types, e.g. custom `AccountId` wrappers
+/// or the return types of chain extensions.
+///
+/// The types are identified by their path in the type registry. Contracts can declare
+/// the mappings in the `transcoder` section of their user defined metadata, which is
+/// set in their `Cargo.toml`:
+///
+/// ```toml
+/// [package.metadata.contract.user.transcoder.types]
+/// "my_extension::CustomAccountId" = "AccountId"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct TypeMappings {
+    #[serde(default)]
+    types: BTreeMap<String, EnvType>,
+}
+
+impl TypeMappings {
+    /// Maps the type at `path`, e.g. `my_extension::CustomAccountId`, to `env_type`.
+    pub fn map(mut self, path: impl Into<String>, env_type: EnvType) -> Self {
+        self.types.insert(path.into(), env_type);
+        self
+    }
+
+    /// Reads the mappings from the user defined metadata of a contract.
+    ///
+    /// Returns empty mappings if the metadata has no `transcoder` section.
+    pub fn from_user_metadata(user: &User) -> Result<Self> {
+        match user.json.get(USER_METADATA_KEY) {
+            Some(mappings) => {
+                serde_json::from_value(mappings.clone()).context(
+                    "Failed to deserialize the type mappings of the user metadata",
+                )
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Returns the paths of the mapped types with their environment types.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, EnvType)> {
+        self.types
+            .iter()
+            .map(|(path, env_type)| (path.as_str(), *env_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        scon,
+        TranscoderBuilder,
+        Value,
+    };
+    use scale_info::{
+        MetaType,
+        PortableRegistry,
+        Registry,
+        TypeInfo,
+    };
+
+    #[derive(TypeInfo)]
+    #[allow(dead_code)]
+    struct CustomAccountId([u8; 32]);
+
+    const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    fn mapped_type_is_transcoded_as_account_id() {
+        let mut registry = Registry::new();
+        let type_id = registry
+            .register_type(&MetaType::new::<CustomAccountId>())
+            .id;
+        let registry: PortableRegistry = registry.into();
+        let path = registry.resolve(type_id).unwrap().path.to_string();
+        let mappings = TypeMappings::default().map(path, EnvType::AccountId);
+        let transcoder = TranscoderBuilder::new(&registry)
+            .register_type_mappings(&mappings)
+            .done();
+
+        let mut encoded = Vec::new();
+        transcoder
+            .encode(
+                &registry,
+                type_id,
+                &scon::parse_value(ALICE).unwrap(),
+                &mut encoded,
+            )
+            .unwrap();
+        let decoded = transcoder
+            .decode(&registry, type_id, &mut &encoded[..])
+            .unwrap();
+
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(decoded, Value::Literal(ALICE.to_string()));
+    }
+
+    #[test]
+    fn mappings_are_read_from_user_metadata() {
+        let user = User::new(
+            serde_json::json!({
+                "transcoder": { "types": { "my_extension::Digest": "Hash" } }
+            })
+            .as_object()
+            .unwrap()
+            .clone(),
+        );
+
+        let mappings = TypeMappings::from_user_metadata(&user).unwrap();
+
+        assert_eq!(
+            mappings.iter().collect::<Vec<_>>(),
+            vec![("my_extension::Digest", EnvType::Hash)]
+        );
+        assert_eq!(
+            TypeMappings::from_user_metadata(&User::new(Default::default())).unwrap(),
+            TypeMappings::default()
+        );
+    }
+}